@@ -0,0 +1,300 @@
+/*
+ * VULNERABLE COSMWASM CONTRACT - DO NOT USE IN PRODUCTION
+ *
+ * cw20 Allowance Race
+ *
+ * This cw20-like token exposes an ERC20-style Approve that overwrites the
+ * spender's allowance. When an owner lowers an allowance from N to M, the
+ * spender can front-run the change, spend the old N, and then spend the
+ * new M as well - N + M in total.
+ */
+
+use cosmwasm_schema::cw_serde;
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_json_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult,
+    Uint128,
+};
+use cw_storage_plus::Map;
+
+pub const BALANCES: Map<&Addr, Uint128> = Map::new("balance");
+// (owner, spender) -> remaining allowance
+pub const ALLOWANCES: Map<(&Addr, &Addr), Uint128> = Map::new("allowance");
+
+#[cw_serde]
+pub struct InitialBalance {
+    pub address: String,
+    pub amount: Uint128,
+}
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub initial_balances: Vec<InitialBalance>,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    Transfer {
+        recipient: String,
+        amount: Uint128,
+    },
+    /// Sets the spender's allowance to exactly `amount`
+    Approve {
+        spender: String,
+        amount: Uint128,
+    },
+    TransferFrom {
+        owner: String,
+        recipient: String,
+        amount: Uint128,
+    },
+}
+
+#[cw_serde]
+pub enum QueryMsg {
+    Balance { address: String },
+    Allowance { owner: String, spender: String },
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> StdResult<Response> {
+    for balance in msg.initial_balances {
+        let address = deps.api.addr_validate(&balance.address)?;
+        BALANCES.save(deps.storage, &address, &balance.amount)?;
+    }
+    Ok(Response::new().add_attribute("action", "instantiate"))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> StdResult<Response> {
+    match msg {
+        ExecuteMsg::Transfer { recipient, amount } => {
+            let recipient = deps.api.addr_validate(&recipient)?;
+            move_tokens(deps, &info.sender, &recipient, amount)?;
+            Ok(Response::new().add_attribute("action", "transfer"))
+        }
+        ExecuteMsg::Approve { spender, amount } => approve(deps, info, spender, amount),
+        ExecuteMsg::TransferFrom {
+            owner,
+            recipient,
+            amount,
+        } => transfer_from(deps, info, owner, recipient, amount),
+    }
+}
+
+fn move_tokens(deps: DepsMut, from: &Addr, to: &Addr, amount: Uint128) -> StdResult<()> {
+    BALANCES.update(deps.storage, from, |balance| -> StdResult<_> {
+        Ok(balance.unwrap_or_default().checked_sub(amount)?)
+    })?;
+    BALANCES.update(deps.storage, to, |balance| -> StdResult<_> {
+        Ok(balance.unwrap_or_default().checked_add(amount)?)
+    })?;
+    Ok(())
+}
+
+pub fn approve(
+    deps: DepsMut,
+    info: MessageInfo,
+    spender: String,
+    amount: Uint128,
+) -> StdResult<Response> {
+    let spender = deps.api.addr_validate(&spender)?;
+
+    // VULNERABILITY 1: The allowance is overwritten, not adjusted
+    // The new value ignores whatever the spender already consumed, so
+    // "change 100 to 50" and "spend 100, then grant 50" are indistinguishable
+    ALLOWANCES.save(deps.storage, (&info.sender, &spender), &amount)?;
+
+    // VULNERABILITY 2: No expected-current-value guard
+    // The owner cannot make the change conditional on the allowance
+    // still being what they saw when they signed the transaction
+    Ok(Response::new()
+        .add_attribute("action", "approve")
+        .add_attribute("spender", spender)
+        .add_attribute("amount", amount))
+}
+
+pub fn transfer_from(
+    deps: DepsMut,
+    info: MessageInfo,
+    owner: String,
+    recipient: String,
+    amount: Uint128,
+) -> StdResult<Response> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let recipient = deps.api.addr_validate(&recipient)?;
+
+    // Spending itself is correctly checked - the race is in approve()
+    ALLOWANCES.update(
+        deps.storage,
+        (&owner, &info.sender),
+        |allowance| -> StdResult<_> {
+            allowance
+                .unwrap_or_default()
+                .checked_sub(amount)
+                .map_err(|_| StdError::generic_err("Insufficient allowance"))
+        },
+    )?;
+
+    move_tokens(deps, &owner, &recipient, amount)?;
+
+    Ok(Response::new().add_attribute("action", "transfer_from"))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Balance { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            to_json_binary(
+                &BALANCES
+                    .may_load(deps.storage, &address)?
+                    .unwrap_or_default(),
+            )
+        }
+        QueryMsg::Allowance { owner, spender } => {
+            let owner = deps.api.addr_validate(&owner)?;
+            let spender = deps.api.addr_validate(&spender)?;
+            to_json_binary(
+                &ALLOWANCES
+                    .may_load(deps.storage, (&owner, &spender))?
+                    .unwrap_or_default(),
+            )
+        }
+    }
+}
+
+/*
+ * SECURE VERSION (cw20 INCREASE / DECREASE ALLOWANCE):
+ *
+ * Allowance changes are relative to what is left, so a front-run spend can
+ * never be combined with a fresh grant. If an absolute setter must exist,
+ * make it compare-and-swap: Approve { spender, current, amount } fails
+ * unless the stored allowance still equals `current`.
+ */
+pub mod secure {
+    use super::{move_tokens, ALLOWANCES};
+    use cosmwasm_schema::cw_serde;
+    use cosmwasm_std::{DepsMut, Env, MessageInfo, Response, StdError, StdResult, Uint128};
+
+    pub use super::{instantiate, query, transfer_from, QueryMsg};
+
+    #[cw_serde]
+    pub enum ExecuteMsg {
+        Transfer {
+            recipient: String,
+            amount: Uint128,
+        },
+        IncreaseAllowance {
+            spender: String,
+            amount: Uint128,
+        },
+        DecreaseAllowance {
+            spender: String,
+            amount: Uint128,
+        },
+        TransferFrom {
+            owner: String,
+            recipient: String,
+            amount: Uint128,
+        },
+    }
+
+    pub fn execute(
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        msg: ExecuteMsg,
+    ) -> StdResult<Response> {
+        match msg {
+            ExecuteMsg::Transfer { recipient, amount } => {
+                let recipient = deps.api.addr_validate(&recipient)?;
+                move_tokens(deps, &info.sender, &recipient, amount)?;
+                Ok(Response::new().add_attribute("action", "transfer"))
+            }
+            ExecuteMsg::IncreaseAllowance { spender, amount } => {
+                increase_allowance(deps, info, spender, amount)
+            }
+            ExecuteMsg::DecreaseAllowance { spender, amount } => {
+                decrease_allowance(deps, info, spender, amount)
+            }
+            ExecuteMsg::TransferFrom {
+                owner,
+                recipient,
+                amount,
+            } => transfer_from(deps, info, owner, recipient, amount),
+        }
+    }
+
+    pub fn increase_allowance(
+        deps: DepsMut,
+        info: MessageInfo,
+        spender: String,
+        amount: Uint128,
+    ) -> StdResult<Response> {
+        let spender = deps.api.addr_validate(&spender)?;
+        if spender == info.sender {
+            return Err(StdError::generic_err("Cannot set allowance to own account"));
+        }
+
+        // SAFE: Relative to what is left, so anything already spent stays spent
+        ALLOWANCES.update(
+            deps.storage,
+            (&info.sender, &spender),
+            |allowance| -> StdResult<_> { Ok(allowance.unwrap_or_default().checked_add(amount)?) },
+        )?;
+
+        Ok(Response::new().add_attribute("action", "increase_allowance"))
+    }
+
+    pub fn decrease_allowance(
+        deps: DepsMut,
+        info: MessageInfo,
+        spender: String,
+        amount: Uint128,
+    ) -> StdResult<Response> {
+        let spender = deps.api.addr_validate(&spender)?;
+        let key = (&info.sender, &spender);
+
+        // SAFE: A front-run spend only shrinks what is left to decrease;
+        // it can never add to the spender's total. Saturating to zero and
+        // removing the entry mirrors cw20-base.
+        let remaining = ALLOWANCES
+            .may_load(deps.storage, key)?
+            .unwrap_or_default()
+            .saturating_sub(amount);
+        if remaining.is_zero() {
+            ALLOWANCES.remove(deps.storage, key);
+        } else {
+            ALLOWANCES.save(deps.storage, key, &remaining)?;
+        }
+
+        Ok(Response::new().add_attribute("action", "decrease_allowance"))
+    }
+}
+
+/*
+ * EXPLOIT SCENARIO:
+ *
+ * 1. Alice approves Bob for 100 tokens: Approve { spender: bob, amount: 100 }
+ * 2. Alice decides to lower it and submits Approve { spender: bob, amount: 50 }
+ * 3. Bob sees the pending transaction in the mempool and front-runs it with
+ *    TransferFrom { owner: alice, amount: 100 } - allowance 100 -> 0
+ * 4. Alice's Approve lands and overwrites the allowance: 0 -> 50
+ * 5. Bob calls TransferFrom { owner: alice, amount: 50 }
+ * 6. Bob moved 150 tokens although Alice never intended more than 100
+ *
+ * With DecreaseAllowance { amount: 50 } instead, step 4 turns 0 into 0
+ * and Bob's total stays at 100.
+ */
@@ -0,0 +1,258 @@
+/*
+ * VULNERABLE COSMWASM CONTRACT - DO NOT USE IN PRODUCTION
+ *
+ * Submessage Ordering / Pseudo-Reentrancy
+ *
+ * This vault sends funds through a submessage BEFORE persisting the
+ * debited balance and finishes accounting in the reply. CosmWasm executes
+ * the recipient's messages depth-first before the reply runs, so a
+ * recipient contract can call back into the vault while the balance is
+ * still undebited.
+ */
+
+use cosmwasm_schema::cw_serde;
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    from_json, to_json_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, Deps, DepsMut, Env,
+    MessageInfo, Reply, Response, StdError, StdResult, SubMsg, SubMsgResult, Uint128, WasmMsg,
+};
+use cw_storage_plus::{Item, Map};
+
+pub const WITHDRAW_REPLY_ID: u64 = 1;
+
+#[cw_serde]
+pub struct Config {
+    pub denom: String,
+}
+
+#[cw_serde]
+pub struct PendingWithdrawal {
+    pub user: Addr,
+    pub amount: Uint128,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+pub const BALANCES: Map<&Addr, Uint128> = Map::new("balances");
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub denom: String,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    Deposit {},
+    /// Withdraw to the sender, optionally invoking a hook on the sender contract
+    Withdraw {
+        amount: Uint128,
+        hook: Option<Binary>,
+    },
+}
+
+#[cw_serde]
+pub enum QueryMsg {
+    Balance { address: String },
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> StdResult<Response> {
+    CONFIG.save(deps.storage, &Config { denom: msg.denom })?;
+    Ok(Response::new().add_attribute("action", "instantiate"))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> StdResult<Response> {
+    match msg {
+        ExecuteMsg::Deposit {} => deposit(deps, info),
+        ExecuteMsg::Withdraw { amount, hook } => withdraw(deps, info, amount, hook),
+    }
+}
+
+pub fn deposit(deps: DepsMut, info: MessageInfo) -> StdResult<Response> {
+    let config = CONFIG.load(deps.storage)?;
+    let amount = cw_utils::must_pay(&info, &config.denom)
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+
+    BALANCES.update(deps.storage, &info.sender, |balance| -> StdResult<_> {
+        Ok(balance.unwrap_or_default().checked_add(amount)?)
+    })?;
+
+    Ok(Response::new().add_attribute("action", "deposit"))
+}
+
+pub fn withdraw(
+    deps: DepsMut,
+    info: MessageInfo,
+    amount: Uint128,
+    hook: Option<Binary>,
+) -> StdResult<Response> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let balance = BALANCES
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default();
+    if balance < amount {
+        return Err(StdError::generic_err("Insufficient balance"));
+    }
+
+    // VULNERABILITY 1: Funds leave BEFORE the balance is debited
+    // The debit is deferred to reply(), so the stored balance is stale
+    // for the entire submessage execution
+    let funds = vec![Coin::new(amount, &config.denom)];
+    let send: CosmosMsg = match hook {
+        // VULNERABILITY 2: The hook hands control to the recipient contract,
+        // whose own messages run depth-first before our reply
+        Some(hook) => WasmMsg::Execute {
+            contract_addr: info.sender.to_string(),
+            msg: hook,
+            funds,
+        }
+        .into(),
+        None => BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: funds,
+        }
+        .into(),
+    };
+
+    let pending = PendingWithdrawal {
+        user: info.sender,
+        amount,
+    };
+
+    Ok(Response::new()
+        .add_submessage(
+            SubMsg::reply_always(send, WITHDRAW_REPLY_ID).with_payload(to_json_binary(&pending)?),
+        )
+        .add_attribute("action", "withdraw"))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> StdResult<Response> {
+    if msg.id != WITHDRAW_REPLY_ID {
+        return Err(StdError::generic_err("Unknown reply id"));
+    }
+
+    let pending: PendingWithdrawal = from_json(&msg.payload)?;
+
+    match msg.result {
+        SubMsgResult::Ok(_) => {
+            // VULNERABILITY 3: Accounting finishes here, after nested
+            // withdrawals already passed the balance check. saturating_sub
+            // hides the double spend instead of failing the transaction.
+            BALANCES.update(deps.storage, &pending.user, |balance| -> StdResult<_> {
+                Ok(balance.unwrap_or_default().saturating_sub(pending.amount))
+            })?;
+            Ok(Response::new().add_attribute("action", "settle_withdraw"))
+        }
+        // VULNERABILITY 4: Errors are swallowed, so the outer transaction
+        // commits whatever intermediate state nested calls produced
+        SubMsgResult::Err(_) => Ok(Response::new().add_attribute("action", "withdraw_failed")),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Balance { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            to_json_binary(
+                &BALANCES
+                    .may_load(deps.storage, &address)?
+                    .unwrap_or_default(),
+            )
+        }
+    }
+}
+
+/*
+ * SECURE VERSION (STATE FIRST):
+ *
+ * The debit is persisted before any message leaves the contract, and the
+ * funds go out as a plain message: a failing recipient reverts the whole
+ * transaction, debit included, so no reply handler is needed.
+ */
+pub mod secure {
+    use super::{ExecuteMsg, BALANCES, CONFIG};
+    use cosmwasm_std::{
+        BankMsg, Binary, Coin, CosmosMsg, DepsMut, Env, MessageInfo, Response, StdResult, Uint128,
+        WasmMsg,
+    };
+
+    pub use super::{deposit, instantiate, query};
+
+    pub fn execute(
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        msg: ExecuteMsg,
+    ) -> StdResult<Response> {
+        match msg {
+            ExecuteMsg::Deposit {} => deposit(deps, info),
+            ExecuteMsg::Withdraw { amount, hook } => withdraw(deps, info, amount, hook),
+        }
+    }
+
+    pub fn withdraw(
+        deps: DepsMut,
+        info: MessageInfo,
+        amount: Uint128,
+        hook: Option<Binary>,
+    ) -> StdResult<Response> {
+        let config = CONFIG.load(deps.storage)?;
+
+        // SAFE: Debit and persist BEFORE any message leaves the contract.
+        // checked_sub fails the whole transaction on insufficient balance.
+        BALANCES.update(deps.storage, &info.sender, |balance| -> StdResult<_> {
+            Ok(balance.unwrap_or_default().checked_sub(amount)?)
+        })?;
+
+        let funds = vec![Coin::new(amount, &config.denom)];
+        let send: CosmosMsg = match hook {
+            Some(hook) => WasmMsg::Execute {
+                contract_addr: info.sender.to_string(),
+                msg: hook,
+                funds,
+            }
+            .into(),
+            None => BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: funds,
+            }
+            .into(),
+        };
+
+        // SAFE: Nested withdrawals now see the already-debited balance.
+        // If a reply is genuinely needed (e.g. to tolerate a failing hook),
+        // use reply_on_error and RE-CREDIT there, never the other way around.
+        Ok(Response::new()
+            .add_message(send)
+            .add_attribute("action", "withdraw"))
+    }
+}
+
+/*
+ * EXPLOIT SCENARIO:
+ *
+ * 1. Attacker deploys a contract and deposits 100 uatom through it
+ * 2. Attacker contract calls Withdraw { amount: 100, hook: Some(drain_msg) }
+ * 3. Vault checks balance (100 >= 100) and sends 100 uatom to the
+ *    attacker contract's hook, WITHOUT debiting yet
+ * 4. The hook returns WasmMsg::Execute { Withdraw { amount: 100, ... } }
+ *    back to the vault; it runs depth-first before the first reply
+ * 5. Vault checks balance again: still 100, sends another 100 uatom
+ * 6. Hook recurses until the vault is empty, then stops
+ * 7. Replies unwind: each debits 100 with saturating_sub, leaving 0
+ * 8. Attacker withdrew N * 100 uatom against a single 100 uatom deposit
+ */
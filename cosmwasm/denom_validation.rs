@@ -0,0 +1,173 @@
+/*
+ * VULNERABLE COSMWASM CONTRACT - DO NOT USE IN PRODUCTION
+ *
+ * Fake Denom Deposit
+ *
+ * This vault credits info.funds[0].amount without checking the denom and
+ * without handling multiple coins, so attackers can deposit a worthless
+ * token (e.g. a self-minted IBC or tokenfactory denom) and withdraw the
+ * real asset.
+ */
+
+use cosmwasm_schema::cw_serde;
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_json_binary, Addr, BankMsg, Binary, Coin, Deps, DepsMut, Env, MessageInfo, Response,
+    StdError, StdResult, Uint128,
+};
+use cw_storage_plus::{Item, Map};
+
+#[cw_serde]
+pub struct Config {
+    pub denom: String, // e.g. "uatom"
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+pub const DEPOSITS: Map<&Addr, Uint128> = Map::new("deposits");
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub denom: String,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    Deposit {},
+    Withdraw { amount: Uint128 },
+}
+
+#[cw_serde]
+pub enum QueryMsg {
+    Deposit { address: String },
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> StdResult<Response> {
+    CONFIG.save(deps.storage, &Config { denom: msg.denom })?;
+    Ok(Response::new().add_attribute("action", "instantiate"))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> StdResult<Response> {
+    match msg {
+        ExecuteMsg::Deposit {} => deposit(deps, info),
+        ExecuteMsg::Withdraw { amount } => withdraw(deps, info, amount),
+    }
+}
+
+pub fn deposit(deps: DepsMut, info: MessageInfo) -> StdResult<Response> {
+    // VULNERABILITY 1: Only the first coin is looked at
+    // Any additional coins are silently kept by the contract
+    let coin = info
+        .funds
+        .first()
+        .ok_or_else(|| StdError::generic_err("No funds sent"))?;
+
+    // VULNERABILITY 2: coin.denom is never compared with config.denom
+    // 1000 "ibc/FAKE..." is credited exactly like 1000 uatom
+    DEPOSITS.update(deps.storage, &info.sender, |deposit| -> StdResult<_> {
+        Ok(deposit.unwrap_or_default().checked_add(coin.amount)?)
+    })?;
+
+    Ok(Response::new()
+        .add_attribute("action", "deposit")
+        .add_attribute("amount", coin.amount))
+}
+
+pub fn withdraw(deps: DepsMut, info: MessageInfo, amount: Uint128) -> StdResult<Response> {
+    let config = CONFIG.load(deps.storage)?;
+
+    DEPOSITS.update(deps.storage, &info.sender, |deposit| -> StdResult<_> {
+        Ok(deposit.unwrap_or_default().checked_sub(amount)?)
+    })?;
+
+    // Withdrawals always pay out the real denom
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![Coin::new(amount, config.denom)],
+        })
+        .add_attribute("action", "withdraw"))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Deposit { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            to_json_binary(
+                &DEPOSITS
+                    .may_load(deps.storage, &address)?
+                    .unwrap_or_default(),
+            )
+        }
+    }
+}
+
+/*
+ * SECURE VERSION USING must_pay:
+ *
+ * must_pay requires exactly one coin, of the configured denom, with a
+ * non-zero amount, returning PaymentError::NoFunds, MultipleDenoms,
+ * MissingDenom or ExtraDenom otherwise. Contracts accepting several assets
+ * should keep a whitelist of full denoms (including the ibc/<hash> trace)
+ * and key balances by denom.
+ */
+pub mod secure {
+    use super::{ExecuteMsg, CONFIG, DEPOSITS};
+    use cosmwasm_std::{DepsMut, Env, MessageInfo, Response, StdError, StdResult};
+    use cw_utils::{must_pay, PaymentError};
+
+    pub use super::{instantiate, query, withdraw};
+
+    pub fn execute(
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        msg: ExecuteMsg,
+    ) -> StdResult<Response> {
+        match msg {
+            ExecuteMsg::Deposit {} => deposit(deps, info),
+            ExecuteMsg::Withdraw { amount } => withdraw(deps, info, amount),
+        }
+    }
+
+    pub fn deposit(deps: DepsMut, info: MessageInfo) -> StdResult<Response> {
+        let config = CONFIG.load(deps.storage)?;
+
+        // CHECK: Exactly one coin, of the configured denom, non-zero
+        let amount = must_pay(&info, &config.denom)
+            .map_err(|err: PaymentError| StdError::generic_err(err.to_string()))?;
+
+        DEPOSITS.update(deps.storage, &info.sender, |deposit| -> StdResult<_> {
+            Ok(deposit.unwrap_or_default().checked_add(amount)?)
+        })?;
+
+        Ok(Response::new()
+            .add_attribute("action", "deposit")
+            .add_attribute("amount", amount))
+    }
+}
+
+/*
+ * EXPLOIT SCENARIO:
+ *
+ * 1. Vault holds 1,000,000 uatom deposited by honest users
+ * 2. Attacker creates a tokenfactory denom (or bridges a worthless IBC token)
+ *    and mints 1,000,000 units of it
+ * 3. Attacker calls Deposit {} attaching 1,000,000 factory/attacker/fake
+ * 4. Contract credits info.funds[0].amount without checking the denom
+ * 5. Attacker calls Withdraw { amount: 1000000 }
+ * 6. Contract sends 1,000,000 real uatom to the attacker
+ */
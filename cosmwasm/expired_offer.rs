@@ -0,0 +1,248 @@
+/*
+ * VULNERABLE COSMWASM CONTRACT - DO NOT USE IN PRODUCTION
+ *
+ * Missing Expiration Check
+ *
+ * This escrow lets makers lock funds in an offer with an ask price and an
+ * expiration. The accept path never checks the stored Expiration, so a
+ * taker can fill an offer long after the maker's price went stale, and
+ * there is no way for the maker to reclaim expired escrow.
+ */
+
+use cosmwasm_schema::cw_serde;
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_json_binary, Addr, BankMsg, Binary, Coin, Deps, DepsMut, Env, MessageInfo, Response,
+    StdError, StdResult,
+};
+use cw_storage_plus::{Item, Map};
+use cw_utils::Expiration;
+
+#[cw_serde]
+pub struct Offer {
+    pub maker: Addr,
+    /// Funds escrowed by the maker
+    pub offered: Coin,
+    /// What the taker must pay for them
+    pub ask: Coin,
+    pub expires: Expiration,
+}
+
+pub const NEXT_OFFER_ID: Item<u64> = Item::new("next_offer_id");
+pub const OFFERS: Map<u64, Offer> = Map::new("offers");
+
+#[cw_serde]
+pub struct InstantiateMsg {}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Escrow the attached coin in exchange for `ask`, valid until `expires`
+    CreateOffer {
+        ask: Coin,
+        expires: Expiration,
+    },
+    AcceptOffer {
+        id: u64,
+    },
+}
+
+#[cw_serde]
+pub enum QueryMsg {
+    Offer { id: u64 },
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    _msg: InstantiateMsg,
+) -> StdResult<Response> {
+    NEXT_OFFER_ID.save(deps.storage, &0)?;
+    Ok(Response::new().add_attribute("action", "instantiate"))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> StdResult<Response> {
+    match msg {
+        ExecuteMsg::CreateOffer { ask, expires } => create_offer(deps, info, ask, expires),
+        ExecuteMsg::AcceptOffer { id } => accept_offer(deps, info, id),
+    }
+}
+
+pub fn create_offer(
+    deps: DepsMut,
+    info: MessageInfo,
+    ask: Coin,
+    expires: Expiration,
+) -> StdResult<Response> {
+    let offered =
+        cw_utils::one_coin(&info).map_err(|err| StdError::generic_err(err.to_string()))?;
+
+    // VULNERABILITY 1: Expiration accepted as-is
+    // Already-expired or Expiration::Never offers are stored without question
+    let id = NEXT_OFFER_ID.load(deps.storage)?;
+    NEXT_OFFER_ID.save(deps.storage, &(id + 1))?;
+    OFFERS.save(
+        deps.storage,
+        id,
+        &Offer {
+            maker: info.sender,
+            offered,
+            ask,
+            expires,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "create_offer")
+        .add_attribute("id", id.to_string()))
+}
+
+pub fn accept_offer(deps: DepsMut, info: MessageInfo, id: u64) -> StdResult<Response> {
+    let offer = OFFERS.load(deps.storage, id)?;
+
+    // VULNERABILITY 2: offer.expires is never compared with the block
+    // The maker's price is honoured forever, no matter how far the market
+    // has moved since the offer was made
+
+    let paid = cw_utils::must_pay(&info, &offer.ask.denom)
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+    if paid < offer.ask.amount {
+        return Err(StdError::generic_err("Insufficient payment"));
+    }
+
+    OFFERS.remove(deps.storage, id);
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: offer.maker.to_string(),
+            amount: info.funds,
+        })
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![offer.offered],
+        })
+        .add_attribute("action", "accept_offer"))
+}
+
+// VULNERABILITY 3: No refund path
+// Once an offer expires the maker has no message to reclaim the escrow,
+// so the only way the funds ever leave is through accept_offer
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Offer { id } => to_json_binary(&OFFERS.load(deps.storage, id)?),
+    }
+}
+
+/*
+ * SECURE VERSION (EXPIRY VALIDATION AND REFUND):
+ *
+ * Offers must carry a real, future expiration, stale offers can no longer
+ * be filled, and expired escrow can always be returned to the maker.
+ */
+pub mod secure {
+    use super::OFFERS;
+    use cosmwasm_schema::cw_serde;
+    use cosmwasm_std::{BankMsg, Coin, DepsMut, Env, MessageInfo, Response, StdError, StdResult};
+    use cw_utils::Expiration;
+
+    pub use super::{instantiate, query};
+
+    #[cw_serde]
+    pub enum ExecuteMsg {
+        CreateOffer { ask: Coin, expires: Expiration },
+        AcceptOffer { id: u64 },
+        Refund { id: u64 },
+    }
+
+    pub fn execute(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: ExecuteMsg,
+    ) -> StdResult<Response> {
+        match msg {
+            ExecuteMsg::CreateOffer { ask, expires } => create_offer(deps, env, info, ask, expires),
+            ExecuteMsg::AcceptOffer { id } => accept_offer(deps, env, info, id),
+            ExecuteMsg::Refund { id } => refund(deps, env, info, id),
+        }
+    }
+
+    pub fn create_offer(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        ask: Coin,
+        expires: Expiration,
+    ) -> StdResult<Response> {
+        // CHECK: Must be a real expiration that has not passed yet
+        if matches!(expires, Expiration::Never {}) || expires.is_expired(&env.block) {
+            return Err(StdError::generic_err("Invalid expiration"));
+        }
+
+        super::create_offer(deps, info, ask, expires)
+    }
+
+    pub fn accept_offer(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        id: u64,
+    ) -> StdResult<Response> {
+        let offer = OFFERS.load(deps.storage, id)?;
+
+        // CHECK: Stale offers can no longer be filled
+        if offer.expires.is_expired(&env.block) {
+            return Err(StdError::generic_err("Offer expired"));
+        }
+
+        // CHECK: Exact payment, so overpayment is not silently forwarded
+        let paid = cw_utils::must_pay(&info, &offer.ask.denom)
+            .map_err(|err| StdError::generic_err(err.to_string()))?;
+        if paid != offer.ask.amount {
+            return Err(StdError::generic_err("Incorrect payment"));
+        }
+
+        super::accept_offer(deps, info, id)
+    }
+
+    pub fn refund(deps: DepsMut, env: Env, info: MessageInfo, id: u64) -> StdResult<Response> {
+        let offer = OFFERS.load(deps.storage, id)?;
+
+        // SAFE: The maker may cancel at any time; anyone may trigger the
+        // refund once the offer has expired. Funds only ever go to the maker.
+        if info.sender != offer.maker && !offer.expires.is_expired(&env.block) {
+            return Err(StdError::generic_err("Offer still active"));
+        }
+
+        OFFERS.remove(deps.storage, id);
+        Ok(Response::new()
+            .add_message(BankMsg::Send {
+                to_address: offer.maker.to_string(),
+                amount: vec![offer.offered],
+            })
+            .add_attribute("action", "refund"))
+    }
+}
+
+/*
+ * EXPLOIT SCENARIO:
+ *
+ * 1. Maker escrows 1,000 uatom asking 10,000 uusd (ATOM at 10 USD),
+ *    expiring at height 1,000,000
+ * 2. The offer expires; the maker considers it dead and, with no Refund
+ *    message, cannot withdraw the escrow anyway
+ * 3. Months later ATOM trades at 40 USD
+ * 4. Taker calls AcceptOffer { id } at height 3,000,000, paying 10,000 uusd
+ * 5. Contract never checks offer.expires and settles the trade
+ * 6. Taker receives 40,000 USD worth of ATOM for 10,000 uusd
+ */
@@ -0,0 +1,272 @@
+/*
+ * VULNERABLE COSMWASM CONTRACT - DO NOT USE IN PRODUCTION
+ *
+ * Instantiate Front-Running
+ *
+ * This factory assumes it knows the address of the pool it is about to
+ * instantiate and writes it into its registry, and sends seed funds to it,
+ * before the instantiation is confirmed. Classic contract addresses depend
+ * on a chain-global instance counter, so an attacker who instantiates
+ * first squats the expected address and intercepts the funds.
+ */
+
+use cosmwasm_schema::cw_serde;
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_json_binary, Addr, BankMsg, Binary, Coin, Deps, DepsMut, Empty, Env, MessageInfo, Response,
+    StdError, StdResult, Uint128, WasmMsg,
+};
+use cw_storage_plus::{Item, Map};
+
+#[cw_serde]
+pub struct Config {
+    pub owner: Addr,
+    pub pool_code_id: u64,
+    pub seed_denom: String,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+// pair name -> pool contract address
+pub const POOLS: Map<&str, Addr> = Map::new("pools");
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub pool_code_id: u64,
+    pub seed_denom: String,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Owner creates a pool; expected_address is queried off-chain beforehand
+    CreatePool {
+        pair: String,
+        expected_address: String,
+        seed_amount: Uint128,
+    },
+    /// Users route liquidity through the factory registry
+    Deposit { pair: String },
+}
+
+#[cw_serde]
+pub enum QueryMsg {
+    Pool { pair: String },
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> StdResult<Response> {
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            owner: info.sender,
+            pool_code_id: msg.pool_code_id,
+            seed_denom: msg.seed_denom,
+        },
+    )?;
+    Ok(Response::new().add_attribute("action", "instantiate"))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> StdResult<Response> {
+    match msg {
+        ExecuteMsg::CreatePool {
+            pair,
+            expected_address,
+            seed_amount,
+        } => create_pool(deps, info, pair, expected_address, seed_amount),
+        ExecuteMsg::Deposit { pair } => deposit(deps, info, pair),
+    }
+}
+
+pub fn create_pool(
+    deps: DepsMut,
+    info: MessageInfo,
+    pair: String,
+    expected_address: String,
+    seed_amount: Uint128,
+) -> StdResult<Response> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(StdError::generic_err("Unauthorized"));
+    }
+
+    // VULNERABILITY 1: The pool address is assumed, not observed
+    // It was predicted off-chain from the current instance counter, which
+    // any other instantiation on the chain can advance first
+    let expected_address = deps.api.addr_validate(&expected_address)?;
+
+    // VULNERABILITY 2: Registry written before instantiation is confirmed
+    POOLS.save(deps.storage, &pair, &expected_address)?;
+
+    let instantiate = WasmMsg::Instantiate {
+        admin: None,
+        code_id: config.pool_code_id,
+        msg: to_json_binary(&Empty {})?,
+        funds: vec![],
+        // VULNERABILITY 3: Labels are not unique and prove nothing;
+        // an attacker can deploy a contract with the same label
+        label: format!("pool-{pair}"),
+    };
+
+    // VULNERABILITY 4: Seed liquidity sent to the assumed address
+    let seed = BankMsg::Send {
+        to_address: expected_address.to_string(),
+        amount: vec![Coin::new(seed_amount, config.seed_denom)],
+    };
+
+    Ok(Response::new()
+        .add_message(instantiate)
+        .add_message(seed)
+        .add_attribute("action", "create_pool")
+        .add_attribute("pool", expected_address))
+}
+
+pub fn deposit(deps: DepsMut, info: MessageInfo, pair: String) -> StdResult<Response> {
+    let pool = POOLS.load(deps.storage, &pair)?;
+
+    // User funds follow the (possibly squatted) registry entry
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: pool.to_string(),
+            amount: info.funds,
+        })
+        .add_attribute("action", "deposit"))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Pool { pair } => to_json_binary(&POOLS.load(deps.storage, &pair)?),
+    }
+}
+
+/*
+ * SECURE VERSION (REPLY-BASED ADDRESS CAPTURE):
+ *
+ * Nothing is registered or funded until the chain reports the real pool
+ * address in the instantiate reply. Alternative: WasmMsg::Instantiate2 with
+ * a factory-chosen salt. The address is derived from (checksum, factory
+ * address, salt), so nobody else can occupy it and instantiate2_address()
+ * computes it on-chain.
+ */
+pub mod secure {
+    use super::{ExecuteMsg, CONFIG, POOLS};
+    use cosmwasm_schema::cw_serde;
+    use cosmwasm_std::{
+        from_json, to_json_binary, BankMsg, Coin, DepsMut, Empty, Env, MessageInfo, Reply,
+        Response, StdError, StdResult, SubMsg, Uint128, WasmMsg,
+    };
+    use cw_utils::parse_instantiate_response_data;
+
+    pub use super::{deposit, instantiate, query};
+
+    pub const INSTANTIATE_POOL_REPLY_ID: u64 = 1;
+
+    #[cw_serde]
+    pub struct PendingPool {
+        pub pair: String,
+        pub seed_amount: Uint128,
+    }
+
+    pub fn execute(
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        msg: ExecuteMsg,
+    ) -> StdResult<Response> {
+        match msg {
+            // SAFE: expected_address is accepted for message compatibility
+            // but never trusted
+            ExecuteMsg::CreatePool {
+                pair, seed_amount, ..
+            } => create_pool(deps, info, pair, seed_amount),
+            ExecuteMsg::Deposit { pair } => deposit(deps, info, pair),
+        }
+    }
+
+    pub fn create_pool(
+        deps: DepsMut,
+        info: MessageInfo,
+        pair: String,
+        seed_amount: Uint128,
+    ) -> StdResult<Response> {
+        let config = CONFIG.load(deps.storage)?;
+        if info.sender != config.owner {
+            return Err(StdError::generic_err("Unauthorized"));
+        }
+        if POOLS.has(deps.storage, &pair) {
+            return Err(StdError::generic_err("Pool exists"));
+        }
+
+        let instantiate = WasmMsg::Instantiate {
+            admin: None,
+            code_id: config.pool_code_id,
+            msg: to_json_binary(&Empty {})?,
+            funds: vec![],
+            label: format!("pool-{pair}"),
+        };
+
+        // SAFE: Nothing is registered or funded until the chain tells us
+        // the real address in the reply
+        Ok(Response::new()
+            .add_submessage(
+                SubMsg::reply_on_success(instantiate, INSTANTIATE_POOL_REPLY_ID)
+                    .with_payload(to_json_binary(&PendingPool { pair, seed_amount })?),
+            )
+            .add_attribute("action", "create_pool"))
+    }
+
+    pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> StdResult<Response> {
+        if msg.id != INSTANTIATE_POOL_REPLY_ID {
+            return Err(StdError::generic_err("Unknown reply id"));
+        }
+        let pending: PendingPool = from_json(&msg.payload)?;
+        let response = msg.result.into_result().map_err(StdError::generic_err)?;
+
+        // SAFE: Address captured from the instantiate response itself
+        let pool = response
+            .msg_responses
+            .first()
+            .and_then(|resp| parse_instantiate_response_data(&resp.value).ok())
+            .map(|data| data.contract_address)
+            .ok_or_else(|| StdError::generic_err("Missing instantiate response"))?;
+        let pool = deps.api.addr_validate(&pool)?;
+
+        POOLS.save(deps.storage, &pending.pair, &pool)?;
+
+        let config = CONFIG.load(deps.storage)?;
+        Ok(Response::new()
+            .add_message(BankMsg::Send {
+                to_address: pool.to_string(),
+                amount: vec![Coin::new(pending.seed_amount, config.seed_denom)],
+            })
+            .add_attribute("pool", pool))
+    }
+}
+
+/*
+ * EXPLOIT SCENARIO:
+ *
+ * 1. Operator queries the chain and predicts the next classic contract
+ *    address, then submits CreatePool { pair: "ATOM-OSMO", expected_address }
+ * 2. Attacker sees the pending transaction in the mempool
+ * 3. Attacker front-runs it by instantiating the same pool code id
+ *    themselves (instantiation is permissionless; classic addresses depend
+ *    only on code id and instance counter), so their instance - which
+ *    records the attacker as its owner - is assigned the predicted address
+ * 4. Factory tx lands: its real pool gets a different address, but the
+ *    registry stores and funds the predicted (attacker's) address
+ * 5. Seed liquidity and every later Deposit { pair: "ATOM-OSMO" } flow
+ *    into the attacker-owned pool, which the attacker withdraws
+ */
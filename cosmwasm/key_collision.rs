@@ -0,0 +1,285 @@
+/*
+ * VULNERABLE COSMWASM CONTRACT - DO NOT USE IN PRODUCTION
+ *
+ * Storage Key Collision
+ *
+ * This contract builds raw storage keys by concatenating a namespace with
+ * a user-chosen name. Without length prefixes, one namespace's keys can
+ * spell another namespace's keys - the same ambiguity as concatenated
+ * PDA seeds on Solana - letting attackers overwrite other users' records.
+ */
+
+use cosmwasm_schema::cw_serde;
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    from_json, to_json_binary, to_json_vec, Addr, BankMsg, Binary, Deps, DepsMut, Env, MessageInfo,
+    Response, StdError, StdResult, Storage,
+};
+
+// Namespaces for the two kinds of per-pool records
+pub const POOL_OWNER_NAMESPACE: &str = "pool";
+pub const FEE_RECIPIENT_NAMESPACE: &str = "poolfee";
+
+#[cw_serde]
+pub struct InstantiateMsg {}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    CreatePool {
+        name: String,
+    },
+    SetFeeRecipient {
+        pool: String,
+        recipient: String,
+    },
+    /// Traders pay swap fees here; they are forwarded to the fee recipient
+    PayFees {
+        pool: String,
+    },
+}
+
+#[cw_serde]
+pub enum QueryMsg {
+    PoolOwner { pool: String },
+    FeeRecipient { pool: String },
+}
+
+// VULNERABILITY 1: Keys are plain concatenations
+// key("pool", "feeatom") == key("poolfee", "atom") == b"poolfeeatom"
+pub fn key(namespace: &str, name: &str) -> Vec<u8> {
+    [namespace.as_bytes(), name.as_bytes()].concat()
+}
+
+fn load_addr(storage: &dyn Storage, key: &[u8]) -> StdResult<Option<Addr>> {
+    storage.get(key).map(|raw| from_json(&raw)).transpose()
+}
+
+fn save_addr(storage: &mut dyn Storage, key: &[u8], addr: &Addr) -> StdResult<()> {
+    storage.set(key, &to_json_vec(addr)?);
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    _deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    _msg: InstantiateMsg,
+) -> StdResult<Response> {
+    Ok(Response::new().add_attribute("action", "instantiate"))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> StdResult<Response> {
+    match msg {
+        ExecuteMsg::CreatePool { name } => create_pool(deps, info, name),
+        ExecuteMsg::SetFeeRecipient { pool, recipient } => {
+            set_fee_recipient(deps, info, pool, recipient)
+        }
+        ExecuteMsg::PayFees { pool } => pay_fees(deps, info, pool),
+    }
+}
+
+pub fn create_pool(deps: DepsMut, info: MessageInfo, name: String) -> StdResult<Response> {
+    // VULNERABILITY 2: Pool names are not validated
+    // Names may start with another namespace's suffix ("fee...")
+    let owner_key = key(POOL_OWNER_NAMESPACE, &name);
+
+    // The uniqueness check only looks at the colliding raw key
+    if load_addr(deps.storage, &owner_key)?.is_some() {
+        return Err(StdError::generic_err("Pool already exists"));
+    }
+
+    save_addr(deps.storage, &owner_key, &info.sender)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "create_pool")
+        .add_attribute("pool", name))
+}
+
+pub fn set_fee_recipient(
+    deps: DepsMut,
+    info: MessageInfo,
+    pool: String,
+    recipient: String,
+) -> StdResult<Response> {
+    let owner = load_addr(deps.storage, &key(POOL_OWNER_NAMESPACE, &pool))?
+        .ok_or_else(|| StdError::generic_err("Unknown pool"))?;
+
+    if info.sender != owner {
+        return Err(StdError::generic_err("Unauthorized"));
+    }
+
+    let recipient = deps.api.addr_validate(&recipient)?;
+    save_addr(
+        deps.storage,
+        &key(FEE_RECIPIENT_NAMESPACE, &pool),
+        &recipient,
+    )?;
+
+    Ok(Response::new().add_attribute("action", "set_fee_recipient"))
+}
+
+pub fn pay_fees(deps: DepsMut, info: MessageInfo, pool: String) -> StdResult<Response> {
+    let owner = load_addr(deps.storage, &key(POOL_OWNER_NAMESPACE, &pool))?
+        .ok_or_else(|| StdError::generic_err("Unknown pool"))?;
+
+    // VULNERABILITY 3: Reads a key another pool's registration can write
+    // Fee recipient defaults to the owner only when the key is empty
+    let recipient = load_addr(deps.storage, &key(FEE_RECIPIENT_NAMESPACE, &pool))?.unwrap_or(owner);
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: info.funds,
+        })
+        .add_attribute("action", "pay_fees"))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::PoolOwner { pool } => {
+            to_json_binary(&load_addr(deps.storage, &key(POOL_OWNER_NAMESPACE, &pool))?)
+        }
+        QueryMsg::FeeRecipient { pool } => to_json_binary(&load_addr(
+            deps.storage,
+            &key(FEE_RECIPIENT_NAMESPACE, &pool),
+        )?),
+    }
+}
+
+/*
+ * SECURE VERSION (LENGTH-PREFIXED KEYS):
+ *
+ * cw-storage-plus length-prefixes the namespace (and every element of
+ * composite keys), so b"pool" + "feeatom" can never equal b"poolfee" +
+ * "atom" in storage. When raw keys are unavoidable, length-prefix each
+ * component manually, as key() does below.
+ */
+pub mod secure {
+    use super::{ExecuteMsg, QueryMsg};
+    use cosmwasm_std::{
+        to_json_binary, Addr, BankMsg, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError,
+        StdResult,
+    };
+    use cw_storage_plus::Map;
+
+    pub use super::instantiate;
+
+    pub const POOL_OWNERS: Map<&str, Addr> = Map::new("pool");
+    pub const FEE_RECIPIENTS: Map<&str, Addr> = Map::new("poolfee");
+
+    pub fn key(namespace: &str, name: &str) -> Vec<u8> {
+        let mut key = Vec::with_capacity(4 + namespace.len() + name.len());
+        key.extend_from_slice(&(namespace.len() as u16).to_be_bytes());
+        key.extend_from_slice(namespace.as_bytes());
+        key.extend_from_slice(&(name.len() as u16).to_be_bytes());
+        key.extend_from_slice(name.as_bytes());
+        key
+    }
+
+    pub fn execute(
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        msg: ExecuteMsg,
+    ) -> StdResult<Response> {
+        match msg {
+            ExecuteMsg::CreatePool { name } => create_pool(deps, info, name),
+            ExecuteMsg::SetFeeRecipient { pool, recipient } => {
+                set_fee_recipient(deps, info, pool, recipient)
+            }
+            ExecuteMsg::PayFees { pool } => pay_fees(deps, info, pool),
+        }
+    }
+
+    pub fn create_pool(deps: DepsMut, info: MessageInfo, name: String) -> StdResult<Response> {
+        // CHECK: Restrict names to a simple, bounded alphabet
+        if name.is_empty()
+            || name.len() > 32
+            || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        {
+            return Err(StdError::generic_err("Invalid pool name"));
+        }
+
+        if POOL_OWNERS.has(deps.storage, &name) {
+            return Err(StdError::generic_err("Pool already exists"));
+        }
+        POOL_OWNERS.save(deps.storage, &name, &info.sender)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "create_pool")
+            .add_attribute("pool", name))
+    }
+
+    pub fn set_fee_recipient(
+        deps: DepsMut,
+        info: MessageInfo,
+        pool: String,
+        recipient: String,
+    ) -> StdResult<Response> {
+        let owner = POOL_OWNERS
+            .may_load(deps.storage, &pool)?
+            .ok_or_else(|| StdError::generic_err("Unknown pool"))?;
+
+        if info.sender != owner {
+            return Err(StdError::generic_err("Unauthorized"));
+        }
+
+        let recipient = deps.api.addr_validate(&recipient)?;
+        FEE_RECIPIENTS.save(deps.storage, &pool, &recipient)?;
+
+        Ok(Response::new().add_attribute("action", "set_fee_recipient"))
+    }
+
+    pub fn pay_fees(deps: DepsMut, info: MessageInfo, pool: String) -> StdResult<Response> {
+        let owner = POOL_OWNERS
+            .may_load(deps.storage, &pool)?
+            .ok_or_else(|| StdError::generic_err("Unknown pool"))?;
+
+        // SAFE: Only SetFeeRecipient for this exact pool can write this key
+        let recipient = FEE_RECIPIENTS
+            .may_load(deps.storage, &pool)?
+            .unwrap_or(owner);
+
+        Ok(Response::new()
+            .add_message(BankMsg::Send {
+                to_address: recipient.to_string(),
+                amount: info.funds,
+            })
+            .add_attribute("action", "pay_fees"))
+    }
+
+    pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+        match msg {
+            QueryMsg::PoolOwner { pool } => {
+                to_json_binary(&POOL_OWNERS.may_load(deps.storage, &pool)?)
+            }
+            QueryMsg::FeeRecipient { pool } => {
+                to_json_binary(&FEE_RECIPIENTS.may_load(deps.storage, &pool)?)
+            }
+        }
+    }
+}
+
+/*
+ * EXPLOIT SCENARIO:
+ *
+ * 1. Victim creates pool "atom"; owner stored at b"poolatom",
+ *    fee recipient unset (defaults to the victim)
+ * 2. Attacker creates pool "feeatom"; owner stored at
+ *    key("pool", "feeatom") = b"poolfeeatom"
+ * 3. b"poolfeeatom" is exactly key("poolfee", "atom") -
+ *    the victim pool's fee recipient slot now holds the attacker
+ * 4. Every PayFees { pool: "atom" } forwards the victim's fees to the attacker
+ * 5. Even the victim's fix is corrupted: SetFeeRecipient { pool: "atom" }
+ *    rewrites b"poolfeeatom", silently changing the owner of pool "feeatom"
+ *    too - both records share a single storage slot
+ */
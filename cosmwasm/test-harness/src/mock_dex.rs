@@ -0,0 +1,187 @@
+//! Two-asset constant-product pool
+//!
+//! Speaks the message shapes the examples expect from a DEX:
+//! `Swap { ask_denom, recipient }` (unchecked_reply.rs) answers with a
+//! `SwapResult` in its response data, `Reserves {}` (spot_price_trust.rs)
+//! returns the pool reserves, and `CumulativePrice { denom }` exposes
+//! Uniswap-V2-style cumulative prices for TWAP consumers.
+//!
+//! Reserves are tracked internally (seeded by the instantiate funds), so
+//! plain bank transfers to the pool do not move its price.
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    to_json_binary, BankMsg, Binary, Coin, Decimal256, Deps, DepsMut, Empty, Env, MessageInfo,
+    Response, StdError, StdResult, Uint128,
+};
+use cw_multi_test::{Contract, ContractWrapper};
+use cw_storage_plus::Item;
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub denoms: [String; 2],
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Swap the attached coin for the other pool asset
+    Swap {
+        ask_denom: Option<String>,
+        recipient: Option<String>,
+    },
+}
+
+#[cw_serde]
+pub enum QueryMsg {
+    Reserves {},
+    /// Running sum of `price(denom) * seconds`, priced in the other asset
+    CumulativePrice {
+        denom: String,
+    },
+}
+
+#[cw_serde]
+pub struct ReservesResponse {
+    pub reserves: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct CumulativePriceResponse {
+    pub price_cumulative: Decimal256,
+    pub timestamp: u64,
+}
+
+#[cw_serde]
+pub struct SwapResult {
+    pub recipient: String,
+    pub denom: String,
+    pub amount_out: Uint128,
+}
+
+#[cw_serde]
+struct Oracle {
+    price_cumulative: [Decimal256; 2],
+    timestamp: u64,
+}
+
+const DENOMS: Item<[String; 2]> = Item::new("denoms");
+const RESERVES: Item<[Uint128; 2]> = Item::new("reserves");
+const ORACLE: Item<Oracle> = Item::new("oracle");
+
+pub fn contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(execute, instantiate, query))
+}
+
+pub fn instantiate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> StdResult<Response> {
+    let reserves = msg.denoms.clone().map(|denom| {
+        info.funds
+            .iter()
+            .filter(|coin| coin.denom == denom)
+            .map(|coin| coin.amount)
+            .sum()
+    });
+    DENOMS.save(deps.storage, &msg.denoms)?;
+    RESERVES.save(deps.storage, &reserves)?;
+    ORACLE.save(
+        deps.storage,
+        &Oracle {
+            price_cumulative: [Decimal256::zero(); 2],
+            timestamp: env.block.time.seconds(),
+        },
+    )?;
+    Ok(Response::new())
+}
+
+/// Cumulative prices carried forward to `env.block.time` at `reserves`.
+///
+/// Called before reserves change, so a swap only ever affects the price
+/// accrued from its own block onwards.
+fn accumulate(oracle: &Oracle, reserves: &[Uint128; 2], env: &Env) -> StdResult<Oracle> {
+    let now = env.block.time.seconds();
+    let elapsed = Decimal256::from_ratio(now.saturating_sub(oracle.timestamp), 1u64);
+    let mut price_cumulative = oracle.price_cumulative;
+    if !reserves[0].is_zero() && !reserves[1].is_zero() {
+        for (index, cumulative) in price_cumulative.iter_mut().enumerate() {
+            let price = Decimal256::from_ratio(reserves[1 - index], reserves[index]);
+            *cumulative = cumulative.checked_add(price.checked_mul(elapsed)?)?;
+        }
+    }
+    Ok(Oracle {
+        price_cumulative,
+        timestamp: now,
+    })
+}
+
+pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+    let ExecuteMsg::Swap {
+        ask_denom,
+        recipient,
+    } = msg;
+    let denoms = DENOMS.load(deps.storage)?;
+    let offer = cw_utils::one_coin(&info).map_err(|err| StdError::generic_err(err.to_string()))?;
+
+    let offer_index = denoms
+        .iter()
+        .position(|denom| *denom == offer.denom)
+        .ok_or_else(|| StdError::generic_err("Unsupported denom"))?;
+    let ask_index = 1 - offer_index;
+    let ask = denoms[ask_index].clone();
+    if ask_denom.is_some_and(|denom| denom != ask) {
+        return Err(StdError::generic_err("Unsupported pair"));
+    }
+    let recipient = match recipient {
+        Some(recipient) => deps.api.addr_validate(&recipient)?,
+        None => info.sender,
+    };
+
+    let mut reserves = RESERVES.load(deps.storage)?;
+    let oracle = accumulate(&ORACLE.load(deps.storage)?, &reserves, &env)?;
+    ORACLE.save(deps.storage, &oracle)?;
+
+    let amount_out =
+        reserves[ask_index].multiply_ratio(offer.amount, reserves[offer_index] + offer.amount);
+    reserves[offer_index] = reserves[offer_index].checked_add(offer.amount)?;
+    reserves[ask_index] = reserves[ask_index].checked_sub(amount_out)?;
+    RESERVES.save(deps.storage, &reserves)?;
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: vec![Coin::new(amount_out, &ask)],
+        })
+        .set_data(to_json_binary(&SwapResult {
+            recipient: recipient.to_string(),
+            denom: ask,
+            amount_out,
+        })?))
+}
+
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    let denoms = DENOMS.load(deps.storage)?;
+    let reserves = RESERVES.load(deps.storage)?;
+    match msg {
+        QueryMsg::Reserves {} => to_json_binary(&ReservesResponse {
+            reserves: denoms
+                .into_iter()
+                .zip(reserves)
+                .map(|(denom, amount)| Coin::new(amount, denom))
+                .collect(),
+        }),
+        QueryMsg::CumulativePrice { denom } => {
+            let index = denoms
+                .iter()
+                .position(|pool_denom| *pool_denom == denom)
+                .ok_or_else(|| StdError::generic_err("Unsupported denom"))?;
+            let oracle = accumulate(&ORACLE.load(deps.storage)?, &reserves, &env)?;
+            to_json_binary(&CumulativePriceResponse {
+                price_cumulative: oracle.price_cumulative[index],
+                timestamp: oracle.timestamp,
+            })
+        }
+    }
+}
@@ -0,0 +1,130 @@
+//! The paged distribution in unbounded_iteration's fix: a round walks the
+//! stakers a page at a time and pays each exactly once, against the stakes
+//! it started with
+
+use cosmwasm_std::{coins, from_json, Addr};
+use cosmwasm_test_harness::contracts::unbounded_iteration::{
+    secure::{self, DistributionRound, ExecuteMsg, MIN_STAKE},
+    InstantiateMsg,
+};
+use cosmwasm_test_harness::{Harness, ATOM};
+use cw_multi_test::ContractWrapper;
+
+const STAKERS: u128 = 25;
+const PAGE: u32 = 10;
+const REWARDS: u128 = 1_000_003;
+
+/// The secure contract with [`STAKERS`] stakers, staking 1 to 25 times
+/// the minimum, and [`REWARDS`] to pay out; the stakers and their stakes
+/// in address order, as the contract walks them
+fn staked() -> (Harness, Addr, Vec<(Addr, u128)>) {
+    let mut h = Harness::new();
+    let owner = h.owner.clone();
+    let contract = h
+        .deploy(
+            Box::new(ContractWrapper::new(
+                secure::execute,
+                secure::instantiate,
+                secure::query,
+            )),
+            &InstantiateMsg {
+                denom: ATOM.to_string(),
+            },
+            &[],
+            "staking",
+        )
+        .unwrap();
+    let mut stakers: Vec<(Addr, u128)> = (1..=STAKERS)
+        .map(|i| (h.account(&format!("staker{i}")), i * MIN_STAKE.u128()))
+        .collect();
+    for (staker, stake) in &stakers {
+        h.fund(staker, &coins(*stake, ATOM));
+        h.execute(
+            staker,
+            &contract,
+            &ExecuteMsg::Register {},
+            &coins(*stake, ATOM),
+        )
+        .unwrap();
+    }
+    h.send(&owner, &contract, &coins(REWARDS, ATOM)).unwrap();
+    stakers.sort();
+    (h, contract, stakers)
+}
+
+fn round(h: &Harness, contract: &Addr) -> Option<DistributionRound> {
+    let raw = h
+        .app
+        .wrap()
+        .query_wasm_raw(contract, b"round".as_slice())
+        .unwrap()
+        .expect("a distribution has run");
+    from_json(raw).unwrap()
+}
+
+fn distribute(h: &mut Harness, contract: &Addr) {
+    let owner = h.owner.clone();
+    h.execute(
+        &owner,
+        contract,
+        &ExecuteMsg::Distribute { limit: Some(PAGE) },
+        &[],
+    )
+    .unwrap();
+}
+
+#[test]
+fn every_staker_is_paid_once_across_the_pages() {
+    let (mut h, contract, stakers) = staked();
+    let total: u128 = stakers.iter().map(|(_, stake)| stake).sum();
+
+    // Pages of 10, 10 and 5: the cursor stops on each page's last staker
+    for page in [10, 20] {
+        distribute(&mut h, &contract);
+        let round = round(&h, &contract).expect("the round is still open");
+        assert_eq!(round.cursor.as_ref(), Some(&stakers[page - 1].0));
+        assert_eq!(round.rewards.u128(), REWARDS);
+        assert_eq!(round.total_staked.u128(), total);
+        for (index, (staker, _)) in stakers.iter().enumerate() {
+            assert_eq!(h.balance(staker, ATOM) > 0, index < page, "{staker}");
+        }
+    }
+    distribute(&mut h, &contract);
+    assert_eq!(round(&h, &contract), None, "the last page closes the round");
+
+    let mut paid = 0;
+    for (staker, stake) in &stakers {
+        let share = REWARDS * stake / total;
+        assert_eq!(h.balance(staker, ATOM), share, "{staker}");
+        paid += share;
+    }
+    // Only the rounding of each share stays behind
+    assert!(paid <= REWARDS && REWARDS - paid < STAKERS, "{paid}");
+    assert_eq!(h.balance(&contract, ATOM), total + REWARDS - paid);
+}
+
+#[test]
+fn stakes_are_frozen_while_a_round_is_open() {
+    let (mut h, contract, _) = staked();
+    let late = h.account("late");
+    h.fund(&late, &coins(MIN_STAKE.u128(), ATOM));
+    let register = |h: &mut Harness| {
+        h.execute(
+            &late,
+            &contract,
+            &ExecuteMsg::Register {},
+            &coins(MIN_STAKE.u128(), ATOM),
+        )
+    };
+
+    distribute(&mut h, &contract);
+    let err = register(&mut h).unwrap_err();
+    assert!(
+        format!("{err:#}").contains("Distribution in progress"),
+        "{err:#}"
+    );
+
+    distribute(&mut h, &contract);
+    distribute(&mut h, &contract);
+    register(&mut h).unwrap();
+}
@@ -0,0 +1,93 @@
+//! Every example ships a runnable exploit and a fix it cannot break
+
+use std::fs;
+use std::path::Path;
+
+use cosmwasm_test_harness::exploits::{find, EXPLOITS};
+
+fn run(example: &str) {
+    find(example)
+        .unwrap_or_else(|| panic!("no exploit registered for {example}"))
+        .assert();
+}
+
+#[test]
+fn allowance_race() {
+    run("allowance_race");
+}
+
+#[test]
+fn callback_ordering() {
+    run("callback_ordering");
+}
+
+#[test]
+fn denom_validation() {
+    run("denom_validation");
+}
+
+#[test]
+fn expired_offer() {
+    run("expired_offer");
+}
+
+#[test]
+fn instantiate_frontrun() {
+    run("instantiate_frontrun");
+}
+
+#[test]
+fn key_collision() {
+    run("key_collision");
+}
+
+#[test]
+fn raw_math_overflow() {
+    run("raw_math_overflow");
+}
+
+#[test]
+fn share_rounding() {
+    run("share_rounding");
+}
+
+#[test]
+fn spot_price_trust() {
+    run("spot_price_trust");
+}
+
+#[test]
+fn unbounded_iteration() {
+    run("unbounded_iteration");
+}
+
+#[test]
+fn unchecked_reply() {
+    run("unchecked_reply");
+}
+
+#[test]
+fn unprotected_migrate() {
+    run("unprotected_migrate");
+}
+
+#[test]
+fn every_example_has_an_exploit() {
+    let examples_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+    let mut examples: Vec<String> = fs::read_dir(&examples_dir)
+        .unwrap()
+        .filter_map(|entry| {
+            let path = entry.unwrap().path();
+            (path.extension()? == "rs").then(|| path.file_stem()?.to_str().map(String::from))?
+        })
+        .collect();
+    examples.sort();
+
+    let mut registered: Vec<String> = EXPLOITS
+        .iter()
+        .map(|spec| spec.example.to_string())
+        .collect();
+    registered.sort();
+
+    assert_eq!(examples, registered);
+}
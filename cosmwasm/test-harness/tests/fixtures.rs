@@ -0,0 +1,120 @@
+//! Sanity checks for the shared mocks
+
+use cosmwasm_std::{coin, coins, Uint128};
+use cosmwasm_test_harness::{mock_cw20, mock_dex, Harness, ATOM, USD};
+use cw20::{AllowanceResponse, BalanceResponse, Cw20Coin, Cw20ExecuteMsg, Cw20QueryMsg};
+
+#[test]
+fn mock_dex_prices_by_constant_product() {
+    let mut h = Harness::new();
+    let trader = h.attacker.clone();
+    let pool = h
+        .deploy(
+            mock_dex::contract(),
+            &mock_dex::InstantiateMsg {
+                denoms: [ATOM.to_string(), USD.to_string()],
+            },
+            &[coin(1_000, ATOM), coin(1_000, USD)],
+            "pool",
+        )
+        .unwrap();
+
+    let before = h.balance(&trader, USD);
+    h.execute(
+        &trader,
+        &pool,
+        &mock_dex::ExecuteMsg::Swap {
+            ask_denom: Some(USD.to_string()),
+            recipient: None,
+        },
+        &coins(1_000, ATOM),
+    )
+    .unwrap();
+
+    // 1,000 * 1,000 / (1,000 + 1,000)
+    assert_eq!(h.balance(&trader, USD), before + 500);
+
+    let reserves: mock_dex::ReservesResponse = h
+        .app
+        .wrap()
+        .query_wasm_smart(&pool, &mock_dex::QueryMsg::Reserves {})
+        .unwrap();
+    assert_eq!(reserves.reserves, vec![coin(2_000, ATOM), coin(500, USD)]);
+}
+
+#[test]
+fn mock_cw20_decrease_allowance_is_relative() {
+    let mut h = Harness::new();
+    let (alice, bob) = (h.victim.clone(), h.attacker.clone());
+    let token = h
+        .deploy(
+            mock_cw20::contract(),
+            &mock_cw20::InstantiateMsg {
+                initial_balances: vec![Cw20Coin {
+                    address: alice.to_string(),
+                    amount: Uint128::new(1_000),
+                }],
+            },
+            &[],
+            "token",
+        )
+        .unwrap();
+
+    let spend = |amount: u128| Cw20ExecuteMsg::TransferFrom {
+        owner: alice.to_string(),
+        recipient: bob.to_string(),
+        amount: Uint128::new(amount),
+    };
+
+    h.execute(
+        &alice,
+        &token,
+        &Cw20ExecuteMsg::IncreaseAllowance {
+            spender: bob.to_string(),
+            amount: Uint128::new(100),
+            expires: None,
+        },
+        &[],
+    )
+    .unwrap();
+    h.execute(&bob, &token, &spend(100), &[]).unwrap();
+    h.execute(
+        &alice,
+        &token,
+        &Cw20ExecuteMsg::DecreaseAllowance {
+            spender: bob.to_string(),
+            amount: Uint128::new(50),
+            expires: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    // The front-run spend already used the allowance; nothing is left
+    h.execute(&bob, &token, &spend(50), &[]).unwrap_err();
+
+    let allowance: AllowanceResponse = h
+        .app
+        .wrap()
+        .query_wasm_smart(
+            &token,
+            &Cw20QueryMsg::Allowance {
+                owner: alice.to_string(),
+                spender: bob.to_string(),
+            },
+        )
+        .unwrap();
+    assert!(allowance.allowance.is_zero());
+
+    let balance: BalanceResponse = h
+        .app
+        .wrap()
+        .query_wasm_smart(
+            &token,
+            &Cw20QueryMsg::Balance {
+                address: bob.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(balance.balance, Uint128::new(100));
+}
@@ -0,0 +1,325 @@
+/*
+ * VULNERABLE COSMWASM CONTRACT - DO NOT USE IN PRODUCTION
+ *
+ * Unbounded Storage Iteration (Gas DoS)
+ *
+ * This contract pays out every entry of a Map in a single execute message.
+ * Registration is free and unbounded, so an attacker can register
+ * thousands of entries until distribution exceeds the block gas limit
+ * and rewards are locked forever.
+ */
+
+use cosmwasm_schema::cw_serde;
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_json_binary, Addr, BankMsg, Binary, Coin, Deps, DepsMut, Env, MessageInfo, Order, Response,
+    StdError, StdResult, Uint128,
+};
+use cw_storage_plus::{Item, Map};
+
+#[cw_serde]
+pub struct Config {
+    pub owner: Addr,
+    pub denom: String,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+pub const TOTAL_STAKED: Item<Uint128> = Item::new("total_staked");
+pub const STAKERS: Map<&Addr, Uint128> = Map::new("stakers");
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub denom: String,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    Register {},
+    Distribute {},
+}
+
+#[cw_serde]
+pub enum QueryMsg {
+    Stakers {},
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> StdResult<Response> {
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            owner: info.sender,
+            denom: msg.denom,
+        },
+    )?;
+    TOTAL_STAKED.save(deps.storage, &Uint128::zero())?;
+    Ok(Response::new().add_attribute("action", "instantiate"))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+    match msg {
+        ExecuteMsg::Register {} => register(deps, info),
+        ExecuteMsg::Distribute {} => distribute(deps, env, info),
+    }
+}
+
+pub fn register(deps: DepsMut, info: MessageInfo) -> StdResult<Response> {
+    let config = CONFIG.load(deps.storage)?;
+    let amount = info
+        .funds
+        .iter()
+        .find(|coin| coin.denom == config.denom)
+        .map(|coin| coin.amount)
+        .unwrap_or_default();
+
+    // VULNERABILITY 1: No minimum stake and no cap on entries
+    // Registering with zero funds still creates a storage entry
+    STAKERS.update(deps.storage, &info.sender, |stake| -> StdResult<_> {
+        Ok(stake.unwrap_or_default().checked_add(amount)?)
+    })?;
+    TOTAL_STAKED.update(deps.storage, |total| -> StdResult<_> {
+        Ok(total.checked_add(amount)?)
+    })?;
+
+    Ok(Response::new().add_attribute("action", "register"))
+}
+
+pub fn distribute(deps: DepsMut, env: Env, info: MessageInfo) -> StdResult<Response> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(StdError::generic_err("Unauthorized"));
+    }
+
+    let total_staked = TOTAL_STAKED.load(deps.storage)?;
+    if total_staked.is_zero() {
+        return Err(StdError::generic_err("Nothing staked"));
+    }
+
+    let rewards = deps
+        .querier
+        .query_balance(env.contract.address, &config.denom)?
+        .amount
+        .checked_sub(total_staked)?;
+
+    // VULNERABILITY 2: Iterates every entry of the Map in one message
+    // Gas grows linearly with the number of stakers; once it exceeds the
+    // block gas limit, Distribute can never succeed again
+    let stakers: Vec<(Addr, Uint128)> = STAKERS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<_>>()?;
+
+    // VULNERABILITY 3: One BankMsg per entry, including zero-stake entries
+    let mut response = Response::new().add_attribute("action", "distribute");
+    for (staker, stake) in stakers {
+        let share = rewards.multiply_ratio(stake, total_staked);
+        response = response.add_message(BankMsg::Send {
+            to_address: staker.to_string(),
+            amount: vec![Coin::new(share, &config.denom)],
+        });
+    }
+
+    Ok(response)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        // VULNERABILITY 4: Unbounded query also exceeds the query gas limit
+        QueryMsg::Stakers {} => {
+            let stakers: Vec<(Addr, Uint128)> = STAKERS
+                .range(deps.storage, None, None, Order::Ascending)
+                .collect::<StdResult<_>>()?;
+            to_json_binary(&stakers)
+        }
+    }
+}
+
+/*
+ * SECURE VERSION (PAGINATED DISTRIBUTION):
+ *
+ * Entries cost a minimum stake, and distribution walks the Map one bounded
+ * page per message, resuming from a stored cursor. A pull-based design
+ * (per-user reward index, users claim themselves) avoids iteration entirely
+ * and is preferable for large user sets.
+ */
+pub mod secure {
+    use super::{CONFIG, STAKERS, TOTAL_STAKED};
+    use cosmwasm_schema::cw_serde;
+    use cosmwasm_std::{
+        to_json_binary, Addr, BankMsg, Binary, Coin, Deps, DepsMut, Env, MessageInfo, Order,
+        Response, StdError, StdResult, Uint128,
+    };
+    use cw_storage_plus::{Bound, Item};
+
+    pub use super::instantiate;
+
+    pub const MIN_STAKE: Uint128 = Uint128::new(1_000_000);
+    pub const DEFAULT_LIMIT: u32 = 10;
+    pub const MAX_LIMIT: u32 = 30;
+
+    #[cw_serde]
+    pub enum ExecuteMsg {
+        Register {},
+        Distribute { limit: Option<u32> },
+    }
+
+    #[cw_serde]
+    pub enum QueryMsg {
+        Stakers {
+            start_after: Option<String>,
+            limit: Option<u32>,
+        },
+    }
+
+    #[cw_serde]
+    pub struct DistributionRound {
+        pub rewards: Uint128,
+        pub total_staked: Uint128,
+        pub cursor: Option<Addr>,
+    }
+
+    pub const ROUND: Item<Option<DistributionRound>> = Item::new("round");
+
+    pub fn execute(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: ExecuteMsg,
+    ) -> StdResult<Response> {
+        match msg {
+            ExecuteMsg::Register {} => register(deps, info),
+            ExecuteMsg::Distribute { limit } => distribute_page(deps, env, info, limit),
+        }
+    }
+
+    pub fn register(deps: DepsMut, info: MessageInfo) -> StdResult<Response> {
+        let config = CONFIG.load(deps.storage)?;
+
+        // CHECK: Stakes are frozen while a round pays out; each page pays a
+        // stake against the total snapshotted when the round started
+        if ROUND.may_load(deps.storage)?.flatten().is_some() {
+            return Err(StdError::generic_err("Distribution in progress"));
+        }
+
+        // CHECK: Entries cost real funds, making mass registration expensive
+        let amount = cw_utils::must_pay(&info, &config.denom)
+            .map_err(|err| StdError::generic_err(err.to_string()))?;
+        if amount < MIN_STAKE {
+            return Err(StdError::generic_err("Stake below minimum"));
+        }
+
+        STAKERS.update(deps.storage, &info.sender, |stake| -> StdResult<_> {
+            Ok(stake.unwrap_or_default().checked_add(amount)?)
+        })?;
+        TOTAL_STAKED.update(deps.storage, |total| -> StdResult<_> {
+            Ok(total.checked_add(amount)?)
+        })?;
+
+        Ok(Response::new().add_attribute("action", "register"))
+    }
+
+    pub fn distribute_page(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        limit: Option<u32>,
+    ) -> StdResult<Response> {
+        let config = CONFIG.load(deps.storage)?;
+        if info.sender != config.owner {
+            return Err(StdError::generic_err("Unauthorized"));
+        }
+
+        // Snapshot rewards and total once, at the start of a round
+        let mut round = match ROUND.may_load(deps.storage)?.flatten() {
+            Some(round) => round,
+            None => {
+                let total_staked = TOTAL_STAKED.load(deps.storage)?;
+                if total_staked.is_zero() {
+                    return Err(StdError::generic_err("Nothing staked"));
+                }
+                let rewards = deps
+                    .querier
+                    .query_balance(env.contract.address, &config.denom)?
+                    .amount
+                    .checked_sub(total_staked)?;
+                DistributionRound {
+                    rewards,
+                    total_staked,
+                    cursor: None,
+                }
+            }
+        };
+
+        // SAFE: Bounded page starting after the stored cursor
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start = round.cursor.as_ref().map(Bound::exclusive);
+        let page: Vec<(Addr, Uint128)> = STAKERS
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .collect::<StdResult<_>>()?;
+
+        // SAFE: Zero shares are skipped instead of emitting empty sends
+        let mut response = Response::new().add_attribute("action", "distribute_page");
+        for (staker, stake) in &page {
+            let share = round.rewards.multiply_ratio(*stake, round.total_staked);
+            if !share.is_zero() {
+                response = response.add_message(BankMsg::Send {
+                    to_address: staker.to_string(),
+                    amount: vec![Coin::new(share, &config.denom)],
+                });
+            }
+        }
+
+        // Advance the cursor, or close the round when the last page is done
+        if page.len() < limit {
+            ROUND.save(deps.storage, &None)?;
+        } else {
+            round.cursor = page.last().map(|(staker, _)| staker.clone());
+            ROUND.save(deps.storage, &Some(round))?;
+        }
+
+        Ok(response)
+    }
+
+    pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+        match msg {
+            // SAFE: Queries are paginated and clamped the same way
+            QueryMsg::Stakers { start_after, limit } => {
+                let start_after = start_after
+                    .map(|addr| deps.api.addr_validate(&addr))
+                    .transpose()?;
+                let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+                let stakers: Vec<(Addr, Uint128)> = STAKERS
+                    .range(
+                        deps.storage,
+                        start_after.as_ref().map(Bound::exclusive),
+                        None,
+                        Order::Ascending,
+                    )
+                    .take(limit)
+                    .collect::<StdResult<_>>()?;
+                to_json_binary(&stakers)
+            }
+        }
+    }
+}
+
+/*
+ * EXPLOIT SCENARIO:
+ *
+ * 1. Contract works normally with a handful of stakers
+ * 2. Attacker scripts thousands of Register {} calls from fresh addresses,
+ *    each with zero funds
+ * 3. Every entry costs the attacker only transaction fees
+ * 4. Distribute {} now has to read and pay thousands of entries
+ * 5. The message exceeds the block gas limit and always fails
+ * 6. Rewards held by the contract can never be distributed
+ */
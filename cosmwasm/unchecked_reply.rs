@@ -0,0 +1,344 @@
+/*
+ * VULNERABLE COSMWASM CONTRACT - DO NOT USE IN PRODUCTION
+ *
+ * Unchecked Reply Handler
+ *
+ * This contract's reply entrypoint trusts the data returned in msg.result
+ * without matching the reply id to pending state and without validating
+ * which contract produced it, allowing crafted submessage flows to
+ * corrupt balances.
+ */
+
+use cosmwasm_schema::cw_serde;
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    from_json, to_json_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response,
+    StdError, StdResult, SubMsg, Uint128, WasmMsg,
+};
+use cw_storage_plus::{Item, Map};
+use cw_utils::parse_execute_response_data;
+
+pub const SWAP_REPLY_ID: u64 = 1;
+pub const FORWARD_REPLY_ID: u64 = 2;
+
+#[cw_serde]
+pub struct Config {
+    pub dex: Addr,
+}
+
+#[cw_serde]
+pub struct PendingSwap {
+    pub user: Addr,
+    pub ask_denom: String,
+}
+
+/// Data the trusted DEX sets on its execute response
+#[cw_serde]
+pub struct SwapResult {
+    pub recipient: String,
+    pub denom: String,
+    pub amount_out: Uint128,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+pub const NEXT_SWAP_ID: Item<u64> = Item::new("next_swap_id");
+pub const PENDING_SWAPS: Map<u64, PendingSwap> = Map::new("pending_swaps");
+pub const BALANCES: Map<(&Addr, &str), Uint128> = Map::new("balances");
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub dex: String,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Swap attached funds on the trusted DEX, crediting the output in reply
+    Swap { ask_denom: String },
+    /// Convenience router: forward a call to any contract
+    Forward { contract: String, msg: Binary },
+}
+
+#[cw_serde]
+pub enum DexExecuteMsg {
+    Swap {
+        ask_denom: String,
+        recipient: String,
+    },
+}
+
+#[cw_serde]
+pub enum QueryMsg {
+    Balance { address: String, denom: String },
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> StdResult<Response> {
+    let dex = deps.api.addr_validate(&msg.dex)?;
+    CONFIG.save(deps.storage, &Config { dex })?;
+    NEXT_SWAP_ID.save(deps.storage, &0)?;
+    Ok(Response::new().add_attribute("action", "instantiate"))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> StdResult<Response> {
+    match msg {
+        ExecuteMsg::Swap { ask_denom } => swap(deps, info, ask_denom),
+        ExecuteMsg::Forward { contract, msg } => forward(deps, info, contract, msg),
+    }
+}
+
+pub fn swap(deps: DepsMut, info: MessageInfo, ask_denom: String) -> StdResult<Response> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let swap_id = NEXT_SWAP_ID.load(deps.storage)?;
+    NEXT_SWAP_ID.save(deps.storage, &(swap_id + 1))?;
+
+    // Pending state is recorded here...
+    PENDING_SWAPS.save(
+        deps.storage,
+        swap_id,
+        &PendingSwap {
+            user: info.sender.clone(),
+            ask_denom: ask_denom.clone(),
+        },
+    )?;
+
+    let msg = WasmMsg::Execute {
+        contract_addr: config.dex.to_string(),
+        msg: to_json_binary(&DexExecuteMsg::Swap {
+            ask_denom,
+            recipient: info.sender.to_string(),
+        })?,
+        funds: info.funds,
+    };
+
+    Ok(Response::new()
+        .add_submessage(SubMsg::reply_on_success(msg, SWAP_REPLY_ID))
+        .add_attribute("action", "swap")
+        .add_attribute("swap_id", swap_id.to_string()))
+}
+
+pub fn forward(
+    deps: DepsMut,
+    info: MessageInfo,
+    contract: String,
+    msg: Binary,
+) -> StdResult<Response> {
+    let contract = deps.api.addr_validate(&contract)?;
+
+    let msg = WasmMsg::Execute {
+        contract_addr: contract.to_string(),
+        msg,
+        funds: info.funds,
+    };
+
+    // The forwarded call also replies into the same handler
+    Ok(Response::new()
+        .add_submessage(SubMsg::reply_on_success(msg, FORWARD_REPLY_ID))
+        .add_attribute("action", "forward"))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> StdResult<Response> {
+    // VULNERABILITY 1: msg.id is never inspected
+    // Replies from Forward (calling any contract) are processed exactly
+    // like replies from the trusted DEX swap
+
+    // VULNERABILITY 2: The emitting contract is never validated
+    // Whatever contract answered the submessage controls this data
+    let response = msg.result.into_result().map_err(StdError::generic_err)?;
+    let data = response
+        .msg_responses
+        .first()
+        .and_then(|resp| parse_execute_response_data(&resp.value).ok()?.data)
+        .ok_or_else(|| StdError::generic_err("Missing swap result"))?;
+    let result: SwapResult = from_json(data)?;
+
+    // VULNERABILITY 3: Recipient, denom and amount come from the reply payload
+    // instead of the PENDING_SWAPS entry recorded before dispatch
+    let recipient = deps.api.addr_validate(&result.recipient)?;
+    BALANCES.update(
+        deps.storage,
+        (&recipient, result.denom.as_str()),
+        |balance| -> StdResult<_> { Ok(balance.unwrap_or_default() + result.amount_out) },
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "credit_swap")
+        .add_attribute("recipient", recipient)
+        .add_attribute("amount", result.amount_out))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Balance { address, denom } => {
+            let address = deps.api.addr_validate(&address)?;
+            let balance = BALANCES
+                .may_load(deps.storage, (&address, denom.as_str()))?
+                .unwrap_or_default();
+            to_json_binary(&balance)
+        }
+    }
+}
+
+/*
+ * SECURE VERSION:
+ *
+ * Every swap gets its own reply id, which keys the pending state recorded
+ * before dispatch; Forward uses a reserved id that never touches balances,
+ * and the credited user and denom come from storage, not the reply.
+ */
+pub mod secure {
+    use super::{
+        DexExecuteMsg, ExecuteMsg, PendingSwap, SwapResult, BALANCES, CONFIG, NEXT_SWAP_ID,
+        PENDING_SWAPS,
+    };
+    use cosmwasm_std::{
+        from_json, to_json_binary, Binary, DepsMut, Env, MessageInfo, Reply, Response, StdError,
+        StdResult, SubMsg, WasmMsg,
+    };
+    use cw_utils::parse_execute_response_data;
+
+    pub use super::{instantiate, query};
+
+    // Forward replies use a reserved id outside the swap id range
+    pub const FORWARD_REPLY_ID: u64 = u64::MAX;
+
+    pub fn execute(
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        msg: ExecuteMsg,
+    ) -> StdResult<Response> {
+        match msg {
+            ExecuteMsg::Swap { ask_denom } => swap(deps, info, ask_denom),
+            ExecuteMsg::Forward { contract, msg } => forward(deps, info, contract, msg),
+        }
+    }
+
+    pub fn swap(deps: DepsMut, info: MessageInfo, ask_denom: String) -> StdResult<Response> {
+        let config = CONFIG.load(deps.storage)?;
+
+        let swap_id = NEXT_SWAP_ID.load(deps.storage)?;
+        if swap_id == FORWARD_REPLY_ID {
+            return Err(StdError::generic_err("Swap ids exhausted"));
+        }
+        NEXT_SWAP_ID.save(deps.storage, &(swap_id + 1))?;
+        PENDING_SWAPS.save(
+            deps.storage,
+            swap_id,
+            &PendingSwap {
+                user: info.sender.clone(),
+                ask_denom: ask_denom.clone(),
+            },
+        )?;
+
+        let msg = WasmMsg::Execute {
+            contract_addr: config.dex.to_string(),
+            msg: to_json_binary(&DexExecuteMsg::Swap {
+                ask_denom,
+                recipient: info.sender.to_string(),
+            })?,
+            funds: info.funds,
+        };
+
+        // SAFE: The reply id is the key of this swap's pending state
+        Ok(Response::new()
+            .add_submessage(SubMsg::reply_on_success(msg, swap_id))
+            .add_attribute("action", "swap")
+            .add_attribute("swap_id", swap_id.to_string()))
+    }
+
+    pub fn forward(
+        deps: DepsMut,
+        info: MessageInfo,
+        contract: String,
+        msg: Binary,
+    ) -> StdResult<Response> {
+        let contract = deps.api.addr_validate(&contract)?;
+
+        let msg = WasmMsg::Execute {
+            contract_addr: contract.to_string(),
+            msg,
+            funds: info.funds,
+        };
+
+        Ok(Response::new()
+            .add_submessage(SubMsg::reply_on_success(msg, FORWARD_REPLY_ID))
+            .add_attribute("action", "forward"))
+    }
+
+    pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> StdResult<Response> {
+        // CHECK: Forward replies never touch balances
+        if msg.id == FORWARD_REPLY_ID {
+            return Ok(Response::new().add_attribute("action", "forward_done"));
+        }
+
+        // CHECK: Reply id must match a pending swap, consumed exactly once
+        let pending = PENDING_SWAPS
+            .may_load(deps.storage, msg.id)?
+            .ok_or_else(|| StdError::generic_err("Unknown reply id"))?;
+        PENDING_SWAPS.remove(deps.storage, msg.id);
+
+        // CHECK: The response must come from the configured DEX
+        let config = CONFIG.load(deps.storage)?;
+        let response = msg.result.into_result().map_err(StdError::generic_err)?;
+        #[allow(deprecated)]
+        let emitted_by_dex = response.events.iter().any(|event| {
+            event.ty == "execute"
+                && event.attributes.iter().any(|attr| {
+                    attr.key == "_contract_address" && attr.value == config.dex.as_str()
+                })
+        });
+        if !emitted_by_dex {
+            return Err(StdError::generic_err("Reply not emitted by DEX"));
+        }
+
+        let data = response
+            .msg_responses
+            .first()
+            .and_then(|resp| parse_execute_response_data(&resp.value).ok()?.data)
+            .ok_or_else(|| StdError::generic_err("Missing swap result"))?;
+        let result: SwapResult = from_json(data)?;
+
+        // SAFE: Credit the user and denom recorded before dispatch
+        BALANCES.update(
+            deps.storage,
+            (&pending.user, pending.ask_denom.as_str()),
+            |balance| -> StdResult<_> {
+                Ok(balance.unwrap_or_default().checked_add(result.amount_out)?)
+            },
+        )?;
+
+        Ok(Response::new()
+            .add_attribute("action", "credit_swap")
+            .add_attribute("amount", result.amount_out))
+    }
+}
+
+/*
+ * EXPLOIT SCENARIO:
+ *
+ * 1. Attacker deploys a contract whose execute handler returns
+ *    set_data(SwapResult { recipient: attacker, denom: "uatom", amount_out: 10^12 })
+ * 2. Attacker calls Forward { contract: attacker_contract, msg: ... }
+ * 3. The submessage succeeds and reply() is invoked with FORWARD_REPLY_ID
+ * 4. reply() ignores the id, never checks the emitting contract,
+ *    and credits the attacker with the fabricated amount_out
+ * 5. Attacker withdraws real assets against the fake balance
+ *
+ * Variant: a DEX-controlled or compromised pool returns a recipient other
+ * than the swapping user, redirecting every pending swap's output.
+ */
@@ -0,0 +1,224 @@
+/*
+ * VULNERABLE COSMWASM CONTRACT - DO NOT USE IN PRODUCTION
+ *
+ * Migrate Without Admin Check
+ *
+ * This contract's migrate entrypoint overwrites the whole config and takes
+ * the new owner straight from MigrateMsg, without verifying who triggered
+ * the migration or which version is being migrated from.
+ */
+
+use cosmwasm_schema::cw_serde;
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_json_binary, Addr, BankMsg, Binary, Coin, Deps, DepsMut, Env, MessageInfo, Response,
+    StdError, StdResult,
+};
+use cw_storage_plus::Item;
+
+#[cw_serde]
+pub struct Config {
+    pub owner: Addr,
+    pub treasury: Addr,
+    pub fee_bps: u16,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub treasury: String,
+    pub fee_bps: u16,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    SweepFees { denom: String },
+}
+
+#[cw_serde]
+pub struct MigrateMsg {
+    pub new_owner: String,
+    pub treasury: String,
+    pub fee_bps: u16,
+}
+
+#[cw_serde]
+pub enum QueryMsg {
+    Config {},
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> StdResult<Response> {
+    let treasury = deps.api.addr_validate(&msg.treasury)?;
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            owner: info.sender,
+            treasury,
+            fee_bps: msg.fee_bps,
+        },
+    )?;
+    Ok(Response::new().add_attribute("action", "instantiate"))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+    match msg {
+        ExecuteMsg::SweepFees { denom } => sweep_fees(deps, env, info, denom),
+    }
+}
+
+pub fn sweep_fees(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    denom: String,
+) -> StdResult<Response> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(StdError::generic_err("Unauthorized"));
+    }
+
+    let balance = deps.querier.query_balance(env.contract.address, denom)?;
+
+    // Funds go to whatever treasury is currently configured
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: config.treasury.to_string(),
+            amount: vec![Coin::new(balance.amount, balance.denom)],
+        })
+        .add_attribute("action", "sweep_fees"))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> StdResult<Response> {
+    // VULNERABILITY 1: No check of who triggered the migration
+    // The contract relies entirely on whoever holds (or relays for) the
+    // chain-level admin, and never asserts its own stored owner
+
+    // VULNERABILITY 2: No contract name/version check (cw2)
+    // Any code id can be migrated in, including downgrades
+
+    // VULNERABILITY 3: Config is reset and ownership handed to MigrateMsg
+    let new_owner = deps.api.addr_validate(&msg.new_owner)?;
+    let treasury = deps.api.addr_validate(&msg.treasury)?;
+
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            owner: new_owner,
+            treasury,
+            fee_bps: msg.fee_bps,
+        },
+    )?;
+
+    Ok(Response::new().add_attribute("action", "migrate"))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_json_binary(&CONFIG.load(deps.storage)?),
+    }
+}
+
+/*
+ * SECURE VERSION:
+ *
+ * Rely on the chain-level admin (set with `wasmd tx wasm instantiate --admin`
+ * to a multisig/DAO, never to a permissionless relayer) AND assert the
+ * contract's own owner inside migrate. Requires cosmwasm-std's
+ * `cosmwasm_2_2` feature for MigrateInfo, plus the cw2 and semver crates.
+ */
+pub mod secure {
+    use super::{InstantiateMsg, CONFIG};
+    use cosmwasm_schema::cw_serde;
+    use cosmwasm_std::{DepsMut, Env, MessageInfo, MigrateInfo, Response, StdError, StdResult};
+
+    pub use super::{execute, query};
+
+    pub const CONTRACT_NAME: &str = "crates.io:fee-vault";
+    pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+    // SAFE: Migration can only tune parameters, never reassign ownership
+    #[cw_serde]
+    pub struct MigrateMsg {
+        pub fee_bps: Option<u16>,
+    }
+
+    pub fn instantiate(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: InstantiateMsg,
+    ) -> StdResult<Response> {
+        cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+        super::instantiate(deps, env, info, msg)
+    }
+
+    pub fn migrate(
+        deps: DepsMut,
+        _env: Env,
+        msg: MigrateMsg,
+        info: MigrateInfo,
+    ) -> StdResult<Response> {
+        let mut config = CONFIG.load(deps.storage)?;
+
+        // CHECK: The migration must be triggered by the stored owner
+        if info.sender != config.owner {
+            return Err(StdError::generic_err("Unauthorized"));
+        }
+
+        // CHECK: Same contract, strictly newer version
+        let stored = cw2::get_contract_version(deps.storage)?;
+        if stored.contract != CONTRACT_NAME {
+            return Err(StdError::generic_err(
+                "Cannot migrate from a different contract",
+            ));
+        }
+        let stored_version: semver::Version = stored
+            .version
+            .parse()
+            .map_err(|err: semver::Error| StdError::generic_err(err.to_string()))?;
+        let new_version: semver::Version = CONTRACT_VERSION
+            .parse()
+            .map_err(|err: semver::Error| StdError::generic_err(err.to_string()))?;
+        if stored_version >= new_version {
+            return Err(StdError::generic_err(
+                "Cannot migrate to an older or equal version",
+            ));
+        }
+        cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+        // SAFE: Existing config is preserved; only explicitly migrated fields
+        // change. Ownership changes go through a separate two-step flow.
+        if let Some(fee_bps) = msg.fee_bps {
+            config.fee_bps = fee_bps;
+        }
+        CONFIG.save(deps.storage, &config)?;
+
+        Ok(Response::new().add_attribute("action", "migrate"))
+    }
+}
+
+/*
+ * EXPLOIT SCENARIO:
+ *
+ * 1. The vault is instantiated with its chain-level admin set to a keeper
+ *    contract that lets anyone trigger "migrate every vault to the latest
+ *    code id" (or to a widely shared deployer key)
+ * 2. Attacker triggers a migration with
+ *    MigrateMsg { new_owner: attacker, treasury: attacker, fee_bps: 10000 }
+ * 3. migrate() never checks the sender against the stored owner
+ *    and overwrites the whole config
+ * 4. Attacker is now owner and treasury, and calls SweepFees
+ * 5. All accumulated fees are sent to the attacker
+ */
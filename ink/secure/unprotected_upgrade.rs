@@ -0,0 +1,114 @@
+/*
+ * SECURE INK! CONTRACT
+ *
+ * Fixed counterpart of ../unprotected_upgrade.rs
+ *
+ * Upgrades take two owner-signed steps: stage_upgrade publishes the code
+ * hash, and deploy_upgrade switches to exactly that code once a review
+ * window has passed. There is no message that sets the code directly.
+ */
+
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+#[ink::contract]
+pub mod unprotected_upgrade {
+    /// Two days, in milliseconds
+    const UPGRADE_DELAY_MS: Timestamp = 2 * 24 * 60 * 60 * 1_000;
+
+    #[derive(Debug, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum Error {
+        NotOwner,
+        TransferFailed,
+        NothingStaged,
+        CodeHashMismatch,
+        UpgradeDelayNotElapsed,
+        UpgradeFailed,
+    }
+
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct StagedUpgrade {
+        pub code_hash: Hash,
+        pub staged_at: Timestamp,
+    }
+
+    #[ink(storage)]
+    pub struct Treasury {
+        owner: AccountId,
+        staged: Option<StagedUpgrade>,
+    }
+
+    impl Treasury {
+        #[ink(constructor)]
+        pub fn new(owner: AccountId) -> Self {
+            Self {
+                owner,
+                staged: None,
+            }
+        }
+
+        #[ink(message, payable)]
+        pub fn fund(&mut self) {}
+
+        #[ink(message)]
+        pub fn withdraw(&mut self, amount: Balance) -> Result<()> {
+            self.ensure_owner()?;
+            self.env()
+                .transfer(self.owner, amount)
+                .map_err(|_| Error::TransferFailed)
+        }
+
+        /// Step 1: the owner publishes the hash of the next code; everyone
+        /// can inspect it before it can take effect
+        #[ink(message)]
+        pub fn stage_upgrade(&mut self, code_hash: Hash) -> Result<()> {
+            // CHECK: Owner only
+            self.ensure_owner()?;
+            self.staged = Some(StagedUpgrade {
+                code_hash,
+                staged_at: self.env().block_timestamp(),
+            });
+            Ok(())
+        }
+
+        /// Step 2: after the delay, the owner switches to exactly the
+        /// staged code
+        #[ink(message)]
+        pub fn deploy_upgrade(&mut self, code_hash: Hash) -> Result<()> {
+            self.ensure_owner()?;
+            let staged = self.staged.take().ok_or(Error::NothingStaged)?;
+            if staged.code_hash != code_hash {
+                return Err(Error::CodeHashMismatch);
+            }
+            if self.env().block_timestamp() < staged.staged_at.saturating_add(UPGRADE_DELAY_MS) {
+                return Err(Error::UpgradeDelayNotElapsed);
+            }
+            // SAFE: The new code keeps this storage; the staged code must be
+            // written for the same layout, which reviewers check in step 1
+            self.env()
+                .set_code_hash(&code_hash)
+                .map_err(|_| Error::UpgradeFailed)
+        }
+
+        #[ink(message)]
+        pub fn owner(&self) -> AccountId {
+            self.owner
+        }
+
+        #[ink(message)]
+        pub fn staged_upgrade(&self) -> Option<StagedUpgrade> {
+            self.staged.clone()
+        }
+
+        fn ensure_owner(&self) -> Result<()> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,124 @@
+/*
+ * VULNERABLE INK! CONTRACT - DO NOT USE IN PRODUCTION
+ *
+ * Unbounded Storage Growth (Weight DoS)
+ *
+ * Every ink! message runs within the weight limit of the call, itself
+ * capped by the block. This airdrop lets anyone append to a StorageVec,
+ * and its admin-only distribute() pays the whole list in one message - so
+ * the list can be grown until distribute() no longer fits in a block,
+ * locking the airdrop's funds.
+ */
+
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+#[ink::contract]
+pub mod storage_exhaustion {
+    use ink::prelude::vec::Vec;
+    use ink::storage::StorageVec;
+
+    #[derive(Debug, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum Error {
+        NotAdmin,
+        NoRecipients,
+        TransferFailed,
+    }
+
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    #[ink(storage)]
+    pub struct Airdrop {
+        admin: AccountId,
+        // VULNERABILITY 1: A list with no length limit
+        // Each entry has its own storage cell, so unlike a packed Vec
+        // (which stops fitting ink!'s 16 KiB storage buffer at about 500
+        // AccountIds) nothing caps how long it gets
+        recipients: StorageVec<AccountId>,
+    }
+
+    impl Airdrop {
+        #[ink(constructor)]
+        pub fn new(admin: AccountId) -> Self {
+            Self {
+                admin,
+                recipients: StorageVec::new(),
+            }
+        }
+
+        /// Tokens for the next distribution
+        #[ink(message, payable)]
+        pub fn fund(&mut self) {}
+
+        /// Signs the caller up for the airdrop
+        #[ink(message)]
+        pub fn register(&mut self) {
+            // VULNERABILITY 2: Free, unbounded and repeatable
+            // Nothing limits the list's length or stops one account
+            // registering many times; each entry costs the attacker one
+            // cheap call and costs distribute() a transfer
+            self.recipients.push(&self.env().caller());
+        }
+
+        /// Admin-only: shares the contract's balance among all recipients
+        #[ink(message)]
+        pub fn distribute(&mut self) -> Result<()> {
+            if self.env().caller() != self.admin {
+                return Err(Error::NotAdmin);
+            }
+            let share = self
+                .env()
+                .balance()
+                .saturating_sub(self.env().minimum_balance())
+                .checked_div(Balance::from(self.recipients.len()))
+                .ok_or(Error::NoRecipients)?;
+
+            // VULNERABILITY 3: Iterates the whole list in one message
+            // Weight grows linearly with the list; past the block limit the
+            // call always runs out of gas and reverts, so the funds can
+            // never be distributed
+            for index in 0..self.recipients.len() {
+                if let Some(recipient) = self.recipients.get(index) {
+                    self.env()
+                        .transfer(recipient, share)
+                        .map_err(|_| Error::TransferFailed)?;
+                }
+            }
+            self.recipients.clear();
+            Ok(())
+        }
+
+        // VULNERABILITY 4: Unbounded read
+        // Returns the whole list; dry-run RPC calls fail the same way
+        #[ink(message)]
+        pub fn recipients(&self) -> Vec<AccountId> {
+            (0..self.recipients.len())
+                .filter_map(|index| self.recipients.get(index))
+                .collect()
+        }
+    }
+}
+
+/*
+ * SECURE VERSION (BOUNDED, PAGINATED):
+ *
+ * Recipients live in a Mapping keyed by index. Registration is once per
+ * account and capped at MAX_RECIPIENTS. distribute pays at most
+ * MAX_PAGE recipients per call, resuming from a cursor stored with the
+ * share fixed when the round started, and registration is closed while a
+ * round is open. The view is paginated the same way.
+ *
+ * Compiled in secure/storage_exhaustion.rs.
+ */
+
+/*
+ * EXPLOIT SCENARIO:
+ *
+ * 1. The airdrop is funded with 100,000 units for its community
+ * 2. Attacker calls register from a script, a few thousand times, from
+ *    one account or many
+ * 3. Paying the recipient list now takes more weight than a block
+ *    allows: distribute() fails every time it is called
+ * 4. The 100,000 units can never leave the contract; honest recipients
+ *    get nothing
+ */
@@ -0,0 +1,119 @@
+//! Minimal fungible token
+//!
+//! The `Token` interface arbitrary_call.rs holds deposits in: `transfer`,
+//! `approve` and an allowance-based `transfer_from`. It is an ink! trait,
+//! so its selectors are those of `Token::transfer` and so on, and callers
+//! can use it through `contract_ref!(Token)`.
+
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+use ink::primitives::AccountId;
+
+pub type Balance = u128;
+
+#[derive(Debug, PartialEq, Eq)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+pub enum TokenError {
+    InsufficientBalance,
+    InsufficientAllowance,
+}
+
+#[ink::trait_definition]
+pub trait Token {
+    #[ink(message)]
+    fn balance_of(&self, owner: AccountId) -> Balance;
+
+    #[ink(message)]
+    fn transfer(&mut self, to: AccountId, value: Balance) -> Result<(), TokenError>;
+
+    #[ink(message)]
+    fn approve(&mut self, spender: AccountId, value: Balance);
+
+    #[ink(message)]
+    fn transfer_from(
+        &mut self,
+        from: AccountId,
+        to: AccountId,
+        value: Balance,
+    ) -> Result<(), TokenError>;
+}
+
+#[ink::contract]
+pub mod mock_token {
+    use super::{Token, TokenError};
+    use ink::storage::Mapping;
+
+    #[ink(storage)]
+    pub struct MockToken {
+        balances: Mapping<AccountId, Balance>,
+        /// (owner, spender) -> remaining allowance
+        allowances: Mapping<(AccountId, AccountId), Balance>,
+    }
+
+    impl MockToken {
+        /// Mints `supply` to each of `holders`
+        #[ink(constructor)]
+        pub fn new(holders: ink::prelude::vec::Vec<AccountId>, supply: Balance) -> Self {
+            let mut balances = Mapping::default();
+            for holder in holders {
+                balances.insert(holder, &supply);
+            }
+            Self {
+                balances,
+                allowances: Mapping::default(),
+            }
+        }
+
+        fn move_tokens(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+        ) -> Result<(), TokenError> {
+            let from_balance = self
+                .balance_of(from)
+                .checked_sub(value)
+                .ok_or(TokenError::InsufficientBalance)?;
+            self.balances.insert(from, &from_balance);
+            let to_balance = self.balance_of(to).saturating_add(value);
+            self.balances.insert(to, &to_balance);
+            Ok(())
+        }
+    }
+
+    impl Token for MockToken {
+        #[ink(message)]
+        fn balance_of(&self, owner: AccountId) -> Balance {
+            self.balances.get(owner).unwrap_or(0)
+        }
+
+        #[ink(message)]
+        fn transfer(&mut self, to: AccountId, value: Balance) -> Result<(), TokenError> {
+            self.move_tokens(self.env().caller(), to, value)
+        }
+
+        #[ink(message)]
+        fn approve(&mut self, spender: AccountId, value: Balance) {
+            self.allowances
+                .insert((self.env().caller(), spender), &value);
+        }
+
+        #[ink(message)]
+        fn transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+        ) -> Result<(), TokenError> {
+            let key = (from, self.env().caller());
+            let allowance = self
+                .allowances
+                .get(key)
+                .unwrap_or(0)
+                .checked_sub(value)
+                .ok_or(TokenError::InsufficientAllowance)?;
+            self.allowances.insert(key, &allowance);
+            self.move_tokens(from, to, value)
+        }
+    }
+}
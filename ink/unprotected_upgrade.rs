@@ -0,0 +1,95 @@
+/*
+ * VULNERABLE INK! CONTRACT - DO NOT USE IN PRODUCTION
+ *
+ * Unprotected set_code_hash Upgrade
+ *
+ * pallet-contracts lets a contract swap its own code for any code already
+ * uploaded to the chain with set_code_hash; the address, balance and
+ * storage stay the same. This treasury exposes that as a public set_code
+ * message without checking the caller, so anyone can upload their own
+ * code and make it the treasury.
+ */
+
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+#[ink::contract]
+pub mod unprotected_upgrade {
+    #[derive(Debug, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum Error {
+        NotOwner,
+        TransferFailed,
+        UpgradeFailed,
+    }
+
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    #[ink(storage)]
+    pub struct Treasury {
+        owner: AccountId,
+    }
+
+    impl Treasury {
+        #[ink(constructor)]
+        pub fn new(owner: AccountId) -> Self {
+            Self { owner }
+        }
+
+        #[ink(message, payable)]
+        pub fn fund(&mut self) {}
+
+        /// Owner-only payout - correctly checked, and irrelevant once the
+        /// code itself can be replaced
+        #[ink(message)]
+        pub fn withdraw(&mut self, amount: Balance) -> Result<()> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+            self.env()
+                .transfer(self.owner, amount)
+                .map_err(|_| Error::TransferFailed)
+        }
+
+        /// Replaces this contract's code with previously uploaded code
+        #[ink(message)]
+        pub fn set_code(&mut self, code_hash: Hash) -> Result<()> {
+            // VULNERABILITY 1: No owner check
+            // Any account can upload a contract and make it this one
+
+            // VULNERABILITY 2: Takes effect immediately
+            // No review window: users cannot inspect the new code and exit
+            // before it runs with the treasury's balance
+            self.env()
+                .set_code_hash(&code_hash)
+                .map_err(|_| Error::UpgradeFailed)
+        }
+
+        #[ink(message)]
+        pub fn owner(&self) -> AccountId {
+            self.owner
+        }
+    }
+}
+
+/*
+ * SECURE VERSION (OWNER-GATED, TWO-STEP UPGRADE):
+ *
+ * stage_upgrade records a code hash and the time it was staged;
+ * deploy_upgrade switches to exactly that hash once a review delay has
+ * passed. Both are owner-only, and set_code is gone. Stronger still: make
+ * a multisig or governance contract the owner.
+ *
+ * Compiled in secure/unprotected_upgrade.rs.
+ */
+
+/*
+ * EXPLOIT SCENARIO:
+ *
+ * 1. The treasury holds 10,000 units; only the owner can withdraw
+ * 2. Attacker uploads attackers/unprotected_upgrade.rs, whose drain
+ *    message sends the contract's whole balance to its caller, and gets
+ *    its code hash back (no instantiation needed)
+ * 3. Attacker calls set_code with that hash
+ * 4. The treasury's address now runs the attacker's code; one drain call
+ *    later the 10,000 units are gone, and the code cannot be changed back
+ */
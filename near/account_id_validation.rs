@@ -0,0 +1,146 @@
+/*
+ * VULNERABLE NEAR CONTRACT - DO NOT USE IN PRODUCTION
+ *
+ * Unvalidated Account ID Input
+ *
+ * Account ids on NEAR are strictly formatted: 2-64 characters, lowercase
+ * alphanumerics separated by '.', '-' or '_'. near-sdk's AccountId type
+ * enforces this when an argument is deserialized. This tip jar takes
+ * recipients as raw Strings, stores them as-is and only parses them when a
+ * payout is sent - so ids no account can ever have are accepted, credited
+ * and queued, where they lock funds and brick payouts for everyone.
+ */
+
+use near_sdk::store::{LookupMap, Vector};
+use near_sdk::{env, near, require, AccountId, NearToken, PanicOnDefault, Promise};
+
+/// A transfer scheduled by send_later
+#[near(serializers = [borsh])]
+pub struct Payout {
+    pub recipient: String,
+    pub amount: NearToken,
+}
+
+#[near(contract_state)]
+#[derive(PanicOnDefault)]
+pub struct TipJar {
+    /// Claimable tips, keyed by the recipient string exactly as given
+    balances: LookupMap<String, NearToken>,
+    queue: Vector<Payout>,
+    /// Index of the next payout to send
+    next_payout: u32,
+}
+
+#[near]
+impl TipJar {
+    #[init]
+    pub fn new() -> Self {
+        Self {
+            balances: LookupMap::new(b"b"),
+            queue: Vector::new(b"q"),
+            next_payout: 0,
+        }
+    }
+
+    /// Credits the attached deposit to `recipient`, who claims it later
+    #[payable]
+    pub fn tip(&mut self, recipient: String) {
+        // VULNERABILITY 1: Raw String used as the balance key
+        // "Alice.near", "alice.near " or "alice" can never be a
+        // predecessor_account_id(), so tips to them are unspendable forever
+        let balance = self.balance(&recipient);
+        self.balances
+            .insert(recipient, balance.saturating_add(env::attached_deposit()));
+    }
+
+    pub fn claim(&mut self) -> Promise {
+        let account_id = env::predecessor_account_id();
+        let amount = self
+            .balances
+            .remove(account_id.as_str())
+            .unwrap_or_else(|| env::panic_str("Nothing to claim"));
+        Promise::new(account_id).transfer(amount)
+    }
+
+    /// Queues the attached deposit to be pushed to `recipient`
+    #[payable]
+    pub fn send_later(&mut self, recipient: String) {
+        require!(
+            env::attached_deposit() > NearToken::from_yoctonear(0),
+            "Attach the amount to send"
+        );
+        // VULNERABILITY 2: Queued without validation
+        // Nothing stops an id that will fail to parse when it is paid out
+        self.queue.push(Payout {
+            recipient,
+            amount: env::attached_deposit(),
+        });
+    }
+
+    /// Sends up to `limit` queued payouts, in order
+    pub fn process_queue(&mut self, limit: u32) {
+        let end = self.queue.len().min(self.next_payout.saturating_add(limit));
+        for index in self.next_payout..end {
+            let payout = &self.queue[index];
+
+            // VULNERABILITY 3: Parsed only at the point of use
+            // One invalid entry panics the whole batch, which reverts every
+            // payout before it and leaves next_payout where it was - no
+            // later payout can ever be sent
+            let recipient: AccountId = payout.recipient.parse().unwrap();
+
+            // VULNERABILITY 4: No callback
+            // A well-formed id with no account behind it fails the transfer;
+            // the refund lands on the contract with no record of whose it is
+            Promise::new(recipient).transfer(payout.amount).detach();
+        }
+        self.next_payout = end;
+    }
+
+    pub fn get_balance(&self, recipient: String) -> NearToken {
+        self.balance(&recipient)
+    }
+
+    pub fn get_next_payout(&self) -> u32 {
+        self.next_payout
+    }
+
+    fn balance(&self, recipient: &str) -> NearToken {
+        self.balances
+            .get(recipient)
+            .copied()
+            .unwrap_or(NearToken::from_yoctonear(0))
+    }
+}
+
+/*
+ * SECURE VERSION (TYPED AccountId AT THE BOUNDARY):
+ *
+ * tip and send_later take recipient: AccountId, so a malformed id fails
+ * argument deserialization with a validation error and nothing is
+ * credited or queued. Balances are keyed by AccountId. Each payout carries
+ * its sender and a #[private] callback that returns the amount to the
+ * sender's claimable balance when the transfer fails, e.g. because a
+ * well-formed account does not exist.
+ *
+ * Compiled in secure/account_id_validation.rs.
+ */
+
+/*
+ * EXPLOIT SCENARIOS:
+ *
+ * Queue Griefing:
+ * 1. Alice calls send_later { recipient: "bob.near" } with 5 NEAR
+ * 2. Attacker calls send_later { recipient: "Not An Account!" } with
+ *    1 yoctoNEAR
+ * 3. process_queue { limit: 10 } schedules Bob's transfer, then panics
+ *    parsing the attacker's recipient; the whole call reverts
+ * 4. Every retry stops at the same entry: Alice's 5 NEAR and every
+ *    payout queued after it are locked for good, for the price of one
+ *    yoctoNEAR
+ *
+ * Unspendable Tips:
+ * 1. A frontend passes the user-typed "Bob.near" to tip with 10 NEAR
+ * 2. The tip is stored under a key no predecessor can ever match;
+ *    bob.near's claim finds nothing and the 10 NEAR are stuck
+ */
@@ -0,0 +1,185 @@
+/*
+ * VULNERABLE NEAR CONTRACT - DO NOT USE IN PRODUCTION
+ *
+ * u128 Overflow and Precision Loss
+ *
+ * Balances on NEAR are u128 yoctoNEAR (10^24 per NEAR) and timestamps are
+ * u64 nanoseconds, so products of the two leave u128 far sooner than
+ * intuition suggests. Contracts compiled without overflow-checks silently
+ * wrap. This staking vault aggregates batch deposits, lock boosts and
+ * rewards with unchecked arithmetic and divides before it multiplies.
+ */
+
+use near_sdk::json_types::U128;
+use near_sdk::store::LookupMap;
+use near_sdk::{env, near, require, AccountId, NearToken, PanicOnDefault, Promise};
+
+const NS_PER_DAY: u64 = 24 * 60 * 60 * 1_000_000_000;
+const NS_PER_YEAR: u128 = 365 * NS_PER_DAY as u128;
+const BASE_APR_BPS: u128 = 500;
+const BPS: u128 = 10_000;
+
+#[near(serializers = [borsh])]
+#[derive(Clone, Default)]
+pub struct Stake {
+    pub amount: u128,
+    pub unlock_at: u64,
+    /// Reward multiplier in percent; 100 = no boost
+    pub boost: u128,
+    pub last_claim: u64,
+}
+
+#[near(contract_state)]
+#[derive(PanicOnDefault)]
+pub struct StakingVault {
+    stakes: LookupMap<AccountId, Stake>,
+    total_staked: u128,
+    reward_pool: u128,
+}
+
+#[near]
+impl StakingVault {
+    #[init]
+    pub fn new() -> Self {
+        Self {
+            stakes: LookupMap::new(b"s"),
+            total_staked: 0,
+            reward_pool: 0,
+        }
+    }
+
+    #[payable]
+    pub fn fund_rewards(&mut self) {
+        self.reward_pool += env::attached_deposit().as_yoctonear();
+    }
+
+    /// Stakes the attached deposit, locked for `lock_days` in exchange for
+    /// a boost of one percentage point per day
+    #[payable]
+    pub fn stake(&mut self, lock_days: u64) {
+        let account_id = env::predecessor_account_id();
+        let amount = env::attached_deposit().as_yoctonear();
+        let mut stake = self.stake_of(&account_id);
+
+        // VULNERABILITY 1: Unchecked u64 timestamp arithmetic
+        // With a 2026 timestamp the sum passes u64::MAX for lock_days above
+        // ~192,770 and wraps, so a huge lock lands unlock_at in the past
+        stake.unlock_at = env::block_timestamp() + lock_days * NS_PER_DAY;
+
+        // VULNERABILITY 2: Uncapped, unchecked multiplier
+        // The same huge lock_days buys an astronomical boost
+        stake.boost = 100 + lock_days as u128;
+
+        stake.amount += amount;
+        stake.last_claim = env::block_timestamp();
+        self.total_staked += amount;
+        self.stakes.insert(account_id, stake);
+    }
+
+    /// Splits the attached deposit between several unlocked stakes
+    #[payable]
+    pub fn stake_for(&mut self, recipients: Vec<(AccountId, U128)>) {
+        // VULNERABILITY 3: Unchecked u128 aggregation
+        // [u128::MAX, 2] sums to 1, so one attached yoctoNEAR "covers"
+        // arbitrarily large credits
+        let mut total: u128 = 0;
+        for (_, amount) in &recipients {
+            total += amount.0; // Should use checked_add()
+        }
+        require!(
+            total == env::attached_deposit().as_yoctonear(),
+            "Deposit must equal the sum of all amounts"
+        );
+
+        for (account_id, amount) in recipients {
+            let mut stake = self.stake_of(&account_id);
+            if stake.boost == 0 {
+                stake.boost = 100;
+                stake.last_claim = env::block_timestamp();
+            }
+            stake.amount += amount.0; // Should use checked_add()
+            self.stakes.insert(account_id, stake);
+        }
+        self.total_staked += total;
+    }
+
+    pub fn claim_rewards(&mut self) -> Promise {
+        let account_id = env::predecessor_account_id();
+        let mut stake = self.stake_of(&account_id);
+        let elapsed = (env::block_timestamp() - stake.last_claim) as u128;
+
+        // VULNERABILITY 4: Multiplication overflows u128
+        // yoctoNEAR * bps * percent * nanoseconds: 1 NEAR staked for one
+        // hour already exceeds u128::MAX and wraps to a meaningless value
+        let reward =
+            stake.amount * BASE_APR_BPS * stake.boost * elapsed / (BPS * 100 * NS_PER_YEAR);
+
+        // VULNERABILITY 5: Divides before multiplying
+        // amount / total_staked truncates to 0 for everyone but a sole
+        // staker, so honest users' reward cap is always 0
+        let cap = stake.amount / self.total_staked * self.reward_pool;
+
+        let reward = reward.min(cap);
+        stake.last_claim = env::block_timestamp();
+        self.stakes.insert(account_id.clone(), stake);
+        self.reward_pool -= reward;
+        Promise::new(account_id).transfer(NearToken::from_yoctonear(reward))
+    }
+
+    pub fn unstake(&mut self) -> Promise {
+        let account_id = env::predecessor_account_id();
+        let stake = self.stake_of(&account_id);
+        require!(env::block_timestamp() >= stake.unlock_at, "Stake is locked");
+        self.stakes.remove(&account_id);
+
+        // VULNERABILITY 6: Unchecked subtraction
+        // After a wrapped stake_for, total_staked is smaller than the
+        // credited stake and underflows to nearly u128::MAX
+        self.total_staked -= stake.amount;
+        Promise::new(account_id).transfer(NearToken::from_yoctonear(stake.amount))
+    }
+
+    pub fn get_stake(&self, account_id: AccountId) -> (U128, u64, U128) {
+        let stake = self.stake_of(&account_id);
+        (stake.amount.into(), stake.unlock_at, stake.boost.into())
+    }
+
+    fn stake_of(&self, account_id: &AccountId) -> Stake {
+        self.stakes.get(account_id).cloned().unwrap_or_default()
+    }
+}
+
+/*
+ * SECURE VERSION USING CHECKED ARITHMETIC:
+ *
+ * lock_days is capped at MAX_LOCK_DAYS before it reaches any arithmetic,
+ * every sum and difference uses checked_add / checked_sub and panics
+ * instead of wrapping, and yoctoNEAR products are taken in 256 bits with a
+ * single division at the end. Building with overflow-checks = true is a
+ * second line of defence, not a substitute.
+ *
+ * Compiled in secure/balance_overflow.rs.
+ */
+
+/*
+ * EXPLOIT SCENARIOS:
+ *
+ * Aggregation Overflow:
+ * 1. The vault holds 1,000 NEAR of honest stakes (X yoctoNEAR)
+ * 2. Attacker calls stake_for with [(attacker, X), (burner, u128::MAX - X + 2)]
+ *    and attaches 1 yoctoNEAR
+ * 3. The sum wraps to 1, matching the deposit
+ * 4. Attacker's stake is credited X; unstake() pays out the whole vault
+ *
+ * Lock Overflow:
+ * 1. Attacker stakes with lock_days = 192,768, just past
+ *    (u64::MAX - block_timestamp) / NS_PER_DAY
+ * 2. block_timestamp + lock_days * NS_PER_DAY wraps to a moment in 1970:
+ *    the stake is already unlocked
+ * 3. Attacker holds a 192,868% boost on a stake they can withdraw at will
+ *
+ * Precision Loss:
+ * 1. 100 users each stake 10 NEAR; total_staked = 1,000 NEAR
+ * 2. Wrong: cap = 10 NEAR / 1,000 NEAR * pool = 0 (integer division)
+ * 3. Every claim pays 0 rewards even though the pool is funded
+ */
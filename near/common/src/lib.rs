@@ -0,0 +1,112 @@
+//! Guards shared by the secure NEAR examples
+//!
+//! Each one is a single `require!`, but getting the details right - which
+//! account id to compare, what exactly to attach - is where the vulnerable
+//! examples go wrong, so the secure versions all use these.
+
+use near_sdk::{env, require, AccountId, NearToken, PromiseError};
+
+/// Panics unless the immediate caller is `owner`.
+///
+/// Compares `predecessor_account_id()`, never `signer_account_id()`: the
+/// signer is still the owner when the owner calls a malicious contract
+/// that relays the call.
+pub fn assert_owner(owner: &AccountId) {
+    require!(
+        env::predecessor_account_id() == *owner,
+        "Only the owner can call this method"
+    );
+}
+
+/// Panics unless exactly one yoctoNEAR is attached.
+///
+/// Function-call access keys cannot attach deposits, so this proves the
+/// call was signed with a full-access key and confirmed in a wallet.
+pub fn assert_one_yocto() {
+    require!(
+        env::attached_deposit() == NearToken::from_yoctonear(1),
+        "Requires attached deposit of exactly 1 yoctoNEAR"
+    );
+}
+
+/// [`assert_one_yocto`] and [`assert_owner`], for privileged methods
+pub fn assert_owner_one_yocto(owner: &AccountId) {
+    assert_one_yocto();
+    assert_owner(owner);
+}
+
+/// Panics unless the contract is calling itself.
+///
+/// The check `#[private]` generates: a callback scheduled with `.then()`
+/// runs with the contract as predecessor, a direct call never does.
+pub fn assert_self() {
+    require!(
+        env::predecessor_account_id() == env::current_account_id(),
+        "Method is private"
+    );
+}
+
+/// Settles a `#[callback_result]`: runs `rollback` if the awaited promise
+/// failed, otherwise returns its value
+pub fn settle<T>(result: Result<T, PromiseError>, rollback: impl FnOnce()) -> Option<T> {
+    match result {
+        Ok(value) => Some(value),
+        Err(_) => {
+            rollback();
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::testing_env;
+
+    fn call_from(predecessor: AccountId, deposit: NearToken) {
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .predecessor_account_id(predecessor)
+            .attached_deposit(deposit)
+            .build());
+    }
+
+    #[test]
+    fn owner_may_call() {
+        call_from(accounts(1), NearToken::from_yoctonear(1));
+        assert_owner_one_yocto(&accounts(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the owner")]
+    fn stranger_may_not_call() {
+        call_from(accounts(2), NearToken::from_yoctonear(0));
+        assert_owner(&accounts(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "exactly 1 yoctoNEAR")]
+    fn missing_yocto_is_rejected() {
+        call_from(accounts(1), NearToken::from_yoctonear(0));
+        assert_one_yocto();
+    }
+
+    #[test]
+    #[should_panic(expected = "Method is private")]
+    fn direct_callback_is_rejected() {
+        call_from(accounts(2), NearToken::from_yoctonear(0));
+        assert_self();
+    }
+
+    #[test]
+    fn failed_promise_rolls_back() {
+        let mut rolled_back = false;
+        assert_eq!(
+            settle::<()>(Err(PromiseError::Failed), || rolled_back = true),
+            None
+        );
+        assert!(rolled_back);
+        assert_eq!(settle(Ok(7), || unreachable!()), Some(7));
+    }
+}
@@ -0,0 +1,199 @@
+/*
+ * VULNERABLE NEAR CONTRACT - DO NOT USE IN PRODUCTION
+ *
+ * ft_transfer_call Double Spend
+ *
+ * NEP-141 deposits arrive as ft_on_transfer calls from the token contract.
+ * The receiver returns how much it did NOT use, and the token's
+ * ft_resolve_transfer refunds that amount to the sender in a later
+ * receipt. This exchange credits the deposit before checking which
+ * contract called it, and hands a failed swap back as "unused" while the
+ * credit stays on its books - the same tokens are counted twice.
+ */
+
+use near_sdk::json_types::U128;
+use near_sdk::store::LookupMap;
+use near_sdk::{
+    env, ext_contract, near, require, serde_json, AccountId, Gas, NearToken, PanicOnDefault,
+    Promise, PromiseError, PromiseOrValue,
+};
+
+const GAS_FOR_FT_TRANSFER: Gas = Gas::from_tgas(10);
+const GAS_FOR_CALLBACK: Gas = Gas::from_tgas(10);
+
+/// NEP-141 core
+#[ext_contract(ext_ft)]
+pub trait FungibleToken {
+    fn ft_transfer(&mut self, receiver_id: AccountId, amount: U128, memo: Option<String>);
+}
+
+/// ft_transfer_call `msg`: swap the deposit immediately
+#[near(serializers = [json])]
+pub struct SwapAction {
+    pub min_amount_out: U128,
+}
+
+#[near(contract_state)]
+#[derive(PanicOnDefault)]
+pub struct Exchange {
+    tokens: [AccountId; 2],
+    reserves: [u128; 2],
+    /// (account, token) -> internal balance
+    deposits: LookupMap<(AccountId, AccountId), u128>,
+}
+
+#[near]
+impl Exchange {
+    /// Reserves are seeded separately by the owner's ft_transfer
+    #[init]
+    pub fn new(tokens: [AccountId; 2], reserves: [U128; 2]) -> Self {
+        Self {
+            tokens,
+            reserves: reserves.map(|reserve| reserve.0),
+            deposits: LookupMap::new(b"d"),
+        }
+    }
+
+    /// NEP-141 receiver: called by the token contract during ft_transfer_call
+    pub fn ft_on_transfer(
+        &mut self,
+        sender_id: AccountId,
+        amount: U128,
+        msg: String,
+    ) -> PromiseOrValue<U128> {
+        // VULNERABILITY 1: The token contract id is never verified
+        // Any contract can call ft_on_transfer, so an attacker-deployed
+        // "token" deposits whatever amount it claims
+        let token_id = env::predecessor_account_id();
+        self.credit(&sender_id, &token_id, amount.0);
+
+        if msg.is_empty() {
+            return PromiseOrValue::Value(U128(0));
+        }
+        let action: SwapAction =
+            serde_json::from_str(&msg).unwrap_or_else(|_| env::panic_str("Invalid swap action"));
+
+        if self.swap(&sender_id, &token_id, amount.0, action.min_amount_out.0) {
+            PromiseOrValue::Value(U128(0))
+        } else {
+            // VULNERABILITY 2: Refund without reversing the credit
+            // The token's ft_resolve_transfer sends `amount` back to the
+            // sender, but the deposit credited above stays withdrawable
+            PromiseOrValue::Value(amount)
+        }
+    }
+
+    pub fn withdraw(&mut self, token_id: AccountId, amount: U128) -> Promise {
+        let account_id = env::predecessor_account_id();
+        self.debit(&account_id, &token_id, amount.0);
+        ext_ft::ext(token_id.clone())
+            .with_attached_deposit(NearToken::from_yoctonear(1))
+            .with_static_gas(GAS_FOR_FT_TRANSFER)
+            .ft_transfer(account_id.clone(), amount, None)
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_CALLBACK)
+                    .on_withdraw(account_id, token_id, amount),
+            )
+    }
+
+    #[private]
+    pub fn on_withdraw(
+        &mut self,
+        account_id: AccountId,
+        token_id: AccountId,
+        amount: U128,
+        #[callback_result] result: Result<(), PromiseError>,
+    ) {
+        if result.is_err() {
+            self.credit(&account_id, &token_id, amount.0);
+        }
+    }
+
+    pub fn get_deposit(&self, account_id: AccountId, token_id: AccountId) -> U128 {
+        U128(self.deposit_of(&account_id, &token_id))
+    }
+
+    /// Swaps `amount_in` of the caller's deposit; false if slippage is exceeded
+    fn swap(
+        &mut self,
+        account_id: &AccountId,
+        token_in: &AccountId,
+        amount_in: u128,
+        min_amount_out: u128,
+    ) -> bool {
+        // VULNERABILITY 3: Anything that is not tokens[0] is treated as tokens[1]
+        // A fake token swaps into the real reserves
+        let (index_in, index_out) = if *token_in == self.tokens[0] {
+            (0, 1)
+        } else {
+            (1, 0)
+        };
+        let amount_out =
+            self.reserves[index_out] * amount_in / (self.reserves[index_in] + amount_in);
+        if amount_out < min_amount_out {
+            return false;
+        }
+        self.debit(account_id, token_in, amount_in);
+        self.reserves[index_in] += amount_in;
+        self.reserves[index_out] -= amount_out;
+        let token_out = self.tokens[index_out].clone();
+        self.credit(account_id, &token_out, amount_out);
+        true
+    }
+
+    fn deposit_of(&self, account_id: &AccountId, token_id: &AccountId) -> u128 {
+        self.deposits
+            .get(&(account_id.clone(), token_id.clone()))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    fn credit(&mut self, account_id: &AccountId, token_id: &AccountId, amount: u128) {
+        let balance = self.deposit_of(account_id, token_id);
+        self.deposits.insert(
+            (account_id.clone(), token_id.clone()),
+            balance.saturating_add(amount),
+        );
+    }
+
+    fn debit(&mut self, account_id: &AccountId, token_id: &AccountId, amount: u128) {
+        let balance = self.deposit_of(account_id, token_id);
+        require!(balance >= amount, "Insufficient deposit");
+        self.deposits
+            .insert((account_id.clone(), token_id.clone()), balance - amount);
+    }
+}
+
+/*
+ * SECURE VERSION (WHITELIST + RESOLVE-BASED REFUNDS):
+ *
+ * ft_on_transfer rejects any predecessor that is not one of the pool's two
+ * tokens before touching state. A swap deposit is never credited: either
+ * the swap completes and only its output is credited, or the call panics
+ * and the token's ft_resolve_transfer refunds an amount the exchange never
+ * counted.
+ *
+ * Compiled in secure/ft_transfer_call_double_spend.rs.
+ */
+
+/*
+ * EXPLOIT SCENARIOS:
+ *
+ * Refund Double Count:
+ * 1. Attacker calls usdc.ft_transfer_call { receiver_id: exchange,
+ *    amount: 1,000, msg: "{\"min_amount_out\":\"<unreachable>\"}" }
+ * 2. ft_on_transfer credits 1,000 USDC, the swap misses its minimum and
+ *    the method returns 1,000 as unused
+ * 3. usdc.ft_resolve_transfer refunds 1,000 USDC to the attacker
+ * 4. The exchange still shows a 1,000 USDC deposit; attacker withdraws
+ *    it and repeats until the reserves are gone
+ *
+ * Fake Token:
+ * 1. Attacker deploys fake.near whose ft_transfer_call mints nothing and
+ *    simply calls exchange.ft_on_transfer { sender_id: attacker,
+ *    amount: 10^24, msg: "{\"min_amount_out\":\"0\"}" }
+ * 2. The exchange treats fake.near as tokens[1] and swaps it into the
+ *    real tokens[0] reserve
+ * 3. Attacker withdraws the real tokens
+ */
@@ -0,0 +1,120 @@
+/*
+ * VULNERABLE NEAR CONTRACT - DO NOT USE IN PRODUCTION
+ *
+ * Missing One-yoctoNEAR Assertion
+ *
+ * NEAR accounts routinely hand dapps function-call access keys so that
+ * routine actions (here: claiming a daily reward) need no wallet prompt.
+ * Such keys may call ANY non-payable method of the contract they are
+ * scoped to, but can never attach a deposit. This vault's fund-moving
+ * methods do not require an attached yoctoNEAR, so whoever holds a leaked
+ * dapp key can move the user's funds.
+ */
+
+use near_sdk::store::LookupMap;
+use near_sdk::{env, near, AccountId, NearToken, PanicOnDefault, Promise};
+
+const DAILY_REWARD: NearToken = NearToken::from_millinear(1);
+const DAY_NS: u64 = 24 * 60 * 60 * 1_000_000_000;
+
+#[near(contract_state)]
+#[derive(PanicOnDefault)]
+pub struct Vault {
+    balances: LookupMap<AccountId, NearToken>,
+    last_claim: LookupMap<AccountId, u64>,
+}
+
+#[near]
+impl Vault {
+    #[init]
+    pub fn new() -> Self {
+        Self {
+            balances: LookupMap::new(b"b"),
+            last_claim: LookupMap::new(b"c"),
+        }
+    }
+
+    #[payable]
+    pub fn deposit(&mut self) {
+        let account_id = env::predecessor_account_id();
+        let balance = self.balance(&account_id);
+        self.balances
+            .insert(account_id, balance.saturating_add(env::attached_deposit()));
+    }
+
+    /// Meant to be signed by the dapp's function-call key - no prompt needed
+    pub fn claim_daily_reward(&mut self) {
+        let account_id = env::predecessor_account_id();
+        let now = env::block_timestamp();
+        let last = self.last_claim.get(&account_id).copied().unwrap_or(0);
+        if now < last + DAY_NS {
+            env::panic_str("Already claimed today");
+        }
+        self.last_claim.insert(account_id.clone(), now);
+        let balance = self.balance(&account_id);
+        self.balances
+            .insert(account_id, balance.saturating_add(DAILY_REWARD));
+    }
+
+    pub fn transfer(&mut self, receiver_id: AccountId, amount: NearToken) {
+        // VULNERABILITY 1: No assert_one_yocto()
+        // A function-call access key can sign this call, so a leaked dapp
+        // key moves the user's balance without any wallet confirmation
+        let sender_id = env::predecessor_account_id();
+        self.debit(&sender_id, amount);
+        let balance = self.balance(&receiver_id);
+        self.balances
+            .insert(receiver_id, balance.saturating_add(amount));
+    }
+
+    pub fn withdraw_to(&mut self, receiver_id: AccountId, amount: NearToken) -> Promise {
+        // VULNERABILITY 2: Same for withdrawals to an arbitrary account
+        // Nothing here distinguishes a full-access key from a dapp key
+        let account_id = env::predecessor_account_id();
+        self.debit(&account_id, amount);
+        Promise::new(receiver_id).transfer(amount)
+    }
+
+    pub fn balance_of(&self, account_id: AccountId) -> NearToken {
+        self.balance(&account_id)
+    }
+
+    fn balance(&self, account_id: &AccountId) -> NearToken {
+        self.balances
+            .get(account_id)
+            .copied()
+            .unwrap_or(NearToken::from_yoctonear(0))
+    }
+
+    fn debit(&mut self, account_id: &AccountId, amount: NearToken) {
+        let remaining = self
+            .balance(account_id)
+            .checked_sub(amount)
+            .unwrap_or_else(|| env::panic_str("Insufficient balance"));
+        self.balances.insert(account_id.clone(), remaining);
+    }
+}
+
+/*
+ * SECURE VERSION (FULL-ACCESS KEY REQUIRED FOR FUND MOVEMENTS):
+ *
+ * transfer and withdraw_to are #[payable] and require exactly one attached
+ * yoctoNEAR, which a function-call key cannot attach. claim_daily_reward
+ * stays non-payable: it only ever credits the caller. NEP-141 and NEP-145
+ * require the same for ft_transfer, storage_withdraw and friends.
+ *
+ * Compiled in secure/missing_one_yocto.rs.
+ */
+
+/*
+ * EXPLOIT SCENARIO:
+ *
+ * 1. Alice deposits 50 NEAR and signs into a rewards dapp, which adds a
+ *    function-call access key for the vault contract to her account
+ *    (allowance 0.25 NEAR of gas, any method)
+ * 2. The dapp's frontend is compromised and the key is exfiltrated
+ * 3. Attacker signs withdraw_to { receiver_id: attacker, amount: 50 NEAR }
+ *    as alice.near using the stolen function-call key
+ * 4. The call attaches no deposit - which the vault never required -
+ *    so the key is accepted and Alice's balance is paid to the attacker
+ */
@@ -0,0 +1,101 @@
+/*
+ * VULNERABLE NEAR CONTRACT - DO NOT USE IN PRODUCTION
+ *
+ * Missing Predecessor Check
+ *
+ * This vault stores an owner but never compares it with the account that
+ * is actually calling. Withdrawals are open to everyone, and ownership
+ * transfer checks the transaction signer instead of the immediate caller -
+ * the NEAR counterpart of the missing signer check on Solana.
+ */
+
+use near_sdk::{env, near, require, AccountId, NearToken, PanicOnDefault, Promise};
+
+#[near(contract_state)]
+#[derive(PanicOnDefault)]
+pub struct Vault {
+    owner: AccountId,
+    /// Deposited funds; the rest of the account balance covers storage
+    balance: NearToken,
+}
+
+#[near]
+impl Vault {
+    #[init]
+    pub fn new(owner: AccountId) -> Self {
+        Self {
+            owner,
+            balance: NearToken::from_yoctonear(0),
+        }
+    }
+
+    #[payable]
+    pub fn deposit(&mut self) {
+        self.balance = self.balance.saturating_add(env::attached_deposit());
+    }
+
+    pub fn withdraw(&mut self, amount: NearToken, receiver_id: AccountId) -> Promise {
+        // VULNERABILITY 1: No check that the caller is the owner
+        // Should have: require!(env::predecessor_account_id() == self.owner)
+        // Any account can send the vault's funds anywhere
+
+        self.balance = self
+            .balance
+            .checked_sub(amount)
+            .unwrap_or_else(|| env::panic_str("Insufficient balance"));
+
+        // VULNERABILITY 2: Receiver is caller-chosen
+        // Combined with the missing check, funds go straight to the attacker
+        Promise::new(receiver_id).transfer(amount)
+    }
+
+    pub fn transfer_ownership(&mut self, new_owner: AccountId) {
+        // VULNERABILITY 3: signer_account_id() is the account that signed the
+        // ORIGINAL transaction, not the immediate caller. If the owner calls
+        // any malicious contract, that contract can relay this call and the
+        // signer still matches. Authorization must use predecessor_account_id().
+        require!(
+            env::signer_account_id() == self.owner,
+            "Only the owner can transfer ownership"
+        );
+
+        self.owner = new_owner;
+    }
+
+    pub fn get_owner(&self) -> AccountId {
+        self.owner.clone()
+    }
+
+    pub fn get_balance(&self) -> NearToken {
+        self.balance
+    }
+}
+
+/*
+ * SECURE VERSION:
+ *
+ * withdraw and transfer_ownership compare predecessor_account_id() - not
+ * signer_account_id(), and not nothing - against the stored owner, and
+ * withdrawals can only pay the owner.
+ *
+ * Compiled in secure/missing_predecessor_check.rs.
+ */
+
+/*
+ * EXPLOIT SCENARIO:
+ *
+ * Missing check:
+ * 1. Owner deposits 5 NEAR into the vault
+ * 2. Attacker calls withdraw { amount: 5 NEAR, receiver_id: attacker }
+ * 3. The contract never looks at env::predecessor_account_id()
+ * 4. The vault's funds are transferred to the attacker
+ *
+ * Signer confusion:
+ * 1. Attacker deploys a contract with a harmless-looking method
+ *    (e.g. claim_airdrop) and convinces the owner to call it
+ * 2. That method issues a cross-contract call to
+ *    vault.transfer_ownership { new_owner: attacker }
+ * 3. Inside the vault, signer_account_id() is still the owner,
+ *    while predecessor_account_id() is the attacker's contract
+ * 4. The signer check passes and ownership moves to the attacker
+ */
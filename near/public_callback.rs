@@ -0,0 +1,135 @@
+/*
+ * VULNERABLE NEAR CONTRACT - DO NOT USE IN PRODUCTION
+ *
+ * Missing #[private] on a Callback
+ *
+ * This staking proxy unstakes in two steps: unstake() asks the validator
+ * pool to unstake, and the on_unstaked() callback credits the withdrawable
+ * balance. Callbacks are ordinary public methods on NEAR - without
+ * #[private] (predecessor == current account) anyone can call on_unstaked
+ * directly, with whatever account and amount they like.
+ */
+
+use near_sdk::store::LookupMap;
+use near_sdk::{env, ext_contract, near, AccountId, Gas, NearToken, PanicOnDefault, Promise};
+
+const GAS_FOR_POOL_CALL: Gas = Gas::from_tgas(50);
+const GAS_FOR_CALLBACK: Gas = Gas::from_tgas(10);
+
+/// Subset of the core staking pool interface
+#[ext_contract(ext_staking_pool)]
+pub trait StakingPool {
+    fn deposit_and_stake(&mut self);
+    fn unstake(&mut self, amount: NearToken);
+}
+
+#[near(contract_state)]
+#[derive(PanicOnDefault)]
+pub struct StakingProxy {
+    pool: AccountId,
+    staked: LookupMap<AccountId, NearToken>,
+    withdrawable: LookupMap<AccountId, NearToken>,
+}
+
+#[near]
+impl StakingProxy {
+    #[init]
+    pub fn new(pool: AccountId) -> Self {
+        Self {
+            pool,
+            staked: LookupMap::new(b"s"),
+            withdrawable: LookupMap::new(b"w"),
+        }
+    }
+
+    #[payable]
+    pub fn stake(&mut self) -> Promise {
+        let account_id = env::predecessor_account_id();
+        let amount = env::attached_deposit();
+        let staked = get(&self.staked, &account_id);
+        self.staked
+            .insert(account_id, staked.saturating_add(amount));
+
+        ext_staking_pool::ext(self.pool.clone())
+            .with_static_gas(GAS_FOR_POOL_CALL)
+            .with_attached_deposit(amount)
+            .deposit_and_stake()
+    }
+
+    /// Step 1: ask the pool to unstake
+    pub fn unstake(&mut self, amount: NearToken) -> Promise {
+        let account_id = env::predecessor_account_id();
+        let staked = get(&self.staked, &account_id)
+            .checked_sub(amount)
+            .unwrap_or_else(|| env::panic_str("Insufficient stake"));
+        self.staked.insert(account_id.clone(), staked);
+
+        ext_staking_pool::ext(self.pool.clone())
+            .with_static_gas(GAS_FOR_POOL_CALL)
+            .unstake(amount)
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_CALLBACK)
+                    .on_unstaked(account_id, amount),
+            )
+    }
+
+    /// Step 2: credit the unstaked amount
+    // VULNERABILITY 1: Missing #[private]
+    // Should be #[private], i.e.
+    // require!(env::predecessor_account_id() == env::current_account_id())
+    // As written, any account can call on_unstaked directly
+    pub fn on_unstaked(&mut self, account_id: AccountId, amount: NearToken) {
+        // VULNERABILITY 2: Arguments are trusted as-is
+        // account_id and amount only mean something when this contract
+        // built them in unstake(); a direct call fabricates both
+
+        // VULNERABILITY 3: The pool's result is never checked
+        // Even the legitimate callback credits a failed unstake
+        let withdrawable = get(&self.withdrawable, &account_id);
+        self.withdrawable
+            .insert(account_id, withdrawable.saturating_add(amount));
+    }
+
+    pub fn withdraw(&mut self, amount: NearToken) -> Promise {
+        let account_id = env::predecessor_account_id();
+        let withdrawable = get(&self.withdrawable, &account_id)
+            .checked_sub(amount)
+            .unwrap_or_else(|| env::panic_str("Insufficient withdrawable balance"));
+        self.withdrawable.insert(account_id.clone(), withdrawable);
+        Promise::new(account_id).transfer(amount)
+    }
+
+    pub fn get_withdrawable(&self, account_id: AccountId) -> NearToken {
+        get(&self.withdrawable, &account_id)
+    }
+}
+
+fn get(map: &LookupMap<AccountId, NearToken>, account_id: &AccountId) -> NearToken {
+    map.get(account_id)
+        .copied()
+        .unwrap_or(NearToken::from_yoctonear(0))
+}
+
+/*
+ * SECURE VERSION:
+ *
+ * on_unstaked is #[private], so only the promise chain started by unstake
+ * can reach it, and it restores the stake when the pool call failed.
+ * Callback arguments are trusted only because nobody else can supply them.
+ *
+ * Compiled in secure/public_callback.rs.
+ */
+
+/*
+ * EXPLOIT SCENARIO:
+ *
+ * 1. Users have staked 1,000 NEAR through the proxy; the proxy also holds
+ *    liquid NEAR from completed withdrawals from the pool
+ * 2. Attacker calls on_unstaked { account_id: attacker, amount: 500 NEAR }
+ *    directly - no stake, no unstake, no pool involved
+ * 3. The method is public and trusts its arguments: the attacker now has
+ *    500 NEAR withdrawable
+ * 4. Attacker calls withdraw { amount: 500 NEAR } and takes the liquid
+ *    NEAR belonging to honest users
+ */
@@ -0,0 +1,132 @@
+/*
+ * SECURE NEAR CONTRACT
+ *
+ * Fixed counterpart of ../account_id_validation.rs
+ *
+ * Recipients are typed AccountIds, so malformed ids are rejected with a
+ * validation error when the arguments are deserialized, before anything is
+ * credited or queued. Payouts that still fail - a well-formed id with no
+ * account behind it - are returned to the sender.
+ */
+
+use near_common::settle;
+use near_sdk::store::{LookupMap, Vector};
+use near_sdk::{
+    env, near, require, AccountId, Gas, NearToken, PanicOnDefault, Promise, PromiseError,
+};
+
+const GAS_FOR_CALLBACK: Gas = Gas::from_tgas(5);
+
+/// A transfer scheduled by send_later
+#[near(serializers = [borsh])]
+pub struct Payout {
+    pub sender: AccountId,
+    pub recipient: AccountId,
+    pub amount: NearToken,
+}
+
+#[near(contract_state)]
+#[derive(PanicOnDefault)]
+pub struct TipJar {
+    balances: LookupMap<AccountId, NearToken>,
+    queue: Vector<Payout>,
+    /// Index of the next payout to send
+    next_payout: u32,
+}
+
+#[near]
+impl TipJar {
+    #[init]
+    pub fn new() -> Self {
+        Self {
+            balances: LookupMap::new(b"b"),
+            queue: Vector::new(b"q"),
+            next_payout: 0,
+        }
+    }
+
+    /// Credits the attached deposit to `recipient`, who claims it later
+    #[payable]
+    pub fn tip(&mut self, recipient: AccountId) {
+        // SAFE: `recipient` was validated during deserialization; an id
+        // that could never sign a claim does not get this far
+        self.credit(recipient, env::attached_deposit());
+    }
+
+    pub fn claim(&mut self) -> Promise {
+        let account_id = env::predecessor_account_id();
+        let amount = self
+            .balances
+            .remove(&account_id)
+            .unwrap_or_else(|| env::panic_str("Nothing to claim"));
+        Promise::new(account_id).transfer(amount)
+    }
+
+    /// Queues the attached deposit to be pushed to `recipient`
+    #[payable]
+    pub fn send_later(&mut self, recipient: AccountId) {
+        require!(
+            env::attached_deposit() > NearToken::from_yoctonear(0),
+            "Attach the amount to send"
+        );
+        self.queue.push(Payout {
+            sender: env::predecessor_account_id(),
+            recipient,
+            amount: env::attached_deposit(),
+        });
+    }
+
+    /// Sends up to `limit` queued payouts, in order
+    pub fn process_queue(&mut self, limit: u32) {
+        let end = self.queue.len().min(self.next_payout.saturating_add(limit));
+        for index in self.next_payout..end {
+            let payout = &self.queue[index];
+
+            // SAFE: Every queued recipient is already a valid AccountId, and
+            // a failed transfer is settled by the callback
+            Promise::new(payout.recipient.clone())
+                .transfer(payout.amount)
+                .then(
+                    Self::ext(env::current_account_id())
+                        .with_static_gas(GAS_FOR_CALLBACK)
+                        .on_payout(payout.sender.clone(), payout.amount),
+                )
+                .detach();
+        }
+        self.next_payout = end;
+    }
+
+    /// Returns a failed payout to its sender's claimable balance
+    #[private]
+    pub fn on_payout(
+        &mut self,
+        sender: AccountId,
+        amount: NearToken,
+        #[callback_result] result: Result<(), PromiseError>,
+    ) {
+        settle(result, || self.credit(sender, amount));
+    }
+
+    pub fn get_balance(&self, recipient: AccountId) -> NearToken {
+        self.balance(&recipient)
+    }
+
+    pub fn get_next_payout(&self) -> u32 {
+        self.next_payout
+    }
+
+    fn balance(&self, account_id: &AccountId) -> NearToken {
+        self.balances
+            .get(account_id)
+            .copied()
+            .unwrap_or(NearToken::from_yoctonear(0))
+    }
+
+    fn credit(&mut self, account_id: AccountId, amount: NearToken) {
+        let balance = self
+            .balance(&account_id)
+            .checked_add(amount)
+            .unwrap_or_else(|| env::panic_str("Balance overflow"));
+        self.balances.insert(account_id, balance);
+    }
+}
@@ -0,0 +1,181 @@
+/*
+ * SECURE NEAR CONTRACT
+ *
+ * Fixed counterpart of ../balance_overflow.rs
+ *
+ * User input is bounded before it reaches any arithmetic, every sum is
+ * checked, and products of yoctoNEAR amounts are taken in 256 bits with a
+ * single division at the end.
+ */
+
+use near_sdk::json_types::U128;
+use near_sdk::store::LookupMap;
+use near_sdk::{env, near, require, AccountId, NearToken, PanicOnDefault, Promise};
+
+const NS_PER_DAY: u64 = 24 * 60 * 60 * 1_000_000_000;
+const NS_PER_YEAR: u128 = 365 * NS_PER_DAY as u128;
+const BASE_APR_BPS: u128 = 500;
+const BPS: u128 = 10_000;
+const MAX_LOCK_DAYS: u64 = 4 * 365;
+
+mod wide {
+    // The macro's expansion trips these lints, not code written here
+    #![allow(clippy::assign_op_pattern, clippy::manual_div_ceil)]
+
+    uint::construct_uint! {
+        /// Wide enough for the product of any two u128 values
+        pub struct U256(4);
+    }
+}
+
+use wide::U256;
+
+#[near(serializers = [borsh])]
+#[derive(Clone, Default)]
+pub struct Stake {
+    pub amount: u128,
+    pub unlock_at: u64,
+    /// Reward multiplier in percent; 100 = no boost
+    pub boost: u128,
+    pub last_claim: u64,
+}
+
+#[near(contract_state)]
+#[derive(PanicOnDefault)]
+pub struct StakingVault {
+    stakes: LookupMap<AccountId, Stake>,
+    total_staked: u128,
+    reward_pool: u128,
+}
+
+#[near]
+impl StakingVault {
+    #[init]
+    pub fn new() -> Self {
+        Self {
+            stakes: LookupMap::new(b"s"),
+            total_staked: 0,
+            reward_pool: 0,
+        }
+    }
+
+    #[payable]
+    pub fn fund_rewards(&mut self) {
+        self.reward_pool = checked_add(self.reward_pool, env::attached_deposit().as_yoctonear());
+    }
+
+    /// Stakes the attached deposit, locked for `lock_days` in exchange for
+    /// a boost of one percentage point per day
+    #[payable]
+    pub fn stake(&mut self, lock_days: u64) {
+        // CHECK: Bound user input before it reaches any arithmetic
+        require!(lock_days <= MAX_LOCK_DAYS, "Lock too long");
+
+        let account_id = env::predecessor_account_id();
+        let amount = env::attached_deposit().as_yoctonear();
+        let mut stake = self.stake_of(&account_id);
+
+        // SAFE: Checked arithmetic, panicking instead of wrapping; a new
+        // stake never shortens an existing lock
+        let unlock_at = lock_days
+            .checked_mul(NS_PER_DAY)
+            .and_then(|lock| env::block_timestamp().checked_add(lock))
+            .unwrap_or_else(|| env::panic_str("Unlock time overflow"));
+        stake.unlock_at = stake.unlock_at.max(unlock_at);
+        stake.boost = stake.boost.max(100 + u128::from(lock_days));
+
+        stake.amount = checked_add(stake.amount, amount);
+        stake.last_claim = env::block_timestamp();
+        self.total_staked = checked_add(self.total_staked, amount);
+        self.stakes.insert(account_id, stake);
+    }
+
+    /// Splits the attached deposit between several unlocked stakes
+    #[payable]
+    pub fn stake_for(&mut self, recipients: Vec<(AccountId, U128)>) {
+        // SAFE: An overflowing sum aborts the call instead of wrapping
+        let total = recipients
+            .iter()
+            .try_fold(0u128, |total, (_, amount)| total.checked_add(amount.0))
+            .unwrap_or_else(|| env::panic_str("Amount overflow"));
+        require!(
+            total == env::attached_deposit().as_yoctonear(),
+            "Deposit must equal the sum of all amounts"
+        );
+
+        for (account_id, amount) in recipients {
+            let mut stake = self.stake_of(&account_id);
+            if stake.boost == 0 {
+                stake.boost = 100;
+                stake.last_claim = env::block_timestamp();
+            }
+            stake.amount = checked_add(stake.amount, amount.0);
+            self.stakes.insert(account_id, stake);
+        }
+        self.total_staked = checked_add(self.total_staked, total);
+    }
+
+    pub fn claim_rewards(&mut self) -> Promise {
+        let account_id = env::predecessor_account_id();
+        let mut stake = self.stake_of(&account_id);
+        let elapsed = env::block_timestamp()
+            .checked_sub(stake.last_claim)
+            .unwrap_or_else(|| env::panic_str("Clock went backwards"));
+
+        // SAFE: The small factors are bounded, so their product is checked
+        // once; the yoctoNEAR amount only ever meets it in 256 bits
+        let rate = (BASE_APR_BPS * stake.boost)
+            .checked_mul(u128::from(elapsed))
+            .unwrap_or_else(|| env::panic_str("Reward overflow"));
+        let reward = mul_div(stake.amount, rate, BPS * 100 * NS_PER_YEAR);
+
+        // SAFE: Correct order - multiply first, then divide
+        let cap = mul_div(self.reward_pool, stake.amount, self.total_staked);
+
+        let reward = reward.min(cap);
+        stake.last_claim = env::block_timestamp();
+        self.stakes.insert(account_id.clone(), stake);
+        self.reward_pool = self
+            .reward_pool
+            .checked_sub(reward)
+            .unwrap_or_else(|| env::panic_str("Reward pool exhausted"));
+        Promise::new(account_id).transfer(NearToken::from_yoctonear(reward))
+    }
+
+    pub fn unstake(&mut self) -> Promise {
+        let account_id = env::predecessor_account_id();
+        let stake = self.stake_of(&account_id);
+        require!(env::block_timestamp() >= stake.unlock_at, "Stake is locked");
+        self.stakes.remove(&account_id);
+
+        // SAFE: total_staked is the checked sum of every stake, so this can
+        // only fail if accounting is already broken - and then it should
+        self.total_staked = self
+            .total_staked
+            .checked_sub(stake.amount)
+            .unwrap_or_else(|| env::panic_str("Stake accounting underflow"));
+        Promise::new(account_id).transfer(NearToken::from_yoctonear(stake.amount))
+    }
+
+    pub fn get_stake(&self, account_id: AccountId) -> (U128, u64, U128) {
+        let stake = self.stake_of(&account_id);
+        (stake.amount.into(), stake.unlock_at, stake.boost.into())
+    }
+
+    fn stake_of(&self, account_id: &AccountId) -> Stake {
+        self.stakes.get(account_id).cloned().unwrap_or_default()
+    }
+}
+
+fn checked_add(a: u128, b: u128) -> u128 {
+    a.checked_add(b)
+        .unwrap_or_else(|| env::panic_str("Balance overflow"))
+}
+
+/// a * b / denominator without an intermediate overflow
+fn mul_div(a: u128, b: u128, denominator: u128) -> u128 {
+    require!(denominator > 0, "Division by zero");
+    (U256::from(a) * U256::from(b) / U256::from(denominator))
+        .try_into()
+        .unwrap_or_else(|_| env::panic_str("Result overflow"))
+}
@@ -0,0 +1,158 @@
+/*
+ * SECURE NEAR CONTRACT
+ *
+ * Fixed counterpart of ../ft_transfer_call_double_spend.rs
+ *
+ * Only the pool's own token contracts may deposit, checked before any
+ * state changes. A swap deposit is never credited: either the swap
+ * completes and only its output is credited, or ft_on_transfer panics and
+ * the token's ft_resolve_transfer refunds the whole amount - which the
+ * exchange never counted.
+ */
+
+use near_common::settle;
+use near_sdk::json_types::U128;
+use near_sdk::store::LookupMap;
+use near_sdk::{
+    env, ext_contract, near, require, serde_json, AccountId, Gas, NearToken, PanicOnDefault,
+    Promise, PromiseError, PromiseOrValue,
+};
+
+const GAS_FOR_FT_TRANSFER: Gas = Gas::from_tgas(10);
+const GAS_FOR_CALLBACK: Gas = Gas::from_tgas(10);
+
+/// NEP-141 core
+#[ext_contract(ext_ft)]
+pub trait FungibleToken {
+    fn ft_transfer(&mut self, receiver_id: AccountId, amount: U128, memo: Option<String>);
+}
+
+/// ft_transfer_call `msg`: swap the deposit immediately
+#[near(serializers = [json])]
+pub struct SwapAction {
+    pub min_amount_out: U128,
+}
+
+#[near(contract_state)]
+#[derive(PanicOnDefault)]
+pub struct Exchange {
+    tokens: [AccountId; 2],
+    reserves: [u128; 2],
+    /// (account, token) -> internal balance
+    deposits: LookupMap<(AccountId, AccountId), u128>,
+}
+
+#[near]
+impl Exchange {
+    /// Reserves are seeded separately by the owner's ft_transfer
+    #[init]
+    pub fn new(tokens: [AccountId; 2], reserves: [U128; 2]) -> Self {
+        require!(tokens[0] != tokens[1], "Tokens must differ");
+        Self {
+            tokens,
+            reserves: reserves.map(|reserve| reserve.0),
+            deposits: LookupMap::new(b"d"),
+        }
+    }
+
+    /// NEP-141 receiver: called by the token contract during ft_transfer_call
+    pub fn ft_on_transfer(
+        &mut self,
+        sender_id: AccountId,
+        amount: U128,
+        msg: String,
+    ) -> PromiseOrValue<U128> {
+        // CHECK: Whitelist the calling token before touching any state
+        let token_id = env::predecessor_account_id();
+        let index_in = self.token_index(&token_id);
+
+        if msg.is_empty() {
+            self.credit(&sender_id, &token_id, amount.0);
+            return PromiseOrValue::Value(U128(0));
+        }
+        let action: SwapAction =
+            serde_json::from_str(&msg).unwrap_or_else(|_| env::panic_str("Invalid swap action"));
+
+        // SAFE: Slippage panics instead of returning the deposit as unused,
+        // so the only refund path is the token's own resolve step
+        let amount_out = self.swap_exact_in(index_in, amount.0, action.min_amount_out.0);
+        let token_out = self.tokens[1 - index_in].clone();
+        self.credit(&sender_id, &token_out, amount_out);
+        PromiseOrValue::Value(U128(0))
+    }
+
+    pub fn withdraw(&mut self, token_id: AccountId, amount: U128) -> Promise {
+        let account_id = env::predecessor_account_id();
+        self.debit(&account_id, &token_id, amount.0);
+        ext_ft::ext(token_id.clone())
+            .with_attached_deposit(NearToken::from_yoctonear(1))
+            .with_static_gas(GAS_FOR_FT_TRANSFER)
+            .ft_transfer(account_id.clone(), amount, None)
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_CALLBACK)
+                    .on_withdraw(account_id, token_id, amount),
+            )
+    }
+
+    #[private]
+    pub fn on_withdraw(
+        &mut self,
+        account_id: AccountId,
+        token_id: AccountId,
+        amount: U128,
+        #[callback_result] result: Result<(), PromiseError>,
+    ) {
+        settle(result, || self.credit(&account_id, &token_id, amount.0));
+    }
+
+    pub fn get_deposit(&self, account_id: AccountId, token_id: AccountId) -> U128 {
+        U128(self.deposit_of(&account_id, &token_id))
+    }
+
+    fn token_index(&self, token_id: &AccountId) -> usize {
+        self.tokens
+            .iter()
+            .position(|token| token == token_id)
+            .unwrap_or_else(|| env::panic_str("Token not whitelisted"))
+    }
+
+    /// Moves `amount_in` into the reserves and returns the output amount
+    fn swap_exact_in(&mut self, index_in: usize, amount_in: u128, min_amount_out: u128) -> u128 {
+        let index_out = 1 - index_in;
+        let reserve_in = self.reserves[index_in]
+            .checked_add(amount_in)
+            .unwrap_or_else(|| env::panic_str("Reserve overflow"));
+        let amount_out = self.reserves[index_out]
+            .checked_mul(amount_in)
+            .unwrap_or_else(|| env::panic_str("Swap overflow"))
+            / reserve_in;
+        require!(amount_out >= min_amount_out, "Slippage exceeded");
+        self.reserves[index_in] = reserve_in;
+        self.reserves[index_out] -= amount_out;
+        amount_out
+    }
+
+    fn deposit_of(&self, account_id: &AccountId, token_id: &AccountId) -> u128 {
+        self.deposits
+            .get(&(account_id.clone(), token_id.clone()))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    fn credit(&mut self, account_id: &AccountId, token_id: &AccountId, amount: u128) {
+        let balance = self
+            .deposit_of(account_id, token_id)
+            .checked_add(amount)
+            .unwrap_or_else(|| env::panic_str("Deposit overflow"));
+        self.deposits
+            .insert((account_id.clone(), token_id.clone()), balance);
+    }
+
+    fn debit(&mut self, account_id: &AccountId, token_id: &AccountId, amount: u128) {
+        let balance = self.deposit_of(account_id, token_id);
+        require!(balance >= amount, "Insufficient deposit");
+        self.deposits
+            .insert((account_id.clone(), token_id.clone()), balance - amount);
+    }
+}
@@ -0,0 +1,96 @@
+/*
+ * SECURE NEAR CONTRACT
+ *
+ * Fixed counterpart of ../missing_one_yocto.rs
+ *
+ * Fund-moving methods are #[payable] and require exactly one attached
+ * yoctoNEAR, which only a full-access key can attach. claim_daily_reward
+ * stays callable by the dapp's function-call key: it can only ever credit
+ * the caller.
+ */
+
+use near_common::assert_one_yocto;
+use near_sdk::store::LookupMap;
+use near_sdk::{env, near, AccountId, NearToken, PanicOnDefault, Promise};
+
+const DAILY_REWARD: NearToken = NearToken::from_millinear(1);
+const DAY_NS: u64 = 24 * 60 * 60 * 1_000_000_000;
+
+#[near(contract_state)]
+#[derive(PanicOnDefault)]
+pub struct Vault {
+    balances: LookupMap<AccountId, NearToken>,
+    last_claim: LookupMap<AccountId, u64>,
+}
+
+#[near]
+impl Vault {
+    #[init]
+    pub fn new() -> Self {
+        Self {
+            balances: LookupMap::new(b"b"),
+            last_claim: LookupMap::new(b"c"),
+        }
+    }
+
+    #[payable]
+    pub fn deposit(&mut self) {
+        let account_id = env::predecessor_account_id();
+        let balance = self.balance(&account_id);
+        self.balances
+            .insert(account_id, balance.saturating_add(env::attached_deposit()));
+    }
+
+    /// Meant to be signed by the dapp's function-call key - no prompt needed
+    pub fn claim_daily_reward(&mut self) {
+        let account_id = env::predecessor_account_id();
+        let now = env::block_timestamp();
+        let last = self.last_claim.get(&account_id).copied().unwrap_or(0);
+        if now < last + DAY_NS {
+            env::panic_str("Already claimed today");
+        }
+        self.last_claim.insert(account_id.clone(), now);
+        let balance = self.balance(&account_id);
+        self.balances
+            .insert(account_id, balance.saturating_add(DAILY_REWARD));
+    }
+
+    #[payable]
+    pub fn transfer(&mut self, receiver_id: AccountId, amount: NearToken) {
+        // CHECK: Exactly one yoctoNEAR proves a full-access key signed this
+        // and the user's wallet showed them a confirmation
+        assert_one_yocto();
+        let sender_id = env::predecessor_account_id();
+        self.debit(&sender_id, amount);
+        let balance = self.balance(&receiver_id);
+        self.balances
+            .insert(receiver_id, balance.saturating_add(amount));
+    }
+
+    #[payable]
+    pub fn withdraw_to(&mut self, receiver_id: AccountId, amount: NearToken) -> Promise {
+        assert_one_yocto();
+        let account_id = env::predecessor_account_id();
+        self.debit(&account_id, amount);
+        Promise::new(receiver_id).transfer(amount)
+    }
+
+    pub fn balance_of(&self, account_id: AccountId) -> NearToken {
+        self.balance(&account_id)
+    }
+
+    fn balance(&self, account_id: &AccountId) -> NearToken {
+        self.balances
+            .get(account_id)
+            .copied()
+            .unwrap_or(NearToken::from_yoctonear(0))
+    }
+
+    fn debit(&mut self, account_id: &AccountId, amount: NearToken) {
+        let remaining = self
+            .balance(account_id)
+            .checked_sub(amount)
+            .unwrap_or_else(|| env::panic_str("Insufficient balance"));
+        self.balances.insert(account_id.clone(), remaining);
+    }
+}
@@ -0,0 +1,63 @@
+/*
+ * SECURE NEAR CONTRACT
+ *
+ * Fixed counterpart of ../missing_predecessor_check.rs
+ *
+ * Every privileged method authorizes predecessor_account_id() against the
+ * stored owner, and withdrawals can only pay the owner.
+ */
+
+use near_common::{assert_owner, assert_owner_one_yocto};
+use near_sdk::{env, near, AccountId, NearToken, PanicOnDefault, Promise};
+
+#[near(contract_state)]
+#[derive(PanicOnDefault)]
+pub struct Vault {
+    owner: AccountId,
+    /// Deposited funds; the rest of the account balance covers storage
+    balance: NearToken,
+}
+
+#[near]
+impl Vault {
+    #[init]
+    pub fn new(owner: AccountId) -> Self {
+        Self {
+            owner,
+            balance: NearToken::from_yoctonear(0),
+        }
+    }
+
+    #[payable]
+    pub fn deposit(&mut self) {
+        self.balance = self.balance.saturating_add(env::attached_deposit());
+    }
+
+    pub fn withdraw(&mut self, amount: NearToken) -> Promise {
+        // CHECK: The immediate caller - a user or a contract - must be the owner
+        assert_owner(&self.owner);
+
+        self.balance = self
+            .balance
+            .checked_sub(amount)
+            .unwrap_or_else(|| env::panic_str("Insufficient balance"));
+
+        // SAFE: Funds only ever go to the owner
+        Promise::new(self.owner.clone()).transfer(amount)
+    }
+
+    #[payable]
+    pub fn transfer_ownership(&mut self, new_owner: AccountId) {
+        // CHECK: predecessor, not signer - a relaying contract cannot pass
+        assert_owner_one_yocto(&self.owner);
+        self.owner = new_owner;
+    }
+
+    pub fn get_owner(&self) -> AccountId {
+        self.owner.clone()
+    }
+
+    pub fn get_balance(&self) -> NearToken {
+        self.balance
+    }
+}
@@ -0,0 +1,122 @@
+/*
+ * SECURE NEAR CONTRACT
+ *
+ * Fixed counterpart of ../public_callback.rs
+ *
+ * on_unstaked is #[private]: it refuses any caller but the contract
+ * itself, so only the promise scheduled by unstake() can reach it. It
+ * also checks the pool's result: a failed unstake restores the stake
+ * instead of crediting it.
+ */
+
+use near_common::settle;
+use near_sdk::store::LookupMap;
+use near_sdk::{
+    env, ext_contract, near, AccountId, Gas, NearToken, PanicOnDefault, Promise, PromiseError,
+};
+
+const GAS_FOR_POOL_CALL: Gas = Gas::from_tgas(50);
+const GAS_FOR_CALLBACK: Gas = Gas::from_tgas(10);
+
+/// Subset of the core staking pool interface
+#[ext_contract(ext_staking_pool)]
+pub trait StakingPool {
+    fn deposit_and_stake(&mut self);
+    fn unstake(&mut self, amount: NearToken);
+}
+
+#[near(contract_state)]
+#[derive(PanicOnDefault)]
+pub struct StakingProxy {
+    pool: AccountId,
+    staked: LookupMap<AccountId, NearToken>,
+    withdrawable: LookupMap<AccountId, NearToken>,
+}
+
+#[near]
+impl StakingProxy {
+    #[init]
+    pub fn new(pool: AccountId) -> Self {
+        Self {
+            pool,
+            staked: LookupMap::new(b"s"),
+            withdrawable: LookupMap::new(b"w"),
+        }
+    }
+
+    #[payable]
+    pub fn stake(&mut self) -> Promise {
+        let account_id = env::predecessor_account_id();
+        let amount = env::attached_deposit();
+        let staked = get(&self.staked, &account_id);
+        self.staked
+            .insert(account_id, staked.saturating_add(amount));
+
+        ext_staking_pool::ext(self.pool.clone())
+            .with_static_gas(GAS_FOR_POOL_CALL)
+            .with_attached_deposit(amount)
+            .deposit_and_stake()
+    }
+
+    /// Step 1: ask the pool to unstake
+    pub fn unstake(&mut self, amount: NearToken) -> Promise {
+        let account_id = env::predecessor_account_id();
+        let staked = get(&self.staked, &account_id)
+            .checked_sub(amount)
+            .unwrap_or_else(|| env::panic_str("Insufficient stake"));
+        self.staked.insert(account_id.clone(), staked);
+
+        ext_staking_pool::ext(self.pool.clone())
+            .with_static_gas(GAS_FOR_POOL_CALL)
+            .unstake(amount)
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_CALLBACK)
+                    .on_unstaked(account_id, amount),
+            )
+    }
+
+    /// Step 2: credit the unstaked amount
+    // CHECK: #[private] expands to
+    // require!(env::predecessor_account_id() == env::current_account_id())
+    // and runs before the callback result is even read, so account_id and
+    // amount can only come from unstake()
+    #[private]
+    pub fn on_unstaked(
+        &mut self,
+        account_id: AccountId,
+        amount: NearToken,
+        #[callback_result] result: Result<(), PromiseError>,
+    ) {
+        // CHECK: Pool refused - restore the stake instead of crediting
+        let unstaked = settle(result, || {
+            let staked = get(&self.staked, &account_id);
+            self.staked
+                .insert(account_id.clone(), staked.saturating_add(amount));
+        });
+        if unstaked.is_some() {
+            let withdrawable = get(&self.withdrawable, &account_id);
+            self.withdrawable
+                .insert(account_id, withdrawable.saturating_add(amount));
+        }
+    }
+
+    pub fn withdraw(&mut self, amount: NearToken) -> Promise {
+        let account_id = env::predecessor_account_id();
+        let withdrawable = get(&self.withdrawable, &account_id)
+            .checked_sub(amount)
+            .unwrap_or_else(|| env::panic_str("Insufficient withdrawable balance"));
+        self.withdrawable.insert(account_id.clone(), withdrawable);
+        Promise::new(account_id).transfer(amount)
+    }
+
+    pub fn get_withdrawable(&self, account_id: AccountId) -> NearToken {
+        get(&self.withdrawable, &account_id)
+    }
+}
+
+fn get(map: &LookupMap<AccountId, NearToken>, account_id: &AccountId) -> NearToken {
+    map.get(account_id)
+        .copied()
+        .unwrap_or(NearToken::from_yoctonear(0))
+}
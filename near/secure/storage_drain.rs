@@ -0,0 +1,140 @@
+/*
+ * SECURE NEAR CONTRACT
+ *
+ * Fixed counterpart of ../storage_drain.rs
+ *
+ * NEP-145 style storage management: callers deposit NEAR up front, every
+ * claim is charged for exactly the bytes it adds, and releasing a handle
+ * credits the freed bytes back. Handle and metadata sizes are bounded.
+ */
+
+use near_common::assert_one_yocto;
+use near_sdk::store::LookupMap;
+use near_sdk::{env, near, require, AccountId, NearToken, PanicOnDefault, Promise};
+
+pub const MAX_HANDLE_LEN: usize = 32;
+pub const MAX_METADATA_LEN: usize = 256;
+
+#[near(serializers = [borsh, json])]
+#[derive(Clone)]
+pub struct Handle {
+    pub owner: AccountId,
+    pub metadata: String,
+}
+
+#[near(contract_state)]
+#[derive(PanicOnDefault)]
+pub struct Registry {
+    handles: LookupMap<String, Handle>,
+    handle_count: u64,
+    /// Unspent NEAR each account has deposited to pay for its own storage
+    storage_deposits: LookupMap<AccountId, NearToken>,
+}
+
+#[near]
+impl Registry {
+    #[init]
+    pub fn new() -> Self {
+        Self {
+            handles: LookupMap::new(b"h"),
+            handle_count: 0,
+            storage_deposits: LookupMap::new(b"d"),
+        }
+    }
+
+    #[payable]
+    pub fn storage_deposit(&mut self, account_id: Option<AccountId>) -> NearToken {
+        let account_id = account_id.unwrap_or_else(env::predecessor_account_id);
+        let available = self
+            .storage_available(&account_id)
+            .saturating_add(env::attached_deposit());
+        self.storage_deposits.insert(account_id, available);
+        available
+    }
+
+    #[payable]
+    pub fn storage_withdraw(&mut self, amount: NearToken) -> Promise {
+        assert_one_yocto();
+        let account_id = env::predecessor_account_id();
+        let available = self
+            .storage_available(&account_id)
+            .checked_sub(amount)
+            .unwrap_or_else(|| env::panic_str("Insufficient storage deposit"));
+        self.storage_deposits.insert(account_id.clone(), available);
+        Promise::new(account_id).transfer(amount)
+    }
+
+    pub fn storage_balance_of(&self, account_id: AccountId) -> NearToken {
+        self.storage_available(&account_id)
+    }
+
+    pub fn claim_handle(&mut self, handle: String, metadata: String) {
+        // CHECK: Bounded sizes
+        require!(
+            !handle.is_empty() && handle.len() <= MAX_HANDLE_LEN,
+            "Invalid handle"
+        );
+        require!(metadata.len() <= MAX_METADATA_LEN, "Metadata too long");
+        require!(!self.handles.contains_key(&handle), "Handle taken");
+
+        let account_id = env::predecessor_account_id();
+        let initial_usage = env::storage_usage();
+
+        self.handles.insert(
+            handle,
+            Handle {
+                owner: account_id.clone(),
+                metadata,
+            },
+        );
+        self.handles.flush();
+        self.handle_count += 1;
+
+        // SAFE: The caller pays for exactly the bytes they added
+        let cost = bytes_cost(env::storage_usage() - initial_usage);
+        let available = self
+            .storage_available(&account_id)
+            .checked_sub(cost)
+            .unwrap_or_else(|| env::panic_str("Insufficient storage deposit"));
+        self.storage_deposits.insert(account_id, available);
+    }
+
+    pub fn release_handle(&mut self, handle: String) {
+        let entry = self
+            .handles
+            .get(&handle)
+            .unwrap_or_else(|| env::panic_str("Unknown handle"));
+        let account_id = env::predecessor_account_id();
+        require!(entry.owner == account_id, "Not the handle owner");
+
+        let initial_usage = env::storage_usage();
+        self.handles.remove(&handle);
+        self.handles.flush();
+        self.handle_count -= 1;
+
+        // SAFE: Freed storage goes back to whoever paid for it
+        let refund = bytes_cost(initial_usage - env::storage_usage());
+        let available = self.storage_available(&account_id).saturating_add(refund);
+        self.storage_deposits.insert(account_id, available);
+    }
+
+    pub fn get_handle(&self, handle: String) -> Option<Handle> {
+        self.handles.get(&handle).cloned()
+    }
+
+    /// NEAR currently locked to cover this contract's storage
+    pub fn storage_locked(&self) -> NearToken {
+        bytes_cost(env::storage_usage())
+    }
+
+    fn storage_available(&self, account_id: &AccountId) -> NearToken {
+        self.storage_deposits
+            .get(account_id)
+            .copied()
+            .unwrap_or(NearToken::from_yoctonear(0))
+    }
+}
+
+fn bytes_cost(bytes: u64) -> NearToken {
+    env::storage_byte_cost().saturating_mul(bytes.into())
+}
@@ -0,0 +1,132 @@
+/*
+ * SECURE NEAR CONTRACT
+ *
+ * Fixed counterpart of ../unbounded_views.rs
+ *
+ * Dividends are paid one bounded page per call, resuming from a stored
+ * cursor, and the member view is paginated the same way. No call's gas
+ * depends on how many members exist.
+ */
+
+use near_sdk::store::IterableMap;
+use near_sdk::{env, log, near, require, AccountId, NearToken, PanicOnDefault, Promise};
+
+const SEAT_PRICE: NearToken = NearToken::from_millinear(10);
+const DEFAULT_LIMIT: u32 = 50;
+const MAX_LIMIT: u32 = 100;
+
+/// Payout in progress; per_seat is fixed when the round starts
+#[near(serializers = [borsh])]
+pub struct PayoutRound {
+    pub per_seat: NearToken,
+    pub cursor: u32,
+}
+
+#[near(contract_state)]
+#[derive(PanicOnDefault)]
+pub struct Cooperative {
+    /// Member -> seats held; IterableMap reaches any index in O(1)
+    members: IterableMap<AccountId, u32>,
+    total_seats: u32,
+    dividend_pool: NearToken,
+    round: Option<PayoutRound>,
+}
+
+#[near]
+impl Cooperative {
+    #[init]
+    pub fn new() -> Self {
+        Self {
+            members: IterableMap::new(b"m"),
+            total_seats: 0,
+            dividend_pool: NearToken::from_yoctonear(0),
+            round: None,
+        }
+    }
+
+    /// Buys one seat for `account_id`, or for the caller
+    #[payable]
+    pub fn buy_seat(&mut self, account_id: Option<AccountId>) {
+        require!(
+            env::attached_deposit() == SEAT_PRICE,
+            "Attach exactly the seat price"
+        );
+        // CHECK: Seats are frozen while a round pays out against a snapshot
+        require!(self.round.is_none(), "Payout in progress");
+        let account_id = account_id.unwrap_or_else(env::predecessor_account_id);
+
+        let seats = self.members.get(&account_id).copied().unwrap_or(0);
+        self.members.insert(account_id, seats + 1);
+        self.total_seats = self
+            .total_seats
+            .checked_add(1)
+            .unwrap_or_else(|| env::panic_str("Too many seats"));
+    }
+
+    /// Revenue paid into the cooperative, shared at the next payout
+    #[payable]
+    pub fn fund_dividends(&mut self) {
+        self.dividend_pool = self.dividend_pool.saturating_add(env::attached_deposit());
+    }
+
+    /// Pays the next page of at most `limit` members; true once the round
+    /// is complete. Anyone may call it, so a round always finishes.
+    pub fn payout(&mut self, limit: Option<u32>) -> bool {
+        let mut round = match self.round.take() {
+            Some(round) => round,
+            None => {
+                require!(self.total_seats > 0, "No members");
+                PayoutRound {
+                    per_seat: self.dividend_pool.saturating_div(self.total_seats.into()),
+                    cursor: 0,
+                }
+            }
+        };
+
+        // SAFE: A bounded page starting at the stored cursor
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+        let mut paid = NearToken::from_yoctonear(0);
+        for (account_id, seats) in self
+            .members
+            .iter()
+            .skip(round.cursor as usize)
+            .take(limit as usize)
+        {
+            let amount = round.per_seat.saturating_mul((*seats).into());
+            Promise::new(account_id.clone()).transfer(amount).detach();
+            paid = paid.saturating_add(amount);
+        }
+        self.dividend_pool = self
+            .dividend_pool
+            .checked_sub(paid)
+            .unwrap_or_else(|| env::panic_str("Dividend pool exhausted"));
+
+        // Advance the cursor, or close the round after the last page
+        round.cursor = round.cursor.saturating_add(limit);
+        if round.cursor >= self.members.len() {
+            log!("Payout round complete");
+            return true;
+        }
+        self.round = Some(round);
+        false
+    }
+
+    // SAFE: Views are paginated and clamped the same way
+    pub fn get_members(
+        &self,
+        from_index: Option<u32>,
+        limit: Option<u32>,
+    ) -> Vec<(AccountId, u32)> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT);
+        self.members
+            .iter()
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(limit as usize)
+            .map(|(account_id, seats)| (account_id.clone(), *seats))
+            .collect()
+    }
+
+    pub fn get_dividend_pool(&self) -> NearToken {
+        self.dividend_pool
+    }
+}
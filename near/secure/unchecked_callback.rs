@@ -0,0 +1,125 @@
+/*
+ * SECURE NEAR CONTRACT
+ *
+ * Fixed counterpart of ../unchecked_callback.rs
+ *
+ * Deposits are credited only once the pull is known to have succeeded,
+ * and a failed payout rolls the debit back. Both callbacks reserve their
+ * own gas so the rollback always gets to run.
+ */
+
+use near_common::settle;
+use near_sdk::json_types::U128;
+use near_sdk::store::LookupMap;
+use near_sdk::{
+    env, ext_contract, log, near, AccountId, Gas, NearToken, PanicOnDefault, Promise, PromiseError,
+};
+
+const GAS_FOR_BANK_CALL: Gas = Gas::from_tgas(10);
+const GAS_FOR_CALLBACK: Gas = Gas::from_tgas(10);
+
+/// External token ledger with an allowance-based pull
+#[ext_contract(ext_bank)]
+pub trait Bank {
+    fn transfer(&mut self, receiver_id: AccountId, amount: U128);
+    fn transfer_from(&mut self, owner_id: AccountId, receiver_id: AccountId, amount: U128);
+}
+
+#[near(contract_state)]
+#[derive(PanicOnDefault)]
+pub struct Custody {
+    bank: AccountId,
+    balances: LookupMap<AccountId, u128>,
+}
+
+#[near]
+impl Custody {
+    #[init]
+    pub fn new(bank: AccountId) -> Self {
+        Self {
+            bank,
+            balances: LookupMap::new(b"b"),
+        }
+    }
+
+    /// Pulls `amount` from the caller's bank account into custody
+    pub fn deposit(&mut self, amount: U128) -> Promise {
+        let account_id = env::predecessor_account_id();
+
+        // SAFE: Nothing is credited until the pull is known to have succeeded
+        ext_bank::ext(self.bank.clone())
+            .with_static_gas(GAS_FOR_BANK_CALL)
+            .transfer_from(account_id.clone(), env::current_account_id(), amount)
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_CALLBACK)
+                    .on_deposit(account_id, amount),
+            )
+    }
+
+    /// Pays `amount` out of custody to the caller's bank account
+    pub fn withdraw(&mut self, amount: U128) -> Promise {
+        let account_id = env::predecessor_account_id();
+        let balance = self.balance(&account_id);
+        if balance < amount.0 {
+            env::panic_str("Insufficient balance");
+        }
+
+        // Debit first so a concurrent withdraw cannot spend it twice;
+        // on_withdraw restores it if the payout fails
+        self.balances.insert(account_id.clone(), balance - amount.0);
+
+        ext_bank::ext(self.bank.clone())
+            .with_static_gas(GAS_FOR_BANK_CALL)
+            .with_attached_deposit(NearToken::from_yoctonear(1))
+            .transfer(account_id.clone(), amount)
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_CALLBACK)
+                    .on_withdraw(account_id, amount),
+            )
+    }
+
+    #[private]
+    pub fn on_deposit(
+        &mut self,
+        account_id: AccountId,
+        amount: U128,
+        #[callback_result] result: Result<(), PromiseError>,
+    ) -> bool {
+        // CHECK: Credit only when the external transfer succeeded
+        if result.is_err() {
+            log!("Deposit of {} for {} failed", amount.0, account_id);
+            return false;
+        }
+        let balance = self
+            .balance(&account_id)
+            .checked_add(amount.0)
+            .unwrap_or_else(|| env::panic_str("Balance overflow"));
+        self.balances.insert(account_id, balance);
+        true
+    }
+
+    #[private]
+    pub fn on_withdraw(
+        &mut self,
+        account_id: AccountId,
+        amount: U128,
+        #[callback_result] result: Result<(), PromiseError>,
+    ) -> bool {
+        // SAFE: Roll the optimistic debit back when the payout failed
+        settle(result, || {
+            let balance = self.balance(&account_id);
+            self.balances.insert(account_id.clone(), balance + amount.0);
+        })
+        .is_some()
+    }
+
+    pub fn balance_of(&self, account_id: AccountId) -> U128 {
+        U128(self.balance(&account_id))
+    }
+
+    fn balance(&self, account_id: &AccountId) -> u128 {
+        self.balances.get(account_id).copied().unwrap_or(0)
+    }
+}
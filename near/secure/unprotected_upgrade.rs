@@ -0,0 +1,112 @@
+/*
+ * SECURE NEAR CONTRACT
+ *
+ * Fixed counterpart of ../unprotected_upgrade.rs
+ *
+ * Upgrades take two owner-signed steps: stage_upgrade publishes the code
+ * and its hash, and deploy_upgrade deploys exactly that code once a review
+ * window has passed. migrate is #[private] and carries the owner over from
+ * the old state instead of taking it as an argument.
+ */
+
+use near_common::{assert_owner, assert_owner_one_yocto};
+use near_sdk::json_types::Base58CryptoHash;
+use near_sdk::{env, near, require, AccountId, Gas, NearToken, PanicOnDefault, Promise};
+
+const GAS_FOR_MIGRATE: Gas = Gas::from_tgas(50);
+const UPGRADE_DELAY_NS: u64 = 2 * 24 * 60 * 60 * 1_000_000_000;
+const STAGED_CODE_KEY: &[u8] = b"staged_code";
+
+#[near(serializers = [borsh, json])]
+pub struct StagedUpgrade {
+    pub code_hash: Base58CryptoHash,
+    pub staged_at: u64,
+}
+
+#[near(contract_state)]
+#[derive(PanicOnDefault)]
+pub struct Treasury {
+    owner: AccountId,
+    staged: Option<StagedUpgrade>,
+}
+
+#[near]
+impl Treasury {
+    #[init]
+    pub fn new(owner: AccountId) -> Self {
+        Self {
+            owner,
+            staged: None,
+        }
+    }
+
+    #[payable]
+    pub fn fund(&mut self) {}
+
+    pub fn withdraw(&mut self, amount: NearToken) -> Promise {
+        assert_owner(&self.owner);
+        Promise::new(self.owner.clone()).transfer(amount)
+    }
+
+    /// Step 1: the owner publishes the raw wasm passed as the call's input;
+    /// everyone can see its hash before it can be deployed
+    #[payable]
+    pub fn stage_upgrade(&mut self) {
+        // CHECK: Owner only, signed with a full-access key
+        assert_owner_one_yocto(&self.owner);
+        let code = env::input().unwrap_or_else(|| env::panic_str("Missing code"));
+        let code_hash = env::sha256_array(&code);
+        env::storage_write(STAGED_CODE_KEY, &code);
+        self.staged = Some(StagedUpgrade {
+            code_hash: code_hash.into(),
+            staged_at: env::block_timestamp(),
+        });
+    }
+
+    /// Step 2: after the delay, the owner deploys exactly the staged code
+    #[payable]
+    pub fn deploy_upgrade(&mut self, code_hash: Base58CryptoHash) -> Promise {
+        assert_owner_one_yocto(&self.owner);
+        let staged = self
+            .staged
+            .take()
+            .unwrap_or_else(|| env::panic_str("Nothing staged"));
+        require!(staged.code_hash == code_hash, "Code hash mismatch");
+        require!(
+            env::block_timestamp() >= staged.staged_at.saturating_add(UPGRADE_DELAY_NS),
+            "Upgrade delay has not elapsed"
+        );
+        let code = env::storage_read(STAGED_CODE_KEY)
+            .unwrap_or_else(|| env::panic_str("Staged code missing"));
+        env::storage_remove(STAGED_CODE_KEY);
+
+        Promise::new(env::current_account_id())
+            .deploy_contract(code)
+            .function_call(
+                "migrate".to_string(),
+                vec![],
+                NearToken::from_yoctonear(0),
+                GAS_FOR_MIGRATE,
+            )
+    }
+
+    // SAFE: Only this account - i.e. the batch created by deploy_upgrade -
+    // can migrate, and nothing in the new state comes from the caller
+    #[private]
+    #[init(ignore_state)]
+    pub fn migrate() -> Self {
+        let old: Self = env::state_read().unwrap_or_else(|| env::panic_str("No state"));
+        Self {
+            owner: old.owner,
+            staged: None,
+        }
+    }
+
+    pub fn get_owner(&self) -> AccountId {
+        self.owner.clone()
+    }
+
+    pub fn get_staged_upgrade(&self) -> Option<&StagedUpgrade> {
+        self.staged.as_ref()
+    }
+}
@@ -0,0 +1,110 @@
+/*
+ * VULNERABLE NEAR CONTRACT - DO NOT USE IN PRODUCTION
+ *
+ * Storage Staking Drain
+ *
+ * On NEAR a contract must lock 1 NEAR per 100 kB of state it stores
+ * (storage staking). This handle registry lets anyone claim any number of
+ * handles with arbitrary-length metadata, and the contract pays for all of
+ * it. An attacker fills storage until the contract's balance can no longer
+ * cover its own state, after which every state-growing call fails.
+ */
+
+use near_sdk::store::LookupMap;
+use near_sdk::{env, near, require, AccountId, NearToken, PanicOnDefault};
+
+#[near(serializers = [borsh, json])]
+#[derive(Clone)]
+pub struct Handle {
+    pub owner: AccountId,
+    pub metadata: String,
+}
+
+#[near(contract_state)]
+#[derive(PanicOnDefault)]
+pub struct Registry {
+    handles: LookupMap<String, Handle>,
+    handle_count: u64,
+}
+
+#[near]
+impl Registry {
+    #[init]
+    pub fn new() -> Self {
+        Self {
+            handles: LookupMap::new(b"h"),
+            handle_count: 0,
+        }
+    }
+
+    pub fn claim_handle(&mut self, handle: String, metadata: String) {
+        require!(!self.handles.contains_key(&handle), "Handle taken");
+
+        // VULNERABILITY 1: No attached deposit covers the new storage
+        // The contract's own balance is locked for every byte written here
+
+        // VULNERABILITY 2: Handle and metadata length are unbounded
+        // A single call can write hundreds of kilobytes at the contract's cost
+
+        // VULNERABILITY 3: No per-account limit on entries
+        // One account can claim millions of handles
+        self.handles.insert(
+            handle,
+            Handle {
+                owner: env::predecessor_account_id(),
+                metadata,
+            },
+        );
+        self.handle_count += 1;
+    }
+
+    pub fn release_handle(&mut self, handle: String) {
+        let entry = self
+            .handles
+            .get(&handle)
+            .unwrap_or_else(|| env::panic_str("Unknown handle"));
+        require!(
+            entry.owner == env::predecessor_account_id(),
+            "Not the handle owner"
+        );
+
+        // Freed storage unlocks NEAR - but it returns to the contract,
+        // never to whoever originally paid (nobody did)
+        self.handles.remove(&handle);
+        self.handle_count -= 1;
+    }
+
+    pub fn get_handle(&self, handle: String) -> Option<Handle> {
+        self.handles.get(&handle).cloned()
+    }
+
+    /// NEAR currently locked to cover this contract's storage
+    pub fn storage_locked(&self) -> NearToken {
+        env::storage_byte_cost().saturating_mul(env::storage_usage().into())
+    }
+}
+
+/*
+ * SECURE VERSION (NEP-145 STORAGE MANAGEMENT):
+ *
+ * Users prepay storage with storage_deposit. claim_handle bounds the handle
+ * and metadata sizes, measures the bytes the write actually added and
+ * charges them to the caller's deposit; release_handle refunds them. The
+ * contract's own balance never pays for user data.
+ *
+ * Compiled in secure/storage_drain.rs.
+ */
+
+/*
+ * EXPLOIT SCENARIO:
+ *
+ * 1. Registry account holds 10 NEAR; its code and state lock ~2 NEAR
+ * 2. Attacker scripts claim_handle calls with ~400 kB of metadata each
+ *    (well within the transaction size limit), paying only gas
+ * 3. Every call locks ~4 NEAR more of the contract's balance
+ * 4. After two calls the contract's balance no longer covers its state:
+ *    every further write fails with LackBalanceForState
+ * 5. Honest users can no longer claim handles, and any other method that
+ *    writes state is bricked until someone tops up the account - which
+ *    the attacker can immediately drain again
+ */
@@ -0,0 +1,68 @@
+//! Token ledger with an allowance-based pull
+//!
+//! The `Bank` interface unchecked_callback.rs calls: `transfer` (one
+//! attached yoctoNEAR, like NEP-141) and `transfer_from`, which spends an
+//! allowance granted with `approve`. Both panic on insufficient funds, so a
+//! failed pull shows up as a failed promise in the caller's callback.
+
+use near_sdk::json_types::U128;
+use near_sdk::store::LookupMap;
+use near_sdk::{assert_one_yocto, env, near, require, AccountId, PanicOnDefault};
+
+#[near(contract_state)]
+#[derive(PanicOnDefault)]
+pub struct MockBank {
+    balances: LookupMap<AccountId, u128>,
+    /// (owner, spender) -> remaining allowance
+    allowances: LookupMap<(AccountId, AccountId), u128>,
+}
+
+#[near]
+impl MockBank {
+    #[init]
+    pub fn new(holders: Vec<(AccountId, U128)>) -> Self {
+        let mut balances = LookupMap::new(b"b");
+        for (account_id, amount) in holders {
+            balances.insert(account_id, amount.0);
+        }
+        Self {
+            balances,
+            allowances: LookupMap::new(b"a"),
+        }
+    }
+
+    #[payable]
+    pub fn transfer(&mut self, receiver_id: AccountId, amount: U128) {
+        assert_one_yocto();
+        self.move_tokens(&env::predecessor_account_id(), &receiver_id, amount.0);
+    }
+
+    pub fn approve(&mut self, spender_id: AccountId, amount: U128) {
+        self.allowances
+            .insert((env::predecessor_account_id(), spender_id), amount.0);
+    }
+
+    pub fn transfer_from(&mut self, owner_id: AccountId, receiver_id: AccountId, amount: U128) {
+        let key = (owner_id.clone(), env::predecessor_account_id());
+        let allowance = self.allowances.get(&key).copied().unwrap_or(0);
+        require!(allowance >= amount.0, "Insufficient allowance");
+        self.allowances.insert(key, allowance - amount.0);
+        self.move_tokens(&owner_id, &receiver_id, amount.0);
+    }
+
+    pub fn balance_of(&self, account_id: AccountId) -> U128 {
+        U128(self.balance(&account_id))
+    }
+
+    fn balance(&self, account_id: &AccountId) -> u128 {
+        self.balances.get(account_id).copied().unwrap_or(0)
+    }
+
+    fn move_tokens(&mut self, from: &AccountId, to: &AccountId, amount: u128) {
+        let from_balance = self.balance(from);
+        require!(from_balance >= amount, "Insufficient balance");
+        self.balances.insert(from.clone(), from_balance - amount);
+        let to_balance = self.balance(to);
+        self.balances.insert(to.clone(), to_balance + amount);
+    }
+}
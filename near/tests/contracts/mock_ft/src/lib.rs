@@ -0,0 +1,108 @@
+//! Minimal NEP-141 token
+//!
+//! Implements `ft_transfer`, `ft_transfer_call` and `ft_resolve_transfer`
+//! with the standard's refund semantics: whatever the receiver reports as
+//! unused - or everything, if the receiver panics - goes back to the
+//! sender. Storage registration is omitted; every account can hold tokens.
+
+use near_sdk::json_types::U128;
+use near_sdk::store::LookupMap;
+use near_sdk::{
+    assert_one_yocto, env, ext_contract, near, require, AccountId, Gas, PanicOnDefault,
+    PromiseError, PromiseOrValue,
+};
+
+const GAS_FOR_FT_ON_TRANSFER: Gas = Gas::from_tgas(50);
+const GAS_FOR_RESOLVE_TRANSFER: Gas = Gas::from_tgas(10);
+
+#[ext_contract(ext_ft_receiver)]
+pub trait FungibleTokenReceiver {
+    fn ft_on_transfer(
+        &mut self,
+        sender_id: AccountId,
+        amount: U128,
+        msg: String,
+    ) -> PromiseOrValue<U128>;
+}
+
+#[near(contract_state)]
+#[derive(PanicOnDefault)]
+pub struct MockFt {
+    balances: LookupMap<AccountId, u128>,
+}
+
+#[near]
+impl MockFt {
+    #[init]
+    pub fn new(holders: Vec<(AccountId, U128)>) -> Self {
+        let mut balances = LookupMap::new(b"b");
+        for (account_id, amount) in holders {
+            balances.insert(account_id, amount.0);
+        }
+        Self { balances }
+    }
+
+    #[payable]
+    pub fn ft_transfer(&mut self, receiver_id: AccountId, amount: U128, memo: Option<String>) {
+        assert_one_yocto();
+        let _ = memo;
+        self.move_tokens(&env::predecessor_account_id(), &receiver_id, amount.0);
+    }
+
+    #[payable]
+    pub fn ft_transfer_call(
+        &mut self,
+        receiver_id: AccountId,
+        amount: U128,
+        memo: Option<String>,
+        msg: String,
+    ) -> PromiseOrValue<U128> {
+        assert_one_yocto();
+        let _ = memo;
+        let sender_id = env::predecessor_account_id();
+        self.move_tokens(&sender_id, &receiver_id, amount.0);
+
+        ext_ft_receiver::ext(receiver_id.clone())
+            .with_static_gas(GAS_FOR_FT_ON_TRANSFER)
+            .ft_on_transfer(sender_id.clone(), amount, msg)
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_RESOLVE_TRANSFER)
+                    .ft_resolve_transfer(sender_id, receiver_id, amount),
+            )
+            .into()
+    }
+
+    /// Refunds the unused amount; returns how much the receiver kept
+    #[private]
+    pub fn ft_resolve_transfer(
+        &mut self,
+        sender_id: AccountId,
+        receiver_id: AccountId,
+        amount: U128,
+        #[callback_result] unused: Result<U128, PromiseError>,
+    ) -> U128 {
+        let unused = unused.map_or(amount.0, |unused| unused.0.min(amount.0));
+        let refund = unused.min(self.balance_of(&receiver_id));
+        if refund > 0 {
+            self.move_tokens(&receiver_id, &sender_id, refund);
+        }
+        U128(amount.0 - refund)
+    }
+
+    pub fn ft_balance_of(&self, account_id: AccountId) -> U128 {
+        U128(self.balance_of(&account_id))
+    }
+
+    fn balance_of(&self, account_id: &AccountId) -> u128 {
+        self.balances.get(account_id).copied().unwrap_or(0)
+    }
+
+    fn move_tokens(&mut self, from: &AccountId, to: &AccountId, amount: u128) {
+        let from_balance = self.balance_of(from);
+        require!(from_balance >= amount, "Insufficient balance");
+        self.balances.insert(from.clone(), from_balance - amount);
+        let to_balance = self.balance_of(to);
+        self.balances.insert(to.clone(), to_balance + amount);
+    }
+}
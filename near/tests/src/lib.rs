@@ -0,0 +1,23 @@
+//! near-workspaces sandbox suite for the vulnerable NEAR examples
+//!
+//! Every example in `near/` has a wasm crate under `contracts/` that
+//! compiles the example file as-is, a `_secure` crate that compiles its
+//! fixed counterpart from `near/secure/`, and a runnable exploit in
+//! [`exploits`] that must break the first and fail against the second.
+//! Exploits build on the shared [`Sandbox`] (a local node with funded
+//! owner / attacker / victim accounts) and the `mock_ft` and `mock_bank`
+//! contracts.
+//!
+//! ```bash
+//! rustup target add wasm32-unknown-unknown
+//! cargo install cargo-near
+//! cd near/tests
+//! cargo test
+//! ```
+
+pub mod exploits;
+pub mod sandbox;
+pub mod spec;
+
+pub use sandbox::{wasm, Sandbox, CONTRACT_BALANCE, INITIAL_BALANCE};
+pub use spec::{Attack, AttackFuture, ExploitSpec, SpecFailure};
@@ -0,0 +1,109 @@
+//! Shared sandbox node, funded accounts and cached contract builds
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, LazyLock};
+
+use anyhow::{Context, Result as AnyResult};
+use near_workspaces::network::Sandbox as SandboxNetwork;
+use near_workspaces::types::NearToken;
+use near_workspaces::{Account, AccountId, Contract, Worker};
+use serde_json::Value;
+use tokio::sync::Mutex;
+
+/// Starting balance of every named account
+pub const INITIAL_BALANCE: NearToken = NearToken::from_near(100);
+
+/// Default balance of a freshly deployed contract account, enough to
+/// cover its code and state with some liquid NEAR to steal
+pub const CONTRACT_BALANCE: NearToken = NearToken::from_near(20);
+
+/// Compiled wasm per contract crate, shared by every test in the process
+static WASM: LazyLock<Mutex<HashMap<String, Arc<Vec<u8>>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Builds `contracts/<contract>` with cargo-near, once per test run.
+///
+/// The lock also serializes builds, which would otherwise queue on the
+/// shared target directory anyway.
+pub async fn wasm(contract: &str) -> AnyResult<Arc<Vec<u8>>> {
+    let mut cache = WASM.lock().await;
+    if let Some(wasm) = cache.get(contract) {
+        return Ok(wasm.clone());
+    }
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("contracts")
+        .join(contract);
+    let path = path.to_str().context("non UTF-8 contract path")?;
+    let wasm = Arc::new(
+        near_workspaces::compile_project(path)
+            .await
+            .with_context(|| format!("building {contract}"))?,
+    );
+    cache.insert(contract.to_string(), wasm.clone());
+    Ok(wasm)
+}
+
+/// A fresh sandbox node with three funded accounts.
+///
+/// `owner` deploys and administers contracts, `victim` stands in for honest
+/// users, `attacker` runs the exploit. Each holds [`INITIAL_BALANCE`].
+pub struct Sandbox {
+    pub worker: Worker<SandboxNetwork>,
+    pub owner: Account,
+    pub attacker: Account,
+    pub victim: Account,
+}
+
+impl Sandbox {
+    pub async fn new() -> AnyResult<Self> {
+        let worker = near_workspaces::sandbox().await?;
+        let root = worker.root_account()?;
+        Ok(Self {
+            owner: create_account(&root, "owner", INITIAL_BALANCE).await?,
+            attacker: create_account(&root, "attacker", INITIAL_BALANCE).await?,
+            victim: create_account(&root, "victim", INITIAL_BALANCE).await?,
+            worker,
+        })
+    }
+
+    /// Any additional named account, funded with [`INITIAL_BALANCE`]
+    pub async fn account(&self, name: &str) -> AnyResult<Account> {
+        create_account(&self.worker.root_account()?, name, INITIAL_BALANCE).await
+    }
+
+    /// Deploys `contracts/<contract>` to a new `<name>` account holding
+    /// `balance` and calls its `new` initializer with `args`
+    pub async fn deploy(
+        &self,
+        contract: &str,
+        name: &str,
+        balance: NearToken,
+        args: Value,
+    ) -> AnyResult<Contract> {
+        let wasm = wasm(contract).await?;
+        let account = create_account(&self.worker.root_account()?, name, balance).await?;
+        let contract = account.deploy(&wasm).await?.into_result()?;
+        contract
+            .call("new")
+            .args_json(args)
+            .transact()
+            .await?
+            .into_result()
+            .with_context(|| format!("initializing {name}"))?;
+        Ok(contract)
+    }
+
+    pub async fn balance(&self, account_id: &AccountId) -> AnyResult<NearToken> {
+        Ok(self.worker.view_account(account_id).await?.balance)
+    }
+}
+
+async fn create_account(root: &Account, name: &str, balance: NearToken) -> AnyResult<Account> {
+    Ok(root
+        .create_subaccount(name)
+        .initial_balance(balance)
+        .transact()
+        .await?
+        .into_result()?)
+}
@@ -0,0 +1,94 @@
+//! Must-succeed / must-fail pairing for exploits
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+
+use anyhow::Result as AnyResult;
+
+pub type AttackFuture = Pin<Box<dyn Future<Output = AnyResult<()>>>>;
+
+/// One attack, run against a fresh [`Sandbox`](crate::Sandbox) per side.
+///
+/// An attack returns `Ok(())` only when it achieved its goal (stole funds,
+/// locked storage, took over the contract) and an error otherwise, whether
+/// because a transaction was rejected or because a profit check failed.
+pub type Attack = fn() -> AttackFuture;
+
+/// An exploit paired with the contracts it must and must not break.
+pub struct ExploitSpec {
+    /// Example file under `near/`, without the `.rs` extension
+    pub example: &'static str,
+    pub description: &'static str,
+    /// The attack against the vulnerable contract; must succeed
+    pub must_succeed: Attack,
+    /// The same attack against the example's secure version; must fail
+    pub must_fail: Attack,
+    /// How `must_fail` must fail: text in its error or any error it wraps.
+    /// Usually the fix's own rejection, the secure contract's panic
+    /// message as the `FunctionCallError` reports it; where every call
+    /// goes through and only the payoff is missing, the attack's profit
+    /// check instead. Failing any other way, e.g. on a setup mistake, does
+    /// not count.
+    pub rejection: &'static str,
+}
+
+#[derive(Debug)]
+pub enum SpecFailure {
+    /// The exploit did not work against the vulnerable contract
+    ExploitFailed(anyhow::Error),
+    /// The exploit still worked against the secure contract
+    FixBypassed,
+    /// The exploit failed against the secure contract, but not for the
+    /// reason the fix gives
+    WrongRejection {
+        expected: &'static str,
+        err: anyhow::Error,
+    },
+}
+
+impl fmt::Display for SpecFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpecFailure::ExploitFailed(err) => {
+                write!(f, "exploit failed against vulnerable contract: {err:#}")
+            }
+            SpecFailure::FixBypassed => write!(f, "exploit succeeded against secure contract"),
+            SpecFailure::WrongRejection { expected, err } => write!(
+                f,
+                "exploit failed against secure contract without {expected:?}: {err:#}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SpecFailure {}
+
+impl ExploitSpec {
+    pub async fn run(&self) -> Result<(), SpecFailure> {
+        (self.must_succeed)()
+            .await
+            .map_err(SpecFailure::ExploitFailed)?;
+        match (self.must_fail)().await {
+            Ok(()) => Err(SpecFailure::FixBypassed),
+            Err(err) if rejected_with(&err, self.rejection) => Ok(()),
+            Err(err) => Err(SpecFailure::WrongRejection {
+                expected: self.rejection,
+                err,
+            }),
+        }
+    }
+
+    /// Panics with the example name if either side of the pairing breaks
+    pub async fn assert(&self) {
+        if let Err(failure) = self.run().await {
+            panic!("{}: {failure}", self.example);
+        }
+    }
+}
+
+/// Whether `err`, or any error it wraps, says `rejection`
+fn rejected_with(err: &anyhow::Error, rejection: &str) -> bool {
+    err.chain()
+        .any(|cause| cause.to_string().contains(rejection))
+}
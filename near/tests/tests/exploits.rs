@@ -0,0 +1,98 @@
+//! Every example ships a runnable exploit and a fix it cannot break
+
+use std::fs;
+use std::path::Path;
+
+use near_exploit_tests::exploits::{find, EXPLOITS};
+
+async fn run(example: &str) {
+    find(example)
+        .unwrap_or_else(|| panic!("no exploit registered for {example}"))
+        .assert()
+        .await;
+}
+
+#[tokio::test]
+async fn account_id_validation() {
+    run("account_id_validation").await;
+}
+
+#[tokio::test]
+async fn balance_overflow() {
+    run("balance_overflow").await;
+}
+
+#[tokio::test]
+async fn ft_transfer_call_double_spend() {
+    run("ft_transfer_call_double_spend").await;
+}
+
+#[tokio::test]
+async fn missing_one_yocto() {
+    run("missing_one_yocto").await;
+}
+
+#[tokio::test]
+async fn missing_predecessor_check() {
+    run("missing_predecessor_check").await;
+}
+
+#[tokio::test]
+async fn public_callback() {
+    run("public_callback").await;
+}
+
+#[tokio::test]
+async fn storage_drain() {
+    run("storage_drain").await;
+}
+
+#[tokio::test]
+async fn unbounded_views() {
+    run("unbounded_views").await;
+}
+
+#[tokio::test]
+async fn unchecked_callback() {
+    run("unchecked_callback").await;
+}
+
+#[tokio::test]
+async fn unprotected_upgrade() {
+    run("unprotected_upgrade").await;
+}
+
+fn examples() -> Vec<String> {
+    let examples_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+    let mut examples: Vec<String> = fs::read_dir(examples_dir)
+        .unwrap()
+        .filter_map(|entry| {
+            let path = entry.unwrap().path();
+            (path.extension()? == "rs").then(|| path.file_stem()?.to_str().map(String::from))?
+        })
+        .collect();
+    examples.sort();
+    examples
+}
+
+#[test]
+fn every_example_has_an_exploit() {
+    let mut registered: Vec<String> = EXPLOITS
+        .iter()
+        .map(|spec| spec.example.to_string())
+        .collect();
+    registered.sort();
+
+    assert_eq!(examples(), registered);
+}
+
+#[test]
+fn every_example_has_a_contract_crate() {
+    let contracts_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("contracts");
+    let missing: Vec<String> = examples()
+        .into_iter()
+        .flat_map(|example| [format!("{example}_secure"), example])
+        .filter(|krate| !contracts_dir.join(krate).join("Cargo.toml").is_file())
+        .collect();
+    assert!(missing.is_empty(), "no contracts/ crate for {missing:?}");
+}
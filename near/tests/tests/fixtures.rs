@@ -0,0 +1,78 @@
+//! Sanity checks for the shared mocks
+
+use near_exploit_tests::{Sandbox, CONTRACT_BALANCE};
+use near_workspaces::types::NearToken;
+use serde_json::json;
+
+#[tokio::test]
+async fn mock_ft_refunds_unused_amount() {
+    let s = Sandbox::new().await.unwrap();
+    let token = s
+        .deploy(
+            "mock_ft",
+            "token",
+            CONTRACT_BALANCE,
+            json!({ "holders": [[s.victim.id(), "100"]] }),
+        )
+        .await
+        .unwrap();
+
+    // The receiver is a plain account: ft_on_transfer fails, so the
+    // whole amount comes back
+    s.victim
+        .call(token.id(), "ft_transfer_call")
+        .args_json(json!({ "receiver_id": s.owner.id(), "amount": "100", "msg": "" }))
+        .deposit(NearToken::from_yoctonear(1))
+        .max_gas()
+        .transact()
+        .await
+        .unwrap()
+        .into_result()
+        .unwrap();
+
+    let balance: String = token
+        .view("ft_balance_of")
+        .args_json(json!({ "account_id": s.victim.id() }))
+        .await
+        .unwrap()
+        .json()
+        .unwrap();
+    assert_eq!(balance, "100");
+}
+
+#[tokio::test]
+async fn mock_bank_requires_allowance() {
+    let s = Sandbox::new().await.unwrap();
+    let bank = s
+        .deploy(
+            "mock_bank",
+            "bank",
+            CONTRACT_BALANCE,
+            json!({ "holders": [[s.victim.id(), "100"]] }),
+        )
+        .await
+        .unwrap();
+
+    let pull = |amount: &'static str| {
+        s.attacker
+            .call(bank.id(), "transfer_from")
+            .args_json(json!({
+                "owner_id": s.victim.id(),
+                "receiver_id": s.attacker.id(),
+                "amount": amount,
+            }))
+            .transact()
+    };
+    assert!(pull("40").await.unwrap().is_failure());
+
+    s.victim
+        .call(bank.id(), "approve")
+        .args_json(json!({ "spender_id": s.attacker.id(), "amount": "40" }))
+        .transact()
+        .await
+        .unwrap()
+        .into_result()
+        .unwrap();
+    assert!(pull("40").await.unwrap().is_success());
+    assert!(pull("1").await.unwrap().is_failure());
+}
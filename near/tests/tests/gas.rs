@@ -0,0 +1,103 @@
+//! Gas of one payout call as the member list grows
+//!
+//! The vulnerable payout must grow linearly and, extrapolated, cross the
+//! per-call limit well before the exploit's member count; one page of the
+//! secure payout must cost the same at every size.
+
+use anyhow::Result as AnyResult;
+use near_exploit_tests::exploits::unbounded_views::{buy_seats, MEMBERS};
+use near_exploit_tests::{Sandbox, CONTRACT_BALANCE};
+use near_workspaces::types::{Gas, NearToken};
+use serde_json::json;
+
+const SIZES: [u32; 4] = [25, 50, 100, 200];
+/// Most gas a single function call may use
+const CALL_LIMIT: Gas = Gas::from_tgas(300);
+const PAGE: u32 = 25;
+
+/// Gas burnt by the payout receipt itself at each of SIZES members
+async fn payout_gas(contract: &str) -> AnyResult<Vec<Gas>> {
+    let s = Sandbox::new().await?;
+    let cooperative = s
+        .deploy(contract, "cooperative", CONTRACT_BALANCE, json!({}))
+        .await?;
+
+    let mut gas = Vec::new();
+    let mut members = 0;
+    for size in SIZES {
+        buy_seats(&s.owner, &cooperative, members..size).await?;
+        members = size;
+        s.owner
+            .call(cooperative.id(), "fund_dividends")
+            .deposit(NearToken::from_near(1))
+            .transact()
+            .await?
+            .into_result()?;
+
+        let outcome = s
+            .owner
+            .call(cooperative.id(), "payout")
+            .args_json(json!({ "limit": PAGE }))
+            .max_gas()
+            .transact()
+            .await?
+            .into_result()?;
+        gas.push(outcome.receipt_outcomes()[0].gas_burnt);
+
+        // The secure payout returns false until its round is complete;
+        // seats cannot be bought again before then
+        let mut done = outcome.raw_bytes()? != b"false";
+        while !done {
+            done = s
+                .owner
+                .call(cooperative.id(), "payout")
+                .args_json(json!({ "limit": PAGE }))
+                .max_gas()
+                .transact()
+                .await?
+                .json()?;
+        }
+    }
+    Ok(gas)
+}
+
+fn report(contract: &str, gas: &[Gas]) {
+    for (size, gas) in SIZES.iter().zip(gas) {
+        println!(
+            "{contract}: {size:>4} members, {:>6.2} TGas",
+            gas.as_gas() as f64 / 1e12
+        );
+    }
+}
+
+#[tokio::test]
+async fn vulnerable_payout_gas_grows_with_members() {
+    let gas = payout_gas("unbounded_views").await.unwrap();
+    report("unbounded_views", &gas);
+
+    assert!(
+        gas.windows(2).all(|pair| pair[1] > pair[0]),
+        "payout gas did not grow with the member list"
+    );
+    let first = gas[0].as_gas();
+    let last = gas[gas.len() - 1].as_gas();
+    let per_member = (last - first) / u64::from(SIZES[SIZES.len() - 1] - SIZES[0]);
+    let projected = first + per_member * u64::from(MEMBERS - SIZES[0]);
+    assert!(
+        projected > CALL_LIMIT.as_gas(),
+        "{MEMBERS} members project to only {projected} gas"
+    );
+}
+
+#[tokio::test]
+async fn secure_payout_page_gas_is_flat() {
+    let gas = payout_gas("unbounded_views_secure").await.unwrap();
+    report("unbounded_views_secure", &gas);
+
+    let min = gas.iter().min().unwrap().as_gas();
+    let max = gas.iter().max().unwrap().as_gas();
+    assert!(
+        max <= min + min / 10,
+        "page gas varies from {min} to {max} with the member count"
+    );
+}
@@ -0,0 +1,125 @@
+/*
+ * VULNERABLE NEAR CONTRACT - DO NOT USE IN PRODUCTION
+ *
+ * Unbounded Iteration (Gas DoS)
+ *
+ * A function call may burn at most 300 TGas, and a view call is capped by
+ * the RPC node as well. This cooperative pays dividends to every member of
+ * an UnorderedMap in a single call and returns the whole map from a view.
+ * Seats are cheap and anyone may buy one for any account, so the member
+ * list can be grown until payout() no longer fits in one call - after
+ * which no dividend can ever be paid again.
+ */
+
+// store::UnorderedMap is deprecated for its iteration cost - the subject
+// of this example
+#![allow(deprecated)]
+
+use near_sdk::store::UnorderedMap;
+use near_sdk::{env, near, require, AccountId, NearToken, PanicOnDefault, Promise};
+
+const SEAT_PRICE: NearToken = NearToken::from_millinear(10);
+
+#[near(contract_state)]
+#[derive(PanicOnDefault)]
+pub struct Cooperative {
+    /// Member -> seats held; dividends are paid per seat
+    members: UnorderedMap<AccountId, u32>,
+    total_seats: u32,
+    dividend_pool: NearToken,
+}
+
+#[near]
+impl Cooperative {
+    #[init]
+    pub fn new() -> Self {
+        Self {
+            members: UnorderedMap::new(b"m"),
+            total_seats: 0,
+            dividend_pool: NearToken::from_yoctonear(0),
+        }
+    }
+
+    /// Buys one seat for `account_id`, or for the caller
+    #[payable]
+    pub fn buy_seat(&mut self, account_id: Option<AccountId>) {
+        require!(
+            env::attached_deposit() == SEAT_PRICE,
+            "Attach exactly the seat price"
+        );
+        let account_id = account_id.unwrap_or_else(env::predecessor_account_id);
+
+        // VULNERABILITY 1: No bound on the number of members
+        // Every new member adds a storage read and a transfer to payout(),
+        // and the seat price is far below the gas that costs everyone else
+        let seats = self.members.get(&account_id).copied().unwrap_or(0);
+        self.members.insert(account_id, seats + 1);
+        self.total_seats += 1;
+    }
+
+    /// Revenue paid into the cooperative, shared at the next payout
+    #[payable]
+    pub fn fund_dividends(&mut self) {
+        self.dividend_pool = self.dividend_pool.saturating_add(env::attached_deposit());
+    }
+
+    /// Pays the whole dividend pool out to all members, pro rata by seats
+    pub fn payout(&mut self) {
+        require!(self.total_seats > 0, "No members");
+        let per_seat = self.dividend_pool.saturating_div(self.total_seats.into());
+
+        // VULNERABILITY 2: Iterates every member in one call
+        // Gas grows linearly with the map; once a payout needs more than
+        // 300 TGas it fails every time, and the pool is locked for good
+        let mut paid = NearToken::from_yoctonear(0);
+        for (account_id, seats) in self.members.iter() {
+            let amount = per_seat.saturating_mul((*seats).into());
+            Promise::new(account_id.clone()).transfer(amount).detach();
+            paid = paid.saturating_add(amount);
+        }
+        self.dividend_pool = self.dividend_pool.saturating_sub(paid);
+    }
+
+    // VULNERABILITY 3: Unbounded view
+    // Serializes the entire map; frontends and indexers calling it fail
+    // once it outgrows the RPC node's view gas limit
+    pub fn get_members(&self) -> Vec<(AccountId, u32)> {
+        self.members
+            .iter()
+            .map(|(account_id, seats)| (account_id.clone(), *seats))
+            .collect()
+    }
+
+    pub fn get_dividend_pool(&self) -> NearToken {
+        self.dividend_pool
+    }
+}
+
+/*
+ * SECURE VERSION (PAGINATED PAYOUT WITH A CURSOR):
+ *
+ * Members live in an IterableMap, whose entries can be reached by index
+ * in constant time. payout(limit) snapshots the per-seat dividend when a
+ * round starts, pays one bounded page per call from a stored cursor and
+ * closes the round after the last page; seats cannot change while a round
+ * is open. Anyone can advance a round, so it always completes.
+ * get_members(from_index, limit) is paginated the same way. A pull-based
+ * design (a per-seat reward index that members claim against) avoids
+ * iteration entirely and is preferable for large member sets.
+ *
+ * Compiled in secure/unbounded_views.rs.
+ */
+
+/*
+ * EXPLOIT SCENARIO:
+ *
+ * 1. Alice holds a seat; 5 NEAR of revenue sits in the dividend pool
+ * 2. Attacker batches buy_seat { account_id: "seat-<n>.near" } calls,
+ *    50 per transaction, until 2,000 members exist (20 NEAR of seats,
+ *    which stay credited to accounts the attacker controls or invents)
+ * 3. payout() now needs a storage read, a receipt and a transfer for
+ *    every member - well over 300 TGas
+ * 4. Every payout fails with "Exceeded the prepaid gas"; members cannot
+ *    be removed, so Alice's dividend and all future revenue are locked
+ * 5. get_members() fails for every frontend the same way
+ */
@@ -0,0 +1,145 @@
+/*
+ * VULNERABLE NEAR CONTRACT - DO NOT USE IN PRODUCTION
+ *
+ * Unchecked Cross-Contract Callback Result
+ *
+ * This custody contract keeps an internal ledger of tokens held on an
+ * external bank contract. Both directions commit the ledger change BEFORE
+ * the cross-contract call resolves, and the callbacks never act on the
+ * promise result. NEAR does not roll back the caller's state when a callee
+ * fails, so a failed pull still credits and a failed payout still debits.
+ */
+
+use near_sdk::json_types::U128;
+use near_sdk::store::LookupMap;
+use near_sdk::{
+    env, ext_contract, log, near, AccountId, Gas, NearToken, PanicOnDefault, Promise, PromiseError,
+};
+
+const GAS_FOR_BANK_CALL: Gas = Gas::from_tgas(10);
+const GAS_FOR_CALLBACK: Gas = Gas::from_tgas(10);
+
+/// External token ledger with an allowance-based pull
+#[ext_contract(ext_bank)]
+pub trait Bank {
+    fn transfer(&mut self, receiver_id: AccountId, amount: U128);
+    fn transfer_from(&mut self, owner_id: AccountId, receiver_id: AccountId, amount: U128);
+}
+
+#[near(contract_state)]
+#[derive(PanicOnDefault)]
+pub struct Custody {
+    bank: AccountId,
+    balances: LookupMap<AccountId, u128>,
+}
+
+#[near]
+impl Custody {
+    #[init]
+    pub fn new(bank: AccountId) -> Self {
+        Self {
+            bank,
+            balances: LookupMap::new(b"b"),
+        }
+    }
+
+    /// Pulls `amount` from the caller's bank account into custody
+    pub fn deposit(&mut self, amount: U128) -> Promise {
+        let account_id = env::predecessor_account_id();
+
+        // VULNERABILITY 1: Credit committed before the pull has happened
+        // This state change is final even if transfer_from fails below
+        let balance = self.balances.get(&account_id).copied().unwrap_or(0);
+        self.balances
+            .insert(account_id.clone(), balance.saturating_add(amount.0));
+
+        ext_bank::ext(self.bank.clone())
+            .with_static_gas(GAS_FOR_BANK_CALL)
+            .transfer_from(account_id.clone(), env::current_account_id(), amount)
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_CALLBACK)
+                    .on_deposit(account_id, amount),
+            )
+    }
+
+    /// Pays `amount` out of custody to the caller's bank account
+    pub fn withdraw(&mut self, amount: U128) -> Promise {
+        let account_id = env::predecessor_account_id();
+        let balance = self.balances.get(&account_id).copied().unwrap_or(0);
+        if balance < amount.0 {
+            env::panic_str("Insufficient balance");
+        }
+
+        // Debiting before the call is correct - but only with a rollback
+        self.balances.insert(account_id.clone(), balance - amount.0);
+
+        ext_bank::ext(self.bank.clone())
+            .with_static_gas(GAS_FOR_BANK_CALL)
+            .with_attached_deposit(NearToken::from_yoctonear(1))
+            .transfer(account_id.clone(), amount)
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_CALLBACK)
+                    .on_withdraw(account_id, amount),
+            )
+    }
+
+    #[private]
+    pub fn on_deposit(&mut self, account_id: AccountId, amount: U128) {
+        // VULNERABILITY 2: The promise result is never read
+        // A failed transfer_from (no allowance, no funds) leaves the
+        // credit from deposit() in place
+        log!("Deposit of {} for {} settled", amount.0, account_id);
+    }
+
+    #[private]
+    pub fn on_withdraw(
+        &mut self,
+        account_id: AccountId,
+        amount: U128,
+        #[callback_result] result: Result<(), PromiseError>,
+    ) {
+        // VULNERABILITY 3: Failure is detected but not rolled back
+        // The debit from withdraw() stands while the tokens never left
+        // custody - the user's funds are silently lost
+        if result.is_err() {
+            log!("Withdrawal of {} for {} failed", amount.0, account_id);
+        }
+    }
+
+    pub fn balance_of(&self, account_id: AccountId) -> U128 {
+        U128(self.balances.get(&account_id).copied().unwrap_or(0))
+    }
+}
+
+/*
+ * SECURE VERSION (CREDIT ON SUCCESS, ROLL BACK ON FAILURE):
+ *
+ * deposit credits nothing up front; on_deposit reads the promise result
+ * and credits only a successful pull. withdraw still debits first, and
+ * on_withdraw rolls the debit back when the payout failed. Both callbacks
+ * reserve their own gas so the rollback always gets to run.
+ *
+ * Compiled in secure/unchecked_callback.rs.
+ */
+
+/*
+ * EXPLOIT SCENARIO:
+ *
+ * Failed pull still credits:
+ * 1. Custody holds 1,000 tokens on the bank deposited by honest users
+ * 2. Attacker, with no bank balance and no allowance, calls
+ *    deposit { amount: 1000 }
+ * 3. deposit() credits 1,000 immediately and schedules transfer_from
+ * 4. transfer_from fails; on_deposit runs anyway and changes nothing
+ * 5. Attacker calls withdraw { amount: 1000 } and is paid the honest
+ *    users' tokens
+ *
+ * Failed payout still debits:
+ * 1. User with 500 tokens in custody calls withdraw { amount: 500 }
+ *    before registering with the bank
+ * 2. transfer fails, the tokens stay in custody
+ * 3. on_withdraw only logs: the user's ledger balance is 0 and the 500
+ *    tokens are stranded forever
+ */
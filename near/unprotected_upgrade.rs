@@ -0,0 +1,104 @@
+/*
+ * VULNERABLE NEAR CONTRACT - DO NOT USE IN PRODUCTION
+ *
+ * Unprotected Contract Upgrade
+ *
+ * A NEAR contract can redeploy its own account's code with a
+ * DeployContract action and re-initialize state in the same batch. This
+ * treasury exposes that as upgrade() without checking the caller, and its
+ * migrate() is not #[private], so anyone can swap in their own code or
+ * rewrite the owner.
+ */
+
+use near_sdk::{env, near, require, AccountId, Gas, NearToken, PanicOnDefault, Promise};
+
+const GAS_FOR_MIGRATE: Gas = Gas::from_tgas(50);
+
+#[near(contract_state)]
+#[derive(PanicOnDefault)]
+pub struct Treasury {
+    owner: AccountId,
+}
+
+#[near]
+impl Treasury {
+    #[init]
+    pub fn new(owner: AccountId) -> Self {
+        Self { owner }
+    }
+
+    #[payable]
+    pub fn fund(&mut self) {}
+
+    /// Owner-only payout - correctly checked, and irrelevant once the code
+    /// itself can be replaced
+    pub fn withdraw(&mut self, amount: NearToken) -> Promise {
+        require!(
+            env::predecessor_account_id() == self.owner,
+            "Only the owner can withdraw"
+        );
+        Promise::new(self.owner.clone()).transfer(amount)
+    }
+
+    /// Deploys the raw wasm passed as the call's input, then migrates
+    pub fn upgrade(&self) -> Promise {
+        // VULNERABILITY 1: No owner check
+        // Any account can deploy arbitrary code to this contract's account
+        let code = env::input().unwrap_or_else(|| env::panic_str("Missing code"));
+
+        // VULNERABILITY 2: Deployed immediately
+        // No review window: users cannot see the new code hash and exit
+        // before it takes effect
+        Promise::new(env::current_account_id())
+            .deploy_contract(code)
+            .function_call(
+                "migrate".to_string(),
+                near_sdk::serde_json::json!({ "owner": self.owner })
+                    .to_string()
+                    .into_bytes(),
+                NearToken::from_yoctonear(0),
+                GAS_FOR_MIGRATE,
+            )
+    }
+
+    // VULNERABILITY 3: Migration is a public initializer
+    // Missing #[private]: anyone can call migrate directly and, thanks to
+    // ignore_state, overwrite the existing state with their own owner
+    #[init(ignore_state)]
+    pub fn migrate(owner: AccountId) -> Self {
+        Self { owner }
+    }
+
+    pub fn get_owner(&self) -> AccountId {
+        self.owner.clone()
+    }
+}
+
+/*
+ * SECURE VERSION (OWNER-GATED, TWO-STEP UPGRADE):
+ *
+ * stage_upgrade stores the code and publishes its hash; deploy_upgrade
+ * deploys exactly that code after a review delay. Both are owner-only and
+ * need one yoctoNEAR. migrate is #[private] and carries the owner over from
+ * the old state. Stronger still: remove the account's full-access keys and
+ * make a multisig or DAO the owner.
+ *
+ * Compiled in secure/unprotected_upgrade.rs.
+ */
+
+/*
+ * EXPLOIT SCENARIO:
+ *
+ * Code Replacement:
+ * 1. The treasury holds 10,000 NEAR; only the owner can withdraw
+ * 2. Attacker compiles a contract whose only method sends the account's
+ *    whole balance to the attacker
+ * 3. Attacker calls upgrade with that wasm as raw input
+ * 4. The treasury's account now runs the attacker's code; one call later
+ *    the 10,000 NEAR are gone, and the code cannot be changed back
+ *
+ * State Overwrite:
+ * 1. Attacker calls migrate { owner: attacker } directly
+ * 2. ignore_state discards the existing owner
+ * 3. Attacker calls withdraw as the new owner
+ */
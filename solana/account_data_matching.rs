@@ -8,6 +8,9 @@
  */
 
 use borsh::{BorshDeserialize, BorshSerialize};
+use solana_common::clock::{ClockProvider, SysvarClock};
+use solana_common::events::{emit, WithdrawEvent};
+use solana_common::token_utils::{transfer, Authority};
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
     entrypoint,
@@ -17,26 +20,28 @@ use solana_program::{
     pubkey::Pubkey,
 };
 
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
-pub struct UserProfile {
-    pub owner: Pubkey,
-    pub escrow_account: Pubkey,
-    pub total_deposits: u64,
-}
-
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
-pub struct EscrowAccount {
-    pub beneficiary: Pubkey,
-    pub amount: u64,
-    pub release_time: i64,
-}
+// UserProfile { owner: Pubkey, escrow_account: Pubkey, total_deposits: u64 }
+// and EscrowAccount { beneficiary: Pubkey, amount: u64, release_time: i64 },
+// laid out once for every example that keeps an escrow
+pub use lab_domain::{EscrowAccount, UserProfile};
 
+#[cfg(not(feature = "no-entrypoint"))]
 entrypoint!(process_instruction);
 
 pub fn process_instruction(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
     instruction_data: &[u8],
+) -> ProgramResult {
+    process_instruction_with_clock(program_id, accounts, instruction_data, &SysvarClock)
+}
+
+/// process_instruction, with the time read from `clock`
+pub fn process_instruction_with_clock(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+    clock: &impl ClockProvider,
 ) -> ProgramResult {
     msg!("Vulnerable: Account Data Matching");
 
@@ -71,7 +76,7 @@ pub fn process_instruction(
     // Should have: if user_profile.escrow_account != *escrow_account.key { return Err(...); }
 
     // CHECK 2: Verify release time has passed
-    let current_time = 1000000_i64;  // Placeholder
+    let current_time = clock.unix_timestamp()?;
     if current_time < escrow_data.release_time {
         return Err(ProgramError::InvalidAccountData);
     }
@@ -88,6 +93,11 @@ pub fn process_instruction(
 
     **escrow_account.try_borrow_mut_lamports()? -= amount;
     **beneficiary_account.try_borrow_mut_lamports()? += amount;
+    emit(WithdrawEvent {
+        account: *escrow_account.key,
+        recipient: *beneficiary_account.key,
+        amount,
+    });
 
     Ok(())
 }
@@ -99,6 +109,7 @@ pub fn process_instruction(
  *     program_id: &Pubkey,
  *     accounts: &[AccountInfo],
  *     instruction_data: &[u8],
+ *     clock: &impl ClockProvider,
  * ) -> ProgramResult {
  *     let accounts_iter = &mut accounts.iter();
  *     let user_profile_account = next_account_info(accounts_iter)?;
@@ -133,7 +144,7 @@ pub fn process_instruction(
  *     }
  *
  *     // CHECK: Verify release time
- *     let current_time = 1000000_i64;
+ *     let current_time = clock.unix_timestamp()?;
  *     if current_time < escrow_data.release_time {
  *         return Err(ProgramError::InvalidAccountData);
  *     }
@@ -153,6 +164,8 @@ pub fn process_instruction(
  *
  *     Ok(())
  * }
+ *
+ * Compiled in secure/account_data_matching.rs.
  */
 
 /*
@@ -201,6 +214,9 @@ pub fn vulnerable_token_withdraw(
     let vault_account = next_account_info(accounts_iter)?;
     let token_account = next_account_info(accounts_iter)?;  // SPL Token account
     let authority = next_account_info(accounts_iter)?;
+    let destination = next_account_info(accounts_iter)?;
+    let vault_signer = next_account_info(accounts_iter)?;  // PDA that owns every vault's tokens
+    let token_program = next_account_info(accounts_iter)?;
 
     if !authority.is_signer {
         return Err(ProgramError::MissingRequiredSignature);
@@ -216,8 +232,19 @@ pub fn vulnerable_token_withdraw(
     // Attacker can pass different token account and steal tokens
     // Should have: if vault_data.token_account != *token_account.key { return Err(...); }
 
-    // Transfer tokens (pseudocode)
-    msg!("Would transfer tokens from {} to authority", token_account.key);
+    let amount = u64::from_le_bytes(instruction_data[0..8].try_into().unwrap());
+
+    // The vault signer owns every vault's token account, so the transfer
+    // goes through from whichever one was passed
+    let (_, bump) = Pubkey::find_program_address(&[b"token_authority"], program_id);
+    let seeds: &[&[u8]] = &[b"token_authority", &[bump]];
+    transfer(
+        token_program,
+        token_account,
+        destination,
+        Authority::Pda { account: vault_signer, seeds },
+        amount,
+    )?;
 
     Ok(())
 }
@@ -0,0 +1,286 @@
+/*
+ * VULNERABLE SOLANA PROGRAM - DO NOT USE IN PRODUCTION
+ *
+ * Account Versioning Vulnerability
+ *
+ * The second release of this program added a delegate to every user
+ * account, between the owner and the balance, but its withdrawal still
+ * reads the first release's layout. Nothing in the data says which
+ * layout it holds, so a new account is read as an old one, and the
+ * delegate's first eight bytes become the balance.
+ */
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_common::account::grow_account;
+use solana_common::deserialize::lenient_deserialize;
+use solana_common::events::{emit, WithdrawEvent};
+use solana_common::pda::{find_and_validate, PdaSpec};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+
+/// The program's one vault, which holds every user's lamports
+pub const VAULT: PdaSpec<0> = PdaSpec::new(b"vault", []);
+
+/// A user account as the first release wrote it
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct UserAccountV1 {
+    pub owner: Pubkey,
+    pub balance: u64,
+}
+
+impl UserAccountV1 {
+    pub const LEN: usize = 32 + 8;
+}
+
+/// A user account as the second release writes it
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct UserAccountV2 {
+    pub owner: Pubkey,
+    // New in the second release, and placed before the balance
+    pub delegate: Pubkey,
+    pub balance: u64,
+}
+
+impl UserAccountV2 {
+    pub const LEN: usize = 32 + 32 + 8;
+}
+
+#[cfg(not(feature = "no-entrypoint"))]
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let (instruction, rest) = instruction_data
+        .split_first()
+        .ok_or(ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        0 => open(program_id, accounts, rest),
+        1 => withdraw(program_id, accounts, rest),
+        2 => migrate(program_id, accounts),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+/// Writes a new user account, in the second release's layout
+pub fn open(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let user = next_account_info(accounts_iter)?;
+    let user_account = next_account_info(accounts_iter)?;
+
+    if !user.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if user_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    if user_account.data.borrow().iter().any(|byte| *byte != 0) {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    let delegate = Pubkey::try_from(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    // VULNERABILITY 1: No version byte; the data does not say which layout it holds
+    let account = UserAccountV2 {
+        owner: *user.key,
+        delegate,
+        balance: 0,
+    };
+    account.serialize(&mut &mut user_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+/// Pays `amount` of the user's balance out of the vault
+pub fn withdraw(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    msg!("Vulnerable: Withdraw (reads every account as v1)");
+
+    let accounts_iter = &mut accounts.iter();
+    let user = next_account_info(accounts_iter)?;
+    let user_account = next_account_info(accounts_iter)?;
+    let vault = next_account_info(accounts_iter)?;
+
+    if !user.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if user_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    find_and_validate(&VAULT.seeds([]), program_id, vault.key)?;
+
+    // VULNERABILITY 2: Reads the first release's layout from every account. A v2
+    // account's delegate sits where v1 kept the balance, and the v2 fields
+    // after it are ignored
+    let mut account = lenient_deserialize::<UserAccountV1>(&user_account.data.borrow())?;
+
+    if account.owner != *user.key {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let amount = instruction_data
+        .get(..8)
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(u64::from_le_bytes)
+        .ok_or(ProgramError::InvalidInstructionData)?;
+
+    if account.balance < amount {
+        return Err(ProgramError::InsufficientFunds);
+    }
+
+    // VULNERABILITY 3: Writes v1 back over a v2 account, corrupting its delegate
+    account.balance -= amount;
+    account.serialize(&mut &mut user_account.data.borrow_mut()[..])?;
+
+    **vault.try_borrow_mut_lamports()? -= amount;
+    **user.try_borrow_mut_lamports()? += amount;
+    emit(WithdrawEvent {
+        account: *user_account.key,
+        recipient: *user.key,
+        amount,
+    });
+
+    Ok(())
+}
+
+/// Moves a first-release account to the second release's layout
+pub fn migrate(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let user = next_account_info(accounts_iter)?;
+    let user_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !user.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if user_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    // VULNERABILITY 4: Nothing says the account is v1; a v2 account is migrated again
+    let old = lenient_deserialize::<UserAccountV1>(&user_account.data.borrow())?;
+    if old.owner != *user.key {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    grow_account(user_account, user, system_program, UserAccountV2::LEN, &Rent::get()?)?;
+    let account = UserAccountV2 {
+        owner: old.owner,
+        delegate: Pubkey::default(),
+        balance: old.balance,
+    };
+    account.serialize(&mut &mut user_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+/*
+ * SECURE VERSION:
+ *
+ * use solana_common::versioning::{
+ *     migrate_in_place, read_versioned, version, write_versioned, Versioned,
+ * };
+ *
+ * // VERSION EVERY LAYOUT: one byte ahead of the fields names the layout
+ * impl Versioned for UserAccountV1 {
+ *     const VERSION: u8 = 1;
+ * }
+ *
+ * impl Versioned for UserAccountV2 {
+ *     const VERSION: u8 = 2;
+ * }
+ *
+ * pub fn withdraw_secure(
+ *     program_id: &Pubkey,
+ *     accounts: &[AccountInfo],
+ *     instruction_data: &[u8],
+ * ) -> ProgramResult {
+ *     ...
+ *     let mut data = user_account.data.borrow_mut();
+ *     // CHECK: Read the layout the account says it holds, and only that
+ *     match version(&data)? {
+ *         UserAccountV1::VERSION => {
+ *             let mut account = read_versioned::<UserAccountV1>(&data)?;
+ *             assert_key_eq(user, &account.owner)?;
+ *             account.balance = account
+ *                 .balance
+ *                 .checked_sub(amount)
+ *                 .ok_or(ProgramError::InsufficientFunds)?;
+ *             write_versioned(&account, &mut data)?;
+ *         }
+ *         UserAccountV2::VERSION => {
+ *             let mut account = read_versioned::<UserAccountV2>(&data)?;
+ *             assert_key_eq(user, &account.owner)?;
+ *             account.balance = account
+ *                 .balance
+ *                 .checked_sub(amount)
+ *                 .ok_or(ProgramError::InsufficientFunds)?;
+ *             write_versioned(&account, &mut data)?;
+ *         }
+ *         _ => return Err(StateError::WrongVersion.into()),
+ *     }
+ *     ...
+ * }
+ *
+ * // Migration reads a v1 account, and only a v1 account, and rewrites it
+ * // as v2 behind the new version byte, growing it at the user's expense
+ * migrate_in_place::<UserAccountV1, UserAccountV2>(
+ *     user_account,
+ *     user,
+ *     system_program,
+ *     &Rent::get()?,
+ *     |old| {
+ *         assert_key_eq(user, &old.owner)?;
+ *         Ok(UserAccountV2 { owner: old.owner, delegate: Pubkey::default(), balance: old.balance })
+ *     },
+ * )?;
+ *
+ * Compiled in secure/account_versioning.rs.
+ */
+
+/*
+ * EXPLOIT SCENARIO:
+ *
+ * 1. Attacker opens a new user account with a delegate whose first eight
+ *    bytes are 0xff; no one needs the delegate's private key
+ * 2. The program writes it in the v2 layout:
+ *    - owner: ATTACKER
+ *    - delegate: ff ff ff ff ff ff ff ff ...
+ *    - balance: 0
+ *
+ * 3. Attacker withdraws everything in the vault
+ * 4. Program reads the account as v1:
+ *    - owner: ATTACKER ✓
+ *    - balance: u64::MAX (actually the delegate's first eight bytes!)
+ *
+ * 5. Check passes: balance >= amount ✓
+ * 6. Every user's lamports leave the vault for the attacker
+ */
+
+/*
+ * REAL-WORLD EXAMPLES:
+ *
+ * - Upgradeable programs outlive their layouts; a release that inserts a
+ *   field must still read, or migrate, every account the last one wrote
+ * - Anchor's 8-byte discriminator names the type, not its version, so a
+ *   changed struct under the same name reads old data just as wrongly
+ */
@@ -18,6 +18,7 @@ use solana_program::{
     pubkey::Pubkey,
 };
 
+#[cfg(not(feature = "no-entrypoint"))]
 entrypoint!(process_instruction);
 
 pub fn process_instruction(
@@ -110,6 +111,8 @@ pub fn process_instruction(
  *
  *     Ok(())
  * }
+ *
+ * Compiled in secure/arbitrary_cpi.rs.
  */
 
 /*
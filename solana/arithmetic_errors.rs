@@ -8,6 +8,8 @@
  */
 
 use borsh::{BorshDeserialize, BorshSerialize};
+use solana_common::clock::{ClockProvider, SysvarClock};
+use solana_common::events::{emit, DepositEvent};
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
     entrypoint,
@@ -17,31 +19,34 @@ use solana_program::{
     pubkey::Pubkey,
 };
 
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
-pub struct StakingPool {
-    pub total_staked: u64,
-    pub reward_rate: u64,  // Rewards per second
-    pub last_update: i64,
-}
-
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
-pub struct UserStake {
-    pub amount: u64,
-    pub last_claim: i64,
-}
+// StakingPool { total_staked: u64, reward_rate: u64, last_update: i64 } and
+// UserStake { amount: u64, last_claim: i64 }, laid out once for every
+// example that stakes
+pub use lab_domain::{StakingPool, UserStake};
 
+#[cfg(not(feature = "no-entrypoint"))]
 entrypoint!(process_instruction);
 
 pub fn process_instruction(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
     instruction_data: &[u8],
+) -> ProgramResult {
+    process_instruction_with_clock(program_id, accounts, instruction_data, &SysvarClock)
+}
+
+/// process_instruction, with the time read from `clock`
+pub fn process_instruction_with_clock(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+    clock: &impl ClockProvider,
 ) -> ProgramResult {
     let instruction = instruction_data[0];
 
     match instruction {
         0 => stake(program_id, accounts, &instruction_data[1..]),
-        1 => calculate_rewards(program_id, accounts, &instruction_data[1..]),
+        1 => calculate_rewards(program_id, accounts, &instruction_data[1..], clock),
         2 => vulnerable_transfer(program_id, accounts, &instruction_data[1..]),
         _ => Err(ProgramError::InvalidInstructionData),
     }
@@ -80,6 +85,12 @@ pub fn stake(
 
     msg!("Staked {} tokens", amount);
 
+    emit(DepositEvent {
+        account: *pool_account.key,
+        depositor: *user_account.key,
+        amount,
+    });
+
     Ok(())
 }
 
@@ -87,6 +98,7 @@ pub fn calculate_rewards(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
     _instruction_data: &[u8],
+    clock: &impl ClockProvider,
 ) -> ProgramResult {
     msg!("Vulnerable: Arithmetic Precision Loss");
 
@@ -103,8 +115,7 @@ pub fn calculate_rewards(
     let pool_data = StakingPool::try_from_slice(&pool_account.data.borrow())?;
     let user_data = UserStake::try_from_slice(&user_stake_account.data.borrow())?;
 
-    // Get current timestamp (simplified - normally use Clock sysvar)
-    let current_time = 1000000_i64;  // Placeholder
+    let current_time = clock.unix_timestamp()?;
 
     // VULNERABILITY 3: Unchecked subtraction can underflow
     let time_elapsed = current_time - user_data.last_claim;  // Should use checked_sub()
@@ -195,6 +206,7 @@ pub fn vulnerable_transfer(
  *     program_id: &Pubkey,
  *     accounts: &[AccountInfo],
  *     _instruction_data: &[u8],
+ *     clock: &impl ClockProvider,
  * ) -> ProgramResult {
  *     let accounts_iter = &mut accounts.iter();
  *     let pool_account = next_account_info(accounts_iter)?;
@@ -208,7 +220,7 @@ pub fn vulnerable_transfer(
  *     let pool_data = StakingPool::try_from_slice(&pool_account.data.borrow())?;
  *     let user_data = UserStake::try_from_slice(&user_stake_account.data.borrow())?;
  *
- *     let current_time = 1000000_i64;
+ *     let current_time = clock.unix_timestamp()?;
  *
  *     // SAFE: Checked subtraction
  *     let time_elapsed = current_time
@@ -264,6 +276,8 @@ pub fn vulnerable_transfer(
  *
  *     Ok(())
  * }
+ *
+ * Compiled in secure/arithmetic_errors.rs.
  */
 
 /*
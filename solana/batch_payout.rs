@@ -0,0 +1,272 @@
+/*
+ * VULNERABLE SOLANA PROGRAM - DO NOT USE IN PRODUCTION
+ *
+ * Batch Payout Denial of Service
+ *
+ * This program pays every registered recipient in one instruction, so the
+ * transaction that pays them must lock every recipient's account. Anyone
+ * can register, and nothing bounds the list by what one transaction can
+ * carry, so an attacker who registers enough addresses of their own makes
+ * the payout impossible to send.
+ */
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct Payroll {
+    pub authority: Pubkey,
+    /// Lamports each recipient is paid per payout
+    pub amount: u64,
+    /// In the order they registered; the account grows with the list
+    pub recipients: Vec<Pubkey>,
+}
+
+#[cfg(not(feature = "no-entrypoint"))]
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = instruction_data[0];
+
+    match instruction {
+        0 => initialize(program_id, accounts, &instruction_data[1..]),
+        1 => register(program_id, accounts, &instruction_data[1..]),
+        2 => distribute(program_id, accounts, &instruction_data[1..]),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+pub fn initialize(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let payroll_account = next_account_info(accounts_iter)?;
+    let authority = next_account_info(accounts_iter)?;
+
+    if payroll_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let existing = Payroll::try_from_slice(&payroll_account.data.borrow())?;
+    if existing.authority != Pubkey::default() {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    let amount = instruction_data
+        .get(..8)
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(u64::from_le_bytes)
+        .ok_or(ProgramError::InvalidInstructionData)?;
+
+    let payroll = Payroll {
+        authority: *authority.key,
+        amount,
+        recipients: Vec::new(),
+    };
+
+    payroll.serialize(&mut &mut payroll_account.data.borrow_mut()[..])?;
+
+    msg!("Payroll initialized: {} lamports per recipient", amount);
+
+    Ok(())
+}
+
+pub fn register(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    _instruction_data: &[u8],
+) -> ProgramResult {
+    msg!("Vulnerable: Unbounded Open Registration");
+
+    let accounts_iter = &mut accounts.iter();
+    let payroll_account = next_account_info(accounts_iter)?;
+    let recipient = next_account_info(accounts_iter)?;
+    let _approver = next_account_info(accounts_iter)?;
+
+    if payroll_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    if !recipient.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut payroll = Payroll::try_from_slice(&payroll_account.data.borrow())?;
+
+    // VULNERABILITY: Anyone can register, as many times over as they have keypairs,
+    // and nothing bounds the list by what distribute can pay at once
+    // Should require the authority's approval:
+    //   if !approver.is_signer || *approver.key != payroll.authority { ... }
+    payroll.recipients.push(*recipient.key);
+
+    payroll_account.resize(borsh::object_length(&payroll)?)?;
+    payroll.serialize(&mut &mut payroll_account.data.borrow_mut()[..])?;
+
+    msg!("Registered recipient {}", payroll.recipients.len());
+
+    Ok(())
+}
+
+pub fn distribute(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    _instruction_data: &[u8],
+) -> ProgramResult {
+    msg!("Vulnerable: Paying Every Recipient At Once");
+
+    let accounts_iter = &mut accounts.iter();
+    let payroll_account = next_account_info(accounts_iter)?;
+    let authority = next_account_info(accounts_iter)?;
+    let recipient_accounts = accounts_iter.as_slice();
+
+    if payroll_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let payroll = Payroll::try_from_slice(&payroll_account.data.borrow())?;
+
+    if payroll.authority != *authority.key {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    // VULNERABILITY: Every registered recipient, in one instruction. The transaction
+    // must lock all of them, and a transaction locks at most 64 accounts;
+    // with the payroll, the authority and this program, 61 recipients is
+    // the most it can ever pay, and one more registration bricks it
+    // Should pay a bounded page and remember where it stopped:
+    //   let page = &payroll.recipients[payroll.cursor..][..recipient_accounts.len()];
+    //   if recipient_accounts.len() > MAX_PAGE { ... }
+    let registered = &payroll.recipients;
+    if recipient_accounts.len() != registered.len()
+        || recipient_accounts
+            .iter()
+            .zip(registered)
+            .any(|(account, key)| account.key != key)
+    {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let total = payroll
+        .amount
+        .checked_mul(registered.len() as u64)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    let min_balance = Rent::get()?.minimum_balance(payroll_account.data_len());
+    if payroll_account.lamports().saturating_sub(min_balance) < total {
+        return Err(ProgramError::InsufficientFunds);
+    }
+
+    **payroll_account.try_borrow_mut_lamports()? -= total;
+    for recipient in recipient_accounts {
+        **recipient.try_borrow_mut_lamports()? += payroll.amount;
+    }
+
+    msg!("Paid {} recipients {} lamports each", registered.len(), payroll.amount);
+
+    Ok(())
+}
+
+/*
+ * SECURE VERSION:
+ *
+ * pub fn register_secure(
+ *     program_id: &Pubkey,
+ *     accounts: &[AccountInfo],
+ *     _instruction_data: &[u8],
+ * ) -> ProgramResult {
+ *     ...
+ *     // CHECK: The authority approves every recipient
+ *     if !approver.is_signer {
+ *         return Err(ProgramError::MissingRequiredSignature);
+ *     }
+ *     if payroll.authority != *approver.key {
+ *         return Err(ProgramError::InvalidAccountData);
+ *     }
+ *     ...
+ * }
+ *
+ * pub fn distribute_secure(
+ *     program_id: &Pubkey,
+ *     accounts: &[AccountInfo],
+ *     _instruction_data: &[u8],
+ * ) -> ProgramResult {
+ *     ...
+ *     // SAFE: A page of at most MAX_PAGE recipients, starting where the
+ *     // last page stopped; the round ends when the cursor wraps
+ *     if recipient_accounts.is_empty() || recipient_accounts.len() > MAX_PAGE {
+ *         return Err(ProgramError::InvalidArgument);
+ *     }
+ *     let start = payroll.cursor as usize;
+ *     let end = start + recipient_accounts.len();
+ *     let page = payroll
+ *         .recipients
+ *         .get(start..end)
+ *         .ok_or(ProgramError::InvalidArgument)?;
+ *     ...
+ *     payroll.cursor = (end % payroll.recipients.len()) as u32;
+ *     ...
+ * }
+ *
+ * Compiled in secure/batch_payout.rs.
+ */
+
+/*
+ * WHY A TRANSACTION CANNOT NAME EVERY ACCOUNT:
+ *
+ * - A transaction must fit in one 1232-byte packet; a legacy transaction
+ *   spends 32 bytes per account, so about 35 accounts fill it
+ * - A v0 transaction names accounts held in an address lookup table by a
+ *   one-byte index, so far more fit in the packet
+ * - Either way, a transaction locks at most 64 accounts, the program and
+ *   signers included; no table gets around that
+ * - An instruction that must see every account in an unbounded list
+ *   stops working once the list outgrows those limits
+ */
+
+/*
+ * EXPLOIT SCENARIO:
+ *
+ * 1. A payroll pays 40 recipients; the authority pays them in one v0
+ *    transaction, through a lookup table, as a legacy one is too big
+ *
+ * 2. Attacker registers 22 addresses of their own, one recipient each
+ *
+ * 3. Paying 62 recipients takes 65 accounts: the payroll, the authority,
+ *    the program and the recipients
+ *
+ * 4. The runtime refuses any transaction that locks more than 64
+ *
+ * 5. Distribute can never run again, and no instruction removes a
+ *    recipient; the payroll's lamports are stuck and nobody is paid
+ */
+
+/*
+ * REAL-WORLD IMPACT:
+ *
+ * - Reward and airdrop distributors frozen by sybil sign-ups
+ * - Funds locked in a program that can no longer send its only payout
+ * - Liquidations, settlements and crank instructions that walk a list
+ *   an attacker can grow, halted the same way
+ */
@@ -0,0 +1,220 @@
+/*
+ * CTF VERIFIER FOR THE SOLANA EXAMPLES
+ *
+ * Not an example: this program keeps score. Each challenge gets a flag, a
+ * PDA of this program seeded with the vulnerable program's id, that names
+ * an account and a goal for it (drain it, take it over, wrap a counter in
+ * it, get it purged, grow a list in it past what can be processed). Capture checks the goal against the account as it is
+ * now and records every signer it passes for.
+ */
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+use solana_system_interface::instruction as system_instruction;
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+pub const FLAG_SEED: &[u8] = b"flag";
+/// Solvers a flag records before it is full
+pub const MAX_SOLVERS: usize = 16;
+/// target, the largest goal, baseline, then the solvers vector
+pub const FLAG_SPACE: usize = 32 + 41 + 8 + 4 + 32 * MAX_SOLVERS;
+
+/// What capturing a flag takes
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Goal {
+    /// The account lost at least `amount` lamports since the flag was planted
+    Drain { account: Pubkey, amount: u64 },
+    /// The 32 bytes at `offset` in the account's data are the solver's key
+    Authority { account: Pubkey, offset: u32 },
+    /// The little-endian u64 at `offset` in the account's data is lower
+    /// than when the flag was planted
+    Decrease { account: Pubkey, offset: u32 },
+    /// The account no longer exists
+    Close { account: Pubkey },
+    /// The little-endian u32 at `offset` in the account's data is above
+    /// `limit`
+    Exceed {
+        account: Pubkey,
+        offset: u32,
+        limit: u32,
+    },
+}
+
+impl Goal {
+    /// The account the goal is about
+    pub fn account(&self) -> &Pubkey {
+        match self {
+            Goal::Drain { account, .. }
+            | Goal::Authority { account, .. }
+            | Goal::Decrease { account, .. }
+            | Goal::Close { account }
+            | Goal::Exceed { account, .. } => account,
+        }
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Eq)]
+pub struct Flag {
+    /// The vulnerable program this flag is for
+    pub target: Pubkey,
+    pub goal: Goal,
+    /// The account's lamports (Drain) or the u64 at the offset (Decrease)
+    /// when the flag was planted
+    pub baseline: u64,
+    pub solvers: Vec<Pubkey>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum VerifierInstruction {
+    /// Accounts: flag (writable), payer (writable, signer), the goal's
+    /// account, system program
+    Plant { target: Pubkey, goal: Goal },
+    /// Accounts: flag (writable), solver (signer), the goal's account
+    Capture,
+}
+
+/// Custom error codes
+pub const NOT_CAPTURED: u32 = 0;
+pub const SOLVERS_FULL: u32 = 1;
+
+pub fn flag_address(target: &Pubkey, verifier: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[FLAG_SEED, target.as_ref()], verifier)
+}
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    match VerifierInstruction::try_from_slice(instruction_data)? {
+        VerifierInstruction::Plant { target, goal } => plant(program_id, accounts, target, goal),
+        VerifierInstruction::Capture => capture(program_id, accounts),
+    }
+}
+
+fn plant(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    target: Pubkey,
+    goal: Goal,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let flag_account = next_account_info(accounts_iter)?;
+    let payer = next_account_info(accounts_iter)?;
+    let watched = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let (address, bump) = flag_address(&target, program_id);
+    if *flag_account.key != address {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    if flag_account.lamports() != 0 {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+    if watched.key != goal.account() {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let baseline = match goal {
+        Goal::Drain { .. } => watched.lamports(),
+        Goal::Decrease { offset, .. } => {
+            read_u64(&watched.data.borrow(), offset).ok_or(ProgramError::InvalidAccountData)?
+        }
+        Goal::Authority { .. } | Goal::Close { .. } | Goal::Exceed { .. } => 0,
+    };
+
+    invoke_signed(
+        &system_instruction::create_account(
+            payer.key,
+            flag_account.key,
+            Rent::get()?.minimum_balance(FLAG_SPACE),
+            FLAG_SPACE as u64,
+            program_id,
+        ),
+        &[payer.clone(), flag_account.clone(), system_program.clone()],
+        &[&[FLAG_SEED, target.as_ref(), &[bump]]],
+    )?;
+
+    let flag = Flag {
+        target,
+        goal,
+        baseline,
+        solvers: Vec::new(),
+    };
+    flag.serialize(&mut &mut flag_account.data.borrow_mut()[..])?;
+    msg!("Flag planted for {}", target);
+    Ok(())
+}
+
+fn capture(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let flag_account = next_account_info(accounts_iter)?;
+    let solver = next_account_info(accounts_iter)?;
+    let watched = next_account_info(accounts_iter)?;
+
+    if flag_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    if !solver.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let mut flag = Flag::deserialize(&mut &flag_account.data.borrow()[..])?;
+    if watched.key != flag.goal.account() {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let captured = match flag.goal {
+        Goal::Drain { amount, .. } => flag.baseline.saturating_sub(watched.lamports()) >= amount,
+        Goal::Authority { offset, .. } => {
+            let offset = offset as usize;
+            watched.data.borrow().get(offset..offset + 32) == Some(solver.key.as_ref())
+        }
+        Goal::Decrease { offset, .. } => {
+            read_u64(&watched.data.borrow(), offset).is_some_and(|value| value < flag.baseline)
+        }
+        Goal::Close { .. } => watched.lamports() == 0 && watched.data_is_empty(),
+        Goal::Exceed { offset, limit, .. } => {
+            read_u32(&watched.data.borrow(), offset).is_some_and(|value| value > limit)
+        }
+    };
+    if !captured {
+        msg!("Flag for {} not captured", flag.target);
+        return Err(ProgramError::Custom(NOT_CAPTURED));
+    }
+
+    if !flag.solvers.contains(solver.key) {
+        if flag.solvers.len() == MAX_SOLVERS {
+            return Err(ProgramError::Custom(SOLVERS_FULL));
+        }
+        flag.solvers.push(*solver.key);
+        flag.serialize(&mut &mut flag_account.data.borrow_mut()[..])?;
+    }
+    msg!("Flag for {} captured by {}", flag.target, solver.key);
+    Ok(())
+}
+
+fn read_u64(data: &[u8], offset: u32) -> Option<u64> {
+    let offset = offset as usize;
+    let bytes = data.get(offset..offset + 8)?;
+    Some(u64::from_le_bytes(bytes.try_into().ok()?))
+}
+
+fn read_u32(data: &[u8], offset: u32) -> Option<u32> {
+    let offset = offset as usize;
+    let bytes = data.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes(bytes.try_into().ok()?))
+}
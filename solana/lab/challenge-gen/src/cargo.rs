@@ -0,0 +1,188 @@
+//! Compiling variants with `cargo check`
+//!
+//! Each variant becomes a crate in a scratch workspace with the
+//! dependencies of its example's `_secure` program crate and the lab's
+//! `Cargo.lock`, so it builds against exactly what the examples do. The
+//! scratch workspace shares a target directory under the lab's, so only
+//! the first run compiles `solana-program`.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use anyhow::{bail, Context, Result as AnyResult};
+use serde_json::Value;
+use toml::{Table, Value as Toml};
+
+/// A variant to compile
+pub struct Crate<'a> {
+    /// Also the crate's name, so it must be a valid one
+    pub name: &'a str,
+    pub example: &'a str,
+    pub source: &'a Path,
+}
+
+/// A variant's manifest, before its name, source and dependencies
+const TEMPLATE: &str = r#"
+[package]
+version = "0.1.0"
+edition = "2021"
+publish = false
+
+[features]
+no-entrypoint = []
+custom-heap = []
+custom-panic = []
+
+# Removing a check leaves names unused
+[lints.rust]
+unused = "allow"
+unexpected_cfgs = "allow"
+"#;
+
+pub fn lab_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("..")
+}
+
+/// Runs `cargo check` over every crate in a scratch workspace under `dir`
+/// and returns the names of those that failed to compile
+pub fn check(dir: &Path, crates: &[Crate]) -> AnyResult<Vec<String>> {
+    let workspace = dir.join(".check");
+    if workspace.exists() {
+        fs::remove_dir_all(&workspace)
+            .with_context(|| format!("clearing {}", workspace.display()))?;
+    }
+    fs::create_dir_all(&workspace)?;
+
+    let lab = lab_dir().canonicalize()?;
+    let lab_manifest = read_toml(&lab.join("Cargo.toml"))?;
+    let shared = lab_manifest
+        .get("workspace")
+        .and_then(|workspace| workspace.get("dependencies"))
+        .and_then(Toml::as_table)
+        .cloned()
+        .unwrap_or_default();
+
+    let mut members = Vec::new();
+    for krate in crates {
+        let program = lab
+            .join("programs")
+            .join(format!("{}_secure", krate.example))
+            .join("Cargo.toml");
+        let program = read_toml(&program)?;
+        let mut dependencies = Table::new();
+        for (name, spec) in program
+            .get("dependencies")
+            .and_then(Toml::as_table)
+            .into_iter()
+            .flatten()
+        {
+            dependencies.insert(name.clone(), resolve(name, spec, &shared, &lab)?);
+        }
+
+        let source = krate
+            .source
+            .canonicalize()
+            .with_context(|| format!("{} does not exist", krate.source.display()))?;
+        let mut manifest: Table = TEMPLATE.parse()?;
+        manifest["package"]
+            .as_table_mut()
+            .expect("package is a table")
+            .insert("name".into(), krate.name.into());
+        manifest.insert(
+            "lib".into(),
+            Toml::Table(Table::from_iter([(
+                "path".into(),
+                source.display().to_string().into(),
+            )])),
+        );
+        manifest.insert("dependencies".into(), Toml::Table(dependencies));
+        let member = workspace.join(krate.name);
+        fs::create_dir_all(&member)?;
+        fs::write(member.join("Cargo.toml"), toml::to_string(&manifest)?)?;
+        members.push(Toml::from(krate.name));
+    }
+
+    fs::write(
+        workspace.join("Cargo.toml"),
+        format!(
+            "[workspace]\nresolver = \"2\"\nmembers = {}\n",
+            Toml::Array(members)
+        ),
+    )?;
+    let lock = lab.join("Cargo.lock");
+    if lock.is_file() {
+        fs::copy(&lock, workspace.join("Cargo.lock"))?;
+    }
+
+    let output = Command::new(std::env::var("CARGO").unwrap_or_else(|_| "cargo".into()))
+        .args(["check", "--workspace", "--message-format", "json"])
+        .current_dir(&workspace)
+        .env("CARGO_TARGET_DIR", lab.join("target").join("challenge-gen"))
+        .stderr(Stdio::piped())
+        .output()
+        .context("running cargo check")?;
+
+    let mut failed = BTreeSet::new();
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let Ok(message) = serde_json::from_str::<Value>(line) else {
+            continue;
+        };
+        if message["reason"] == "compiler-message" && message["message"]["level"] == "error" {
+            if let Some(target) = message["target"]["name"].as_str() {
+                failed.insert(target.replace('_', "-"));
+            }
+        }
+    }
+    if !output.status.success() && failed.is_empty() {
+        bail!(
+            "cargo check failed before compiling any variant:\n{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    Ok(crates
+        .iter()
+        .filter(|krate| failed.contains(&krate.name.replace('_', "-")))
+        .map(|krate| krate.name.to_string())
+        .collect())
+}
+
+fn read_toml(path: &Path) -> AnyResult<Table> {
+    let text = fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    text.parse()
+        .with_context(|| format!("parsing {}", path.display()))
+}
+
+/// A dependency as the scratch workspace has to spell it: `workspace =
+/// true` replaced by the lab's entry, and paths made absolute
+fn resolve(name: &str, spec: &Toml, shared: &Table, lab: &Path) -> AnyResult<Toml> {
+    let inherits = spec
+        .get("workspace")
+        .and_then(Toml::as_bool)
+        .unwrap_or(false);
+    if !inherits {
+        return Ok(spec.clone());
+    }
+    let mut resolved = match shared.get(name) {
+        Some(Toml::String(version)) => {
+            Table::from_iter([("version".into(), version.clone().into())])
+        }
+        Some(Toml::Table(table)) => table.clone(),
+        _ => bail!("{name} is not in the lab's [workspace.dependencies]"),
+    };
+    if let Some(Toml::Array(features)) = spec.get("features") {
+        let mut all = resolved
+            .get("features")
+            .and_then(Toml::as_array)
+            .cloned()
+            .unwrap_or_default();
+        all.extend(features.iter().cloned());
+        resolved.insert("features".into(), Toml::Array(all));
+    }
+    if let Some(Toml::String(path)) = resolved.get("path") {
+        let path = lab.join(path).display().to_string();
+        resolved.insert("path".into(), path.into());
+    }
+    Ok(Toml::Table(resolved))
+}
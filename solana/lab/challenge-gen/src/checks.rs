@@ -0,0 +1,381 @@
+//! The checks a secure program makes, and taking one of them out
+//!
+//! Four shapes count as a check:
+//!
+//! - a guard: `if cond { ...; return Err(..); }` with no `else`
+//! - an assertion: `assert_signer(account)?;` or another of
+//!   `solana_common::validation`'s helpers, or one of `solana_common::pda`'s
+//!   checks, such as `find_and_validate(&seeds, program_id, key)?;`
+//! - checked arithmetic: `a.checked_add(b).ok_or(..)?`, which becomes
+//!   `a + b` when removed, and `solana_common::safe_math`'s
+//!   `(CheckedU64(a) + b)?` and `mul_div_floor(a, b, c)?`, which become
+//!   `CheckedU64(a + b)` and `a * b / c`
+//! - a checked read: `T::try_deserialize_checked(&data)`, which becomes
+//!   `T::try_from_slice(&data[8..])`, skipping the discriminator unread,
+//!   and `load::<T>(account, program_id)`, which becomes the same read of
+//!   `account` with its owner unchecked too
+
+use proc_macro2::Span;
+use quote::ToTokens;
+use serde::{Deserialize, Serialize};
+use syn::visit_mut::{self, VisitMut};
+use syn::{
+    parse_quote, BinOp, Block, Expr, ExprBinary, ExprCall, ExprMethodCall, File, GenericArgument,
+    ImplItemFn, ItemFn, PathArguments, Stmt,
+};
+
+/// Stands in for the removed check until the variant is printed, so the
+/// answer key can say which line it was on
+pub const MARKER: &str = "__removed_check";
+
+/// What a removed check was guarding against
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Kind {
+    Signer,
+    Owner,
+    AccountMatch,
+    Initialization,
+    RentExemption,
+    Arithmetic,
+    Other,
+}
+
+impl Kind {
+    fn of_condition(condition: &Expr) -> Kind {
+        let condition = condition.to_token_stream().to_string();
+        if condition.contains("is_signer") {
+            Kind::Signer
+        } else if condition.contains("owner") {
+            Kind::Owner
+        } else if condition.contains("is_initialized")
+            || condition.contains("discriminator")
+            || condition.contains("DISCRIMINATOR")
+        {
+            Kind::Initialization
+        } else if condition.contains("rent") || condition.contains("exempt") {
+            Kind::RentExemption
+        } else if condition.contains("key") {
+            Kind::AccountMatch
+        } else {
+            Kind::Other
+        }
+    }
+
+    fn of_assertion(helper: &str) -> Kind {
+        match helper {
+            "assert_signer" => Kind::Signer,
+            "assert_owned_by" => Kind::Owner,
+            "assert_key_eq"
+            | "assert_pda"
+            | "find_and_validate"
+            | "verify_stored_bump"
+            | "assert_canonical_bump" => Kind::AccountMatch,
+            "assert_rent_exempt" => Kind::RentExemption,
+            _ => Kind::Other,
+        }
+    }
+}
+
+/// A check taken out of a program
+#[derive(Clone, Debug)]
+pub struct Removed {
+    /// The function it was in
+    pub function: String,
+    pub kind: Kind,
+    /// The check's code
+    pub code: String,
+}
+
+/// How many checks the program makes
+pub fn count(file: &File) -> usize {
+    let mut remover = Remover::new(usize::MAX);
+    remover.visit_file_mut(&mut file.clone());
+    remover.seen
+}
+
+/// Takes out the `index`th check, in source order, leaving [`MARKER`]
+/// before the statement it was in
+pub fn remove(file: &mut File, index: usize) -> Option<Removed> {
+    let mut remover = Remover::new(index);
+    remover.visit_file_mut(file);
+    remover.removed
+}
+
+struct Remover {
+    target: usize,
+    seen: usize,
+    function: String,
+    removed: Option<Removed>,
+    /// Checked arithmetic was just replaced; its statement gets the marker
+    mark: bool,
+}
+
+impl Remover {
+    fn new(target: usize) -> Self {
+        Self {
+            target,
+            seen: 0,
+            function: String::new(),
+            removed: None,
+            mark: false,
+        }
+    }
+
+    /// Counts a check and reports whether it is the one to remove
+    fn take(&mut self) -> bool {
+        self.seen += 1;
+        self.seen - 1 == self.target
+    }
+}
+
+impl VisitMut for Remover {
+    fn visit_item_fn_mut(&mut self, item: &mut ItemFn) {
+        self.function = item.sig.ident.to_string();
+        visit_mut::visit_item_fn_mut(self, item);
+    }
+
+    fn visit_impl_item_fn_mut(&mut self, item: &mut ImplItemFn) {
+        self.function = item.sig.ident.to_string();
+        visit_mut::visit_impl_item_fn_mut(self, item);
+    }
+
+    fn visit_block_mut(&mut self, block: &mut Block) {
+        let marker = syn::Ident::new(MARKER, Span::call_site());
+        let mut stmts = Vec::with_capacity(block.stmts.len());
+        for mut stmt in block.stmts.drain(..) {
+            if let Some(condition) = guard(&stmt) {
+                if self.take() {
+                    self.removed = Some(Removed {
+                        function: self.function.clone(),
+                        kind: Kind::of_condition(condition),
+                        code: unparse(&stmt),
+                    });
+                    stmts.push(parse_quote!(#marker!();));
+                } else {
+                    stmts.push(stmt);
+                }
+                continue;
+            }
+            if let Some(helper) = assertion(&stmt) {
+                if self.take() {
+                    self.removed = Some(Removed {
+                        function: self.function.clone(),
+                        kind: Kind::of_assertion(&helper),
+                        code: unparse(&stmt),
+                    });
+                    stmts.push(parse_quote!(#marker!();));
+                } else {
+                    stmts.push(stmt);
+                }
+                continue;
+            }
+            self.visit_stmt_mut(&mut stmt);
+            if std::mem::take(&mut self.mark) {
+                stmts.push(parse_quote!(#marker!();));
+            }
+            stmts.push(stmt);
+        }
+        block.stmts = stmts;
+    }
+
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        visit_mut::visit_expr_mut(self, expr);
+        let unchecked = checked_arithmetic(expr)
+            .map(|unchecked| (Kind::Arithmetic, unchecked))
+            .or_else(|| checked_read(expr));
+        if let Some((kind, unchecked)) = unchecked {
+            if self.take() {
+                self.removed = Some(Removed {
+                    function: self.function.clone(),
+                    kind,
+                    code: unparse(&*expr),
+                });
+                *expr = unchecked;
+                self.mark = true;
+            }
+        }
+    }
+}
+
+/// The condition of `if cond { ...; return Err(..) }` without an `else`
+fn guard(stmt: &Stmt) -> Option<&Expr> {
+    let Stmt::Expr(Expr::If(check), _) = stmt else {
+        return None;
+    };
+    if check.else_branch.is_some() {
+        return None;
+    }
+    let returns_err = match check.then_branch.stmts.last()? {
+        Stmt::Expr(Expr::Return(ret), _) => match ret.expr.as_deref() {
+            Some(Expr::Call(call)) => match &*call.func {
+                Expr::Path(path) => path.path.is_ident("Err"),
+                _ => false,
+            },
+            _ => false,
+        },
+        _ => false,
+    };
+    returns_err.then_some(&*check.cond)
+}
+
+/// `solana_common::pda`'s checks not named `assert_..`
+const PDA_CHECKS: [&str; 2] = ["find_and_validate", "verify_stored_bump"];
+
+/// The helper `assert_..(..)?;` calls, and the PDA checks called the same way
+fn assertion(stmt: &Stmt) -> Option<String> {
+    let Stmt::Expr(Expr::Try(attempt), Some(_)) = stmt else {
+        return None;
+    };
+    let Expr::Call(call) = &*attempt.expr else {
+        return None;
+    };
+    let Expr::Path(function) = &*call.func else {
+        return None;
+    };
+    let helper = function.path.segments.last()?.ident.to_string();
+    (helper.starts_with("assert_") || PDA_CHECKS.contains(&helper.as_str())).then_some(helper)
+}
+
+/// The unchecked form of a checked operation, whose result is `?`-ed
+/// directly or after `ok_or` or `map_err`
+fn checked_arithmetic(expr: &Expr) -> Option<Expr> {
+    let Expr::Try(attempt) = expr else {
+        return None;
+    };
+    let checked = match &*attempt.expr {
+        Expr::MethodCall(handled)
+            if ["ok_or", "ok_or_else", "map_err"]
+                .contains(&handled.method.to_string().as_str()) =>
+        {
+            &*handled.receiver
+        }
+        checked => checked,
+    };
+    match checked {
+        Expr::MethodCall(checked) => checked_method(checked),
+        Expr::Paren(checked) => checked_operator(&checked.expr),
+        Expr::Call(checked) => mul_div(checked),
+        _ => None,
+    }
+}
+
+/// `a op b` for `a.checked_op(b)`
+fn checked_method(checked: &ExprMethodCall) -> Option<Expr> {
+    let op: BinOp = match checked.method.to_string().as_str() {
+        "checked_add" => parse_quote!(+),
+        "checked_sub" => parse_quote!(-),
+        "checked_mul" => parse_quote!(*),
+        "checked_div" => parse_quote!(/),
+        _ => return None,
+    };
+    if checked.args.len() != 1 {
+        return None;
+    }
+    Some(binary(&checked.receiver, op, &checked.args[0]))
+}
+
+/// `CheckedU64(a op b)` for `CheckedU64(a) op b`
+fn checked_operator(checked: &Expr) -> Option<Expr> {
+    let Expr::Binary(operation) = checked else {
+        return None;
+    };
+    if !matches!(operation.op, BinOp::Add(_) | BinOp::Sub(_) | BinOp::Mul(_)) {
+        return None;
+    }
+    let Expr::Call(wrapped) = &*operation.left else {
+        return None;
+    };
+    let Expr::Path(newtype) = &*wrapped.func else {
+        return None;
+    };
+    let name = &newtype.path.segments.last()?.ident;
+    if (name != "CheckedU64" && name != "CheckedU128") || wrapped.args.len() != 1 {
+        return None;
+    }
+    let unchecked = binary(&wrapped.args[0], operation.op, &operation.right);
+    Some(parse_quote!(#newtype(#unchecked)))
+}
+
+/// `a * b / c` for `mul_div_floor(a, b, c)` or `mul_div_ceil`
+fn mul_div(checked: &ExprCall) -> Option<Expr> {
+    let Expr::Path(function) = &*checked.func else {
+        return None;
+    };
+    let name = &function.path.segments.last()?.ident;
+    if (name != "mul_div_floor" && name != "mul_div_ceil") || checked.args.len() != 3 {
+        return None;
+    }
+    let product = binary(&checked.args[0], parse_quote!(*), &checked.args[1]);
+    Some(binary(&product, parse_quote!(/), &checked.args[2]))
+}
+
+/// `T::try_from_slice(&data[8..])` for `T::try_deserialize_checked(&data)`
+/// and, checking no owner either, for `load::<T>(account, program_id)`
+fn checked_read(expr: &Expr) -> Option<(Kind, Expr)> {
+    let Expr::Call(read) = expr else {
+        return None;
+    };
+    let Expr::Path(function) = &*read.func else {
+        return None;
+    };
+    let mut path = function.path.clone();
+    let last = path.segments.last_mut()?;
+    if last.ident == "load" && read.args.len() == 2 {
+        let PathArguments::AngleBracketed(generics) = &last.arguments else {
+            return None;
+        };
+        let Some(GenericArgument::Type(ty)) = generics.args.first() else {
+            return None;
+        };
+        let account = operand(&read.args[0]);
+        return Some((
+            Kind::Owner,
+            parse_quote!(#ty::try_from_slice(&#account.data.borrow()[8..])),
+        ));
+    }
+    if last.ident != "try_deserialize_checked" || read.args.len() != 1 {
+        return None;
+    }
+    last.ident = syn::Ident::new("try_from_slice", last.ident.span());
+    let data = match &read.args[0] {
+        Expr::Reference(data) => &*data.expr,
+        data => data,
+    };
+    let data = operand(data);
+    Some((Kind::Initialization, parse_quote!(#path(&#data[8..]))))
+}
+
+fn binary(left: &Expr, op: BinOp, right: &Expr) -> Expr {
+    Expr::Binary(ExprBinary {
+        attrs: Vec::new(),
+        left: Box::new(operand(left)),
+        op,
+        right: Box::new(operand(right)),
+    })
+}
+
+/// Parenthesizes anything that could bind looser than a binary operator
+fn operand(expr: &Expr) -> Expr {
+    match expr {
+        Expr::Path(_)
+        | Expr::Field(_)
+        | Expr::Lit(_)
+        | Expr::MethodCall(_)
+        | Expr::Call(_)
+        | Expr::Paren(_)
+        | Expr::Index(_) => expr.clone(),
+        other => parse_quote!((#other)),
+    }
+}
+
+/// A statement or expression as it would read in the variant
+fn unparse(code: impl ToTokens) -> String {
+    let file: File = parse_quote!(fn f() { #code });
+    prettyplease::unparse(&file)
+        .lines()
+        .filter(|line| line.starts_with("    "))
+        .map(|line| &line[4..])
+        .collect::<Vec<_>>()
+        .join("\n")
+}
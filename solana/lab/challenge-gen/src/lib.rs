@@ -0,0 +1,130 @@
+//! Find-the-bug exercises generated from the secure Solana examples
+//!
+//! A variant is one of the fixed programs under `solana/secure/` with
+//! exactly one of its checks taken out (see [`checks`]), its bindings and
+//! functions renamed and its comments dropped, so it cannot be matched
+//! against the original by eye. The answer key records which check is
+//! missing, where it was and what every name used to be, and
+//! [`cargo::check`] compiles each variant against the lab's own
+//! dependencies before anything is handed out:
+//!
+//! ```bash
+//! cd solana/lab
+//! cargo run -p challenge-gen -- --variants 3 --out exam
+//! ```
+
+pub mod cargo;
+pub mod checks;
+mod rename;
+
+use std::collections::BTreeMap;
+
+use proc_macro2::Span;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use checks::{Kind, Removed, MARKER};
+
+/// One exercise: a program with a check missing
+#[derive(Clone, Debug)]
+pub struct Variant {
+    pub source: String,
+    /// The 1-based line the check was on; the line after it, now
+    pub line: usize,
+    pub removed: Removed,
+    /// Original name to the name in the variant
+    pub renamed: BTreeMap<String, String>,
+}
+
+/// What an instructor needs to mark one exercise
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Answer {
+    /// The exercise's file name
+    pub challenge: String,
+    /// The example it was made from
+    pub example: String,
+    /// The function missing the check, as named in the exercise
+    pub function: String,
+    pub line: usize,
+    pub kind: Kind,
+    /// The missing code, with the exercise's names
+    pub removed: String,
+    pub renamed: BTreeMap<String, String>,
+}
+
+/// `answers.json`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AnswerKey {
+    /// Regenerates the same exercises with `--seed`
+    pub seed: u64,
+    pub answers: Vec<Answer>,
+}
+
+impl Answer {
+    pub fn new(challenge: String, example: String, variant: &Variant) -> Self {
+        Self {
+            challenge,
+            example,
+            function: variant.removed.function.clone(),
+            line: variant.line,
+            kind: variant.removed.kind,
+            removed: variant.removed.code.clone(),
+            renamed: variant.renamed.clone(),
+        }
+    }
+}
+
+/// How many checks a program makes, and so how many distinct variants it
+/// has
+pub fn check_count(source: &str) -> syn::Result<usize> {
+    Ok(checks::count(&syn::parse_file(source)?))
+}
+
+/// The program in `source` with its names shuffled and check number
+/// `check` (in source order) taken out
+pub fn variant(source: &str, check: usize, rng: &mut impl Rng) -> syn::Result<Variant> {
+    let (mut file, renamed) = rename::rename(&syn::parse_file(source)?, rng)?;
+    let removed = checks::remove(&mut file, check).ok_or_else(|| {
+        syn::Error::new(
+            Span::call_site(),
+            format!("the program makes fewer than {} checks", check + 1),
+        )
+    })?;
+
+    let printed = prettyplease::unparse(&file);
+    let mut lines = Vec::new();
+    let mut line = 0;
+    for text in printed.lines() {
+        if text.trim_start().starts_with(MARKER) {
+            line = lines.len() + 1;
+        } else {
+            lines.push(text);
+        }
+    }
+    Ok(Variant {
+        source: lines.join("\n") + "\n",
+        line,
+        removed,
+        renamed,
+    })
+}
+
+/// `count` variants of one program, each missing a different check until
+/// every check has been taken out once
+pub fn variants(source: &str, count: usize, rng: &mut impl Rng) -> syn::Result<Vec<Variant>> {
+    let mut order: Vec<usize> = (0..check_count(source)?).collect();
+    if order.is_empty() {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "the program makes no checks to remove",
+        ));
+    }
+    order.shuffle(rng);
+    order
+        .into_iter()
+        .cycle()
+        .take(count)
+        .map(|check| variant(source, check, rng))
+        .collect()
+}
@@ -0,0 +1,184 @@
+//! challenge-gen: find-the-bug exercises from the secure Solana examples
+//!
+//! ```bash
+//! challenge-gen [--variants N] [--out DIR] [--seed SEED] [--example NAME]... [--no-check]
+//! ```
+//!
+//! Writes `DIR/challenges/challenge_NN.rs`, shuffled across examples, and
+//! the answer key to `DIR/answers.json`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result as AnyResult};
+use challenge_gen::cargo::{self, Crate};
+use challenge_gen::{Answer, AnswerKey, Variant};
+use clap::Parser;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+/// Variants that still fail to compile are regenerated this many times
+const ATTEMPTS: usize = 3;
+
+#[derive(Parser)]
+#[command(
+    name = "challenge-gen",
+    about = "Generate find-the-bug exercises from the secure Solana examples"
+)]
+struct Cli {
+    /// Variants per example
+    #[arg(long, default_value_t = 3)]
+    variants: usize,
+    #[arg(long, default_value = "challenges")]
+    out: PathBuf,
+    /// Reproduce an earlier run; its seed is in its answers.json
+    #[arg(long)]
+    seed: Option<u64>,
+    /// Only these examples (default: all of them)
+    #[arg(long)]
+    example: Vec<String>,
+    /// Skip compiling the variants
+    #[arg(long)]
+    no_check: bool,
+}
+
+struct Exercise {
+    example: String,
+    source: String,
+    variant: Variant,
+}
+
+fn main() -> AnyResult<()> {
+    let cli = Cli::parse();
+    let seed = cli.seed.unwrap_or_else(rand::random);
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let mut exercises = Vec::new();
+    for (example, source) in examples(&cli.example)? {
+        let variants = challenge_gen::variants(&source, cli.variants, &mut rng)
+            .with_context(|| format!("generating variants of {example}"))?;
+        exercises.extend(variants.into_iter().map(|variant| Exercise {
+            example: example.clone(),
+            source: source.clone(),
+            variant,
+        }));
+    }
+    exercises.shuffle(&mut rng);
+
+    let challenges = cli.out.join("challenges");
+    if challenges.exists() {
+        fs::remove_dir_all(&challenges)?;
+    }
+    fs::create_dir_all(&challenges)
+        .with_context(|| format!("creating {}", challenges.display()))?;
+    let names: Vec<String> = (1..=exercises.len())
+        .map(|number| format!("challenge_{number:02}"))
+        .collect();
+    let paths: Vec<PathBuf> = names
+        .iter()
+        .map(|name| challenges.join(format!("{name}.rs")))
+        .collect();
+    for (exercise, path) in exercises.iter().zip(&paths) {
+        fs::write(path, &exercise.variant.source)?;
+    }
+
+    if !cli.no_check {
+        let mut pending: Vec<usize> = (0..exercises.len()).collect();
+        for attempt in 1..=ATTEMPTS {
+            let crates: Vec<Crate> = pending
+                .iter()
+                .map(|&index| Crate {
+                    name: &names[index],
+                    example: &exercises[index].example,
+                    source: &paths[index],
+                })
+                .collect();
+            let failed = cargo::check(&cli.out, &crates)?;
+            pending.retain(|&index| failed.contains(&names[index]));
+            if pending.is_empty() {
+                break;
+            }
+            if attempt == ATTEMPTS {
+                bail!(
+                    "{} still do not compile; see {}",
+                    failed.join(", "),
+                    cli.out.join(".check").display()
+                );
+            }
+            for &index in &pending {
+                let exercise = &mut exercises[index];
+                let checks = challenge_gen::check_count(&exercise.source)?;
+                exercise.variant =
+                    challenge_gen::variant(&exercise.source, rng.gen_range(0..checks), &mut rng)?;
+                fs::write(&paths[index], &exercise.variant.source)?;
+            }
+        }
+        fs::remove_dir_all(cli.out.join(".check"))?;
+    }
+
+    let key = AnswerKey {
+        seed,
+        answers: exercises
+            .iter()
+            .zip(&paths)
+            .map(|(exercise, path)| {
+                let challenge = path.file_name().unwrap_or_default().to_string_lossy();
+                Answer::new(
+                    format!("challenges/{challenge}"),
+                    exercise.example.clone(),
+                    &exercise.variant,
+                )
+            })
+            .collect(),
+    };
+    let answers = cli.out.join("answers.json");
+    fs::write(&answers, serde_json::to_string_pretty(&key)? + "\n")?;
+
+    for answer in &key.answers {
+        println!(
+            "{:<30} {:<24} {:?} check in {}, line {}",
+            answer.challenge, answer.example, answer.kind, answer.function, answer.line
+        );
+    }
+    let checked = if cli.no_check {
+        "not compiled"
+    } else {
+        "all compile"
+    };
+    println!(
+        "{} exercises ({checked}); answer key in {}",
+        key.answers.len(),
+        answers.display()
+    );
+    Ok(())
+}
+
+/// The secure examples to draw from, by name
+fn examples(only: &[String]) -> AnyResult<Vec<(String, String)>> {
+    let secure = cargo::lab_dir().join("..").join("secure");
+    let mut examples = Vec::new();
+    for entry in fs::read_dir(&secure).with_context(|| format!("reading {}", secure.display()))? {
+        let path = entry?.path();
+        let Some(example) = stem(&path) else { continue };
+        if only.is_empty() || only.contains(&example) {
+            let source = fs::read_to_string(&path)?;
+            examples.push((example, source));
+        }
+    }
+    examples.sort();
+    if let Some(missing) = only
+        .iter()
+        .find(|name| !examples.iter().any(|(example, _)| example == *name))
+    {
+        bail!("no secure version of {missing}");
+    }
+    Ok(examples)
+}
+
+fn stem(path: &Path) -> Option<String> {
+    if path.extension()? != "rs" {
+        return None;
+    }
+    Some(path.file_stem()?.to_str()?.to_string())
+}
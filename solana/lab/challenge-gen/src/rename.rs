@@ -0,0 +1,215 @@
+//! Fresh names for a program's bindings and functions, and no doc comments
+//!
+//! Renaming works on tokens, so it reaches into macro calls like `msg!` and
+//! changes every use of a name at once. It only touches names nothing
+//! outside the file can mean: `let` bindings, parameters and free
+//! functions, minus any name that is also a field, a method, a path
+//! segment or an import somewhere in the file.
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+use proc_macro2::{Delimiter, Group, Ident, TokenStream, TokenTree};
+use quote::ToTokens;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use syn::visit::{self, Visit};
+use syn::{
+    ExprField, ExprMethodCall, Field, FieldPat, FieldValue, File, ItemFn, Member, PatIdent, Path,
+    UseTree,
+};
+
+const ADJECTIVES: &[&str] = &[
+    "amber", "brisk", "calm", "dusky", "eager", "fabled", "gentle", "hollow", "ivory", "jolly",
+    "keen", "lunar", "mellow", "nimble", "opal", "proud", "quiet", "rustic", "silent", "tidy",
+    "umber", "vivid", "wry", "zesty",
+];
+
+const NOUNS: &[&str] = &[
+    "anchor", "beacon", "cedar", "delta", "ember", "falcon", "garnet", "harbor", "island",
+    "juniper", "kestrel", "lantern", "meadow", "nectar", "orchid", "pebble", "quartz", "raven",
+    "summit", "thistle", "umbra", "valley", "willow", "yarrow",
+];
+
+/// The entrypoint has to keep its name
+const KEEP: &[&str] = &["process_instruction", "main", "self"];
+
+/// The file with its names replaced and doc comments dropped, and what
+/// each name became
+pub fn rename(file: &File, rng: &mut impl Rng) -> syn::Result<(File, BTreeMap<String, String>)> {
+    let mut names = Names::default();
+    names.visit_file(file);
+
+    let tokens = file.to_token_stream();
+    let mut taken = HashSet::new();
+    idents(tokens.clone(), &mut taken);
+
+    let mut pool: Vec<String> = ADJECTIVES
+        .iter()
+        .flat_map(|adjective| NOUNS.iter().map(move |noun| format!("{adjective}_{noun}")))
+        .filter(|name| !taken.contains(name))
+        .collect();
+    pool.shuffle(rng);
+
+    let mut renamed = BTreeMap::new();
+    for name in names.bound.difference(&names.external) {
+        let Some(fresh) = pool.pop() else { break };
+        let fresh = if name.starts_with('_') {
+            format!("_{fresh}")
+        } else {
+            fresh
+        };
+        renamed.insert(name.clone(), fresh);
+    }
+
+    let file = syn::parse2(rewrite(tokens, &renamed))?;
+    Ok((file, renamed))
+}
+
+#[derive(Default)]
+struct Names {
+    /// Names the file binds or defines
+    bound: BTreeSet<String>,
+    /// Names that may mean something outside the file
+    external: BTreeSet<String>,
+}
+
+impl<'ast> Visit<'ast> for Names {
+    fn visit_pat_ident(&mut self, pat: &'ast PatIdent) {
+        let name = pat.ident.to_string();
+        // Upper case is a constant or a variant like `None`
+        if !name.starts_with(char::is_uppercase) && !KEEP.contains(&name.as_str()) {
+            self.bound.insert(name);
+        }
+        visit::visit_pat_ident(self, pat);
+    }
+
+    fn visit_item_fn(&mut self, item: &'ast ItemFn) {
+        let name = item.sig.ident.to_string();
+        if !KEEP.contains(&name.as_str()) {
+            self.bound.insert(name);
+        }
+        visit::visit_item_fn(self, item);
+    }
+
+    fn visit_expr_field(&mut self, expr: &'ast ExprField) {
+        self.member(&expr.member);
+        visit::visit_expr_field(self, expr);
+    }
+
+    fn visit_expr_method_call(&mut self, expr: &'ast ExprMethodCall) {
+        self.external.insert(expr.method.to_string());
+        visit::visit_expr_method_call(self, expr);
+    }
+
+    fn visit_path(&mut self, path: &'ast Path) {
+        if path.segments.len() > 1 {
+            self.external.extend(
+                path.segments
+                    .iter()
+                    .map(|segment| segment.ident.to_string()),
+            );
+        }
+        visit::visit_path(self, path);
+    }
+
+    fn visit_field(&mut self, field: &'ast Field) {
+        if let Some(ident) = &field.ident {
+            self.external.insert(ident.to_string());
+        }
+        visit::visit_field(self, field);
+    }
+
+    fn visit_field_value(&mut self, field: &'ast FieldValue) {
+        self.member(&field.member);
+        visit::visit_field_value(self, field);
+    }
+
+    fn visit_field_pat(&mut self, field: &'ast FieldPat) {
+        // Also covers `Struct { field }`, which binds the field's own name
+        self.member(&field.member);
+        visit::visit_field_pat(self, field);
+    }
+
+    fn visit_use_tree(&mut self, tree: &'ast UseTree) {
+        match tree {
+            UseTree::Path(path) => {
+                self.external.insert(path.ident.to_string());
+            }
+            UseTree::Name(name) => {
+                self.external.insert(name.ident.to_string());
+            }
+            UseTree::Rename(rename) => {
+                self.external.insert(rename.ident.to_string());
+                self.external.insert(rename.rename.to_string());
+            }
+            UseTree::Glob(_) | UseTree::Group(_) => {}
+        }
+        visit::visit_use_tree(self, tree);
+    }
+}
+
+impl Names {
+    fn member(&mut self, member: &Member) {
+        if let Member::Named(ident) = member {
+            self.external.insert(ident.to_string());
+        }
+    }
+}
+
+/// Every identifier in `tokens`, macro arguments included
+fn idents(tokens: TokenStream, into: &mut HashSet<String>) {
+    for token in tokens {
+        match token {
+            TokenTree::Ident(ident) => {
+                into.insert(ident.to_string());
+            }
+            TokenTree::Group(group) => idents(group.stream(), into),
+            TokenTree::Punct(_) | TokenTree::Literal(_) => {}
+        }
+    }
+}
+
+fn rewrite(tokens: TokenStream, renamed: &BTreeMap<String, String>) -> TokenStream {
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+    let mut output = Vec::with_capacity(tokens.len());
+    let mut index = 0;
+    while index < tokens.len() {
+        if let Some(length) = doc_attribute(&tokens[index..]) {
+            index += length;
+            continue;
+        }
+        output.push(match &tokens[index] {
+            TokenTree::Ident(ident) => match renamed.get(&ident.to_string()) {
+                Some(fresh) => TokenTree::Ident(Ident::new(fresh, ident.span())),
+                None => TokenTree::Ident(ident.clone()),
+            },
+            TokenTree::Group(group) => {
+                let mut fresh = Group::new(group.delimiter(), rewrite(group.stream(), renamed));
+                fresh.set_span(group.span());
+                TokenTree::Group(fresh)
+            }
+            other => other.clone(),
+        });
+        index += 1;
+    }
+    output.into_iter().collect()
+}
+
+/// How many tokens a `#[doc = ..]` or `#![doc = ..]` at the start of
+/// `tokens` spans
+fn doc_attribute(tokens: &[TokenTree]) -> Option<usize> {
+    let TokenTree::Punct(pound) = tokens.first()? else {
+        return None;
+    };
+    if pound.as_char() != '#' {
+        return None;
+    }
+    let bang = matches!(tokens.get(1), Some(TokenTree::Punct(bang)) if bang.as_char() == '!');
+    let length = if bang { 3 } else { 2 };
+    let TokenTree::Group(group) = tokens.get(length - 1)? else {
+        return None;
+    };
+    let is_doc = group.delimiter() == Delimiter::Bracket
+        && matches!(group.stream().into_iter().next(), Some(TokenTree::Ident(ident)) if ident == "doc");
+    is_doc.then_some(length)
+}
@@ -0,0 +1,81 @@
+//! Every variant is its program minus exactly one check
+
+use std::fs;
+use std::path::Path;
+
+use challenge_gen::checks::MARKER;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+fn secure_examples() -> Vec<(String, String)> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../secure");
+    let mut examples: Vec<(String, String)> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| {
+            let path = entry.unwrap().path();
+            let name = path.file_stem().unwrap().to_string_lossy().into_owned();
+            (name, fs::read_to_string(&path).unwrap())
+        })
+        .collect();
+    examples.sort();
+    examples
+}
+
+#[test]
+fn each_variant_removes_exactly_one_check() {
+    let mut rng = StdRng::seed_from_u64(1);
+    for (example, source) in secure_examples() {
+        let checks = challenge_gen::check_count(&source).unwrap();
+        assert!(checks > 0, "{example} makes no checks");
+        for check in 0..checks {
+            let variant = challenge_gen::variant(&source, check, &mut rng).unwrap();
+            assert_eq!(
+                challenge_gen::check_count(&variant.source).unwrap(),
+                checks - 1,
+                "{example} check {check}"
+            );
+            assert!(!variant.source.contains(MARKER));
+            assert!(variant.line > 0);
+            assert!(variant.source.contains("fn process_instruction"));
+            assert!(!variant.source.contains("///"));
+        }
+    }
+}
+
+#[test]
+fn variants_cover_distinct_checks_first() {
+    let (_, source) = secure_examples()
+        .into_iter()
+        .find(|(example, _)| example == "type_confusion")
+        .unwrap();
+    let checks = challenge_gen::check_count(&source).unwrap();
+    let variants = challenge_gen::variants(&source, checks, &mut StdRng::seed_from_u64(2)).unwrap();
+    let mut removed: Vec<(String, String)> = variants
+        .iter()
+        .map(|variant| {
+            // Names differ between variants; map the code back to the original's
+            let mut code = variant.removed.code.clone();
+            let mut function = variant.removed.function.clone();
+            for (original, fresh) in &variant.renamed {
+                code = code.replace(fresh, original);
+                if function == *fresh {
+                    function = original.clone();
+                }
+            }
+            (function, code)
+        })
+        .collect();
+    removed.sort();
+    removed.dedup();
+    assert_eq!(removed.len(), checks);
+}
+
+#[test]
+fn a_seed_reproduces_its_variants() {
+    let (_, source) = secure_examples().remove(0);
+    let first = challenge_gen::variants(&source, 3, &mut StdRng::seed_from_u64(3)).unwrap();
+    let again = challenge_gen::variants(&source, 3, &mut StdRng::seed_from_u64(3)).unwrap();
+    for (first, again) in first.iter().zip(&again) {
+        assert_eq!(first.source, again.source);
+    }
+}
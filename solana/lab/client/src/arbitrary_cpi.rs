@@ -0,0 +1,36 @@
+//! arbitrary_cpi: a call the program forwards to another program
+
+use anyhow::Result as AnyResult;
+use lab_instructions::arbitrary_cpi::invoke_ix;
+use solana_program::pubkey::Pubkey;
+
+use crate::{Keypair, Signature, Signer};
+
+example_client!("arbitrary_cpi");
+
+impl Client {
+    /// Has the program invoke `target_program` with `data`, passing `user`
+    /// as signer, `target_account` and the program's `relay` account. The
+    /// exploit is this call with the
+    /// attacker's program as the target, signed by a victim who meant
+    /// something else, so there is no exploit method: it is the victim's
+    /// client that sends it
+    pub async fn invoke(
+        &self,
+        user: &Keypair,
+        target_program: Pubkey,
+        target_account: Pubkey,
+        relay: Pubkey,
+        data: Vec<u8>,
+    ) -> AnyResult<Signature> {
+        let invoke = invoke_ix(
+            self.program_id(),
+            user.pubkey(),
+            target_program,
+            target_account,
+            relay,
+            data,
+        );
+        self.send(&[invoke], &[user]).await
+    }
+}
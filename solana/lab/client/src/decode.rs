@@ -0,0 +1,471 @@
+//! Every example account type, decoded and shown with its units
+//!
+//! [`fetch`] reads an account and decodes it as the types of the program
+//! crate that owns it, the crate the ids it is given name for the owner.
+//! A secure build's types are told apart the way the build itself tells
+//! them apart, by discriminator or version byte. A vulnerable build's types
+//! are only told apart by whether the data is exactly one value of them,
+//! so an account can decode as more than one: that is `type_confusion`'s
+//! bug, and [`Inspected`] says so rather than picking one.
+//!
+//! ```no_run
+//! # use anyhow::Context;
+//! # use lab_client::{decode, ids, rpc::Rpc};
+//! # let rpc = Rpc::new("http://127.0.0.1:8899");
+//! # let address = solana_program::pubkey::Pubkey::new_unique();
+//! let account = decode::fetch(&rpc, &address, ids::ALL)?;
+//! println!("{}", account.context("no account there")?);
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+//!
+//! Lamports are shown in SOL as well, times as Unix time and fees as a
+//! percentage; amounts a program only records, such as a stake, are left
+//! as numbers. A crate with no types written out here, such as an example
+//! added since, is decoded by its registry layouts ([`layout::fit`]), and
+//! its fields shown as plainly as their types allow.
+
+use std::fmt;
+
+use anyhow::Result as AnyResult;
+use lab_domain::{EscrowAccount, StakingPool, UserProfile, UserStake, VaultConfig};
+use solana_common::access_control::Role;
+use solana_common::deserialize::{lenient_deserialize, strict_deserialize};
+use solana_common::discriminator::AccountDiscriminator;
+use solana_common::versioning::{read_versioned, Versioned};
+use solana_program::native_token::LAMPORTS_PER_SOL;
+use solana_program::pubkey::Pubkey;
+
+use crate::layout::{self, Fit};
+use crate::rpc::Rpc;
+
+/// A field's value, with its unit
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Value {
+    Key(Pubkey),
+    Lamports(u64),
+    /// A count, or an amount the program only records
+    Number(u64),
+    /// Seconds since the Unix epoch
+    Timestamp(i64),
+    /// A whole percentage
+    Percent(u8),
+    Bytes(Vec<u8>),
+    Keys(Vec<Pubkey>),
+    /// Anything else, as the program's source would print it
+    Text(String),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Key(key) => write!(f, "{key}"),
+            Value::Lamports(lamports) => write!(f, "{} SOL ({lamports} lamports)", sol(*lamports)),
+            Value::Number(number) => write!(f, "{number}"),
+            Value::Timestamp(seconds) => write!(f, "{seconds} (unix time)"),
+            Value::Percent(percent) => write!(f, "{percent}%"),
+            Value::Bytes(bytes) if bytes.iter().all(|byte| *byte == 0) => {
+                write!(f, "{} zero bytes", bytes.len())
+            }
+            Value::Bytes(bytes) => bytes.iter().try_for_each(|byte| write!(f, "{byte:02x}")),
+            Value::Keys(keys) => {
+                write!(f, "{} keys", keys.len())?;
+                keys.iter().try_for_each(|key| write!(f, "\n  {key}"))
+            }
+            Value::Text(text) => write!(f, "{text}"),
+        }
+    }
+}
+
+/// `lamports` in SOL, with no trailing zeros
+fn sol(lamports: u64) -> String {
+    let (whole, fraction) = (lamports / LAMPORTS_PER_SOL, lamports % LAMPORTS_PER_SOL);
+    if fraction == 0 {
+        return whole.to_string();
+    }
+    let fraction = format!("{fraction:09}");
+    format!("{whole}.{}", fraction.trim_end_matches('0'))
+}
+
+/// The names of the roles set, "none" when there are none
+fn roles(roles: Role) -> String {
+    if roles.is_empty() {
+        return "none".to_string();
+    }
+    let names: Vec<&str> = roles.iter_names().map(|(name, _)| name).collect();
+    names.join(" | ")
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Field {
+    pub name: &'static str,
+    pub value: Value,
+}
+
+/// An account type [`decode`] knows, and how it is shown
+pub trait Inspect {
+    /// Its name in the program's source
+    const NAME: &'static str;
+
+    fn fields(&self) -> Vec<Field>;
+}
+
+/// Implements [`Inspect`] for types of the same name and fields, such as
+/// an example's type and its secure counterpart
+macro_rules! inspect {
+    ($name:literal for $($type:ty),+ => |$account:ident| $fields:expr) => {
+        $(
+            impl Inspect for $type {
+                const NAME: &'static str = $name;
+
+                fn fields(&self) -> Vec<Field> {
+                    let $account = self;
+                    $fields
+                        .into_iter()
+                        .map(|(name, value)| Field { name, value })
+                        .collect()
+                }
+            }
+        )+
+    };
+}
+
+inspect!("TokenVault" for account_data_matching::TokenVault, account_data_matching_secure::TokenVault => |vault| [
+    ("authority", Value::Key(vault.authority)),
+    ("token_account", Value::Key(vault.token_account)),
+]);
+inspect!("UserProfile" for UserProfile => |profile| [
+    ("owner", Value::Key(profile.owner)),
+    ("escrow_account", Value::Key(profile.escrow_account)),
+    ("total_deposits", Value::Lamports(profile.total_deposits)),
+]);
+inspect!("EscrowAccount" for EscrowAccount => |escrow| [
+    ("beneficiary", Value::Key(escrow.beneficiary)),
+    ("amount", Value::Lamports(escrow.amount)),
+    ("release_time", Value::Timestamp(escrow.release_time)),
+]);
+inspect!("UserAccountV1" for account_versioning::UserAccountV1, account_versioning_secure::UserAccountV1 => |account| [
+    ("owner", Value::Key(account.owner)),
+    ("balance", Value::Lamports(account.balance)),
+]);
+inspect!("UserAccountV2" for account_versioning::UserAccountV2, account_versioning_secure::UserAccountV2 => |account| [
+    ("owner", Value::Key(account.owner)),
+    ("delegate", Value::Key(account.delegate)),
+    ("balance", Value::Lamports(account.balance)),
+]);
+inspect!("StakingPool" for StakingPool => |pool| [
+    ("total_staked", Value::Number(pool.total_staked)),
+    ("reward_rate", Value::Number(pool.reward_rate)),
+    ("last_update", Value::Timestamp(pool.last_update)),
+]);
+inspect!("UserStake" for UserStake => |stake| [
+    ("amount", Value::Number(stake.amount)),
+    ("last_claim", Value::Timestamp(stake.last_claim)),
+]);
+inspect!("Payroll" for batch_payout::Payroll => |payroll| [
+    ("authority", Value::Key(payroll.authority)),
+    ("amount", Value::Lamports(payroll.amount)),
+    ("recipients", Value::Keys(payroll.recipients.clone())),
+]);
+inspect!("Payroll" for batch_payout_secure::Payroll => |payroll| [
+    ("authority", Value::Key(payroll.authority.authority)),
+    ("pending_authority", Value::Keys(payroll.authority.pending.into_iter().collect())),
+    ("amount", Value::Lamports(payroll.amount)),
+    ("recipients", Value::Keys(payroll.recipients.clone())),
+    ("cursor", Value::Number(payroll.cursor.into())),
+]);
+inspect!("Flag" for ctf_verifier::Flag => |flag| [
+    ("target", Value::Key(flag.target)),
+    ("goal", Value::Text(format!("{:?}", flag.goal))),
+    ("baseline", Value::Number(flag.baseline)),
+    ("solvers", Value::Keys(flag.solvers.clone())),
+]);
+inspect!("VaultData" for missing_owner_check::VaultData => |vault| [
+    ("authority", Value::Key(vault.authority)),
+    ("vault", Value::Key(vault.vault)),
+]);
+inspect!("VaultData" for missing_owner_check_secure::VaultData => |vault| [
+    ("authority", Value::Key(vault.authority.key)),
+    ("roles", Value::Text(roles(vault.authority.roles))),
+    ("vault", Value::Key(vault.vault)),
+    ("paused", Value::Text(vault.pause.paused.to_string())),
+    ("guardian", Value::Key(vault.pause.guardian)),
+    ("cap", Value::Lamports(vault.cap.cap)),
+    ("withdrawn", Value::Lamports(vault.cap.withdrawn)),
+    ("window_start", Value::Timestamp(vault.cap.window_start)),
+]);
+inspect!("UserData" for pda_issues::UserData, pda_issues_secure::UserData => |user| [
+    ("owner", Value::Key(user.owner)),
+    ("balance", Value::Lamports(user.balance)),
+]);
+inspect!("UserData" for rent_exemption::UserData, rent_exemption_secure::UserData => |user| [
+    ("owner", Value::Key(user.owner)),
+    ("balance", Value::Lamports(user.balance)),
+    ("metadata", Value::Bytes(user.metadata.to_vec())),
+]);
+inspect!("VaultConfig" for VaultConfig => |vault| [
+    ("authority", Value::Key(vault.authority)),
+    ("total_deposited", Value::Lamports(vault.total_deposited)),
+    ("fee_percentage", Value::Percent(vault.fee_percentage)),
+]);
+inspect!("UserAccount" for type_confusion::UserAccount => |account| [
+    ("owner", Value::Key(account.owner)),
+    ("balance", Value::Lamports(account.balance)),
+    ("rewards", Value::Number(account.rewards)),
+]);
+inspect!("AdminAccount" for type_confusion::AdminAccount => |account| [
+    ("owner", Value::Key(account.owner)),
+    ("balance", Value::Lamports(account.balance)),
+    ("admin_level", Value::Number(account.admin_level)),
+]);
+inspect!("UserAccountSecure" for type_confusion_secure::UserAccountSecure => |account| [
+    ("owner", Value::Key(account.owner)),
+    ("balance", Value::Lamports(account.balance)),
+    ("rewards", Value::Number(account.rewards)),
+]);
+inspect!("AdminAccountSecure" for type_confusion_secure::AdminAccountSecure => |account| [
+    ("owner", Value::Key(account.owner)),
+    ("balance", Value::Lamports(account.balance)),
+    ("admin_level", Value::Number(account.admin_level)),
+]);
+
+/// How an account's type was told
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Detection {
+    /// By the 8-byte discriminator its data starts with
+    Discriminator,
+    /// By the version byte its data starts with
+    Version(u8),
+    /// Only by the data being exactly one value of the type, which any
+    /// type of the same layout would pass as too
+    Layout,
+}
+
+impl fmt::Display for Detection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Detection::Discriminator => write!(f, "by its discriminator"),
+            Detection::Version(version) => write!(f, "by its version byte, {version}"),
+            Detection::Layout => write!(f, "by its layout alone"),
+        }
+    }
+}
+
+/// One type an account's data decodes as
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Decoded {
+    pub type_name: &'static str,
+    pub detection: Detection,
+    pub fields: Vec<Field>,
+}
+
+impl Decoded {
+    fn new<T: Inspect>(account: &T, detection: Detection) -> Self {
+        Self {
+            type_name: T::NAME,
+            detection,
+            fields: account.fields(),
+        }
+    }
+}
+
+/// The types of `krate`'s, a program crate's, that `data` decodes as, by
+/// the checks that build makes; empty for a crate that stores nothing, or
+/// data that is none of its types
+pub fn decode(krate: &str, data: &[u8]) -> Vec<Decoded> {
+    let mut decoded = Vec::new();
+    let mut layout = |decode: fn(&[u8]) -> Option<Decoded>| decoded.extend(decode(data));
+    match krate {
+        "account_data_matching" => {
+            layout(exactly::<account_data_matching::TokenVault>);
+            layout(exactly::<UserProfile>);
+            layout(exactly::<EscrowAccount>);
+        }
+        "account_data_matching_secure" => {
+            layout(exactly::<account_data_matching_secure::TokenVault>);
+            layout(exactly::<UserProfile>);
+            layout(exactly::<EscrowAccount>);
+        }
+        "account_versioning" => {
+            layout(exactly::<account_versioning::UserAccountV1>);
+            layout(exactly::<account_versioning::UserAccountV2>);
+        }
+        "account_versioning_secure" => {
+            layout(versioned::<account_versioning_secure::UserAccountV1>);
+            layout(versioned::<account_versioning_secure::UserAccountV2>);
+        }
+        "arithmetic_errors" | "arithmetic_errors_checked" | "arithmetic_errors_secure" => {
+            layout(exactly::<StakingPool>);
+            layout(exactly::<UserStake>);
+        }
+        "batch_payout" => layout(exactly::<batch_payout::Payroll>),
+        "batch_payout_secure" => layout(exactly::<batch_payout_secure::Payroll>),
+        "ctf_verifier" => layout(flag),
+        "missing_owner_check" => layout(exactly::<missing_owner_check::VaultData>),
+        "missing_owner_check_secure" => {
+            layout(discriminated::<missing_owner_check_secure::VaultData>)
+        }
+        "pda_issues" => layout(exactly::<pda_issues::UserData>),
+        "pda_issues_secure" => layout(exactly::<pda_issues_secure::UserData>),
+        "reinitialization" => layout(exactly::<VaultConfig>),
+        "reinitialization_secure" => layout(discriminated::<VaultConfig>),
+        "rent_exemption" => layout(exactly::<rent_exemption::UserData>),
+        "rent_exemption_secure" => layout(exactly::<rent_exemption_secure::UserData>),
+        "type_confusion" => {
+            layout(exactly::<type_confusion::UserAccount>);
+            layout(exactly::<type_confusion::AdminAccount>);
+        }
+        "type_confusion_secure" => {
+            layout(discriminated::<type_confusion_secure::UserAccountSecure>);
+            layout(discriminated::<type_confusion_secure::AdminAccountSecure>);
+        }
+        // A crate with none of the above: what its layouts make of it
+        _ => decoded.extend(layout::fit(krate, data).into_iter().map(Decoded::from)),
+    }
+    decoded
+}
+
+impl From<Fit> for Decoded {
+    fn from(fit: Fit) -> Self {
+        Self {
+            type_name: fit.layout.name,
+            detection: fit.detection,
+            fields: fit
+                .fields
+                .into_iter()
+                .map(|(name, value)| Field {
+                    name,
+                    value: value.into(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// A field only its layout describes, in what unit its type can tell
+impl From<layout::Value> for Value {
+    fn from(value: layout::Value) -> Self {
+        match value {
+            layout::Value::Pubkey(key) => Value::Key(key),
+            layout::Value::Unsigned(number, _) => u64::try_from(number)
+                .map_or_else(|_| Value::Text(number.to_string()), Value::Number),
+            layout::Value::Bytes(bytes) => Value::Bytes(bytes),
+            layout::Value::List(values)
+                if values
+                    .iter()
+                    .all(|value| matches!(value, layout::Value::Pubkey(_))) =>
+            {
+                let keys = values.into_iter().filter_map(|value| match value {
+                    layout::Value::Pubkey(key) => Some(key),
+                    _ => None,
+                });
+                Value::Keys(keys.collect())
+            }
+            value => Value::Text(value.to_string()),
+        }
+    }
+}
+
+fn exactly<T: Inspect + borsh::BorshDeserialize>(data: &[u8]) -> Option<Decoded> {
+    let account = strict_deserialize::<T>(data).ok()?;
+    Some(Decoded::new(&account, Detection::Layout))
+}
+
+/// A flag, read from the front of the fixed space the verifier gives it
+fn flag(data: &[u8]) -> Option<Decoded> {
+    let flag = lenient_deserialize::<ctf_verifier::Flag>(data).ok()?;
+    Some(Decoded::new(&flag, Detection::Layout))
+}
+
+fn discriminated<T: Inspect + AccountDiscriminator>(data: &[u8]) -> Option<Decoded> {
+    let account = T::try_deserialize_checked(data).ok()?;
+    Some(Decoded::new(&account, Detection::Discriminator))
+}
+
+fn versioned<T: Inspect + Versioned>(data: &[u8]) -> Option<Decoded> {
+    let account = read_versioned::<T>(data).ok()?;
+    Some(Decoded::new(&account, Detection::Version(T::VERSION)))
+}
+
+/// An account as [`fetch`] found it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Inspected {
+    pub address: Pubkey,
+    pub lamports: u64,
+    pub owner: Pubkey,
+    /// The program crate that owns it, if the ids it was fetched with name
+    /// its owner
+    pub program: Option<String>,
+    pub data_len: usize,
+    /// Every type its data decodes as
+    pub decoded: Vec<Decoded>,
+}
+
+/// The account at `address`, decoded as the types of whichever program
+/// crate `programs`, crate names and their ids such as [`ids::ALL`](crate::ids::ALL),
+/// says owns it; `None` if there is no account
+pub fn fetch(
+    rpc: &Rpc,
+    address: &Pubkey,
+    programs: &[(&str, Pubkey)],
+) -> AnyResult<Option<Inspected>> {
+    let Some((owner, data)) = rpc.account(address)? else {
+        return Ok(None);
+    };
+    let lamports = rpc.balance(address)?;
+    let program = programs
+        .iter()
+        .find(|(_, id)| *id == owner)
+        .map(|(krate, _)| krate.to_string());
+    let decoded = program
+        .as_deref()
+        .map(|krate| decode(krate, &data))
+        .unwrap_or_default();
+    Ok(Some(Inspected {
+        address: *address,
+        lamports,
+        owner,
+        program,
+        data_len: data.len(),
+        decoded,
+    }))
+}
+
+impl fmt::Display for Inspected {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}", self.address)?;
+        writeln!(f, "  lamports  {}", Value::Lamports(self.lamports))?;
+        match &self.program {
+            Some(krate) => writeln!(f, "  owner     {} ({krate})", self.owner)?,
+            None => writeln!(f, "  owner     {}", self.owner)?,
+        }
+        writeln!(f, "  data      {} bytes", self.data_len)?;
+        if self.decoded.len() > 1 {
+            writeln!(
+                f,
+                "  decodes as {} types, and nothing in the data tells them apart",
+                self.decoded.len()
+            )?;
+        }
+        for decoded in &self.decoded {
+            writeln!(f, "  {}, {}", decoded.type_name, decoded.detection)?;
+            let width = decoded
+                .fields
+                .iter()
+                .map(|field| field.name.len())
+                .max()
+                .unwrap_or(0);
+            for field in &decoded.fields {
+                let value = field.value.to_string().replace('\n', "\n    ");
+                writeln!(f, "    {:<width$}  {value}", field.name)?;
+            }
+        }
+        match (&self.program, self.decoded.is_empty()) {
+            (None, _) => writeln!(f, "  not owned by any of the lab's programs")?,
+            (Some(krate), true) if self.data_len > 0 => {
+                writeln!(f, "  none of the types {krate} stores")?
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,274 @@
+//! Example accounts read by the registry's layouts, with no IDL
+//!
+//! The registry lays out every Borsh struct an example's program stores,
+//! from the program's source, as `lab_registry::Metadata::accounts`. That
+//! is enough to read any of the program's accounts field by field without
+//! its types, as [`decode`] does, and to tell which type an account holds:
+//! a type its build writes a discriminator for is laid out with one, and
+//! only data that starts with the first 8 bytes of the SHA-256 of
+//! `account:<TypeName>` holds it. [`fit`] is every type an account's bytes
+//! could be, for a tool that has nothing else, and [`fetch_decoded`] reads
+//! an account as a type the caller does have, once its owner is found to
+//! store that type and its discriminator, if it has one, matches:
+//!
+//! ```no_run
+//! # use lab_client::{accounts::StakingPool, layout, rpc::Rpc};
+//! # let rpc = Rpc::new("http://127.0.0.1:8899");
+//! # let address = solana_program::pubkey::Pubkey::new_unique();
+//! let pool: StakingPool = layout::fetch_decoded(&rpc, &address)?;
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+//!
+//! Layouts say nothing of a version byte, so `account_versioning_secure`'s
+//! accounts are read by [`decode::decode`](crate::decode::decode) alone.
+
+use std::fmt;
+
+use anyhow::{anyhow, ensure, Context, Result as AnyResult};
+use borsh::BorshDeserialize;
+use lab_registry::{metadata, FieldType, Layout};
+use solana_common::deserialize::strict_deserialize;
+use solana_common::discriminator::CLOSED_ACCOUNT_DISCRIMINATOR;
+use solana_program::hash::hash;
+use solana_program::pubkey::Pubkey;
+
+use crate::decode::{Detection, Inspect};
+use crate::ids;
+use crate::rpc::Rpc;
+
+/// A decoded field's value
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Value {
+    Bool(bool),
+    /// An unsigned integer and its width in bits
+    Unsigned(u128, u32),
+    Signed(i128),
+    Pubkey(Pubkey),
+    String(String),
+    /// A byte array
+    Bytes(Vec<u8>),
+    List(Vec<Value>),
+    Option(Option<Box<Value>>),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Bool(value) => write!(f, "{value}"),
+            Value::Unsigned(value, _) => write!(f, "{value}"),
+            Value::Signed(value) => write!(f, "{value}"),
+            Value::Pubkey(pubkey) => write!(f, "{pubkey}"),
+            Value::String(value) => write!(f, "{value:?}"),
+            Value::Bytes(bytes) if bytes.iter().all(|&byte| byte == 0) => {
+                write!(f, "[0; {}]", bytes.len())
+            }
+            Value::Bytes(bytes) => {
+                f.write_str("0x")?;
+                bytes.iter().try_for_each(|byte| write!(f, "{byte:02x}"))
+            }
+            Value::List(values) => {
+                f.write_str("[")?;
+                for (index, value) in values.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{value}")?;
+                }
+                f.write_str("]")
+            }
+            Value::Option(None) => f.write_str("None"),
+            Value::Option(Some(value)) => write!(f, "Some({value})"),
+        }
+    }
+}
+
+/// The layouts of the types `krate`, a program crate, stores; none for a
+/// crate the registry has no example for
+pub fn layouts(krate: &str) -> &'static [Layout] {
+    let (example, secure) = match krate.strip_suffix("_secure") {
+        Some(example) => (example, true),
+        None => (krate.strip_suffix("_checked").unwrap_or(krate), false),
+    };
+    match metadata(example) {
+        Some(metadata) if secure => metadata.secure_accounts,
+        Some(metadata) => metadata.accounts,
+        None => &[],
+    }
+}
+
+/// The discriminator `#[derive(AccountDiscriminator)]` gives the type
+/// named `type_name`
+pub fn discriminator(type_name: &str) -> [u8; 8] {
+    let hash = hash(format!("account:{type_name}").as_bytes());
+    hash.to_bytes()[..8].try_into().expect("8 of 32 bytes")
+}
+
+/// Whether `layout` starts with a discriminator, as the registry lays one
+/// out
+fn discriminated(layout: &Layout) -> bool {
+    layout.fields.first().is_some_and(|field| {
+        field.name == "discriminator" && field.ty == FieldType::Array(&FieldType::U8, 8)
+    })
+}
+
+/// `data` decoded as `layout`, field by field; `None` unless it decodes
+/// and uses every byte, as `try_from_slice` requires. A discriminator is
+/// read as bytes like any other field, whatever it holds
+pub fn decode(layout: &Layout, data: &[u8]) -> Option<Vec<(&'static str, Value)>> {
+    let mut rest = data;
+    let fields = layout
+        .fields
+        .iter()
+        .map(|field| Some((field.name, read(&field.ty, &mut rest)?)))
+        .collect::<Option<Vec<_>>>()?;
+    rest.is_empty().then_some(fields)
+}
+
+fn take<'a>(rest: &mut &'a [u8], len: usize) -> Option<&'a [u8]> {
+    let (taken, remaining) = rest.split_at_checked(len)?;
+    *rest = remaining;
+    Some(taken)
+}
+
+fn unsigned(rest: &mut &[u8], bytes: usize) -> Option<u128> {
+    let mut buffer = [0; 16];
+    buffer[..bytes].copy_from_slice(take(rest, bytes)?);
+    Some(u128::from_le_bytes(buffer))
+}
+
+fn signed(rest: &mut &[u8], bytes: usize) -> Option<i128> {
+    let bits = 8 * bytes as u32;
+    let value = unsigned(rest, bytes)?;
+    // Sign-extend from the field's width
+    Some(((value << (128 - bits)) as i128) >> (128 - bits))
+}
+
+fn read(ty: &FieldType, rest: &mut &[u8]) -> Option<Value> {
+    Some(match ty {
+        FieldType::Bool => match take(rest, 1)? {
+            [0] => Value::Bool(false),
+            [1] => Value::Bool(true),
+            _ => return None,
+        },
+        FieldType::U8 => Value::Unsigned(unsigned(rest, 1)?, 8),
+        FieldType::U16 => Value::Unsigned(unsigned(rest, 2)?, 16),
+        FieldType::U32 => Value::Unsigned(unsigned(rest, 4)?, 32),
+        FieldType::U64 => Value::Unsigned(unsigned(rest, 8)?, 64),
+        FieldType::U128 => Value::Unsigned(unsigned(rest, 16)?, 128),
+        FieldType::I8 => Value::Signed(signed(rest, 1)?),
+        FieldType::I16 => Value::Signed(signed(rest, 2)?),
+        FieldType::I32 => Value::Signed(signed(rest, 4)?),
+        FieldType::I64 => Value::Signed(signed(rest, 8)?),
+        FieldType::I128 => Value::Signed(signed(rest, 16)?),
+        FieldType::Pubkey => {
+            Value::Pubkey(Pubkey::new_from_array(take(rest, 32)?.try_into().ok()?))
+        }
+        FieldType::String => {
+            let len = unsigned(rest, 4)? as usize;
+            Value::String(String::from_utf8(take(rest, len)?.to_vec()).ok()?)
+        }
+        FieldType::Array(FieldType::U8, len) => Value::Bytes(take(rest, *len)?.to_vec()),
+        FieldType::Array(element, len) => Value::List(
+            (0..*len)
+                .map(|_| read(element, rest))
+                .collect::<Option<_>>()?,
+        ),
+        FieldType::Vec(element) => {
+            let len = unsigned(rest, 4)? as usize;
+            // Every element takes at least a byte; a longer length is garbage
+            if len > rest.len() {
+                return None;
+            }
+            Value::List(
+                (0..len)
+                    .map(|_| read(element, rest))
+                    .collect::<Option<_>>()?,
+            )
+        }
+        FieldType::Option(inner) => match take(rest, 1)? {
+            [0] => Value::Option(None),
+            [1] => Value::Option(Some(Box::new(read(inner, rest)?))),
+            _ => return None,
+        },
+        FieldType::Other(_) => return None,
+    })
+}
+
+/// A type an account's data decodes as, by its layout
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Fit {
+    pub layout: &'static Layout,
+    /// [`Detection::Discriminator`] if the layout has one, which the data
+    /// starts with; [`Detection::Layout`] if not
+    pub detection: Detection,
+    /// Its fields, after the discriminator
+    pub fields: Vec<(&'static str, Value)>,
+}
+
+/// Every one of `krate`'s layouts that `data` decodes as, in the order
+/// the registry lists them. A layout with a discriminator only fits data
+/// that starts with its own
+pub fn fit(krate: &str, data: &[u8]) -> Vec<Fit> {
+    layouts(krate)
+        .iter()
+        .filter_map(|layout| {
+            let mut fields = decode(layout, data)?;
+            let detection = if discriminated(layout) {
+                if data[..8] != discriminator(layout.name) {
+                    return None;
+                }
+                fields.remove(0);
+                Detection::Discriminator
+            } else {
+                Detection::Layout
+            };
+            Some(Fit {
+                layout,
+                detection,
+                fields,
+            })
+        })
+        .collect()
+}
+
+/// The `T` at `address`, whichever of the lab's programs owns it, by
+/// [`ids::ALL`]. The owner must store a type named as `T` is, which starts
+/// with its discriminator if its layout has one, and the rest of the data
+/// must be exactly one `T`
+pub fn fetch_decoded<T: Inspect + BorshDeserialize>(rpc: &Rpc, address: &Pubkey) -> AnyResult<T> {
+    let (owner, data) = rpc
+        .account(address)?
+        .with_context(|| format!("no account at {address}"))?;
+    let krate = ids::ALL
+        .iter()
+        .find(|(_, id)| *id == owner)
+        .map(|(krate, _)| *krate)
+        .with_context(|| format!("{address} is owned by {owner}, none of the lab's programs"))?;
+    let layout = layouts(krate)
+        .iter()
+        .find(|layout| layout.name == T::NAME)
+        .with_context(|| format!("{krate}, which owns {address}, stores no {}", T::NAME))?;
+    let fields = if discriminated(layout) {
+        let (discriminator, fields) = data.split_at_checked(8).with_context(|| {
+            format!(
+                "{address} holds {} bytes, too few for a {}",
+                data.len(),
+                T::NAME
+            )
+        })?;
+        ensure!(
+            *discriminator != CLOSED_ACCOUNT_DISCRIMINATOR,
+            "{address} was closed"
+        );
+        ensure!(
+            *discriminator == self::discriminator(T::NAME),
+            "{address} is not a {}: it starts with another type's discriminator",
+            T::NAME
+        );
+        fields
+    } else {
+        &data[..]
+    };
+    strict_deserialize(fields)
+        .map_err(|err| anyhow!("decoding {address} as {krate}'s {}: {err}", T::NAME))
+}
@@ -0,0 +1,446 @@
+//! The example programs from outside: their ids, account types and
+//! instructions, and a client that sends them to a cluster
+//!
+//! Exploit PoCs and other tools that talk to a deployed example need what
+//! the lab's tests use in-process: the ids `Programs.toml` lists, the
+//! account layouts in `lab_domain` and the builders in `lab_instructions`.
+//! This crate gathers them, native builds all, so nothing has to
+//! reimplement a program's serialization. [`ids`] holds each program
+//! crate's id for the cluster the crate was built for (`LAB_CLUSTER`,
+//! localnet by default), [`accounts`] and [`instructions`] are the shared
+//! crates as they are, and [`rpc`] is the JSON RPC the `vuln-lab` CLI makes
+//! its cluster calls with. [`decode`] reads any of the programs' accounts
+//! back and shows its fields, with their units, and [`layout`] reads them
+//! by the registry's layouts alone. [`signers`] is where the attacker's,
+//! the victim's and every other role's keypair comes from. [`budget`] is
+//! the compute unit limit and priority fee a client's transactions start
+//! with, and [`confirm`] how they are sent, retried and waited for.
+//! [`simulate`] shows what a transaction would do without sending it, its
+//! [`logs`] read back as invocations and events.
+//!
+//! [`VulnClient`] sends instructions to one program, paid for by one
+//! keypair, and reads back the accounts it owns, decoded. Each example has
+//! a module with a client of its own, a [`VulnClient`] underneath, whose
+//! async methods are the example's instructions, one each, and the
+//! exploit as the registry runs it, against accounts already on the
+//! cluster:
+//!
+//! ```
+//! # use lab_client::{ids, missing_signer_check, rpc::Rpc, Keypair, VulnClient};
+//! # use solana_program::pubkey::Pubkey;
+//! # async fn drain(attacker: Keypair, deposit: Pubkey) -> anyhow::Result<()> {
+//! let rpc = Rpc::new("http://127.0.0.1:8899");
+//! let client = missing_signer_check::Client::new(VulnClient::new(rpc, ids::MISSING_SIGNER_CHECK, attacker));
+//! let stolen = client.exploit_drain(deposit).await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The calls are the blocking ones [`rpc::Rpc`] makes. With the `async`
+//! feature, each runs on tokio's blocking pool when the client is awaited
+//! in a tokio runtime, so it never stalls the runtime; outside one, or
+//! without the feature, each runs where it is awaited. The `blocking`
+//! feature adds [`blocking::Wait`], which runs any of the clients' futures
+//! to completion on the calling thread, for scripts with no runtime at
+//! all; the instructions and decoding are the same code either way:
+//!
+//! ```no_run
+//! use lab_client::blocking::Wait;
+//! # use lab_client::{ids, missing_signer_check, rpc::Rpc, Keypair, VulnClient};
+//! # let rpc = Rpc::new("http://127.0.0.1:8899");
+//! # let client = missing_signer_check::Client::new(VulnClient::new(rpc, ids::MISSING_SIGNER_CHECK, Keypair::new()));
+//! # let deposit = solana_program::pubkey::Pubkey::new_unique();
+//! let stolen = client.exploit_drain(deposit).wait()?;
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+//!
+//! Both features are on by default. The PoCs build with `async` alone, and
+//! the `vuln-lab` CLI, which makes its calls through [`rpc`] and [`decode`]
+//! itself, with neither.
+
+/// An example module's `Client`: a [`VulnClient`] it derefs to, for the
+/// example's instructions to be methods on
+macro_rules! example_client {
+    ($example:literal) => {
+        #[doc = concat!("Sends ", $example, "'s instructions, to either version")]
+        #[derive(Clone)]
+        pub struct Client(crate::VulnClient);
+
+        impl Client {
+            pub fn new(client: crate::VulnClient) -> Self {
+                Self(client)
+            }
+        }
+
+        impl std::ops::Deref for Client {
+            type Target = crate::VulnClient;
+
+            fn deref(&self) -> &crate::VulnClient {
+                &self.0
+            }
+        }
+    };
+}
+
+pub mod account_data_matching;
+pub mod account_versioning;
+pub mod arbitrary_cpi;
+pub mod arithmetic_errors;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod budget;
+pub mod confirm;
+pub mod decode;
+pub mod layout;
+pub mod logs;
+pub mod missing_owner_check;
+pub mod missing_signer_check;
+pub mod pda_issues;
+pub mod reinitialization;
+pub mod rent_exemption;
+pub mod rpc;
+pub mod signers;
+pub mod simulate;
+pub mod type_confusion;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, bail, ensure, Context, Result as AnyResult};
+use borsh::BorshDeserialize;
+use solana_address_lookup_table_interface::instruction as lookup_table;
+use solana_message::{v0, VersionedMessage};
+use solana_program::hash::Hash;
+use solana_program::instruction::Instruction;
+use solana_program::pubkey::Pubkey;
+use solana_system_interface::instruction as system_instruction;
+
+pub use lab_domain as accounts;
+pub use lab_instructions as instructions;
+pub use solana_keypair::Keypair;
+pub use solana_message::AddressLookupTableAccount;
+pub use solana_signature::Signature;
+pub use solana_signer::Signer;
+pub use solana_transaction::versioned::VersionedTransaction;
+pub use solana_transaction::Transaction;
+
+use crate::budget::ComputeBudget;
+use crate::confirm::{Landing, SendStrategy};
+use crate::decode::{Inspect, Inspected};
+use crate::rpc::Rpc;
+use crate::simulate::Projection;
+
+/// Every program crate's id, from `Programs.toml` for the cluster this
+/// crate was built for
+pub mod ids {
+    use solana_program::pubkey::Pubkey;
+
+    include!(concat!(env!("OUT_DIR"), "/ids.rs"));
+
+    /// The id `krate` has on [`CLUSTER`]
+    pub fn id(krate: &str) -> Option<Pubkey> {
+        ALL.iter()
+            .find(|(name, _)| *name == krate)
+            .map(|(_, id)| *id)
+    }
+}
+
+/// Where the Solana CLI keeps its default keypair
+pub fn default_keypair_path() -> PathBuf {
+    let home = std::env::var_os("HOME").unwrap_or_default();
+    Path::new(&home).join(".config/solana/id.json")
+}
+
+/// A keypair file as the Solana CLI writes it: a JSON array of 64 bytes
+pub fn read_keypair(path: &Path) -> AnyResult<Keypair> {
+    let json = fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    let bytes: Vec<u8> = serde_json::from_str(&json)
+        .with_context(|| format!("{} is not a keypair file", path.display()))?;
+    Keypair::try_from(bytes.as_slice())
+        .map_err(|err| anyhow!("{} is not a keypair file: {err}", path.display()))
+}
+
+/// How many addresses one extend instruction adds to a lookup table: as
+/// many as fit in a packet with room to spare
+const LOOKUP_TABLE_EXTEND_CHUNK: usize = 20;
+
+/// Sends instructions to one program, paid for by one keypair
+#[derive(Clone)]
+pub struct VulnClient {
+    rpc: Arc<Rpc>,
+    program_id: Pubkey,
+    payer: Arc<Keypair>,
+    budget: ComputeBudget,
+    strategy: SendStrategy,
+}
+
+impl VulnClient {
+    pub fn new(rpc: Rpc, program_id: Pubkey, payer: Keypair) -> Self {
+        Self {
+            rpc: Arc::new(rpc),
+            program_id,
+            payer: Arc::new(payer),
+            budget: ComputeBudget::default(),
+            strategy: SendStrategy::default(),
+        }
+    }
+
+    /// Every transaction sent starts with `budget`'s instructions
+    pub fn with_budget(mut self, budget: ComputeBudget) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    /// Transactions are sent, retried and waited for as `strategy` says
+    pub fn with_strategy(mut self, strategy: SendStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    pub fn rpc(&self) -> &Rpc {
+        &self.rpc
+    }
+
+    pub fn program_id(&self) -> Pubkey {
+        self.program_id
+    }
+
+    /// Whoever pays, and signs first
+    pub fn payer(&self) -> &Keypair {
+        &self.payer
+    }
+
+    pub fn budget(&self) -> ComputeBudget {
+        self.budget
+    }
+
+    pub fn strategy(&self) -> SendStrategy {
+        self.strategy
+    }
+
+    /// Sends `instructions` in one transaction, after the budget's and
+    /// signed by the payer and `signers`, and waits for it to land, as the
+    /// client's [`SendStrategy`] says
+    pub async fn send(
+        &self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> AnyResult<Signature> {
+        let mut keypairs = vec![&*self.payer];
+        keypairs.extend(signers);
+        let instructions = self.budget.prepend(instructions);
+        self.confirm(|blockhash| {
+            let mut transaction =
+                Transaction::new_with_payer(&instructions, Some(&self.payer.pubkey()));
+            transaction.try_sign(&keypairs, blockhash)?;
+            Ok(transaction.into())
+        })
+        .await
+    }
+
+    /// [`send`](VulnClient::send) as a v0 transaction, which names every
+    /// account `lookup_tables` hold by its index there: one byte where a
+    /// legacy transaction spends 32, so more accounts fit in a packet
+    pub async fn send_v0(
+        &self,
+        instructions: &[Instruction],
+        lookup_tables: &[AddressLookupTableAccount],
+        signers: &[&Keypair],
+    ) -> AnyResult<Signature> {
+        let mut keypairs = vec![&*self.payer];
+        keypairs.extend(signers);
+        let instructions = self.budget.prepend(instructions);
+        self.confirm(|blockhash| {
+            let message = v0::Message::try_compile(
+                &self.payer.pubkey(),
+                &instructions,
+                lookup_tables,
+                blockhash,
+            )?;
+            Ok(VersionedTransaction::try_new(
+                VersionedMessage::V0(message),
+                &keypairs,
+            )?)
+        })
+        .await
+    }
+
+    /// Creates an address lookup table holding `addresses`, the payer its
+    /// authority, and waits until they can be looked up, from the slot
+    /// after the last were added
+    pub async fn create_lookup_table(
+        &self,
+        addresses: &[Pubkey],
+    ) -> AnyResult<AddressLookupTableAccount> {
+        let payer = self.payer.pubkey();
+        let recent_slot = self.call(Rpc::slot).await?;
+        let (create, table) = lookup_table::create_lookup_table(payer, payer, recent_slot);
+        self.send(&[create], &[]).await?;
+        for chunk in addresses.chunks(LOOKUP_TABLE_EXTEND_CHUNK) {
+            let extend =
+                lookup_table::extend_lookup_table(table, payer, Some(payer), chunk.to_vec());
+            self.send(&[extend], &[]).await?;
+        }
+        let strategy = self.strategy;
+        self.call(move |rpc| {
+            let extended = rpc.slot()?;
+            let timeout = strategy.timeout;
+            let deadline = Instant::now() + timeout;
+            while rpc.slot()? <= extended {
+                ensure!(
+                    Instant::now() < deadline,
+                    "the cluster stayed at slot {extended} for {timeout:?}"
+                );
+                thread::sleep(Duration::from_millis(200));
+            }
+            Ok(())
+        })
+        .await?;
+        Ok(AddressLookupTableAccount {
+            key: table,
+            addresses: addresses.to_vec(),
+        })
+    }
+
+    /// The address lookup table at `address`, for [`send_v0`](VulnClient::send_v0)
+    pub async fn lookup_table(&self, address: Pubkey) -> AnyResult<AddressLookupTableAccount> {
+        self.call(move |rpc| rpc.lookup_table(&address)).await
+    }
+
+    /// The lamports at `address`; 0 if there is no account
+    pub async fn lamports(&self, address: Pubkey) -> AnyResult<u64> {
+        self.call(move |rpc| rpc.balance(&address)).await
+    }
+
+    /// The lamports an account of `len` bytes needs to be rent-exempt
+    pub async fn minimum_balance(&self, len: usize) -> AnyResult<u64> {
+        self.call(move |rpc| rpc.minimum_balance(len)).await
+    }
+
+    /// Creates `account` with `len` zeroed bytes the program owns, funded
+    /// by the payer with `lamports` beyond its rent. Every example account
+    /// a program reads starts this way; those whose bytes must hold
+    /// something before the program's first instruction cannot be made
+    /// from outside
+    pub async fn create_account(
+        &self,
+        account: &Keypair,
+        len: usize,
+        lamports: u64,
+    ) -> AnyResult<Signature> {
+        let rent = self.minimum_balance(len).await?;
+        let create = system_instruction::create_account(
+            &self.payer.pubkey(),
+            &account.pubkey(),
+            rent + lamports,
+            len as u64,
+            &self.program_id,
+        );
+        self.send(&[create], &[account]).await
+    }
+
+    /// The `T` at `address`, which the program must own
+    pub async fn account<T: BorshDeserialize>(&self, address: Pubkey) -> AnyResult<T> {
+        let (owner, data) = self
+            .call(move |rpc| rpc.account(&address))
+            .await?
+            .ok_or_else(|| anyhow!("no account at {address}"))?;
+        ensure!(
+            owner == self.program_id,
+            "{address} is owned by {owner}, not {}",
+            self.program_id
+        );
+        T::try_from_slice(&data)
+            .with_context(|| format!("decoding {address} as {}", std::any::type_name::<T>()))
+    }
+
+    /// The `T` at `address`, as [`layout::fetch_decoded`] reads it from
+    /// whichever of the lab's programs owns it
+    pub async fn fetch_decoded<T>(&self, address: Pubkey) -> AnyResult<T>
+    where
+        T: Inspect + BorshDeserialize + Send + 'static,
+    {
+        self.call(move |rpc| layout::fetch_decoded(rpc, &address))
+            .await
+    }
+
+    /// The account at `address` as [`decode::fetch`] shows it, decoded as
+    /// `krate`'s types if this client's program owns it; `None` if there
+    /// is no account
+    pub async fn inspect(&self, address: Pubkey, krate: &str) -> AnyResult<Option<Inspected>> {
+        let krate = krate.to_string();
+        let program_id = self.program_id;
+        self.call(move |rpc| decode::fetch(rpc, &address, &[(&krate, program_id)]))
+            .await
+    }
+
+    /// The transaction [`send`](VulnClient::send) would send first, signed
+    /// with the latest blockhash, for [`simulate`](VulnClient::simulate)
+    /// or to send some other way
+    pub async fn sign(
+        &self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> AnyResult<VersionedTransaction> {
+        let blockhash = self.call(Rpc::latest_blockhash).await?;
+        let mut keypairs = vec![&*self.payer];
+        keypairs.extend(signers);
+        let instructions = self.budget.prepend(instructions);
+        let mut transaction =
+            Transaction::new_with_payer(&instructions, Some(&self.payer.pubkey()));
+        transaction.try_sign(&keypairs, blockhash)?;
+        Ok(transaction.into())
+    }
+
+    /// What `transaction` would do, as [`simulate::simulate`] projects it;
+    /// nothing lands
+    pub async fn simulate(&self, transaction: VersionedTransaction) -> AnyResult<Projection> {
+        self.call(move |rpc| simulate::simulate(rpc, &transaction))
+            .await
+    }
+
+    /// Sends the transaction `sign` signs with a fresh blockhash and waits
+    /// for it to land, signing again each time its blockhash expires first
+    async fn confirm(
+        &self,
+        sign: impl Fn(Hash) -> AnyResult<VersionedTransaction>,
+    ) -> AnyResult<Signature> {
+        let strategy = self.strategy;
+        let mut expired = Vec::new();
+        for _ in 0..strategy.attempts {
+            let (blockhash, last_valid) = self
+                .call(move |rpc| rpc.latest_blockhash_at(strategy.read_commitment()))
+                .await?;
+            let transaction = sign(blockhash)?;
+            let landing = self
+                .call(move |rpc| confirm::send_and_wait(rpc, &transaction, last_valid, &strategy))
+                .await?;
+            match landing {
+                Landing::Landed(signature) => return Ok(signature),
+                Landing::Failed(signature, err) => bail!("transaction {signature} failed: {err}"),
+                Landing::Expired(signature) => expired.push(signature.to_string()),
+            }
+        }
+        bail!(
+            "every attempt's blockhash expired before it landed: {}",
+            expired.join(", ")
+        )
+    }
+
+    /// Runs `call` on tokio's blocking pool when awaited in a runtime, and
+    /// where it is awaited otherwise
+    async fn call<T: Send + 'static>(
+        &self,
+        call: impl FnOnce(&Rpc) -> AnyResult<T> + Send + 'static,
+    ) -> AnyResult<T> {
+        #[cfg(feature = "async")]
+        if tokio::runtime::Handle::try_current().is_ok() {
+            let rpc = Arc::clone(&self.rpc);
+            return tokio::task::spawn_blocking(move || call(&rpc)).await?;
+        }
+        call(&self.rpc)
+    }
+}
@@ -0,0 +1,115 @@
+//! Transaction logs read back: the program invocations they record, what
+//! each logged, and the events it emitted
+//!
+//! The runtime writes a line as each program is invoked and another as it
+//! succeeds or fails, nested by depth, with what the program logs between
+//! them; [`parse`] rebuilds the tree. A `Program data:` line that holds a
+//! [`solana_common::events::Event`] is decoded into it.
+
+use base64::prelude::{Engine, BASE64_STANDARD};
+use solana_common::events::Event;
+use solana_program::pubkey::Pubkey;
+
+/// How an invocation ended
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Succeeded,
+    /// With the error as logged
+    Failed(String),
+    /// The logs stop before it ends
+    Unfinished,
+}
+
+/// One program invocation and the ones it made
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Invocation {
+    pub program_id: Pubkey,
+    /// 1 for the transaction's own instructions
+    pub depth: usize,
+    /// What the program logged itself, without the `Program log: ` prefix
+    pub logs: Vec<String>,
+    /// What it emitted as `Program data:` lines that decode as events
+    pub events: Vec<Event>,
+    pub outcome: Outcome,
+    pub inner: Vec<Invocation>,
+}
+
+/// The invocations a transaction's logs record, in order; lines in no
+/// format the runtime writes are skipped
+pub fn parse(logs: &[String]) -> Vec<Invocation> {
+    let mut top = Vec::new();
+    // The invocations still running, innermost last
+    let mut stack: Vec<Invocation> = Vec::new();
+    for line in logs {
+        if let Some(message) = line.strip_prefix("Program log: ") {
+            if let Some(current) = stack.last_mut() {
+                current.logs.push(message.to_string());
+            }
+            continue;
+        }
+        if let Some(data) = line.strip_prefix("Program data: ") {
+            if let (Some(current), Some(event)) = (stack.last_mut(), event(data)) {
+                current.events.push(event);
+            }
+            continue;
+        }
+        let Some(rest) = line.strip_prefix("Program ") else {
+            continue;
+        };
+        let Some((program, event)) = rest.split_once(' ') else {
+            continue;
+        };
+        let Ok(program_id) = program.parse::<Pubkey>() else {
+            continue;
+        };
+        let outcome = if let Some(depth) = event
+            .strip_prefix("invoke [")
+            .and_then(|depth| depth.strip_suffix(']'))
+        {
+            stack.push(Invocation {
+                program_id,
+                depth: depth.parse().unwrap_or(stack.len() + 1),
+                logs: Vec::new(),
+                events: Vec::new(),
+                outcome: Outcome::Unfinished,
+                inner: Vec::new(),
+            });
+            continue;
+        } else if event == "success" {
+            Outcome::Succeeded
+        } else if let Some(err) = event.strip_prefix("failed: ") {
+            Outcome::Failed(err.to_string())
+        } else {
+            continue;
+        };
+        if stack
+            .last()
+            .is_some_and(|current| current.program_id == program_id)
+        {
+            let mut finished = stack.pop().expect("checked above");
+            finished.outcome = outcome;
+            match stack.last_mut() {
+                Some(caller) => caller.inner.push(finished),
+                None => top.push(finished),
+            }
+        }
+    }
+    // Whatever never ended, nested in its caller
+    while let Some(unfinished) = stack.pop() {
+        match stack.last_mut() {
+            Some(caller) => caller.inner.push(unfinished),
+            None => top.push(unfinished),
+        }
+    }
+    top
+}
+
+/// The event in a `Program data:` line's base64 fields, if they hold one
+fn event(data: &str) -> Option<Event> {
+    let fields = data
+        .split(' ')
+        .map(|field| BASE64_STANDARD.decode(field).ok())
+        .collect::<Option<Vec<_>>>()?;
+    let fields: Vec<&[u8]> = fields.iter().map(Vec::as_slice).collect();
+    Event::decode(&fields)
+}
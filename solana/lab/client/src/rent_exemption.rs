@@ -0,0 +1,29 @@
+//! rent_exemption: opening a record and withdrawing everything from it
+
+use anyhow::Result as AnyResult;
+use lab_instructions::rent_exemption::{initialize_ix, withdraw_all_ix};
+use solana_program::pubkey::Pubkey;
+
+use crate::{Keypair, Signature, Signer};
+
+example_client!("rent_exemption");
+
+impl Client {
+    /// `user` opens `record`
+    pub async fn initialize(&self, record: Pubkey, user: &Keypair) -> AnyResult<Signature> {
+        let initialize = initialize_ix(self.program_id(), record, user.pubkey());
+        self.send(&[initialize], &[user]).await
+    }
+
+    /// `user` withdraws all of `record`'s lamports to `recipient`, rent
+    /// included, which closes it in the vulnerable version
+    pub async fn withdraw_all(
+        &self,
+        record: Pubkey,
+        user: &Keypair,
+        recipient: Pubkey,
+    ) -> AnyResult<Signature> {
+        let withdraw = withdraw_all_ix(self.program_id(), record, user.pubkey(), recipient);
+        self.send(&[withdraw], &[user]).await
+    }
+}
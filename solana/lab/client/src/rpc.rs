@@ -0,0 +1,376 @@
+//! The few Solana JSON RPC calls the CLI makes against a real cluster
+
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, Context, Result as AnyResult};
+use base64::prelude::{Engine, BASE64_STANDARD};
+use serde_json::{json, Value};
+use solana_address_lookup_table_interface::program as lookup_table_program;
+use solana_address_lookup_table_interface::state::AddressLookupTable;
+use solana_message::AddressLookupTableAccount;
+use solana_program::clock::Slot;
+use solana_program::hash::Hash;
+use solana_program::pubkey::Pubkey;
+use solana_signature::Signature;
+use solana_transaction::versioned::VersionedTransaction;
+use solana_transaction::Transaction;
+
+pub struct Rpc {
+    url: String,
+}
+
+/// How settled the cluster's answer must be: seen by the node asked, voted
+/// on by a supermajority, or rooted and beyond rollback
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Commitment {
+    Processed,
+    #[default]
+    Confirmed,
+    Finalized,
+}
+
+impl Commitment {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Commitment::Processed => "processed",
+            Commitment::Confirmed => "confirmed",
+            Commitment::Finalized => "finalized",
+        }
+    }
+
+    fn parse(status: &str) -> Option<Self> {
+        [
+            Commitment::Processed,
+            Commitment::Confirmed,
+            Commitment::Finalized,
+        ]
+        .into_iter()
+        .find(|commitment| commitment.as_str() == status)
+    }
+}
+
+impl FromStr for Commitment {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> AnyResult<Self> {
+        Self::parse(s).ok_or_else(|| anyhow!("{s} is not processed, confirmed or finalized"))
+    }
+}
+
+impl std::fmt::Display for Commitment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Rpc {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self { url: url.into() }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// The `result` of one call, or its `error` as an error
+    pub fn call(&self, method: &str, params: Value) -> AnyResult<Value> {
+        let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let mut response: Value = ureq::post(&self.url)
+            .send_json(request)
+            .with_context(|| format!("{method} to {}", self.url))?
+            .into_json()
+            .with_context(|| format!("reading the {method} response"))?;
+        if let Some(error) = response.get("error") {
+            bail!(
+                "{method} failed: {}",
+                error["message"].as_str().unwrap_or("unknown error")
+            );
+        }
+        Ok(response["result"].take())
+    }
+
+    pub fn latest_blockhash(&self) -> AnyResult<Hash> {
+        Ok(self.latest_blockhash_at(Commitment::Confirmed)?.0)
+    }
+
+    /// The latest blockhash, and the last block height a transaction
+    /// signed with it can land at, if the node says
+    pub fn latest_blockhash_at(&self, commitment: Commitment) -> AnyResult<(Hash, Option<u64>)> {
+        let result = self.call(
+            "getLatestBlockhash",
+            json!([{ "commitment": commitment.as_str() }]),
+        )?;
+        let blockhash = result["value"]["blockhash"]
+            .as_str()
+            .ok_or_else(|| anyhow!("getLatestBlockhash returned no blockhash"))?;
+        Ok((
+            Hash::from_str(blockhash)?,
+            result["value"]["lastValidBlockHeight"].as_u64(),
+        ))
+    }
+
+    pub fn block_height(&self, commitment: Commitment) -> AnyResult<u64> {
+        let result = self.call(
+            "getBlockHeight",
+            json!([{ "commitment": commitment.as_str() }]),
+        )?;
+        result
+            .as_u64()
+            .ok_or_else(|| anyhow!("getBlockHeight returned {result}"))
+    }
+
+    pub fn balance(&self, address: &Pubkey) -> AnyResult<u64> {
+        let result = self.call(
+            "getBalance",
+            json!([address.to_string(), { "commitment": "confirmed" }]),
+        )?;
+        result["value"]
+            .as_u64()
+            .ok_or_else(|| anyhow!("getBalance returned {result}"))
+    }
+
+    pub fn slot(&self) -> AnyResult<Slot> {
+        let result = self.call("getSlot", json!([{ "commitment": "confirmed" }]))?;
+        result
+            .as_u64()
+            .ok_or_else(|| anyhow!("getSlot returned {result}"))
+    }
+
+    pub fn minimum_balance(&self, len: usize) -> AnyResult<u64> {
+        let result = self.call("getMinimumBalanceForRentExemption", json!([len]))?;
+        result
+            .as_u64()
+            .ok_or_else(|| anyhow!("getMinimumBalanceForRentExemption returned {result}"))
+    }
+
+    /// Asks the cluster's faucet for lamports; only devnet, testnet and
+    /// test validators have one
+    pub fn request_airdrop(&self, address: &Pubkey, lamports: u64) -> AnyResult<Signature> {
+        let signature = self.call(
+            "requestAirdrop",
+            json!([address.to_string(), lamports, { "commitment": "confirmed" }]),
+        )?;
+        let signature = signature
+            .as_str()
+            .ok_or_else(|| anyhow!("requestAirdrop returned no signature"))?;
+        Ok(Signature::from_str(signature)?)
+    }
+
+    /// For each signature: `None` while the cluster has not confirmed it,
+    /// then whether it succeeded
+    pub fn signature_statuses(&self, signatures: &[Signature]) -> AnyResult<Vec<Option<bool>>> {
+        Ok(self
+            .signature_statuses_at(signatures, Commitment::Confirmed)?
+            .into_iter()
+            .map(|status| status.map(|landed| landed.is_ok()))
+            .collect())
+    }
+
+    /// For each signature: `None` until it reaches `commitment`, then the
+    /// error it failed with, as the cluster reports it, if it did
+    pub fn signature_statuses_at(
+        &self,
+        signatures: &[Signature],
+        commitment: Commitment,
+    ) -> AnyResult<Vec<Option<Result<(), String>>>> {
+        let signatures: Vec<String> = signatures.iter().map(ToString::to_string).collect();
+        let result = self.call("getSignatureStatuses", json!([signatures]))?;
+        let Value::Array(statuses) = &result["value"] else {
+            bail!("getSignatureStatuses returned {result}");
+        };
+        Ok(statuses
+            .iter()
+            .map(|status| {
+                let reached = status["confirmationStatus"]
+                    .as_str()
+                    .and_then(Commitment::parse)
+                    .is_some_and(|status| status >= commitment);
+                let err = &status["err"];
+                reached.then(|| {
+                    if err.is_null() {
+                        Ok(())
+                    } else {
+                        Err(err.to_string())
+                    }
+                })
+            })
+            .collect())
+    }
+
+    /// Submits a signed transaction; it is simulated first, so a
+    /// transaction the cluster would reject fails here with its logs
+    pub fn send_transaction(&self, transaction: &Transaction) -> AnyResult<Signature> {
+        self.send_encoded(
+            bincode::serialize(transaction)?,
+            Some(Commitment::Confirmed),
+        )
+    }
+
+    /// [`send_transaction`](Rpc::send_transaction), for either kind of
+    /// transaction
+    pub fn send_versioned_transaction(
+        &self,
+        transaction: &VersionedTransaction,
+    ) -> AnyResult<Signature> {
+        self.send_versioned_transaction_with(transaction, Some(Commitment::Confirmed))
+    }
+
+    /// Submits a signed transaction, simulated first against the state at
+    /// `preflight`; with `None`, the cluster takes it unsimulated, and a
+    /// failing transaction lands, failed, and pays its fee
+    pub fn send_versioned_transaction_with(
+        &self,
+        transaction: &VersionedTransaction,
+        preflight: Option<Commitment>,
+    ) -> AnyResult<Signature> {
+        self.send_encoded(bincode::serialize(transaction)?, preflight)
+    }
+
+    fn send_encoded(
+        &self,
+        transaction: Vec<u8>,
+        preflight: Option<Commitment>,
+    ) -> AnyResult<Signature> {
+        let encoded = BASE64_STANDARD.encode(transaction);
+        let config = match preflight {
+            Some(commitment) => {
+                json!({ "encoding": "base64", "preflightCommitment": commitment.as_str() })
+            }
+            None => json!({ "encoding": "base64", "skipPreflight": true }),
+        };
+        let signature = self.call("sendTransaction", json!([encoded, config]))?;
+        let signature = signature
+            .as_str()
+            .ok_or_else(|| anyhow!("sendTransaction returned no signature"))?;
+        Ok(Signature::from_str(signature)?)
+    }
+
+    /// Runs a transaction against the cluster's state without landing it.
+    /// Signatures are not checked and the blockhash is replaced, so a
+    /// transaction signed for another chain still runs; `accounts` are the
+    /// addresses to report balances after for.
+    pub fn simulate_transaction(
+        &self,
+        transaction: &Transaction,
+        accounts: &[Pubkey],
+    ) -> AnyResult<Simulation> {
+        self.simulate_encoded(bincode::serialize(transaction)?, accounts)
+    }
+
+    /// [`simulate_transaction`](Rpc::simulate_transaction), for either kind
+    /// of transaction
+    pub fn simulate_versioned_transaction(
+        &self,
+        transaction: &VersionedTransaction,
+        accounts: &[Pubkey],
+    ) -> AnyResult<Simulation> {
+        self.simulate_encoded(bincode::serialize(transaction)?, accounts)
+    }
+
+    fn simulate_encoded(&self, transaction: Vec<u8>, accounts: &[Pubkey]) -> AnyResult<Simulation> {
+        let encoded = BASE64_STANDARD.encode(transaction);
+        let addresses: Vec<String> = accounts.iter().map(ToString::to_string).collect();
+        let result = self.call(
+            "simulateTransaction",
+            json!([encoded, {
+                "encoding": "base64",
+                "commitment": "confirmed",
+                "sigVerify": false,
+                "replaceRecentBlockhash": true,
+                "accounts": { "encoding": "base64", "addresses": addresses },
+            }]),
+        )?;
+        let value = &result["value"];
+        let Value::Array(after) = &value["accounts"] else {
+            bail!("simulateTransaction returned {result}");
+        };
+        Ok(Simulation {
+            err: (!value["err"].is_null()).then(|| value["err"].to_string()),
+            logs: value["logs"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|line| Some(line.as_str()?.to_string()))
+                .collect(),
+            units_consumed: value["unitsConsumed"].as_u64(),
+            // An account the transaction closes comes back null
+            post_balances: after
+                .iter()
+                .map(|account| account["lamports"].as_u64().unwrap_or(0))
+                .collect(),
+        })
+    }
+
+    /// Owner and data of the account at `address`, if there is one
+    pub fn account(&self, address: &Pubkey) -> AnyResult<Option<(Pubkey, Vec<u8>)>> {
+        let result = self.call(
+            "getAccountInfo",
+            json!([address.to_string(), { "encoding": "base64", "commitment": "confirmed" }]),
+        )?;
+        let account = &result["value"];
+        if account.is_null() {
+            return Ok(None);
+        }
+        let owner = account["owner"].as_str().unwrap_or_default();
+        let data = account["data"][0].as_str().unwrap_or_default();
+        Ok(Some((
+            Pubkey::from_str(owner)?,
+            BASE64_STANDARD
+                .decode(data)
+                .context("account data is not base64")?,
+        )))
+    }
+
+    /// The address lookup table at `address`, every address it holds;
+    /// those added in the current slot cannot be looked up until the next
+    pub fn lookup_table(&self, address: &Pubkey) -> AnyResult<AddressLookupTableAccount> {
+        let (owner, data) = self
+            .account(address)?
+            .ok_or_else(|| anyhow!("no lookup table at {address}"))?;
+        if owner != lookup_table_program::ID {
+            bail!("{address} is owned by {owner}, not the lookup table program");
+        }
+        let table = AddressLookupTable::deserialize(&data)
+            .map_err(|err| anyhow!("decoding lookup table {address}: {err}"))?;
+        Ok(AddressLookupTableAccount {
+            key: *address,
+            addresses: table.addresses.to_vec(),
+        })
+    }
+
+    /// Address and data of every account `program` owns
+    pub fn program_accounts(&self, program: &Pubkey) -> AnyResult<Vec<(Pubkey, Vec<u8>)>> {
+        let result = self.call(
+            "getProgramAccounts",
+            json!([program.to_string(), { "encoding": "base64" }]),
+        )?;
+        let Value::Array(accounts) = result else {
+            bail!("getProgramAccounts returned {result}");
+        };
+        accounts
+            .iter()
+            .map(|entry| {
+                let address = entry["pubkey"].as_str().unwrap_or_default();
+                let data = entry["account"]["data"][0].as_str().unwrap_or_default();
+                Ok((
+                    Pubkey::from_str(address)?,
+                    BASE64_STANDARD
+                        .decode(data)
+                        .context("account data is not base64")?,
+                ))
+            })
+            .collect()
+    }
+}
+
+/// What `simulateTransaction` said a transaction would do
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Simulation {
+    /// The error it would fail with, as the cluster reports it
+    pub err: Option<String>,
+    pub logs: Vec<String>,
+    /// The compute units it would use, if the node says
+    pub units_consumed: Option<u64>,
+    /// Each requested account's lamports afterwards
+    pub post_balances: Vec<u64>,
+}
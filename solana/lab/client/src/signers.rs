@@ -0,0 +1,159 @@
+//! Where the demos' identities come from
+//!
+//! Everyone a demo signs for, the attacker, the victim, the owner of a
+//! vault, is a role, and a [`SignerProvider`] hands out one keypair per
+//! role, the same one each time it is asked. [`KeypairFiles`] reads a
+//! role's from a keypair file as the Solana CLI writes them, [`Ephemeral`]
+//! makes a fresh one per role that lasts as long as the provider does, and
+//! [`Workshop`] derives each from a seed phrase, so everyone in a workshop
+//! who uses the instructor's phrase gets the instructor's addresses. The
+//! `vuln-lab` CLI's fixtures are a [`Workshop`], and the PoCs take theirs
+//! from one when run with `--signers workshop`:
+//!
+//! ```
+//! # use lab_client::default_keypair_path;
+//! # use lab_client::signers::{Ephemeral, KeypairFiles, SignerProvider, ATTACKER, OWNER};
+//! let signers = KeypairFiles::default()
+//!     .with(ATTACKER, default_keypair_path())
+//!     .or(Ephemeral::default());
+//! let owner = signers.keypair(OWNER)?;
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use anyhow::{bail, Result as AnyResult};
+use solana_program::hash::hashv;
+use solana_program::pubkey::Pubkey;
+
+use crate::{read_keypair, Keypair, Signer};
+
+/// Runs the exploit, and pays for whatever the PoCs set up
+pub const ATTACKER: &str = "attacker";
+/// Stands in for honest users
+pub const VICTIM: &str = "victim";
+/// Deploys programs and creates and initializes their accounts
+pub const OWNER: &str = "owner";
+
+/// The workshop phrase when nothing sets another
+pub const DEFAULT_PHRASE: &str = "vulnerable smart contract examples";
+
+/// The environment variable [`Workshop::from_env`] reads the phrase from
+pub const PHRASE_VAR: &str = "VULN_LAB_SEED_PHRASE";
+
+/// Hands out one keypair per role
+pub trait SignerProvider: Send + Sync {
+    /// `role`'s keypair; the same role always gets the same one
+    fn keypair(&self, role: &str) -> AnyResult<Keypair>;
+
+    /// `role`'s address
+    fn pubkey(&self, role: &str) -> AnyResult<Pubkey> {
+        Ok(self.keypair(role)?.pubkey())
+    }
+}
+
+/// Each role's keypair from its own file, and any other role's from a
+/// fallback
+#[derive(Default)]
+pub struct KeypairFiles {
+    files: BTreeMap<String, PathBuf>,
+    fallback: Option<Box<dyn SignerProvider>>,
+}
+
+impl KeypairFiles {
+    /// `role` signs with the keypair in `path`
+    pub fn with(mut self, role: &str, path: impl Into<PathBuf>) -> Self {
+        self.files.insert(role.to_string(), path.into());
+        self
+    }
+
+    /// Roles with no file of their own get their keypair from `fallback`
+    pub fn or(mut self, fallback: impl SignerProvider + 'static) -> Self {
+        self.fallback = Some(Box::new(fallback));
+        self
+    }
+}
+
+impl SignerProvider for KeypairFiles {
+    fn keypair(&self, role: &str) -> AnyResult<Keypair> {
+        match (self.files.get(role), &self.fallback) {
+            (Some(path), _) => read_keypair(path),
+            (None, Some(fallback)) => fallback.keypair(role),
+            (None, None) => bail!("no keypair file for the {role}"),
+        }
+    }
+}
+
+/// A fresh keypair per role, never written anywhere; its roles are gone
+/// with it
+#[derive(Default)]
+pub struct Ephemeral {
+    keypairs: Mutex<HashMap<String, Keypair>>,
+}
+
+impl SignerProvider for Ephemeral {
+    fn keypair(&self, role: &str) -> AnyResult<Keypair> {
+        let mut keypairs = self.keypairs.lock().unwrap();
+        Ok(keypairs
+            .entry(role.to_string())
+            .or_insert_with(Keypair::new)
+            .insecure_clone())
+    }
+}
+
+/// Every role's keypair derived from SHA-256 of a seed phrase and the
+/// role, so the same phrase always gives the same keypairs. Anyone who
+/// knows the phrase can sign with them, so nothing of value belongs in
+/// them
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Workshop {
+    phrase: String,
+}
+
+impl Workshop {
+    /// Runs of whitespace in `phrase` count as one space, so a phrase read
+    /// off a slide matches however it was typed
+    pub fn new(phrase: &str) -> Self {
+        Self {
+            phrase: phrase.split_whitespace().collect::<Vec<_>>().join(" "),
+        }
+    }
+
+    /// `$VULN_LAB_SEED_PHRASE`'s, else [`DEFAULT_PHRASE`]'s
+    pub fn from_env() -> Self {
+        match std::env::var(PHRASE_VAR) {
+            Ok(phrase) if !phrase.trim().is_empty() => Self::new(&phrase),
+            _ => Self::default(),
+        }
+    }
+
+    pub fn phrase(&self) -> &str {
+        &self.phrase
+    }
+
+    /// The keypair for `label`, a role or any other name, such as
+    /// `missing_signer_check/deposit` for an account
+    pub fn derive(&self, label: &str) -> Keypair {
+        let seed = hashv(&[
+            b"vuln-lab fixture",
+            self.phrase.as_bytes(),
+            b"\0",
+            label.as_bytes(),
+        ]);
+        Keypair::new_from_array(seed.to_bytes())
+    }
+}
+
+impl Default for Workshop {
+    fn default() -> Self {
+        Self::new(DEFAULT_PHRASE)
+    }
+}
+
+impl SignerProvider for Workshop {
+    fn keypair(&self, role: &str) -> AnyResult<Keypair> {
+        Ok(self.derive(role))
+    }
+}
@@ -0,0 +1,138 @@
+//! What a transaction would do, without it landing
+//!
+//! [`simulate`] runs a transaction through the cluster's
+//! `simulateTransaction` and reads it back as a [`Projection`]: whether it
+//! would fail, the program invocations its logs record, with the events
+//! they emit, the compute units it would use and every balance it would
+//! change. Nothing lands and nobody pays, so a test can assert on an
+//! exploit's effect and leave the cluster as it was, and the `vuln-lab`
+//! CLI's dry run shows an attack's effect before anyone sends it. The
+//! accounts are read at `confirmed` first, so the balances are a
+//! projection from there.
+
+use anyhow::{anyhow, Result as AnyResult};
+use solana_common::events::Event;
+use solana_program::pubkey::Pubkey;
+use solana_transaction::versioned::VersionedTransaction;
+
+use crate::logs::{self, Invocation};
+use crate::rpc::Rpc;
+
+/// One account's lamports before and after a transaction
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BalanceChange {
+    pub address: Pubkey,
+    pub before: u64,
+    pub after: u64,
+}
+
+impl BalanceChange {
+    pub fn delta(&self) -> i128 {
+        i128::from(self.after) - i128::from(self.before)
+    }
+}
+
+/// What a simulated transaction would have done
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Projection {
+    /// Why it would fail, if it would
+    pub err: Option<String>,
+    pub logs: Vec<String>,
+    /// The logs, read back as the invocations they record
+    pub invocations: Vec<Invocation>,
+    /// The compute units it would use, where the runtime says
+    pub compute_units: Option<u64>,
+    /// Only the balances that would change, in the transaction's order
+    pub changes: Vec<BalanceChange>,
+}
+
+impl Projection {
+    /// `keys`' balances, `before` and `after`, line up with each other
+    pub fn new(
+        err: Option<String>,
+        logs: Vec<String>,
+        compute_units: Option<u64>,
+        keys: &[Pubkey],
+        before: &[u64],
+        after: &[u64],
+    ) -> Self {
+        let changes = keys
+            .iter()
+            .zip(before.iter().zip(after))
+            .filter(|(_, (before, after))| before != after)
+            .map(|(address, (&before, &after))| BalanceChange {
+                address: *address,
+                before,
+                after,
+            })
+            .collect();
+        Projection {
+            err,
+            invocations: logs::parse(&logs),
+            logs,
+            compute_units,
+            changes,
+        }
+    }
+
+    /// Every event any invocation emitted, in the order they were logged
+    pub fn events(&self) -> Vec<&Event> {
+        fn collect<'a>(invocations: &'a [Invocation], events: &mut Vec<&'a Event>) {
+            for invocation in invocations {
+                events.extend(&invocation.events);
+                collect(&invocation.inner, events);
+            }
+        }
+        let mut events = Vec::new();
+        collect(&self.invocations, &mut events);
+        events
+    }
+
+    /// What `address`'s balance would change by; 0 if it would not
+    pub fn delta(&self, address: &Pubkey) -> i128 {
+        self.changes
+            .iter()
+            .find(|change| change.address == *address)
+            .map_or(0, BalanceChange::delta)
+    }
+}
+
+/// Simulates `transaction` on the cluster behind `rpc`, reading each of
+/// its accounts' balances first, those it looks up in the cluster's tables
+/// included. Signatures are not checked and the blockhash is replaced, so
+/// an unsigned transaction simulates as well as a signed one
+pub fn simulate(rpc: &Rpc, transaction: &VersionedTransaction) -> AnyResult<Projection> {
+    let message = &transaction.message;
+    let mut keys = message.static_account_keys().to_vec();
+    let mut readonly = Vec::new();
+    for lookup in message.address_table_lookups().into_iter().flatten() {
+        let table = rpc.lookup_table(&lookup.account_key)?;
+        let address = |index: &u8| {
+            table
+                .addresses
+                .get(usize::from(*index))
+                .copied()
+                .ok_or_else(|| anyhow!("lookup table {} has no index {index}", lookup.account_key))
+        };
+        for index in &lookup.writable_indexes {
+            keys.push(address(index)?);
+        }
+        for index in &lookup.readonly_indexes {
+            readonly.push(address(index)?);
+        }
+    }
+    keys.extend(readonly);
+    let before = keys
+        .iter()
+        .map(|key| rpc.balance(key))
+        .collect::<AnyResult<Vec<_>>>()?;
+    let simulation = rpc.simulate_versioned_transaction(transaction, &keys)?;
+    Ok(Projection::new(
+        simulation.err,
+        simulation.logs,
+        simulation.units_consumed,
+        &keys,
+        &before,
+        &simulation.post_balances,
+    ))
+}
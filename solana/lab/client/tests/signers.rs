@@ -0,0 +1,56 @@
+//! Signer providers: each role keeps its keypair for as long as the
+//! provider lasts, and a workshop's follow from its phrase alone
+
+use lab_client::signers::{
+    Ephemeral, KeypairFiles, SignerProvider, Workshop, ATTACKER, OWNER, VICTIM,
+};
+use lab_client::{Keypair, Signer};
+
+#[test]
+fn files_fall_back_and_ephemeral_roles_keep_their_keypairs() {
+    let attacker = Keypair::new();
+    let path = std::env::temp_dir().join(format!("signers-{}.json", attacker.pubkey()));
+    std::fs::write(&path, format!("{:?}", attacker.to_bytes())).unwrap();
+
+    let alone = KeypairFiles::default().with(ATTACKER, &path);
+    assert_eq!(alone.pubkey(ATTACKER).unwrap(), attacker.pubkey());
+    assert!(alone.keypair(VICTIM).is_err(), "no file and no fallback");
+
+    let signers = KeypairFiles::default()
+        .with(ATTACKER, &path)
+        .or(Ephemeral::default());
+    let victim = signers.pubkey(VICTIM).unwrap();
+    assert_eq!(signers.pubkey(VICTIM).unwrap(), victim);
+    assert_ne!(signers.pubkey(OWNER).unwrap(), victim);
+    assert_eq!(signers.pubkey(ATTACKER).unwrap(), attacker.pubkey());
+    std::fs::remove_file(path).unwrap();
+
+    assert_ne!(
+        Ephemeral::default().pubkey(VICTIM).unwrap(),
+        Ephemeral::default().pubkey(VICTIM).unwrap(),
+        "each provider makes its own"
+    );
+}
+
+#[test]
+fn a_workshop_derives_every_role_from_its_phrase() {
+    let workshop = Workshop::new("room 101 workshop");
+    assert_eq!(
+        workshop.pubkey(VICTIM).unwrap(),
+        Workshop::new(" room  101\nworkshop")
+            .pubkey(VICTIM)
+            .unwrap()
+    );
+    assert_eq!(
+        workshop.pubkey(OWNER).unwrap(),
+        workshop.derive(OWNER).pubkey()
+    );
+    assert_ne!(
+        workshop.pubkey(VICTIM).unwrap(),
+        workshop.pubkey(ATTACKER).unwrap()
+    );
+    assert_ne!(
+        workshop.pubkey(VICTIM).unwrap(),
+        Workshop::default().pubkey(VICTIM).unwrap()
+    );
+}
@@ -0,0 +1,427 @@
+//! Static detector for the anti-patterns the Solana examples demonstrate
+//!
+//! Parses a program's source with `syn` and walks each function in source
+//! order, remembering which accounts it has checked so far. It flags:
+//!
+//! - lamports debited before any account's `is_signer` is read, directly
+//!   or by `assert_signer`, `only_role` or `only_authority`
+//! - account data deserialized before that account's `owner` is read,
+//!   directly or by `assert_owned_by`
+//! - `+=` / `-=` on a `u64` struct field that no earlier comparison bounds
+//!
+//! The struct fields are the file's own and those of the `lab_domain`
+//! types it imports, whose sources are compiled in.
+//!
+//! It knows nothing about types or control flow beyond that, so it misses
+//! bugs (an arbitrary CPI target, a reused PDA) and flags code that is
+//! safe for reasons it cannot see; `vuln-lab scan` shows how its findings
+//! compare with the registry's ground truth.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result as AnyResult};
+use lab_registry::Category;
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+use syn::{
+    BinOp, Expr, Fields, ImplItemFn, Item, ItemFn, ItemStruct, ItemUse, Member, Type, UnOp, UseTree,
+};
+
+/// An anti-pattern the detector looks for
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Rule {
+    MissingSignerCheck,
+    MissingOwnerCheck,
+    UncheckedArithmetic,
+}
+
+impl Rule {
+    pub const ALL: [Rule; 3] = [
+        Rule::MissingSignerCheck,
+        Rule::MissingOwnerCheck,
+        Rule::UncheckedArithmetic,
+    ];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            Rule::MissingSignerCheck => "missing-signer-check",
+            Rule::MissingOwnerCheck => "missing-owner-check",
+            Rule::UncheckedArithmetic => "unchecked-arithmetic",
+        }
+    }
+
+    /// The registry category a finding of this rule is evidence for
+    pub fn category(self) -> Category {
+        match self {
+            Rule::MissingSignerCheck => Category::MissingSignerCheck,
+            Rule::MissingOwnerCheck => Category::MissingOwnerCheck,
+            Rule::UncheckedArithmetic => Category::ArithmeticOverflow,
+        }
+    }
+
+    /// The rule that detects `category`, if any does
+    pub fn for_category(category: Category) -> Option<Rule> {
+        Rule::ALL
+            .into_iter()
+            .find(|rule| rule.category() == category)
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.id())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Finding {
+    pub rule: Rule,
+    /// The function the pattern is in
+    pub function: String,
+    /// 1-based
+    pub line: usize,
+    /// 1-based
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{} {} in {}: {}",
+            self.line, self.column, self.rule, self.function, self.message
+        )
+    }
+}
+
+/// Scans one source file
+pub fn scan_file(path: &Path) -> AnyResult<Vec<Finding>> {
+    let source = fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    scan(&source).with_context(|| format!("parsing {}", path.display()))
+}
+
+/// Scans Rust source, returning findings in source order
+pub fn scan(source: &str) -> syn::Result<Vec<Finding>> {
+    let file = syn::parse_file(source)?;
+
+    let mut fields = U64Fields::default();
+    fields.visit_file(&file);
+    for source in DOMAIN {
+        let domain = syn::parse_file(source).expect("lab_domain's sources parse");
+        for item in &domain.items {
+            if let Item::Struct(item) = item {
+                if fields.imported.contains(&item.ident.to_string()) {
+                    fields.visit_item_struct(item);
+                }
+            }
+        }
+    }
+    let mut functions = Functions {
+        u64_fields: fields.fields,
+        findings: Vec::new(),
+    };
+    functions.visit_file(&file);
+
+    let mut findings = functions.findings;
+    findings.sort_by_key(|finding| (finding.line, finding.column));
+    Ok(findings)
+}
+
+/// The shared account types the examples import from `lab_domain`
+const DOMAIN: [&str; 3] = [
+    include_str!("../../domain/src/escrow.rs"),
+    include_str!("../../domain/src/staking.rs"),
+    include_str!("../../domain/src/vault.rs"),
+];
+
+/// Names of every `u64` field of every struct in the file, and the types
+/// it imports from `lab_domain`
+#[derive(Default)]
+struct U64Fields {
+    fields: HashSet<String>,
+    imported: HashSet<String>,
+}
+
+impl<'ast> Visit<'ast> for U64Fields {
+    fn visit_item_struct(&mut self, item: &'ast ItemStruct) {
+        if let Fields::Named(fields) = &item.fields {
+            for field in &fields.named {
+                if let (Some(name), Type::Path(ty)) = (&field.ident, &field.ty) {
+                    if ty.path.is_ident("u64") {
+                        self.fields.insert(name.to_string());
+                    }
+                }
+            }
+        }
+    }
+
+    fn visit_item_use(&mut self, item: &'ast ItemUse) {
+        if let UseTree::Path(path) = &item.tree {
+            if path.ident == "lab_domain" {
+                imported_names(&path.tree, &mut self.imported);
+            }
+        }
+    }
+}
+
+/// Every name a `use` tree brings in
+fn imported_names(tree: &UseTree, names: &mut HashSet<String>) {
+    match tree {
+        UseTree::Path(path) => imported_names(&path.tree, names),
+        UseTree::Name(name) => {
+            names.insert(name.ident.to_string());
+        }
+        UseTree::Rename(rename) => {
+            names.insert(rename.ident.to_string());
+        }
+        UseTree::Group(group) => {
+            for tree in &group.items {
+                imported_names(tree, names);
+            }
+        }
+        UseTree::Glob(_) => {}
+    }
+}
+
+/// Hands every function body to a fresh [`Scan`]
+struct Functions {
+    u64_fields: HashSet<String>,
+    findings: Vec<Finding>,
+}
+
+impl<'ast> Visit<'ast> for Functions {
+    fn visit_item_fn(&mut self, item: &'ast ItemFn) {
+        self.scan(item.sig.ident.to_string(), |scan| {
+            scan.visit_block(&item.block)
+        });
+    }
+
+    fn visit_impl_item_fn(&mut self, item: &'ast ImplItemFn) {
+        self.scan(item.sig.ident.to_string(), |scan| {
+            scan.visit_block(&item.block)
+        });
+    }
+}
+
+impl Functions {
+    fn scan(&mut self, function: String, body: impl FnOnce(&mut Scan)) {
+        let mut scan = Scan {
+            function,
+            u64_fields: &self.u64_fields,
+            signer_checked: false,
+            owner_checked: HashSet::new(),
+            bounded: HashSet::new(),
+            findings: Vec::new(),
+        };
+        body(&mut scan);
+        self.findings.append(&mut scan.findings);
+    }
+}
+
+/// One function body, walked in source order
+struct Scan<'a> {
+    function: String,
+    u64_fields: &'a HashSet<String>,
+    /// Some account's `is_signer` has been read, or it was passed to
+    /// `assert_signer`, `only_role` or `only_authority`
+    signer_checked: bool,
+    /// Accounts whose `owner` has been read, or passed to `assert_owned_by`
+    owner_checked: HashSet<String>,
+    /// Field paths (`user_data.balance`) that appeared in a comparison
+    bounded: HashSet<String>,
+    findings: Vec<Finding>,
+}
+
+impl Scan<'_> {
+    fn flag(&mut self, rule: Rule, node: &impl Spanned, message: String) {
+        let start = node.span().start();
+        self.findings.push(Finding {
+            rule,
+            function: self.function.clone(),
+            line: start.line,
+            column: start.column + 1,
+            message,
+        });
+    }
+}
+
+impl<'ast> Visit<'ast> for Scan<'_> {
+    fn visit_expr_field(&mut self, expr: &'ast syn::ExprField) {
+        if let Member::Named(member) = &expr.member {
+            if member == "is_signer" {
+                self.signer_checked = true;
+            } else if member == "owner" {
+                if let Some(account) = ident(&expr.base) {
+                    self.owner_checked.insert(account);
+                }
+            }
+        }
+        visit::visit_expr_field(self, expr);
+    }
+
+    fn visit_expr_binary(&mut self, expr: &'ast syn::ExprBinary) {
+        match expr.op {
+            BinOp::Lt(_) | BinOp::Le(_) | BinOp::Gt(_) | BinOp::Ge(_) => {
+                self.bounded.extend(field_path(&expr.left));
+                self.bounded.extend(field_path(&expr.right));
+            }
+            BinOp::SubAssign(_) => {
+                if let Some(account) = lamports_account(&expr.left) {
+                    if !self.signer_checked {
+                        self.flag(
+                            Rule::MissingSignerCheck,
+                            expr,
+                            format!(
+                                "lamports debited from `{account}` before any `is_signer` check"
+                            ),
+                        );
+                    }
+                }
+            }
+            _ => {}
+        }
+        if let BinOp::AddAssign(_) | BinOp::SubAssign(_) = expr.op {
+            if let Expr::Field(field) = &*expr.left {
+                if let (Member::Named(name), Some(path)) = (&field.member, field_path(&expr.left)) {
+                    if self.u64_fields.contains(&name.to_string()) && !self.bounded.contains(&path)
+                    {
+                        let op = if let BinOp::AddAssign(_) = expr.op {
+                            "+="
+                        } else {
+                            "-="
+                        };
+                        self.flag(
+                            Rule::UncheckedArithmetic,
+                            expr,
+                            format!("unchecked `{op}` on u64 field `{path}`"),
+                        );
+                    }
+                }
+            }
+        }
+        visit::visit_expr_binary(self, expr);
+    }
+
+    fn visit_expr_assign(&mut self, expr: &'ast syn::ExprAssign) {
+        if let Some(account) = lamports_account(&expr.left) {
+            if !self.signer_checked {
+                self.flag(
+                    Rule::MissingSignerCheck,
+                    expr,
+                    format!("lamports of `{account}` set before any `is_signer` check"),
+                );
+            }
+        }
+        visit::visit_expr_assign(self, expr);
+    }
+
+    fn visit_expr_call(&mut self, expr: &'ast syn::ExprCall) {
+        if let Expr::Path(function) = &*expr.func {
+            let name = function.path.segments.last().map(|segment| &segment.ident);
+            // solana_common::validation's helpers read the same fields, as
+            // does access_control's guard
+            if name.is_some_and(|name| name == "assert_signer" || name == "only_role") {
+                self.signer_checked = true;
+            }
+            if name.is_some_and(|name| name == "assert_owned_by") {
+                self.owner_checked.extend(expr.args.first().and_then(ident));
+            }
+            if name.is_some_and(|name| {
+                name == "try_from_slice"
+                    || name == "deserialize"
+                    || name == "unpack"
+                    || name == "strict_deserialize"
+                    || name == "lenient_deserialize"
+            }) {
+                for account in expr.args.iter().filter_map(data_account) {
+                    if !self.owner_checked.contains(&account) {
+                        self.flag(
+                            Rule::MissingOwnerCheck,
+                            expr,
+                            format!("`{account}` deserialized before its `owner` is checked"),
+                        );
+                    }
+                }
+            }
+        }
+        visit::visit_expr_call(self, expr);
+    }
+
+    fn visit_expr_method_call(&mut self, expr: &'ast syn::ExprMethodCall) {
+        // TwoStepAuthority's guard
+        if expr.method == "only_authority" {
+            self.signer_checked = true;
+        }
+        visit::visit_expr_method_call(self, expr);
+    }
+}
+
+/// `name` for a single-identifier path
+fn ident(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Path(path) => path.path.get_ident().map(ToString::to_string),
+        Expr::Paren(inner) => ident(&inner.expr),
+        _ => None,
+    }
+}
+
+/// `a.b.c` for a chain of named fields on an identifier
+fn field_path(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Field(field) => match &field.member {
+            Member::Named(name) => Some(format!("{}.{name}", field_path(&field.base)?)),
+            Member::Unnamed(_) => None,
+        },
+        other => ident(other),
+    }
+}
+
+/// Strips `*`, `&`, `?` and parentheses
+fn peel(expr: &Expr) -> &Expr {
+    match expr {
+        Expr::Unary(unary) if matches!(unary.op, UnOp::Deref(_)) => peel(&unary.expr),
+        Expr::Reference(reference) => peel(&reference.expr),
+        Expr::Try(try_expr) => peel(&try_expr.expr),
+        Expr::Paren(paren) => peel(&paren.expr),
+        other => other,
+    }
+}
+
+/// The account in `**account.try_borrow_mut_lamports()?` or
+/// `**account.lamports.borrow_mut()`
+fn lamports_account(expr: &Expr) -> Option<String> {
+    let Expr::MethodCall(call) = peel(expr) else {
+        return None;
+    };
+    if call.method == "try_borrow_mut_lamports" {
+        return ident(&call.receiver);
+    }
+    match peel(&call.receiver) {
+        Expr::Field(field) if call.method == "borrow_mut" => match &field.member {
+            Member::Named(name) if name == "lamports" => ident(&field.base),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The account in `&account.data.borrow()` or `&account.try_borrow_data()?`
+fn data_account(expr: &Expr) -> Option<String> {
+    let Expr::MethodCall(call) = peel(expr) else {
+        return None;
+    };
+    if call.method == "try_borrow_data" || call.method == "try_borrow_mut_data" {
+        return ident(&call.receiver);
+    }
+    match peel(&call.receiver) {
+        Expr::Field(field) => match &field.member {
+            Member::Named(name) if name == "data" => ident(&field.base),
+            _ => None,
+        },
+        _ => None,
+    }
+}
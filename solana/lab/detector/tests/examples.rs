@@ -0,0 +1,98 @@
+//! The detector flags the examples it covers and not their fixes
+
+use std::path::{Path, PathBuf};
+
+use lab_detector::{scan, scan_file, Rule};
+
+fn example(path: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../..")
+        .join(path)
+}
+
+fn rules(path: &str) -> Vec<Rule> {
+    scan_file(&example(path))
+        .unwrap()
+        .into_iter()
+        .map(|finding| finding.rule)
+        .collect()
+}
+
+const COVERED: [(&str, Rule); 3] = [
+    ("missing_signer_check", Rule::MissingSignerCheck),
+    ("missing_owner_check", Rule::MissingOwnerCheck),
+    ("arithmetic_errors", Rule::UncheckedArithmetic),
+];
+
+#[test]
+fn flags_the_examples_it_covers() {
+    for (name, rule) in COVERED {
+        assert!(
+            rules(&format!("{name}.rs")).contains(&rule),
+            "{rule} not flagged in {name}.rs"
+        );
+    }
+}
+
+#[test]
+fn does_not_flag_their_fixes() {
+    for (name, rule) in COVERED {
+        assert!(
+            !rules(&format!("secure/{name}.rs")).contains(&rule),
+            "{rule} flagged in secure/{name}.rs"
+        );
+    }
+}
+
+#[test]
+fn checks_must_come_first() {
+    let findings = scan(
+        r#"
+        struct Pool { total: u64 }
+
+        fn withdraw(pool: &mut Pool, user: &AccountInfo, vault: &AccountInfo, amount: u64) {
+            **user.try_borrow_mut_lamports()? -= amount;
+            if !user.is_signer {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            let data = Vault::try_from_slice(&vault.data.borrow())?;
+            if vault.owner != program_id {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            if pool.total < amount {
+                return Err(ProgramError::InsufficientFunds);
+            }
+            pool.total -= amount;
+            pool.total += amount;
+        }
+        "#,
+    )
+    .unwrap();
+    let flagged: Vec<(Rule, usize)> = findings
+        .iter()
+        .map(|finding| (finding.rule, finding.line))
+        .collect();
+    assert_eq!(
+        flagged,
+        [(Rule::MissingSignerCheck, 5), (Rule::MissingOwnerCheck, 9)]
+    );
+}
+
+#[test]
+fn access_control_guards_check_the_signer() {
+    let findings = scan(
+        r#"
+        fn withdraw(vault: &AccountInfo, authority: &AccountInfo, data: &VaultData) {
+            only_role(authority, &data.authority, Role::TREASURER)?;
+            **vault.try_borrow_mut_lamports()? -= 1;
+        }
+
+        fn distribute(payroll: &AccountInfo, authority: &AccountInfo, data: &Payroll) {
+            data.authority.only_authority(authority)?;
+            **payroll.try_borrow_mut_lamports()? -= 1;
+        }
+        "#,
+    )
+    .unwrap();
+    assert!(findings.is_empty(), "{findings:?}");
+}
@@ -0,0 +1,86 @@
+//! [`Arbitrary`] for every account type here, behind the `arbitrary`
+//! feature, so fuzz targets and property tests can build accounts that
+//! hold a well-formed value of the right type instead of random bytes
+//!
+//! Every field is arbitrary, including ones no honest instruction would
+//! write, such as a fee over 100% or a release time before the epoch:
+//! what a program does with a state it did not expect is the point. A key
+//! is any 32 bytes.
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+use solana_program::pubkey::Pubkey;
+
+use crate::{EscrowAccount, StakingPool, UserProfile, UserStake, VaultConfig};
+
+fn pubkey(u: &mut Unstructured) -> Result<Pubkey> {
+    Ok(Pubkey::new_from_array(u.arbitrary()?))
+}
+
+impl<'a> Arbitrary<'a> for StakingPool {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Self {
+            total_staked: u.arbitrary()?,
+            reward_rate: u.arbitrary()?,
+            last_update: u.arbitrary()?,
+        })
+    }
+
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        (Self::LEN, Some(Self::LEN))
+    }
+}
+
+impl<'a> Arbitrary<'a> for UserStake {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Self {
+            amount: u.arbitrary()?,
+            last_claim: u.arbitrary()?,
+        })
+    }
+
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        (Self::LEN, Some(Self::LEN))
+    }
+}
+
+impl<'a> Arbitrary<'a> for VaultConfig {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Self {
+            authority: pubkey(u)?,
+            total_deposited: u.arbitrary()?,
+            fee_percentage: u.arbitrary()?,
+        })
+    }
+
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        (Self::LEN, Some(Self::LEN))
+    }
+}
+
+impl<'a> Arbitrary<'a> for EscrowAccount {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Self {
+            beneficiary: pubkey(u)?,
+            amount: u.arbitrary()?,
+            release_time: u.arbitrary()?,
+        })
+    }
+
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        (Self::LEN, Some(Self::LEN))
+    }
+}
+
+impl<'a> Arbitrary<'a> for UserProfile {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Self {
+            owner: pubkey(u)?,
+            escrow_account: pubkey(u)?,
+            total_deposits: u.arbitrary()?,
+        })
+    }
+
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        (Self::LEN, Some(Self::LEN))
+    }
+}
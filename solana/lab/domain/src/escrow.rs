@@ -0,0 +1,44 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::pubkey::Pubkey;
+
+/// A user, and the escrow their deposits are held in
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct UserProfile {
+    pub owner: Pubkey,
+    pub escrow_account: Pubkey,
+    pub total_deposits: u64,
+}
+
+impl UserProfile {
+    /// Its fields: owner, escrow_account, total_deposits
+    pub const LEN: usize = 32 + 32 + 8;
+
+    pub fn new(owner: Pubkey, escrow_account: Pubkey, total_deposits: u64) -> Self {
+        Self {
+            owner,
+            escrow_account,
+            total_deposits,
+        }
+    }
+}
+
+/// Lamports held for `beneficiary` until `release_time`
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct EscrowAccount {
+    pub beneficiary: Pubkey,
+    pub amount: u64,
+    pub release_time: i64,
+}
+
+impl EscrowAccount {
+    /// Its fields: beneficiary, amount, release_time
+    pub const LEN: usize = 32 + 8 + 8;
+
+    pub fn new(beneficiary: Pubkey, amount: u64, release_time: i64) -> Self {
+        Self {
+            beneficiary,
+            amount,
+            release_time,
+        }
+    }
+}
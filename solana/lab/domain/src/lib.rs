@@ -0,0 +1,29 @@
+//! Account types more than one example stores
+//!
+//! A vault's config, an escrow and the profile that points at it, a
+//! staking pool and a stake in it: each is declared once here, with its
+//! Borsh size as `LEN` and a constructor for a new one, and both versions
+//! of an example re-export the types they store. Programs that compose
+//! examples, and the tests that set up their accounts, read and write the
+//! same layouts, and rent is computed from the same sizes:
+//!
+//! ```
+//! # use lab_domain::VaultConfig;
+//! # use solana_program::rent::Rent;
+//! let data = vec![0; VaultConfig::SPACE];
+//! let rent = Rent::default().minimum_balance(data.len());
+//! ```
+//!
+//! What a version does with a type is still its own: only a secure
+//! version writes [`VaultConfig`]'s discriminator. With the `arbitrary`
+//! feature on, every type implements `arbitrary::Arbitrary`, for fuzzing.
+
+#[cfg(feature = "arbitrary")]
+mod arbitrary;
+pub mod escrow;
+pub mod staking;
+pub mod vault;
+
+pub use escrow::{EscrowAccount, UserProfile};
+pub use staking::{StakingPool, UserStake};
+pub use vault::VaultConfig;
@@ -0,0 +1,44 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Everything staked, and the rewards it earns
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct StakingPool {
+    pub total_staked: u64,
+    /// Rewards per second
+    pub reward_rate: u64,
+    pub last_update: i64,
+}
+
+impl StakingPool {
+    /// Its fields: total_staked, reward_rate, last_update
+    pub const LEN: usize = 8 + 8 + 8;
+
+    /// A pool opened at `now`, with nothing staked
+    pub fn new(reward_rate: u64, now: i64) -> Self {
+        Self {
+            total_staked: 0,
+            reward_rate,
+            last_update: now,
+        }
+    }
+}
+
+/// One user's stake, and when they last claimed its rewards
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct UserStake {
+    pub amount: u64,
+    pub last_claim: i64,
+}
+
+impl UserStake {
+    /// Its fields: amount, last_claim
+    pub const LEN: usize = 8 + 8;
+
+    /// `amount` staked at `now`, with no rewards claimed since
+    pub fn new(amount: u64, now: i64) -> Self {
+        Self {
+            amount,
+            last_claim: now,
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_common::discriminator::AccountDiscriminator;
+use solana_program::pubkey::Pubkey;
+
+/// A vault's authority and the fee it charges on deposits
+#[derive(BorshSerialize, BorshDeserialize, AccountDiscriminator, Clone, Debug, PartialEq, Eq)]
+pub struct VaultConfig {
+    pub authority: Pubkey,
+    pub total_deposited: u64,
+    pub fee_percentage: u8,
+}
+
+impl VaultConfig {
+    /// Its fields: authority, total_deposited, fee_percentage
+    pub const LEN: usize = 32 + 8 + 1;
+    /// Its fields after its discriminator, as a secure version writes it
+    pub const SPACE: usize = Self::DISCRIMINATOR.len() + Self::LEN;
+
+    /// A vault nothing has been deposited into yet
+    pub fn new(authority: Pubkey, fee_percentage: u8) -> Self {
+        Self {
+            authority,
+            total_deposited: 0,
+            fee_percentage,
+        }
+    }
+}
@@ -0,0 +1,32 @@
+//! Every type is as long as its LEN says, and secure vaults leave room for
+//! the discriminator
+
+use borsh::to_vec;
+use lab_domain::{EscrowAccount, StakingPool, UserProfile, UserStake, VaultConfig};
+use solana_common::discriminator::AccountDiscriminator;
+use solana_program::pubkey::Pubkey;
+
+#[test]
+fn lengths_match_the_serialized_size() {
+    let key = Pubkey::new_unique();
+    let vault = VaultConfig::new(key, 1);
+    assert_eq!(to_vec(&vault).unwrap().len(), VaultConfig::LEN);
+    let profile = UserProfile::new(key, key, 1);
+    assert_eq!(to_vec(&profile).unwrap().len(), UserProfile::LEN);
+    let escrow = EscrowAccount::new(key, 1, 1);
+    assert_eq!(to_vec(&escrow).unwrap().len(), EscrowAccount::LEN);
+    let pool = StakingPool::new(1, 1);
+    assert_eq!(to_vec(&pool).unwrap().len(), StakingPool::LEN);
+    let stake = UserStake::new(1, 1);
+    assert_eq!(to_vec(&stake).unwrap().len(), UserStake::LEN);
+}
+
+#[test]
+fn a_secure_vault_fills_its_space() {
+    let vault = VaultConfig::new(Pubkey::new_unique(), 1);
+    assert_eq!(vault.total_deposited, 0);
+    let mut data = Vec::new();
+    vault.serialize_with_discriminator(&mut data).unwrap();
+    assert_eq!(data.len(), VaultConfig::SPACE);
+    assert_eq!(VaultConfig::try_deserialize_checked(&data), Ok(vault));
+}
@@ -0,0 +1,49 @@
+use std::fmt;
+
+/// Why a signer may not do what it asked; codes 5000 and up
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum AccessError {
+    /// It does not hold every role the instruction needs
+    MissingRole = 5000,
+    /// There is no authority transfer waiting to be accepted
+    NoPendingAuthority = 5001,
+    /// It is not the authority the transfer was proposed to
+    NotPendingAuthority = 5002,
+    /// The program is paused
+    Paused = 5003,
+    /// It would take more out in this window than the cap allows
+    WithdrawCapExceeded = 5004,
+}
+
+impl AccessError {
+    pub const ALL: [AccessError; 5] = [
+        AccessError::MissingRole,
+        AccessError::NoPendingAuthority,
+        AccessError::NotPendingAuthority,
+        AccessError::Paused,
+        AccessError::WithdrawCapExceeded,
+    ];
+
+    pub fn code(self) -> u32 {
+        self as u32
+    }
+
+    pub fn from_code(code: u32) -> Option<Self> {
+        Self::ALL.into_iter().find(|error| error.code() == code)
+    }
+}
+
+impl fmt::Display for AccessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            AccessError::MissingRole => "signer does not hold the required role",
+            AccessError::NoPendingAuthority => "no authority transfer is pending",
+            AccessError::NotPendingAuthority => "signer is not the proposed authority",
+            AccessError::Paused => "program is paused",
+            AccessError::WithdrawCapExceeded => "withdrawal exceeds the cap for this window",
+        })
+    }
+}
+
+impl std::error::Error for AccessError {}
@@ -0,0 +1,35 @@
+use std::fmt;
+
+/// Why checked math failed; codes 2000 and up
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum ArithmeticError {
+    /// The result does not fit the type, so the bare operation would wrap
+    Overflow = 2000,
+    /// The divisor was zero
+    DivisionByZero = 2001,
+}
+
+impl ArithmeticError {
+    pub const ALL: [ArithmeticError; 2] =
+        [ArithmeticError::Overflow, ArithmeticError::DivisionByZero];
+
+    pub fn code(self) -> u32 {
+        self as u32
+    }
+
+    pub fn from_code(code: u32) -> Option<Self> {
+        Self::ALL.into_iter().find(|error| error.code() == code)
+    }
+}
+
+impl fmt::Display for ArithmeticError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ArithmeticError::Overflow => "arithmetic would overflow",
+            ArithmeticError::DivisionByZero => "division by zero",
+        })
+    }
+}
+
+impl std::error::Error for ArithmeticError {}
@@ -0,0 +1,137 @@
+//! Every error the code the examples share can fail with, by code
+//!
+//! The checks in `solana-common` fail with one of five enums:
+//! [`ValidationError`] for an account that is not what the instruction
+//! needs, [`ArithmeticError`] for math that would wrap, [`StateError`]
+//! for account data that is not the type it should hold, [`OracleError`]
+//! for a price that should not be trusted and [`AccessError`] for a signer
+//! without the role or authority an instruction needs, or a program paused
+//! or over its withdrawal cap. Each variant has a code that never
+//! changes, from its own range per enum, and `?` turns it into
+//! [`ProgramError::Custom`] with that code, so a failed transaction says
+//! exactly which check stopped it. [`decode`] reads the code back out of
+//! the `InstructionError` a transaction failed with:
+//!
+//! ```
+//! # use lab_errors::{decode, LabError, ValidationError::WrongOwner};
+//! # use solana_program::{instruction::InstructionError, program_error::ProgramError};
+//! // what assert_owned_by(vault, program_id)? fails with
+//! assert_eq!(ProgramError::from(WrongOwner), ProgramError::Custom(0x3e9));
+//! assert_eq!(decode(&InstructionError::Custom(0x3e9)), Some(LabError::Validation(WrongOwner)));
+//! ```
+//!
+//! [`ProgramError::Custom`]: solana_program::program_error::ProgramError::Custom
+
+mod access;
+mod arithmetic;
+mod oracle;
+mod state;
+mod validation;
+
+use std::fmt;
+
+use solana_program::instruction::InstructionError;
+use solana_program::program_error::ProgramError;
+
+pub use access::AccessError;
+pub use arithmetic::ArithmeticError;
+pub use oracle::OracleError;
+pub use state::StateError;
+pub use validation::ValidationError;
+
+/// Any of the shared errors
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LabError {
+    Validation(ValidationError),
+    Arithmetic(ArithmeticError),
+    State(StateError),
+    Oracle(OracleError),
+    Access(AccessError),
+}
+
+impl LabError {
+    /// The error with this code, if any has it
+    pub fn from_code(code: u32) -> Option<Self> {
+        ValidationError::from_code(code)
+            .map(LabError::Validation)
+            .or_else(|| ArithmeticError::from_code(code).map(LabError::Arithmetic))
+            .or_else(|| StateError::from_code(code).map(LabError::State))
+            .or_else(|| OracleError::from_code(code).map(LabError::Oracle))
+            .or_else(|| AccessError::from_code(code).map(LabError::Access))
+    }
+
+    pub fn code(self) -> u32 {
+        match self {
+            LabError::Validation(error) => error.code(),
+            LabError::Arithmetic(error) => error.code(),
+            LabError::State(error) => error.code(),
+            LabError::Oracle(error) => error.code(),
+            LabError::Access(error) => error.code(),
+        }
+    }
+
+    /// Which of the five it is
+    pub fn category(self) -> &'static str {
+        match self {
+            LabError::Validation(_) => "validation",
+            LabError::Arithmetic(_) => "arithmetic",
+            LabError::State(_) => "state",
+            LabError::Oracle(_) => "oracle",
+            LabError::Access(_) => "access",
+        }
+    }
+}
+
+/// `validation error 1001: account is owned by another program`
+impl fmt::Display for LabError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} error {}: ", self.category(), self.code())?;
+        match self {
+            LabError::Validation(error) => error.fmt(f),
+            LabError::Arithmetic(error) => error.fmt(f),
+            LabError::State(error) => error.fmt(f),
+            LabError::Oracle(error) => error.fmt(f),
+            LabError::Access(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for LabError {}
+
+impl From<LabError> for ProgramError {
+    fn from(error: LabError) -> Self {
+        ProgramError::Custom(error.code())
+    }
+}
+
+macro_rules! into_lab_error {
+    ($($error:ident => $variant:ident),*) => {$(
+        impl From<$error> for LabError {
+            fn from(error: $error) -> Self {
+                LabError::$variant(error)
+            }
+        }
+
+        impl From<$error> for ProgramError {
+            fn from(error: $error) -> Self {
+                ProgramError::Custom(error.code())
+            }
+        }
+    )*};
+}
+
+into_lab_error!(
+    ValidationError => Validation,
+    ArithmeticError => Arithmetic,
+    StateError => State,
+    OracleError => Oracle,
+    AccessError => Access
+);
+
+/// The shared error an instruction failed with, if it failed with one
+pub fn decode(error: &InstructionError) -> Option<LabError> {
+    match error {
+        InstructionError::Custom(code) => LabError::from_code(*code),
+        _ => None,
+    }
+}
@@ -0,0 +1,41 @@
+use std::fmt;
+
+/// Why a price cannot be used; codes 4000 and up
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum OracleError {
+    /// It was published longer ago than the reader allows
+    StalePrice = 4000,
+    /// Its confidence interval is too wide a share of the price
+    ConfidenceTooWide = 4001,
+    /// It is zero or negative
+    InvalidPrice = 4002,
+}
+
+impl OracleError {
+    pub const ALL: [OracleError; 3] = [
+        OracleError::StalePrice,
+        OracleError::ConfidenceTooWide,
+        OracleError::InvalidPrice,
+    ];
+
+    pub fn code(self) -> u32 {
+        self as u32
+    }
+
+    pub fn from_code(code: u32) -> Option<Self> {
+        Self::ALL.into_iter().find(|error| error.code() == code)
+    }
+}
+
+impl fmt::Display for OracleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            OracleError::StalePrice => "price is too old",
+            OracleError::ConfidenceTooWide => "price confidence interval is too wide",
+            OracleError::InvalidPrice => "price is not positive",
+        })
+    }
+}
+
+impl std::error::Error for OracleError {}
@@ -0,0 +1,54 @@
+use std::fmt;
+
+/// Why an account's data is not the value it should hold; codes 3000 and
+/// up
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum StateError {
+    /// It is too short to hold a discriminator
+    DataTooSmall = 3000,
+    /// Its discriminator is another type's, or it was never written
+    WrongAccountType = 3001,
+    /// The bytes after its discriminator are not exactly one value
+    MalformedData = 3002,
+    /// An instruction that holds its lock is still running
+    Locked = 3003,
+    /// It was closed, and holds the closed-account discriminator
+    AccountClosed = 3004,
+    /// Its version byte is not the layout's being read
+    WrongVersion = 3005,
+}
+
+impl StateError {
+    pub const ALL: [StateError; 6] = [
+        StateError::DataTooSmall,
+        StateError::WrongAccountType,
+        StateError::MalformedData,
+        StateError::Locked,
+        StateError::AccountClosed,
+        StateError::WrongVersion,
+    ];
+
+    pub fn code(self) -> u32 {
+        self as u32
+    }
+
+    pub fn from_code(code: u32) -> Option<Self> {
+        Self::ALL.into_iter().find(|error| error.code() == code)
+    }
+}
+
+impl fmt::Display for StateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            StateError::DataTooSmall => "account data is too small to hold a discriminator",
+            StateError::WrongAccountType => "account holds another type",
+            StateError::MalformedData => "account data does not deserialize to its type",
+            StateError::Locked => "account is locked by an instruction still running",
+            StateError::AccountClosed => "account has been closed",
+            StateError::WrongVersion => "account holds another version of its layout",
+        })
+    }
+}
+
+impl std::error::Error for StateError {}
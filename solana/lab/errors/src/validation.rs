@@ -0,0 +1,76 @@
+use std::fmt;
+
+/// Why an account failed validation; codes 1000 and up
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum ValidationError {
+    /// It had to sign the transaction and did not
+    MissingSignature = 1000,
+    /// Another program owns it, so it could hold anything
+    WrongOwner = 1001,
+    /// It is not the account that was expected
+    KeyMismatch = 1002,
+    /// It is not the address its seeds and bump derive
+    InvalidPda = 1003,
+    /// It holds less than rent exemption for its size
+    NotRentExempt = 1004,
+    /// It was passed read-only
+    NotWritable = 1005,
+    /// Its bump derives a valid address, but not the canonical one
+    NonCanonicalBump = 1006,
+    /// It is the same account as another the instruction needs to be
+    /// different
+    DuplicateAccount = 1007,
+}
+
+impl ValidationError {
+    pub const ALL: [ValidationError; 8] = [
+        ValidationError::MissingSignature,
+        ValidationError::WrongOwner,
+        ValidationError::KeyMismatch,
+        ValidationError::InvalidPda,
+        ValidationError::NotRentExempt,
+        ValidationError::NotWritable,
+        ValidationError::NonCanonicalBump,
+        ValidationError::DuplicateAccount,
+    ];
+
+    pub fn code(self) -> u32 {
+        self as u32
+    }
+
+    pub fn from_code(code: u32) -> Option<Self> {
+        Self::ALL.into_iter().find(|error| error.code() == code)
+    }
+
+    /// The `solana_common` helper that returns it
+    pub fn assertion(self) -> &'static str {
+        match self {
+            ValidationError::MissingSignature => "assert_signer",
+            ValidationError::WrongOwner => "assert_owned_by",
+            ValidationError::KeyMismatch => "assert_key_eq",
+            ValidationError::InvalidPda => "assert_pda",
+            ValidationError::NotRentExempt => "assert_rent_exempt",
+            ValidationError::NotWritable => "assert_writable",
+            ValidationError::NonCanonicalBump => "assert_canonical_bump",
+            ValidationError::DuplicateAccount => "assert_distinct",
+        }
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ValidationError::MissingSignature => "account did not sign",
+            ValidationError::WrongOwner => "account is owned by another program",
+            ValidationError::KeyMismatch => "account is not the expected one",
+            ValidationError::InvalidPda => "account is not the PDA of its seeds",
+            ValidationError::NotRentExempt => "account is not rent-exempt",
+            ValidationError::NotWritable => "account is not writable",
+            ValidationError::NonCanonicalBump => "bump is not the canonical one",
+            ValidationError::DuplicateAccount => "account was passed twice",
+        })
+    }
+}
+
+impl std::error::Error for ValidationError {}
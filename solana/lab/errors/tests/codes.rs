@@ -0,0 +1,47 @@
+//! Every error keeps its code, and decodes from the instruction error `?`
+//! turns it into
+
+use lab_errors::{
+    decode, AccessError, ArithmeticError, LabError, OracleError, StateError, ValidationError,
+};
+use solana_program::instruction::InstructionError;
+use solana_program::program_error::ProgramError;
+
+fn all() -> Vec<LabError> {
+    let validation = ValidationError::ALL.map(LabError::from);
+    let arithmetic = ArithmeticError::ALL.map(LabError::from);
+    let state = StateError::ALL.map(LabError::from);
+    let oracle = OracleError::ALL.map(LabError::from);
+    let access = AccessError::ALL.map(LabError::from);
+    [&validation[..], &arithmetic, &state, &oracle, &access].concat()
+}
+
+#[test]
+fn codes_are_stable() {
+    let codes: Vec<u32> = all().into_iter().map(LabError::code).collect();
+    assert_eq!(
+        codes,
+        [
+            1000, 1001, 1002, 1003, 1004, 1005, 1006, 1007, 2000, 2001, 3000, 3001, 3002, 3003,
+            3004, 3005, 4000, 4001, 4002, 5000, 5001, 5002, 5003, 5004
+        ]
+    );
+    assert_eq!(
+        ProgramError::from(ValidationError::WrongOwner),
+        ProgramError::Custom(1001)
+    );
+    assert_eq!(
+        LabError::from(ValidationError::WrongOwner).to_string(),
+        "validation error 1001: account is owned by another program"
+    );
+}
+
+#[test]
+fn failed_instructions_decode() {
+    for error in all() {
+        let logged = InstructionError::from(u64::from(ProgramError::from(error)));
+        assert_eq!(decode(&logged), Some(error));
+    }
+    assert_eq!(decode(&InstructionError::Custom(7)), None);
+    assert_eq!(decode(&InstructionError::MissingRequiredSignature), None);
+}
@@ -0,0 +1,5 @@
+#![cfg_attr(feature = "libfuzzer", no_main)]
+
+lab_fuzz::target!(|input: &[u8]| {
+    vuln_lab::fuzz::run("account_data_matching", input);
+});
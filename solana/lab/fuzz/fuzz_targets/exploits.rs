@@ -0,0 +1,17 @@
+#![cfg_attr(feature = "libfuzzer", no_main)]
+
+use vuln_lab::REGISTRY;
+
+// The first byte picks the example; the rest is the attack. Getting the
+// attacker something is the finding
+lab_fuzz::target!(|input: &[u8]| {
+    let Some((pick, attack)) = input.split_first() else {
+        return;
+    };
+    let example = REGISTRY
+        .iter()
+        .nth(usize::from(*pick) % REGISTRY.len())
+        .unwrap()
+        .example();
+    vuln_lab::fuzz::exploit(example, attack);
+});
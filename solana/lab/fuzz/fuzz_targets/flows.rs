@@ -0,0 +1,16 @@
+#![cfg_attr(feature = "libfuzzer", no_main)]
+
+use vuln_lab::REGISTRY;
+
+// The first byte picks the example; the rest is its flow
+lab_fuzz::target!(|input: &[u8]| {
+    let Some((pick, flow)) = input.split_first() else {
+        return;
+    };
+    let example = REGISTRY
+        .iter()
+        .nth(usize::from(*pick) % REGISTRY.len())
+        .unwrap()
+        .example();
+    vuln_lab::fuzz::flow(example, flow);
+});
@@ -0,0 +1,8 @@
+#![cfg_attr(feature = "libfuzzer", no_main)]
+
+// A collision is a finding: the message is the collision as JSON
+lab_fuzz::target!(|input: &[u8]| {
+    if let Some(collision) = vuln_lab::collisions::fuzz(input) {
+        panic!("{}", collision.to_json());
+    }
+});
@@ -0,0 +1,42 @@
+//! The entry point every fuzz target declares, for whichever fuzzer the
+//! crate is built for: libFuzzer through cargo-fuzz by default, or
+//! honggfuzz through cargo-hfuzz with the `honggfuzz` feature, for
+//! machines libFuzzer does not build on
+//!
+//! A target is its body and nothing else, so both fuzzers run the same
+//! `vuln_lab::fuzz` functions over the same input bytes, and either can
+//! start from the corpus the other grew.
+
+#[cfg(all(feature = "libfuzzer", feature = "honggfuzz"))]
+compile_error!("build the fuzz targets for libfuzzer or honggfuzz, not both");
+
+#[cfg(feature = "honggfuzz")]
+#[doc(hidden)]
+pub use honggfuzz;
+#[cfg(feature = "libfuzzer")]
+#[doc(hidden)]
+pub use libfuzzer_sys;
+
+/// Declares the target's entry point, which runs `$body` with each input
+/// the fuzzer generates bound to `$input`
+#[cfg(feature = "libfuzzer")]
+#[macro_export]
+macro_rules! target {
+    (|$input:ident: &[u8]| $body:block) => {
+        $crate::libfuzzer_sys::fuzz_target!(|$input: &[u8]| $body);
+    };
+}
+
+/// Declares the target's entry point, which runs `$body` with each input
+/// the fuzzer generates bound to `$input`
+#[cfg(feature = "honggfuzz")]
+#[macro_export]
+macro_rules! target {
+    (|$input:ident: &[u8]| $body:block) => {
+        fn main() {
+            loop {
+                $crate::honggfuzz::fuzz!(|$input: &[u8]| $body);
+            }
+        }
+    };
+}
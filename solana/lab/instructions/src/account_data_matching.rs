@@ -0,0 +1,24 @@
+//! account_data_matching: releasing an escrow
+
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::pubkey::Pubkey;
+
+/// `signer`, the owner of `profile`, releases `escrow` to `beneficiary`
+pub fn release_ix(
+    program_id: Pubkey,
+    profile: Pubkey,
+    escrow: Pubkey,
+    beneficiary: Pubkey,
+    signer: Pubkey,
+) -> Instruction {
+    Instruction::new_with_bytes(
+        program_id,
+        &[],
+        vec![
+            AccountMeta::new_readonly(profile, false),
+            AccountMeta::new(escrow, false),
+            AccountMeta::new(beneficiary, false),
+            AccountMeta::new_readonly(signer, true),
+        ],
+    )
+}
@@ -0,0 +1,63 @@
+//! account_versioning: opening, withdrawing from and migrating a user
+//! account
+
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::pubkey::Pubkey;
+use solana_system_interface::program as system_program;
+
+use crate::amount_data;
+
+pub const OPEN: u8 = 0;
+pub const WITHDRAW: u8 = 1;
+pub const MIGRATE: u8 = 2;
+
+/// `user` opens `user_account`, naming `delegate`
+pub fn open_ix(
+    program_id: Pubkey,
+    user: Pubkey,
+    user_account: Pubkey,
+    delegate: Pubkey,
+) -> Instruction {
+    let data: Vec<u8> = [OPEN].into_iter().chain(delegate.to_bytes()).collect();
+    Instruction::new_with_bytes(
+        program_id,
+        &data,
+        vec![
+            AccountMeta::new_readonly(user, true),
+            AccountMeta::new(user_account, false),
+        ],
+    )
+}
+
+/// `user` withdraws `amount` of `user_account`'s balance from `vault`
+pub fn withdraw_ix(
+    program_id: Pubkey,
+    user: Pubkey,
+    user_account: Pubkey,
+    vault: Pubkey,
+    amount: u64,
+) -> Instruction {
+    Instruction::new_with_bytes(
+        program_id,
+        &amount_data(Some(WITHDRAW), amount),
+        vec![
+            AccountMeta::new(user, true),
+            AccountMeta::new(user_account, false),
+            AccountMeta::new(vault, false),
+        ],
+    )
+}
+
+/// `user` migrates `user_account` to the second layout, paying for the
+/// room it grows by
+pub fn migrate_ix(program_id: Pubkey, user: Pubkey, user_account: Pubkey) -> Instruction {
+    Instruction::new_with_bytes(
+        program_id,
+        &[MIGRATE],
+        vec![
+            AccountMeta::new(user, true),
+            AccountMeta::new(user_account, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+    )
+}
@@ -0,0 +1,27 @@
+//! arbitrary_cpi: a call the program forwards to another program
+
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::pubkey::Pubkey;
+
+/// Has the program invoke `target_program` with `data`, passing `user` as
+/// signer and `target_account`. `relay` is the program's relay account,
+/// which only the secure version locks
+pub fn invoke_ix(
+    program_id: Pubkey,
+    user: Pubkey,
+    target_program: Pubkey,
+    target_account: Pubkey,
+    relay: Pubkey,
+    data: Vec<u8>,
+) -> Instruction {
+    Instruction::new_with_bytes(
+        program_id,
+        &data,
+        vec![
+            AccountMeta::new(user, true),
+            AccountMeta::new_readonly(target_program, false),
+            AccountMeta::new(target_account, false),
+            AccountMeta::new(relay, false),
+        ],
+    )
+}
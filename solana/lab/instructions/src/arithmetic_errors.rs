@@ -0,0 +1,70 @@
+//! arithmetic_errors: staking, rewards and transfers
+
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::pubkey::Pubkey;
+use solana_program::sysvar;
+
+use crate::amount_data;
+
+pub const STAKE: u8 = 0;
+pub const CALCULATE_REWARDS: u8 = 1;
+pub const TRANSFER: u8 = 2;
+
+/// `user` stakes `amount` into `pool`, recorded in `stake`
+pub fn stake_ix(
+    program_id: Pubkey,
+    pool: Pubkey,
+    stake: Pubkey,
+    user: Pubkey,
+    amount: u64,
+) -> Instruction {
+    Instruction::new_with_bytes(
+        program_id,
+        &amount_data(Some(STAKE), amount),
+        vec![
+            AccountMeta::new(pool, false),
+            AccountMeta::new(stake, false),
+            AccountMeta::new_readonly(user, true),
+        ],
+    )
+}
+
+/// `user` has the rewards on `stake` calculated; only the vulnerable
+/// version reads the clock account, but both are passed it
+pub fn calculate_rewards_ix(
+    program_id: Pubkey,
+    pool: Pubkey,
+    stake: Pubkey,
+    user: Pubkey,
+) -> Instruction {
+    Instruction::new_with_bytes(
+        program_id,
+        &[CALCULATE_REWARDS],
+        vec![
+            AccountMeta::new_readonly(pool, false),
+            AccountMeta::new_readonly(stake, false),
+            AccountMeta::new_readonly(user, true),
+            AccountMeta::new_readonly(sysvar::clock::ID, false),
+        ],
+    )
+}
+
+/// `user` moves `amount` lamports from `from`, which the program owns, to
+/// `to`
+pub fn transfer_ix(
+    program_id: Pubkey,
+    from: Pubkey,
+    to: Pubkey,
+    user: Pubkey,
+    amount: u64,
+) -> Instruction {
+    Instruction::new_with_bytes(
+        program_id,
+        &amount_data(Some(TRANSFER), amount),
+        vec![
+            AccountMeta::new(from, false),
+            AccountMeta::new(to, false),
+            AccountMeta::new_readonly(user, true),
+        ],
+    )
+}
@@ -0,0 +1,108 @@
+//! batch_payout: a payroll its recipients register with, paying them, and
+//! handing it to a new authority
+
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::pubkey::Pubkey;
+
+use crate::amount_data;
+
+pub const INITIALIZE: u8 = 0;
+pub const REGISTER: u8 = 1;
+pub const DISTRIBUTE: u8 = 2;
+/// Secure version only
+pub const PROPOSE_AUTHORITY: u8 = 3;
+/// Secure version only
+pub const ACCEPT_AUTHORITY: u8 = 4;
+
+/// `authority` sets `payroll` up to pay each recipient `amount`
+pub fn initialize_ix(
+    program_id: Pubkey,
+    payroll: Pubkey,
+    authority: Pubkey,
+    amount: u64,
+) -> Instruction {
+    Instruction::new_with_bytes(
+        program_id,
+        &amount_data(Some(INITIALIZE), amount),
+        vec![
+            AccountMeta::new(payroll, false),
+            AccountMeta::new_readonly(authority, true),
+        ],
+    )
+}
+
+/// `recipient` joins `payroll`, `approver`, its authority, signing too
+pub fn register_ix(
+    program_id: Pubkey,
+    payroll: Pubkey,
+    recipient: Pubkey,
+    approver: Pubkey,
+) -> Instruction {
+    Instruction::new_with_bytes(
+        program_id,
+        &[REGISTER],
+        vec![
+            AccountMeta::new(payroll, false),
+            AccountMeta::new_readonly(recipient, true),
+            AccountMeta::new_readonly(approver, true),
+        ],
+    )
+}
+
+/// `authority` pays `recipients`, in the order they registered: all of
+/// them in the vulnerable version, a page from where the last stopped in
+/// the secure one
+pub fn distribute_ix(
+    program_id: Pubkey,
+    payroll: Pubkey,
+    authority: Pubkey,
+    recipients: &[Pubkey],
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(payroll, false),
+        AccountMeta::new_readonly(authority, true),
+    ];
+    accounts.extend(
+        recipients
+            .iter()
+            .map(|recipient| AccountMeta::new(*recipient, false)),
+    );
+    Instruction::new_with_bytes(program_id, &[DISTRIBUTE], accounts)
+}
+
+/// `authority` offers `payroll` to `new_authority`, which has to accept
+/// it; the secure version only
+pub fn propose_authority_ix(
+    program_id: Pubkey,
+    payroll: Pubkey,
+    authority: Pubkey,
+    new_authority: Pubkey,
+) -> Instruction {
+    let mut data = vec![PROPOSE_AUTHORITY];
+    data.extend_from_slice(new_authority.as_ref());
+    Instruction::new_with_bytes(
+        program_id,
+        &data,
+        vec![
+            AccountMeta::new(payroll, false),
+            AccountMeta::new_readonly(authority, true),
+        ],
+    )
+}
+
+/// `new_authority` takes over the `payroll` it was offered; the secure
+/// version only
+pub fn accept_authority_ix(
+    program_id: Pubkey,
+    payroll: Pubkey,
+    new_authority: Pubkey,
+) -> Instruction {
+    Instruction::new_with_bytes(
+        program_id,
+        &[ACCEPT_AUTHORITY],
+        vec![
+            AccountMeta::new(payroll, false),
+            AccountMeta::new_readonly(new_authority, true),
+        ],
+    )
+}
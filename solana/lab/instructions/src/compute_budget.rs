@@ -0,0 +1,67 @@
+//! The compute budget program: the most compute units a transaction may
+//! use, and what it bids per unit for priority
+//!
+//! Not an example, but what every example's transactions may start with.
+//! Each instruction is a tag byte, then a little-endian value; none takes
+//! accounts. A validator reads them before it runs anything, and refuses a
+//! transaction that gives any of them twice.
+
+use solana_program::instruction::Instruction;
+
+solana_program::declare_id!("ComputeBudget111111111111111111111111111111");
+
+pub const REQUEST_HEAP_FRAME: u8 = 1;
+pub const SET_COMPUTE_UNIT_LIMIT: u8 = 2;
+pub const SET_COMPUTE_UNIT_PRICE: u8 = 3;
+pub const SET_LOADED_ACCOUNTS_DATA_SIZE_LIMIT: u8 = 4;
+
+/// The most units a transaction may ask for
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+/// What a compute budget instruction sets
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ComputeBudgetInstruction {
+    /// Bytes of heap, a multiple of 1 KiB past the default 32 KiB
+    RequestHeapFrame(u32),
+    SetComputeUnitLimit(u32),
+    /// Micro-lamports per compute unit
+    SetComputeUnitPrice(u64),
+    SetLoadedAccountsDataSizeLimit(u32),
+}
+
+impl ComputeBudgetInstruction {
+    /// The instruction `data` packs; `None` if it packs none of them
+    pub fn unpack(data: &[u8]) -> Option<Self> {
+        let (tag, value) = data.split_first()?;
+        let u32_value = || value.try_into().ok().map(u32::from_le_bytes);
+        match *tag {
+            REQUEST_HEAP_FRAME => u32_value().map(Self::RequestHeapFrame),
+            SET_COMPUTE_UNIT_LIMIT => u32_value().map(Self::SetComputeUnitLimit),
+            SET_COMPUTE_UNIT_PRICE => value
+                .try_into()
+                .ok()
+                .map(u64::from_le_bytes)
+                .map(Self::SetComputeUnitPrice),
+            SET_LOADED_ACCOUNTS_DATA_SIZE_LIMIT => {
+                u32_value().map(Self::SetLoadedAccountsDataSizeLimit)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The transaction may use at most `units` compute units
+pub fn set_compute_unit_limit_ix(units: u32) -> Instruction {
+    let data: Vec<u8> = [SET_COMPUTE_UNIT_LIMIT]
+        .into_iter()
+        .chain(units.to_le_bytes())
+        .collect();
+    Instruction::new_with_bytes(ID, &data, Vec::new())
+}
+
+/// The transaction pays `micro_lamports` per compute unit of its limit,
+/// on top of its signature fees, to be scheduled ahead of cheaper ones
+pub fn set_compute_unit_price_ix(micro_lamports: u64) -> Instruction {
+    let data = crate::amount_data(Some(SET_COMPUTE_UNIT_PRICE), micro_lamports);
+    Instruction::new_with_bytes(ID, &data, Vec::new())
+}
@@ -0,0 +1,40 @@
+//! The instructions every example program takes, one builder each
+//!
+//! A builder takes the program id and the accounts by role, in the order
+//! the program reads them, and packs the instruction data the way the
+//! program parses it: a tag byte where the program has several
+//! instructions, then little-endian amounts. Both versions of an example
+//! take the same instructions, so one builder serves both. Each account is
+//! marked as an honest caller would mark it; an exploit that needs a
+//! different mark, an account that should have signed and did not, edits
+//! the returned `Instruction`'s `accounts`:
+//!
+//! ```
+//! # use lab_instructions::missing_signer_check;
+//! # use solana_program::pubkey::Pubkey;
+//! # let [program_id, deposit, attacker] = [(); 3].map(|()| Pubkey::new_unique());
+//! # let amount = 1;
+//! let mut withdraw = missing_signer_check::withdraw_ix(program_id, deposit, attacker, amount);
+//! withdraw.accounts[0].is_signer = false;
+//! ```
+//!
+//! [`compute_budget`] builds the compute budget program's instructions,
+//! which any of them can be sent with.
+
+pub mod account_data_matching;
+pub mod account_versioning;
+pub mod arbitrary_cpi;
+pub mod arithmetic_errors;
+pub mod batch_payout;
+pub mod compute_budget;
+pub mod missing_owner_check;
+pub mod missing_signer_check;
+pub mod pda_issues;
+pub mod reinitialization;
+pub mod rent_exemption;
+pub mod type_confusion;
+
+/// Instruction data: an optional instruction tag, then a little-endian amount
+fn amount_data(tag: Option<u8>, amount: u64) -> Vec<u8> {
+    tag.into_iter().chain(amount.to_le_bytes()).collect()
+}
@@ -0,0 +1,29 @@
+//! missing_owner_check: withdrawing from a vault
+
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::pubkey::Pubkey;
+
+use crate::amount_data;
+
+/// `authority`, as named in `vault_data`, withdraws `amount` from `vault`
+/// to `recipient`. `vault_data` is writable: the secure version counts
+/// the withdrawal in it
+pub fn withdraw_ix(
+    program_id: Pubkey,
+    vault_data: Pubkey,
+    vault: Pubkey,
+    authority: Pubkey,
+    recipient: Pubkey,
+    amount: u64,
+) -> Instruction {
+    Instruction::new_with_bytes(
+        program_id,
+        &amount_data(None, amount),
+        vec![
+            AccountMeta::new(vault_data, false),
+            AccountMeta::new(vault, false),
+            AccountMeta::new_readonly(authority, true),
+            AccountMeta::new(recipient, false),
+        ],
+    )
+}
@@ -0,0 +1,23 @@
+//! missing_signer_check: withdrawing from a deposit account
+
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::pubkey::Pubkey;
+
+use crate::amount_data;
+
+/// `user`'s deposit account signs to send `amount` to `destination`
+pub fn withdraw_ix(
+    program_id: Pubkey,
+    user: Pubkey,
+    destination: Pubkey,
+    amount: u64,
+) -> Instruction {
+    Instruction::new_with_bytes(
+        program_id,
+        &amount_data(None, amount),
+        vec![
+            AccountMeta::new(user, true),
+            AccountMeta::new(destination, false),
+        ],
+    )
+}
@@ -0,0 +1,25 @@
+//! pda_issues: withdrawing from a user's PDA
+
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::pubkey::Pubkey;
+
+use crate::amount_data;
+
+/// `user` withdraws `amount` from `pda`, their data account, to `recipient`
+pub fn withdraw_ix(
+    program_id: Pubkey,
+    user: Pubkey,
+    pda: Pubkey,
+    recipient: Pubkey,
+    amount: u64,
+) -> Instruction {
+    Instruction::new_with_bytes(
+        program_id,
+        &amount_data(None, amount),
+        vec![
+            AccountMeta::new(user, true),
+            AccountMeta::new(pda, false),
+            AccountMeta::new(recipient, false),
+        ],
+    )
+}
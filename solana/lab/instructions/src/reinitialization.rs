@@ -0,0 +1,43 @@
+//! reinitialization: setting up a vault and depositing into it
+
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::pubkey::Pubkey;
+
+use crate::amount_data;
+
+pub const INITIALIZE: u8 = 0;
+pub const DEPOSIT: u8 = 1;
+
+/// Makes `authority` the authority of `vault`, charging `fee_percentage`
+pub fn initialize_ix(
+    program_id: Pubkey,
+    vault: Pubkey,
+    authority: Pubkey,
+    fee_percentage: u8,
+) -> Instruction {
+    Instruction::new_with_bytes(
+        program_id,
+        &[INITIALIZE, fee_percentage],
+        vec![
+            AccountMeta::new(vault, false),
+            AccountMeta::new_readonly(authority, true),
+        ],
+    )
+}
+
+/// `depositor`, an account the program owns, pays `amount` into `vault`
+pub fn deposit_ix(
+    program_id: Pubkey,
+    vault: Pubkey,
+    depositor: Pubkey,
+    amount: u64,
+) -> Instruction {
+    Instruction::new_with_bytes(
+        program_id,
+        &amount_data(Some(DEPOSIT), amount),
+        vec![
+            AccountMeta::new(vault, false),
+            AccountMeta::new(depositor, true),
+        ],
+    )
+}
@@ -0,0 +1,37 @@
+//! rent_exemption: opening a record and withdrawing everything from it
+
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::pubkey::Pubkey;
+
+pub const INITIALIZE: u8 = 0;
+pub const WITHDRAW_ALL: u8 = 1;
+
+/// `user` opens `record`
+pub fn initialize_ix(program_id: Pubkey, record: Pubkey, user: Pubkey) -> Instruction {
+    Instruction::new_with_bytes(
+        program_id,
+        &[INITIALIZE],
+        vec![
+            AccountMeta::new(record, false),
+            AccountMeta::new_readonly(user, true),
+        ],
+    )
+}
+
+/// `user` withdraws all of `record`'s lamports to `recipient`
+pub fn withdraw_all_ix(
+    program_id: Pubkey,
+    record: Pubkey,
+    user: Pubkey,
+    recipient: Pubkey,
+) -> Instruction {
+    Instruction::new_with_bytes(
+        program_id,
+        &[WITHDRAW_ALL],
+        vec![
+            AccountMeta::new(record, false),
+            AccountMeta::new_readonly(user, true),
+            AccountMeta::new(recipient, false),
+        ],
+    )
+}
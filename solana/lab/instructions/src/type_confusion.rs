@@ -0,0 +1,38 @@
+//! type_confusion: a user's withdrawal and an admin's action
+
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::pubkey::Pubkey;
+
+use crate::amount_data;
+
+pub const WITHDRAW_USER: u8 = 0;
+pub const ADMIN_ACTION: u8 = 1;
+
+/// `owner` withdraws `amount` from `user_account`
+pub fn withdraw_user_ix(
+    program_id: Pubkey,
+    user_account: Pubkey,
+    owner: Pubkey,
+    amount: u64,
+) -> Instruction {
+    Instruction::new_with_bytes(
+        program_id,
+        &amount_data(Some(WITHDRAW_USER), amount),
+        vec![
+            AccountMeta::new(user_account, false),
+            AccountMeta::new_readonly(owner, true),
+        ],
+    )
+}
+
+/// `admin`, the owner of `admin_account`, acts as an admin
+pub fn admin_action_ix(program_id: Pubkey, admin_account: Pubkey, admin: Pubkey) -> Instruction {
+    Instruction::new_with_bytes(
+        program_id,
+        &[ADMIN_ACTION],
+        vec![
+            AccountMeta::new_readonly(admin_account, false),
+            AccountMeta::new_readonly(admin, true),
+        ],
+    )
+}
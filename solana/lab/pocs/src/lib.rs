@@ -0,0 +1,337 @@
+//! What the PoC binaries share: the target they are pointed at and the
+//! report they print
+//!
+//! Each `poc-<example>` binary runs one example's exploit against a
+//! deployed build of it, over whatever RPC endpoint it is given, outside
+//! the test harness: on a test validator in a live demo, or on devnet.
+//! Where the example's accounts can be made from outside, the PoC makes a
+//! victim of its own first, the attacker paying for it; where an account
+//! must hold bytes no instruction of the example writes, the PoC takes it
+//! as an argument, and says so in its `--help`.
+//!
+//! ```bash
+//! poc-missing-signer-check --rpc http://127.0.0.1:8899
+//! poc-missing-signer-check --rpc https://api.devnet.solana.com --victim <deposit>
+//! poc-missing-signer-check --secure
+//! ```
+//!
+//! Every run prints a [`Report`] as JSON on stdout: what setup sent, the
+//! account the exploit was after, decoded, before and after it, and
+//! whether the exploit landed and what it took. The exit status is 0 if it
+//! landed, 1 if it did not, as against the secure builds, and 2 if the
+//! PoC could not get as far as trying.
+
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use anyhow::{anyhow, Context, Result as AnyResult};
+use clap::{Args, Parser, ValueEnum};
+use lab_client::budget::ComputeBudget;
+use lab_client::confirm::SendStrategy;
+use lab_client::decode::{Inspected, Value};
+use lab_client::rpc::{Commitment, Rpc};
+use lab_client::signers::{Ephemeral, KeypairFiles, SignerProvider, Workshop, ATTACKER};
+use lab_client::{default_keypair_path, ids, Signature, Signer, VulnClient};
+use serde::Serialize;
+use solana_program::pubkey::Pubkey;
+
+/// Where the example is deployed and who attacks it
+#[derive(Args)]
+pub struct Target {
+    /// JSON RPC endpoint of the cluster to attack
+    #[arg(long, default_value = "http://127.0.0.1:8899")]
+    pub rpc: String,
+    /// Keypair file of the attacker, who pays for setup and the exploit
+    /// (default: the Solana CLI's)
+    #[arg(long)]
+    pub keypair: Option<PathBuf>,
+    /// Where the attacker's, the victim's and the owner's keypairs come
+    /// from
+    #[arg(long, value_enum, default_value_t = Signers::File)]
+    pub signers: Signers,
+    /// The workshop's seed phrase, with `--signers workshop` (default:
+    /// `$VULN_LAB_SEED_PHRASE`, else the lab's)
+    #[arg(long)]
+    pub seed_phrase: Option<String>,
+    /// Attack the secure build, which should refuse
+    #[arg(long)]
+    pub secure: bool,
+    /// The program to attack, if not where `Programs.toml` puts it on the
+    /// cluster the PoCs were built for
+    #[arg(long)]
+    pub program_id: Option<Pubkey>,
+    /// Compute units each transaction may use (default: the example's
+    /// own limit)
+    #[arg(long)]
+    pub unit_limit: Option<u32>,
+    /// Priority fee, in micro-lamports per compute unit, for a busy
+    /// cluster such as devnet during a workshop
+    #[arg(long, default_value_t = 0)]
+    pub unit_price: u64,
+    /// How settled a transaction must be before the PoC goes on:
+    /// processed, confirmed or finalized
+    #[arg(long, default_value_t = Commitment::Confirmed)]
+    pub commitment: Commitment,
+    /// Send without simulating first, so a refused exploit lands, failed,
+    /// with its logs on chain
+    #[arg(long)]
+    pub skip_preflight: bool,
+    /// Times a transaction is signed again when its blockhash expires
+    /// before it lands
+    #[arg(long, default_value_t = 3)]
+    pub attempts: u32,
+}
+
+/// Where a PoC's identities come from
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Signers {
+    /// The attacker from `--keypair`, everyone else made fresh for the run
+    File,
+    /// Everyone derived from the workshop's seed phrase, as `vuln-lab`'s
+    /// fixtures are; the attacker must be funded on the cluster
+    Workshop,
+}
+
+impl Target {
+    /// The identities the PoC signs for; with `--signers file`, each call
+    /// makes everyone but the attacker afresh, so a PoC takes this once
+    pub fn signers(&self) -> Box<dyn SignerProvider> {
+        match (self.signers, &self.seed_phrase) {
+            (Signers::File, _) => {
+                let keypair = self.keypair.clone().unwrap_or_else(default_keypair_path);
+                Box::new(
+                    KeypairFiles::default()
+                        .with(ATTACKER, keypair)
+                        .or(Ephemeral::default()),
+                )
+            }
+            (Signers::Workshop, Some(phrase)) => Box::new(Workshop::new(phrase)),
+            (Signers::Workshop, None) => Box::new(Workshop::from_env()),
+        }
+    }
+
+    /// A client for `example`'s build, paid for by the attacker
+    pub fn client(&self, example: &str) -> AnyResult<VulnClient> {
+        let attacker = self.signers().keypair(ATTACKER)?;
+        let program_id = match self.program_id {
+            Some(program_id) => program_id,
+            None => {
+                let krate = self.crate_name(example);
+                ids::id(&krate).ok_or_else(|| {
+                    anyhow!("Programs.toml has no {} id for {krate}", ids::CLUSTER)
+                })?
+            }
+        };
+        Ok(VulnClient::new(Rpc::new(&self.rpc), program_id, attacker)
+            .with_budget(self.budget(example))
+            .with_strategy(self.strategy()))
+    }
+
+    /// How the PoC's transactions are sent and waited for
+    pub fn strategy(&self) -> SendStrategy {
+        SendStrategy::default()
+            .with_commitment(self.commitment)
+            .with_preflight(!self.skip_preflight)
+            .with_attempts(self.attempts)
+    }
+
+    /// The compute budget `example`'s transactions start with
+    pub fn budget(&self, example: &str) -> ComputeBudget {
+        let budget = ComputeBudget::for_example(example).with_unit_price(self.unit_price);
+        match self.unit_limit {
+            Some(units) => budget.with_unit_limit(units),
+            None => budget,
+        }
+    }
+
+    /// `example`'s program crate for the build attacked
+    pub fn crate_name(&self, example: &str) -> String {
+        if self.secure {
+            format!("{example}_secure")
+        } else {
+            example.to_string()
+        }
+    }
+}
+
+/// What a PoC did, as it prints it
+#[derive(Serialize)]
+pub struct Report {
+    pub example: &'static str,
+    /// The program crate attacked
+    pub program: String,
+    pub program_id: String,
+    pub attacker: String,
+    /// The account the exploit was after
+    pub victim: Option<String>,
+    /// What setup sent, in order
+    pub setup: Vec<Step>,
+    /// The victim before and after the exploit
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub snapshots: Vec<Snapshot>,
+    /// Whether the exploit landed and did what it is meant to
+    pub exploited: bool,
+    /// What it did, or why it did not
+    pub outcome: String,
+    /// What the attacker took, for the exploits that take lamports
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lamports_taken: Option<u64>,
+}
+
+/// One transaction setup sent
+#[derive(Serialize)]
+pub struct Step {
+    pub what: String,
+    pub signature: String,
+}
+
+/// An account as the PoC read it
+#[derive(Serialize)]
+pub struct Snapshot {
+    /// Where the exploit stood: before or after
+    pub when: &'static str,
+    pub address: String,
+    /// In SOL and lamports; absent if there is no account
+    pub lamports: Option<String>,
+    /// Each type its data decodes as, with its fields shown in their units
+    pub decoded: Vec<DecodedAccount>,
+}
+
+#[derive(Serialize)]
+pub struct DecodedAccount {
+    #[serde(rename = "type")]
+    pub type_name: &'static str,
+    pub fields: BTreeMap<&'static str, String>,
+}
+
+impl Snapshot {
+    fn new(when: &'static str, address: Pubkey, inspected: Option<Inspected>) -> Self {
+        Self {
+            when,
+            address: address.to_string(),
+            lamports: inspected
+                .as_ref()
+                .map(|account| Value::Lamports(account.lamports).to_string()),
+            decoded: inspected
+                .into_iter()
+                .flat_map(|account| account.decoded)
+                .map(|decoded| DecodedAccount {
+                    type_name: decoded.type_name,
+                    fields: decoded
+                        .fields
+                        .into_iter()
+                        .map(|field| (field.name, field.value.to_string()))
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// What an exploit that was not refused did
+pub struct Exploit {
+    /// Whether that is what the exploit is meant to do; a secure build may
+    /// take a transaction and still not give the attacker anything
+    pub landed: bool,
+    pub outcome: String,
+    pub lamports_taken: Option<u64>,
+}
+
+impl Exploit {
+    /// `lamports` from `victim` to the attacker
+    pub fn took(lamports: u64, victim: Pubkey) -> Self {
+        Self {
+            landed: lamports > 0,
+            outcome: if lamports > 0 {
+                format!("took {lamports} lamports from {victim}")
+            } else {
+                format!("{victim} held nothing to take")
+            },
+            lamports_taken: Some(lamports),
+        }
+    }
+}
+
+impl Report {
+    pub fn new(example: &'static str, target: &Target, client: &VulnClient) -> Self {
+        Self {
+            example,
+            program: target.crate_name(example),
+            program_id: client.program_id().to_string(),
+            attacker: client.payer().pubkey().to_string(),
+            victim: None,
+            setup: Vec::new(),
+            snapshots: Vec::new(),
+            exploited: false,
+            outcome: String::new(),
+            lamports_taken: None,
+        }
+    }
+
+    /// Records a setup transaction
+    pub fn setup(&mut self, what: impl Into<String>, signature: Signature) {
+        self.setup.push(Step {
+            what: what.into(),
+            signature: signature.to_string(),
+        });
+    }
+
+    /// Records the account at `address` as it stands `when`, decoded as
+    /// the attacked crate's types
+    pub async fn inspect(
+        &mut self,
+        client: &VulnClient,
+        when: &'static str,
+        address: Pubkey,
+    ) -> AnyResult<()> {
+        let inspected = client.inspect(address, &self.program).await?;
+        self.snapshots.push(Snapshot::new(when, address, inspected));
+        Ok(())
+    }
+
+    /// The report once the exploit against `victim` has run; an error is
+    /// the program refusing it
+    pub fn exploit(mut self, victim: Pubkey, exploit: AnyResult<Exploit>) -> Self {
+        self.victim = Some(victim.to_string());
+        match exploit {
+            Ok(exploit) => {
+                self.exploited = exploit.landed;
+                self.outcome = exploit.outcome;
+                self.lamports_taken = exploit.lamports_taken;
+            }
+            Err(err) => self.outcome = format!("refused: {err:#}"),
+        }
+        self
+    }
+}
+
+/// Parses a PoC's arguments, runs it and prints its report
+pub fn run<A, F>(poc: impl FnOnce(A) -> F) -> ExitCode
+where
+    A: Parser,
+    F: Future<Output = AnyResult<Report>>,
+{
+    let args = A::parse();
+    let report = tokio::runtime::Runtime::new()
+        .context("starting the tokio runtime")
+        .and_then(|runtime| runtime.block_on(poc(args)));
+    match report {
+        Ok(report) => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&report).expect("a report serializes")
+            );
+            if report.exploited {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::from(1)
+            }
+        }
+        Err(err) => {
+            eprintln!("error: {err:#}");
+            ExitCode::from(2)
+        }
+    }
+}
@@ -0,0 +1,231 @@
+//! The PoC binaries' exploits land against the vulnerable programs and are
+//! refused by the fixes, set up from nothing through a stand-in RPC server
+//! over the in-process runtime
+
+use std::collections::HashSet;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use base64::prelude::{Engine, BASE64_STANDARD};
+use lab_client::accounts::VaultConfig;
+use lab_client::{ids, Keypair, Signer};
+use lab_svm::{Svm, Transaction, LAMPORTS_PER_SOL};
+use serde_json::{json, Value};
+use solana_program::entrypoint::ProcessInstruction;
+use solana_program::pubkey::Pubkey;
+
+/// Serves getLatestBlockhash, sendTransaction, getSignatureStatuses,
+/// getBalance, getAccountInfo and getMinimumBalanceForRentExemption from
+/// `svm`. A transaction the runtime
+/// refuses fails to send, as preflight would have it
+fn serve(svm: Arc<Mutex<Svm>>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    thread::spawn(move || {
+        let mut landed = HashSet::new();
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(&mut stream);
+            let mut length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                    length = value.trim().parse().unwrap();
+                }
+                if line == "\r\n" {
+                    break;
+                }
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            let request: Value = serde_json::from_slice(&body).unwrap();
+
+            let mut svm = svm.lock().unwrap();
+            let params = &request["params"];
+            let address = || params[0].as_str().unwrap().parse::<Pubkey>().unwrap();
+            let response = match request["method"].as_str().unwrap() {
+                "getLatestBlockhash" => {
+                    json!({ "result": { "value": { "blockhash": svm.latest_blockhash().to_string() } } })
+                }
+                "sendTransaction" => {
+                    let bytes = BASE64_STANDARD.decode(params[0].as_str().unwrap()).unwrap();
+                    let transaction: Transaction = bincode::deserialize(&bytes).unwrap();
+                    match svm.send_transaction(&transaction) {
+                        Ok(meta) => {
+                            landed.insert(meta.signature.to_string());
+                            json!({ "result": meta.signature.to_string() })
+                        }
+                        Err(failed) => {
+                            json!({ "error": { "code": -32002, "message": failed.err.to_string() } })
+                        }
+                    }
+                }
+                "getSignatureStatuses" => {
+                    let statuses: Vec<Value> = params[0]
+                        .as_array()
+                        .unwrap()
+                        .iter()
+                        .map(|signature| {
+                            if landed.contains(signature.as_str().unwrap()) {
+                                json!({ "confirmationStatus": "confirmed", "err": null })
+                            } else {
+                                Value::Null
+                            }
+                        })
+                        .collect();
+                    json!({ "result": { "value": statuses } })
+                }
+                "getMinimumBalanceForRentExemption" => {
+                    let len = params[0].as_u64().unwrap() as usize;
+                    json!({ "result": svm.minimum_balance(len) })
+                }
+                "getBalance" => json!({ "result": { "value": svm.lamports(&address()) } }),
+                "getAccountInfo" => {
+                    let account = svm.get_account(&address()).map(|account| {
+                        json!({
+                            "lamports": account.lamports,
+                            "owner": account.owner.to_string(),
+                            "data": [BASE64_STANDARD.encode(&account.data), "base64"],
+                        })
+                    });
+                    json!({ "result": { "value": account } })
+                }
+                method => panic!("unexpected {method}"),
+            };
+            let body = response.to_string();
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+                 Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+            .unwrap();
+        }
+    });
+    url
+}
+
+/// A runtime with both builds of an example at their ids, and the
+/// attacker funded
+fn svm(builds: [(Pubkey, ProcessInstruction); 2]) -> Arc<Mutex<Svm>> {
+    let mut svm = Svm::new();
+    for (program_id, program) in builds {
+        svm.add_program_with_id(program_id, program);
+    }
+    svm.airdrop(&attacker().pubkey(), 10 * LAMPORTS_PER_SOL);
+    Arc::new(Mutex::new(svm))
+}
+
+fn attacker() -> Keypair {
+    Keypair::new_from_array([7; 32])
+}
+
+/// The attacker's keypair file, as the Solana CLI writes one
+fn keypair_file() -> PathBuf {
+    let path = std::env::temp_dir().join(format!("lab-pocs-attacker-{}.json", std::process::id()));
+    std::fs::write(&path, json!(attacker().to_bytes().to_vec()).to_string()).unwrap();
+    path
+}
+
+/// Runs `poc` against `url`, the secure build if `secure`; its exit
+/// status and report
+fn run(poc: &str, url: &str, secure: bool) -> (i32, Value) {
+    let mut command = Command::new(poc);
+    command
+        .args(["--rpc", url, "--keypair"])
+        .arg(keypair_file());
+    if secure {
+        command.arg("--secure");
+    }
+    let output = command.output().unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let report = serde_json::from_slice(&output.stdout)
+        .unwrap_or_else(|err| panic!("{poc} printed no report ({err}): {stderr}"));
+    (output.status.code().unwrap(), report)
+}
+
+#[test]
+fn the_deposit_the_poc_opens_is_drained_unsigned() {
+    let runtime = svm([
+        (
+            ids::MISSING_SIGNER_CHECK,
+            missing_signer_check::process_instruction as ProcessInstruction,
+        ),
+        (
+            ids::MISSING_SIGNER_CHECK_SECURE,
+            missing_signer_check_secure::process_instruction,
+        ),
+    ]);
+    let url = serve(runtime.clone());
+    let poc = env!("CARGO_BIN_EXE_poc-missing-signer-check");
+    let rent = runtime.lock().unwrap().minimum_balance(0);
+
+    let (status, report) = run(poc, &url, false);
+    assert_eq!(status, 0, "{report:#}");
+    assert_eq!(report["exploited"], true);
+    assert_eq!(report["program"], "missing_signer_check");
+    assert_eq!(report["setup"].as_array().unwrap().len(), 1);
+    assert_eq!(report["lamports_taken"], rent + 100_000_000);
+    let deposit: Pubkey = report["victim"].as_str().unwrap().parse().unwrap();
+    assert_eq!(runtime.lock().unwrap().lamports(&deposit), 0);
+
+    let (status, report) = run(poc, &url, true);
+    assert_eq!(status, 1, "{report:#}");
+    assert_eq!(report["exploited"], false);
+    assert!(report["outcome"].as_str().unwrap().starts_with("refused: "));
+    let deposit: Pubkey = report["victim"].as_str().unwrap().parse().unwrap();
+    assert_eq!(
+        runtime.lock().unwrap().lamports(&deposit),
+        rent + 100_000_000
+    );
+}
+
+/// The vault is set up and initialized by an owner, then initialized again
+/// by the attacker, which only the vulnerable build takes
+#[test]
+fn the_vault_the_poc_sets_up_changes_hands() {
+    let runtime = svm([
+        (
+            ids::REINITIALIZATION,
+            reinitialization::process_instruction as ProcessInstruction,
+        ),
+        (
+            ids::REINITIALIZATION_SECURE,
+            reinitialization_secure::process_instruction,
+        ),
+    ]);
+    let url = serve(runtime.clone());
+    let poc = env!("CARGO_BIN_EXE_poc-reinitialization");
+    let authority = |report: &Value| {
+        let vault: Pubkey = report["victim"].as_str().unwrap().parse().unwrap();
+        let data = runtime
+            .lock()
+            .unwrap()
+            .get_account(&vault)
+            .unwrap()
+            .data
+            .clone();
+        // Past the secure layout's discriminator, if there is one
+        let data = &data[data.len() - VaultConfig::LEN..];
+        Pubkey::try_from(&data[..32]).unwrap()
+    };
+
+    let (status, report) = run(poc, &url, false);
+    assert_eq!(status, 0, "{report:#}");
+    assert_eq!(report["setup"].as_array().unwrap().len(), 2);
+    assert!(report.get("lamports_taken").is_none());
+    assert_eq!(authority(&report), attacker().pubkey());
+    let shown =
+        |when: usize| report["snapshots"][when]["decoded"][0]["fields"]["authority"].clone();
+    assert_ne!(shown(0), attacker().pubkey().to_string());
+    assert_eq!(shown(1), attacker().pubkey().to_string());
+
+    let (status, report) = run(poc, &url, true);
+    assert_eq!(status, 1, "{report:#}");
+    assert_ne!(authority(&report), attacker().pubkey());
+}
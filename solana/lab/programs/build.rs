@@ -0,0 +1,67 @@
+//! The build script every program crate shares. It writes the crate's root
+//! for `program.rs` to include: the crate's `declare_id!`, from
+//! `Programs.toml` for the cluster being built for (`LAB_CLUSTER`,
+//! localnet by default), then the example file named by the crate's
+//! `[package.metadata.lab] source`, included as it is, or the file
+//! `LAB_SOURCE_<CRATE>` names in its place, which is how `vuln-lab
+//! mutants` builds a secure crate with one of its checks taken out.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn main() {
+    let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let manifest = manifest_dir.join("Cargo.toml");
+    let config = manifest_dir.join("../../Programs.toml");
+    println!("cargo:rerun-if-changed={}", manifest.display());
+    println!("cargo:rerun-if-changed={}", config.display());
+    println!("cargo:rerun-if-env-changed=LAB_CLUSTER");
+
+    let krate = env::var("CARGO_PKG_NAME").unwrap().replace('-', "_");
+    let cluster = env::var("LAB_CLUSTER").unwrap_or_else(|_| "localnet".into());
+    let id = read(&config)
+        .get(&cluster)
+        .and_then(|ids| ids.get(&krate))
+        .and_then(toml::Value::as_str)
+        .map(str::to_string)
+        .unwrap_or_else(|| {
+            panic!(
+                "Programs.toml has no {cluster} id for {krate}; \
+                 run `cargo run -p vuln-lab -- keys --cluster {cluster}`"
+            )
+        });
+    let mutant = format!("LAB_SOURCE_{}", krate.to_uppercase());
+    println!("cargo:rerun-if-env-changed={mutant}");
+    let source = match env::var_os(&mutant) {
+        Some(path) => PathBuf::from(path),
+        None => read(&manifest)
+            .get("package")
+            .and_then(|package| package.get("metadata")?.get("lab")?.get("source"))
+            .and_then(toml::Value::as_str)
+            .map(|source| manifest_dir.join(source))
+            .unwrap_or_else(|| {
+                panic!(
+                    "{} has no [package.metadata.lab] source",
+                    manifest.display()
+                )
+            }),
+    };
+
+    let out = PathBuf::from(env::var("OUT_DIR").unwrap()).join("program.rs");
+    fs::write(
+        out,
+        format!(
+            "solana_program::declare_id!({id:?});\n\ninclude!({:?});\n",
+            source.display().to_string()
+        ),
+    )
+    .unwrap();
+}
+
+fn read(path: &Path) -> toml::Table {
+    let text =
+        fs::read_to_string(path).unwrap_or_else(|err| panic!("reading {}: {err}", path.display()));
+    text.parse()
+        .unwrap_or_else(|err| panic!("parsing {}: {err}", path.display()))
+}
@@ -0,0 +1,3 @@
+// The root of every program crate: its declare_id! and the example file it
+// builds, both written by build.rs
+include!(concat!(env!("OUT_DIR"), "/program.rs"));
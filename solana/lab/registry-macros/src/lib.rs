@@ -0,0 +1,161 @@
+//! `#[vulnerability]`: implements `lab_registry::Vulnerability` for an
+//! exploit function and registers it in `lab_registry::VULNERABILITIES`
+//!
+//! ```ignore
+//! /// the victim's deposit account is passed unsigned and drained to the attacker
+//! #[vulnerability(
+//!     id = "SOL-001",
+//!     category = "MissingSignerCheck",
+//!     severity = "Critical",
+//!     difficulty = "Beginner",
+//!     rejection = "Validation(MissingSignature)",
+//!     reference(title = "Sealevel Attacks: signer authorization", url = "https://..."),
+//! )]
+//! fn exploit(harness: &mut Harness) -> AnyResult<()> { ... }
+//! ```
+//!
+//! The function's doc comment is the description unless `description` is
+//! given, and the example is the name of the module it is in unless
+//! `example` is given. Each `prerequisite = "example"` names an example
+//! to work through first. `rejection`, which every example gives, is how
+//! the exploit must fail against the secure version, as
+//! `Vulnerability::rejection` matches it.
+//! `honest_use = function` names a function taking the same harness that
+//! uses the example legitimately. The exploit returns anything that
+//! converts into an `ExploitOutcome`.
+
+mod properties;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Error, ItemFn, Result};
+
+use properties::{doc_comment, required, variant, Properties};
+
+#[proc_macro_attribute]
+pub fn vulnerability(args: TokenStream, item: TokenStream) -> TokenStream {
+    let mut properties = Properties::default();
+    let parser = syn::meta::parser(|meta| properties.parse(meta));
+    parse_macro_input!(args with parser);
+    let function = parse_macro_input!(item as ItemFn);
+    expand(properties, function)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand(properties: Properties, function: ItemFn) -> Result<TokenStream2> {
+    let id = required(properties.id, "id")?;
+    let category = variant(&required(properties.category, "category")?)?;
+    let severity = variant(&required(properties.severity, "severity")?)?;
+    let difficulty = variant(&required(properties.difficulty, "difficulty")?)?;
+    let prerequisites = properties.prerequisites;
+    let description = match properties.description.or_else(|| doc_comment(&function)) {
+        Some(description) => description,
+        None => {
+            return Err(Error::new_spanned(
+                &function.sig,
+                "document the exploit or give `description = \"...\"`",
+            ))
+        }
+    };
+    let example = match properties.example {
+        Some(example) => quote!(#example),
+        None => quote! {
+            match ::core::module_path!().rsplit("::").next() {
+                Some(module) => module,
+                None => "",
+            }
+        },
+    };
+    let (titles, urls): (Vec<_>, Vec<_>) = properties.references.into_iter().unzip();
+    let Some(rejection) = properties.rejection else {
+        return Err(Error::new_spanned(
+            &function.sig,
+            "name how the secure version stops the exploit with `rejection = \"...\"`",
+        ));
+    };
+    let honest_use = properties.honest_use.map(|honest_use| {
+        quote! {
+            fn honest_use(
+                &self,
+                harness: &mut ::lab_registry::Harness,
+            ) -> ::core::option::Option<::lab_registry::anyhow::Result<()>> {
+                ::core::option::Option::Some(#honest_use(harness))
+            }
+        }
+    });
+
+    let name = &function.sig.ident;
+    let camel: String = name
+        .to_string()
+        .split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect();
+    let ty = format_ident!("{camel}Vulnerability");
+    let registration = format_ident!("__REGISTER_{}", name.to_string().to_uppercase());
+
+    Ok(quote! {
+        #function
+
+        #[doc = #description]
+        pub struct #ty;
+
+        impl ::lab_registry::Vulnerability for #ty {
+            fn id(&self) -> &'static str {
+                #id
+            }
+
+            fn example(&self) -> &'static str {
+                #example
+            }
+
+            fn category(&self) -> ::lab_registry::Category {
+                ::lab_registry::Category::#category
+            }
+
+            fn severity(&self) -> ::lab_registry::Severity {
+                ::lab_registry::Severity::#severity
+            }
+
+            fn difficulty(&self) -> ::lab_registry::Difficulty {
+                ::lab_registry::Difficulty::#difficulty
+            }
+
+            fn prerequisites(&self) -> &'static [&'static str] {
+                &[#(#prerequisites),*]
+            }
+
+            fn description(&self) -> &'static str {
+                #description
+            }
+
+            fn references(&self) -> &'static [::lab_registry::Reference] {
+                &[#(::lab_registry::Reference { title: #titles, url: #urls }),*]
+            }
+
+            fn rejection(&self) -> &'static str {
+                #rejection
+            }
+
+            fn exploit(
+                &self,
+                harness: &mut ::lab_registry::Harness,
+            ) -> ::lab_registry::ExploitOutcome {
+                ::core::convert::Into::into(#name(harness))
+            }
+
+            #honest_use
+        }
+
+        #[::lab_registry::linkme::distributed_slice(::lab_registry::VULNERABILITIES)]
+        #[linkme(crate = ::lab_registry::linkme)]
+        static #registration: &'static dyn ::lab_registry::Vulnerability = &#ty;
+    })
+}
@@ -0,0 +1,113 @@
+//! What a `#[vulnerability]` attribute says, parsed
+//!
+//! Shared with `registry/build.rs`, which reads the same attributes to
+//! catalog the examples without compiling their exploits.
+
+use proc_macro2::Span;
+use syn::meta::ParseNestedMeta;
+use syn::{Error, Expr, ExprLit, Ident, ItemFn, Lit, LitStr, Meta, Path, Result};
+
+#[derive(Default)]
+pub struct Properties {
+    pub id: Option<LitStr>,
+    pub example: Option<LitStr>,
+    pub category: Option<LitStr>,
+    pub severity: Option<LitStr>,
+    pub difficulty: Option<LitStr>,
+    pub description: Option<LitStr>,
+    pub rejection: Option<LitStr>,
+    pub references: Vec<(LitStr, LitStr)>,
+    pub prerequisites: Vec<LitStr>,
+    pub honest_use: Option<Path>,
+}
+
+impl Properties {
+    pub fn parse(&mut self, meta: ParseNestedMeta) -> Result<()> {
+        let slot = if meta.path.is_ident("id") {
+            &mut self.id
+        } else if meta.path.is_ident("example") {
+            &mut self.example
+        } else if meta.path.is_ident("category") {
+            &mut self.category
+        } else if meta.path.is_ident("severity") {
+            &mut self.severity
+        } else if meta.path.is_ident("difficulty") {
+            &mut self.difficulty
+        } else if meta.path.is_ident("description") {
+            &mut self.description
+        } else if meta.path.is_ident("rejection") {
+            &mut self.rejection
+        } else if meta.path.is_ident("prerequisite") {
+            self.prerequisites.push(meta.value()?.parse()?);
+            return Ok(());
+        } else if meta.path.is_ident("reference") {
+            let (mut title, mut url) = (None, None);
+            meta.parse_nested_meta(|nested| {
+                if nested.path.is_ident("title") {
+                    title = Some(nested.value()?.parse()?);
+                } else if nested.path.is_ident("url") {
+                    url = Some(nested.value()?.parse()?);
+                } else {
+                    return Err(nested.error("expected `title` or `url`"));
+                }
+                Ok(())
+            })?;
+            match (title, url) {
+                (Some(title), Some(url)) => self.references.push((title, url)),
+                _ => return Err(meta.error("a reference needs both `title` and `url`")),
+            }
+            return Ok(());
+        } else if meta.path.is_ident("honest_use") {
+            if self.honest_use.is_some() {
+                return Err(meta.error("duplicate vulnerability property"));
+            }
+            self.honest_use = Some(meta.value()?.parse()?);
+            return Ok(());
+        } else {
+            return Err(meta.error("unknown vulnerability property"));
+        };
+        if slot.is_some() {
+            return Err(meta.error("duplicate vulnerability property"));
+        }
+        *slot = Some(meta.value()?.parse()?);
+        Ok(())
+    }
+}
+
+pub fn required(value: Option<LitStr>, name: &str) -> Result<LitStr> {
+    value.ok_or_else(|| {
+        Error::new(
+            Span::call_site(),
+            format!("#[vulnerability] needs `{name} = \"...\"`"),
+        )
+    })
+}
+
+/// `"MissingSignerCheck"` as a variant name, reported at the string if the
+/// enum has no such variant
+pub fn variant(value: &LitStr) -> Result<Ident> {
+    syn::parse_str::<Ident>(&value.value())
+        .map(|ident| Ident::new(&ident.to_string(), value.span()))
+        .map_err(|_| Error::new(value.span(), "expected a variant name"))
+}
+
+/// The function's `///` lines, joined into one
+pub fn doc_comment(function: &ItemFn) -> Option<LitStr> {
+    let lines: Vec<String> = function
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta {
+            Meta::NameValue(doc) => match &doc.value {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(line),
+                    ..
+                }) => Some(line.value().trim().to_string()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .filter(|line| !line.is_empty())
+        .collect();
+    (!lines.is_empty()).then(|| LitStr::new(&lines.join(" "), function.sig.ident.span()))
+}
@@ -0,0 +1,315 @@
+//! Writes `CATALOG`: what every `#[vulnerability]` function in the
+//! directory named by `[package.metadata.lab] exploits` says of its
+//! example, the first paragraph of its module's docs as the scenario, and
+//! the Borsh structs of the example's two versions in the directory named
+//! by `[package.metadata.lab] examples`, including those they re-export
+//! from the shared types in `[package.metadata.lab] domain`.
+//! The attributes are parsed as the macro parses them, so the catalog and
+//! the registered vulnerabilities agree without the exploits, or anything
+//! Solana, being compiled.
+
+#[allow(dead_code)] // honest_use is the macro's business
+#[path = "../registry-macros/src/properties.rs"]
+mod properties;
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    Attribute, Expr, ExprLit, GenericArgument, Ident, Item, ItemStruct, Lit, LitStr, Meta,
+    PathArguments, Type, UseTree,
+};
+
+use properties::{doc_comment, required, variant, Properties};
+
+fn main() {
+    let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let manifest = manifest_dir.join("Cargo.toml");
+    println!("cargo:rerun-if-changed={}", manifest.display());
+    println!("cargo:rerun-if-changed=../registry-macros/src/properties.rs");
+    let table = read(&manifest);
+    let lab_path = |key: &str| {
+        table
+            .get("package")
+            .and_then(|package| package.get("metadata")?.get("lab")?.get(key))
+            .and_then(toml::Value::as_str)
+            .map(|path| manifest_dir.join(path))
+            .unwrap_or_else(|| panic!("{} has no [package.metadata.lab] {key}", manifest.display()))
+    };
+    let exploits = lab_path("exploits");
+    let examples = lab_path("examples");
+    let domain = domain(&lab_path("domain"));
+    println!("cargo:rerun-if-changed={}", exploits.display());
+
+    let mut entries = Vec::new();
+    for entry in fs::read_dir(&exploits).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_some_and(|extension| extension == "rs") {
+            println!("cargo:rerun-if-changed={}", path.display());
+            entries.extend(catalog(&path, &examples, &domain));
+        }
+    }
+    entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+    let entries = entries.into_iter().map(|(_, entry)| entry);
+
+    let out = PathBuf::from(env::var("OUT_DIR").unwrap()).join("catalog.rs");
+    fs::write(out, quote!(&[#(#entries),*]).to_string()).unwrap();
+}
+
+/// The file's `#[vulnerability]` functions, each with its example's name
+fn catalog(path: &Path, examples: &Path, domain: &[ItemStruct]) -> Vec<(String, TokenStream)> {
+    let source = fs::read_to_string(path).unwrap();
+    let file =
+        syn::parse_file(&source).unwrap_or_else(|err| panic!("parsing {}: {err}", path.display()));
+    let module = path.file_stem().unwrap().to_string_lossy().into_owned();
+    let scenario = scenario(&file.attrs);
+
+    let mut entries = Vec::new();
+    for item in &file.items {
+        let Item::Fn(function) = item else {
+            continue;
+        };
+        let Some(attribute) = function
+            .attrs
+            .iter()
+            .find(|attr| attr.path().is_ident("vulnerability"))
+        else {
+            continue;
+        };
+        let entry = || -> syn::Result<_> {
+            let mut properties = Properties::default();
+            attribute.parse_nested_meta(|meta| properties.parse(meta))?;
+            let example = properties.example.map_or(module.clone(), |lit| lit.value());
+            let id = required(properties.id, "id")?;
+            let category = variant(&required(properties.category, "category")?)?;
+            let severity = variant(&required(properties.severity, "severity")?)?;
+            let difficulty = variant(&required(properties.difficulty, "difficulty")?)?;
+            let prerequisites = properties.prerequisites;
+            let description = properties
+                .description
+                .or_else(|| doc_comment(function))
+                .ok_or_else(|| syn::Error::new_spanned(&function.sig, "no description"))?;
+            let (titles, urls): (Vec<_>, Vec<_>) = properties.references.into_iter().unzip();
+            let accounts = layouts(&examples.join(format!("{example}.rs")), domain);
+            let secure_accounts = layouts(&examples.join(format!("secure/{example}.rs")), domain);
+            let tokens = quote! {
+                crate::Metadata {
+                    id: #id,
+                    example: #example,
+                    category: crate::Category::#category,
+                    severity: crate::Severity::#severity,
+                    difficulty: crate::Difficulty::#difficulty,
+                    prerequisites: &[#(#prerequisites),*],
+                    description: #description,
+                    scenario: #scenario,
+                    references: &[#(crate::Reference { title: #titles, url: #urls }),*],
+                    accounts: &[#(#accounts),*],
+                    secure_accounts: &[#(#secure_accounts),*],
+                }
+            };
+            Ok((example, tokens))
+        };
+        entries.push(entry().unwrap_or_else(|err| {
+            let start = err.span().start();
+            panic!("{}:{}: {err}", path.display(), start.line)
+        }));
+    }
+    entries
+}
+
+/// The first paragraph of a module's `//!` docs, as one line
+fn scenario(attrs: &[Attribute]) -> LitStr {
+    let mut lines = Vec::new();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("doc")) {
+        let Meta::NameValue(doc) = &attr.meta else {
+            continue;
+        };
+        let Expr::Lit(ExprLit {
+            lit: Lit::Str(line),
+            ..
+        }) = &doc.value
+        else {
+            continue;
+        };
+        let line = line.value().trim().to_string();
+        if line.is_empty() {
+            if lines.is_empty() {
+                continue;
+            }
+            break;
+        }
+        lines.push(line);
+    }
+    LitStr::new(&lines.join(" "), proc_macro2::Span::call_site())
+}
+
+/// Every struct the shared domain types' sources declare
+fn domain(dir: &Path) -> Vec<ItemStruct> {
+    println!("cargo:rerun-if-changed={}", dir.display());
+    let mut structs = Vec::new();
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_some_and(|extension| extension == "rs") {
+            println!("cargo:rerun-if-changed={}", path.display());
+            structs.extend(
+                parse(&path)
+                    .items
+                    .into_iter()
+                    .filter_map(|item| match item {
+                        Item::Struct(item) => Some(item),
+                        _ => None,
+                    }),
+            );
+        }
+    }
+    structs
+}
+
+/// The Borsh structs an example declares or re-exports from `lab_domain`,
+/// as `crate::Layout`s; none if it has no such file
+fn layouts(path: &Path, domain: &[ItemStruct]) -> Vec<TokenStream> {
+    println!("cargo:rerun-if-changed={}", path.display());
+    if !path.exists() {
+        return Vec::new();
+    }
+    let file = parse(path);
+    let mut imported = Vec::new();
+    for item in &file.items {
+        if let Item::Use(item) = item {
+            imported_names(&item.tree, None, &mut imported);
+        }
+    }
+    // The shared types derive AccountDiscriminator for the versions that
+    // write one, and only those versions import the trait
+    let writes_discriminators = imported
+        .iter()
+        .any(|(_, name)| name == "AccountDiscriminator");
+    let shared = imported
+        .iter()
+        .filter(|(root, _)| root == "lab_domain")
+        .filter_map(|(_, name)| domain.iter().find(|item| item.ident == name))
+        .map(|item| (item, writes_discriminators));
+    let own = file.items.iter().filter_map(|item| match item {
+        Item::Struct(item) => Some((item, true)),
+        _ => None,
+    });
+    shared
+        .chain(own)
+        .filter(|(item, _)| derives(&item.attrs, "BorshDeserialize"))
+        .map(|(item, may_discriminate)| {
+            let name = item.ident.to_string();
+            // #[derive(AccountDiscriminator)] writes 8 bytes ahead of the fields
+            let discriminator = (may_discriminate && derives(&item.attrs, "AccountDiscriminator"))
+                .then(|| {
+                    quote!(crate::Field {
+                        name: "discriminator",
+                        ty: crate::FieldType::Array(&crate::FieldType::U8, 8),
+                    })
+                });
+            let fields = item.fields.iter().map(|field| {
+                let name = field.ident.as_ref().map_or(String::new(), Ident::to_string);
+                let ty = field_type(&field.ty);
+                quote!(crate::Field { name: #name, ty: #ty })
+            });
+            let fields = discriminator.into_iter().chain(fields);
+            quote!(crate::Layout { name: #name, fields: &[#(#fields),*] })
+        })
+        .collect()
+}
+
+/// Every name a `use` tree brings in, with the crate it starts from
+fn imported_names(tree: &UseTree, root: Option<&Ident>, names: &mut Vec<(String, String)>) {
+    let root_name = || root.map_or(String::new(), Ident::to_string);
+    match tree {
+        UseTree::Path(path) => imported_names(&path.tree, root.or(Some(&path.ident)), names),
+        UseTree::Name(name) => names.push((root_name(), name.ident.to_string())),
+        UseTree::Rename(rename) => names.push((root_name(), rename.ident.to_string())),
+        UseTree::Group(group) => {
+            for tree in &group.items {
+                imported_names(tree, root, names);
+            }
+        }
+        UseTree::Glob(_) => {}
+    }
+}
+
+fn derives(attrs: &[Attribute], name: &str) -> bool {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("derive"))
+        .any(|attr| {
+            let mut found = false;
+            let _ = attr.parse_nested_meta(|meta| {
+                found |= meta.path.is_ident(name);
+                Ok(())
+            });
+            found
+        })
+}
+
+/// A field's type as a `crate::FieldType`
+fn field_type(ty: &Type) -> TokenStream {
+    let other = || {
+        let name = quote!(#ty).to_string();
+        quote!(crate::FieldType::Other(#name))
+    };
+    match ty {
+        Type::Array(array) => {
+            let Expr::Lit(ExprLit {
+                lit: Lit::Int(len), ..
+            }) = &array.len
+            else {
+                return other();
+            };
+            let element = field_type(&array.elem);
+            quote!(crate::FieldType::Array(&#element, #len))
+        }
+        Type::Path(path) if path.qself.is_none() => {
+            let Some(segment) = path.path.segments.last() else {
+                return other();
+            };
+            let argument = match &segment.arguments {
+                PathArguments::AngleBracketed(arguments) => match arguments.args.first() {
+                    Some(GenericArgument::Type(argument)) => Some(field_type(argument)),
+                    _ => None,
+                },
+                _ => None,
+            };
+            match (segment.ident.to_string().as_str(), argument) {
+                ("Vec", Some(element)) => quote!(crate::FieldType::Vec(&#element)),
+                ("Option", Some(inner)) => quote!(crate::FieldType::Option(&#inner)),
+                ("bool", None) => quote!(crate::FieldType::Bool),
+                ("u8", None) => quote!(crate::FieldType::U8),
+                ("u16", None) => quote!(crate::FieldType::U16),
+                ("u32", None) => quote!(crate::FieldType::U32),
+                ("u64", None) => quote!(crate::FieldType::U64),
+                ("u128", None) => quote!(crate::FieldType::U128),
+                ("i8", None) => quote!(crate::FieldType::I8),
+                ("i16", None) => quote!(crate::FieldType::I16),
+                ("i32", None) => quote!(crate::FieldType::I32),
+                ("i64", None) => quote!(crate::FieldType::I64),
+                ("i128", None) => quote!(crate::FieldType::I128),
+                ("Pubkey", None) => quote!(crate::FieldType::Pubkey),
+                ("String", None) => quote!(crate::FieldType::String),
+                _ => other(),
+            }
+        }
+        _ => other(),
+    }
+}
+
+fn parse(path: &Path) -> syn::File {
+    let source =
+        fs::read_to_string(path).unwrap_or_else(|err| panic!("reading {}: {err}", path.display()));
+    syn::parse_file(&source).unwrap_or_else(|err| panic!("parsing {}: {err}", path.display()))
+}
+
+fn read(path: &Path) -> toml::Table {
+    let text =
+        fs::read_to_string(path).unwrap_or_else(|err| panic!("reading {}: {err}", path.display()));
+    text.parse()
+        .unwrap_or_else(|err| panic!("parsing {}: {err}", path.display()))
+}
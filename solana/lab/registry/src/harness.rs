@@ -0,0 +1,82 @@
+//! Where exploits run
+
+use std::ops::{Deref, DerefMut};
+
+use anyhow::Result as AnyResult;
+use lab_svm::Svm;
+
+/// Which build of an example an exploit is attacking
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Version {
+    /// The example file under `solana/`
+    Vulnerable,
+    /// Its fixed counterpart under `solana/secure/`
+    Secure,
+}
+
+/// A fresh [`Svm`] and the version of the example to deploy on it
+///
+/// Derefs to the runtime, so exploit code takes a `&mut Svm`.
+pub struct Harness {
+    svm: Svm,
+    version: Version,
+}
+
+impl Harness {
+    pub fn new(version: Version) -> Self {
+        Self {
+            svm: Svm::new(),
+            version,
+        }
+    }
+
+    pub fn version(&self) -> Version {
+        self.version
+    }
+}
+
+impl Deref for Harness {
+    type Target = Svm;
+
+    fn deref(&self) -> &Svm {
+        &self.svm
+    }
+}
+
+impl DerefMut for Harness {
+    fn deref_mut(&mut self) -> &mut Svm {
+        &mut self.svm
+    }
+}
+
+/// How an exploit ended
+#[derive(Debug)]
+pub enum ExploitOutcome {
+    /// The attack reached its goal
+    Succeeded,
+    /// It did not, because a transaction was rejected or a profit check
+    /// came up short
+    Failed(anyhow::Error),
+}
+
+impl ExploitOutcome {
+    pub fn succeeded(&self) -> bool {
+        matches!(self, ExploitOutcome::Succeeded)
+    }
+
+    pub fn into_result(self) -> AnyResult<()> {
+        match self {
+            ExploitOutcome::Succeeded => Ok(()),
+            ExploitOutcome::Failed(err) => Err(err),
+        }
+    }
+}
+
+impl From<AnyResult<()>> for ExploitOutcome {
+    fn from(result: AnyResult<()>) -> Self {
+        match result {
+            Ok(()) => ExploitOutcome::Succeeded,
+            Err(err) => ExploitOutcome::Failed(err),
+        }
+    }
+}
@@ -0,0 +1,187 @@
+//! What each vulnerable Solana example is, and how it is exploited
+//!
+//! Every example implements [`Vulnerability`]: a stable id, the class of
+//! bug, how bad it is, where to read more, and an exploit that runs on a
+//! [`Harness`] against either version of the example's program.
+//! [`macro@vulnerability`] implements the trait for an exploit function and
+//! registers it in [`VULNERABILITIES`], and [`Registry::collect`] gathers
+//! them, so the CLI, the tests and the report generator all enumerate the
+//! same examples without a list to keep up to date.
+//!
+//! The build script reads the same `#[vulnerability]` attributes, and the
+//! exploit modules' docs, into [`CATALOG`], which needs nothing compiled.
+//! Without the default `harness` feature the crate is that metadata alone,
+//! with no Solana dependencies, and builds for `wasm32-unknown-unknown`;
+//! the `wasm` feature adds bindings for web tooling, in `wasm`:
+//!
+//! ```bash
+//! cargo build -p lab-registry --target wasm32-unknown-unknown --no-default-features --features wasm
+//! ```
+
+#[cfg(feature = "harness")]
+mod harness;
+#[cfg(feature = "harness")]
+mod vulnerability;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+use std::fmt;
+
+use serde::Serialize;
+
+#[cfg(feature = "harness")]
+#[doc(hidden)]
+pub use anyhow;
+#[cfg(feature = "harness")]
+pub use harness::{ExploitOutcome, Harness, Version};
+#[cfg(feature = "harness")]
+pub use lab_registry_macros::vulnerability;
+#[cfg(feature = "harness")]
+#[doc(hidden)]
+pub use linkme;
+#[cfg(feature = "harness")]
+pub use vulnerability::{rejected_with, Registry, VerifyFailure, Vulnerability, VULNERABILITIES};
+
+/// The class of bug an example demonstrates
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum Category {
+    MissingSignerCheck,
+    MissingOwnerCheck,
+    ArbitraryCpi,
+    PdaValidation,
+    Reinitialization,
+    TypeConfusion,
+    ArithmeticOverflow,
+    AccountDataMatching,
+    RentExemption,
+    AccountVersioning,
+    DenialOfService,
+}
+
+impl Category {
+    /// Every category, in the README's order
+    pub const ALL: [Category; 11] = [
+        Category::MissingSignerCheck,
+        Category::MissingOwnerCheck,
+        Category::ArbitraryCpi,
+        Category::PdaValidation,
+        Category::Reinitialization,
+        Category::TypeConfusion,
+        Category::ArithmeticOverflow,
+        Category::AccountDataMatching,
+        Category::RentExemption,
+        Category::AccountVersioning,
+        Category::DenialOfService,
+    ];
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+/// Impact, as rated in the README's summary table
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum Severity {
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+/// How much of Solana's account model an example takes to follow
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum Difficulty {
+    Beginner,
+    Intermediate,
+    Advanced,
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+/// Further reading on a vulnerability
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct Reference {
+    pub title: &'static str,
+    pub url: &'static str,
+}
+
+/// An account type a program stores, as Borsh lays it out
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct Layout {
+    pub name: &'static str,
+    pub fields: &'static [Field],
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct Field {
+    pub name: &'static str,
+    pub ty: FieldType,
+}
+
+/// A field's type, as far as its encoding goes
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum FieldType {
+    Bool,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+    Pubkey,
+    String,
+    Array(&'static FieldType, usize),
+    Vec(&'static FieldType),
+    Option(&'static FieldType),
+    /// A type defined elsewhere, which the layout cannot see into
+    Other(&'static str),
+}
+
+/// An example as the catalog knows it: what its exploit's
+/// `#[vulnerability]` attribute and module docs say
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct Metadata {
+    pub id: &'static str,
+    pub example: &'static str,
+    pub category: Category,
+    pub severity: Severity,
+    pub difficulty: Difficulty,
+    /// Examples to work through first
+    pub prerequisites: &'static [&'static str],
+    /// What the exploit does, in one line
+    pub description: &'static str,
+    /// The attack, as the exploit module's docs tell it
+    pub scenario: &'static str,
+    pub references: &'static [Reference],
+    /// The Borsh structs the example's program stores in accounts
+    pub accounts: &'static [Layout],
+    /// Its secure version's, which may add a flag or a discriminator
+    pub secure_accounts: &'static [Layout],
+}
+
+/// Every example with an exploit, sorted by example file name as a
+/// `Registry` is
+pub static CATALOG: &[Metadata] = include!(concat!(env!("OUT_DIR"), "/catalog.rs"));
+
+/// Looks an example up in [`CATALOG`] by its id or its example file name
+pub fn metadata(name: &str) -> Option<&'static Metadata> {
+    CATALOG
+        .iter()
+        .find(|metadata| metadata.id == name || metadata.example == name)
+}
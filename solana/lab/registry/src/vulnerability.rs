@@ -0,0 +1,165 @@
+//! The exploit side: the trait every example implements and where the
+//! implementations are collected
+
+use std::fmt;
+
+use lab_svm::{FailedTransaction, TransactionError};
+use linkme::distributed_slice;
+
+use crate::{Category, Difficulty, ExploitOutcome, Harness, Layout, Reference, Severity, Version};
+
+/// Every vulnerability registered with [`macro@crate::vulnerability`], in link order
+#[distributed_slice]
+pub static VULNERABILITIES: [&'static dyn Vulnerability];
+
+/// One vulnerable example and its exploit
+///
+/// [`exploit`](Vulnerability::exploit) deploys the programs it needs on the
+/// harness, picking the vulnerable or secure build by
+/// [`Harness::version`], sets up the victim's state and succeeds only when
+/// the attack reached its goal (stole funds, took over an account,
+/// destroyed a record).
+pub trait Vulnerability: Sync {
+    /// Stable identifier, `SOL-` and the example's number in the README
+    fn id(&self) -> &'static str;
+    /// Example file under `solana/`, without the `.rs` extension
+    fn example(&self) -> &'static str;
+    fn category(&self) -> Category;
+    fn severity(&self) -> Severity;
+    fn difficulty(&self) -> Difficulty;
+    /// Examples, by file name, to work through before this one
+    fn prerequisites(&self) -> &'static [&'static str] {
+        &[]
+    }
+    /// What the exploit does, in one line
+    fn description(&self) -> &'static str;
+    fn references(&self) -> &'static [Reference];
+    /// How the exploit must fail against the secure version, as
+    /// [`rejected_with`] matches it: usually the error the fix's check
+    /// returns, or where every transaction goes through and only the
+    /// payoff is missing, the attack's own profit check
+    fn rejection(&self) -> &'static str;
+    /// The account types `version` of the example's program stores, from
+    /// [`CATALOG`](crate::CATALOG)
+    fn accounts(&self, version: Version) -> &'static [Layout] {
+        match (crate::metadata(self.example()), version) {
+            (Some(metadata), Version::Vulnerable) => metadata.accounts,
+            (Some(metadata), Version::Secure) => metadata.secure_accounts,
+            (None, _) => &[],
+        }
+    }
+    fn exploit(&self, harness: &mut Harness) -> ExploitOutcome;
+    /// Uses the example the way it is meant to be used, so that every
+    /// check the secure version makes runs and passes; `None` if the
+    /// example has no such use to measure
+    fn honest_use(&self, _harness: &mut Harness) -> Option<anyhow::Result<()>> {
+        None
+    }
+
+    /// Runs the exploit against the vulnerable version, where it must
+    /// succeed, then against the secure one, where it must fail with its
+    /// [`rejection`](Vulnerability::rejection), each on a fresh harness
+    fn verify(&self) -> Result<(), VerifyFailure> {
+        if let ExploitOutcome::Failed(err) = self.exploit(&mut Harness::new(Version::Vulnerable)) {
+            return Err(VerifyFailure::ExploitFailed(err));
+        }
+        let expected = self.rejection();
+        match self.exploit(&mut Harness::new(Version::Secure)) {
+            ExploitOutcome::Succeeded => Err(VerifyFailure::FixBypassed),
+            ExploitOutcome::Failed(err) if rejected_with(&err, expected) => Ok(()),
+            ExploitOutcome::Failed(err) => Err(VerifyFailure::WrongRejection { expected, err }),
+        }
+    }
+
+    /// Panics with the example name if either side of the pairing breaks
+    fn assert(&self) {
+        if let Err(failure) = self.verify() {
+            panic!("{}: {failure}", self.example());
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum VerifyFailure {
+    /// The exploit did not work against the vulnerable program
+    ExploitFailed(anyhow::Error),
+    /// The exploit still worked against the secure program
+    FixBypassed,
+    /// The exploit failed against the secure program, but not with the
+    /// rejection the example names: a setup mistake, or a fix that stops it
+    /// by accident
+    WrongRejection {
+        expected: &'static str,
+        err: anyhow::Error,
+    },
+}
+
+impl fmt::Display for VerifyFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerifyFailure::ExploitFailed(err) => {
+                write!(f, "exploit failed against vulnerable program: {err:#}")
+            }
+            VerifyFailure::FixBypassed => write!(f, "exploit succeeded against secure program"),
+            VerifyFailure::WrongRejection { expected, err } => write!(
+                f,
+                "exploit failed against secure program without {expected:?}: {err:#}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for VerifyFailure {}
+
+/// Whether `err` is the rejection `expected` names: `expected` is in its
+/// message or the message of an error it wraps, or, for a failed
+/// transaction, in the shared error it carries, by variant as
+/// `Validation(WrongOwner)`
+pub fn rejected_with(err: &anyhow::Error, expected: &str) -> bool {
+    err.chain().any(|cause| {
+        cause.to_string().contains(expected)
+            || cause
+                .downcast_ref::<FailedTransaction>()
+                .and_then(|failed| match &failed.err {
+                    TransactionError::InstructionError(_, error) => lab_errors::decode(error),
+                    _ => None,
+                })
+                .is_some_and(|lab_error| format!("{lab_error:?}").contains(expected))
+    })
+}
+
+/// A set of vulnerabilities, sorted by example file name
+pub struct Registry {
+    vulnerabilities: Vec<&'static dyn Vulnerability>,
+}
+
+impl Registry {
+    pub fn new(vulnerabilities: impl IntoIterator<Item = &'static dyn Vulnerability>) -> Self {
+        let mut vulnerabilities: Vec<_> = vulnerabilities.into_iter().collect();
+        vulnerabilities.sort_by_key(|vulnerability| vulnerability.example());
+        Self { vulnerabilities }
+    }
+
+    /// Everything in [`VULNERABILITIES`]
+    pub fn collect() -> Self {
+        Self::new(VULNERABILITIES.iter().copied())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &'static dyn Vulnerability> + '_ {
+        self.vulnerabilities.iter().copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.vulnerabilities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vulnerabilities.is_empty()
+    }
+
+    /// Looks up a vulnerability by its id or its example file name
+    pub fn find(&self, name: &str) -> Option<&'static dyn Vulnerability> {
+        self.iter()
+            .find(|vulnerability| vulnerability.id() == name || vulnerability.example() == name)
+    }
+}
@@ -0,0 +1,25 @@
+//! Bindings for web tooling, through wasm-bindgen
+//!
+//! ```js
+//! import init, { vulnerabilities } from "./lab_registry.js";
+//! await init();
+//! for (const { id, example, category, scenario } of JSON.parse(vulnerabilities())) {
+//!     // ...
+//! }
+//! ```
+
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::{Category, CATALOG};
+
+/// [`CATALOG`] as a JSON array
+#[wasm_bindgen]
+pub fn vulnerabilities() -> String {
+    serde_json::to_string(CATALOG).expect("the catalog serializes")
+}
+
+/// Every [`Category`], as a JSON array of names
+#[wasm_bindgen]
+pub fn categories() -> String {
+    serde_json::to_string(&Category::ALL).expect("categories serialize")
+}
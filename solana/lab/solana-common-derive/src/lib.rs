@@ -0,0 +1,38 @@
+//! `#[derive(AccountDiscriminator)]`: implements
+//! `solana_common::discriminator::AccountDiscriminator` for an account type
+//!
+//! ```
+//! # use borsh::{BorshDeserialize, BorshSerialize};
+//! # use solana_common::discriminator::AccountDiscriminator;
+//! # use solana_program::pubkey::Pubkey;
+//! #[derive(BorshSerialize, BorshDeserialize, AccountDiscriminator)]
+//! pub struct VaultConfig {
+//!     pub authority: Pubkey,
+//! }
+//! ```
+//!
+//! The discriminator is the first 8 bytes of the SHA-256 of
+//! `account:<TypeName>`, the same as Anchor's, computed when the program
+//! is compiled.
+
+use proc_macro::TokenStream;
+use quote::quote;
+use sha2::{Digest, Sha256};
+use syn::{parse_macro_input, DeriveInput};
+
+#[proc_macro_derive(AccountDiscriminator)]
+pub fn account_discriminator(item: TokenStream) -> TokenStream {
+    let item = parse_macro_input!(item as DeriveInput);
+    let name = &item.ident;
+    let (impl_generics, type_generics, where_clause) = item.generics.split_for_impl();
+    let hash = Sha256::digest(format!("account:{name}"));
+    let discriminator = &hash[..8];
+    quote! {
+        impl #impl_generics ::solana_common::discriminator::AccountDiscriminator
+            for #name #type_generics #where_clause
+        {
+            const DISCRIMINATOR: [u8; 8] = [#(#discriminator),*];
+        }
+    }
+    .into()
+}
@@ -0,0 +1,137 @@
+//! Who may do what, and handing over an authority without losing it
+//!
+//! A [`Role`] is a set of bit flags, and a [`Member`] is an account with
+//! the roles it has been granted. [`only_role`] is the guard an instruction
+//! starts with: the member must have signed and hold every role asked for.
+//!
+//! A [`TwoStepAuthority`] changes hands in two transactions: the current
+//! authority proposes a new one, which takes over only when it signs an
+//! accept itself. A typo in the proposal leaves the authority where it
+//! was, where setting it in one step would hand it to a key nobody holds:
+//!
+//! ```
+//! # use solana_common::access_control::{only_role, Member, Role, TwoStepAuthority};
+//! # use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult};
+//! # struct Config { admin: Member, authority: TwoStepAuthority }
+//! # fn handover(config: &mut Config, [admin, authority, new_authority]: [&AccountInfo; 3]) -> ProgramResult {
+//! only_role(admin, &config.admin, Role::PAUSER)?;
+//! config.authority.propose(authority, *new_authority.key)?; // then, signed by new_authority:
+//! config.authority.accept(new_authority)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::io;
+
+use bitflags::bitflags;
+use borsh::{BorshDeserialize, BorshSerialize};
+use lab_errors::AccessError;
+use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey};
+
+use crate::validation::{assert_key_eq, assert_signer};
+
+bitflags! {
+    /// What a member may do
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+    pub struct Role: u8 {
+        /// Grants and revokes roles
+        const ADMIN = 1 << 0;
+        /// Pauses and unpauses the program
+        const PAUSER = 1 << 1;
+        /// Moves the program's funds
+        const TREASURER = 1 << 2;
+    }
+}
+
+/// One byte, the flags
+impl BorshSerialize for Role {
+    fn serialize<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.bits().serialize(writer)
+    }
+}
+
+/// Fails on a bit no role has
+impl BorshDeserialize for Role {
+    fn deserialize_reader<R: io::Read>(reader: &mut R) -> io::Result<Self> {
+        let bits = u8::deserialize_reader(reader)?;
+        Role::from_bits(bits)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "unknown role"))
+    }
+}
+
+/// An account and the roles it holds
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Member {
+    pub key: Pubkey,
+    pub roles: Role,
+}
+
+impl Member {
+    pub const LEN: usize = 32 + 1;
+
+    pub fn new(key: Pubkey, roles: Role) -> Self {
+        Self { key, roles }
+    }
+}
+
+/// Fails unless `signer` signed, is `member` and holds every role in
+/// `required`
+pub fn only_role(signer: &AccountInfo, member: &Member, required: Role) -> ProgramResult {
+    assert_signer(signer)?;
+    assert_key_eq(signer, &member.key)?;
+    if !member.roles.contains(required) {
+        return Err(AccessError::MissingRole.into());
+    }
+    Ok(())
+}
+
+/// An authority, and the one it has offered to hand over to
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TwoStepAuthority {
+    pub authority: Pubkey,
+    pub pending: Option<Pubkey>,
+}
+
+impl TwoStepAuthority {
+    pub const LEN: usize = 32 + 1 + 32;
+
+    pub fn new(authority: Pubkey) -> Self {
+        Self {
+            authority,
+            pending: None,
+        }
+    }
+
+    /// Fails unless `signer` is the authority and signed
+    pub fn only_authority(&self, signer: &AccountInfo) -> ProgramResult {
+        assert_signer(signer)?;
+        assert_key_eq(signer, &self.authority)?;
+        Ok(())
+    }
+
+    /// Offers the authority to `new_authority`, replacing any earlier offer
+    pub fn propose(&mut self, signer: &AccountInfo, new_authority: Pubkey) -> ProgramResult {
+        self.only_authority(signer)?;
+        self.pending = Some(new_authority);
+        Ok(())
+    }
+
+    /// Takes the authority, if it was offered to `signer`
+    pub fn accept(&mut self, signer: &AccountInfo) -> ProgramResult {
+        assert_signer(signer)?;
+        let pending = self.pending.ok_or(AccessError::NoPendingAuthority)?;
+        if *signer.key != pending {
+            return Err(AccessError::NotPendingAuthority.into());
+        }
+        self.authority = pending;
+        self.pending = None;
+        Ok(())
+    }
+
+    /// Withdraws the offer
+    pub fn cancel(&mut self, signer: &AccountInfo) -> ProgramResult {
+        self.only_authority(signer)?;
+        self.pending = None;
+        Ok(())
+    }
+}
@@ -0,0 +1,131 @@
+//! Reading a program's own accounts in one call
+//!
+//! [`load`] makes every check that has to come before an account's data is
+//! trusted: this program owns it, it is long enough to hold a
+//! discriminator, the discriminator is the expected type's and the rest is
+//! exactly one value of that type. A read that leaves one out has to be
+//! spelled out by hand, where it stands out:
+//!
+//! ```
+//! # use borsh::{BorshDeserialize, BorshSerialize};
+//! # use solana_common::{account::load, discriminator::AccountDiscriminator};
+//! # use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey};
+//! # #[derive(BorshSerialize, BorshDeserialize, AccountDiscriminator)]
+//! # struct VaultData {}
+//! # fn withdraw(program_id: &Pubkey, vault_data_account: &AccountInfo) -> ProgramResult {
+//! let vault = load::<VaultData>(vault_data_account, program_id)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`close_account`] is the other end of an account's life. Draining its
+//! lamports alone leaves the data readable until the runtime collects it,
+//! and lets the same transaction refund the account and use it again;
+//! closing also zeroes the data and marks it with
+//! [`CLOSED_ACCOUNT_DISCRIMINATOR`], which [`load`] refuses.
+//!
+//! [`grow_account`] makes room for a list that has grown. Resizing alone
+//! leaves the account below rent exemption for its new size, so it also
+//! has the payer make up the difference.
+
+use lab_errors::{ArithmeticError, StateError};
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program::invoke,
+    program_error::ProgramError, pubkey::Pubkey, rent::Rent,
+};
+use solana_system_interface::{instruction as system_instruction, program as system_program};
+
+use crate::discriminator::{AccountDiscriminator, CLOSED_ACCOUNT_DISCRIMINATOR};
+use crate::validation::{assert_distinct, assert_key_eq, assert_owned_by};
+
+/// An account type that can be read, checked, from an [`AccountInfo`]
+pub trait TryFromAccountInfo: Sized {
+    fn try_from_account_info(
+        account: &AccountInfo,
+        program_id: &Pubkey,
+    ) -> Result<Self, ProgramError>;
+}
+
+/// Fails with [`ValidationError::WrongOwner`] if `program_id` does not own
+/// the account, then as [`AccountDiscriminator::try_deserialize_checked`]
+/// does: [`StateError::DataTooSmall`] if it cannot hold a discriminator,
+/// [`StateError::WrongAccountType`] if it holds another type and
+/// [`StateError::MalformedData`] if anything follows the value
+///
+/// [`ValidationError::WrongOwner`]: crate::validation::ValidationError::WrongOwner
+/// [`StateError::DataTooSmall`]: lab_errors::StateError::DataTooSmall
+/// [`StateError::WrongAccountType`]: lab_errors::StateError::WrongAccountType
+/// [`StateError::MalformedData`]: lab_errors::StateError::MalformedData
+impl<T: AccountDiscriminator> TryFromAccountInfo for T {
+    fn try_from_account_info(
+        account: &AccountInfo,
+        program_id: &Pubkey,
+    ) -> Result<Self, ProgramError> {
+        assert_owned_by(account, program_id)?;
+        T::try_deserialize_checked(&account.data.borrow())
+    }
+}
+
+/// `account`'s data as a `T`, after every check [`TryFromAccountInfo`]
+/// makes
+pub fn load<T: TryFromAccountInfo>(
+    account: &AccountInfo,
+    program_id: &Pubkey,
+) -> Result<T, ProgramError> {
+    T::try_from_account_info(account, program_id)
+}
+
+/// Closes `account`: zeroes its data, marks it closed and moves all its
+/// lamports to `destination`. Fails with
+/// [`ValidationError::DuplicateAccount`] if `destination` is `account`
+/// itself, which would leave it funded and only look closed, and with
+/// [`StateError::DataTooSmall`] if it cannot hold the mark
+///
+/// [`ValidationError::DuplicateAccount`]: crate::validation::ValidationError::DuplicateAccount
+pub fn close_account(account: &AccountInfo, destination: &AccountInfo) -> ProgramResult {
+    assert_distinct(account, destination)?;
+    {
+        let mut data = account.try_borrow_mut_data()?;
+        if data.len() < CLOSED_ACCOUNT_DISCRIMINATOR.len() {
+            return Err(StateError::DataTooSmall.into());
+        }
+        data.fill(0);
+        data[..CLOSED_ACCOUNT_DISCRIMINATOR.len()].copy_from_slice(&CLOSED_ACCOUNT_DISCRIMINATOR);
+    }
+    let lamports = account.lamports();
+    let credited = destination
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(ArithmeticError::Overflow)?;
+    **destination.try_borrow_mut_lamports()? = credited;
+    **account.try_borrow_mut_lamports()? = 0;
+    Ok(())
+}
+
+/// Grows `account`'s data to `new_len`, the new bytes zeroed, and has
+/// `payer` transfer whatever it then lacks for rent exemption through
+/// `system_program`. Fails with [`ProgramError::InvalidRealloc`] if
+/// `new_len` is shorter than the data already is
+pub fn grow_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    new_len: usize,
+    rent: &Rent,
+) -> ProgramResult {
+    assert_key_eq(system_program, &system_program::ID)?;
+    if new_len < account.data_len() {
+        return Err(ProgramError::InvalidRealloc);
+    }
+    account.resize(new_len)?;
+    let shortfall = rent
+        .minimum_balance(new_len)
+        .saturating_sub(account.lamports());
+    if shortfall > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, account.key, shortfall),
+            &[payer.clone(), account.clone(), system_program.clone()],
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,56 @@
+//! Where a program gets the time
+//!
+//! Programs that compare against a deadline or accrue over an interval
+//! take a [`ClockProvider`] rather than reading the time themselves. The
+//! entrypoint passes [`SysvarClock`], the cluster's `Clock` sysvar, and a
+//! host test passes a [`MockClock`] set to whatever moment it needs:
+//!
+//! ```
+//! # use solana_common::clock::{ClockProvider, MockClock, SysvarClock};
+//! # use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey};
+//! # fn process_instruction_with_clock(
+//! #     _: &Pubkey, _: &[AccountInfo], _: &[u8], clock: &impl ClockProvider,
+//! # ) -> ProgramResult {
+//! #     clock.unix_timestamp().map(drop)
+//! # }
+//! # fn process_instruction(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+//! // in process_instruction
+//! process_instruction_with_clock(program_id, accounts, data, &SysvarClock)
+//! # }
+//! # let (program_id, accounts, data, release_time) = (Pubkey::new_unique(), [], [], 100);
+//! // in a test
+//! process_instruction_with_clock(&program_id, &accounts, &data, &MockClock(release_time - 1))
+//! # .unwrap();
+//! ```
+
+use solana_program::{
+    clock::{Clock, UnixTimestamp},
+    program_error::ProgramError,
+    sysvar::Sysvar,
+};
+
+/// A source of the current time
+pub trait ClockProvider {
+    /// Seconds since the Unix epoch, as the cluster reckons them
+    fn unix_timestamp(&self) -> Result<UnixTimestamp, ProgramError>;
+}
+
+/// The `Clock` sysvar, read with `Clock::get`
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SysvarClock;
+
+impl ClockProvider for SysvarClock {
+    fn unix_timestamp(&self) -> Result<UnixTimestamp, ProgramError> {
+        Ok(Clock::get()?.unix_timestamp)
+    }
+}
+
+/// A clock stopped at a fixed time, for tests
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MockClock(pub UnixTimestamp);
+
+impl ClockProvider for MockClock {
+    fn unix_timestamp(&self) -> Result<UnixTimestamp, ProgramError> {
+        Ok(self.0)
+    }
+}
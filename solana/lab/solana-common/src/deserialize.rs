@@ -0,0 +1,46 @@
+//! Reading exactly one value from account data, or knowingly less
+//!
+//! [`strict_deserialize`] reads a `T` and then requires the data to be
+//! over: bytes left after it, or too few for it, are
+//! [`StateError::MalformedData`]. An account sized for one layout and read
+//! as another, or with a value smuggled in after the fields a program
+//! reads, fails instead of being half-read. Every secure version reads its
+//! accounts this way, or through [`load`](crate::account::load), which
+//! does:
+//!
+//! ```
+//! # use borsh::{BorshDeserialize, BorshSerialize};
+//! # use solana_common::deserialize::strict_deserialize;
+//! # use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult};
+//! # #[derive(BorshSerialize, BorshDeserialize)]
+//! # struct StakingPool {}
+//! # fn stake(pool_account: &AccountInfo) -> ProgramResult {
+//! let pool = strict_deserialize::<StakingPool>(&pool_account.data.borrow())?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`lenient_deserialize`] is Borsh's `deserialize` on a slice, which stops
+//! after the value and ignores the rest. It exists so that a vulnerable
+//! version can name the mistake it makes.
+
+use borsh::BorshDeserialize;
+use lab_errors::StateError;
+use solana_program::program_error::ProgramError;
+
+/// `data` as exactly one `T`; fails with [`StateError::MalformedData`] if
+/// it is not one, or has bytes left over
+pub fn strict_deserialize<T: BorshDeserialize>(data: &[u8]) -> Result<T, ProgramError> {
+    let mut rest = data;
+    let value = T::deserialize(&mut rest).map_err(|_| StateError::MalformedData)?;
+    if !rest.is_empty() {
+        return Err(StateError::MalformedData.into());
+    }
+    Ok(value)
+}
+
+/// The `T` at the start of `data`, whatever follows it; for the vulnerable
+/// versions only
+pub fn lenient_deserialize<T: BorshDeserialize>(data: &[u8]) -> Result<T, ProgramError> {
+    T::deserialize(&mut &data[..]).map_err(|_| StateError::MalformedData.into())
+}
@@ -0,0 +1,66 @@
+//! Account types that say what they are
+//!
+//! `#[derive(AccountDiscriminator)]` gives a Borsh account type an 8-byte
+//! discriminator, hashed from its name, that is written ahead of its
+//! fields. Reading the account back checks those 8 bytes before any field
+//! is trusted, so one account type cannot be passed off as another with
+//! the same layout, and an account that was never written holds no type at
+//! all:
+//!
+//! ```
+//! # use borsh::{BorshDeserialize, BorshSerialize};
+//! # use solana_common::discriminator::AccountDiscriminator;
+//! # use solana_program::account_info::AccountInfo;
+//! # #[derive(BorshSerialize, BorshDeserialize, AccountDiscriminator)]
+//! # struct UserAccount { balance: u64 }
+//! # fn withdraw(account: &AccountInfo, amount: u64) -> Result<(), Box<dyn std::error::Error>> {
+//! let mut user = UserAccount::try_deserialize_checked(&account.data.borrow())?;
+//! user.balance -= amount;
+//! user.serialize_with_discriminator(&mut &mut account.data.borrow_mut()[..])?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::io::{self, Write};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use lab_errors::StateError;
+use solana_program::program_error::ProgramError;
+
+use crate::deserialize::strict_deserialize;
+
+pub use solana_common_derive::AccountDiscriminator;
+
+/// What a closed account holds in place of its type's discriminator,
+/// Anchor's value; no type hashes to it
+pub const CLOSED_ACCOUNT_DISCRIMINATOR: [u8; 8] = [255; 8];
+
+/// A Borsh account type whose data starts with [`DISCRIMINATOR`](Self::DISCRIMINATOR)
+pub trait AccountDiscriminator: BorshSerialize + BorshDeserialize {
+    /// The first 8 bytes of the SHA-256 of `account:<TypeName>`
+    const DISCRIMINATOR: [u8; 8];
+
+    /// Reads `data` as this type; fails with [`StateError::DataTooSmall`]
+    /// if it cannot hold a discriminator, [`StateError::AccountClosed`] if
+    /// it was closed, [`StateError::WrongAccountType`] unless it starts with
+    /// this type's and [`StateError::MalformedData`] unless exactly one
+    /// value of this type follows
+    fn try_deserialize_checked(data: &[u8]) -> Result<Self, ProgramError> {
+        let (discriminator, fields) = data
+            .split_first_chunk::<8>()
+            .ok_or(StateError::DataTooSmall)?;
+        if *discriminator == CLOSED_ACCOUNT_DISCRIMINATOR {
+            return Err(StateError::AccountClosed.into());
+        }
+        if *discriminator != Self::DISCRIMINATOR {
+            return Err(StateError::WrongAccountType.into());
+        }
+        strict_deserialize(fields)
+    }
+
+    /// Writes this type's discriminator, then the value
+    fn serialize_with_discriminator<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&Self::DISCRIMINATOR)?;
+        self.serialize(writer)
+    }
+}
@@ -0,0 +1,89 @@
+//! Events a program logs for whoever reads its transactions
+//!
+//! A program calls [`emit`] when something happens that a client, a test
+//! or the log analyzer should be able to see without decoding accounts:
+//! lamports paid in or out, a new authority. Each is logged with
+//! `sol_log_data`, which the runtime writes as a `Program data:` line of
+//! two base64 fields, the [`VERSION`] of the layout and the [`Event`] in
+//! Borsh. [`Event::decode`] reads those fields back on the host:
+//!
+//! ```
+//! # use solana_common::events::{emit, WithdrawEvent};
+//! # use solana_program::account_info::AccountInfo;
+//! # fn withdraw(vault: &AccountInfo, recipient: &AccountInfo, amount: u64) {
+//! emit(WithdrawEvent { account: *vault.key, recipient: *recipient.key, amount });
+//! # }
+//! ```
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{log::sol_log_data, pubkey::Pubkey};
+
+/// The layout of [`Event`] this crate writes; changed whenever a variant
+/// or field is added, so an old decoder skips what it cannot read
+pub const VERSION: u8 = 1;
+
+/// Lamports paid into `account` by `depositor`
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DepositEvent {
+    pub account: Pubkey,
+    pub depositor: Pubkey,
+    pub amount: u64,
+}
+
+/// Lamports paid out of `account` to `recipient`
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct WithdrawEvent {
+    pub account: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+}
+
+/// `account` answers to `authority` from now on
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AuthorityChanged {
+    pub account: Pubkey,
+    pub authority: Pubkey,
+}
+
+/// Every event an example emits
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum Event {
+    Deposit(DepositEvent),
+    Withdraw(WithdrawEvent),
+    AuthorityChanged(AuthorityChanged),
+}
+
+impl Event {
+    /// The event `sol_log_data` was called with; `None` for data logged at
+    /// another version or by anything else
+    pub fn decode(fields: &[&[u8]]) -> Option<Event> {
+        match fields {
+            [[version], event] if *version == VERSION => Event::try_from_slice(event).ok(),
+            _ => None,
+        }
+    }
+}
+
+impl From<DepositEvent> for Event {
+    fn from(event: DepositEvent) -> Self {
+        Event::Deposit(event)
+    }
+}
+
+impl From<WithdrawEvent> for Event {
+    fn from(event: WithdrawEvent) -> Self {
+        Event::Withdraw(event)
+    }
+}
+
+impl From<AuthorityChanged> for Event {
+    fn from(event: AuthorityChanged) -> Self {
+        Event::AuthorityChanged(event)
+    }
+}
+
+/// Logs `event` at [`VERSION`]
+pub fn emit(event: impl Into<Event>) {
+    let event = borsh::to_vec(&event.into()).expect("events serialize into a Vec");
+    sol_log_data(&[&[VERSION], &event]);
+}
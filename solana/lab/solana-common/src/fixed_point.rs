@@ -0,0 +1,151 @@
+//! Fractions that keep their precision
+//!
+//! A [`U64F64`] is a Q64.64 number: a `u128` whose low 64 bits are the
+//! fraction. A price, a share of a vault or a collateral ratio is one of
+//! these rather than an integer, which would round `2 / 3` to 0 and lose
+//! the rest. Every operation that can lose a bit says which way it rounds,
+//! with a [`Rounding`], so the remainder always falls to the protocol:
+//!
+//! ```
+//! # use solana_common::fixed_point::{Rounding, U64F64};
+//! # let (deposit, total_assets, total_shares) = (100, 3_000, 1_000);
+//! let share = U64F64::from_ratio(deposit, total_assets, Rounding::Down)?;
+//! let minted = share.mul_int(total_shares, Rounding::Down)?;
+//! # assert_eq!(minted, 33);
+//! # Ok::<(), solana_program::program_error::ProgramError>(())
+//! ```
+//!
+//! Products are taken in 256 bits before they are divided, so neither
+//! [`U64F64::mul`] nor [`U64F64::div`] overflows unless its result does,
+//! which fails with [`ArithmeticError::Overflow`].
+
+use lab_errors::ArithmeticError;
+use solana_program::program_error::ProgramError;
+
+/// Which way a result that does not fit is rounded
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rounding {
+    /// Toward zero, for what a user is owed
+    Down,
+    /// Away from zero, for what a user owes
+    Up,
+}
+
+/// An unsigned Q64.64 number
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct U64F64(u128);
+
+/// Fractional bits
+const FRAC: u32 = 64;
+const ONE: u128 = 1 << FRAC;
+
+impl U64F64 {
+    pub const ZERO: U64F64 = U64F64(0);
+    pub const ONE: U64F64 = U64F64(ONE);
+
+    pub fn from_int(value: u64) -> Self {
+        Self(u128::from(value) << FRAC)
+    }
+
+    /// The number whose representation is `bits`
+    pub fn from_bits(bits: u128) -> Self {
+        Self(bits)
+    }
+
+    pub fn to_bits(self) -> u128 {
+        self.0
+    }
+
+    /// `numerator / denominator`
+    pub fn from_ratio(
+        numerator: u64,
+        denominator: u64,
+        rounding: Rounding,
+    ) -> Result<Self, ProgramError> {
+        mul_div(numerator.into(), ONE, denominator.into(), rounding).map(Self)
+    }
+
+    /// The integer part, or the next integer up if there is a fraction
+    pub fn to_int(self, rounding: Rounding) -> Result<u64, ProgramError> {
+        self.mul_int(1, rounding)
+    }
+
+    /// `self × value`, as an integer
+    pub fn mul_int(self, value: u64, rounding: Rounding) -> Result<u64, ProgramError> {
+        let product = mul_div(self.0, value.into(), ONE, rounding)?;
+        u64::try_from(product).map_err(|_| ArithmeticError::Overflow.into())
+    }
+
+    pub fn checked_add(self, rhs: Self) -> Result<Self, ProgramError> {
+        self.0
+            .checked_add(rhs.0)
+            .map(Self)
+            .ok_or(ArithmeticError::Overflow.into())
+    }
+
+    pub fn checked_sub(self, rhs: Self) -> Result<Self, ProgramError> {
+        self.0
+            .checked_sub(rhs.0)
+            .map(Self)
+            .ok_or(ArithmeticError::Overflow.into())
+    }
+
+    pub fn mul(self, rhs: Self, rounding: Rounding) -> Result<Self, ProgramError> {
+        mul_div(self.0, rhs.0, ONE, rounding).map(Self)
+    }
+
+    /// Fails with [`ArithmeticError::DivisionByZero`] if `rhs` is zero
+    pub fn div(self, rhs: Self, rounding: Rounding) -> Result<Self, ProgramError> {
+        mul_div(self.0, ONE, rhs.0, rounding).map(Self)
+    }
+}
+
+impl From<u64> for U64F64 {
+    fn from(value: u64) -> Self {
+        Self::from_int(value)
+    }
+}
+
+/// `a * b / c` with the product in 256 bits, rounded as asked
+fn mul_div(a: u128, b: u128, c: u128, rounding: Rounding) -> Result<u128, ProgramError> {
+    if c == 0 {
+        return Err(ArithmeticError::DivisionByZero.into());
+    }
+    let (high, low) = widening_mul(a, b);
+    // The quotient would need more than 128 bits
+    if high >= c {
+        return Err(ArithmeticError::Overflow.into());
+    }
+    // Long division, one bit of `low` at a time; `remainder < c` throughout
+    let (mut quotient, mut remainder) = (0u128, high);
+    for bit in (0..128).rev() {
+        let carry = remainder >> 127;
+        remainder = (remainder << 1) | ((low >> bit) & 1);
+        quotient <<= 1;
+        if carry == 1 || remainder >= c {
+            remainder = remainder.wrapping_sub(c);
+            quotient |= 1;
+        }
+    }
+    match rounding {
+        Rounding::Up if remainder != 0 => quotient
+            .checked_add(1)
+            .ok_or(ArithmeticError::Overflow.into()),
+        _ => Ok(quotient),
+    }
+}
+
+/// The 256-bit product of `a` and `b`, as its high and low halves
+fn widening_mul(a: u128, b: u128) -> (u128, u128) {
+    const LOW: u128 = u64::MAX as u128;
+    let (a_high, a_low) = (a >> 64, a & LOW);
+    let (b_high, b_low) = (b >> 64, b & LOW);
+    let low_low = a_low * b_low;
+    let low_high = a_low * b_high;
+    let high_low = a_high * b_low;
+    // At most three 64-bit values, so it cannot overflow
+    let middle = (low_low >> 64) + (low_high & LOW) + (high_low & LOW);
+    let low = (low_low & LOW) | (middle << 64);
+    let high = a_high * b_high + (low_high >> 64) + (high_low >> 64) + (middle >> 64);
+    (high, low)
+}
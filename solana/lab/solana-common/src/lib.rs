@@ -0,0 +1,66 @@
+//! Code the secure Solana examples share
+//!
+//! The fixed programs under `solana/secure/` make their checks through
+//! these modules rather than spelling each one out, so every example reads
+//! in the same vocabulary:
+//!
+//! - [`validation`]: `assert_signer`, `assert_owned_by` and the other
+//!   account checks, used by every secure version.
+//! - [`account`]: [`account::load`], which checks an account's owner,
+//!   discriminator and length before reading it, used by
+//!   `missing_owner_check` and `type_confusion`.
+//! - [`deserialize`]: `strict_deserialize`, which reads exactly one value,
+//!   used wherever a secure version reads raw account data.
+//! - [`discriminator`]: the type tag that keeps one account type from
+//!   passing for another, used by `missing_owner_check`,
+//!   `reinitialization` and `type_confusion`.
+//! - [`versioning`]: the layout byte that keeps a new release from reading
+//!   an old account as its own, used by `account_versioning`.
+//! - [`pda`]: one spec per kind of PDA, derived and checked the same way,
+//!   used by `account_data_matching`, `account_versioning`,
+//!   `arbitrary_cpi` and `pda_issues`.
+//! - [`access_control`]: roles and a two-step authority, used by
+//!   `missing_owner_check` through `only_role` and by `batch_payout`
+//!   through `TwoStepAuthority`.
+//! - [`pausable`]: a guardian's pause switch and a withdrawal cap, used by
+//!   `missing_owner_check`.
+//! - [`safe_math`]: amounts whose arithmetic cannot wrap, used by
+//!   `arithmetic_errors`.
+//! - [`fixed_point`]: fractions that round as they are told, used by
+//!   `arithmetic_errors` for its reward share.
+//! - [`clock`]: the time, read through a provider tests can set, used by
+//!   both versions of `account_data_matching` and `arithmetic_errors` and
+//!   by the secure `missing_owner_check`.
+//! - [`events`]: what a handler did, logged for a client to decode, used
+//!   by both versions of nearly every example.
+//! - [`token_utils`]: SPL Token CPIs that check the token program first,
+//!   used by `account_data_matching`.
+//! - [`reentrancy`]: a lock held across a CPI that could call back in,
+//!   used by `arbitrary_cpi`.
+//! - [`oracle`]: checked and unchecked price reads, which no example uses
+//!   yet.
+//!
+//! The crate builds to SBF with the programs that depend on it, and the
+//! math, PDA and deserialization helpers carry Kani proofs besides their
+//! tests.
+
+// The derive names this crate by its path, which has to resolve here too
+extern crate self as solana_common;
+
+pub mod access_control;
+pub mod account;
+pub mod clock;
+pub mod deserialize;
+pub mod discriminator;
+pub mod events;
+pub mod fixed_point;
+pub mod oracle;
+pub mod pausable;
+pub mod pda;
+#[cfg(kani)]
+mod proofs;
+pub mod reentrancy;
+pub mod safe_math;
+pub mod token_utils;
+pub mod validation;
+pub mod versioning;
@@ -0,0 +1,266 @@
+//! Reading a price from an oracle account, checked or not
+//!
+//! A [`PriceSource`] is one oracle's account layout: who owns its price
+//! accounts and how to decode a [`Price`] from one. [`MockOracle`] is the
+//! examples' own, always built; with the `pyth` and `switchboard` features,
+//! `PythPullOracle` and `SwitchboardOnDemand` read those oracles'
+//! accounts. Their layouts are decoded here, without the oracles' SDKs.
+//!
+//! A price is read one of two ways. [`checked_price`] is the correct
+//! reader: the account must belong to the oracle, and the price must be
+//! positive, recent and tight enough by the caller's [`PriceLimits`].
+//! [`unchecked_price`] is the broken one, which takes whatever the account
+//! says. No example in this tree reads a price yet:
+//!
+//! ```
+//! # use solana_common::clock::SysvarClock;
+//! # use solana_common::oracle::{checked_price, MockOracle, PriceLimits};
+//! # use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey};
+//! # const LIMITS: PriceLimits = PriceLimits { max_age: 60, max_conf_bps: 100 };
+//! # fn borrow(program_id: Pubkey, feed: &AccountInfo) -> ProgramResult {
+//! let price = checked_price(&MockOracle::new(program_id), feed, &LIMITS, &SysvarClock)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use lab_errors::OracleError;
+use solana_program::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
+
+use crate::clock::ClockProvider;
+use crate::discriminator::AccountDiscriminator;
+use crate::validation::assert_owned_by;
+
+/// A price: `price` × 10^`expo`, give or take `conf` in the same units
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Price {
+    pub price: i128,
+    pub conf: u128,
+    pub expo: i32,
+    /// When it was published, in Unix seconds
+    pub publish_time: i64,
+}
+
+/// One oracle's price accounts
+pub trait PriceSource {
+    /// The program that owns them
+    fn owner(&self) -> Pubkey;
+
+    /// The price `data` holds, as it stands
+    fn decode(&self, data: &[u8]) -> Result<Price, ProgramError>;
+}
+
+/// How far a price may be trusted
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PriceLimits {
+    /// Oldest a price may be, in seconds
+    pub max_age: i64,
+    /// Widest the confidence interval may be, in basis points of the price
+    pub max_conf_bps: u32,
+}
+
+/// The price in `account`, if `source` owns it and it is within `limits`
+/// at `clock`'s time
+pub fn checked_price(
+    source: &impl PriceSource,
+    account: &AccountInfo,
+    limits: &PriceLimits,
+    clock: &impl ClockProvider,
+) -> Result<Price, ProgramError> {
+    assert_owned_by(account, &source.owner())?;
+    let price = source.decode(&account.try_borrow_data()?)?;
+    if price.price <= 0 {
+        return Err(OracleError::InvalidPrice.into());
+    }
+    let age = clock.unix_timestamp()?.saturating_sub(price.publish_time);
+    if !(0..=limits.max_age).contains(&age) {
+        return Err(OracleError::StalePrice.into());
+    }
+    // conf / price > max_conf_bps / 10_000, without dividing
+    let widest = price.price.unsigned_abs() * u128::from(limits.max_conf_bps);
+    if price.conf.saturating_mul(10_000) > widest {
+        return Err(OracleError::ConfidenceTooWide.into());
+    }
+    Ok(price)
+}
+
+/// The price in `account`, whoever owns it, however old and however
+/// uncertain; for the vulnerable versions only
+pub fn unchecked_price(
+    source: &impl PriceSource,
+    account: &AccountInfo,
+) -> Result<Price, ProgramError> {
+    source.decode(&account.try_borrow_data()?)
+}
+
+/// The examples' oracle: a [`MockPriceFeed`] in an account of the program
+/// that publishes it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MockOracle {
+    pub program_id: Pubkey,
+}
+
+impl MockOracle {
+    pub fn new(program_id: Pubkey) -> Self {
+        Self { program_id }
+    }
+}
+
+/// What a [`MockOracle`] account holds
+#[derive(BorshSerialize, BorshDeserialize, AccountDiscriminator, Clone, Debug, PartialEq, Eq)]
+pub struct MockPriceFeed {
+    pub price: i64,
+    pub conf: u64,
+    pub expo: i32,
+    pub publish_time: i64,
+}
+
+impl PriceSource for MockOracle {
+    fn owner(&self) -> Pubkey {
+        self.program_id
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Price, ProgramError> {
+        let feed = MockPriceFeed::try_deserialize_checked(data)?;
+        Ok(Price {
+            price: feed.price.into(),
+            conf: feed.conf.into(),
+            expo: feed.expo,
+            publish_time: feed.publish_time,
+        })
+    }
+}
+
+#[cfg(feature = "pyth")]
+pub use pyth::PythPullOracle;
+
+#[cfg(feature = "pyth")]
+mod pyth {
+    use super::*;
+
+    /// Pyth's pull oracle: `PriceUpdateV2` accounts of the Pyth Solana
+    /// receiver
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    pub struct PythPullOracle;
+
+    /// The receiver program
+    const RECEIVER: Pubkey = solana_program::pubkey!("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ");
+
+    /// sha256("account:PriceUpdateV2")[..8]
+    const DISCRIMINATOR: [u8; 8] = [34, 241, 35, 99, 157, 126, 244, 205];
+
+    /// pyth-solana-receiver-sdk's account, after its discriminator
+    #[derive(BorshDeserialize)]
+    #[allow(dead_code)]
+    struct PriceUpdateV2 {
+        write_authority: Pubkey,
+        verification_level: VerificationLevel,
+        price_message: PriceFeedMessage,
+        posted_slot: u64,
+    }
+
+    #[derive(BorshDeserialize)]
+    #[allow(dead_code)]
+    enum VerificationLevel {
+        Partial { num_signatures: u8 },
+        Full,
+    }
+
+    #[derive(BorshDeserialize)]
+    #[allow(dead_code)]
+    struct PriceFeedMessage {
+        feed_id: [u8; 32],
+        price: i64,
+        conf: u64,
+        exponent: i32,
+        publish_time: i64,
+        prev_publish_time: i64,
+        ema_price: i64,
+        ema_conf: u64,
+    }
+
+    impl PriceSource for PythPullOracle {
+        fn owner(&self) -> Pubkey {
+            RECEIVER
+        }
+
+        fn decode(&self, data: &[u8]) -> Result<Price, ProgramError> {
+            let data = data
+                .strip_prefix(&DISCRIMINATOR)
+                .ok_or(lab_errors::StateError::WrongAccountType)?;
+            // The account is allocated larger than the update it holds
+            let update = PriceUpdateV2::deserialize(&mut &data[..])
+                .map_err(|_| lab_errors::StateError::MalformedData)?;
+            let message = update.price_message;
+            Ok(Price {
+                price: message.price.into(),
+                conf: message.conf.into(),
+                expo: message.exponent,
+                publish_time: message.publish_time,
+            })
+        }
+    }
+}
+
+#[cfg(feature = "switchboard")]
+pub use switchboard::SwitchboardOnDemand;
+
+#[cfg(feature = "switchboard")]
+mod switchboard {
+    use super::*;
+
+    /// Switchboard On-Demand: `PullFeedAccountData` accounts, read for the
+    /// feed's current result
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    pub struct SwitchboardOnDemand;
+
+    /// The On-Demand program
+    const ON_DEMAND: Pubkey =
+        solana_program::pubkey!("SBondMDrcV3K4kxZR1HNVT7osZxAHVHgYXL5Ze1oMUv");
+
+    /// sha256("account:PullFeedAccountData")[..8]
+    const DISCRIMINATOR: [u8; 8] = [196, 27, 108, 196, 10, 215, 219, 40];
+
+    // Offsets into the zero-copy account, discriminator included: 32
+    // 64-byte submissions, the feed's keys and settings, then the result
+    const LAST_UPDATE_TIMESTAMP: usize = 8 + 2208;
+    const RESULT: usize = 8 + 2256;
+    /// `value` and `std_dev`, the first two fields of the result
+    const RESULT_LEN: usize = 32;
+
+    /// Switchboard scales every value by 10^18
+    const EXPO: i32 = -18;
+
+    impl PriceSource for SwitchboardOnDemand {
+        fn owner(&self) -> Pubkey {
+            ON_DEMAND
+        }
+
+        fn decode(&self, data: &[u8]) -> Result<Price, ProgramError> {
+            if !data.starts_with(&DISCRIMINATOR) {
+                return Err(lab_errors::StateError::WrongAccountType.into());
+            }
+            let field = |at: usize, len: usize| {
+                data.get(at..at + len)
+                    .ok_or(lab_errors::StateError::MalformedData)
+            };
+            let i128_at = |at| -> Result<i128, ProgramError> {
+                Ok(i128::from_le_bytes(
+                    field(at, 16)?.try_into().expect("16 bytes"),
+                ))
+            };
+            field(RESULT, RESULT_LEN)?;
+            let std_dev = i128_at(RESULT + 16)?;
+            Ok(Price {
+                price: i128_at(RESULT)?,
+                conf: std_dev.unsigned_abs(),
+                expo: EXPO,
+                publish_time: i64::from_le_bytes(
+                    field(LAST_UPDATE_TIMESTAMP, 8)?
+                        .try_into()
+                        .expect("8 bytes"),
+                ),
+            })
+        }
+    }
+}
@@ -0,0 +1,129 @@
+//! Stopping a program, or slowing it down, while an incident is handled
+//!
+//! A [`PauseState`] is a paused flag and the guardian key allowed to flip
+//! it. Every instruction that moves funds starts with
+//! [`PauseState::when_not_paused`], so one transaction from the guardian
+//! stops them all while the rest of the program, and the guardian's own
+//! instructions, keep working.
+//!
+//! A [`WithdrawCap`] limits how much may leave in a window of time. It does
+//! not stop an attacker, but it bounds what they take before anyone
+//! notices:
+//!
+//! ```
+//! # use solana_common::clock::ClockProvider;
+//! # use solana_common::pausable::{PauseState, WithdrawCap};
+//! # use solana_program::entrypoint::ProgramResult;
+//! # struct Config { pause: PauseState, cap: WithdrawCap }
+//! # fn withdraw(config: &mut Config, clock: &impl ClockProvider, amount: u64) -> ProgramResult {
+//! config.pause.when_not_paused()?;
+//! config.cap.record(amount, clock.unix_timestamp()?)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use lab_errors::{AccessError, ArithmeticError};
+use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey};
+
+use crate::validation::{assert_key_eq, assert_signer};
+
+/// Whether a program is paused, and who may pause it
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PauseState {
+    pub paused: bool,
+    pub guardian: Pubkey,
+}
+
+impl PauseState {
+    pub const LEN: usize = 1 + 32;
+
+    pub fn new(guardian: Pubkey) -> Self {
+        Self {
+            paused: false,
+            guardian,
+        }
+    }
+
+    /// Fails with [`AccessError::Paused`] while the program is paused
+    pub fn when_not_paused(&self) -> ProgramResult {
+        if self.paused {
+            return Err(AccessError::Paused.into());
+        }
+        Ok(())
+    }
+
+    /// Pauses the program; `signer` must be the guardian
+    pub fn pause(&mut self, signer: &AccountInfo) -> ProgramResult {
+        self.set(signer, true)
+    }
+
+    /// Unpauses the program; `signer` must be the guardian
+    pub fn unpause(&mut self, signer: &AccountInfo) -> ProgramResult {
+        self.set(signer, false)
+    }
+
+    fn set(&mut self, signer: &AccountInfo, paused: bool) -> ProgramResult {
+        assert_signer(signer)?;
+        assert_key_eq(signer, &self.guardian)?;
+        self.paused = paused;
+        Ok(())
+    }
+}
+
+/// At most `cap` may be withdrawn in any `window` seconds, counted from
+/// the first withdrawal of the window
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WithdrawCap {
+    pub cap: u64,
+    pub window: i64,
+    /// When the current window opened, in Unix seconds
+    pub window_start: i64,
+    /// What has been withdrawn in it
+    pub withdrawn: u64,
+}
+
+impl WithdrawCap {
+    pub const LEN: usize = 8 + 8 + 8 + 8;
+
+    pub fn new(cap: u64, window: i64) -> Self {
+        Self {
+            cap,
+            window,
+            window_start: 0,
+            withdrawn: 0,
+        }
+    }
+
+    /// What may still be withdrawn at `now`
+    pub fn remaining(&self, now: i64) -> u64 {
+        if self.expired(now) {
+            self.cap
+        } else {
+            self.cap.saturating_sub(self.withdrawn)
+        }
+    }
+
+    /// Counts `amount` against the cap at `now`, or fails with
+    /// [`AccessError::WithdrawCapExceeded`] and counts nothing
+    pub fn record(&mut self, amount: u64, now: i64) -> ProgramResult {
+        if amount > self.remaining(now) {
+            return Err(AccessError::WithdrawCapExceeded.into());
+        }
+        if self.expired(now) {
+            self.window_start = now;
+            self.withdrawn = 0;
+        }
+        self.withdrawn = self
+            .withdrawn
+            .checked_add(amount)
+            .ok_or(ArithmeticError::Overflow)?;
+        Ok(())
+    }
+
+    /// Whether the window has closed; a clock that went backwards is still
+    /// in it, so it cannot open a fresh one early
+    fn expired(&self, now: i64) -> bool {
+        now >= self.window_start.saturating_add(self.window)
+    }
+}
@@ -0,0 +1,100 @@
+//! Program derived addresses, derived and checked one way
+//!
+//! Each example declares the PDAs it uses as a [`PdaSpec`]: a literal
+//! prefix, then the keys that follow it. The program and the tests that
+//! set up its accounts derive addresses from the same spec, so the two
+//! cannot drift apart:
+//!
+//! ```
+//! # use solana_common::pda::{find_and_validate, PdaSpec};
+//! # use solana_program::pubkey::Pubkey;
+//! pub const USER_DATA: PdaSpec<1> = PdaSpec::new(b"user_data", ["user"]);
+//! # let (program_id, user) = (Pubkey::new_unique(), Pubkey::new_unique());
+//! // in a test
+//! let (address, bump) = USER_DATA.find([&user], &program_id);
+//! // in the program
+//! find_and_validate(&USER_DATA.seeds([&user]), &program_id, &address)?;
+//! # Ok::<(), solana_common::validation::ValidationError>(())
+//! ```
+//!
+//! [`find_and_validate`] searches for the canonical bump, which costs
+//! compute for every bump it tries. A program that stored the canonical
+//! bump when it created the account checks it with
+//! [`assert_canonical_bump`] once, then re-derives from it with
+//! [`verify_stored_bump`], which tries only that bump.
+
+use solana_program::pubkey::Pubkey;
+
+use crate::validation::ValidationError;
+
+/// The seeds of one kind of PDA: `prefix`, then `N` keys
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PdaSpec<const N: usize> {
+    pub prefix: &'static [u8],
+    /// What each key seed is, in order
+    pub keys: [&'static str; N],
+}
+
+impl<const N: usize> PdaSpec<N> {
+    pub const fn new(prefix: &'static [u8], keys: [&'static str; N]) -> Self {
+        Self { prefix, keys }
+    }
+
+    /// The seeds for these keys, without a bump
+    pub fn seeds<'a>(&self, keys: [&'a Pubkey; N]) -> Vec<&'a [u8]> {
+        std::iter::once(self.prefix)
+            .chain(keys.into_iter().map(Pubkey::as_ref))
+            .collect()
+    }
+
+    /// The address and canonical bump for these keys
+    pub fn find(&self, keys: [&Pubkey; N], program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&self.seeds(keys), program_id)
+    }
+}
+
+/// `provided_key` is `program_id`'s PDA of `seeds` at the canonical bump,
+/// which is returned to be stored or signed with
+pub fn find_and_validate(
+    seeds: &[&[u8]],
+    program_id: &Pubkey,
+    provided_key: &Pubkey,
+) -> Result<u8, ValidationError> {
+    let (pda, bump) = Pubkey::find_program_address(seeds, program_id);
+    if pda != *provided_key {
+        return Err(ValidationError::InvalidPda);
+    }
+    Ok(bump)
+}
+
+/// `provided_key` is `program_id`'s PDA of `seeds` and `bump`, a bump read
+/// from the account's own data; it must have been the canonical bump when
+/// it was stored
+pub fn verify_stored_bump(
+    seeds: &[&[u8]],
+    bump: u8,
+    program_id: &Pubkey,
+    provided_key: &Pubkey,
+) -> Result<(), ValidationError> {
+    let bump = [bump];
+    let mut seeds = seeds.to_vec();
+    seeds.push(&bump);
+    match Pubkey::create_program_address(&seeds, program_id) {
+        Ok(pda) if pda == *provided_key => Ok(()),
+        _ => Err(ValidationError::InvalidPda),
+    }
+}
+
+/// `bump` is the canonical bump of `seeds`, the one `find_program_address`
+/// returns, and not another that also derives a valid address
+pub fn assert_canonical_bump(
+    seeds: &[&[u8]],
+    bump: u8,
+    program_id: &Pubkey,
+) -> Result<(), ValidationError> {
+    let (_, canonical) = Pubkey::find_program_address(seeds, program_id);
+    if bump != canonical {
+        return Err(ValidationError::NonCanonicalBump);
+    }
+    Ok(())
+}
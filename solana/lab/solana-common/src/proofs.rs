@@ -0,0 +1,150 @@
+//! Kani harnesses for the helpers every secure example leans on
+//!
+//! The tests under `tests/` check these helpers at the values someone
+//! thought of. The harnesses here check them at every value Kani can
+//! reach: all `u64` operands of [`mul_div_floor`] and [`mul_div_ceil`],
+//! every account key and bump [`assert_pda`] can be handed, and every
+//! input of up to six bytes to [`strict_deserialize`]. Kani also fails a
+//! harness on any panic, overflow included, on the way to its assertions.
+//! Only `cargo kani` compiles this module:
+//!
+//! ```bash
+//! cargo kani -p solana-common
+//! ```
+
+use lab_errors::{ArithmeticError, StateError};
+use solana_program::account_info::AccountInfo;
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::{Pubkey, MAX_SEED_LEN};
+
+use crate::deserialize::strict_deserialize;
+use crate::safe_math::{mul_div_ceil, mul_div_floor};
+use crate::validation::{assert_pda, ValidationError};
+
+/// Rounding down never gives more than the exact quotient, nor less than
+/// it by a whole unit, and only a zero divisor or a quotient past
+/// `u64::MAX` fails
+#[kani::proof]
+fn mul_div_floor_is_the_exact_quotient_rounded_down() {
+    let (a, b, c): (u64, u64, u64) = kani::any();
+    let product = u128::from(a) * u128::from(b);
+    match mul_div_floor(a, b, c) {
+        Ok(quotient) => {
+            let (quotient, c) = (u128::from(quotient), u128::from(c));
+            assert!(quotient * c <= product);
+            assert!(product < (quotient + 1) * c);
+        }
+        Err(error) if c == 0 => {
+            assert_eq!(error, ProgramError::from(ArithmeticError::DivisionByZero));
+        }
+        Err(error) => {
+            assert_eq!(error, ProgramError::from(ArithmeticError::Overflow));
+            assert!(product / u128::from(c) > u128::from(u64::MAX));
+        }
+    }
+}
+
+/// Rounding up never gives less than the exact quotient, nor more than it
+/// by a whole unit, and only a zero divisor or a quotient past `u64::MAX`
+/// fails
+#[kani::proof]
+fn mul_div_ceil_is_the_exact_quotient_rounded_up() {
+    let (a, b, c): (u64, u64, u64) = kani::any();
+    let product = u128::from(a) * u128::from(b);
+    match mul_div_ceil(a, b, c) {
+        Ok(quotient) => {
+            let (quotient, c) = (u128::from(quotient), u128::from(c));
+            assert!(quotient * c >= product);
+            assert!(quotient * c - product < c);
+        }
+        Err(error) if c == 0 => {
+            assert_eq!(error, ProgramError::from(ArithmeticError::DivisionByZero));
+        }
+        Err(error) => {
+            assert_eq!(error, ProgramError::from(ArithmeticError::Overflow));
+            assert!(product.div_ceil(u128::from(c)) > u128::from(u64::MAX));
+        }
+    }
+}
+
+/// Of every key and bump, only the address the seeds and bump derive
+/// passes
+#[kani::proof]
+fn assert_pda_accepts_only_the_derived_address() {
+    let program_id = Pubkey::new_from_array([7; 32]);
+    let key = Pubkey::new_from_array(kani::any());
+    let bump: u8 = kani::any();
+    let derived = Pubkey::create_program_address(&[b"vault", &[bump]], &program_id);
+
+    let (owner, mut lamports, mut data) = (Pubkey::default(), 0, []);
+    let account = AccountInfo::new(
+        &key,
+        false,
+        false,
+        &mut lamports,
+        &mut data,
+        &owner,
+        false,
+        0,
+    );
+    match assert_pda(&account, &[b"vault"], bump, &program_id) {
+        Ok(()) => assert_eq!(derived, Ok(key)),
+        Err(error) => {
+            assert_eq!(error, ValidationError::InvalidPda);
+            assert_ne!(derived, Ok(key));
+        }
+    }
+}
+
+/// A seed no PDA can have, one longer than `MAX_SEED_LEN`, fails whatever
+/// the key and bump
+#[kani::proof]
+fn assert_pda_rejects_an_overlong_seed() {
+    let program_id = Pubkey::new_from_array([7; 32]);
+    let key = Pubkey::new_from_array(kani::any());
+    let seed: [u8; MAX_SEED_LEN + 8] = kani::any();
+    let len = kani::any_where(|len: &usize| *len > MAX_SEED_LEN && *len <= seed.len());
+
+    let (owner, mut lamports, mut data) = (Pubkey::default(), 0, []);
+    let account = AccountInfo::new(
+        &key,
+        false,
+        false,
+        &mut lamports,
+        &mut data,
+        &owner,
+        false,
+        0,
+    );
+    assert_eq!(
+        assert_pda(&account, &[&seed[..len]], kani::any(), &program_id),
+        Err(ValidationError::InvalidPda)
+    );
+}
+
+/// Of every input of up to six bytes, exactly the well-formed encodings of
+/// a `(u16, bool, Option<u8>)` are read, each as the value it encodes:
+/// a bool or an option tag other than 0 or 1, a short input or a byte
+/// left over is `MalformedData`
+#[kani::proof]
+#[kani::unwind(8)]
+fn strict_deserialize_reads_exactly_one_value() {
+    let bytes: [u8; 6] = kani::any();
+    let len = kani::any_where(|len: &usize| *len <= bytes.len());
+    let data = &bytes[..len];
+
+    let expected = match *data {
+        [low, high, flag @ (0 | 1), 0] => Some((u16::from_le_bytes([low, high]), flag == 1, None)),
+        [low, high, flag @ (0 | 1), 1, value] => {
+            Some((u16::from_le_bytes([low, high]), flag == 1, Some(value)))
+        }
+        _ => None,
+    };
+    match strict_deserialize::<(u16, bool, Option<u8>)>(data) {
+        Ok(value) => assert_eq!(Some(value), expected),
+        Err(error) => {
+            assert_eq!(error, ProgramError::from(StateError::MalformedData));
+            assert_eq!(expected, None);
+        }
+    }
+}
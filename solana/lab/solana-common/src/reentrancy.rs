@@ -0,0 +1,95 @@
+//! A lock an instruction holds across the CPIs it makes
+//!
+//! Solana allows only direct self-recursion. A program may invoke itself,
+//! A→A, but a CPI back into a program further down the stack, A→B→A,
+//! fails with `ReentrancyNotAllowed`. So a callee can never call back in;
+//! the only way back into a program mid-change is its own recursion, for
+//! instance when it relays a call to a program its caller named and that
+//! program is itself. A lock byte in the account being changed closes
+//! that: [`acquire`] sets it before the CPI, fails with
+//! [`StateError::Locked`] if it was already set, and [`release`] clears it
+//! once the state is consistent again. The secure `arbitrary_cpi` holds
+//! one across the call it relays.
+//!
+//! The byte is a field of the account's own type, a [`ReentrancyLock`], at
+//! an offset the program knows. Because the call it protects against reads
+//! the account from its data, the lock has to be in the data, not just in
+//! the value the program deserialized:
+//!
+//! ```
+//! # use solana_common::reentrancy::{acquire, release};
+//! # use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult};
+//! # use solana_program::{instruction::Instruction, program::invoke};
+//! # struct VaultState;
+//! # impl VaultState { const LOCK: usize = 8; }
+//! # fn withdraw(vault_account: &AccountInfo, callback: Instruction, accounts: &[AccountInfo]) -> ProgramResult {
+//! acquire(vault_account, VaultState::LOCK)?;
+//! invoke(&callback, accounts)?;
+//! release(vault_account, VaultState::LOCK)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! A failed instruction rolls its writes back, so on chain a lock never
+//! outlives the instruction that took it. A host test that calls
+//! `process_instruction` directly has no such rollback; [`LockGuard`]
+//! releases the lock when it is dropped, however the test's call returns.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use lab_errors::StateError;
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
+};
+
+/// The lock, as a field of the account it guards; one byte, 0 or 1
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ReentrancyLock {
+    pub locked: bool,
+}
+
+impl ReentrancyLock {
+    pub const LEN: usize = 1;
+}
+
+/// Sets the lock byte at `at` in `account`'s data, or fails with
+/// [`StateError::Locked`] if it is already set
+pub fn acquire(account: &AccountInfo, at: usize) -> ProgramResult {
+    set(account, at, true)
+}
+
+/// Clears the lock byte at `at` in `account`'s data
+pub fn release(account: &AccountInfo, at: usize) -> ProgramResult {
+    set(account, at, false)
+}
+
+fn set(account: &AccountInfo, at: usize, locked: bool) -> ProgramResult {
+    let mut data = account.try_borrow_mut_data()?;
+    let byte = data.get_mut(at).ok_or(StateError::DataTooSmall)?;
+    match (*byte, locked) {
+        (0 | 1, false) => *byte = 0,
+        (0, true) => *byte = 1,
+        (1, true) => return Err(StateError::Locked.into()),
+        _ => return Err(StateError::MalformedData.into()),
+    }
+    Ok(())
+}
+
+/// The lock at `at` in an account, held until this is dropped
+pub struct LockGuard<'a, 'info> {
+    account: &'a AccountInfo<'info>,
+    at: usize,
+}
+
+impl<'a, 'info> LockGuard<'a, 'info> {
+    pub fn acquire(account: &'a AccountInfo<'info>, at: usize) -> Result<Self, ProgramError> {
+        acquire(account, at)?;
+        Ok(Self { account, at })
+    }
+}
+
+impl Drop for LockGuard<'_, '_> {
+    fn drop(&mut self) {
+        // Acquiring checked the byte, so only a borrow still held can fail
+        let _ = release(self.account, self.at);
+    }
+}
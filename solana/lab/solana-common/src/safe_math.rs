@@ -0,0 +1,103 @@
+//! Amounts whose arithmetic cannot wrap
+//!
+//! [`CheckedU64`] and [`CheckedU128`] wrap an integer so that `+`, `-` and
+//! `*` return a `Result`, failing with [`ArithmeticError::Overflow`] where
+//! the bare integer would wrap. An overflow is then a `?` a program cannot
+//! leave out, where a `checked_*` call can be forgotten:
+//!
+//! ```
+//! # use solana_common::safe_math::CheckedU64;
+//! # struct Pool { total_staked: u64 }
+//! # let (mut pool, amount) = (Pool { total_staked: u64::MAX - 1 }, 1);
+//! pool.total_staked = (CheckedU64(pool.total_staked) + amount)?.get();
+//! # assert!((CheckedU64(pool.total_staked) + amount).is_err());
+//! # Ok::<(), solana_program::program_error::ProgramError>(())
+//! ```
+//!
+//! [`mul_div_floor`] and [`mul_div_ceil`] compute `a * b / c` for the
+//! share and fee math that divides: the product is taken in `u128`, so it
+//! cannot overflow before the division, and the rounding is named.
+
+use std::ops::{Add, Mul, Sub};
+
+use lab_errors::ArithmeticError;
+use solana_program::program_error::ProgramError;
+
+macro_rules! checked {
+    ($name:ident, $int:ty) => {
+        #[doc = concat!("A `", stringify!($int), "` whose arithmetic is checked")]
+        #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub struct $name(pub $int);
+
+        impl $name {
+            pub fn get(self) -> $int {
+                self.0
+            }
+        }
+
+        impl From<$int> for $name {
+            fn from(value: $int) -> Self {
+                Self(value)
+            }
+        }
+
+        impl From<$name> for $int {
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+
+        checked!(@op $name, $int, Add, add, checked_add);
+        checked!(@op $name, $int, Sub, sub, checked_sub);
+        checked!(@op $name, $int, Mul, mul, checked_mul);
+    };
+    (@op $name:ident, $int:ty, $trait:ident, $method:ident, $checked:ident) => {
+        impl $trait<$int> for $name {
+            type Output = Result<$name, ProgramError>;
+
+            fn $method(self, rhs: $int) -> Self::Output {
+                self.0
+                    .$checked(rhs)
+                    .map($name)
+                    .ok_or(ArithmeticError::Overflow.into())
+            }
+        }
+
+        impl $trait for $name {
+            type Output = Result<$name, ProgramError>;
+
+            fn $method(self, rhs: $name) -> Self::Output {
+                self.$method(rhs.0)
+            }
+        }
+    };
+}
+
+checked!(CheckedU64, u64);
+checked!(CheckedU128, u128);
+
+/// `a * b / c`, rounded down; fails with [`ArithmeticError::DivisionByZero`]
+/// if `c` is zero and [`ArithmeticError::Overflow`] if the result does not
+/// fit a `u64`
+pub fn mul_div_floor(a: u64, b: u64, c: u64) -> Result<u64, ProgramError> {
+    let (quotient, _) = mul_div(a, b, c)?;
+    u64::try_from(quotient).map_err(|_| ArithmeticError::Overflow.into())
+}
+
+/// `a * b / c`, rounded up, for what a user owes rather than is owed;
+/// fails if `c` is zero or the result does not fit a `u64`
+pub fn mul_div_ceil(a: u64, b: u64, c: u64) -> Result<u64, ProgramError> {
+    let (quotient, remainder) = mul_div(a, b, c)?;
+    let quotient = quotient + u128::from(remainder != 0);
+    u64::try_from(quotient).map_err(|_| ArithmeticError::Overflow.into())
+}
+
+/// The quotient and remainder of `a * b / c`, in `u128`
+fn mul_div(a: u64, b: u64, c: u64) -> Result<(u128, u128), ProgramError> {
+    if c == 0 {
+        return Err(ArithmeticError::DivisionByZero.into());
+    }
+    // Cannot overflow: (2^64 - 1)^2 < 2^128
+    let product = u128::from(a) * u128::from(b);
+    Ok((product / u128::from(c), product % u128::from(c)))
+}
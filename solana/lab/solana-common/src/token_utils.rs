@@ -0,0 +1,226 @@
+//! SPL Token CPIs, made the same careful way every time
+//!
+//! [`transfer`], [`mint_to`], [`burn`] and [`close`] each check that the
+//! program they are about to invoke is SPL Token before building its
+//! instruction, so a caller-supplied program can never stand in for it.
+//! The authority is an [`Authority`]: an account that signed the
+//! transaction, or a PDA of the calling program, which is signed for with
+//! its seeds:
+//!
+//! ```
+//! # use solana_common::token_utils::{received, transfer, Authority};
+//! # use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult};
+//! # fn withdraw<'a>(
+//! #     [token_program, source, destination, vault, vault_signer]: [&AccountInfo<'a>; 5],
+//! #     bump: u8,
+//! #     amount: u64,
+//! # ) -> ProgramResult {
+//! let seeds: &[&[u8]] = &[b"token_authority", vault.key.as_ref(), &[bump]];
+//! let received = received(destination, || {
+//!     transfer(token_program, source, destination, Authority::Pda { account: vault_signer, seeds }, amount)
+//! })?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`received`] measures what actually arrived rather than trusting the
+//! amount asked for, which a token with transfer fees would not deliver.
+
+use lab_errors::StateError;
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey,
+    pubkey::Pubkey,
+};
+
+use crate::validation::{assert_key_eq, assert_owned_by};
+
+/// The SPL Token program
+pub const TOKEN_PROGRAM_ID: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+
+/// A token account's length: mint, owner, amount and the rest
+pub const TOKEN_ACCOUNT_LEN: usize = 165;
+
+/// Where a token account keeps its amount, after its mint and owner
+const AMOUNT: std::ops::Range<usize> = 64..72;
+
+// SPL Token's instruction tags
+const TRANSFER: u8 = 3;
+const MINT_TO: u8 = 7;
+const BURN: u8 = 8;
+const CLOSE_ACCOUNT: u8 = 9;
+
+/// Who authorizes a token instruction
+#[derive(Clone, Copy)]
+pub enum Authority<'a, 'info> {
+    /// An account that signed the transaction
+    Signer(&'a AccountInfo<'info>),
+    /// A PDA of the calling program; `seeds` end with its bump
+    Pda {
+        account: &'a AccountInfo<'info>,
+        seeds: &'a [&'a [u8]],
+    },
+}
+
+impl<'a, 'info> Authority<'a, 'info> {
+    fn account(&self) -> &'a AccountInfo<'info> {
+        match *self {
+            Authority::Signer(account) | Authority::Pda { account, .. } => account,
+        }
+    }
+}
+
+/// Moves `amount` from `source` to `destination`
+pub fn transfer<'info>(
+    token_program: &AccountInfo<'info>,
+    source: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+    authority: Authority<'_, 'info>,
+    amount: u64,
+) -> ProgramResult {
+    assert_key_eq(token_program, &TOKEN_PROGRAM_ID)?;
+    let instruction = transfer_ix(source.key, destination.key, authority.account().key, amount);
+    cpi(
+        &instruction,
+        &[source, destination],
+        token_program,
+        authority,
+    )
+}
+
+/// Mints `amount` of `mint` into `destination`
+pub fn mint_to<'info>(
+    token_program: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+    authority: Authority<'_, 'info>,
+    amount: u64,
+) -> ProgramResult {
+    assert_key_eq(token_program, &TOKEN_PROGRAM_ID)?;
+    let instruction = mint_to_ix(mint.key, destination.key, authority.account().key, amount);
+    cpi(&instruction, &[mint, destination], token_program, authority)
+}
+
+/// Burns `amount` of `mint` from `account`
+pub fn burn<'info>(
+    token_program: &AccountInfo<'info>,
+    account: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    authority: Authority<'_, 'info>,
+    amount: u64,
+) -> ProgramResult {
+    assert_key_eq(token_program, &TOKEN_PROGRAM_ID)?;
+    let instruction = burn_ix(account.key, mint.key, authority.account().key, amount);
+    cpi(&instruction, &[account, mint], token_program, authority)
+}
+
+/// Closes the empty `account`, paying its rent to `destination`
+pub fn close<'info>(
+    token_program: &AccountInfo<'info>,
+    account: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+    authority: Authority<'_, 'info>,
+) -> ProgramResult {
+    assert_key_eq(token_program, &TOKEN_PROGRAM_ID)?;
+    let instruction = close_ix(account.key, destination.key, authority.account().key);
+    cpi(
+        &instruction,
+        &[account, destination],
+        token_program,
+        authority,
+    )
+}
+
+fn cpi<'info>(
+    instruction: &Instruction,
+    accounts: &[&AccountInfo<'info>],
+    token_program: &AccountInfo<'info>,
+    authority: Authority<'_, 'info>,
+) -> ProgramResult {
+    let mut infos: Vec<AccountInfo<'info>> = accounts.iter().map(|&info| info.clone()).collect();
+    infos.push(authority.account().clone());
+    infos.push(token_program.clone());
+    match authority {
+        Authority::Signer(_) => invoke(instruction, &infos),
+        Authority::Pda { seeds, .. } => invoke_signed(instruction, &infos, &[seeds]),
+    }
+}
+
+/// SPL Token's `Transfer`
+pub fn transfer_ix(
+    source: &Pubkey,
+    destination: &Pubkey,
+    authority: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    amount_ix(TRANSFER, [source, destination], authority, amount)
+}
+
+/// SPL Token's `MintTo`
+pub fn mint_to_ix(
+    mint: &Pubkey,
+    destination: &Pubkey,
+    authority: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    amount_ix(MINT_TO, [mint, destination], authority, amount)
+}
+
+/// SPL Token's `Burn`
+pub fn burn_ix(account: &Pubkey, mint: &Pubkey, authority: &Pubkey, amount: u64) -> Instruction {
+    amount_ix(BURN, [account, mint], authority, amount)
+}
+
+/// SPL Token's `CloseAccount`
+pub fn close_ix(account: &Pubkey, destination: &Pubkey, authority: &Pubkey) -> Instruction {
+    Instruction::new_with_bytes(
+        TOKEN_PROGRAM_ID,
+        &[CLOSE_ACCOUNT],
+        vec![
+            AccountMeta::new(*account, false),
+            AccountMeta::new(*destination, false),
+            AccountMeta::new_readonly(*authority, true),
+        ],
+    )
+}
+
+/// Two writable accounts and the authority, with the tag and a
+/// little-endian amount
+fn amount_ix(tag: u8, writable: [&Pubkey; 2], authority: &Pubkey, amount: u64) -> Instruction {
+    let mut data = vec![tag];
+    data.extend_from_slice(&amount.to_le_bytes());
+    let mut accounts: Vec<_> = writable
+        .into_iter()
+        .map(|key| AccountMeta::new(*key, false))
+        .collect();
+    accounts.push(AccountMeta::new_readonly(*authority, true));
+    Instruction::new_with_bytes(TOKEN_PROGRAM_ID, &data, accounts)
+}
+
+/// The tokens `account` holds; it must be an SPL Token account
+pub fn balance(account: &AccountInfo) -> Result<u64, ProgramError> {
+    assert_owned_by(account, &TOKEN_PROGRAM_ID)?;
+    let data = account.try_borrow_data()?;
+    if data.len() < TOKEN_ACCOUNT_LEN {
+        return Err(StateError::DataTooSmall.into());
+    }
+    Ok(u64::from_le_bytes(
+        data[AMOUNT].try_into().expect("8 bytes"),
+    ))
+}
+
+/// How many tokens `cpi` added to `account`
+pub fn received(
+    account: &AccountInfo,
+    cpi: impl FnOnce() -> ProgramResult,
+) -> Result<u64, ProgramError> {
+    let before = balance(account)?;
+    cpi()?;
+    balance(account)?
+        .checked_sub(before)
+        .ok_or(ProgramError::InvalidAccountData)
+}
@@ -0,0 +1,86 @@
+//! The account checks every Solana program makes, one helper each
+//!
+//! Each helper returns a [`ValidationError`] naming what was wrong, which
+//! `?` turns into a [`ProgramError::Custom`] carrying its code, so a failed
+//! transaction names the check that stopped it:
+//!
+//! ```
+//! # use solana_common::validation::{assert_key_eq, assert_owned_by, assert_signer};
+//! # use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey};
+//! # struct Config { vault: Pubkey }
+//! # fn withdraw(program_id: &Pubkey, authority: &AccountInfo, vault: &AccountInfo, config: Config) -> ProgramResult {
+//! assert_signer(authority)?;
+//! assert_owned_by(vault, program_id)?;
+//! assert_key_eq(vault, &config.vault)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`ProgramError::Custom`]: solana_program::program_error::ProgramError::Custom
+
+use solana_program::{account_info::AccountInfo, pubkey::Pubkey, rent::Rent};
+
+use crate::pda::verify_stored_bump;
+
+pub use lab_errors::ValidationError;
+
+/// `account` signed the transaction
+pub fn assert_signer(account: &AccountInfo) -> Result<(), ValidationError> {
+    if !account.is_signer {
+        return Err(ValidationError::MissingSignature);
+    }
+    Ok(())
+}
+
+/// `owner` owns `account`, so only `owner` could have written its data
+pub fn assert_owned_by(account: &AccountInfo, owner: &Pubkey) -> Result<(), ValidationError> {
+    if account.owner != owner {
+        return Err(ValidationError::WrongOwner);
+    }
+    Ok(())
+}
+
+/// `account` is at `expected`, typically an address stored in another
+/// account's data
+pub fn assert_key_eq(account: &AccountInfo, expected: &Pubkey) -> Result<(), ValidationError> {
+    if account.key != expected {
+        return Err(ValidationError::KeyMismatch);
+    }
+    Ok(())
+}
+
+/// `account` is `program_id`'s PDA of `seeds` and `bump`; pass the
+/// canonical bump from `find_program_address`, never one the caller chose
+pub fn assert_pda(
+    account: &AccountInfo,
+    seeds: &[&[u8]],
+    bump: u8,
+    program_id: &Pubkey,
+) -> Result<(), ValidationError> {
+    verify_stored_bump(seeds, bump, program_id, account.key)
+}
+
+/// `account` holds enough lamports to be rent-exempt at its size
+pub fn assert_rent_exempt(account: &AccountInfo, rent: &Rent) -> Result<(), ValidationError> {
+    if !rent.is_exempt(account.lamports(), account.data_len()) {
+        return Err(ValidationError::NotRentExempt);
+    }
+    Ok(())
+}
+
+/// `account` was passed writable, so changes to it are kept
+pub fn assert_writable(account: &AccountInfo) -> Result<(), ValidationError> {
+    if !account.is_writable {
+        return Err(ValidationError::NotWritable);
+    }
+    Ok(())
+}
+
+/// `a` and `b` are different accounts, where passing one twice would let
+/// a change to one undo the other
+pub fn assert_distinct(a: &AccountInfo, b: &AccountInfo) -> Result<(), ValidationError> {
+    if a.key == b.key {
+        return Err(ValidationError::DuplicateAccount);
+    }
+    Ok(())
+}
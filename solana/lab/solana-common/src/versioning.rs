@@ -0,0 +1,87 @@
+//! Account layouts that change between releases of a program
+//!
+//! An account written by one release is read by every later one. If a
+//! release adds a field in the middle of a struct, the old reader and the
+//! new data disagree about where each field starts, and Borsh reads
+//! whatever bytes are there: a key becomes a balance. A [`Versioned`] type
+//! is stored behind one byte naming its layout, and [`read_versioned`]
+//! refuses data written in any other, with [`StateError::WrongVersion`]:
+//!
+//! ```
+//! # use borsh::{BorshDeserialize, BorshSerialize};
+//! # use solana_common::versioning::{read_versioned, Versioned};
+//! # use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult};
+//! # #[derive(BorshSerialize, BorshDeserialize)]
+//! # struct UserAccountV2 {}
+//! # impl Versioned for UserAccountV2 { const VERSION: u8 = 2; }
+//! # fn withdraw(user_account: &AccountInfo) -> ProgramResult {
+//! let user = read_versioned::<UserAccountV2>(&user_account.data.borrow())?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`migrate_in_place`] moves an account from one layout to the next: it
+//! reads the old version strictly, builds the new one from it, grows the
+//! account if the new layout is longer, with the payer covering the rent,
+//! and writes the new version byte and value.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use lab_errors::StateError;
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError, rent::Rent,
+};
+
+use crate::account::grow_account;
+use crate::deserialize::strict_deserialize;
+
+/// An account layout, stored after a byte naming it
+pub trait Versioned: BorshSerialize + BorshDeserialize {
+    /// This layout's version byte; each layout of an account has its own
+    const VERSION: u8;
+}
+
+/// The version byte `data` starts with; fails with
+/// [`StateError::DataTooSmall`] if it is empty
+pub fn version(data: &[u8]) -> Result<u8, ProgramError> {
+    data.first()
+        .copied()
+        .ok_or_else(|| StateError::DataTooSmall.into())
+}
+
+/// `data` as exactly one `T`, after `T`'s version byte; fails with
+/// [`StateError::WrongVersion`] if the byte is another layout's
+pub fn read_versioned<T: Versioned>(data: &[u8]) -> Result<T, ProgramError> {
+    if version(data)? != T::VERSION {
+        return Err(StateError::WrongVersion.into());
+    }
+    strict_deserialize(&data[1..])
+}
+
+/// Writes `T`'s version byte, then `value`, at the start of `data`
+pub fn write_versioned<T: Versioned>(value: &T, data: &mut [u8]) -> ProgramResult {
+    let (version, rest) = data.split_first_mut().ok_or(StateError::DataTooSmall)?;
+    *version = T::VERSION;
+    value.serialize(&mut &mut rest[..])?;
+    Ok(())
+}
+
+/// Rewrites `account`, which must hold an `Old`, as the `New` that
+/// `upgrade` makes of it, resized to fit exactly. A longer layout is paid
+/// for by `payer` through `system_program`, as [`grow_account`] does
+pub fn migrate_in_place<'info, Old: Versioned, New: Versioned>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    rent: &Rent,
+    upgrade: impl FnOnce(Old) -> Result<New, ProgramError>,
+) -> ProgramResult {
+    let old = read_versioned::<Old>(&account.try_borrow_data()?)?;
+    let new = upgrade(old)?;
+    let len = 1 + borsh::object_length(&new)?;
+    if len > account.data_len() {
+        grow_account(account, payer, system_program, len, rent)?;
+    } else {
+        account.resize(len)?;
+    }
+    write_versioned(&new, &mut account.try_borrow_mut_data()?)
+}
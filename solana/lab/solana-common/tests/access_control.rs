@@ -0,0 +1,70 @@
+//! only_role takes a signed member with every role asked for, and an
+//! authority moves only when the proposed one accepts
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use lab_errors::{AccessError, ValidationError};
+use lab_test_utils::with_signer;
+use solana_common::access_control::{only_role, Member, Role, TwoStepAuthority};
+use solana_program::pubkey::Pubkey;
+
+#[test]
+fn only_role_needs_the_signer_and_every_role() {
+    let key = Pubkey::new_unique();
+    let member = Member::new(key, Role::PAUSER | Role::TREASURER);
+    let guard = |key: &Pubkey, signed, required| {
+        with_signer(key, signed, |signer| only_role(signer, &member, required))
+    };
+
+    assert_eq!(guard(&key, true, Role::PAUSER), Ok(()));
+    assert_eq!(guard(&key, true, Role::PAUSER | Role::TREASURER), Ok(()));
+    assert_eq!(
+        guard(&key, true, Role::ADMIN | Role::PAUSER),
+        Err(AccessError::MissingRole.into())
+    );
+    assert_eq!(
+        guard(&key, false, Role::PAUSER),
+        Err(ValidationError::MissingSignature.into())
+    );
+    assert_eq!(
+        guard(&Pubkey::new_unique(), true, Role::PAUSER),
+        Err(ValidationError::KeyMismatch.into())
+    );
+
+    let bytes = borsh::to_vec(&member).unwrap();
+    assert_eq!(bytes.len(), Member::LEN);
+    assert_eq!(Member::try_from_slice(&bytes).unwrap(), member);
+    assert!(Role::try_from_slice(&[0x80]).is_err());
+}
+
+#[test]
+fn authority_moves_only_when_accepted() {
+    let (current, next) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let mut authority = TwoStepAuthority::new(current);
+
+    assert_eq!(
+        with_signer(&next, true, |signer| authority.accept(signer)),
+        Err(AccessError::NoPendingAuthority.into())
+    );
+    assert_eq!(
+        with_signer(&next, true, |signer| authority.propose(signer, next)),
+        Err(ValidationError::KeyMismatch.into())
+    );
+    with_signer(&current, true, |signer| authority.propose(signer, next)).unwrap();
+    assert_eq!(authority.authority, current);
+
+    let stranger = Pubkey::new_unique();
+    assert_eq!(
+        with_signer(&stranger, true, |signer| authority.accept(signer)),
+        Err(AccessError::NotPendingAuthority.into())
+    );
+    assert_eq!(
+        with_signer(&next, false, |signer| authority.accept(signer)),
+        Err(ValidationError::MissingSignature.into())
+    );
+    with_signer(&next, true, |signer| authority.accept(signer)).unwrap();
+    assert_eq!(authority, TwoStepAuthority::new(next));
+
+    let mut written = Vec::new();
+    authority.serialize(&mut written).unwrap();
+    assert!(written.len() <= TwoStepAuthority::LEN);
+}
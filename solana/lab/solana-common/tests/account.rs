@@ -0,0 +1,104 @@
+//! load reads a program's own account of the right type, and says which
+//! check any other account fails; a closed account is empty, marked and
+//! refused
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use lab_errors::{StateError, ValidationError};
+use lab_test_utils::{with_account, Stored};
+use solana_common::account::{close_account, load};
+use solana_common::discriminator::{AccountDiscriminator, CLOSED_ACCOUNT_DISCRIMINATOR};
+use solana_program::pubkey::Pubkey;
+
+#[derive(BorshSerialize, BorshDeserialize, AccountDiscriminator, Debug, PartialEq)]
+struct Vault {
+    authority: Pubkey,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, AccountDiscriminator, Debug, PartialEq)]
+struct Profile {
+    owner: Pubkey,
+}
+
+fn written(value: &impl AccountDiscriminator) -> Vec<u8> {
+    let mut data = Vec::new();
+    value.serialize_with_discriminator(&mut data).unwrap();
+    data
+}
+
+#[test]
+fn loads_its_own_account() {
+    let program_id = Pubkey::new_unique();
+    let vault = Vault {
+        authority: Pubkey::new_unique(),
+    };
+    let loaded = with_account(&program_id, written(&vault), |account| {
+        load::<Vault>(account, &program_id)
+    });
+    assert_eq!(loaded, Ok(vault));
+}
+
+#[test]
+fn each_check_has_its_error() {
+    let program_id = Pubkey::new_unique();
+    let vault = written(&Vault {
+        authority: Pubkey::new_unique(),
+    });
+    let error = |owner: &Pubkey, data: Vec<u8>| {
+        with_account(owner, data, |account| load::<Vault>(account, &program_id)).unwrap_err()
+    };
+
+    let forged_by = Pubkey::new_unique();
+    assert_eq!(
+        error(&forged_by, vault.clone()),
+        ValidationError::WrongOwner.into()
+    );
+    assert_eq!(
+        error(&program_id, vec![0; 4]),
+        StateError::DataTooSmall.into()
+    );
+    let profile = written(&Profile {
+        owner: Pubkey::new_unique(),
+    });
+    assert_eq!(
+        error(&program_id, profile),
+        StateError::WrongAccountType.into()
+    );
+    let mut trailing = vault;
+    trailing.push(0);
+    assert_eq!(
+        error(&program_id, trailing),
+        StateError::MalformedData.into()
+    );
+}
+
+#[test]
+fn closed_accounts_are_drained_marked_and_refused() {
+    let program_id = Pubkey::new_unique();
+    let vault = written(&Vault {
+        authority: Pubkey::new_unique(),
+    });
+    let mut stored = Stored {
+        lamports: 1_000,
+        ..Stored::new(program_id, vault)
+    };
+    let mut destination = Stored {
+        lamports: 5,
+        ..Stored::new(program_id, Vec::new())
+    };
+    let (account, destination) = (stored.info(), destination.info());
+
+    assert_eq!(
+        close_account(&account, &account.clone()),
+        Err(ValidationError::DuplicateAccount.into())
+    );
+    assert_eq!(close_account(&account, &destination), Ok(()));
+    assert_eq!((account.lamports(), destination.lamports()), (0, 1_005));
+    let data = account.data.borrow();
+    assert_eq!(data[..8], CLOSED_ACCOUNT_DISCRIMINATOR);
+    assert!(data[8..].iter().all(|&byte| byte == 0));
+    drop(data);
+    assert_eq!(
+        load::<Vault>(&account, &program_id),
+        Err(StateError::AccountClosed.into())
+    );
+}
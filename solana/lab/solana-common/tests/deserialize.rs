@@ -0,0 +1,45 @@
+//! A strict read takes exactly one value; a lenient one ignores whatever
+//! follows it
+
+use borsh::{to_vec, BorshDeserialize, BorshSerialize};
+use lab_errors::StateError;
+use solana_common::deserialize::{lenient_deserialize, strict_deserialize};
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq)]
+struct Stake {
+    amount: u64,
+    last_claim: i64,
+}
+
+const STAKE: Stake = Stake {
+    amount: 100,
+    last_claim: 7,
+};
+
+#[test]
+fn strict_reads_take_exactly_one_value() {
+    let data = to_vec(&STAKE).unwrap();
+    assert_eq!(strict_deserialize::<Stake>(&data), Ok(STAKE));
+
+    let malformed = Err(StateError::MalformedData.into());
+    let mut trailing = data.clone();
+    trailing.extend_from_slice(&[1, 2, 3]);
+    assert_eq!(strict_deserialize::<Stake>(&trailing), malformed);
+    assert_eq!(strict_deserialize::<Stake>(&data[..12]), malformed);
+    // Sized for a wider layout, zero-padded
+    assert_eq!(
+        strict_deserialize::<Stake>(&[data, vec![0; 8]].concat()),
+        malformed
+    );
+}
+
+#[test]
+fn lenient_reads_ignore_what_follows() {
+    let mut data = to_vec(&STAKE).unwrap();
+    data.extend_from_slice(&[0xff; 16]);
+    assert_eq!(lenient_deserialize::<Stake>(&data), Ok(STAKE));
+    assert_eq!(
+        lenient_deserialize::<Stake>(&data[..4]),
+        Err(StateError::MalformedData.into())
+    );
+}
@@ -0,0 +1,56 @@
+//! Derived discriminators are Anchor's, and a checked read takes only the
+//! type that was written
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use lab_errors::StateError;
+use solana_common::discriminator::AccountDiscriminator;
+
+#[derive(BorshSerialize, BorshDeserialize, AccountDiscriminator, Debug, PartialEq)]
+struct Vault {
+    balance: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, AccountDiscriminator, Debug, PartialEq)]
+struct Admin {
+    level: u64,
+}
+
+#[test]
+fn discriminators_hash_the_type_name() {
+    // sha256("account:Vault")[..8]
+    assert_eq!(Vault::DISCRIMINATOR, [211, 8, 232, 43, 2, 152, 117, 119]);
+    assert_ne!(Vault::DISCRIMINATOR, Admin::DISCRIMINATOR);
+}
+
+#[test]
+fn checked_reads_take_only_the_type_written() {
+    let vault = Vault { balance: 7 };
+    let mut data = Vec::new();
+    vault.serialize_with_discriminator(&mut data).unwrap();
+    assert_eq!(data[..8], Vault::DISCRIMINATOR);
+    assert_eq!(Vault::try_deserialize_checked(&data), Ok(vault));
+
+    // Same layout, another type
+    assert_eq!(
+        Admin::try_deserialize_checked(&data).unwrap_err(),
+        StateError::WrongAccountType.into()
+    );
+    // Never written
+    assert_eq!(
+        Vault::try_deserialize_checked(&[0; 16]).unwrap_err(),
+        StateError::WrongAccountType.into()
+    );
+    assert_eq!(
+        Vault::try_deserialize_checked(&data[..4]).unwrap_err(),
+        StateError::DataTooSmall.into()
+    );
+    assert_eq!(
+        Vault::try_deserialize_checked(&data[..12]).unwrap_err(),
+        StateError::MalformedData.into()
+    );
+    data.push(0);
+    assert_eq!(
+        Vault::try_deserialize_checked(&data).unwrap_err(),
+        StateError::MalformedData.into()
+    );
+}
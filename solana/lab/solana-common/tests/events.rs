@@ -0,0 +1,45 @@
+//! Events read back from the fields they are logged as, and nothing else
+//! does
+
+use borsh::to_vec;
+use solana_common::events::{AuthorityChanged, Event, WithdrawEvent, VERSION};
+use solana_program::pubkey::Pubkey;
+
+fn logged(event: &Event) -> Vec<Vec<u8>> {
+    vec![vec![VERSION], to_vec(event).unwrap()]
+}
+
+fn decode(fields: &[Vec<u8>]) -> Option<Event> {
+    let fields: Vec<&[u8]> = fields.iter().map(Vec::as_slice).collect();
+    Event::decode(&fields)
+}
+
+#[test]
+fn events_decode_from_their_fields() {
+    let withdraw = Event::from(WithdrawEvent {
+        account: Pubkey::new_unique(),
+        recipient: Pubkey::new_unique(),
+        amount: 7,
+    });
+    assert_eq!(decode(&logged(&withdraw)), Some(withdraw));
+    let authority = Event::from(AuthorityChanged {
+        account: Pubkey::new_unique(),
+        authority: Pubkey::new_unique(),
+    });
+    assert_eq!(decode(&logged(&authority)), Some(authority));
+}
+
+#[test]
+fn other_data_is_not_an_event() {
+    let event = Event::from(AuthorityChanged {
+        account: Pubkey::new_unique(),
+        authority: Pubkey::new_unique(),
+    });
+    let mut later = logged(&event);
+    later[0] = vec![VERSION + 1];
+    assert_eq!(decode(&later), None);
+    let [version, body] = logged(&event).try_into().unwrap();
+    assert_eq!(decode(&logged(&event)[..1]), None);
+    assert_eq!(decode(&[version.clone(), body[..40].to_vec()]), None);
+    assert_eq!(decode(&[version, body, vec![0]]), None);
+}
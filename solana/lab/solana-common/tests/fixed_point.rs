@@ -0,0 +1,51 @@
+//! Q64.64 keeps the fraction integer division drops, and rounds the way it
+//! is told
+
+use lab_errors::ArithmeticError;
+use solana_common::fixed_point::{Rounding, U64F64};
+use solana_program::program_error::ProgramError;
+
+#[test]
+fn keeps_what_integer_division_drops() {
+    // 2 / 3 of 300 is 200; with the share taken first as an integer, it is 0
+    let (deposit, total) = (2u64, 3u64);
+    assert_eq!(deposit / total * 300, 0);
+    let share = U64F64::from_ratio(deposit, total, Rounding::Down).unwrap();
+    assert_eq!(share.mul_int(300, Rounding::Down), Ok(199));
+    assert_eq!(share.mul_int(300, Rounding::Up), Ok(200));
+    let exact = U64F64::from_ratio(2, 3, Rounding::Up).unwrap();
+    assert_eq!(exact.mul_int(300, Rounding::Up), Ok(201));
+
+    let half = U64F64::from_ratio(1, 2, Rounding::Down).unwrap();
+    let three = U64F64::from_int(3);
+    let one_and_a_half = three.mul(half, Rounding::Down).unwrap();
+    assert_eq!(Ok(one_and_a_half), U64F64::from_ratio(3, 2, Rounding::Down));
+    assert_eq!(one_and_a_half.to_int(Rounding::Down), Ok(1));
+    assert_eq!(one_and_a_half.to_int(Rounding::Up), Ok(2));
+    assert_eq!(three.div(half, Rounding::Down), Ok(U64F64::from_int(6)));
+    assert_eq!(half.checked_add(half), Ok(U64F64::ONE));
+}
+
+#[test]
+fn fails_only_where_the_result_does_not_fit() {
+    // The product needs more than 128 bits; the result does not
+    let big = U64F64::from_int(u64::MAX);
+    assert_eq!(big.mul(U64F64::ONE, Rounding::Down), Ok(big));
+    assert_eq!(big.div(big, Rounding::Up), Ok(U64F64::ONE));
+
+    let overflow = ProgramError::from(ArithmeticError::Overflow);
+    assert_eq!(
+        big.mul(U64F64::from_int(2), Rounding::Down),
+        Err(overflow.clone())
+    );
+    assert_eq!(big.mul_int(2, Rounding::Down), Err(overflow.clone()));
+    assert_eq!(U64F64::ZERO.checked_sub(U64F64::ONE), Err(overflow));
+    assert_eq!(
+        U64F64::ONE.div(U64F64::ZERO, Rounding::Down),
+        Err(ArithmeticError::DivisionByZero.into())
+    );
+    assert_eq!(
+        U64F64::from_ratio(1, 0, Rounding::Down),
+        Err(ArithmeticError::DivisionByZero.into())
+    );
+}
@@ -0,0 +1,113 @@
+//! grow_account keeps the data, zeroes what it adds and leaves the account
+//! rent-exempt at its new size, paid for by the payer
+
+use lab_svm::{Account, Keypair, Signer, Svm, TransactionError, LAMPORTS_PER_SOL};
+use solana_common::account::grow_account;
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+
+/// Grows its first account to the length in the instruction data
+fn grow(_program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [account, payer, system_program] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    let new_len = data
+        .try_into()
+        .map(u64::from_le_bytes)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+    grow_account(
+        account,
+        payer,
+        system_program,
+        new_len as usize,
+        &Rent::get()?,
+    )
+}
+
+/// The program, a 10-byte account of it at rent exemption and a funded
+/// payer
+fn setup() -> (Svm, Pubkey, Pubkey, Keypair) {
+    let mut svm = Svm::new();
+    let program_id = svm.add_program(grow);
+    let account = Pubkey::new_unique();
+    svm.set_account(
+        account,
+        Account {
+            lamports: svm.minimum_balance(10),
+            data: vec![7; 10],
+            owner: program_id,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+    let payer = Keypair::new();
+    svm.airdrop(&payer.pubkey(), LAMPORTS_PER_SOL);
+    (svm, program_id, account, payer)
+}
+
+fn grow_ix(
+    program_id: Pubkey,
+    account: Pubkey,
+    payer: Pubkey,
+    system: Pubkey,
+    len: u64,
+) -> Instruction {
+    Instruction::new_with_bytes(
+        program_id,
+        &len.to_le_bytes(),
+        vec![
+            AccountMeta::new(account, false),
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(system, false),
+        ],
+    )
+}
+
+#[test]
+fn grown_accounts_stay_rent_exempt() {
+    let (mut svm, program_id, account, payer) = setup();
+    let before = svm.lamports(&payer.pubkey());
+    let system = solana_system_interface::program::ID;
+    svm.send(
+        &[grow_ix(program_id, account, payer.pubkey(), system, 100)],
+        &[&payer],
+    )
+    .unwrap();
+
+    let grown = svm.get_account(&account).unwrap().clone();
+    assert_eq!(grown.data[..10], [7; 10]);
+    assert_eq!(grown.data[10..], [0; 90]);
+    assert_eq!(grown.lamports, svm.minimum_balance(100));
+    let topped_up = svm.minimum_balance(100) - svm.minimum_balance(10);
+    assert!(before - svm.lamports(&payer.pubkey()) >= topped_up);
+}
+
+#[test]
+fn only_the_system_program_pays_and_nothing_shrinks() {
+    let (mut svm, program_id, account, payer) = setup();
+    let failure = |svm: &mut Svm, system, len| {
+        let ix = grow_ix(program_id, account, payer.pubkey(), system, len);
+        svm.send(&[ix], &[&payer]).unwrap_err().err
+    };
+
+    let posing = svm.add_program(grow);
+    assert_eq!(
+        failure(&mut svm, posing, 100),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(lab_errors::ValidationError::KeyMismatch.code())
+        )
+    );
+    assert_eq!(
+        failure(&mut svm, solana_system_interface::program::ID, 4),
+        TransactionError::InstructionError(0, InstructionError::InvalidRealloc)
+    );
+    assert_eq!(svm.get_account(&account).unwrap().data, [7; 10]);
+}
@@ -0,0 +1,109 @@
+//! checked_price takes only a recent, positive, tight price from the
+//! oracle's own account; unchecked_price takes whatever it is given
+
+use lab_errors::{OracleError, ValidationError};
+use lab_test_utils::with_account;
+use solana_common::clock::MockClock;
+use solana_common::discriminator::AccountDiscriminator;
+use solana_common::oracle::{
+    checked_price, unchecked_price, MockOracle, MockPriceFeed, Price, PriceLimits,
+};
+use solana_program::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
+
+const NOW: i64 = 1_700_000_000;
+
+const LIMITS: PriceLimits = PriceLimits {
+    max_age: 60,
+    max_conf_bps: 100,
+};
+
+/// $100.00 give or take $0.50, published ten seconds ago
+fn feed() -> MockPriceFeed {
+    MockPriceFeed {
+        price: 10_000,
+        conf: 50,
+        expo: -2,
+        publish_time: NOW - 10,
+    }
+}
+
+/// Runs `read` on an account owned by `owner` holding `feed`
+fn with_feed<R>(owner: &Pubkey, feed: &MockPriceFeed, read: impl FnOnce(&AccountInfo) -> R) -> R {
+    let mut data = Vec::new();
+    feed.serialize_with_discriminator(&mut data).unwrap();
+    with_account(owner, data, read)
+}
+
+#[test]
+fn checked_price_rejects_each_bad_price() {
+    let oracle = MockOracle::new(Pubkey::new_unique());
+    let checked = |owner: &Pubkey, feed: MockPriceFeed| -> Result<Price, ProgramError> {
+        with_feed(owner, &feed, |account| {
+            checked_price(&oracle, account, &LIMITS, &MockClock(NOW))
+        })
+    };
+
+    assert_eq!(checked(&oracle.program_id, feed()).unwrap().price, 10_000);
+
+    let forged_by = Pubkey::new_unique();
+    assert_eq!(
+        checked(&forged_by, feed()),
+        Err(ValidationError::WrongOwner.into())
+    );
+    let negative = MockPriceFeed {
+        price: -10_000,
+        ..feed()
+    };
+    assert_eq!(
+        checked(&oracle.program_id, negative),
+        Err(OracleError::InvalidPrice.into())
+    );
+    let stale = MockPriceFeed {
+        publish_time: NOW - 61,
+        ..feed()
+    };
+    assert_eq!(
+        checked(&oracle.program_id, stale),
+        Err(OracleError::StalePrice.into())
+    );
+    let future = MockPriceFeed {
+        publish_time: NOW + 1,
+        ..feed()
+    };
+    assert_eq!(
+        checked(&oracle.program_id, future),
+        Err(OracleError::StalePrice.into())
+    );
+    // 1.01% of the price
+    let wide = MockPriceFeed {
+        conf: 101,
+        ..feed()
+    };
+    assert_eq!(
+        checked(&oracle.program_id, wide),
+        Err(OracleError::ConfidenceTooWide.into())
+    );
+}
+
+#[test]
+fn unchecked_price_takes_anything() {
+    let oracle = MockOracle::new(Pubkey::new_unique());
+    let forged = MockPriceFeed {
+        price: -1,
+        conf: u64::MAX,
+        expo: 0,
+        publish_time: 0,
+    };
+    let price = with_feed(&Pubkey::new_unique(), &forged, |account| {
+        unchecked_price(&oracle, account)
+    });
+    assert_eq!(
+        price,
+        Ok(Price {
+            price: -1,
+            conf: u64::MAX.into(),
+            expo: 0,
+            publish_time: 0,
+        })
+    );
+}
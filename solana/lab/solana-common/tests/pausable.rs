@@ -0,0 +1,44 @@
+//! Only the guardian pauses a program, and a withdrawal cap holds within
+//! its window and resets after it
+
+use lab_errors::{AccessError, ValidationError};
+use lab_test_utils::with_signer;
+use solana_common::pausable::{PauseState, WithdrawCap};
+use solana_program::pubkey::Pubkey;
+
+#[test]
+fn only_the_guardian_pauses() {
+    let guardian = Pubkey::new_unique();
+    let mut state = PauseState::new(guardian);
+    assert_eq!(state.when_not_paused(), Ok(()));
+
+    let stranger = Pubkey::new_unique();
+    assert_eq!(
+        with_signer(&stranger, true, |signer| state.pause(signer)),
+        Err(ValidationError::KeyMismatch.into())
+    );
+    with_signer(&guardian, true, |signer| state.pause(signer)).unwrap();
+    assert_eq!(state.when_not_paused(), Err(AccessError::Paused.into()));
+
+    with_signer(&guardian, true, |signer| state.unpause(signer)).unwrap();
+    assert_eq!(state.when_not_paused(), Ok(()));
+}
+
+#[test]
+fn cap_holds_within_its_window() {
+    let mut cap = WithdrawCap::new(100, 60);
+    let exceeded = Err(AccessError::WithdrawCapExceeded.into());
+
+    assert_eq!(cap.record(60, 1_000), Ok(()));
+    assert_eq!(cap.record(41, 1_030), exceeded);
+    assert_eq!(cap.remaining(1_030), 40);
+    assert_eq!(cap.record(40, 1_059), Ok(()));
+    assert_eq!(cap.record(1, 1_059), exceeded);
+
+    // A minute after the window opened, a new one does
+    assert_eq!(cap.remaining(1_060), 100);
+    assert_eq!(cap.record(100, 1_060), Ok(()));
+    assert_eq!(cap.record(101, 2_000), exceeded);
+    // A clock that went backwards is still in the window
+    assert_eq!(cap.record(1, 999), exceeded);
+}
@@ -0,0 +1,65 @@
+//! A spec derives the address its program checks, and a stored bump is
+//! trusted only if it was the canonical one
+
+use lab_errors::ValidationError;
+use solana_common::pda::{assert_canonical_bump, find_and_validate, verify_stored_bump, PdaSpec};
+use solana_program::pubkey::Pubkey;
+
+const ESCROW: PdaSpec<2> = PdaSpec::new(b"escrow", ["buyer", "seller"]);
+
+#[test]
+fn specs_derive_what_find_and_validate_accepts() {
+    let program_id = Pubkey::new_unique();
+    let (buyer, seller) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let (escrow, bump) = ESCROW.find([&buyer, &seller], &program_id);
+    assert_eq!(
+        ESCROW.seeds([&buyer, &seller]),
+        [&b"escrow"[..], buyer.as_ref(), seller.as_ref()]
+    );
+
+    let seeds = ESCROW.seeds([&buyer, &seller]);
+    assert_eq!(find_and_validate(&seeds, &program_id, &escrow), Ok(bump));
+    // The same seeds in the other order are another escrow
+    let swapped = ESCROW.seeds([&seller, &buyer]);
+    assert_eq!(
+        find_and_validate(&swapped, &program_id, &escrow),
+        Err(ValidationError::InvalidPda)
+    );
+    assert_eq!(
+        find_and_validate(&seeds, &Pubkey::new_unique(), &escrow),
+        Err(ValidationError::InvalidPda)
+    );
+}
+
+#[test]
+fn only_the_canonical_bump_is_stored() {
+    let program_id = Pubkey::new_unique();
+    let seeds: &[&[u8]] = &[b"vault"];
+    let (vault, canonical) = Pubkey::find_program_address(seeds, &program_id);
+    assert_eq!(
+        verify_stored_bump(seeds, canonical, &program_id, &vault),
+        Ok(())
+    );
+    assert_eq!(assert_canonical_bump(seeds, canonical, &program_id), Ok(()));
+
+    // A lower bump that still lands off the curve: a second valid address
+    let (bump, address) = (0..canonical)
+        .rev()
+        .find_map(|bump| {
+            let address = Pubkey::create_program_address(&[b"vault", &[bump]], &program_id);
+            address.ok().map(|address| (bump, address))
+        })
+        .expect("some lower bump derives an address");
+    assert_eq!(
+        verify_stored_bump(seeds, bump, &program_id, &address),
+        Ok(())
+    );
+    assert_eq!(
+        assert_canonical_bump(seeds, bump, &program_id),
+        Err(ValidationError::NonCanonicalBump)
+    );
+    assert_eq!(
+        verify_stored_bump(seeds, bump, &program_id, &vault),
+        Err(ValidationError::InvalidPda)
+    );
+}
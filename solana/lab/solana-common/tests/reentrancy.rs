@@ -0,0 +1,50 @@
+//! A held lock refuses a second acquire until it is released, and a guard
+//! releases it when dropped
+
+use lab_errors::StateError;
+use lab_test_utils::with_account;
+use solana_common::reentrancy::{acquire, release, LockGuard};
+use solana_program::pubkey::Pubkey;
+
+/// The lock's offset: after an 8-byte discriminator
+const LOCK: usize = 8;
+
+#[test]
+fn a_held_lock_refuses_reentry() {
+    with_account(&Pubkey::default(), vec![0; 16], |account| {
+        assert_eq!(acquire(account, LOCK), Ok(()));
+        // The call back in, while the first still holds it
+        assert_eq!(acquire(account, LOCK), Err(StateError::Locked.into()));
+        assert_eq!(release(account, LOCK), Ok(()));
+        assert_eq!(acquire(account, LOCK), Ok(()));
+        assert_eq!(account.data.borrow()[LOCK], 1);
+    });
+
+    with_account(&Pubkey::default(), vec![0; 4], |account| {
+        assert_eq!(acquire(account, LOCK), Err(StateError::DataTooSmall.into()));
+    });
+    let mut garbage = vec![0; 16];
+    garbage[LOCK] = 7;
+    with_account(&Pubkey::default(), garbage, |account| {
+        assert_eq!(
+            acquire(account, LOCK),
+            Err(StateError::MalformedData.into())
+        );
+    });
+}
+
+#[test]
+fn a_guard_releases_when_dropped() {
+    with_account(&Pubkey::default(), vec![0; 16], |account| {
+        {
+            let _guard = LockGuard::acquire(account, LOCK).unwrap();
+            assert_eq!(account.data.borrow()[LOCK], 1);
+            assert_eq!(
+                LockGuard::acquire(account, LOCK).err(),
+                Some(StateError::Locked.into())
+            );
+        }
+        assert_eq!(account.data.borrow()[LOCK], 0);
+        assert!(LockGuard::acquire(account, LOCK).is_ok());
+    });
+}
@@ -0,0 +1,47 @@
+//! Checked amounts fail where bare integers would wrap, and mul_div rounds
+//! the way it says
+
+use lab_errors::ArithmeticError;
+use solana_common::safe_math::{mul_div_ceil, mul_div_floor, CheckedU128, CheckedU64};
+use solana_program::program_error::ProgramError;
+
+#[test]
+fn operators_fail_instead_of_wrapping() {
+    let overflow = Err(ArithmeticError::Overflow.into());
+    assert_eq!(CheckedU64(2) + 3, Ok(CheckedU64(5)));
+    assert_eq!(CheckedU64(u64::MAX - 100) + 200, overflow);
+    assert_eq!(CheckedU64(5) - CheckedU64(2), Ok(CheckedU64(3)));
+    assert_eq!(CheckedU64(2) - 5, overflow);
+    assert_eq!(CheckedU64(u64::MAX) * 2, overflow);
+    assert_eq!(
+        (CheckedU64(6) * 7).and_then(|product| product - 2),
+        Ok(CheckedU64(40))
+    );
+
+    assert_eq!(
+        CheckedU128(u128::from(u64::MAX)) * u128::from(u64::MAX),
+        Ok(CheckedU128(u128::from(u64::MAX) * u128::from(u64::MAX)))
+    );
+    assert_eq!(
+        CheckedU128(u128::MAX) + 1,
+        Err(ArithmeticError::Overflow.into())
+    );
+    assert_eq!(u64::from(CheckedU64::from(9)), CheckedU64(9).get());
+}
+
+#[test]
+fn mul_div_multiplies_wide_and_names_its_rounding() {
+    assert_eq!(mul_div_floor(10, 10, 3), Ok(33));
+    assert_eq!(mul_div_ceil(10, 10, 3), Ok(34));
+    assert_eq!(mul_div_ceil(10, 9, 3), Ok(30));
+    // The product overflows u64; the quotient does not
+    assert_eq!(mul_div_floor(u64::MAX, 1_000, 1_000), Ok(u64::MAX));
+    assert_eq!(
+        mul_div_ceil(u64::MAX, 3, 2),
+        Err(ProgramError::from(ArithmeticError::Overflow))
+    );
+    assert_eq!(
+        mul_div_floor(1, 1, 0),
+        Err(ProgramError::from(ArithmeticError::DivisionByZero))
+    );
+}
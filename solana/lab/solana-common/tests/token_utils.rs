@@ -0,0 +1,70 @@
+//! Token balances are read only from SPL Token accounts, and no CPI goes
+//! to a program posing as SPL Token
+
+use lab_errors::ValidationError;
+use lab_test_utils::Stored;
+use solana_common::token_utils::{
+    balance, received, transfer, transfer_ix, Authority, TOKEN_ACCOUNT_LEN, TOKEN_PROGRAM_ID,
+};
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+
+/// A token account holding `amount`
+fn token_account(amount: u64) -> Stored {
+    let mut data = vec![0; TOKEN_ACCOUNT_LEN];
+    data[64..72].copy_from_slice(&amount.to_le_bytes());
+    Stored::new(TOKEN_PROGRAM_ID, data)
+}
+
+#[test]
+fn balances_are_read_from_token_accounts() {
+    let mut tokens = token_account(5);
+    let account = tokens.info();
+    assert_eq!(balance(&account), Ok(5));
+    let delivered = received(&account, || {
+        account.try_borrow_mut_data()?[64..72].copy_from_slice(&12u64.to_le_bytes());
+        Ok(())
+    });
+    assert_eq!(delivered, Ok(7));
+
+    let mut forged = Stored::new(Pubkey::new_unique(), token_account(5).data);
+    assert_eq!(
+        balance(&forged.info()),
+        Err(ValidationError::WrongOwner.into())
+    );
+}
+
+#[test]
+fn transfers_go_only_to_spl_token() {
+    let instruction = transfer_ix(
+        &Pubkey::new_unique(),
+        &Pubkey::new_unique(),
+        &Pubkey::new_unique(),
+        7,
+    );
+    assert_eq!(instruction.program_id, TOKEN_PROGRAM_ID);
+    assert_eq!(instruction.data, [&[3][..], &7u64.to_le_bytes()].concat());
+    assert_eq!(
+        instruction
+            .accounts
+            .iter()
+            .map(|meta| meta.is_signer)
+            .collect::<Vec<_>>(),
+        [false, false, true]
+    );
+
+    let (mut source, mut destination) = (token_account(7), token_account(0));
+    let mut signer = Stored::new(Pubkey::default(), Vec::new());
+    let mut impostor = Stored::new(Pubkey::default(), Vec::new());
+    let signer = signer.info();
+    let result = transfer(
+        &impostor.info(),
+        &source.info(),
+        &destination.info(),
+        Authority::Signer(&signer),
+        7,
+    );
+    assert_eq!(
+        result,
+        Err(ProgramError::from(ValidationError::KeyMismatch))
+    );
+}
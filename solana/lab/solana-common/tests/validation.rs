@@ -0,0 +1,107 @@
+//! Each helper rejects exactly the account it exists to catch, with an
+//! error whose code names the check
+
+use lab_test_utils::Stored;
+use solana_common::validation::{
+    assert_distinct, assert_key_eq, assert_owned_by, assert_pda, assert_rent_exempt, assert_signer,
+    assert_writable, ValidationError,
+};
+use solana_program::account_info::AccountInfo;
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+
+#[test]
+fn helpers_reject_what_they_check() {
+    let (key, owner, program_id) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let rent = Rent::default();
+    let mut stored = Stored {
+        key,
+        lamports: rent.minimum_balance(8),
+        signer: true,
+        writable: false,
+        ..Stored::new(owner, vec![0; 8])
+    };
+    let account = stored.info();
+
+    assert_eq!(assert_signer(&account), Ok(()));
+    assert_eq!(assert_owned_by(&account, &owner), Ok(()));
+    assert_eq!(
+        assert_owned_by(&account, &program_id),
+        Err(ValidationError::WrongOwner)
+    );
+    assert_eq!(assert_key_eq(&account, &key), Ok(()));
+    assert_eq!(
+        assert_key_eq(&account, &owner),
+        Err(ValidationError::KeyMismatch)
+    );
+    assert_eq!(assert_rent_exempt(&account, &rent), Ok(()));
+    assert_eq!(assert_writable(&account), Err(ValidationError::NotWritable));
+
+    let unsigned = AccountInfo {
+        is_signer: false,
+        ..account.clone()
+    };
+    assert_eq!(
+        assert_signer(&unsigned),
+        Err(ValidationError::MissingSignature)
+    );
+    **account.try_borrow_mut_lamports().unwrap() -= 1;
+    assert_eq!(
+        assert_rent_exempt(&account, &rent),
+        Err(ValidationError::NotRentExempt)
+    );
+
+    let seeds: &[&[u8]] = &[b"vault", key.as_ref()];
+    let (pda, bump) = Pubkey::find_program_address(seeds, &program_id);
+    let mut stored_vault = Stored {
+        key: pda,
+        ..Stored::new(program_id, Vec::new())
+    };
+    let vault = stored_vault.info();
+    assert_eq!(assert_pda(&vault, seeds, bump, &program_id), Ok(()));
+    assert_eq!(assert_distinct(&vault, &account), Ok(()));
+    assert_eq!(
+        assert_distinct(&vault, &vault.clone()),
+        Err(ValidationError::DuplicateAccount)
+    );
+    assert_eq!(assert_writable(&vault), Ok(()));
+    assert_eq!(
+        assert_pda(&vault, seeds, bump, &owner),
+        Err(ValidationError::InvalidPda)
+    );
+    assert_eq!(
+        assert_pda(&account, seeds, bump, &program_id),
+        Err(ValidationError::InvalidPda)
+    );
+}
+
+#[test]
+fn errors_become_custom_program_errors() {
+    let errors: Vec<ProgramError> = ValidationError::ALL.into_iter().map(Into::into).collect();
+    assert_eq!(
+        errors,
+        [1000, 1001, 1002, 1003, 1004, 1005, 1006, 1007].map(ProgramError::Custom)
+    );
+    let helpers: Vec<&str> = ValidationError::ALL
+        .into_iter()
+        .map(ValidationError::assertion)
+        .collect();
+    assert_eq!(
+        helpers,
+        [
+            "assert_signer",
+            "assert_owned_by",
+            "assert_key_eq",
+            "assert_pda",
+            "assert_rent_exempt",
+            "assert_writable",
+            "assert_canonical_bump",
+            "assert_distinct",
+        ]
+    );
+}
@@ -0,0 +1,127 @@
+//! A versioned account is read only as the layout its byte names, and
+//! migrating it rewrites it as the next layout, grown and paid for
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use lab_errors::StateError;
+use lab_svm::{Account, Keypair, Signer, Svm, TransactionError, LAMPORTS_PER_SOL};
+use solana_common::versioning::{migrate_in_place, read_versioned, write_versioned, Versioned};
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq)]
+struct V1 {
+    owner: Pubkey,
+    balance: u64,
+}
+
+impl Versioned for V1 {
+    const VERSION: u8 = 1;
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq)]
+struct V2 {
+    owner: Pubkey,
+    delegate: Pubkey,
+    balance: u64,
+}
+
+impl Versioned for V2 {
+    const VERSION: u8 = 2;
+}
+
+#[test]
+fn each_layout_reads_only_its_own_version() {
+    let owner = Pubkey::new_unique();
+    let v2 = V2 {
+        owner,
+        delegate: Pubkey::new_from_array([0xff; 32]),
+        balance: 0,
+    };
+    let mut data = vec![0; 1 + 32 + 32 + 8];
+    write_versioned(&v2, &mut data).unwrap();
+    assert_eq!(data[0], 2);
+
+    // Read as v1, the delegate would be the balance
+    assert_eq!(
+        read_versioned::<V1>(&data),
+        Err(StateError::WrongVersion.into())
+    );
+    assert_eq!(read_versioned::<V2>(&data), Ok(v2));
+    assert_eq!(
+        read_versioned::<V2>(&[]),
+        Err(StateError::DataTooSmall.into())
+    );
+}
+
+/// Migrates its first account from V1 to V2
+fn migrate(_program_id: &Pubkey, accounts: &[AccountInfo], _data: &[u8]) -> ProgramResult {
+    let [account, payer, system_program] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    migrate_in_place::<V1, V2>(account, payer, system_program, &Rent::get()?, |old| {
+        Ok(V2 {
+            owner: old.owner,
+            delegate: Pubkey::default(),
+            balance: old.balance,
+        })
+    })
+}
+
+#[test]
+fn migrating_rewrites_v1_as_v2() {
+    let mut svm = Svm::new();
+    let program_id = svm.add_program(migrate);
+    let owner = Pubkey::new_unique();
+    let mut data = vec![0; 1 + 32 + 8];
+    write_versioned(&V1 { owner, balance: 7 }, &mut data).unwrap();
+    let account = Pubkey::new_unique();
+    svm.set_account(
+        account,
+        Account {
+            lamports: svm.minimum_balance(data.len()),
+            data,
+            owner: program_id,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+    let payer = Keypair::new();
+    svm.airdrop(&payer.pubkey(), LAMPORTS_PER_SOL);
+    let ix = Instruction::new_with_bytes(
+        program_id,
+        &[],
+        vec![
+            AccountMeta::new(account, false),
+            AccountMeta::new(payer.pubkey(), true),
+            AccountMeta::new_readonly(solana_system_interface::program::ID, false),
+        ],
+    );
+
+    svm.send(std::slice::from_ref(&ix), &[&payer]).unwrap();
+    let migrated = svm.get_account(&account).unwrap().clone();
+    assert_eq!(
+        read_versioned::<V2>(&migrated.data),
+        Ok(V2 {
+            owner,
+            delegate: Pubkey::default(),
+            balance: 7,
+        })
+    );
+    assert_eq!(migrated.lamports, svm.minimum_balance(migrated.data.len()));
+
+    // It is v2 now, and does not migrate twice
+    assert_eq!(
+        svm.send(&[ix], &[&payer]).unwrap_err().err,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(StateError::WrongVersion.code())
+        )
+    );
+}
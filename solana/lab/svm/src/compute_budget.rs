@@ -0,0 +1,49 @@
+//! The compute budget program, as far as a runtime with no fees needs it
+//!
+//! A transaction's compute budget instructions are read before any of its
+//! instructions run, as a validator reads them: one it cannot unpack fails
+//! the transaction at that instruction, and one given twice fails it as a
+//! duplicate. The unit limit is held against [`meter`](crate::meter)'s
+//! count, which leaves out the instructions program code executes, so a
+//! limit low enough to trip here is far below what the program needs on
+//! chain. The unit price is accepted and charged nothing.
+
+use lab_instructions::compute_budget::ComputeBudgetInstruction;
+use solana_message::compiled_instruction::CompiledInstruction;
+use solana_program::instruction::{AccountMeta, InstructionError};
+use solana_transaction_error::TransactionError;
+
+pub(crate) use lab_instructions::compute_budget::ID;
+
+/// The unit limit `instructions` set, if any
+pub(crate) fn unit_limit(
+    instructions: &[CompiledInstruction],
+    metas: &[AccountMeta],
+) -> Result<Option<u64>, TransactionError> {
+    let mut given = [false; 4];
+    let mut limit = None;
+    for (index, compiled) in instructions.iter().enumerate() {
+        if metas[usize::from(compiled.program_id_index)].pubkey != ID {
+            continue;
+        }
+        let instruction = ComputeBudgetInstruction::unpack(&compiled.data).ok_or(
+            TransactionError::InstructionError(
+                index as u8,
+                InstructionError::InvalidInstructionData,
+            ),
+        )?;
+        let kind = match instruction {
+            ComputeBudgetInstruction::RequestHeapFrame(_) => 0,
+            ComputeBudgetInstruction::SetComputeUnitLimit(units) => {
+                limit = Some(u64::from(units));
+                1
+            }
+            ComputeBudgetInstruction::SetComputeUnitPrice(_) => 2,
+            ComputeBudgetInstruction::SetLoadedAccountsDataSizeLimit(_) => 3,
+        };
+        if std::mem::replace(&mut given[kind], true) {
+            return Err(TransactionError::DuplicateInstruction(index as u8));
+        }
+    }
+    Ok(limit)
+}
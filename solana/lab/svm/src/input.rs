@@ -0,0 +1,128 @@
+//! The aligned input buffer the BPF loader hands a program's entrypoint
+//!
+//! Programs read it with `solana_program::entrypoint::deserialize`, the
+//! same function their SBF entrypoint calls, so `AccountInfo::realloc`,
+//! `assign` and duplicate accounts behave as they do on chain.
+
+use std::mem::size_of;
+
+use solana_account::Account;
+use solana_program::entrypoint::{BPF_ALIGN_OF_U128, MAX_PERMITTED_DATA_INCREASE, NON_DUP_MARKER};
+use solana_program::instruction::AccountMeta;
+use solana_program::pubkey::Pubkey;
+
+/// Where one account's mutable fields sit in the buffer
+struct Slot {
+    meta: usize,
+    owner: usize,
+    lamports: usize,
+    data_len: usize,
+    data: usize,
+}
+
+pub(crate) struct Input {
+    /// u64-backed so every u64 field the entrypoint reads is aligned
+    buffer: Vec<u64>,
+    slots: Vec<Slot>,
+}
+
+impl Input {
+    pub fn serialize(
+        program_id: &Pubkey,
+        metas: &[AccountMeta],
+        accounts: &[Account],
+        instruction_data: &[u8],
+    ) -> Self {
+        let mut bytes = Vec::new();
+        let mut slots: Vec<Slot> = Vec::new();
+        push_u64(&mut bytes, metas.len() as u64);
+        for (position, meta) in metas.iter().enumerate() {
+            if let Some(first) = metas[..position]
+                .iter()
+                .position(|earlier| earlier.pubkey == meta.pubkey)
+            {
+                bytes.push(first as u8);
+                bytes.extend_from_slice(&[0; 7]);
+                continue;
+            }
+            let account = &accounts[position];
+            bytes.push(NON_DUP_MARKER);
+            bytes.push(u8::from(meta.is_signer));
+            bytes.push(u8::from(meta.is_writable));
+            bytes.push(u8::from(account.executable));
+            // Original data length, written by deserialize
+            bytes.extend_from_slice(&[0; 4]);
+            bytes.extend_from_slice(meta.pubkey.as_ref());
+            let owner = bytes.len();
+            bytes.extend_from_slice(account.owner.as_ref());
+            let lamports = bytes.len();
+            push_u64(&mut bytes, account.lamports);
+            let data_len = bytes.len();
+            push_u64(&mut bytes, account.data.len() as u64);
+            let data = bytes.len();
+            bytes.extend_from_slice(&account.data);
+            bytes.resize(bytes.len() + MAX_PERMITTED_DATA_INCREASE, 0);
+            bytes.resize(bytes.len().next_multiple_of(BPF_ALIGN_OF_U128), 0);
+            push_u64(&mut bytes, account.rent_epoch);
+            slots.push(Slot {
+                meta: position,
+                owner,
+                lamports,
+                data_len,
+                data,
+            });
+        }
+        push_u64(&mut bytes, instruction_data.len() as u64);
+        bytes.extend_from_slice(instruction_data);
+        bytes.extend_from_slice(program_id.as_ref());
+
+        let mut buffer = vec![0u64; bytes.len().div_ceil(size_of::<u64>())];
+        as_bytes_mut(&mut buffer)[..bytes.len()].copy_from_slice(&bytes);
+        Self { buffer, slots }
+    }
+
+    /// Start of the buffer, for `entrypoint::deserialize`
+    pub fn as_mut_ptr(&mut self) -> *mut u8 {
+        self.buffer.as_mut_ptr().cast()
+    }
+
+    /// Each unique account as the program left it, with the position of
+    /// its first occurrence in `metas`
+    pub fn accounts(&self, before: &[Account]) -> Vec<(usize, Account)> {
+        let bytes = as_bytes(&self.buffer);
+        self.slots
+            .iter()
+            .map(|slot| {
+                let data_len = read_u64(bytes, slot.data_len) as usize;
+                let account = Account {
+                    lamports: read_u64(bytes, slot.lamports),
+                    data: bytes[slot.data..slot.data + data_len].to_vec(),
+                    owner: Pubkey::try_from(&bytes[slot.owner..slot.owner + 32]).expect("32 bytes"),
+                    executable: before[slot.meta].executable,
+                    rent_epoch: before[slot.meta].rent_epoch,
+                };
+                (slot.meta, account)
+            })
+            .collect()
+    }
+}
+
+fn push_u64(bytes: &mut Vec<u8>, value: u64) {
+    bytes.extend_from_slice(&value.to_ne_bytes());
+}
+
+fn read_u64(bytes: &[u8], offset: usize) -> u64 {
+    u64::from_ne_bytes(bytes[offset..offset + 8].try_into().expect("8 bytes"))
+}
+
+fn as_bytes(words: &[u64]) -> &[u8] {
+    // SAFETY: u8 has no alignment or validity requirements
+    unsafe { std::slice::from_raw_parts(words.as_ptr().cast(), std::mem::size_of_val(words)) }
+}
+
+fn as_bytes_mut(words: &mut [u64]) -> &mut [u8] {
+    // SAFETY: u8 has no alignment or validity requirements
+    unsafe {
+        std::slice::from_raw_parts_mut(words.as_mut_ptr().cast(), std::mem::size_of_val(words))
+    }
+}
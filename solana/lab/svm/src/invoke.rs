@@ -0,0 +1,436 @@
+//! Instruction execution, cross-program invocation and the account rules
+//! the runtime enforces after every program returns
+//!
+//! Programs call `invoke` and `Rent::get` through the syscall stubs
+//! `solana_program` falls back to off chain. They are global, so they
+//! reach the transaction being executed through a thread-local context,
+//! which also keeps the transaction's [`meter`](crate::meter) reading.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::Once;
+
+use base64::prelude::{Engine, BASE64_STANDARD};
+use solana_account::Account;
+use solana_program::account_info::AccountInfo;
+use solana_program::clock::Clock;
+use solana_program::entrypoint::{deserialize, ProcessInstruction, ProgramResult};
+use solana_program::instruction::{AccountMeta, Instruction, InstructionError};
+use solana_program::program_error::ProgramError;
+use solana_program::program_stubs::{set_syscall_stubs, SyscallStubs};
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+use solana_system_interface::program as system_program;
+
+use crate::input::Input;
+use crate::meter::{self, SYSCALL_BASE_UNITS};
+use crate::{compute_budget, lookup_table, system};
+
+/// Deepest instruction stack, counting the transaction's own instruction
+pub const MAX_INSTRUCTION_STACK_DEPTH: usize = 5;
+
+const SUCCESS: u64 = 0;
+
+pub(crate) struct InvokeContext {
+    pub programs: HashMap<Pubkey, ProcessInstruction>,
+    pub rent: Rent,
+    pub clock: Clock,
+    pub logs: Vec<String>,
+    /// Units charged so far, by [`meter`]'s cost table
+    pub compute_units: u64,
+    /// The units the transaction's compute budget allows, if it set any
+    pub compute_unit_limit: Option<u64>,
+    frames: Vec<Frame>,
+    /// Set when a CPI fails; fails every enclosing instruction, whatever
+    /// the caller does with the error
+    aborted: Option<InstructionError>,
+}
+
+/// A program on the instruction stack
+struct Frame {
+    program_id: Pubkey,
+    /// Accounts as of the last point the runtime checked them
+    pre: HashMap<Pubkey, Account>,
+}
+
+impl InvokeContext {
+    pub fn new(programs: HashMap<Pubkey, ProcessInstruction>, rent: Rent, clock: Clock) -> Self {
+        Self {
+            programs,
+            rent,
+            clock,
+            logs: Vec::new(),
+            compute_units: 0,
+            compute_unit_limit: None,
+            frames: Vec::new(),
+            aborted: None,
+        }
+    }
+}
+
+thread_local! {
+    static CONTEXT: RefCell<Option<InvokeContext>> = const { RefCell::new(None) };
+}
+
+/// Runs `f` with `context` installed for the syscall stubs, then hands the
+/// context back with whatever it logged
+pub(crate) fn with_context<R>(context: InvokeContext, f: impl FnOnce() -> R) -> (R, InvokeContext) {
+    static STUBS: Once = Once::new();
+    STUBS.call_once(|| {
+        set_syscall_stubs(Box::new(Stubs));
+    });
+
+    CONTEXT.with(|cell| *cell.borrow_mut() = Some(context));
+    let result = f();
+    let context = CONTEXT
+        .with(|cell| cell.borrow_mut().take())
+        .expect("context installed above");
+    (result, context)
+}
+
+fn context<R>(f: impl FnOnce(&mut InvokeContext) -> R) -> R {
+    CONTEXT.with(|cell| {
+        let mut context = cell.borrow_mut();
+        f(context
+            .as_mut()
+            .expect("called while executing a transaction"))
+    })
+}
+
+fn log(message: String) {
+    context(|context| context.logs.push(message));
+}
+
+fn charge(units: u64) {
+    context(|context| context.compute_units += units);
+}
+
+/// Executes one instruction against `accounts`, which line up with `metas`
+/// and are updated in place when the instruction succeeds
+pub(crate) fn execute(
+    program_id: &Pubkey,
+    metas: &[AccountMeta],
+    accounts: &mut [Account],
+    data: &[u8],
+) -> Result<(), InstructionError> {
+    let depth = context(|context| context.frames.len()) + 1;
+    if depth > MAX_INSTRUCTION_STACK_DEPTH {
+        return Err(InstructionError::CallDepth);
+    }
+    // A program may call itself, but nothing may call back into one
+    // further down the stack
+    let reentered = context(|context| {
+        context
+            .frames
+            .last()
+            .is_some_and(|top| top.program_id != *program_id)
+            && context
+                .frames
+                .iter()
+                .any(|frame| frame.program_id == *program_id)
+    });
+    if reentered {
+        return Err(InstructionError::ReentrancyNotAllowed);
+    }
+
+    log(format!("Program {program_id} invoke [{depth}]"));
+    let result = if *program_id == system_program::ID {
+        charge(meter::BUILTIN_UNITS);
+        system::process(metas, accounts, data)
+    } else if *program_id == compute_budget::ID {
+        // Read before the transaction ran
+        charge(meter::BUILTIN_UNITS);
+        Ok(())
+    } else if *program_id == lookup_table::ID {
+        charge(meter::BUILTIN_UNITS);
+        let (rent, slot) = context(|context| (context.rent.clone(), context.clock.slot));
+        lookup_table::process(metas, accounts, data, &rent, slot)
+    } else {
+        run_program(program_id, metas, accounts, data)
+    };
+    let over_budget = context(|context| {
+        context
+            .compute_unit_limit
+            .is_some_and(|limit| context.compute_units > limit)
+    });
+    let result = match result {
+        Ok(()) if over_budget => Err(InstructionError::ComputationalBudgetExceeded),
+        result => result,
+    };
+    match &result {
+        Ok(()) => log(format!("Program {program_id} success")),
+        Err(err) => log(format!("Program {program_id} failed: {err}")),
+    }
+    result
+}
+
+fn run_program(
+    program_id: &Pubkey,
+    metas: &[AccountMeta],
+    accounts: &mut [Account],
+    data: &[u8],
+) -> Result<(), InstructionError> {
+    let entrypoint = context(|context| context.programs.get(program_id).copied())
+        .ok_or(InstructionError::UnsupportedProgramId)?;
+
+    let mut input = Input::serialize(program_id, metas, accounts, data);
+    let pre = metas
+        .iter()
+        .zip(accounts.iter())
+        .map(|(meta, account)| (meta.pubkey, account.clone()))
+        .collect();
+    context(|context| {
+        context.frames.push(Frame {
+            program_id: *program_id,
+            pre,
+        })
+    });
+
+    let outcome = meter::running(true, || {
+        catch_unwind(AssertUnwindSafe(|| {
+            // SAFETY: the buffer is laid out the way the loader lays it out
+            // and outlives every AccountInfo the program sees
+            let (program_id, infos, data) = unsafe { deserialize(input.as_mut_ptr()) };
+            entrypoint(program_id, &infos, data)
+        }))
+    });
+
+    let (frame, aborted) = context(|context| {
+        let frame = context.frames.pop().expect("pushed above");
+        (frame, context.aborted.clone())
+    });
+    match outcome {
+        Err(panic) => {
+            let message = panic
+                .downcast_ref::<&str>()
+                .map(|message| message.to_string())
+                .or_else(|| panic.downcast_ref::<String>().cloned())
+                .unwrap_or_default();
+            log(format!("Program log: panicked: {message}"));
+            return Err(InstructionError::ProgramFailedToComplete);
+        }
+        Ok(_) if aborted.is_some() => return Err(aborted.expect("checked")),
+        Ok(Err(err)) => return Err(InstructionError::from(u64::from(err))),
+        Ok(Ok(())) => {}
+    }
+
+    let post = input.accounts(accounts);
+    verify(program_id, metas, &frame.pre, &post, true)?;
+    for (position, account) in post {
+        let key = metas[position].pubkey;
+        for (meta, slot) in metas.iter().zip(accounts.iter_mut()) {
+            if meta.pubkey == key {
+                *slot = account.clone();
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The rules every program is held to when it returns, or when it makes a
+/// CPI and the runtime syncs its changes so far
+fn verify(
+    program_id: &Pubkey,
+    metas: &[AccountMeta],
+    pre: &HashMap<Pubkey, Account>,
+    post: &[(usize, Account)],
+    balanced: bool,
+) -> Result<(), InstructionError> {
+    let (mut pre_total, mut post_total) = (0u128, 0u128);
+    for (position, after) in post {
+        let meta = &metas[*position];
+        let before = &pre[&meta.pubkey];
+        pre_total += u128::from(before.lamports);
+        post_total += u128::from(after.lamports);
+
+        if before.owner != after.owner
+            && (!meta.is_writable
+                || before.executable
+                || before.owner != *program_id
+                || after.data.iter().any(|byte| *byte != 0))
+        {
+            return Err(InstructionError::ModifiedProgramId);
+        }
+        if after.lamports < before.lamports && before.owner != *program_id {
+            return Err(InstructionError::ExternalAccountLamportSpend);
+        }
+        if after.lamports != before.lamports && !meta.is_writable {
+            return Err(InstructionError::ReadonlyLamportChange);
+        }
+        if after.data.len() != before.data.len()
+            && (!meta.is_writable || before.owner != *program_id)
+        {
+            return Err(InstructionError::AccountDataSizeChanged);
+        }
+        if after.data != before.data {
+            if !meta.is_writable {
+                return Err(InstructionError::ReadonlyDataModified);
+            }
+            if before.owner != *program_id {
+                return Err(InstructionError::ExternalAccountDataModified);
+            }
+        }
+    }
+    if balanced && pre_total != post_total {
+        return Err(InstructionError::UnbalancedInstruction);
+    }
+    Ok(())
+}
+
+fn invoke_signed(
+    instruction: &Instruction,
+    account_infos: &[AccountInfo],
+    signers_seeds: &[&[&[u8]]],
+) -> Result<(), InstructionError> {
+    let caller = context(|context| context.frames.last().map(|frame| frame.program_id))
+        .expect("invoked from a program");
+
+    let mut signers: Vec<Pubkey> = account_infos
+        .iter()
+        .filter(|info| info.is_signer)
+        .map(|info| *info.key)
+        .collect();
+    for seeds in signers_seeds {
+        let signer = Pubkey::create_program_address(seeds, &caller)
+            .map_err(|_| InstructionError::InvalidSeeds)?;
+        signers.push(signer);
+    }
+
+    let mut infos = Vec::with_capacity(instruction.accounts.len());
+    for meta in &instruction.accounts {
+        let info = account_infos
+            .iter()
+            .find(|info| *info.key == meta.pubkey)
+            .ok_or(InstructionError::MissingAccount)?;
+        if meta.is_writable && !info.is_writable {
+            return Err(InstructionError::PrivilegeEscalation);
+        }
+        if meta.is_signer && !signers.contains(&meta.pubkey) {
+            return Err(InstructionError::PrivilegeEscalation);
+        }
+        infos.push(info);
+    }
+
+    // The caller's changes so far are checked, then become the callee's
+    // starting state
+    let caller_metas: Vec<AccountMeta> = infos
+        .iter()
+        .map(|info| AccountMeta {
+            pubkey: *info.key,
+            is_signer: info.is_signer,
+            is_writable: info.is_writable,
+        })
+        .collect();
+    let mut accounts = infos
+        .iter()
+        .map(|info| snapshot(info))
+        .collect::<Result<Vec<_>, _>>()?;
+    let current: Vec<(usize, Account)> = accounts.iter().cloned().enumerate().collect();
+    context(|context| {
+        let frame = context.frames.last_mut().expect("caller frame");
+        verify(&caller, &caller_metas, &frame.pre, &current, false)?;
+        for (meta, account) in caller_metas.iter().zip(&accounts) {
+            frame.pre.insert(meta.pubkey, account.clone());
+        }
+        Ok::<_, InstructionError>(())
+    })?;
+
+    execute(
+        &instruction.program_id,
+        &instruction.accounts,
+        &mut accounts,
+        &instruction.data,
+    )?;
+
+    for (info, after) in infos.iter().zip(&accounts) {
+        **info
+            .try_borrow_mut_lamports()
+            .map_err(|_| InstructionError::AccountBorrowFailed)? = after.lamports;
+        if info.data_len() != after.data.len() {
+            info.resize(after.data.len())
+                .map_err(|_| InstructionError::InvalidRealloc)?;
+        }
+        info.try_borrow_mut_data()
+            .map_err(|_| InstructionError::AccountBorrowFailed)?
+            .copy_from_slice(&after.data);
+        if *info.owner != after.owner {
+            info.assign(&after.owner);
+        }
+    }
+    context(|context| {
+        let frame = context.frames.last_mut().expect("caller frame");
+        for (meta, account) in caller_metas.iter().zip(accounts) {
+            frame.pre.insert(meta.pubkey, account);
+        }
+    });
+    Ok(())
+}
+
+fn snapshot(info: &AccountInfo) -> Result<Account, InstructionError> {
+    Ok(Account {
+        lamports: info.lamports(),
+        data: info
+            .try_borrow_data()
+            .map_err(|_| InstructionError::AccountBorrowFailed)?
+            .to_vec(),
+        owner: *info.owner,
+        executable: info.executable,
+        rent_epoch: info.rent_epoch,
+    })
+}
+
+struct Stubs;
+
+impl SyscallStubs for Stubs {
+    fn sol_log(&self, message: &str) {
+        meter::running(false, || log(format!("Program log: {message}")));
+    }
+
+    fn sol_log_data(&self, fields: &[&[u8]]) {
+        let fields: Vec<_> = fields
+            .iter()
+            .map(|field| BASE64_STANDARD.encode(field))
+            .collect();
+        meter::running(false, || log(format!("Program data: {}", fields.join(" "))));
+    }
+
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        charge(meter::invoke_units(&instruction.data));
+        meter::running(false, || {
+            invoke_signed(instruction, account_infos, signers_seeds)
+        })
+        .map_err(|err| {
+            context(|context| {
+                context.aborted.get_or_insert_with(|| err.clone());
+            });
+            ProgramError::try_from(err).unwrap_or(ProgramError::Custom(0))
+        })
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        charge(meter::sysvar_units::<Rent>());
+        let rent = context(|context| context.rent.clone());
+        // SAFETY: Rent::get passes a pointer to a Rent
+        unsafe { var_addr.cast::<Rent>().write(rent) };
+        SUCCESS
+    }
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        charge(meter::sysvar_units::<Clock>());
+        let clock = context(|context| context.clock.clone());
+        // SAFETY: Clock::get passes a pointer to a Clock
+        unsafe { var_addr.cast::<Clock>().write(clock) };
+        SUCCESS
+    }
+
+    fn sol_get_stack_height(&self) -> u64 {
+        charge(SYSCALL_BASE_UNITS);
+        context(|context| context.frames.len() as u64)
+    }
+}
@@ -0,0 +1,643 @@
+//! An in-process Solana runtime for the examples
+//!
+//! Programs run as native code, registered by their `process_instruction`,
+//! but see the same input the BPF loader gives them and are held to the
+//! same account rules afterwards: a program may only debit and write
+//! accounts it owns, only writable accounts change, lamports balance, a
+//! CPI may not call back into a program further down the stack, and
+//! accounts left below the rent-exempt minimum are rejected or, at zero
+//! lamports, deleted. Transactions are signed and verified, and commit
+//! atomically. There are no fees, but [`meter`] keeps count of the compute
+//! units and heap a transaction would use, and a transaction that sets a
+//! compute unit limit fails once the count passes it. A
+//! transaction can also be simulated, run without committing anything, and
+//! [`Svm::set_dry_run`] simulates everything sent until it is turned off.
+//! [`Svm::observe`] is told of each transaction as it is recorded.
+//!
+//! Legacy and v0 transactions both run. A v0 transaction's lookups resolve
+//! against the address lookup table accounts the runtime holds, which the
+//! table program's create and extend instructions make, and a transaction
+//! is held to a validator's limits: it must fit in one packet
+//! ([`PACKET_DATA_SIZE`]) and lock at most [`MAX_TX_ACCOUNT_LOCKS`]
+//! accounts, however many it names through tables.
+
+mod compute_budget;
+mod input;
+mod invoke;
+mod lookup_table;
+pub mod meter;
+mod system;
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::time::Duration;
+
+use solana_address_lookup_table_interface::instruction::{
+    create_lookup_table, extend_lookup_table,
+};
+use solana_address_lookup_table_interface::state::AddressLookupTable;
+use solana_message::compiled_instruction::CompiledInstruction;
+use solana_message::v0::{self, LoadedAddresses, LoadedMessage};
+use solana_message::VersionedMessage;
+use solana_program::clock::Clock;
+use solana_program::entrypoint::ProcessInstruction;
+use solana_program::hash::{hash, Hash};
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+use solana_program::slot_hashes::SlotHashes;
+use solana_system_interface::program as system_program;
+
+pub use invoke::MAX_INSTRUCTION_STACK_DEPTH;
+pub use meter::CountingAllocator;
+pub use solana_account::Account;
+pub use solana_keypair::Keypair;
+pub use solana_message::AddressLookupTableAccount;
+pub use solana_program::native_token::LAMPORTS_PER_SOL;
+pub use solana_signature::Signature;
+pub use solana_signer::Signer;
+pub use solana_transaction::versioned::VersionedTransaction;
+pub use solana_transaction::Transaction;
+pub use solana_transaction_error::TransactionError;
+
+use invoke::InvokeContext;
+
+/// Largest transaction a validator takes, serialized: one packet
+pub const PACKET_DATA_SIZE: usize = 1232;
+
+/// Most accounts one transaction may lock, signers and programs included
+pub const MAX_TX_ACCOUNT_LOCKS: usize = 64;
+
+/// Owner of deployed programs
+const BPF_LOADER_UPGRADEABLE: Pubkey =
+    solana_program::pubkey!("BPFLoaderUpgradeab1e11111111111111111111111");
+
+/// What an executed transaction left behind
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransactionMeta {
+    pub signature: Signature,
+    /// Runtime log lines: invocations, their results and `sol_log` output
+    pub logs: Vec<String>,
+    /// The transaction's accounts, with their lamports before and after;
+    /// a failed transaction's are unchanged
+    pub account_keys: Vec<Pubkey>,
+    pub pre_balances: Vec<u64>,
+    pub post_balances: Vec<u64>,
+    /// Units the runtime would charge, leaving out the programs' own
+    /// instructions (see [`meter`])
+    pub compute_units: u64,
+    /// Bytes the programs allocated; zero unless the binary's global
+    /// allocator is a [`CountingAllocator`]
+    pub heap_bytes: u64,
+    /// Time spent in the programs' code, as native code
+    pub program_time: Duration,
+}
+
+impl TransactionMeta {
+    /// The meta of a transaction rejected before it ran
+    fn rejected(signature: Signature) -> Self {
+        Self {
+            signature,
+            logs: Vec::new(),
+            account_keys: Vec::new(),
+            pre_balances: Vec::new(),
+            post_balances: Vec::new(),
+            compute_units: 0,
+            heap_bytes: 0,
+            program_time: Duration::ZERO,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FailedTransaction {
+    pub err: TransactionError,
+    pub meta: Box<TransactionMeta>,
+}
+
+impl fmt::Display for FailedTransaction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "transaction failed: {}", self.err)
+    }
+}
+
+impl std::error::Error for FailedTransaction {}
+
+pub type TransactionResult = Result<TransactionMeta, FailedTransaction>;
+
+/// Called with every transaction as it is recorded
+type Observer = Box<dyn FnMut(&VersionedTransaction, &TransactionResult) + Send>;
+
+/// Called with every transaction before it runs, and the state it runs
+/// against
+type Inspector = Box<dyn FnMut(&Svm, &VersionedTransaction) + Send>;
+
+pub struct Svm {
+    accounts: HashMap<Pubkey, Account>,
+    programs: HashMap<Pubkey, ProcessInstruction>,
+    /// Program ids in the order they were added
+    program_ids: Vec<Pubkey>,
+    rent: Rent,
+    clock: Clock,
+    blockhash: Hash,
+    history: Vec<TransactionResult>,
+    /// What was sent, alongside `history`
+    transactions: Vec<VersionedTransaction>,
+    dry_run: bool,
+    observer: Option<Observer>,
+    inspector: Option<Inspector>,
+}
+
+impl Default for Svm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Svm {
+    pub fn new() -> Self {
+        Self {
+            accounts: HashMap::new(),
+            programs: HashMap::new(),
+            program_ids: Vec::new(),
+            rent: Rent::default(),
+            clock: Clock {
+                slot: 1,
+                epoch_start_timestamp: 1_700_000_000,
+                epoch: 0,
+                leader_schedule_epoch: 1,
+                unix_timestamp: 1_700_000_000,
+            },
+            blockhash: hash(b"genesis"),
+            history: Vec::new(),
+            transactions: Vec::new(),
+            dry_run: false,
+            observer: None,
+            inspector: None,
+        }
+    }
+
+    /// Registers a program under a fresh address
+    pub fn add_program(&mut self, entrypoint: ProcessInstruction) -> Pubkey {
+        let program_id = Pubkey::new_unique();
+        self.add_program_with_id(program_id, entrypoint);
+        program_id
+    }
+
+    pub fn add_program_with_id(&mut self, program_id: Pubkey, entrypoint: ProcessInstruction) {
+        if self.programs.insert(program_id, entrypoint).is_none() {
+            self.program_ids.push(program_id);
+        }
+        self.accounts.insert(
+            program_id,
+            Account {
+                lamports: self.rent.minimum_balance(0),
+                data: Vec::new(),
+                owner: BPF_LOADER_UPGRADEABLE,
+                executable: true,
+                rent_epoch: 0,
+            },
+        );
+    }
+
+    /// Every program added, in order
+    pub fn program_ids(&self) -> &[Pubkey] {
+        &self.program_ids
+    }
+
+    pub fn entrypoint(&self, program_id: &Pubkey) -> Option<ProcessInstruction> {
+        self.programs.get(program_id).copied()
+    }
+
+    /// Writes an account directly, as a test fixture would; the runtime
+    /// rules do not apply
+    pub fn set_account(&mut self, pubkey: Pubkey, account: Account) {
+        if account.lamports == 0 {
+            self.accounts.remove(&pubkey);
+        } else {
+            self.accounts.insert(pubkey, account);
+        }
+    }
+
+    pub fn get_account(&self, pubkey: &Pubkey) -> Option<&Account> {
+        self.accounts.get(pubkey)
+    }
+
+    /// Every account that exists, programs included, in no particular order
+    pub fn accounts(&self) -> impl Iterator<Item = (&Pubkey, &Account)> {
+        self.accounts.iter()
+    }
+
+    pub fn lamports(&self, pubkey: &Pubkey) -> u64 {
+        self.accounts
+            .get(pubkey)
+            .map_or(0, |account| account.lamports)
+    }
+
+    /// Credits a system account, creating it if needed
+    pub fn airdrop(&mut self, pubkey: &Pubkey, lamports: u64) {
+        let account = self.accounts.entry(*pubkey).or_insert_with(|| Account {
+            owner: system_program::ID,
+            ..Account::default()
+        });
+        account.lamports += lamports;
+    }
+
+    pub fn rent(&self) -> &Rent {
+        &self.rent
+    }
+
+    pub fn minimum_balance(&self, data_len: usize) -> u64 {
+        self.rent.minimum_balance(data_len)
+    }
+
+    pub fn clock(&self) -> &Clock {
+        &self.clock
+    }
+
+    pub fn set_clock(&mut self, clock: Clock) {
+        self.clock = clock;
+    }
+
+    pub fn latest_blockhash(&self) -> Hash {
+        self.blockhash
+    }
+
+    /// Every transaction sent so far, in order
+    pub fn history(&self) -> &[TransactionResult] {
+        &self.history
+    }
+
+    /// The transactions behind [`history`](Svm::history), as they were
+    /// sent; a legacy one is [`VersionedMessage::Legacy`]
+    pub fn transactions(&self) -> &[VersionedTransaction] {
+        &self.transactions
+    }
+
+    /// While on, everything sent is simulated: it runs against the current
+    /// state and goes into the history, but changes nothing
+    pub fn set_dry_run(&mut self, dry_run: bool) {
+        self.dry_run = dry_run;
+    }
+
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Calls `observer` with every transaction sent from now on, as it goes
+    /// into the history
+    pub fn observe(
+        &mut self,
+        observer: impl FnMut(&VersionedTransaction, &TransactionResult) + Send + 'static,
+    ) {
+        self.observer = Some(Box::new(observer));
+    }
+
+    /// Calls `inspector` with every transaction sent from now on, before it
+    /// runs, while the runtime still holds the state it will run against
+    pub fn inspect(&mut self, inspector: impl FnMut(&Svm, &VersionedTransaction) + Send + 'static) {
+        self.inspector = Some(Box::new(inspector));
+    }
+
+    /// Runs a transaction against the current state without committing or
+    /// recording it, as an RPC node's `simulateTransaction` does
+    pub fn simulate_transaction(&self, transaction: &Transaction) -> TransactionResult {
+        self.simulate_versioned_transaction(&transaction.clone().into())
+    }
+
+    /// [`simulate_transaction`](Svm::simulate_transaction), for either
+    /// kind of transaction
+    pub fn simulate_versioned_transaction(
+        &self,
+        transaction: &VersionedTransaction,
+    ) -> TransactionResult {
+        let mut fork = Svm {
+            accounts: self.accounts.clone(),
+            programs: self.programs.clone(),
+            program_ids: Vec::new(),
+            rent: self.rent.clone(),
+            clock: self.clock.clone(),
+            blockhash: self.blockhash,
+            history: Vec::new(),
+            transactions: Vec::new(),
+            dry_run: false,
+            observer: None,
+            inspector: None,
+        };
+        fork.send_versioned_transaction(transaction)
+    }
+
+    /// Signs `instructions` with `signers`, the first paying, and sends them
+    pub fn send(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> TransactionResult {
+        let payer = signers.first().map(|signer| signer.pubkey());
+        let mut transaction = Transaction::new_with_payer(instructions, payer.as_ref());
+        if transaction.try_sign(signers, self.blockhash).is_err() {
+            let transaction = transaction.into();
+            self.before_send(&transaction);
+            return self.record(
+                &transaction,
+                Err(FailedTransaction {
+                    err: TransactionError::SignatureFailure,
+                    meta: Box::new(TransactionMeta::rejected(Signature::default())),
+                }),
+            );
+        }
+        self.send_transaction(&transaction)
+    }
+
+    /// Signs `instructions` with `signers`, the first paying, into a v0
+    /// transaction that names through `lookup_tables` every account they
+    /// hold, and sends it. Instructions that cannot be compiled into one,
+    /// with more than 256 accounts or a signer only a table holds, are
+    /// refused before anything is sent
+    pub fn send_v0(
+        &mut self,
+        instructions: &[Instruction],
+        lookup_tables: &[AddressLookupTableAccount],
+        signers: &[&Keypair],
+    ) -> TransactionResult {
+        match self.sign_v0(instructions, lookup_tables, signers) {
+            Ok(transaction) => self.send_versioned_transaction(&transaction),
+            Err(err) => Err(FailedTransaction {
+                err,
+                meta: Box::new(TransactionMeta::rejected(Signature::default())),
+            }),
+        }
+    }
+
+    /// The v0 transaction [`send_v0`](Svm::send_v0) would send, signed
+    /// against the current blockhash
+    pub fn sign_v0(
+        &self,
+        instructions: &[Instruction],
+        lookup_tables: &[AddressLookupTableAccount],
+        signers: &[&Keypair],
+    ) -> Result<VersionedTransaction, TransactionError> {
+        let payer = signers
+            .first()
+            .map(|signer| signer.pubkey())
+            .unwrap_or_default();
+        let message = v0::Message::try_compile(&payer, instructions, lookup_tables, self.blockhash)
+            .map_err(|_| TransactionError::SanitizeFailure)?;
+        VersionedTransaction::try_new(VersionedMessage::V0(message), signers)
+            .map_err(|_| TransactionError::SignatureFailure)
+    }
+
+    pub fn send_transaction(&mut self, transaction: &Transaction) -> TransactionResult {
+        self.send_versioned_transaction(&transaction.clone().into())
+    }
+
+    /// [`send_transaction`](Svm::send_transaction), for either kind of
+    /// transaction
+    pub fn send_versioned_transaction(
+        &mut self,
+        transaction: &VersionedTransaction,
+    ) -> TransactionResult {
+        self.before_send(transaction);
+        if self.dry_run {
+            let result = self.simulate_versioned_transaction(transaction);
+            return self.record(transaction, result);
+        }
+        let signature = transaction.signatures.first().copied().unwrap_or_default();
+        let rejected = |err| {
+            Err(FailedTransaction {
+                err,
+                meta: Box::new(TransactionMeta::rejected(signature)),
+            })
+        };
+        // A validator will not take one that does not fit in a packet
+        let oversized = bincode::serialized_size(transaction)
+            .map_or(true, |size| size > PACKET_DATA_SIZE as u64);
+        if oversized || transaction.sanitize().is_err() {
+            return self.record(transaction, rejected(TransactionError::SanitizeFailure));
+        }
+        if !transaction.verify_with_results().into_iter().all(|ok| ok) {
+            return self.record(transaction, rejected(TransactionError::SignatureFailure));
+        }
+        let metas = match self.account_metas(transaction) {
+            Ok(metas) => metas,
+            Err(err) => return self.record(transaction, rejected(err)),
+        };
+        if metas.len() > MAX_TX_ACCOUNT_LOCKS {
+            return self.record(transaction, rejected(TransactionError::TooManyAccountLocks));
+        }
+
+        let mut context =
+            InvokeContext::new(self.programs.clone(), self.rent.clone(), self.clock.clone());
+        context.compute_unit_limit =
+            match compute_budget::unit_limit(transaction.message.instructions(), &metas) {
+                Ok(limit) => limit,
+                Err(err) => return self.record(transaction, rejected(err)),
+            };
+        let account_keys: Vec<Pubkey> = metas.iter().map(|meta| meta.pubkey).collect();
+        let pre_balances = account_keys.iter().map(|key| self.lamports(key)).collect();
+        let (allocated, elapsed) = (meter::allocated(), meter::elapsed());
+        let (result, context) = invoke::with_context(context, || {
+            self.process(transaction.message.instructions(), &metas)
+        });
+        let meta = TransactionMeta {
+            signature,
+            logs: context.logs,
+            post_balances: account_keys.iter().map(|key| self.lamports(key)).collect(),
+            pre_balances,
+            account_keys,
+            compute_units: context.compute_units,
+            heap_bytes: meter::allocated() - allocated,
+            program_time: meter::elapsed() - elapsed,
+        };
+        // A new blockhash per transaction keeps identical ones distinct
+        self.blockhash = hash(self.blockhash.as_ref());
+        self.record(
+            transaction,
+            match result {
+                Ok(()) => Ok(meta),
+                Err(err) => Err(FailedTransaction {
+                    err,
+                    meta: Box::new(meta),
+                }),
+            },
+        )
+    }
+
+    /// Every account `transaction` names, in order, its v0 lookups
+    /// resolved against the tables as they stand, each with whether it
+    /// signs and whether it may be written
+    pub fn account_metas(
+        &self,
+        transaction: &VersionedTransaction,
+    ) -> Result<Vec<AccountMeta>, TransactionError> {
+        let meta = |pubkey: &Pubkey, is_signer, is_writable| AccountMeta {
+            pubkey: *pubkey,
+            is_signer,
+            is_writable,
+        };
+        match &transaction.message {
+            VersionedMessage::Legacy(message) => Ok(message
+                .account_keys
+                .iter()
+                .enumerate()
+                .map(|(i, key)| {
+                    meta(
+                        key,
+                        message.is_signer(i),
+                        message.is_maybe_writable(i, None),
+                    )
+                })
+                .collect()),
+            VersionedMessage::V0(message) => {
+                let mut loaded = LoadedAddresses::default();
+                for lookup in &message.address_table_lookups {
+                    let table = self.lookup_table(&lookup.account_key)?;
+                    loaded
+                        .writable
+                        .extend(resolve(&table, &lookup.writable_indexes)?);
+                    loaded
+                        .readonly
+                        .extend(resolve(&table, &lookup.readonly_indexes)?);
+                }
+                let message = LoadedMessage::new(message.clone(), loaded, &HashSet::new());
+                Ok(message
+                    .account_keys()
+                    .iter()
+                    .enumerate()
+                    .map(|(i, key)| meta(key, message.is_signer(i), message.is_writable(i)))
+                    .collect())
+            }
+        }
+    }
+
+    /// Creates an address lookup table holding `addresses`, `authority` its
+    /// authority and payer, then moves the clock on a slot so they can be
+    /// looked up, where a client would wait for the next one
+    pub fn create_lookup_table(
+        &mut self,
+        authority: &Keypair,
+        addresses: &[Pubkey],
+    ) -> Result<AddressLookupTableAccount, FailedTransaction> {
+        let key = authority.pubkey();
+        let (create, table) = create_lookup_table(key, key, self.clock.slot);
+        self.send(&[create], &[authority])?;
+        // As many as fit in a packet with room to spare
+        for chunk in addresses.chunks(20) {
+            let extend = extend_lookup_table(table, key, Some(key), chunk.to_vec());
+            self.send(&[extend], &[authority])?;
+        }
+        self.clock.slot += 1;
+        Ok(AddressLookupTableAccount {
+            key: table,
+            addresses: addresses.to_vec(),
+        })
+    }
+
+    /// The table at `address` as a transaction sent now would see it:
+    /// only the addresses added before the current slot
+    pub fn lookup_table(
+        &self,
+        address: &Pubkey,
+    ) -> Result<AddressLookupTableAccount, TransactionError> {
+        let account = self
+            .accounts
+            .get(address)
+            .filter(|account| account.owner == lookup_table::ID)
+            .ok_or(TransactionError::AddressLookupTableNotFound)?;
+        let table = AddressLookupTable::deserialize(&account.data)
+            .map_err(|_| TransactionError::InvalidAddressLookupTableData)?;
+        let active = table
+            .get_active_addresses_len(self.clock.slot, &SlotHashes::default())
+            .map_err(|_| TransactionError::AddressLookupTableNotFound)?;
+        Ok(AddressLookupTableAccount {
+            key: *address,
+            addresses: table.addresses[..active].to_vec(),
+        })
+    }
+
+    fn before_send(&mut self, transaction: &VersionedTransaction) {
+        if let Some(mut inspector) = self.inspector.take() {
+            inspector(self, transaction);
+            self.inspector = Some(inspector);
+        }
+    }
+
+    fn record(
+        &mut self,
+        transaction: &VersionedTransaction,
+        result: TransactionResult,
+    ) -> TransactionResult {
+        if let Some(observer) = &mut self.observer {
+            observer(transaction, &result);
+        }
+        self.transactions.push(transaction.clone());
+        self.history.push(result.clone());
+        result
+    }
+
+    fn process(
+        &mut self,
+        instructions: &[CompiledInstruction],
+        metas: &[AccountMeta],
+    ) -> Result<(), TransactionError> {
+        let mut loaded: Vec<Account> = metas
+            .iter()
+            .map(|meta| self.accounts.get(&meta.pubkey).cloned().unwrap_or_default())
+            .collect();
+
+        for (index, compiled) in instructions.iter().enumerate() {
+            let program_id = metas[usize::from(compiled.program_id_index)].pubkey;
+            let positions: Vec<usize> = compiled.accounts.iter().map(|i| usize::from(*i)).collect();
+            let instruction_metas: Vec<AccountMeta> =
+                positions.iter().map(|&i| metas[i].clone()).collect();
+            let mut accounts: Vec<Account> = positions.iter().map(|&i| loaded[i].clone()).collect();
+            invoke::execute(
+                &program_id,
+                &instruction_metas,
+                &mut accounts,
+                &compiled.data,
+            )
+            .map_err(|err| TransactionError::InstructionError(index as u8, err))?;
+            for (position, account) in positions.into_iter().zip(accounts) {
+                loaded[position] = account;
+            }
+        }
+
+        for (index, (meta, account)) in metas.iter().zip(&loaded).enumerate() {
+            if !meta.is_writable {
+                continue;
+            }
+            if account.lamports > 0 && !self.rent.is_exempt(account.lamports, account.data.len()) {
+                let was_rent_paying = self.accounts.get(&meta.pubkey).is_some_and(|before| {
+                    !self.rent.is_exempt(before.lamports, before.data.len())
+                        && before.data.len() == account.data.len()
+                        && account.lamports <= before.lamports
+                });
+                if !was_rent_paying {
+                    return Err(TransactionError::InsufficientFundsForRent {
+                        account_index: index as u8,
+                    });
+                }
+            }
+        }
+
+        for (meta, account) in metas.iter().zip(loaded) {
+            if meta.is_writable {
+                self.set_account(meta.pubkey, account);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The addresses at `indexes` in `table`
+fn resolve(
+    table: &AddressLookupTableAccount,
+    indexes: &[u8],
+) -> Result<Vec<Pubkey>, TransactionError> {
+    indexes
+        .iter()
+        .map(|&index| table.addresses.get(usize::from(index)).copied())
+        .collect::<Option<_>>()
+        .ok_or(TransactionError::InvalidAddressLookupTableIndex)
+}
@@ -0,0 +1,121 @@
+//! The parts of the address lookup table program v0 transactions need:
+//! creating a table and extending it
+//!
+//! Addresses a table is extended with become usable in lookups from the
+//! next slot on, as on chain, so a runtime has to move its clock on before
+//! a transaction can look them up. There is no `SlotHashes` sysvar here; a
+//! table may be created for any slot up to the current one.
+
+use solana_account::Account;
+use solana_address_lookup_table_interface::instruction::{
+    derive_lookup_table_address, ProgramInstruction,
+};
+use solana_address_lookup_table_interface::state::{
+    AddressLookupTable, LookupTableMeta, LOOKUP_TABLE_MAX_ADDRESSES, LOOKUP_TABLE_META_SIZE,
+};
+use solana_program::clock::Slot;
+use solana_program::instruction::{AccountMeta, InstructionError};
+use solana_program::rent::Rent;
+use solana_system_interface::program as system_program;
+
+pub(crate) use solana_address_lookup_table_interface::program::ID;
+
+pub(crate) fn process(
+    metas: &[AccountMeta],
+    accounts: &mut [Account],
+    data: &[u8],
+    rent: &Rent,
+    slot: Slot,
+) -> Result<(), InstructionError> {
+    let instruction: ProgramInstruction =
+        bincode::deserialize(data).map_err(|_| InstructionError::InvalidInstructionData)?;
+    match instruction {
+        ProgramInstruction::CreateLookupTable {
+            recent_slot,
+            bump_seed,
+        } => {
+            if metas.len() < 3 {
+                return Err(InstructionError::NotEnoughAccountKeys);
+            }
+            let (address, bump) = derive_lookup_table_address(&metas[1].pubkey, recent_slot);
+            if recent_slot > slot || metas[0].pubkey != address || bump_seed != bump {
+                return Err(InstructionError::InvalidArgument);
+            }
+            if accounts[0].owner == ID {
+                return Err(InstructionError::AccountAlreadyInitialized);
+            }
+            let mut data = vec![0; LOOKUP_TABLE_META_SIZE];
+            AddressLookupTable::overwrite_meta_data(
+                &mut data,
+                LookupTableMeta::new(metas[1].pubkey),
+            )?;
+            accounts[0].data = data;
+            accounts[0].owner = ID;
+            fund(metas, accounts, rent)
+        }
+        ProgramInstruction::ExtendLookupTable { new_addresses } => {
+            if metas.len() < 2 {
+                return Err(InstructionError::NotEnoughAccountKeys);
+            }
+            if accounts[0].owner != ID {
+                return Err(InstructionError::InvalidAccountOwner);
+            }
+            let table = AddressLookupTable::deserialize(&accounts[0].data)?;
+            if !metas[1].is_signer {
+                return Err(InstructionError::MissingRequiredSignature);
+            }
+            if table.meta.authority != Some(metas[1].pubkey) {
+                return Err(InstructionError::IncorrectAuthority);
+            }
+            let len = table.addresses.len();
+            if new_addresses.is_empty() || len + new_addresses.len() > LOOKUP_TABLE_MAX_ADDRESSES {
+                return Err(InstructionError::InvalidInstructionData);
+            }
+            let mut meta = table.meta;
+            if meta.last_extended_slot != slot {
+                meta.last_extended_slot = slot;
+                meta.last_extended_slot_start_index = len as u8;
+            }
+            AddressLookupTable::overwrite_meta_data(&mut accounts[0].data, meta)?;
+            for address in new_addresses {
+                accounts[0].data.extend_from_slice(address.as_ref());
+            }
+            // Without a payer, the table must already hold its new rent
+            if metas.len() < 3 {
+                if !rent.is_exempt(accounts[0].lamports, accounts[0].data.len()) {
+                    return Err(InstructionError::InsufficientFunds);
+                }
+                return Ok(());
+            }
+            fund(metas, accounts, rent)
+        }
+        _ => Err(InstructionError::InvalidInstructionData),
+    }
+}
+
+/// Tops the table up to rent exemption from the payer, the third account
+fn fund(
+    metas: &[AccountMeta],
+    accounts: &mut [Account],
+    rent: &Rent,
+) -> Result<(), InstructionError> {
+    let payer = &metas[2];
+    if !payer.is_signer {
+        return Err(InstructionError::MissingRequiredSignature);
+    }
+    if !payer.is_writable || !metas[0].is_writable {
+        return Err(InstructionError::ReadonlyLamportChange);
+    }
+    let needed = rent
+        .minimum_balance(accounts[0].data.len())
+        .saturating_sub(accounts[0].lamports);
+    if accounts[2].owner != system_program::ID {
+        return Err(InstructionError::ExternalAccountLamportSpend);
+    }
+    if needed > accounts[2].lamports {
+        return Err(InstructionError::InsufficientFunds);
+    }
+    accounts[2].lamports -= needed;
+    accounts[0].lamports += needed;
+    Ok(())
+}
@@ -0,0 +1,117 @@
+//! What a transaction would cost on chain, as far as native execution can
+//! tell
+//!
+//! The SBF VM charges a compute unit per instruction a program executes;
+//! run natively, those instructions cannot be counted. What can be is what
+//! the runtime charges on top, by the default compute budget: each CPI,
+//! each sysvar read, each builtin invoked. A check that compares two keys
+//! costs a handful of SBF instructions and so nothing here, and neither
+//! does deriving a PDA, which costs 1,500 units per bump tried on chain,
+//! or logging, which costs 100 a line: natively, neither reaches a syscall.
+//!
+//! Heap is counted exactly, by [`CountingAllocator`] while program code is
+//! running. Programs on chain get a 32 KiB bump allocator that never frees,
+//! so every byte they allocate counts, freed or not. Time in program code
+//! is measured too: native timings only compare with each other, but they
+//! are what catches the PDA derivations.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::mem::size_of;
+use std::time::{Duration, Instant};
+
+/// A builtin program's fixed cost, the system program's included
+pub const BUILTIN_UNITS: u64 = 150;
+/// A cross-program invocation, before its instruction data
+pub const INVOKE_UNITS: u64 = 1_000;
+/// Instruction data a CPI passes per compute unit
+pub const CPI_BYTES_PER_UNIT: u64 = 250;
+/// The cheapest syscall
+pub const SYSCALL_BASE_UNITS: u64 = 100;
+
+pub(crate) fn invoke_units(data: &[u8]) -> u64 {
+    INVOKE_UNITS + data.len() as u64 / CPI_BYTES_PER_UNIT
+}
+
+/// Reading a sysvar costs the base plus its size
+pub(crate) fn sysvar_units<T>() -> u64 {
+    SYSCALL_BASE_UNITS + size_of::<T>() as u64
+}
+
+thread_local! {
+    static RUNNING: Cell<bool> = const { Cell::new(false) };
+    static ALLOCATED: Cell<u64> = const { Cell::new(0) };
+    static STARTED: Cell<Option<Instant>> = const { Cell::new(None) };
+    static ELAPSED: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+}
+
+/// The system allocator, counting what programs allocate on the current
+/// thread; install it with `#[global_allocator]` to fill in
+/// [`TransactionMeta::heap_bytes`](crate::TransactionMeta::heap_bytes)
+pub struct CountingAllocator;
+
+// SAFETY: every call goes straight to the system allocator
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        count(layout.size());
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        count(layout.size());
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        // A bump allocator reallocates by allocating again
+        count(new_size);
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+fn count(size: usize) {
+    // Threads being torn down have no counters left; they run no programs
+    let _ = RUNNING.try_with(|running| {
+        if running.get() {
+            let _ = ALLOCATED.try_with(|allocated| allocated.set(allocated.get() + size as u64));
+        }
+    });
+}
+
+/// Bytes counted on this thread so far
+pub(crate) fn allocated() -> u64 {
+    ALLOCATED.with(Cell::get)
+}
+
+/// Time spent in program code on this thread so far
+pub(crate) fn elapsed() -> Duration {
+    ELAPSED.with(Cell::get)
+}
+
+/// Runs `f` with the meter running or not, then puts it back the way it
+/// was: program code is metered, the runtime work its syscalls do is not
+pub(crate) fn running<R>(running: bool, f: impl FnOnce() -> R) -> R {
+    let outer = switch(running);
+    let result = f();
+    switch(outer);
+    result
+}
+
+/// Starts or stops the meter, returning whether it was running
+fn switch(running: bool) -> bool {
+    let was = RUNNING.with(|cell| cell.replace(running));
+    match (was, running) {
+        (false, true) => STARTED.with(|cell| cell.set(Some(Instant::now()))),
+        (true, false) => {
+            if let Some(started) = STARTED.with(Cell::take) {
+                ELAPSED.with(|cell| cell.set(cell.get() + started.elapsed()));
+            }
+        }
+        _ => {}
+    }
+    was
+}
@@ -0,0 +1,120 @@
+//! The parts of the system program the examples and their exploits use
+
+use solana_account::Account;
+use solana_program::instruction::{AccountMeta, InstructionError};
+use solana_program::pubkey::Pubkey;
+use solana_system_interface::error::SystemError;
+use solana_system_interface::instruction::SystemInstruction;
+use solana_system_interface::program as system_program;
+use solana_system_interface::MAX_PERMITTED_DATA_LENGTH;
+
+pub(crate) fn process(
+    metas: &[AccountMeta],
+    accounts: &mut [Account],
+    data: &[u8],
+) -> Result<(), InstructionError> {
+    let instruction: SystemInstruction =
+        bincode::deserialize(data).map_err(|_| InstructionError::InvalidInstructionData)?;
+    match instruction {
+        SystemInstruction::Transfer { lamports } => transfer(metas, accounts, lamports),
+        SystemInstruction::CreateAccount {
+            lamports,
+            space,
+            owner,
+        } => {
+            account(metas, 1)?;
+            if accounts[1].lamports > 0 {
+                return Err(error(SystemError::AccountAlreadyInUse));
+            }
+            allocate(&metas[1], &mut accounts[1], space)?;
+            assign(&metas[1], &mut accounts[1], &owner)?;
+            transfer(metas, accounts, lamports)
+        }
+        SystemInstruction::Assign { owner } => {
+            account(metas, 0)?;
+            assign(&metas[0], &mut accounts[0], &owner)
+        }
+        SystemInstruction::Allocate { space } => {
+            account(metas, 0)?;
+            allocate(&metas[0], &mut accounts[0], space)
+        }
+        _ => Err(InstructionError::InvalidInstructionData),
+    }
+}
+
+fn account(metas: &[AccountMeta], index: usize) -> Result<(), InstructionError> {
+    if index < metas.len() {
+        Ok(())
+    } else {
+        Err(InstructionError::NotEnoughAccountKeys)
+    }
+}
+
+fn error(err: SystemError) -> InstructionError {
+    InstructionError::Custom(err as u32)
+}
+
+fn transfer(
+    metas: &[AccountMeta],
+    accounts: &mut [Account],
+    lamports: u64,
+) -> Result<(), InstructionError> {
+    account(metas, 1)?;
+    let (from, to) = (&metas[0], &metas[1]);
+    if !from.is_signer {
+        return Err(InstructionError::MissingRequiredSignature);
+    }
+    if !accounts[0].data.is_empty() {
+        return Err(InstructionError::InvalidArgument);
+    }
+    if accounts[0].owner != system_program::ID {
+        return Err(InstructionError::ExternalAccountLamportSpend);
+    }
+    if !from.is_writable || !to.is_writable {
+        return Err(InstructionError::ReadonlyLamportChange);
+    }
+    if lamports > accounts[0].lamports {
+        return Err(error(SystemError::ResultWithNegativeLamports));
+    }
+    if from.pubkey == to.pubkey {
+        return Ok(());
+    }
+    accounts[0].lamports -= lamports;
+    accounts[1].lamports = accounts[1]
+        .lamports
+        .checked_add(lamports)
+        .ok_or(InstructionError::ArithmeticOverflow)?;
+    Ok(())
+}
+
+fn allocate(meta: &AccountMeta, account: &mut Account, space: u64) -> Result<(), InstructionError> {
+    if !meta.is_signer {
+        return Err(InstructionError::MissingRequiredSignature);
+    }
+    if !account.data.is_empty() || account.owner != system_program::ID {
+        return Err(error(SystemError::AccountAlreadyInUse));
+    }
+    if space > MAX_PERMITTED_DATA_LENGTH {
+        return Err(error(SystemError::InvalidAccountDataLength));
+    }
+    account.data = vec![0; space as usize];
+    Ok(())
+}
+
+fn assign(
+    meta: &AccountMeta,
+    account: &mut Account,
+    owner: &Pubkey,
+) -> Result<(), InstructionError> {
+    if account.owner == *owner {
+        return Ok(());
+    }
+    if !meta.is_signer {
+        return Err(InstructionError::MissingRequiredSignature);
+    }
+    if account.owner != system_program::ID {
+        return Err(InstructionError::ModifiedProgramId);
+    }
+    account.owner = *owner;
+    Ok(())
+}
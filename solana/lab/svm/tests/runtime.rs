@@ -0,0 +1,322 @@
+//! The account rules exploits rely on the runtime to enforce
+
+use lab_instructions::compute_budget::{set_compute_unit_limit_ix, set_compute_unit_price_ix};
+use lab_svm::meter::{BUILTIN_UNITS, INVOKE_UNITS};
+use lab_svm::{
+    Account, CountingAllocator, Keypair, Signer, Svm, Transaction, TransactionError,
+    LAMPORTS_PER_SOL,
+};
+use solana_program::account_info::AccountInfo;
+use solana_program::entrypoint::ProgramResult;
+use solana_program::instruction::{AccountMeta, Instruction, InstructionError};
+use solana_program::program::invoke;
+use solana_program::pubkey::Pubkey;
+use solana_system_interface::instruction as system_instruction;
+
+/// Moves one lamport from the first account to the second, whoever owns it
+fn take_one(_program_id: &Pubkey, accounts: &[AccountInfo], _data: &[u8]) -> ProgramResult {
+    **accounts[0].try_borrow_mut_lamports()? -= 1;
+    **accounts[1].try_borrow_mut_lamports()? += 1;
+    Ok(())
+}
+
+/// Asks the system program to move one lamport, signing for the source
+fn transfer_one(_program_id: &Pubkey, accounts: &[AccountInfo], _data: &[u8]) -> ProgramResult {
+    invoke(
+        &system_instruction::transfer(accounts[0].key, accounts[1].key, 1),
+        &accounts[..2],
+    )
+}
+
+/// Invokes the program its data starts with, passing the rest of its data
+/// on; with no data left, does nothing
+fn relay(_program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let Some((target, rest)) = data.split_first_chunk::<32>() else {
+        return Ok(());
+    };
+    invoke(
+        &Instruction::new_with_bytes(Pubkey::from(*target), rest, Vec::new()),
+        accounts,
+    )
+}
+
+/// Allocates a kilobyte and frees it again
+fn allocate(_program_id: &Pubkey, _accounts: &[AccountInfo], _data: &[u8]) -> ProgramResult {
+    drop(std::hint::black_box(vec![0u8; 1024]));
+    Ok(())
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn instruction(program_id: Pubkey, from: Pubkey, from_signs: bool, to: Pubkey) -> Instruction {
+    Instruction::new_with_bytes(
+        program_id,
+        &[],
+        vec![
+            AccountMeta::new(from, from_signs),
+            AccountMeta::new(to, false),
+        ],
+    )
+}
+
+fn instruction_error(result: lab_svm::TransactionResult) -> InstructionError {
+    match result.map_err(|failed| failed.err) {
+        Err(TransactionError::InstructionError(0, err)) => err,
+        other => panic!("expected an instruction error, got {other:?}"),
+    }
+}
+
+#[test]
+fn a_program_cannot_debit_an_account_it_does_not_own() {
+    let mut svm = Svm::new();
+    let program_id = svm.add_program(take_one);
+    let payer = Keypair::new();
+    let victim = Keypair::new();
+    svm.airdrop(&payer.pubkey(), LAMPORTS_PER_SOL);
+    svm.airdrop(&victim.pubkey(), LAMPORTS_PER_SOL);
+
+    let result = svm.send(
+        &[instruction(
+            program_id,
+            victim.pubkey(),
+            false,
+            payer.pubkey(),
+        )],
+        &[&payer],
+    );
+    assert_eq!(
+        instruction_error(result),
+        InstructionError::ExternalAccountLamportSpend
+    );
+    assert_eq!(svm.lamports(&victim.pubkey()), LAMPORTS_PER_SOL);
+}
+
+#[test]
+fn a_program_debits_its_own_accounts() {
+    let mut svm = Svm::new();
+    let program_id = svm.add_program(take_one);
+    let payer = Keypair::new();
+    svm.airdrop(&payer.pubkey(), LAMPORTS_PER_SOL);
+    let vault = Pubkey::new_unique();
+    svm.set_account(
+        vault,
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            owner: program_id,
+            ..Account::default()
+        },
+    );
+
+    svm.send(
+        &[instruction(program_id, vault, false, payer.pubkey())],
+        &[&payer],
+    )
+    .expect("the program owns the vault");
+    assert_eq!(svm.lamports(&vault), LAMPORTS_PER_SOL - 1);
+}
+
+#[test]
+fn a_cpi_cannot_sign_for_an_account_that_did_not() {
+    let mut svm = Svm::new();
+    let program_id = svm.add_program(transfer_one);
+    let payer = Keypair::new();
+    let victim = Keypair::new();
+    svm.airdrop(&payer.pubkey(), LAMPORTS_PER_SOL);
+    svm.airdrop(&victim.pubkey(), LAMPORTS_PER_SOL);
+
+    let result = svm.send(
+        &[instruction(
+            program_id,
+            victim.pubkey(),
+            false,
+            payer.pubkey(),
+        )],
+        &[&payer],
+    );
+    assert_eq!(
+        instruction_error(result),
+        InstructionError::PrivilegeEscalation
+    );
+
+    svm.send(
+        &[instruction(
+            program_id,
+            payer.pubkey(),
+            true,
+            victim.pubkey(),
+        )],
+        &[&payer],
+    )
+    .expect("the payer signed");
+    assert_eq!(svm.lamports(&victim.pubkey()), LAMPORTS_PER_SOL + 1);
+}
+
+#[test]
+fn a_program_may_call_itself_but_not_be_called_back() {
+    let mut svm = Svm::new();
+    let first = svm.add_program(relay);
+    let second = svm.add_program(relay);
+    let payer = Keypair::new();
+    svm.airdrop(&payer.pubkey(), LAMPORTS_PER_SOL);
+    let call = |path: &[Pubkey]| {
+        let data: Vec<u8> = path[1..].iter().flat_map(|key| key.to_bytes()).collect();
+        Instruction::new_with_bytes(path[0], &data, Vec::new())
+    };
+
+    svm.send(&[call(&[first, first, first])], &[&payer])
+        .expect("direct recursion is allowed");
+    svm.send(&[call(&[first, second, second])], &[&payer])
+        .expect("the callee may recurse too");
+    let result = svm.send(&[call(&[first, second, first])], &[&payer]);
+    assert_eq!(
+        instruction_error(result),
+        InstructionError::ReentrancyNotAllowed
+    );
+}
+
+#[test]
+fn a_failed_transaction_changes_nothing() {
+    let mut svm = Svm::new();
+    let program_id = svm.add_program(take_one);
+    let payer = Keypair::new();
+    let victim = Keypair::new();
+    svm.airdrop(&payer.pubkey(), LAMPORTS_PER_SOL);
+    svm.airdrop(&victim.pubkey(), LAMPORTS_PER_SOL);
+
+    // The transfer commits only if the instruction after it succeeds
+    let result = svm.send(
+        &[
+            system_instruction::transfer(&payer.pubkey(), &victim.pubkey(), 1_000),
+            instruction(program_id, victim.pubkey(), false, payer.pubkey()),
+        ],
+        &[&payer],
+    );
+    assert!(matches!(
+        result.map_err(|failed| failed.err),
+        Err(TransactionError::InstructionError(1, _))
+    ));
+    assert_eq!(svm.lamports(&payer.pubkey()), LAMPORTS_PER_SOL);
+    assert_eq!(svm.lamports(&victim.pubkey()), LAMPORTS_PER_SOL);
+}
+
+#[test]
+fn simulating_projects_a_transaction_without_committing_it() {
+    let mut svm = Svm::new();
+    let payer = Keypair::new();
+    let recipient = Pubkey::new_unique();
+    svm.airdrop(&payer.pubkey(), LAMPORTS_PER_SOL);
+    svm.airdrop(&recipient, LAMPORTS_PER_SOL);
+    let transfer = |svm: &Svm| {
+        Transaction::new_signed_with_payer(
+            &[system_instruction::transfer(
+                &payer.pubkey(),
+                &recipient,
+                1_000,
+            )],
+            Some(&payer.pubkey()),
+            &[&payer],
+            svm.latest_blockhash(),
+        )
+    };
+
+    let meta = svm.simulate_transaction(&transfer(&svm)).unwrap();
+    assert_eq!(meta.post_balances[1], LAMPORTS_PER_SOL + 1_000);
+    assert_eq!(svm.lamports(&recipient), LAMPORTS_PER_SOL);
+    assert!(svm.history().is_empty());
+
+    // A dry run records what it simulates
+    svm.set_dry_run(true);
+    svm.send_transaction(&transfer(&svm)).unwrap();
+    assert_eq!(svm.lamports(&recipient), LAMPORTS_PER_SOL);
+    assert_eq!(svm.history().len(), 1);
+    assert_eq!(svm.transactions().len(), 1);
+
+    svm.set_dry_run(false);
+    svm.send_transaction(&transfer(&svm)).unwrap();
+    assert_eq!(svm.lamports(&recipient), LAMPORTS_PER_SOL + 1_000);
+}
+
+#[test]
+fn an_account_left_below_rent_exemption_is_rejected() {
+    let mut svm = Svm::new();
+    let payer = Keypair::new();
+    let recipient = Pubkey::new_unique();
+    svm.airdrop(&payer.pubkey(), LAMPORTS_PER_SOL);
+
+    let result = svm.send(
+        &[system_instruction::transfer(&payer.pubkey(), &recipient, 1)],
+        &[&payer],
+    );
+    assert!(matches!(
+        result.map_err(|failed| failed.err),
+        Err(TransactionError::InsufficientFundsForRent { .. })
+    ));
+}
+
+#[test]
+fn the_meter_charges_cpis_and_counts_what_programs_allocate() {
+    let mut svm = Svm::new();
+    let transfer = svm.add_program(transfer_one);
+    let allocates = svm.add_program(allocate);
+    let payer = Keypair::new();
+    let to = Pubkey::new_unique();
+    svm.airdrop(&payer.pubkey(), LAMPORTS_PER_SOL);
+    svm.airdrop(&to, LAMPORTS_PER_SOL);
+
+    let meta = svm
+        .send(
+            &[instruction(transfer, payer.pubkey(), true, to)],
+            &[&payer],
+        )
+        .expect("the payer signed");
+    assert_eq!(meta.compute_units, INVOKE_UNITS + BUILTIN_UNITS);
+
+    let meta = svm
+        .send(
+            &[instruction(allocates, payer.pubkey(), true, to)],
+            &[&payer],
+        )
+        .expect("allocating is allowed");
+    assert_eq!(meta.compute_units, 0);
+    assert!(meta.heap_bytes >= 1024, "{}", meta.heap_bytes);
+    assert!(meta.heap_bytes < 2048, "{}", meta.heap_bytes);
+}
+
+#[test]
+fn a_compute_unit_limit_is_held_against_the_meter() {
+    let mut svm = Svm::new();
+    let transfer = svm.add_program(transfer_one);
+    let payer = Keypair::new();
+    let to = Pubkey::new_unique();
+    svm.airdrop(&payer.pubkey(), LAMPORTS_PER_SOL);
+    svm.airdrop(&to, LAMPORTS_PER_SOL);
+    let budgeted = |limit| {
+        [
+            set_compute_unit_limit_ix(limit),
+            set_compute_unit_price_ix(1_000),
+            instruction(transfer, payer.pubkey(), true, to),
+        ]
+    };
+
+    let meta = svm
+        .send(&budgeted(10_000), &[&payer])
+        .expect("within the limit");
+    let units = 2 * BUILTIN_UNITS + INVOKE_UNITS + BUILTIN_UNITS;
+    assert_eq!(meta.compute_units, units);
+
+    let failed = svm
+        .send(&budgeted(units as u32 - 1), &[&payer])
+        .expect_err("one unit short");
+    assert_eq!(
+        failed.err,
+        TransactionError::InstructionError(2, InstructionError::ComputationalBudgetExceeded)
+    );
+
+    let twice = [
+        set_compute_unit_limit_ix(10_000),
+        set_compute_unit_limit_ix(20_000),
+    ];
+    let failed = svm.send(&twice, &[&payer]).expect_err("given twice");
+    assert_eq!(failed.err, TransactionError::DuplicateInstruction(1));
+}
@@ -0,0 +1,121 @@
+//! v0 transactions: lookups through address lookup tables, and the packet
+//! and account lock limits that make them necessary
+
+use lab_svm::{Account, Keypair, Signer, Svm, TransactionError, LAMPORTS_PER_SOL};
+use solana_address_lookup_table_interface::instruction::{
+    create_lookup_table, extend_lookup_table,
+};
+use solana_program::account_info::AccountInfo;
+use solana_program::entrypoint::ProgramResult;
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+use solana_program::sysvar::Sysvar;
+
+/// Pays each account after the first, which it owns, enough to exist
+fn pay_each(_program_id: &Pubkey, accounts: &[AccountInfo], _data: &[u8]) -> ProgramResult {
+    let amount = Rent::get()?.minimum_balance(0);
+    for recipient in &accounts[1..] {
+        **accounts[0].try_borrow_mut_lamports()? -= amount;
+        **recipient.try_borrow_mut_lamports()? += amount;
+    }
+    Ok(())
+}
+
+/// A runtime with `pay_each` funding a source it owns, a funded payer and
+/// `recipients` fresh addresses
+fn setup(recipients: usize) -> (Svm, Keypair, Instruction) {
+    let mut svm = Svm::new();
+    let program_id = svm.add_program(pay_each);
+    let payer = Keypair::new();
+    svm.airdrop(&payer.pubkey(), LAMPORTS_PER_SOL);
+    let source = Pubkey::new_unique();
+    svm.set_account(
+        source,
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: Vec::new(),
+            owner: program_id,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+    let mut accounts = vec![AccountMeta::new(source, false)];
+    accounts.extend((0..recipients).map(|_| AccountMeta::new(Pubkey::new_unique(), false)));
+    let instruction = Instruction::new_with_bytes(program_id, &[], accounts);
+    (svm, payer, instruction)
+}
+
+fn addresses(instruction: &Instruction) -> Vec<Pubkey> {
+    instruction
+        .accounts
+        .iter()
+        .map(|meta| meta.pubkey)
+        .collect()
+}
+
+fn error(result: lab_svm::TransactionResult) -> Option<TransactionError> {
+    result.err().map(|failed| failed.err)
+}
+
+#[test]
+fn forty_accounts_overflow_a_packet_but_fit_through_a_table() {
+    let (mut svm, payer, instruction) = setup(40);
+
+    let legacy = svm.send(std::slice::from_ref(&instruction), &[&payer]);
+    assert_eq!(error(legacy), Some(TransactionError::SanitizeFailure));
+
+    let table = svm
+        .create_lookup_table(&payer, &addresses(&instruction))
+        .unwrap();
+    svm.send_v0(std::slice::from_ref(&instruction), &[table], &[&payer])
+        .unwrap();
+    let last = instruction.accounts.last().unwrap().pubkey;
+    assert_eq!(svm.lamports(&last), svm.minimum_balance(0));
+    assert_eq!(
+        svm.transactions()
+            .last()
+            .unwrap()
+            .message
+            .static_account_keys()
+            .len(),
+        2,
+        "only the payer and the program are named in full"
+    );
+}
+
+#[test]
+fn a_table_serves_its_addresses_from_the_next_slot_and_locks_still_count() {
+    let (mut svm, payer, instruction) = setup(3);
+    let key = payer.pubkey();
+    let slot = svm.clock().slot;
+    let (create, address) = create_lookup_table(key, key, slot);
+    let extend = extend_lookup_table(address, key, Some(key), addresses(&instruction));
+    svm.send(&[create, extend], &[&payer]).unwrap();
+    let table = lab_svm::AddressLookupTableAccount {
+        key: address,
+        addresses: addresses(&instruction),
+    };
+
+    let early = svm.send_v0(
+        std::slice::from_ref(&instruction),
+        std::slice::from_ref(&table),
+        &[&payer],
+    );
+    assert_eq!(
+        error(early),
+        Some(TransactionError::InvalidAddressLookupTableIndex)
+    );
+    let mut clock = svm.clock().clone();
+    clock.slot += 1;
+    svm.set_clock(clock);
+    svm.send_v0(&[instruction], &[table], &[&payer]).unwrap();
+
+    // 62 recipients, the source, the payer and the program: one too many
+    let (mut svm, payer, instruction) = setup(62);
+    let table = svm
+        .create_lookup_table(&payer, &addresses(&instruction))
+        .unwrap();
+    let locked = svm.send_v0(&[instruction], &[table], &[&payer]);
+    assert_eq!(error(locked), Some(TransactionError::TooManyAccountLocks));
+}
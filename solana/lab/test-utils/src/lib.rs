@@ -0,0 +1,100 @@
+//! Accounts for tests that call a program or a shared helper directly,
+//! with no runtime in between
+//!
+//! An `AccountInfo` only borrows its key, lamports and data, so something
+//! has to own them for as long as the code under test runs. A [`Stored`]
+//! account is that owner, as the runtime would be, and lends itself out
+//! with [`Stored::info`], or several at once with [`infos`].
+//! [`with_account`] and [`with_signer`] are the one-account cases most
+//! helper tests need. Tests that send transactions instead seed the
+//! runtime's accounts with [`rent_exempt`].
+
+use lab_svm::{Account, Svm};
+use solana_program::{account_info::AccountInfo, pubkey::Pubkey};
+
+/// An account as a test holds it, to be lent out as an `AccountInfo`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Stored {
+    pub key: Pubkey,
+    pub owner: Pubkey,
+    pub lamports: u64,
+    pub data: Vec<u8>,
+    pub signer: bool,
+    pub writable: bool,
+}
+
+impl Stored {
+    /// A writable account at a new address, owned by `owner` and holding
+    /// `data` and no lamports, that did not sign
+    pub fn new(owner: Pubkey, data: Vec<u8>) -> Self {
+        Self {
+            key: Pubkey::new_unique(),
+            owner,
+            lamports: 0,
+            data,
+            signer: false,
+            writable: true,
+        }
+    }
+
+    /// An empty system account for `key` that signed
+    pub fn signer(key: Pubkey) -> Self {
+        Self {
+            key,
+            signer: true,
+            ..Self::new(Pubkey::default(), Vec::new())
+        }
+    }
+
+    pub fn info(&mut self) -> AccountInfo<'_> {
+        AccountInfo::new(
+            &self.key,
+            self.signer,
+            self.writable,
+            &mut self.lamports,
+            &mut self.data,
+            &self.owner,
+            false,
+            0,
+        )
+    }
+}
+
+/// Each of `stored`, in order, lent out together
+pub fn infos(stored: &mut [Stored]) -> Vec<AccountInfo<'_>> {
+    stored.iter_mut().map(Stored::info).collect()
+}
+
+/// Runs `run` on an account owned by `owner` holding `data`
+pub fn with_account<R>(owner: &Pubkey, data: Vec<u8>, run: impl FnOnce(&AccountInfo) -> R) -> R {
+    run(&Stored::new(*owner, data).info())
+}
+
+/// Runs `run` on an account for `key`, signed or not
+pub fn with_signer<R>(key: &Pubkey, signed: bool, run: impl FnOnce(&AccountInfo) -> R) -> R {
+    let mut stored = Stored {
+        signer: signed,
+        ..Stored::signer(*key)
+    };
+    run(&stored.info())
+}
+
+/// Stores a rent-exempt account at `key` in `svm`, owned by `owner`,
+/// holding `data` and `lamports` on top of the rent-exempt minimum
+pub fn rent_exempt(
+    svm: &mut Svm,
+    key: Pubkey,
+    owner: &Pubkey,
+    data: Vec<u8>,
+    lamports: u64,
+) -> Pubkey {
+    let account = Account {
+        lamports: svm.minimum_balance(data.len()) + lamports,
+        data,
+        owner: *owner,
+        executable: false,
+        rent_epoch: 0,
+    };
+    svm.set_account(key, account);
+    key
+}
@@ -0,0 +1,380 @@
+//! Exploit runs read back from their transaction logs: which of the secure
+//! version's checks each attack transaction got past
+//!
+//! [`parse`], the client's, turns a transaction's log lines into the tree
+//! of program invocations they describe, with the events each emitted.
+//! [`analyze`] runs an exploit against both versions and lines up their
+//! attack transactions, those sent after the flag is planted, one for one.
+//! Where the secure version fails a transaction the vulnerable one let
+//! through, the `// CHECK:` annotations in its source that return that
+//! error are the checks the vulnerable version lacks, and each comes with
+//! the `// VULNERABILITY:` note on the same handler, so the story the
+//! annotations tell is tied to the transactions that act it out.
+
+use std::collections::HashSet;
+use std::fmt::Write as _;
+
+use anyhow::Result as AnyResult;
+use lab_errors::{AccessError, ArithmeticError, LabError, StateError, ValidationError};
+use lab_svm::TransactionResult;
+use solana_program::instruction::InstructionError;
+use solana_program::program_error::ProgramError;
+
+pub use lab_client::logs::{parse, Invocation, Outcome};
+
+use crate::ctf::VERIFIER;
+use crate::walkthrough::{self, Annotation, Kind, Source};
+use crate::{programs, Harness, Version, Vulnerability};
+
+fn logs(result: &TransactionResult) -> &[String] {
+    match result {
+        Ok(meta) => &meta.logs,
+        Err(failed) => &failed.meta.logs,
+    }
+}
+
+/// A secure-version check, with what the vulnerable version does in its
+/// place
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Check {
+    pub fix: Annotation,
+    /// The `// VULNERABILITY:` note on the same handler, if there is one
+    pub vuln: Option<Annotation>,
+}
+
+/// What the secure version did with an attack transaction
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    /// It went through there as well
+    Allowed,
+    /// It failed with `error`, which only `checks` return; more than one
+    /// when several checks fail with the same error
+    Blocked { error: String, checks: Vec<Check> },
+    /// The exploit gave up before sending it
+    NotSent,
+}
+
+/// One transaction of the attack against the vulnerable version
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Step {
+    /// Its index in the run's transactions
+    pub index: usize,
+    pub invocations: Vec<Invocation>,
+    pub verdict: Verdict,
+}
+
+/// An exploit run told transaction by transaction
+#[derive(Clone, Debug)]
+pub struct Analysis {
+    pub id: &'static str,
+    pub example: &'static str,
+    pub description: &'static str,
+    pub steps: Vec<Step>,
+    /// When the secure version fails none of the attack's transactions but
+    /// the exploit still comes away empty-handed, all its checks: any of
+    /// them may be why
+    pub unattributed: Vec<Check>,
+    pub vulnerable: Source,
+    pub secure: Source,
+}
+
+/// Where the attack starts in a run's transactions: after the one that
+/// plants the flag
+fn attack(history: &[TransactionResult]) -> Option<usize> {
+    history
+        .iter()
+        .position(|result| {
+            parse(logs(result))
+                .iter()
+                .any(|invocation| invocation.program_id == VERIFIER)
+        })
+        .map(|plant| plant + 1)
+}
+
+/// Whether only the verifier runs: a flag being captured
+fn capture(invocations: &[Invocation]) -> bool {
+    invocations
+        .iter()
+        .all(|invocation| invocation.program_id == VERIFIER)
+}
+
+/// Runs `vulnerability`'s exploit against both versions and tells its
+/// attack from their logs
+pub fn analyze(vulnerability: &dyn Vulnerability) -> AnyResult<Analysis> {
+    let example = vulnerability.example();
+    let run = |version| {
+        let mut harness = Harness::new(version);
+        let _ = vulnerability.exploit(&mut harness);
+        harness.history().to_vec()
+    };
+    let vulnerable_run = run(Version::Vulnerable);
+    let secure_run = run(Version::Secure);
+
+    let vulnerable = Source::load(&programs::source(example, Version::Vulnerable))?;
+    let secure = Source::load(&programs::source(example, Version::Secure))?;
+    let checks = |error: Option<&str>| -> Vec<Check> {
+        secure
+            .annotations
+            .iter()
+            .filter(|fix| fix.kind == Kind::Check)
+            .filter(|fix| error.is_none_or(|error| returns(&secure, fix, error)))
+            .map(|fix| Check {
+                fix: fix.clone(),
+                vuln: counterpart(&vulnerable, &secure, fix).cloned(),
+            })
+            .collect()
+    };
+
+    let vulnerable_attack = attack(&vulnerable_run).unwrap_or(vulnerable_run.len());
+    let secure_attack = attack(&secure_run).map(|start| &secure_run[start..]);
+    let mut steps = Vec::new();
+    for (offset, result) in vulnerable_run[vulnerable_attack..].iter().enumerate() {
+        let invocations = parse(logs(result));
+        if capture(&invocations) {
+            continue;
+        }
+        let verdict = match secure_attack.and_then(|attack| attack.get(offset)) {
+            None => Verdict::NotSent,
+            Some(Ok(_)) => Verdict::Allowed,
+            Some(Err(failed)) => {
+                let error = parse(&failed.meta.logs)
+                    .into_iter()
+                    .find_map(|invocation| match invocation.outcome {
+                        Outcome::Failed(error) => Some(error),
+                        _ => None,
+                    })
+                    .unwrap_or_else(|| failed.err.to_string());
+                let checks = checks(Some(&error));
+                Verdict::Blocked { error, checks }
+            }
+        };
+        steps.push(Step {
+            index: vulnerable_attack + offset,
+            invocations,
+            verdict,
+        });
+    }
+    let blocked = steps
+        .iter()
+        .any(|step| matches!(step.verdict, Verdict::Blocked { .. }));
+    let unattributed = if blocked { Vec::new() } else { checks(None) };
+
+    Ok(Analysis {
+        id: vulnerability.id(),
+        example,
+        description: vulnerability.description(),
+        steps,
+        unattributed,
+        vulnerable,
+        secure,
+    })
+}
+
+/// The `// VULNERABILITY:` note on `fix`'s handler in the vulnerable
+/// version that shares the most words with it, the first of them on a tie
+fn counterpart<'a>(
+    vulnerable: &'a Source,
+    secure: &Source,
+    fix: &Annotation,
+) -> Option<&'a Annotation> {
+    let fix_words = words(secure, fix);
+    let mut best: Option<(&Annotation, usize)> = None;
+    for vuln in vulnerable.annotations.iter().filter(|vuln| {
+        vuln.kind == Kind::Vulnerability && walkthrough::counterparts(&vuln.function, &fix.function)
+    }) {
+        let shared = words(vulnerable, vuln).intersection(&fix_words).count();
+        if best.is_none_or(|(_, most)| shared > most) {
+            best = Some((vuln, shared));
+        }
+    }
+    best.map(|(vuln, _)| vuln)
+}
+
+/// The identifiers and longer words in an annotation and its code
+fn words(source: &Source, annotation: &Annotation) -> HashSet<String> {
+    source
+        .excerpt(annotation)
+        .iter()
+        .flat_map(|(_, line)| line.split(|c: char| !c.is_ascii_alphanumeric() && c != '_'))
+        .filter(|word| word.len() >= 4)
+        .map(str::to_ascii_lowercase)
+        .collect()
+}
+
+/// `solana_common`'s checked and fixed-point math, its strict, discriminator-checked and
+/// versioned reads, `load`, its PDA checks and its access-control guards,
+/// with the errors each fails with
+const CHECKED: [(&str, LabError); 30] = [
+    ("CheckedU64(", OVERFLOW),
+    ("CheckedU128(", OVERFLOW),
+    ("mul_div_floor(", OVERFLOW),
+    ("mul_div_floor(", DIVISION_BY_ZERO),
+    ("mul_div_ceil(", OVERFLOW),
+    ("mul_div_ceil(", DIVISION_BY_ZERO),
+    ("U64F64::from_ratio(", OVERFLOW),
+    ("U64F64::from_ratio(", DIVISION_BY_ZERO),
+    (".mul_int(", OVERFLOW),
+    ("strict_deserialize::<", MALFORMED),
+    ("try_deserialize_checked(", TOO_SMALL),
+    ("try_deserialize_checked(", WRONG_TYPE),
+    ("try_deserialize_checked(", MALFORMED),
+    ("read_versioned::<", WRONG_VERSION),
+    ("read_versioned::<", MALFORMED),
+    ("load::<", LabError::Validation(ValidationError::WrongOwner)),
+    ("load::<", TOO_SMALL),
+    ("load::<", WRONG_TYPE),
+    ("load::<", MALFORMED),
+    ("find_and_validate(", INVALID_PDA),
+    ("verify_stored_bump(", INVALID_PDA),
+    ("only_role(", MISSING_SIGNATURE),
+    ("only_role(", KEY_MISMATCH),
+    ("only_role(", LabError::Access(AccessError::MissingRole)),
+    (".only_authority(", MISSING_SIGNATURE),
+    (".only_authority(", KEY_MISMATCH),
+    (".propose(", MISSING_SIGNATURE),
+    (".propose(", KEY_MISMATCH),
+    (
+        ".accept(",
+        LabError::Access(AccessError::NoPendingAuthority),
+    ),
+    (
+        ".accept(",
+        LabError::Access(AccessError::NotPendingAuthority),
+    ),
+];
+const OVERFLOW: LabError = LabError::Arithmetic(ArithmeticError::Overflow);
+const DIVISION_BY_ZERO: LabError = LabError::Arithmetic(ArithmeticError::DivisionByZero);
+const TOO_SMALL: LabError = LabError::State(StateError::DataTooSmall);
+const WRONG_TYPE: LabError = LabError::State(StateError::WrongAccountType);
+const MALFORMED: LabError = LabError::State(StateError::MalformedData);
+const WRONG_VERSION: LabError = LabError::State(StateError::WrongVersion);
+const INVALID_PDA: LabError = LabError::Validation(ValidationError::InvalidPda);
+const MISSING_SIGNATURE: LabError = LabError::Validation(ValidationError::MissingSignature);
+const KEY_MISMATCH: LabError = LabError::Validation(ValidationError::KeyMismatch);
+
+/// Whether `fix`'s code returns a `ProgramError` logged as `error`: by
+/// name, through one of `solana_common::validation`'s helpers, from
+/// checked math, from a strict, discriminator-checked or versioned read or
+/// a load or from a PDA check
+fn returns(source: &Source, fix: &Annotation, error: &str) -> bool {
+    let logged = |program_error: ProgramError| {
+        InstructionError::from(u64::from(program_error)).to_string() == error
+    };
+    source.excerpt(fix).iter().any(|(_, line)| {
+        let named = line.split("ProgramError::").skip(1).any(|rest| {
+            let name: String = rest
+                .chars()
+                .take_while(|c| c.is_ascii_alphanumeric())
+                .collect();
+            program_error(&name).is_some_and(logged)
+        });
+        named
+            || ValidationError::ALL.into_iter().any(|validation| {
+                line.contains(&format!("{}(", validation.assertion())) && logged(validation.into())
+            })
+            || CHECKED
+                .iter()
+                .any(|(checked, error)| line.contains(checked) && logged((*error).into()))
+    })
+}
+
+/// A `ProgramError` without data, by name
+fn program_error(name: &str) -> Option<ProgramError> {
+    Some(match name {
+        "InvalidArgument" => ProgramError::InvalidArgument,
+        "InvalidInstructionData" => ProgramError::InvalidInstructionData,
+        "InvalidAccountData" => ProgramError::InvalidAccountData,
+        "AccountDataTooSmall" => ProgramError::AccountDataTooSmall,
+        "InsufficientFunds" => ProgramError::InsufficientFunds,
+        "IncorrectProgramId" => ProgramError::IncorrectProgramId,
+        "MissingRequiredSignature" => ProgramError::MissingRequiredSignature,
+        "AccountAlreadyInitialized" => ProgramError::AccountAlreadyInitialized,
+        "UninitializedAccount" => ProgramError::UninitializedAccount,
+        "NotEnoughAccountKeys" => ProgramError::NotEnoughAccountKeys,
+        "AccountBorrowFailed" => ProgramError::AccountBorrowFailed,
+        "InvalidSeeds" => ProgramError::InvalidSeeds,
+        "AccountNotRentExempt" => ProgramError::AccountNotRentExempt,
+        "IllegalOwner" => ProgramError::IllegalOwner,
+        "Immutable" => ProgramError::Immutable,
+        "IncorrectAuthority" => ProgramError::IncorrectAuthority,
+        "ArithmeticOverflow" => ProgramError::ArithmeticOverflow,
+        _ => return None,
+    })
+}
+
+fn check(out: &mut String, analysis: &Analysis, check: &Check) {
+    let _ = writeln!(
+        out,
+        "    missing check ({}): {}",
+        walkthrough::location(&analysis.secure, &check.fix),
+        check.fix.text
+    );
+    if let Some(vuln) = &check.vuln {
+        let _ = writeln!(
+            out,
+            "      where the vulnerable version ({}): {}",
+            walkthrough::location(&analysis.vulnerable, vuln),
+            vuln.text
+        );
+    }
+}
+
+fn invocation(out: &mut String, invocation: &Invocation) {
+    let outcome = match &invocation.outcome {
+        Outcome::Succeeded => "succeeded".to_string(),
+        Outcome::Failed(error) => format!("failed: {error}"),
+        Outcome::Unfinished => "did not finish".to_string(),
+    };
+    let indent = "  ".repeat(invocation.depth);
+    let _ = writeln!(out, "{indent}{} {outcome}", invocation.program_id);
+    for line in &invocation.logs {
+        let _ = writeln!(out, "{indent}  log: {line}");
+    }
+    for inner in &invocation.inner {
+        self::invocation(out, inner);
+    }
+}
+
+/// Plain text, a paragraph per attack transaction
+pub fn terminal(analysis: &Analysis) -> String {
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "{} {}: {}",
+        analysis.id, analysis.example, analysis.description
+    );
+    for step in &analysis.steps {
+        let _ = writeln!(out, "\ntransaction {}:", step.index);
+        for invocation in &step.invocations {
+            self::invocation(&mut out, invocation);
+        }
+        match &step.verdict {
+            Verdict::Allowed => {
+                let _ = writeln!(out, "  the secure version lets it through as well");
+            }
+            Verdict::NotSent => {
+                let _ = writeln!(out, "  never sent to the secure version");
+            }
+            Verdict::Blocked { error, checks } => {
+                let _ = writeln!(out, "  the secure version fails it: {error}");
+                if checks.len() > 1 {
+                    let _ = writeln!(out, "    with one of these checks:");
+                }
+                for check in checks {
+                    self::check(&mut out, analysis, check);
+                }
+            }
+        }
+    }
+    if !analysis.unattributed.is_empty() {
+        let _ = writeln!(
+            out,
+            "\nthe secure version fails no transaction but leaves nothing to \
+             take; its checks:"
+        );
+        for check in &analysis.unattributed {
+            self::check(&mut out, analysis, check);
+        }
+    }
+    out
+}
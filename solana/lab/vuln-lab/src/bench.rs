@@ -0,0 +1,96 @@
+//! What the secure versions' checks cost
+//!
+//! [`compare`] runs an example's honest use (see
+//! [`Vulnerability::honest_use`]) against both builds, so every check the
+//! secure one adds runs and passes, and totals the heap and time the
+//! transactions used. Heap is only counted in a binary whose global
+//! allocator is a [`lab_svm::CountingAllocator`], as the `bench` binary's
+//! is, and time is native and so only comparable with itself. Compute
+//! units are left out: the programs run natively, so no count of them
+//! would include their own instructions.
+
+use std::io;
+use std::time::Duration;
+
+use anyhow::{bail, Result as AnyResult};
+
+use crate::{Harness, Version, Vulnerability};
+
+/// What one build used over an honest use
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Usage {
+    pub transactions: usize,
+    pub heap_bytes: u64,
+    pub program_time: Duration,
+}
+
+/// One example's honest use, against each build
+#[derive(Clone, Debug)]
+pub struct Comparison {
+    pub example: &'static str,
+    pub vulnerable: Usage,
+    pub secure: Usage,
+}
+
+impl Comparison {
+    /// Heap bytes the secure build allocates over the vulnerable one
+    pub fn heap_bytes(&self) -> i64 {
+        self.secure.heap_bytes as i64 - self.vulnerable.heap_bytes as i64
+    }
+}
+
+/// Runs the honest use against one build; `None` if the example has none
+pub fn measure(vulnerability: &dyn Vulnerability, version: Version) -> AnyResult<Option<Usage>> {
+    // Natively, msg! prints; its buffer is set up on first use, which
+    // should not be charged to whichever program logs first
+    let _ = io::stdout();
+    let mut harness = Harness::new(version);
+    let Some(result) = vulnerability.honest_use(&mut harness) else {
+        return Ok(None);
+    };
+    if let Err(err) = result {
+        bail!(
+            "honest use of {} failed against the {version:?} build: {err:#}",
+            vulnerability.example()
+        );
+    }
+
+    let mut usage = Usage::default();
+    for meta in harness.history().iter().flatten() {
+        usage.transactions += 1;
+        usage.heap_bytes += meta.heap_bytes;
+        usage.program_time += meta.program_time;
+    }
+    Ok(Some(usage))
+}
+
+/// Both builds' usage over `runs` runs each, keeping the median time;
+/// `None` if the example has no honest use
+pub fn compare(vulnerability: &dyn Vulnerability, runs: usize) -> AnyResult<Option<Comparison>> {
+    let vulnerable = median(vulnerability, Version::Vulnerable, runs)?;
+    let secure = median(vulnerability, Version::Secure, runs)?;
+    Ok(vulnerable
+        .zip(secure)
+        .map(|(vulnerable, secure)| Comparison {
+            example: vulnerability.example(),
+            vulnerable,
+            secure,
+        }))
+}
+
+/// Everything but the time comes out the same on every run
+fn median(
+    vulnerability: &dyn Vulnerability,
+    version: Version,
+    runs: usize,
+) -> AnyResult<Option<Usage>> {
+    let mut usages = Vec::new();
+    for _ in 0..runs.max(1) {
+        match measure(vulnerability, version)? {
+            Some(usage) => usages.push(usage),
+            None => return Ok(None),
+        }
+    }
+    usages.sort_by_key(|usage| usage.program_time);
+    Ok(Some(usages[usages.len() / 2]))
+}
@@ -0,0 +1,129 @@
+//! bench: what the secure examples' checks cost
+//!
+//! ```bash
+//! bench [example] [--runs N]
+//! ```
+//!
+//! Runs each example's honest use against its vulnerable and secure builds
+//! and compares the heap and time in program code they used (see
+//! [`vuln_lab::bench`] for what is and is not counted). The programs'
+//! `msg!` lines, which print natively, are kept out of the table.
+
+use std::fs::File;
+use std::io::{self, Write};
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::process::ExitCode;
+use std::time::Duration;
+
+use anyhow::{Context, Result as AnyResult};
+use clap::Parser;
+use lab_svm::CountingAllocator;
+use vuln_lab::bench::{self, Comparison};
+use vuln_lab::{Vulnerability, REGISTRY};
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[derive(Parser)]
+#[command(
+    name = "bench",
+    about = "Compare what the vulnerable and secure examples cost to run"
+)]
+struct Cli {
+    /// Only this example (default: all of them)
+    example: Option<String>,
+    /// Runs per build; the median time is reported
+    #[arg(long, default_value_t = 25)]
+    runs: usize,
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err:#}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(cli: Cli) -> AnyResult<()> {
+    let vulnerabilities: Vec<&dyn Vulnerability> = match &cli.example {
+        Some(name) => vec![REGISTRY
+            .find(name)
+            .with_context(|| format!("no example {name}; see `vuln-lab list`"))?],
+        None => REGISTRY.iter().collect(),
+    };
+    let mut comparisons = Vec::new();
+    {
+        let _quiet = Silenced::stdout()?;
+        for vulnerability in vulnerabilities {
+            match bench::compare(vulnerability, cli.runs)? {
+                Some(comparison) => comparisons.push(comparison),
+                None => eprintln!("{}: nothing to compare", vulnerability.example()),
+            }
+        }
+    }
+
+    print_table(&comparisons);
+    Ok(())
+}
+
+/// Stdout pointed at /dev/null until dropped
+struct Silenced {
+    saved: OwnedFd,
+}
+
+impl Silenced {
+    fn stdout() -> AnyResult<Self> {
+        io::stdout().flush()?;
+        let null = File::create("/dev/null").context("opening /dev/null")?;
+        // SAFETY: dup only reads the descriptor
+        let saved = unsafe { libc::dup(libc::STDOUT_FILENO) };
+        if saved < 0 {
+            return Err(io::Error::last_os_error()).context("saving stdout");
+        }
+        // SAFETY: the copy dup made is open and no one else holds it
+        let saved = unsafe { OwnedFd::from_raw_fd(saved) };
+        // SAFETY: both descriptors are open for as long as the call runs
+        if unsafe { libc::dup2(null.as_raw_fd(), libc::STDOUT_FILENO) } < 0 {
+            return Err(io::Error::last_os_error()).context("silencing stdout");
+        }
+        Ok(Self { saved })
+    }
+}
+
+impl Drop for Silenced {
+    fn drop(&mut self) {
+        let _ = io::stdout().flush();
+        // SAFETY: as in `stdout`; the saved descriptor is still open
+        unsafe { libc::dup2(self.saved.as_raw_fd(), libc::STDOUT_FILENO) };
+    }
+}
+
+fn print_table(comparisons: &[Comparison]) {
+    println!(
+        "{:<24} {:>9} {:>9} {:>6}   {:>8} {:>8}",
+        "example", "heap vuln", "heap sec", "Δ", "µs vuln", "µs sec"
+    );
+    for comparison in comparisons {
+        println!(
+            "{:<24} {:>9} {:>9} {:>+6}   {:>8.1} {:>8.1}",
+            comparison.example,
+            comparison.vulnerable.heap_bytes,
+            comparison.secure.heap_bytes,
+            comparison.heap_bytes(),
+            micros(comparison.vulnerable.program_time),
+            micros(comparison.secure.program_time),
+        );
+    }
+    println!(
+        "\nHeap is every byte the programs allocated, as the on-chain bump allocator \
+         would count it.\nTime is spent in program code, natively, and only compares \
+         with itself."
+    );
+}
+
+fn micros(time: Duration) -> f64 {
+    time.as_secs_f64() * 1e6
+}
@@ -0,0 +1,82 @@
+//! walkthrough: each example's bugs and fixes, from their annotations
+//!
+//! ```bash
+//! walkthrough [example] [--format terminal|html] [--output FILE]
+//! ```
+//!
+//! Reads the `// VULNERABILITY:` annotations in each example and the
+//! `// CHECK:` annotations in its secure version and prints them as
+//! ordered steps, each with the source lines it points at (see
+//! [`vuln_lab::walkthrough`]).
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use anyhow::{Context, Result as AnyResult};
+use clap::{Parser, ValueEnum};
+use vuln_lab::walkthrough::{self, Walkthrough};
+use vuln_lab::{Vulnerability, REGISTRY};
+
+#[derive(Parser)]
+#[command(
+    name = "walkthrough",
+    about = "Walk through each example's bugs and fixes, generated from its annotations"
+)]
+struct Cli {
+    /// Only this example (default: all of them, by id)
+    example: Option<String>,
+    #[arg(long, value_enum, default_value_t = Format::Terminal)]
+    format: Format,
+    /// Write here instead of stdout
+    #[arg(long, short)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Terminal,
+    Html,
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err:#}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(cli: Cli) -> AnyResult<()> {
+    let mut vulnerabilities: Vec<&dyn Vulnerability> = match &cli.example {
+        Some(name) => vec![REGISTRY
+            .find(name)
+            .with_context(|| format!("no example {name}; see `vuln-lab list`"))?],
+        None => REGISTRY.iter().collect(),
+    };
+    vulnerabilities.sort_by_key(|vulnerability| vulnerability.id());
+    let walkthroughs = vulnerabilities
+        .into_iter()
+        .map(walkthrough::walkthrough)
+        .collect::<AnyResult<Vec<Walkthrough>>>()?;
+
+    let rendered = match cli.format {
+        Format::Terminal => walkthroughs
+            .iter()
+            .map(walkthrough::terminal)
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Format::Html => walkthrough::html(&walkthroughs),
+    };
+    match cli.output {
+        Some(path) => {
+            fs::write(&path, rendered).with_context(|| format!("writing {}", path.display()))
+        }
+        None => {
+            print!("{rendered}");
+            Ok(())
+        }
+    }
+}
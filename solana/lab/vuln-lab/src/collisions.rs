@@ -0,0 +1,403 @@
+//! PDA seed collisions: two different seed tuples that derive the same
+//! address
+//!
+//! A PDA is the hash of its seeds laid end to end, then its bump, the
+//! program id and a marker. Where one seed ends and the next begins is not
+//! hashed, so two kinds of PDA whose seeds can lay out the same bytes
+//! derive the same address, and one can be passed where the other is
+//! expected. A literal prefix that another kind's prefix and key could
+//! spell, or a seed the caller chooses the length of, is enough.
+//!
+//! Each [`Namespace`] is one kind of PDA a program derives, with the shape
+//! of its seeds. [`ambiguous`] decides from two shapes whether any filling
+//! of them lays out the same bytes, and if so derives both to show it.
+//! [`collide`] is the fuzz target's side: it decodes the fuzzer's bytes
+//! into two namespaces of one program and their seeds, and reports a
+//! [`Collision`] if both derive the same address from different seeds.
+//! A collision serializes to JSON, so a report or a test reads it back
+//! rather than parsing a message.
+//!
+//! The namespaces here, in [`namespaces`], are every PDA the examples in
+//! this tree derive: a literal prefix then fixed-width keys, or
+//! `pda_issues`' vault with the caller's bump. None of them can collide.
+//! The seed-injection and seed-ambiguity examples, whose seeds a caller
+//! writes and sizes, are not in this tree yet; [`Seed::Bytes`] is there
+//! for them.
+
+use std::fmt;
+
+use serde::Serialize;
+use solana_common::pda::PdaSpec;
+use solana_program::pubkey::{Pubkey, MAX_SEED_LEN};
+
+use crate::programs;
+
+/// One seed of a [`Namespace`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Seed {
+    /// The same bytes every time
+    Literal(&'static [u8]),
+    /// A 32-byte address
+    Key,
+    /// Bytes the caller chooses, up to this many
+    Bytes(usize),
+    /// A bump the caller chooses, rather than the canonical one; always
+    /// the last seed
+    Bump,
+}
+
+/// One kind of PDA a program derives
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Namespace {
+    /// Whose program derives it; both builds deploy to the same id
+    pub example: &'static str,
+    pub name: &'static str,
+    /// In order. Without a trailing [`Seed::Bump`], the address is the one
+    /// at the canonical bump
+    pub seeds: Vec<Seed>,
+}
+
+impl Namespace {
+    pub fn new(example: &'static str, name: &'static str, seeds: Vec<Seed>) -> Self {
+        Self {
+            example,
+            name,
+            seeds,
+        }
+    }
+
+    /// The namespace a [`PdaSpec`] declares
+    pub fn spec<const N: usize>(
+        example: &'static str,
+        name: &'static str,
+        spec: &PdaSpec<N>,
+    ) -> Self {
+        let mut seeds = vec![Seed::Literal(spec.prefix)];
+        seeds.extend([Seed::Key; N]);
+        Self::new(example, name, seeds)
+    }
+
+    fn bumped(&self) -> bool {
+        self.seeds.last() == Some(&Seed::Bump)
+    }
+
+    /// The address `seeds` derive in this namespace, with the bump last,
+    /// if they derive one
+    fn derive(&self, seeds: &[Vec<u8>], program_id: &Pubkey) -> Option<Pubkey> {
+        let seeds: Vec<&[u8]> = seeds.iter().map(Vec::as_slice).collect();
+        let (last, rest) = seeds.split_last()?;
+        if self.bumped() {
+            return Pubkey::create_program_address(&seeds, program_id).ok();
+        }
+        let (address, bump) = Pubkey::find_program_address(rest, program_id);
+        (*last == [bump]).then_some(address)
+    }
+
+    /// `seeds`, bump included, with the canonical bump where the namespace
+    /// takes no other
+    fn fill(&self, mut seeds: Vec<Vec<u8>>, program_id: &Pubkey) -> Vec<Vec<u8>> {
+        if !self.bumped() {
+            let unbumped: Vec<&[u8]> = seeds.iter().map(Vec::as_slice).collect();
+            let (_, bump) = Pubkey::find_program_address(&unbumped, program_id);
+            seeds.push(vec![bump]);
+        }
+        seeds
+    }
+}
+
+/// Every kind of PDA the examples in this tree derive
+pub fn namespaces() -> Vec<Namespace> {
+    vec![
+        Namespace::spec(
+            "account_data_matching",
+            "TOKEN_AUTHORITY",
+            &account_data_matching_secure::TOKEN_AUTHORITY,
+        ),
+        Namespace::spec(
+            "account_versioning",
+            "VAULT",
+            &account_versioning_secure::VAULT,
+        ),
+        Namespace::new(
+            "pda_issues",
+            "vault with the caller's bump",
+            vec![Seed::Literal(b"vault"), Seed::Bump],
+        ),
+        Namespace::spec("pda_issues", "USER_DATA", &pda_issues_secure::USER_DATA),
+        Namespace::spec("pda_issues", "VAULT", &pda_issues_secure::VAULT),
+        // The exploit's, laid out as user data
+        Namespace::new(
+            "pda_issues",
+            "ESCROW",
+            vec![Seed::Literal(b"escrow"), Seed::Key, Seed::Key],
+        ),
+    ]
+}
+
+/// The seeds one namespace derived an address from
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Derivation {
+    pub namespace: String,
+    /// Hex, one string per seed, the bump last
+    pub seeds: Vec<String>,
+}
+
+/// Two namespaces deriving one address from different seeds
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Collision {
+    pub example: String,
+    pub program_id: String,
+    pub address: String,
+    pub derivations: [Derivation; 2],
+}
+
+impl Collision {
+    fn new(
+        program_id: &Pubkey,
+        address: Pubkey,
+        derivations: [(&Namespace, &[Vec<u8>]); 2],
+    ) -> Self {
+        Self {
+            example: derivations[0].0.example.to_string(),
+            program_id: program_id.to_string(),
+            address: address.to_string(),
+            derivations: derivations.map(|(namespace, seeds)| Derivation {
+                namespace: namespace.name.to_string(),
+                seeds: seeds.iter().map(|seed| hex(seed)).collect(),
+            }),
+        }
+    }
+
+    /// One line of JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("a collision serializes")
+    }
+}
+
+impl fmt::Display for Collision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}: {} derived twice", self.example, self.address)?;
+        for derivation in &self.derivations {
+            writeln!(
+                f,
+                "  {}: [{}]",
+                derivation.namespace,
+                derivation.seeds.join(", ")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// What the `pda_collisions` fuzz target runs: the first byte picks an
+/// example with more than one namespace, the rest goes to [`collide`]
+pub fn fuzz(input: &[u8]) -> Option<Collision> {
+    let namespaces = namespaces();
+    let mut examples: Vec<&str> = namespaces
+        .iter()
+        .map(|namespace| namespace.example)
+        .collect();
+    examples.dedup();
+    examples.retain(|example| {
+        namespaces
+            .iter()
+            .filter(|namespace| namespace.example == *example)
+            .count()
+            > 1
+    });
+    let (pick, input) = input.split_first()?;
+    let example = examples[usize::from(*pick) % examples.len()];
+    let namespaces: Vec<Namespace> = namespaces
+        .into_iter()
+        .filter(|namespace| namespace.example == example)
+        .collect();
+    let program_id = programs::id(example)?;
+    collide(&namespaces, &program_id, input)
+}
+
+/// The collision `input` decodes to, if it is one: a byte for each of two
+/// of `namespaces`, then each one's seeds in turn, 32 bytes for a key, a
+/// length byte then that many for caller-chosen bytes and one for a bump.
+/// Seeds that derive no address, or the same seeds twice, are not a
+/// collision
+pub fn collide(namespaces: &[Namespace], program_id: &Pubkey, input: &[u8]) -> Option<Collision> {
+    let mut input = input.iter().copied();
+    let mut next = || input.next().unwrap_or(0);
+    let [first, second] =
+        [next(), next()].map(|pick| &namespaces[usize::from(pick) % namespaces.len()]);
+    let [first_seeds, second_seeds] = [first, second].map(|namespace| {
+        let seeds = namespace
+            .seeds
+            .iter()
+            .filter(|seed| **seed != Seed::Bump)
+            .map(|seed| match *seed {
+                Seed::Literal(bytes) => bytes.to_vec(),
+                Seed::Key => (0..32).map(|_| next()).collect(),
+                Seed::Bytes(max) => {
+                    let len = usize::from(next()) % (max.min(MAX_SEED_LEN) + 1);
+                    (0..len).map(|_| next()).collect()
+                }
+                Seed::Bump => unreachable!(),
+            })
+            .collect::<Vec<_>>();
+        if namespace.bumped() {
+            [seeds, vec![vec![next()]]].concat()
+        } else {
+            namespace.fill(seeds, program_id)
+        }
+    });
+    if first_seeds == second_seeds {
+        return None;
+    }
+    let address = first.derive(&first_seeds, program_id)?;
+    (second.derive(&second_seeds, program_id)? == address).then(|| {
+        Collision::new(
+            program_id,
+            address,
+            [(first, &first_seeds), (second, &second_seeds)],
+        )
+    })
+}
+
+/// A collision between `first` and `second` under `program_id`, if any
+/// filling of their seeds lays out the same bytes: every length their
+/// caller-chosen seeds can take is tried, and where both leave a byte
+/// free it is zero
+pub fn ambiguous(first: &Namespace, second: &Namespace, program_id: &Pubkey) -> Option<Collision> {
+    for first_lens in lengths(first) {
+        for second_lens in lengths(second) {
+            let patterns = [layout(first, &first_lens), layout(second, &second_lens)];
+            let [first_layout, second_layout] = &patterns;
+            if first_layout.len() != second_layout.len() {
+                continue;
+            }
+            // Byte by byte, whichever side fixes it; a clash rules the
+            // layouts out
+            let mut bytes = Vec::with_capacity(first_layout.len());
+            for (a, b) in first_layout.iter().zip(second_layout) {
+                match (a, b) {
+                    (Some(a), Some(b)) if a != b => break,
+                    (Some(byte), _) | (_, Some(byte)) => bytes.push(*byte),
+                    (None, None) => bytes.push(0),
+                }
+            }
+            if bytes.len() != first_layout.len() {
+                continue;
+            }
+            // The bump is the last byte; the canonical one where either
+            // side insists on it, the highest that derives an address
+            // otherwise
+            let unbumped = &bytes[..bytes.len() - 1];
+            let [first_seeds, second_seeds] = [(first, &first_lens), (second, &second_lens)]
+                .map(|(namespace, lens)| split(namespace, lens, unbumped));
+            let bump = if !first.bumped() || !second.bumped() {
+                let seeds = if !first.bumped() {
+                    &first_seeds
+                } else {
+                    &second_seeds
+                };
+                let seeds: Vec<&[u8]> = seeds.iter().map(Vec::as_slice).collect();
+                Pubkey::find_program_address(&seeds, program_id).1
+            } else {
+                let seeds: Vec<&[u8]> = first_seeds.iter().map(Vec::as_slice).collect();
+                (0..=u8::MAX).rev().find(|bump| {
+                    let mut seeds = seeds.clone();
+                    let bump = [*bump];
+                    seeds.push(&bump);
+                    Pubkey::create_program_address(&seeds, program_id).is_ok()
+                })?
+            };
+            let [first_seeds, second_seeds] =
+                [first_seeds, second_seeds].map(|seeds| [seeds, vec![vec![bump]]].concat());
+            if first_seeds == second_seeds {
+                continue;
+            }
+            let address = first.derive(&first_seeds, program_id)?;
+            if second.derive(&second_seeds, program_id)? == address {
+                return Some(Collision::new(
+                    program_id,
+                    address,
+                    [(first, &first_seeds), (second, &second_seeds)],
+                ));
+            }
+        }
+    }
+    None
+}
+
+/// Every collision between two of this tree's namespaces of one program
+pub fn search() -> Vec<Collision> {
+    let namespaces = namespaces();
+    let mut collisions = Vec::new();
+    for (index, first) in namespaces.iter().enumerate() {
+        for second in &namespaces[index + 1..] {
+            if first.example != second.example {
+                continue;
+            }
+            let Some(program_id) = programs::id(first.example) else {
+                continue;
+            };
+            collisions.extend(ambiguous(first, second, &program_id));
+        }
+    }
+    collisions
+}
+
+/// Every combination of lengths `namespace`'s caller-chosen seeds can take
+fn lengths(namespace: &Namespace) -> Vec<Vec<usize>> {
+    namespace
+        .seeds
+        .iter()
+        .fold(vec![Vec::new()], |combinations, seed| {
+            let Seed::Bytes(max) = *seed else {
+                return combinations;
+            };
+            combinations
+                .into_iter()
+                .flat_map(|lens| {
+                    (0..=max.min(MAX_SEED_LEN)).map(move |len| [lens.clone(), vec![len]].concat())
+                })
+                .collect()
+        })
+}
+
+/// `namespace`'s seeds laid end to end with `lens` for its caller-chosen
+/// ones, then a bump: the bytes it fixes, and `None` where the caller or
+/// the bump picks
+fn layout(namespace: &Namespace, lens: &[usize]) -> Vec<Option<u8>> {
+    let mut lens = lens.iter();
+    let mut layout: Vec<Option<u8>> = Vec::new();
+    for seed in &namespace.seeds {
+        match *seed {
+            Seed::Literal(bytes) => layout.extend(bytes.iter().copied().map(Some)),
+            Seed::Key => layout.extend([None; 32]),
+            Seed::Bytes(_) => layout.extend(vec![None; *lens.next().unwrap()]),
+            Seed::Bump => {}
+        }
+    }
+    layout.push(None);
+    layout
+}
+
+/// `bytes` cut into `namespace`'s seeds, bump aside
+fn split(namespace: &Namespace, lens: &[usize], bytes: &[u8]) -> Vec<Vec<u8>> {
+    let mut lens = lens.iter();
+    let mut rest = bytes;
+    let mut seeds = Vec::new();
+    for seed in &namespace.seeds {
+        let len = match *seed {
+            Seed::Literal(bytes) => bytes.len(),
+            Seed::Key => 32,
+            Seed::Bytes(_) => *lens.next().unwrap(),
+            Seed::Bump => continue,
+        };
+        let (seed, tail) = rest.split_at(len);
+        seeds.push(seed.to_vec());
+        rest = tail;
+    }
+    seeds
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
@@ -0,0 +1,156 @@
+//! `Programs.toml`: where each program crate lives on each cluster
+//!
+//! The file has a table per cluster, `localnet`, `devnet` and `testnet`,
+//! mapping crate names to program ids. The build script every program
+//! crate shares (`programs/build.rs`) turns the entry for the cluster it is
+//! building for, `$LAB_CLUSTER` or localnet, into the crate's
+//! `declare_id!`, so the id a program checks itself against, the address
+//! [`programs::deploy`](crate::programs::deploy) deploys it to and the one
+//! clients and tests call are the same. Deploying to an address takes its
+//! keypair, kept at `keys/<cluster>/<crate>.json`; localnet's are
+//! committed, since a local validator holds nothing anyone could take,
+//! while [`sync`] makes everyone their own for the public clusters.
+
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{anyhow, bail, ensure, Context, Result as AnyResult};
+use solana_program::pubkey::Pubkey;
+use toml_edit::{DocumentMut, Item, Table};
+
+use crate::funding::read_keypair;
+use crate::programs::{crates, lab_dir};
+use crate::{Keypair, Signer};
+
+/// The clusters `Programs.toml` has ids for
+pub const CLUSTERS: &[&str] = &["localnet", "devnet", "testnet"];
+
+pub fn path() -> PathBuf {
+    lab_dir().join("Programs.toml")
+}
+
+/// Where the keypair a crate deploys with on `cluster` is kept
+pub fn keypair_path(cluster: &str, krate: &str) -> PathBuf {
+    lab_dir()
+        .join("keys")
+        .join(cluster)
+        .join(format!("{krate}.json"))
+}
+
+/// The parsed file, which keeps its comments and layout when saved
+#[derive(Clone, Debug)]
+pub struct ProgramIds {
+    document: DocumentMut,
+}
+
+impl ProgramIds {
+    pub fn load() -> AnyResult<Self> {
+        let path = path();
+        let text =
+            fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("in {}", path.display()))
+    }
+
+    /// Checks every cluster is one of [`CLUSTERS`] and every id a pubkey
+    pub fn parse(text: &str) -> AnyResult<Self> {
+        let document: DocumentMut = text.parse()?;
+        for (cluster, ids) in document.iter() {
+            ensure!(
+                CLUSTERS.contains(&cluster),
+                "unknown cluster {cluster}: expected one of {CLUSTERS:?}"
+            );
+            let Some(ids) = ids.as_table() else {
+                bail!("{cluster} is not a table of program ids");
+            };
+            for (krate, id) in ids.iter() {
+                parse_id(id).with_context(|| format!("{cluster}.{krate}"))?;
+            }
+        }
+        Ok(ProgramIds { document })
+    }
+
+    /// A crate's id on `cluster`, if it has one
+    pub fn get(&self, cluster: &str, krate: &str) -> Option<Pubkey> {
+        let id = self.document.get(cluster)?.get(krate)?;
+        // parse checked every id
+        parse_id(id).ok()
+    }
+
+    /// Every crate with an id on `cluster`, in file order
+    pub fn ids(&self, cluster: &str) -> Vec<(String, Pubkey)> {
+        let Some(ids) = self.document.get(cluster).and_then(Item::as_table) else {
+            return Vec::new();
+        };
+        ids.iter()
+            .filter_map(|(krate, id)| Some((krate.to_string(), parse_id(id).ok()?)))
+            .collect()
+    }
+
+    pub fn set(&mut self, cluster: &str, krate: &str, id: &Pubkey) {
+        let ids = self
+            .document
+            .entry(cluster)
+            .or_insert_with(|| Item::Table(Table::new()));
+        ids[krate] = toml_edit::value(id.to_string());
+    }
+
+    pub fn save(&self) -> AnyResult<()> {
+        let path = path();
+        fs::write(&path, self.document.to_string())
+            .with_context(|| format!("writing {}", path.display()))
+    }
+}
+
+fn parse_id(id: &Item) -> AnyResult<Pubkey> {
+    let Some(id) = id.as_str() else {
+        bail!("program id is not a string");
+    };
+    id.parse()
+        .map_err(|err| anyhow!("{id} is not a program id: {err}"))
+}
+
+/// The keypair a crate deploys with on `cluster`, checked against its id
+/// in `Programs.toml`
+pub fn program_keypair(ids: &ProgramIds, cluster: &str, krate: &str) -> AnyResult<Keypair> {
+    let id = ids.get(cluster, krate).with_context(|| {
+        format!("Programs.toml has no {cluster} id for {krate}; run `vuln-lab keys --cluster {cluster}`")
+    })?;
+    let keypair = read_keypair(&keypair_path(cluster, krate))?;
+    ensure!(
+        keypair.pubkey() == id,
+        "keys/{cluster}/{krate}.json is {} but Programs.toml says {krate} is {id}; \
+         run `vuln-lab keys --cluster {cluster}`",
+        keypair.pubkey()
+    );
+    Ok(keypair)
+}
+
+/// Makes a keypair for every program crate that has none on `cluster` and
+/// writes every crate's id into `Programs.toml`, returning the ids that
+/// changed. Rebuild after, so the programs declare them.
+pub fn sync(cluster: &str) -> AnyResult<Vec<(String, Pubkey)>> {
+    ensure!(
+        CLUSTERS.contains(&cluster),
+        "unknown cluster {cluster}: expected one of {CLUSTERS:?}"
+    );
+    let mut ids = ProgramIds::load()?;
+    let mut changed = Vec::new();
+    for krate in crates() {
+        let path = keypair_path(cluster, &krate);
+        let keypair = if path.is_file() {
+            read_keypair(&path)?
+        } else {
+            let keypair = Keypair::new();
+            fs::create_dir_all(path.parent().unwrap())?;
+            fs::write(&path, serde_json::to_string(&keypair.to_bytes().to_vec())?)
+                .with_context(|| format!("writing {}", path.display()))?;
+            keypair
+        };
+        if ids.get(cluster, &krate) != Some(keypair.pubkey()) {
+            ids.set(cluster, &krate, &keypair.pubkey());
+            changed.push((krate, keypair.pubkey()));
+        }
+    }
+    ids.save()?;
+    Ok(changed)
+}
@@ -0,0 +1,221 @@
+//! Seed corpora and dictionaries for the fuzz targets in `fuzz/`, built
+//! from the instructions each example's honest use sends
+//!
+//! Random bytes rarely get an instruction past the first tag or length
+//! check, so a fuzzer started from nothing spends most of its time on
+//! inputs every build refuses. The honest uses build their instructions
+//! with `lab_instructions`, so what they send is well formed by
+//! construction. [`seeds`] captures each instruction an honest use sends
+//! to its own program, in both builds, along with the accounts it names
+//! as they were just before, and encodes it in the format [`fuzz::run`]
+//! decodes. Where the instruction data ends in an amount, as the builders
+//! lay it out after an optional tag, it is also seeded with that amount
+//! swapped for 0, 1, one either side of it, and `u64::MAX` and one below,
+//! the values arithmetic bugs turn on.
+//! [`flows`] seeds the `flows` target with the honest use in order and
+//! each of its instructions alone, where the builds agree on them, and
+//! [`dictionary`] collects the byte strings worth splicing in: the
+//! instruction datas, the boundary amounts, the typed-state length bytes,
+//! the discriminator, and the account data the instructions read.
+//!
+//! [`write()`] lays them out where cargo-fuzz looks, and cargo-hfuzz takes
+//! the same files through `HFUZZ_INPUT` and `-w`:
+//!
+//! ```bash
+//! cargo run -p vuln-lab -- corpus --out fuzz
+//! cd fuzz
+//! cargo +nightly fuzz run pda_issues -- -dict=dictionaries/pda_issues.dict
+//! HFUZZ_INPUT=corpus/pda_issues HFUZZ_RUN_ARGS="-w dictionaries/pda_issues.dict" \
+//!     cargo hfuzz run pda_issues
+//! ```
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use lab_svm::Account;
+use solana_common::discriminator::AccountDiscriminator;
+use solana_program::instruction::Instruction;
+
+use crate::fixtures;
+use crate::fuzz::{self, MAX_STEPS, TYPED_STATE};
+use crate::{Harness, Version, REGISTRY};
+
+/// Account data no longer than this goes in the dictionary
+const MAX_TOKEN: usize = 64;
+
+/// Seeds for `example`'s target, each an instruction its honest use sends,
+/// or one with its amount at a boundary
+pub fn seeds(example: &str) -> Vec<Vec<u8>> {
+    // Both builds deploy to the same id in the honest uses
+    let program_id = fixtures::address(&format!("{example}/program"));
+    let mut seeds = BTreeSet::new();
+    for version in [Version::Vulnerable, Version::Secure] {
+        for (instruction, accounts) in honest(example, version) {
+            if instruction.program_id != program_id {
+                continue;
+            }
+            for data in amounts(&instruction.data) {
+                seeds.insert(fuzz::encode(&instruction, &accounts, &data));
+            }
+        }
+    }
+    seeds.into_iter().collect()
+}
+
+/// Seeds for the `flows` target that pick `example`: its honest use in
+/// order, then each of its instructions on its own. A flow where the
+/// builds already part is left out, since a seed that crashes the target
+/// stops the fuzzer before it starts; the fuzzer or
+/// [`witness`](crate::witness) finds it anyway
+pub fn flows(example: &str) -> Vec<Vec<u8>> {
+    let pick = REGISTRY
+        .iter()
+        .position(|vulnerability| vulnerability.example() == example)
+        .unwrap_or_else(|| panic!("no example {example}"));
+    let sent = honest(example, Version::Vulnerable).len();
+    let flow = |indices: &[usize]| {
+        let mut bytes = vec![pick as u8, (indices.len() - 1) as u8];
+        for index in indices {
+            // Nothing forged and every account kept
+            bytes.push(*index as u8);
+            bytes.extend([0; 2 + fuzz::MAX_ACCOUNTS]);
+        }
+        bytes
+    };
+    let honest: Vec<usize> = (0..sent.min(MAX_STEPS)).collect();
+    let mut flows = vec![flow(&honest)];
+    if sent > 1 {
+        flows.extend((0..sent.min(usize::from(u8::MAX))).map(|index| flow(&[index])));
+    }
+    flows.retain(|flow| fuzz::diverge(example, &flow[1..]).is_none());
+    flows
+}
+
+/// Byte strings worth splicing into `example`'s inputs, each at most once
+pub fn dictionary(example: &str) -> Vec<Vec<u8>> {
+    let mut tokens = BTreeSet::new();
+    for amount in [1, u64::MAX - 1, u64::MAX] {
+        tokens.insert(amount.to_le_bytes().to_vec());
+    }
+    tokens.extend((TYPED_STATE..=u8::MAX).map(|kind| vec![kind]));
+    tokens.insert(lab_domain::VaultConfig::DISCRIMINATOR.to_vec());
+    for version in [Version::Vulnerable, Version::Secure] {
+        for (instruction, accounts) in honest(example, version) {
+            tokens.insert(instruction.data);
+            tokens.extend(
+                accounts
+                    .into_iter()
+                    .flatten()
+                    .map(|account| account.data)
+                    .filter(|data| data.len() <= MAX_TOKEN),
+            );
+        }
+    }
+    tokens.remove(&Vec::new());
+    tokens.into_iter().collect()
+}
+
+/// `tokens` as a libFuzzer dictionary, which honggfuzz reads too: one
+/// quoted, hex-escaped string per line
+pub fn render(tokens: &[Vec<u8>]) -> String {
+    tokens
+        .iter()
+        .map(|token| {
+            let escaped: String = token.iter().map(|byte| format!("\\x{byte:02x}")).collect();
+            format!("\"{escaped}\"\n")
+        })
+        .collect()
+}
+
+/// How many files [`write()`] wrote
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Written {
+    pub seeds: usize,
+    pub flows: usize,
+    pub tokens: usize,
+}
+
+/// Writes `example`'s seeds to `corpus/<example>/`, its flow seeds to
+/// `corpus/flows/` and its dictionary to `dictionaries/<example>.dict`,
+/// all under `dir`
+pub fn write(example: &str, dir: &Path) -> io::Result<Written> {
+    let seeds = seeds(example);
+    let target = dir.join("corpus").join(example);
+    fs::create_dir_all(&target)?;
+    for (index, seed) in seeds.iter().enumerate() {
+        fs::write(target.join(format!("seed-{index:03}")), seed)?;
+    }
+
+    let flows = flows(example);
+    let target = dir.join("corpus").join("flows");
+    fs::create_dir_all(&target)?;
+    for (index, flow) in flows.iter().enumerate() {
+        fs::write(target.join(format!("{example}-{index:03}")), flow)?;
+    }
+
+    let tokens = dictionary(example);
+    let target = dir.join("dictionaries");
+    fs::create_dir_all(&target)?;
+    fs::write(target.join(format!("{example}.dict")), render(&tokens))?;
+
+    Ok(Written {
+        seeds: seeds.len(),
+        flows: flows.len(),
+        tokens: tokens.len(),
+    })
+}
+
+/// Every instruction `example`'s honest use sends against `version`, with
+/// the accounts it names as they were just before its transaction
+fn honest(example: &str, version: Version) -> Vec<(Instruction, Vec<Option<Account>>)> {
+    let vulnerability = REGISTRY
+        .find(example)
+        .unwrap_or_else(|| panic!("no example {example}"));
+    let sent = Arc::new(Mutex::new(Vec::new()));
+    let mut harness = Harness::new(version);
+    let captured = Arc::clone(&sent);
+    harness.inspect(move |svm, transaction| {
+        let mut captured = captured.lock().unwrap();
+        for instruction in fuzz::decompile(svm, transaction) {
+            let accounts = instruction
+                .accounts
+                .iter()
+                .map(|meta| svm.get_account(&meta.pubkey).cloned())
+                .collect();
+            captured.push((instruction, accounts));
+        }
+    });
+    let (result, _) = fixtures::recorded(|| vulnerability.honest_use(&mut harness));
+    if let Some(Err(err)) = result {
+        panic!("honest use of {example} failed against the {version:?} build: {err:#}");
+    }
+    let sent = std::mem::take(&mut *sent.lock().unwrap());
+    sent
+}
+
+/// `data`, and where it is an optional tag then an amount, the same with
+/// the amount at each boundary
+fn amounts(data: &[u8]) -> Vec<Vec<u8>> {
+    if !matches!(data.len(), 8 | 9) {
+        return vec![data.to_vec()];
+    }
+    let (tag, amount) = data.split_at(data.len() - 8);
+    let amount = u64::from_le_bytes(amount.try_into().unwrap());
+    let mut seen = BTreeSet::new();
+    [
+        amount,
+        0,
+        1,
+        amount.saturating_sub(1),
+        amount.saturating_add(1),
+        u64::MAX - 1,
+        u64::MAX,
+    ]
+    .into_iter()
+    .filter(|amount| seen.insert(*amount))
+    .map(|amount| [tag, &amount.to_le_bytes()].concat())
+    .collect()
+}
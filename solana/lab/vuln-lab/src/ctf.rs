@@ -0,0 +1,311 @@
+//! CTF mode: a flag per example, held by the verifier program in `ctf/`
+//!
+//! Every exploit plants its example's flag once the program and its
+//! victims are set up, attacks, then captures the flag as the attacker.
+//! A flag is a PDA of [`VERIFIER`] seeded with the target program's id; it
+//! names the account the attack is after and what has to happen to it, and
+//! capturing it records the signer, so solving a challenge is something the
+//! chain can check rather than something the player reports.
+//!
+//! [`setup`] runs an exploit only as far as its flag and [`export`] writes
+//! that state out as fixtures for `solana-test-validator`, so players can
+//! attack a real validator with their own code and `vuln-lab ctf score`
+//! can read the scoreboard back from it.
+
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, ensure, Context, Result as AnyResult};
+use base64::prelude::{Engine, BASE64_STANDARD};
+use borsh::BorshDeserialize;
+use lab_svm::{Svm, LAMPORTS_PER_SOL};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::pubkey::Pubkey;
+
+pub use ctf_verifier::{Flag, Goal};
+
+use crate::programs;
+use crate::{attacker, owner, ExploitOutcome, Harness, Severity, Signer, Version, Vulnerability};
+use crate::{diff, simulate};
+
+/// Where the verifier lives, in the runtime and on a test validator: the
+/// id it declares, from `Programs.toml`
+pub const VERIFIER: Pubkey = ctf_verifier::ID;
+
+thread_local! {
+    /// Set while [`setup`] runs an exploit, which then stops at its flag
+    static SETUP_ONLY: Cell<bool> = const { Cell::new(false) };
+}
+
+/// A flag an exploit has planted
+#[derive(Clone, Copy, Debug)]
+pub struct Planted {
+    pub address: Pubkey,
+    pub goal: Goal,
+}
+
+/// Returned by [`plant`] while [`setup`] is running, to stop the exploit
+/// before it attacks
+#[derive(Debug)]
+pub struct SetupComplete;
+
+impl fmt::Display for SetupComplete {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("challenge set up; stopped before the attack")
+    }
+}
+
+impl Error for SetupComplete {}
+
+/// Plants the flag for `target`, deploying the verifier first if the
+/// runtime does not have it yet. The owner pays for the flag account.
+pub fn plant(svm: &mut Svm, target: Pubkey, goal: Goal) -> AnyResult<Planted> {
+    if svm.entrypoint(&VERIFIER).is_none() {
+        svm.add_program_with_id(VERIFIER, ctf_verifier::process_instruction);
+    }
+    let payer = owner().pubkey();
+    svm.airdrop(&payer, LAMPORTS_PER_SOL);
+
+    let (address, _) = ctf_verifier::flag_address(&target, &VERIFIER);
+    svm.send(
+        &[Instruction::new_with_borsh(
+            VERIFIER,
+            &ctf_verifier::VerifierInstruction::Plant { target, goal },
+            vec![
+                AccountMeta::new(address, false),
+                AccountMeta::new(payer, true),
+                AccountMeta::new_readonly(*goal.account(), false),
+                AccountMeta::new_readonly(solana_system_interface::program::ID, false),
+            ],
+        )],
+        &[&owner()],
+    )?;
+
+    if SETUP_ONLY.get() {
+        return Err(SetupComplete.into());
+    }
+    simulate::attack_begins(svm);
+    diff::attack_begins(svm);
+    Ok(Planted { address, goal })
+}
+
+/// The instruction that captures the flag at `flag` for `solver`
+pub fn capture_instruction(flag: &Pubkey, goal: &Goal, solver: &Pubkey) -> Instruction {
+    Instruction::new_with_borsh(
+        VERIFIER,
+        &ctf_verifier::VerifierInstruction::Capture,
+        vec![
+            AccountMeta::new(*flag, false),
+            AccountMeta::new_readonly(*solver, true),
+            AccountMeta::new_readonly(*goal.account(), false),
+        ],
+    )
+}
+
+/// Captures a planted flag as the attacker
+pub fn capture(svm: &mut Svm, planted: &Planted) -> AnyResult<()> {
+    // A simulated attack changed nothing the verifier could accept
+    ensure!(
+        !svm.is_dry_run(),
+        "the attack was simulated; no flag to capture"
+    );
+    let solver = attacker();
+    svm.send(
+        &[capture_instruction(
+            &planted.address,
+            &planted.goal,
+            &solver.pubkey(),
+        )],
+        &[&solver],
+    )
+    .context("the verifier did not accept the capture")?;
+    Ok(())
+}
+
+/// The vulnerable version of an example, set up up to and including its
+/// flag and not attacked
+pub fn setup(vulnerability: &dyn Vulnerability) -> AnyResult<Harness> {
+    let mut harness = Harness::new(Version::Vulnerable);
+    SETUP_ONLY.set(true);
+    let outcome = vulnerability.exploit(&mut harness);
+    SETUP_ONLY.set(false);
+    match outcome {
+        ExploitOutcome::Failed(err) if err.is::<SetupComplete>() => Ok(harness),
+        ExploitOutcome::Failed(err) => Err(err.context("setting up the challenge")),
+        ExploitOutcome::Succeeded => bail!(
+            "the {} exploit never plants a flag",
+            vulnerability.example()
+        ),
+    }
+}
+
+/// Every flag in the runtime, by address
+pub fn flags(svm: &Svm) -> Vec<(Pubkey, Flag)> {
+    let mut flags: Vec<(Pubkey, Flag)> = svm
+        .accounts()
+        .filter(|(_, account)| account.owner == VERIFIER)
+        .filter_map(|(address, account)| Some((*address, decode(&account.data)?)))
+        .collect();
+    flags.sort_by_key(|(address, _)| *address);
+    flags
+}
+
+/// A flag account's data; the account is sized for a full solver list, so
+/// this ignores what follows the flag
+pub fn decode(data: &[u8]) -> Option<Flag> {
+    Flag::deserialize(&mut &data[..]).ok()
+}
+
+/// What a challenge is worth
+pub fn points(severity: Severity) -> u64 {
+    match severity {
+        Severity::Critical => 300,
+        Severity::High => 200,
+        Severity::Medium => 100,
+        Severity::Low => 50,
+    }
+}
+
+/// What capturing a flag takes, in words
+pub fn objective(goal: &Goal) -> String {
+    match goal {
+        Goal::Drain { account, amount } => {
+            format!("drain at least {amount} lamports from {account}")
+        }
+        Goal::Authority { account, offset } => {
+            format!("put your key at byte {offset} of {account}'s data")
+        }
+        Goal::Decrease { account, offset } => {
+            format!("lower the u64 at byte {offset} of {account}'s data")
+        }
+        Goal::Close { account } => format!("get {account} closed"),
+        Goal::Exceed {
+            account,
+            offset,
+            limit,
+        } => {
+            format!("push the u32 at byte {offset} of {account}'s data past {limit}")
+        }
+    }
+}
+
+/// `challenge.json`: what a player is told, and what scoring reads back
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Challenge {
+    pub id: String,
+    pub example: String,
+    pub description: String,
+    pub points: u64,
+    pub program_id: String,
+    pub flag: String,
+    pub objective: String,
+    pub verifier: String,
+}
+
+/// Writes a set-up challenge to `dir`: every account as a validator
+/// fixture under `accounts/`, the player's keypair, `challenge.json`, and
+/// `validator.sh` to start a test validator with the programs deployed at
+/// the addresses the fixtures expect
+pub fn export(
+    vulnerability: &dyn Vulnerability,
+    harness: &Harness,
+    dir: &Path,
+) -> AnyResult<Challenge> {
+    let [(flag_address, flag)] = <[_; 1]>::try_from(flags(harness))
+        .map_err(|flags| anyhow::anyhow!("expected one flag, found {}", flags.len()))?;
+
+    let accounts = dir.join("accounts");
+    fs::create_dir_all(&accounts).with_context(|| format!("creating {}", accounts.display()))?;
+    let mut addresses: Vec<&Pubkey> = harness
+        .accounts()
+        .filter(|(_, account)| !account.executable)
+        .map(|(address, account)| {
+            let fixture = json!({
+                "pubkey": address.to_string(),
+                "account": {
+                    "lamports": account.lamports,
+                    "data": [BASE64_STANDARD.encode(&account.data), "base64"],
+                    "owner": account.owner.to_string(),
+                    "executable": false,
+                    "rentEpoch": account.rent_epoch,
+                    "space": account.data.len(),
+                },
+            });
+            fs::write(
+                accounts.join(format!("{address}.json")),
+                serde_json::to_string_pretty(&fixture)?,
+            )?;
+            Ok(address)
+        })
+        .collect::<AnyResult<_>>()?;
+    addresses.sort();
+
+    let deploy = programs::lab_dir()
+        .canonicalize()
+        .map(|lab| lab.join("target").join("deploy"))
+        .unwrap_or_else(|_| programs::deploy_dir());
+    let mut script = format!(
+        "#!/usr/bin/env bash\n\
+         # {} {}: build the programs with `vuln-lab build {}` and `vuln-lab build ctf_verifier`\n\
+         set -euo pipefail\n\
+         cd \"$(dirname \"$0\")\"\n\
+         DEPLOY=\"${{DEPLOY:-{}}}\"\n\
+         exec solana-test-validator --reset \\\n",
+        vulnerability.id(),
+        vulnerability.example(),
+        vulnerability.example(),
+        deploy.display()
+    );
+    for program_id in harness.program_ids() {
+        let krate = programs::crate_name(harness, program_id)
+            .with_context(|| format!("no program crate for {program_id}"))?;
+        script += &format!("  --bpf-program {program_id} \"$DEPLOY/{krate}.so\" \\\n");
+    }
+    for address in &addresses {
+        script += &format!("  --account {address} accounts/{address}.json \\\n");
+    }
+    script += "  \"$@\"\n";
+    fs::write(dir.join("validator.sh"), script)?;
+
+    fs::write(
+        dir.join("attacker.json"),
+        serde_json::to_string(&attacker().to_bytes().to_vec())?,
+    )?;
+
+    let challenge = Challenge {
+        id: vulnerability.id().to_string(),
+        example: vulnerability.example().to_string(),
+        description: vulnerability.description().to_string(),
+        points: points(vulnerability.severity()),
+        program_id: flag.target.to_string(),
+        flag: flag_address.to_string(),
+        objective: objective(&flag.goal),
+        verifier: VERIFIER.to_string(),
+    };
+    fs::write(
+        dir.join("challenge.json"),
+        serde_json::to_string_pretty(&challenge)? + "\n",
+    )?;
+    Ok(challenge)
+}
+
+/// Total points per solver, highest first
+pub fn leaderboard<'a>(
+    solved: impl IntoIterator<Item = (u64, &'a [Pubkey])>,
+) -> Vec<(Pubkey, u64)> {
+    let mut totals: HashMap<Pubkey, u64> = HashMap::new();
+    for (points, solvers) in solved {
+        for solver in solvers {
+            *totals.entry(*solver).or_default() += points;
+        }
+    }
+    let mut totals: Vec<(Pubkey, u64)> = totals.into_iter().collect();
+    totals.sort_by(|(a, a_points), (b, b_points)| b_points.cmp(a_points).then(a.cmp(b)));
+    totals
+}
@@ -0,0 +1,148 @@
+//! The learning path: the examples in an order that teaches them, and who
+//! has worked through which
+//!
+//! Each example's `#[vulnerability]` attribute gives its difficulty and the
+//! examples to work through first. [`curriculum`] orders them so that every
+//! example comes after its prerequisites, easier and then lower-numbered
+//! ones first wherever the order is free. [`Progress`] keeps each user's
+//! completed examples in a JSON file, by default under the user's data
+//! directory, which one shared machine can keep for a whole classroom.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{bail, Context, Result as AnyResult};
+use serde::{Deserialize, Serialize};
+
+use crate::{Registry, Vulnerability};
+
+/// Every example in `registry`, each after its prerequisites
+pub fn curriculum(registry: &Registry) -> AnyResult<Vec<&'static dyn Vulnerability>> {
+    for vulnerability in registry.iter() {
+        for prerequisite in vulnerability.prerequisites() {
+            if registry.find(prerequisite).is_none() {
+                bail!(
+                    "{} has an unknown prerequisite {prerequisite}",
+                    vulnerability.example()
+                );
+            }
+        }
+    }
+
+    let mut remaining: Vec<_> = registry.iter().collect();
+    let mut path: Vec<&'static dyn Vulnerability> = Vec::new();
+    while !remaining.is_empty() {
+        let ready = |vulnerability: &&dyn Vulnerability| {
+            vulnerability
+                .prerequisites()
+                .iter()
+                .all(|prerequisite| path.iter().any(|done| done.example() == *prerequisite))
+        };
+        let Some(index) = (0..remaining.len())
+            .filter(|&index| ready(&remaining[index]))
+            .min_by_key(|&index| (remaining[index].difficulty(), remaining[index].id()))
+        else {
+            let stuck: Vec<_> = remaining
+                .iter()
+                .map(|vulnerability| vulnerability.example())
+                .collect();
+            bail!("prerequisites go round in a circle among {stuck:?}");
+        };
+        path.push(remaining.remove(index));
+    }
+    Ok(path)
+}
+
+/// Where a user stands with an example
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Status {
+    /// Completed, at this Unix time
+    Done(u64),
+    /// Every prerequisite is done
+    Ready,
+    /// Waiting on these prerequisites
+    Locked(Vec<&'static str>),
+}
+
+/// Every user's completed examples, with when they completed them
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Progress {
+    users: BTreeMap<String, BTreeMap<String, u64>>,
+}
+
+impl Progress {
+    /// `$XDG_DATA_HOME/vuln-lab/progress.json`, or under
+    /// `~/.local/share` when that is not set
+    pub fn default_path() -> PathBuf {
+        let data = std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| {
+                let home = std::env::var_os("HOME").unwrap_or_default();
+                Path::new(&home).join(".local/share")
+            });
+        data.join("vuln-lab/progress.json")
+    }
+
+    /// The progress kept at `path`; none yet if there is no file
+    pub fn load(path: &Path) -> AnyResult<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let json =
+            fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        serde_json::from_str(&json).with_context(|| format!("parsing {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> AnyResult<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+        }
+        let json = serde_json::to_string_pretty(self).expect("progress serializes");
+        fs::write(path, json).with_context(|| format!("writing {}", path.display()))
+    }
+
+    /// When `user` completed `example`, if they have
+    pub fn completed(&self, user: &str, example: &str) -> Option<u64> {
+        self.users.get(user)?.get(example).copied()
+    }
+
+    /// Records `user` completing `example` now, unless they already had;
+    /// returns whether this is the first time
+    pub fn complete(&mut self, user: &str, example: &str) -> bool {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let examples = self.users.entry(user.to_string()).or_default();
+        if examples.contains_key(example) {
+            return false;
+        }
+        examples.insert(example.to_string(), now);
+        true
+    }
+
+    /// Forgets everything `user` completed
+    pub fn reset(&mut self, user: &str) {
+        self.users.remove(user);
+    }
+
+    pub fn status(&self, user: &str, vulnerability: &dyn Vulnerability) -> Status {
+        if let Some(at) = self.completed(user, vulnerability.example()) {
+            return Status::Done(at);
+        }
+        let waiting: Vec<_> = vulnerability
+            .prerequisites()
+            .iter()
+            .copied()
+            .filter(|prerequisite| self.completed(user, prerequisite).is_none())
+            .collect();
+        if waiting.is_empty() {
+            Status::Ready
+        } else {
+            Status::Locked(waiting)
+        }
+    }
+}
@@ -0,0 +1,362 @@
+//! Account diffs: what an exploit's attack did to each account, field by
+//! field
+//!
+//! [`attack`] runs an exploit and compares every account as it was when
+//! the attack began, once [`ctf::plant`] had planted the flag, with how
+//! the exploit left it. Account data that fits one of the example's Borsh
+//! structs, which the registry lays out from its source
+//! ([`Vulnerability::accounts`]), is decoded, so a diff reads
+//! `authority: owner → attacker` rather than as bytes, and a number reset
+//! to zero or wrapped around its range is called out. [`render`] prints
+//! diffs for the CLI, colored on a terminal, and for the exploit tests'
+//! failure messages.
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::fmt::{self, Write};
+
+use anyhow::Result as AnyResult;
+
+use lab_registry::Layout;
+use lab_svm::{Account, Svm};
+use solana_program::pubkey::Pubkey;
+
+use crate::ctf;
+use crate::rpc::Rpc;
+use crate::{ExploitOutcome, Harness, Signer, Version, Vulnerability};
+
+pub use lab_client::layout::{decode, Value};
+
+thread_local! {
+    /// Set while [`attack`] runs an exploit
+    static RECORDING: Cell<bool> = const { Cell::new(false) };
+    static BEFORE_ATTACK: RefCell<Option<Snapshot>> = const { RefCell::new(None) };
+}
+
+/// Called by [`ctf::plant`](crate::ctf::plant): the setup is done and
+/// whatever the exploit sends next is its attack
+pub(crate) fn attack_begins(svm: &Svm) {
+    if RECORDING.get() {
+        BEFORE_ATTACK.set(Some(Snapshot::of(svm)));
+    }
+}
+
+/// Every account at one point in a run
+#[derive(Clone, Debug, Default)]
+pub struct Snapshot(HashMap<Pubkey, Account>);
+
+impl Snapshot {
+    pub fn of(svm: &Svm) -> Self {
+        Self(
+            svm.accounts()
+                .map(|(address, account)| (*address, account.clone()))
+                .collect(),
+        )
+    }
+
+    /// The accounts at `addresses` on the cluster behind `rpc`, leaving
+    /// out any address with no account. JSON RPC's account reads say
+    /// nothing of whether one is executable, so none is
+    pub fn fetch(rpc: &Rpc, addresses: &[Pubkey]) -> AnyResult<Self> {
+        let mut accounts = HashMap::new();
+        for address in addresses {
+            let Some((owner, data)) = rpc.account(address)? else {
+                continue;
+            };
+            let account = Account {
+                lamports: rpc.balance(address)?,
+                data,
+                owner,
+                executable: false,
+                rent_epoch: 0,
+            };
+            accounts.insert(*address, account);
+        }
+        Ok(Self(accounts))
+    }
+}
+
+/// The lab's wallets by name, anything else by address
+fn name(pubkey: &Pubkey) -> String {
+    [
+        ("owner", crate::owner()),
+        ("victim", crate::victim()),
+        ("attacker", crate::attacker()),
+    ]
+    .into_iter()
+    .find(|(_, keypair)| keypair.pubkey() == *pubkey)
+    .map_or_else(|| pubkey.to_string(), |(name, _)| name.to_string())
+}
+
+/// `value` as [`render`] shows it, the lab's wallets by name
+struct Named<'a>(&'a Value);
+
+impl fmt::Display for Named<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Value::Pubkey(pubkey) => f.write_str(&name(pubkey)),
+            Value::List(values) => {
+                f.write_str("[")?;
+                for (index, value) in values.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}", Named(value))?;
+                }
+                f.write_str("]")
+            }
+            Value::Option(Some(value)) => write!(f, "Some({})", Named(value)),
+            value => write!(f, "{value}"),
+        }
+    }
+}
+
+/// One field before and after; `None` on the side where the account did
+/// not exist
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FieldChange {
+    pub name: &'static str,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+}
+
+impl FieldChange {
+    /// What is worth pointing out about the change: an unsigned number
+    /// that moved more than half its range went past zero or its maximum,
+    /// which only wrapping arithmetic does
+    pub fn note(&self) -> Option<&'static str> {
+        let (Some(Value::Unsigned(before, bits)), Some(Value::Unsigned(after, _))) =
+            (&self.before, &self.after)
+        else {
+            return None;
+        };
+        let half = 1u128 << (bits - 1);
+        if *after == 0 {
+            Some("reset")
+        } else if after > before && after - before > half {
+            Some("wrapped below zero")
+        } else if before > after && before - after > half {
+            Some("wrapped past the maximum")
+        } else {
+            None
+        }
+    }
+}
+
+/// How one account changed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AccountDiff {
+    pub address: Pubkey,
+    /// `None` if the account did not exist yet
+    pub before: Option<Account>,
+    /// `None` if it no longer exists
+    pub after: Option<Account>,
+    /// The layout the data decodes as on both sides, if any does
+    pub layout: Option<&'static str>,
+    /// Other layouts it decodes as just as well
+    pub also_fits: Vec<&'static str>,
+    /// The decoded fields that changed
+    pub fields: Vec<FieldChange>,
+}
+
+impl AccountDiff {
+    fn new(
+        address: Pubkey,
+        before: Option<Account>,
+        after: Option<Account>,
+        layouts: &[Layout],
+    ) -> Self {
+        let decoded = |account: &Option<Account>, layout: &Layout| match account {
+            Some(account) => decode(layout, &account.data).map(Some),
+            None => Some(None),
+        };
+        let mut fits = layouts.iter().filter_map(|layout| {
+            Some((
+                layout.name,
+                decoded(&before, layout)?,
+                decoded(&after, layout)?,
+            ))
+        });
+        let (layout, fields) = match fits.next() {
+            Some((name, Some(before), Some(after))) => {
+                let fields = before
+                    .into_iter()
+                    .zip(after)
+                    .filter(|((_, before), (_, after))| before != after)
+                    .map(|((name, before), (_, after))| FieldChange {
+                        name,
+                        before: Some(before),
+                        after: Some(after),
+                    })
+                    .collect();
+                (Some(name), fields)
+            }
+            Some((name, before, after)) => {
+                let fields = before
+                    .map(|fields| (fields, false))
+                    .or(after.map(|fields| (fields, true)))
+                    .map(|(fields, created)| {
+                        fields
+                            .into_iter()
+                            .map(|(name, value)| FieldChange {
+                                name,
+                                before: (!created).then(|| value.clone()),
+                                after: created.then_some(value),
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                (Some(name), fields)
+            }
+            None => (None, Vec::new()),
+        };
+        let also_fits = fits.map(|(name, _, _)| name).collect();
+        Self {
+            address,
+            before,
+            after,
+            layout,
+            also_fits,
+            fields,
+        }
+    }
+
+    fn lamports(&self) -> (u64, u64) {
+        let lamports = |account: &Option<Account>| account.as_ref().map_or(0, |a| a.lamports);
+        (lamports(&self.before), lamports(&self.after))
+    }
+}
+
+/// Every account that differs between `before` and `svm` now, in address
+/// order, decoded with the first of `layouts` its data fits
+pub fn diff(before: &Snapshot, svm: &Svm, layouts: &[Layout]) -> Vec<AccountDiff> {
+    between(before, &Snapshot::of(svm), |_| layouts)
+}
+
+/// Every account that differs between two snapshots, in address order,
+/// decoded with the first of the layouts of its owner's, by `layouts`,
+/// that its data fits
+pub fn between<'a>(
+    before: &Snapshot,
+    after: &Snapshot,
+    layouts: impl Fn(&Pubkey) -> &'a [Layout],
+) -> Vec<AccountDiff> {
+    let mut addresses: Vec<Pubkey> = before.0.keys().chain(after.0.keys()).copied().collect();
+    addresses.sort();
+    addresses.dedup();
+    addresses
+        .into_iter()
+        .filter_map(|address| {
+            let before = before.0.get(&address).cloned();
+            let after = after.0.get(&address).cloned();
+            let owner = after.as_ref().or(before.as_ref())?.owner;
+            (before != after).then(|| AccountDiff::new(address, before, after, layouts(&owner)))
+        })
+        .collect()
+}
+
+/// Runs `vulnerability`'s exploit against `version` on a fresh harness and
+/// diffs its attack; no diffs if it never planted its flag
+pub fn attack(
+    vulnerability: &dyn Vulnerability,
+    version: Version,
+) -> (Harness, ExploitOutcome, Vec<AccountDiff>) {
+    attack_on(Harness::new(version), vulnerability)
+}
+
+/// [`attack`] on a harness the caller has set up, e.g. to record it
+pub fn attack_on(
+    mut harness: Harness,
+    vulnerability: &dyn Vulnerability,
+) -> (Harness, ExploitOutcome, Vec<AccountDiff>) {
+    let version = harness.version();
+    RECORDING.set(true);
+    let outcome = vulnerability.exploit(&mut harness);
+    RECORDING.set(false);
+    let diffs = BEFORE_ATTACK
+        .take()
+        .map(|before| diff(&before, &harness, vulnerability.accounts(version)))
+        .unwrap_or_default();
+    (harness, outcome, diffs)
+}
+
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const YELLOW: &str = "\x1b[33m";
+const RESET: &str = "\x1b[0m";
+
+/// `diffs` as text, one account after another, with ANSI colors if `color`
+pub fn render(diffs: &[AccountDiff], color: bool) -> String {
+    let paint = |code: &'static str, text: &dyn fmt::Display| {
+        if color {
+            format!("{code}{text}{RESET}")
+        } else {
+            text.to_string()
+        }
+    };
+    let change = |before: &dyn fmt::Display, after: &dyn fmt::Display| {
+        format!("{} → {}", paint(RED, before), paint(GREEN, after))
+    };
+
+    let mut out = String::new();
+    for diff in diffs {
+        let mut header = paint(BOLD, &name(&diff.address));
+        let owner = diff
+            .after
+            .as_ref()
+            .or(diff.before.as_ref())
+            .map(|a| a.owner);
+        if owner == Some(ctf::VERIFIER) {
+            header.push_str(" flag");
+        }
+        if let Some(layout) = diff.layout {
+            write!(header, " {layout}").unwrap();
+            if !diff.also_fits.is_empty() {
+                let also = format!("(also fits {})", diff.also_fits.join(", "));
+                write!(header, " {}", paint(DIM, &also)).unwrap();
+            }
+        }
+        match (&diff.before, &diff.after) {
+            (None, _) => write!(header, " {}", paint(GREEN, &"created")).unwrap(),
+            (_, None) => write!(header, " {}", paint(RED, &"closed")).unwrap(),
+            _ => {}
+        }
+        writeln!(out, "{header}").unwrap();
+
+        let (before, after) = diff.lamports();
+        if before != after {
+            let delta = i128::from(after) - i128::from(before);
+            writeln!(out, "  lamports: {} ({delta:+})", change(&before, &after)).unwrap();
+        }
+        for field in &diff.fields {
+            let line = match (&field.before, &field.after) {
+                (Some(before), Some(after)) => change(&Named(before), &Named(after)),
+                (None, Some(after)) => paint(GREEN, &Named(after)),
+                (Some(before), None) => paint(RED, &Named(before)),
+                (None, None) => continue,
+            };
+            write!(out, "  {}: {line}", field.name).unwrap();
+            if let Some(note) = field.note() {
+                write!(out, "  {}", paint(YELLOW, &note)).unwrap();
+            }
+            out.push('\n');
+        }
+        if let (None, Some(before), Some(after)) = (diff.layout, &diff.before, &diff.after) {
+            if let Some(line) = data_change(&before.data, &after.data) {
+                writeln!(out, "  {line}").unwrap();
+            }
+        }
+    }
+    out
+}
+
+/// How data no layout decodes changed, by its bytes
+fn data_change(before: &[u8], after: &[u8]) -> Option<String> {
+    if before.len() != after.len() {
+        return Some(format!("data: {} → {} bytes", before.len(), after.len()));
+    }
+    let changed = before.iter().zip(after).filter(|(a, b)| a != b).count();
+    (changed > 0).then(|| format!("data: {changed} of {} bytes changed", before.len()))
+}
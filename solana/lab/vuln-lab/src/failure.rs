@@ -0,0 +1,42 @@
+//! Failed transactions, with any shared error spelled out
+//!
+//! The checks in `solana-common` fail an instruction with a custom program
+//! error carrying the check's code, which the runtime reports as a bare
+//! hex number. [`describe`] and [`explain`] add what that code means, from
+//! `lab-errors`:
+//!
+//! ```text
+//! Error processing Instruction 0: custom program error: 0x3e9 (validation error 1001: account is owned by another program)
+//! ```
+
+use lab_svm::{FailedTransaction, TransactionError};
+
+/// `err`, followed by the shared error it carries if it carries one
+pub fn describe(err: &TransactionError) -> String {
+    match decoded(err) {
+        Some(lab_error) => format!("{err} ({lab_error})"),
+        None => err.to_string(),
+    }
+}
+
+/// An exploit's failure with its causes, followed by the shared error the
+/// transaction that failed it carries, if one did and it carries one
+pub fn explain(err: &anyhow::Error) -> String {
+    let lab_error = err
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<FailedTransaction>())
+        .and_then(|failed| decoded(&failed.err));
+    match lab_error {
+        Some(lab_error) => format!("{err:#} ({lab_error})"),
+        None => format!("{err:#}"),
+    }
+}
+
+fn decoded(err: &TransactionError) -> Option<lab_errors::LabError> {
+    match err {
+        TransactionError::InstructionError(_, instruction_error) => {
+            lab_errors::decode(instruction_error)
+        }
+        _ => None,
+    }
+}
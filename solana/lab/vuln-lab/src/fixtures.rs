@@ -0,0 +1,128 @@
+//! Every demo keypair and address, derived from a workshop seed phrase
+//!
+//! A keypair is named by a label, `owner` or `missing_signer_check/deposit`,
+//! and derived from SHA-256 of the phrase and the label, so everyone who
+//! runs the lab with the instructor's phrase gets the instructor's
+//! addresses: the wallets, the programs the exploits deploy in-process and
+//! the accounts they create. The phrase is [`DEFAULT_PHRASE`] unless
+//! [`set_phrase`] or `$VULN_LAB_SEED_PHRASE` gives another; runs of
+//! whitespace in it count as one space, so a phrase read off a slide
+//! matches however it was typed.
+//!
+//! These keys are for demos. Anyone who knows the phrase can sign with
+//! them, so nothing of value belongs in them.
+
+use std::cell::RefCell;
+use std::sync::RwLock;
+
+use lab_client::signers::Workshop;
+use lab_svm::{Keypair, Signer};
+use solana_program::pubkey::Pubkey;
+
+pub use lab_client::signers::{DEFAULT_PHRASE, PHRASE_VAR};
+
+/// Set by [`set_phrase`]; overrides the environment
+static PHRASE: RwLock<Option<String>> = RwLock::new(None);
+
+thread_local! {
+    /// Every label derived on this thread while [`recorded`] runs
+    static RECORDED: RefCell<Option<Vec<(String, Pubkey)>>> = const { RefCell::new(None) };
+}
+
+/// The keypairs one seed phrase derives, as the PoCs' `--signers workshop`
+/// derives them
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Fixtures {
+    workshop: Workshop,
+}
+
+impl Fixtures {
+    pub fn new(phrase: &str) -> Self {
+        Self {
+            workshop: Workshop::new(phrase),
+        }
+    }
+
+    pub fn phrase(&self) -> &str {
+        self.workshop.phrase()
+    }
+
+    /// The keypair for `label`; the same phrase and label always give the
+    /// same keypair
+    pub fn keypair(&self, label: &str) -> Keypair {
+        let keypair = self.workshop.derive(label);
+        RECORDED.with_borrow_mut(|recorded| {
+            if let Some(recorded) = recorded {
+                if !recorded.iter().any(|(known, _)| known == label) {
+                    recorded.push((label.to_string(), keypair.pubkey()));
+                }
+            }
+        });
+        keypair
+    }
+
+    /// The address of `label`'s keypair, for accounts nobody signs for
+    pub fn address(&self, label: &str) -> Pubkey {
+        self.keypair(label).pubkey()
+    }
+
+    /// Deploys programs and creates their accounts
+    pub fn owner(&self) -> Keypair {
+        self.keypair("owner")
+    }
+
+    /// Stands in for honest users
+    pub fn victim(&self) -> Keypair {
+        self.keypair("victim")
+    }
+
+    /// Runs the exploit
+    pub fn attacker(&self) -> Keypair {
+        self.keypair("attacker")
+    }
+}
+
+/// Uses `phrase` for the rest of the process, whatever the environment says
+pub fn set_phrase(phrase: &str) {
+    *PHRASE.write().unwrap() = Some(phrase.to_string());
+}
+
+/// The fixtures of the phrase in use: [`set_phrase`]'s, else
+/// `$VULN_LAB_SEED_PHRASE`, else [`DEFAULT_PHRASE`]
+pub fn current() -> Fixtures {
+    match PHRASE.read().unwrap().as_deref() {
+        Some(phrase) => Fixtures::new(phrase),
+        None => Fixtures {
+            workshop: Workshop::from_env(),
+        },
+    }
+}
+
+/// [`current`]'s keypair for `label`
+pub fn keypair(label: &str) -> Keypair {
+    current().keypair(label)
+}
+
+/// [`current`]'s address for `label`
+pub fn address(label: &str) -> Pubkey {
+    current().address(label)
+}
+
+/// Runs `f`, returning every label it derived on this thread, in the
+/// order first derived, with its address; recordings can nest
+pub fn recorded<R>(f: impl FnOnce() -> R) -> (R, Vec<(String, Pubkey)>) {
+    let outer = RECORDED.replace(Some(Vec::new()));
+    let result = f();
+    let recorded = RECORDED.replace(outer).unwrap_or_default();
+    // An enclosing recording saw them derived too
+    RECORDED.with_borrow_mut(|outer| {
+        if let Some(outer) = outer {
+            for (label, address) in &recorded {
+                if !outer.iter().any(|(known, _)| known == label) {
+                    outer.push((label.clone(), *address));
+                }
+            }
+        }
+    });
+    (result, recorded)
+}
@@ -0,0 +1,213 @@
+//! Getting demo wallets onto a real cluster, and their SOL back off it
+//!
+//! The in-process runtime airdrops instantly; faucets do not. They cap each
+//! request and rate-limit bursts, so [`Funding::fund`] splits what each
+//! wallet is short into requests of at most [`Funding::max_airdrop`], sends
+//! them a batch at a time, waits for each batch to confirm, and retries
+//! whatever the faucet refused after a delay that doubles each round.
+//!
+//! Demo wallets are derived from a keypair the presenter already has, so
+//! they are the same on every run and nobody else can spend from them, and
+//! [`reclaim`] sends what is left in them back to that keypair afterwards.
+
+use std::collections::VecDeque;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Result as AnyResult};
+use lab_svm::{Keypair, Signature, Signer, Transaction, LAMPORTS_PER_SOL};
+use solana_program::hash::hashv;
+use solana_program::pubkey::Pubkey;
+use solana_system_interface::instruction as system_instruction;
+
+pub use lab_client::{default_keypair_path, read_keypair};
+
+use crate::rpc::Rpc;
+
+/// The roles the exploits use, as demo wallet labels
+pub const ROLES: [&str; 3] = ["owner", "victim", "attacker"];
+
+/// Delays between rounds of retries
+#[derive(Clone, Copy, Debug)]
+pub struct Backoff {
+    pub initial: Duration,
+    pub max: Duration,
+    /// Rounds of retries before giving up
+    pub attempts: u32,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self {
+            initial: Duration::from_millis(500),
+            max: Duration::from_secs(16),
+            attempts: 6,
+        }
+    }
+}
+
+impl Backoff {
+    /// The wait before retry round `round`, counting from 0
+    pub fn delay(&self, round: u32) -> Duration {
+        self.initial
+            .saturating_mul(2u32.saturating_pow(round))
+            .min(self.max)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Funding {
+    /// Lamports per airdrop request; devnet's faucet refuses more than
+    /// a few SOL at once
+    pub max_airdrop: u64,
+    /// Requests sent before waiting for them to confirm
+    pub batch: usize,
+    pub backoff: Backoff,
+    /// How long a batch may take to confirm before its unconfirmed
+    /// requests count as refused
+    pub confirm_timeout: Duration,
+}
+
+impl Default for Funding {
+    fn default() -> Self {
+        Self {
+            max_airdrop: LAMPORTS_PER_SOL,
+            batch: 4,
+            backoff: Backoff::default(),
+            confirm_timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+impl Funding {
+    /// Airdrops each address up to its target balance; addresses already
+    /// at or above their target get nothing
+    pub fn fund(&self, rpc: &Rpc, targets: &[(Pubkey, u64)]) -> AnyResult<()> {
+        let mut queue = VecDeque::new();
+        for (address, target) in targets {
+            let mut short = target.saturating_sub(rpc.balance(address)?);
+            while short > 0 {
+                let lamports = short.min(self.max_airdrop);
+                queue.push_back((*address, lamports));
+                short -= lamports;
+            }
+        }
+
+        let mut round = 0;
+        while !queue.is_empty() {
+            let batch: Vec<(Pubkey, u64)> =
+                queue.drain(..self.batch.max(1).min(queue.len())).collect();
+            let mut refused = Vec::new();
+            let mut last_error = None;
+            let mut sent = Vec::new();
+            for request in batch {
+                match rpc.request_airdrop(&request.0, request.1) {
+                    Ok(signature) => sent.push((request, signature)),
+                    Err(err) => {
+                        last_error = Some(err);
+                        refused.push(request);
+                    }
+                }
+            }
+            let signatures: Vec<Signature> = sent.iter().map(|(_, signature)| *signature).collect();
+            for ((request, _), landed) in sent.into_iter().zip(self.confirm(rpc, &signatures)?) {
+                if !landed {
+                    refused.push(request);
+                }
+            }
+
+            if refused.is_empty() {
+                round = 0;
+                continue;
+            }
+            if round == self.backoff.attempts {
+                let reason = last_error.map_or_else(
+                    || "airdrops did not confirm".to_string(),
+                    |err| format!("{err:#}"),
+                );
+                bail!(
+                    "the faucet refused {} airdrops after {} retries: {reason}",
+                    refused.len() + queue.len(),
+                    self.backoff.attempts
+                );
+            }
+            thread::sleep(self.backoff.delay(round));
+            round += 1;
+            for request in refused.into_iter().rev() {
+                queue.push_front(request);
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether each signature landed, waiting up to `confirm_timeout`
+    fn confirm(&self, rpc: &Rpc, signatures: &[Signature]) -> AnyResult<Vec<bool>> {
+        let deadline = Instant::now() + self.confirm_timeout;
+        loop {
+            let statuses = if signatures.is_empty() {
+                Vec::new()
+            } else {
+                rpc.signature_statuses(signatures)?
+            };
+            if statuses.iter().all(Option::is_some) || Instant::now() >= deadline {
+                return Ok(statuses
+                    .into_iter()
+                    .map(|status| status.unwrap_or(false))
+                    .collect());
+            }
+            thread::sleep(Duration::from_millis(500));
+        }
+    }
+}
+
+/// A wallet derived from `base` and a label; the same pair always gives
+/// the same wallet
+pub fn demo_wallet(base: &Keypair, label: &str) -> Keypair {
+    let secret = base.to_bytes();
+    let seed = hashv(&[b"vuln-lab demo wallet", &secret[..32], label.as_bytes()]);
+    Keypair::new_from_array(seed.to_bytes())
+}
+
+/// One demo wallet per role in [`ROLES`]
+pub fn demo_wallets(base: &Keypair) -> Vec<(&'static str, Keypair)> {
+    ROLES
+        .iter()
+        .map(|role| (*role, demo_wallet(base, role)))
+        .collect()
+}
+
+/// Sends everything the wallets hold to `to` in one transaction, which `to`
+/// pays the fee for; returns the lamports moved
+pub fn reclaim(rpc: &Rpc, wallets: &[&Keypair], to: &Keypair) -> AnyResult<u64> {
+    let mut instructions = Vec::new();
+    let mut signers = vec![to];
+    let mut total = 0;
+    for wallet in wallets {
+        let balance = rpc.balance(&wallet.pubkey())?;
+        if balance > 0 {
+            instructions.push(system_instruction::transfer(
+                &wallet.pubkey(),
+                &to.pubkey(),
+                balance,
+            ));
+            signers.push(wallet);
+            total += balance;
+        }
+    }
+    if instructions.is_empty() {
+        return Ok(0);
+    }
+
+    let transaction = Transaction::new_signed_with_payer(
+        &instructions,
+        Some(&to.pubkey()),
+        &signers,
+        rpc.latest_blockhash()?,
+    );
+    let signature = rpc.send_transaction(&transaction)?;
+    let landed = Funding::default().confirm(rpc, &[signature])?;
+    if landed != [true] {
+        bail!("reclaim transaction {signature} did not confirm");
+    }
+    Ok(total)
+}
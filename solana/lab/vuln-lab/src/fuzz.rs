@@ -0,0 +1,853 @@
+//! Arbitrary instructions against both builds of an example, for the
+//! cargo-fuzz targets in `fuzz/`
+//!
+//! [`run`] decodes the fuzzer's bytes into one instruction: up to
+//! [`MAX_ACCOUNTS`] account metas, each naming an address from a small
+//! pool, signed or not and writable or not, with an account behind it
+//! holding some lamports and data, owned by the program or by the system
+//! program. The data is raw bytes, or, for a length byte of [`TYPED_STATE`]
+//! or more, one of the `lab_domain` account types built by its `Arbitrary`
+//! impl and serialized, so the fuzzer reaches past the deserialization that
+//! byte noise rarely gets through. Whatever bytes are left are the
+//! instruction data. The pool is small so that the same address turns up
+//! twice, and it includes the system program and the program itself, so
+//! CPIs and self-references get tried too. Every program crate is
+//! registered, and the instruction goes to the vulnerable build, then to
+//! the secure one, each on a fresh [`Svm`].
+//!
+//! The runtime turns a program's panic into a failed instruction and logs
+//! it, as the on-chain runtime does, so triage reads the logs:
+//!
+//! - a panic in the vulnerable build is expected. Those versions index
+//!   instruction data and unwrap without checking lengths, which is not
+//!   what they are there to teach, so [`run`] returns the message and the
+//!   fuzzer carries on
+//! - a panic in the secure build is a finding: [`run`] panics with its
+//!   message, and libFuzzer saves the input under `fuzz/artifacts/`. The
+//!   secure versions parse everything they read and fail with an error, so
+//!   a panic there is a bug in the fix, even where the on-chain runtime
+//!   would have rejected the transaction anyway
+//!
+//! `cargo fuzz` builds with debug assertions, so arithmetic that wraps on
+//! chain, such as `arithmetic_errors`'s, panics instead. In a vulnerable
+//! build that is the bug it teaches; in a secure one it is a missing
+//! checked operation. Replay a saved input with [`execute`] to see which
+//! build panicked and where.
+//!
+//! [`flow`] fuzzes whole flows instead. It starts from the state an
+//! example's honest use runs against and decodes the fuzzer's bytes into a
+//! few of that use's instructions, in any order and any number of times,
+//! with accounts swapped for others the flow knows about, a forged copy of
+//! one, or signatures left off. Each step goes to both builds, and the bug
+//! is a step where they part: one the vulnerable build takes and the secure
+//! build refuses, one both take but that moves lamports differently in
+//! each, or one after which the vulnerable build's accounts break one of
+//! the example's [invariants] and the secure build's do not. Account data
+//! is not compared, since the fixes lay some of it out differently. Most
+//! examples' exploits are a few such steps, so the fuzzer rediscovers them
+//! without being told what the bug is. A panic or a broken invariant in the
+//! secure build is a finding, as with [`run`]. The decoded [`Step`]s replay
+//! with [`replay`], which is what [`witness`](crate::witness) minimizes.
+//!
+//! [`exploit`] gives the fuzzer an objective instead of a crash. It
+//! decodes [`Move`]s: steps whose instruction can also have its tag
+//! changed and its amount replaced with what one of the flow's accounts
+//! holds, so the fuzzer can send a handler the honest use never calls and
+//! hit an exact balance. They go to the vulnerable build alone, and
+//! [`attack`] stops at the first that leaves the attacker with more
+//! lamports than it started with or its key where a program account held
+//! someone else's. Only an instruction the honest use would have sent is
+//! signed by its signers; anything tampered with is signed by the
+//! attacker or not at all.
+
+use std::collections::BTreeSet;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use arbitrary::{Arbitrary, Unstructured};
+use borsh::{to_vec, BorshSerialize};
+use lab_domain::{EscrowAccount, StakingPool, UserProfile, UserStake, VaultConfig};
+use lab_svm::{
+    Account, Keypair, Signer, Svm, TransactionResult, VersionedTransaction, LAMPORTS_PER_SOL,
+};
+use solana_common::discriminator::AccountDiscriminator;
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::pubkey::Pubkey;
+use solana_system_interface::program as system_program;
+
+use crate::invariants::{self, Violation};
+use crate::programs::{self, PROGRAMS};
+use crate::{attacker, fixtures, Harness, Version, REGISTRY};
+
+/// The most account metas one instruction gets
+pub const MAX_ACCOUNTS: usize = 8;
+
+/// Addresses an account meta picks from: six wallets that can sign, then
+/// the system program and the program under test
+const POOL: usize = 8;
+const SYSTEM_PROGRAM: usize = 6;
+const PROGRAM: usize = 7;
+
+/// A length byte from here on names an account type instead: a
+/// `StakingPool`, `UserStake`, `VaultConfig`, `VaultConfig` behind its
+/// discriminator, `EscrowAccount` or `UserProfile`, in that order. Above
+/// those, it is a length again
+pub const TYPED_STATE: u8 = 0xf8;
+
+/// What the runtime logs when a program panics
+const PANICKED: &str = "Program log: panicked: ";
+
+/// Runs `input` against both of `example`'s builds. Returns the vulnerable
+/// build's panic message, if it panicked; panics if the secure build does
+pub fn run(example: &str, input: &[u8]) -> Option<String> {
+    let vulnerable = execute(example, Version::Vulnerable, input);
+    if let Some(message) = execute(example, Version::Secure, input) {
+        panic!("{example}'s secure build panicked: {message}");
+    }
+    vulnerable
+}
+
+/// Runs `input` against one of `example`'s builds; the message it panicked
+/// with, if it did
+pub fn execute(example: &str, version: Version, input: &[u8]) -> Option<String> {
+    let failed = send(example, version, input).err()?;
+    failed
+        .meta
+        .logs
+        .iter()
+        .find_map(|line| line.strip_prefix(PANICKED))
+        .map(String::from)
+}
+
+/// Whether one of `example`'s builds takes the instruction `input` decodes
+/// to, as it would an honest caller's
+pub fn takes(example: &str, version: Version, input: &[u8]) -> bool {
+    send(example, version, input).is_ok()
+}
+
+fn send(example: &str, version: Version, input: &[u8]) -> TransactionResult {
+    let krate = match version {
+        Version::Vulnerable => example.to_string(),
+        Version::Secure => format!("{example}_secure"),
+    };
+    let program_id = programs::id(&krate).unwrap_or_else(|| panic!("no program crate {krate}"));
+
+    let mut svm = Svm::new();
+    for (_, id, entrypoint) in PROGRAMS {
+        svm.add_program_with_id(*id, *entrypoint);
+    }
+    let wallets: Vec<Keypair> = (0..SYSTEM_PROGRAM).map(wallet).collect();
+    let payer = Keypair::new_from_array([0xfa; 32]);
+    svm.airdrop(&payer.pubkey(), LAMPORTS_PER_SOL);
+
+    let mut input = Bytes(input);
+    let mut metas = Vec::new();
+    let mut signers = vec![&payer];
+    for _ in 0..usize::from(input.byte()) % (MAX_ACCOUNTS + 1) {
+        let flags = input.byte();
+        let index = usize::from(flags >> 3) % POOL;
+        let (is_signer, is_writable) = (flags & 1 != 0, flags & 2 != 0);
+        let owner = if flags & 4 != 0 {
+            program_id
+        } else {
+            system_program::ID
+        };
+        let lamports = u64::from(input.byte()) * LAMPORTS_PER_SOL / 16;
+        let data = match input.byte() {
+            len if len < TYPED_STATE => input.take(usize::from(len)),
+            kind => input.state(kind - TYPED_STATE),
+        };
+
+        let pubkey = match index {
+            SYSTEM_PROGRAM => system_program::ID,
+            PROGRAM => program_id,
+            wallet => {
+                let pubkey = wallets[wallet].pubkey();
+                if svm.get_account(&pubkey).is_none() {
+                    let account = Account {
+                        lamports,
+                        data,
+                        owner,
+                        executable: false,
+                        rent_epoch: 0,
+                    };
+                    svm.set_account(pubkey, account);
+                }
+                if is_signer && !signers.iter().any(|signer| signer.pubkey() == pubkey) {
+                    signers.push(&wallets[wallet]);
+                }
+                pubkey
+            }
+        };
+        // Only a wallet can sign; the rest keep the flag off
+        let is_signer = is_signer && index < SYSTEM_PROGRAM;
+        metas.push(AccountMeta {
+            pubkey,
+            is_signer,
+            is_writable,
+        });
+    }
+
+    let instruction = Instruction::new_with_bytes(program_id, input.0, metas);
+    svm.send(&[instruction], &signers)
+}
+
+/// What [`run`] decodes back into `instruction`, as near as the pool
+/// allows, with `data` as its instruction data: each account the
+/// instruction names becomes the next wallet in the pool, the system
+/// program or the program, with the lamports and data `accounts` give it,
+/// and wherever an address the instruction names turns up in that data or
+/// in `data`, it becomes the wallet's too. Lamports round up to the next
+/// sixteenth of a SOL, data longer than a length byte can say is cut
+/// short, and accounts past the sixth wallet share the last
+pub(crate) fn encode(
+    instruction: &Instruction,
+    accounts: &[Option<Account>],
+    data: &[u8],
+) -> Vec<u8> {
+    let metas = &instruction.accounts[..instruction.accounts.len().min(MAX_ACCOUNTS)];
+    let mut wallets: Vec<Pubkey> = Vec::new();
+    let indices: Vec<usize> = metas
+        .iter()
+        .map(|meta| {
+            if meta.pubkey == system_program::ID {
+                return SYSTEM_PROGRAM;
+            }
+            if meta.pubkey == instruction.program_id {
+                return PROGRAM;
+            }
+            let wallet = wallets
+                .iter()
+                .position(|wallet| *wallet == meta.pubkey)
+                .unwrap_or_else(|| {
+                    wallets.push(meta.pubkey);
+                    wallets.len() - 1
+                });
+            wallet.min(SYSTEM_PROGRAM - 1)
+        })
+        .collect();
+    let rename = |bytes: &[u8]| {
+        let mut bytes = bytes.to_vec();
+        for (index, wallet) in wallets.iter().enumerate().take(SYSTEM_PROGRAM) {
+            let pool = self::wallet(index).pubkey();
+            let mut at = 0;
+            while let Some(found) = bytes[at..]
+                .windows(32)
+                .position(|window| window == wallet.as_ref())
+            {
+                at += found;
+                bytes[at..at + 32].copy_from_slice(pool.as_ref());
+                at += 32;
+            }
+        }
+        bytes
+    };
+
+    let mut bytes = vec![metas.len() as u8];
+    for ((meta, account), index) in metas.iter().zip(accounts).zip(indices) {
+        let owned = account
+            .as_ref()
+            .is_some_and(|account| account.owner == instruction.program_id);
+        bytes.push(
+            (index as u8) << 3
+                | u8::from(owned) << 2
+                | u8::from(meta.is_writable) << 1
+                | u8::from(meta.is_signer),
+        );
+        let lamports = account.as_ref().map_or(0, |account| account.lamports);
+        bytes.push(lamports.div_ceil(LAMPORTS_PER_SOL / 16).min(255) as u8);
+        let state = account
+            .as_ref()
+            .map_or(&[][..], |account| &account.data[..]);
+        let state = rename(&state[..state.len().min(usize::from(TYPED_STATE) - 1)]);
+        bytes.push(state.len() as u8);
+        bytes.extend(state);
+    }
+    bytes.extend(rename(data));
+    bytes
+}
+
+/// The pool's wallet at `index`, the same in every run
+fn wallet(index: usize) -> Keypair {
+    Keypair::new_from_array([index as u8 + 1; 32])
+}
+
+/// The most instructions one flow sends
+pub const MAX_STEPS: usize = 4;
+
+/// One step of a flow: which of the honest use's instructions to send, and
+/// what to do to it first
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Step {
+    /// The instruction, counting round the honest use's
+    pub index: u8,
+    /// Which account in the pool the forged account copies, and which of
+    /// its 32-byte words becomes the attacker's key
+    pub forged: (u8, u8),
+    /// One per account meta, in order: 1 in the low two bits swaps it for
+    /// the address in the pool the rest of the bits pick, 2 leaves its
+    /// signature off, and anything else keeps it
+    pub mutations: [u8; MAX_ACCOUNTS],
+}
+
+/// A step of a flow where the builds parted: the vulnerable build took it,
+/// and the secure build refused it, moved lamports differently, or kept an
+/// invariant the vulnerable build broke
+#[derive(Debug)]
+pub struct Divergence {
+    /// Counting from zero
+    pub step: usize,
+    /// As the vulnerable build received it
+    pub instruction: Instruction,
+    /// The steps before it, as the vulnerable build received them
+    pub earlier: Vec<Instruction>,
+    /// Why the secure build refused it; `None` if it took it too
+    pub error: Option<String>,
+    /// What each of its accounts gained or lost in lamports, in the
+    /// vulnerable build and then the secure one
+    pub lamports: [Vec<i128>; 2],
+    /// The example's invariant that the vulnerable build broke, if it did
+    pub violation: Option<Violation>,
+    /// Names for the addresses the flow knows
+    pub labels: Vec<(String, Pubkey)>,
+}
+
+/// Runs the flow `input` decodes against both of `example`'s builds, and
+/// panics at the first step where they part
+pub fn flow(example: &str, input: &[u8]) {
+    if let Some(divergence) = diverge(example, input) {
+        panic!(
+            "{example}'s builds parted at step {}: {divergence:?}",
+            divergence.step
+        );
+    }
+}
+
+/// The first step of the flow `input` decodes where `example`'s two builds
+/// part, if they do; panics if the secure build does
+pub fn diverge(example: &str, input: &[u8]) -> Option<Divergence> {
+    replay(example, &decode(input))
+}
+
+/// The flow `input` decodes to: a byte for how many steps, then each
+/// step's index, forged account and mutations
+pub fn decode(input: &[u8]) -> Vec<Step> {
+    let mut input = Bytes(input);
+    (0..1 + usize::from(input.byte()) % MAX_STEPS)
+        .map(|_| input.step())
+        .collect()
+}
+
+/// The first of `steps` where `example`'s two builds part, if they do;
+/// panics if the secure build panics or breaks one of the example's
+/// [invariants]
+pub fn replay(example: &str, steps: &[Step]) -> Option<Divergence> {
+    let mut vulnerable = Scenario::new(example, Version::Vulnerable);
+    let mut secure = Scenario::new(example, Version::Secure);
+    let pool = pool(&vulnerable, &secure);
+
+    let mut earlier = Vec::new();
+    for (index, step) in steps.iter().enumerate() {
+        let [(instruction, taken, moved), (_, refused, secure_moved)] =
+            [&mut vulnerable, &mut secure].map(|scenario| {
+                let instruction = scenario.tamper(&pool, step);
+                let before = scenario.lamports(&instruction);
+                let result = scenario.send(&instruction);
+                let moved = before
+                    .iter()
+                    .zip(scenario.lamports(&instruction))
+                    .map(|(before, after)| after - before)
+                    .collect::<Vec<_>>();
+                (instruction, result, moved)
+            });
+        if let Err(violation) = secure.invariants() {
+            panic!(
+                "{example}'s secure build broke an invariant at step {index}: {violation}; \
+                 the steps were {:?}",
+                earlier.iter().chain([&instruction]).collect::<Vec<_>>()
+            );
+        }
+        // The vulnerable build's state only changes by a step it takes
+        let violation = vulnerable.invariants().err();
+        if taken.is_ok() && (refused.is_err() || moved != secure_moved || violation.is_some()) {
+            let mut labels = vulnerable.labels.clone();
+            labels.push(("fuzz/forged".to_string(), pool[pool.len() - 1]));
+            return Some(Divergence {
+                step: index,
+                instruction,
+                earlier,
+                error: refused.err(),
+                lamports: [moved, secure_moved],
+                violation,
+                labels,
+            });
+        }
+        earlier.push(instruction);
+    }
+    None
+}
+
+/// The addresses a mutation picks from: only those both honest uses know,
+/// in the same order on both, then the forged account
+fn pool(vulnerable: &Scenario, secure: &Scenario) -> Vec<Pubkey> {
+    vulnerable
+        .addresses()
+        .intersection(&secure.addresses())
+        .copied()
+        .chain([fixtures::address("fuzz/forged")])
+        .collect()
+}
+
+/// One move of an attack: a [`Step`], and what to do to its instruction's
+/// data
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Move {
+    pub step: Step,
+    /// 0 keeps the instruction's tag, its first byte; anything else makes
+    /// it `tag - 1`
+    pub tag: u8,
+    /// 0 keeps the instruction's amount, the eight bytes after its tag;
+    /// anything else makes it what the address in the pool `amount - 1`
+    /// picks holds in lamports just then
+    pub amount: u8,
+}
+
+/// What an attack gets the attacker
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Objective {
+    /// More lamports than the attacker started with, fees paid
+    Profit { lamports: u64 },
+    /// The attacker's key where a program account's data held someone
+    /// else's
+    Takeover { account: Pubkey, offset: usize },
+}
+
+impl fmt::Display for Objective {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Objective::Profit { lamports } => {
+                write!(f, "the attacker is {lamports} lamports up")
+            }
+            Objective::Takeover { account, offset } => write!(
+                f,
+                "the attacker's key replaced another at byte {offset} of {account}"
+            ),
+        }
+    }
+}
+
+/// The step of an attack that got the attacker an [`Objective`]
+#[derive(Debug)]
+pub struct Exploit {
+    /// Counting from zero
+    pub step: usize,
+    pub objective: Objective,
+    /// It and the steps before it, as the build received them
+    pub instructions: Vec<Instruction>,
+    /// Names for the addresses the attack knows
+    pub labels: Vec<(String, Pubkey)>,
+}
+
+/// What an attack did, step by step, and what it got
+#[derive(Debug, Default)]
+pub struct Attack {
+    /// For each step it sent, the tag, whether the attacker signed and how
+    /// it went: what [`hunt`](crate::hunt) counts as coverage
+    pub outcomes: Vec<String>,
+    pub exploit: Option<Exploit>,
+}
+
+/// Runs the attack `input` decodes against `example`'s vulnerable build,
+/// and panics if it gets the attacker anything
+pub fn exploit(example: &str, input: &[u8]) {
+    if let Some(exploit) = attack(example, Version::Vulnerable, &decode_moves(input)).exploit {
+        panic!(
+            "{example}'s vulnerable build was exploited at step {}: {}: {exploit:?}",
+            exploit.step, exploit.objective
+        );
+    }
+}
+
+/// The attack `input` decodes to: a byte for how many moves, then each
+/// move's step as [`decode`] reads it, tag and amount
+pub fn decode_moves(input: &[u8]) -> Vec<Move> {
+    let mut input = Bytes(input);
+    (0..1 + usize::from(input.byte()) % MAX_STEPS)
+        .map(|_| Move {
+            step: input.step(),
+            tag: input.byte(),
+            amount: input.byte(),
+        })
+        .collect()
+}
+
+/// Sends `moves` to one of `example`'s builds, as the attacker, and stops
+/// at the first that gets the attacker an [`Objective`]; panics if the
+/// secure build panics
+pub fn attack(example: &str, version: Version, moves: &[Move]) -> Attack {
+    let vulnerable = Scenario::new(example, Version::Vulnerable);
+    let secure = Scenario::new(example, Version::Secure);
+    let pool = pool(&vulnerable, &secure);
+    let mut scenario = match version {
+        Version::Vulnerable => vulnerable,
+        Version::Secure => secure,
+    };
+    let attacker = attacker().pubkey();
+    let start = scenario.svm.lamports(&attacker);
+
+    let mut attack = Attack::default();
+    let mut instructions = Vec::new();
+    for (index, step) in moves.iter().enumerate() {
+        let mut instruction = scenario.tamper(&pool, &step.step);
+        if step.tag > 0 {
+            if let Some(tag) = instruction.data.first_mut() {
+                *tag = step.tag - 1;
+            }
+        }
+        if step.amount > 0 {
+            let source = pool[usize::from(step.amount - 1) % pool.len()];
+            let lamports = scenario.svm.lamports(&source);
+            if let Some(amount) = instruction.data.get_mut(1..9) {
+                amount.copy_from_slice(&lamports.to_le_bytes());
+            }
+        }
+
+        // The honest use's signers sign what they would have sent, and
+        // nothing else
+        if instruction != scenario.instruction(step.step.index) {
+            for meta in &mut instruction.accounts {
+                meta.is_signer &= meta.pubkey == attacker;
+            }
+        }
+
+        let before = scenario.program_accounts();
+        let result = scenario.send(&instruction);
+        attack
+            .outcomes
+            .push(outcome(&instruction, result.as_ref().err()));
+        instructions.push(instruction);
+
+        let lamports = scenario.svm.lamports(&attacker);
+        let objective = if lamports > start {
+            Some(Objective::Profit {
+                lamports: lamports - start,
+            })
+        } else {
+            before.into_iter().find_map(|(account, data)| {
+                let now = scenario.svm.get_account(&account)?;
+                let offset = (0..data.len().saturating_sub(31)).find(|&offset| {
+                    let word = &data[offset..offset + 32];
+                    now.data.get(offset..offset + 32) == Some(attacker.as_ref())
+                        && word != attacker.as_ref()
+                        && word.iter().any(|byte| *byte != 0)
+                })?;
+                Some(Objective::Takeover { account, offset })
+            })
+        };
+        if let Some(objective) = objective {
+            attack.exploit = Some(Exploit {
+                step: index,
+                objective,
+                instructions,
+                labels: scenario.labels.clone(),
+            });
+            break;
+        }
+    }
+    attack
+}
+
+/// `instruction`'s tag, whether the attacker signs it, and whether the
+/// build took it
+fn outcome(instruction: &Instruction, error: Option<&String>) -> String {
+    let signed = instruction
+        .accounts
+        .iter()
+        .any(|meta| meta.is_signer && meta.pubkey == attacker().pubkey());
+    format!(
+        "tag {:?}{}: {}",
+        instruction.data.first(),
+        if signed {
+            ", signed by the attacker"
+        } else {
+            ""
+        },
+        error.map_or("taken", String::as_str)
+    )
+}
+
+/// One build of an example, as its honest use left it just before sending
+/// anything, with what that use sent and every keypair it derived
+struct Scenario {
+    example: String,
+    version: Version,
+    svm: Svm,
+    instructions: Vec<Instruction>,
+    keypairs: Vec<Keypair>,
+    labels: Vec<(String, Pubkey)>,
+}
+
+impl Scenario {
+    fn new(example: &str, version: Version) -> Self {
+        let vulnerability = REGISTRY
+            .find(example)
+            .unwrap_or_else(|| panic!("no example {example}"));
+        let start = Arc::new(Mutex::new(None));
+        let mut harness = Harness::new(version);
+        let first = Arc::clone(&start);
+        harness.inspect(move |svm, _| {
+            first.lock().unwrap().get_or_insert_with(|| fork(svm));
+        });
+        let (result, labels) = fixtures::recorded(|| vulnerability.honest_use(&mut harness));
+        if let Some(Err(err)) = result {
+            panic!("honest use of {example} failed against the {version:?} build: {err:#}");
+        }
+
+        let mut svm = start
+            .lock()
+            .unwrap()
+            .take()
+            .unwrap_or_else(|| panic!("{example}'s honest use sends nothing"));
+        let attacker = attacker();
+        let attacker_key = attacker.pubkey();
+        svm.airdrop(&attacker_key, LAMPORTS_PER_SOL);
+        let keypairs = labels
+            .iter()
+            .map(|(label, _)| fixtures::keypair(label))
+            .chain([attacker])
+            .collect();
+        let mut labels: Vec<(String, Pubkey)> = labels;
+        labels.push(("attacker".to_string(), attacker_key));
+        Self {
+            example: example.to_string(),
+            version,
+            svm,
+            instructions: harness
+                .transactions()
+                .iter()
+                .flat_map(|transaction| decompile(&harness, transaction))
+                .collect(),
+            keypairs,
+            labels,
+        }
+    }
+
+    /// Every address the honest use's accounts and keypairs have
+    fn addresses(&self) -> BTreeSet<Pubkey> {
+        self.svm
+            .accounts()
+            .map(|(pubkey, _)| *pubkey)
+            .chain(self.keypairs.iter().map(Keypair::pubkey))
+            .collect()
+    }
+
+    /// The lamports of each of `instruction`'s accounts
+    fn lamports(&self, instruction: &Instruction) -> Vec<i128> {
+        instruction
+            .accounts
+            .iter()
+            .map(|meta| i128::from(self.svm.lamports(&meta.pubkey)))
+            .collect()
+    }
+
+    /// The first of the example's invariants its state does not keep
+    fn invariants(&self) -> Result<(), Violation> {
+        let program_id = fixtures::address(&format!("{}/program", self.example));
+        invariants::check(&self.example, &self.svm, &program_id)
+    }
+
+    fn instruction(&self, index: u8) -> Instruction {
+        self.instructions[usize::from(index) % self.instructions.len()].clone()
+    }
+
+    /// `step`'s instruction with its mutations applied, forging the
+    /// account it asks for on the way
+    fn tamper(&mut self, pool: &[Pubkey], step: &Step) -> Instruction {
+        let mut instruction = self.instruction(step.index);
+        for (meta, mutation) in instruction.accounts.iter_mut().zip(&step.mutations) {
+            match mutation % 4 {
+                1 => meta.pubkey = pool[usize::from(mutation >> 2) % pool.len()],
+                2 => meta.is_signer = false,
+                _ => {}
+            }
+        }
+        self.forge(pool, step.forged);
+        instruction
+    }
+
+    /// The address and data of every account the program owns
+    fn program_accounts(&self) -> Vec<(Pubkey, Vec<u8>)> {
+        let programs: BTreeSet<Pubkey> = self
+            .instructions
+            .iter()
+            .map(|instruction| instruction.program_id)
+            .collect();
+        self.svm
+            .accounts()
+            .filter(|(_, account)| programs.contains(&account.owner))
+            .map(|(pubkey, account)| (*pubkey, account.data.clone()))
+            .collect()
+    }
+
+    /// Writes the forged account: a copy of one in `pool`'s data, with the
+    /// attacker's key over one of its 32-byte words and no owner but the
+    /// system program
+    fn forge(&mut self, pool: &[Pubkey], (source, word): (u8, u8)) {
+        let source = pool[usize::from(source) % pool.len()];
+        let mut data = self
+            .svm
+            .get_account(&source)
+            .map(|account| account.data.clone())
+            .unwrap_or_default();
+        let offset = usize::from(word) * 32;
+        if let Some(word) = data.get_mut(offset..offset + 32) {
+            word.copy_from_slice(attacker().pubkey().as_ref());
+        }
+        let account = Account {
+            lamports: self.svm.minimum_balance(data.len()),
+            data,
+            owner: system_program::ID,
+            executable: false,
+            rent_epoch: 0,
+        };
+        self.svm
+            .set_account(fixtures::address("fuzz/forged"), account);
+    }
+
+    /// Sends `instruction` with the attacker paying, signed by whichever
+    /// keypairs it asks for; a signature no keypair can give is left off
+    fn send(&mut self, instruction: &Instruction) -> Result<(), String> {
+        let mut instruction = instruction.clone();
+        let mut signers = vec![&self.keypairs[self.keypairs.len() - 1]];
+        for meta in instruction
+            .accounts
+            .iter_mut()
+            .filter(|meta| meta.is_signer)
+        {
+            match self
+                .keypairs
+                .iter()
+                .find(|keypair| keypair.pubkey() == meta.pubkey)
+            {
+                Some(keypair) => {
+                    if !signers.iter().any(|signer| signer.pubkey() == meta.pubkey) {
+                        signers.push(keypair);
+                    }
+                }
+                None => meta.is_signer = false,
+            }
+        }
+        let failed = match self.svm.send(&[instruction], &signers) {
+            Ok(_) => return Ok(()),
+            Err(failed) => failed,
+        };
+        if self.version == Version::Secure {
+            if let Some(message) = failed
+                .meta
+                .logs
+                .iter()
+                .find_map(|line| line.strip_prefix(PANICKED))
+            {
+                panic!("{}'s secure build panicked: {message}", self.example);
+            }
+        }
+        Err(failed.err.to_string())
+    }
+}
+
+/// A copy of `svm`'s programs, accounts and clock
+fn fork(svm: &Svm) -> Svm {
+    let mut fork = Svm::new();
+    for program_id in svm.program_ids() {
+        if let Some(entrypoint) = svm.entrypoint(program_id) {
+            fork.add_program_with_id(*program_id, entrypoint);
+        }
+    }
+    for (pubkey, account) in svm.accounts() {
+        fork.set_account(*pubkey, account.clone());
+    }
+    fork.set_clock(svm.clock().clone());
+    fork
+}
+
+/// `transaction`'s instructions, as they were before it was compiled; its
+/// lookups resolve against the tables `svm` holds, and none if they cannot
+pub(crate) fn decompile(svm: &Svm, transaction: &VersionedTransaction) -> Vec<Instruction> {
+    let Ok(metas) = svm.account_metas(transaction) else {
+        return Vec::new();
+    };
+    transaction
+        .message
+        .instructions()
+        .iter()
+        .map(|compiled| Instruction {
+            program_id: metas[usize::from(compiled.program_id_index)].pubkey,
+            accounts: compiled
+                .accounts
+                .iter()
+                .map(|&index| metas[usize::from(index)].clone())
+                .collect(),
+            data: compiled.data.clone(),
+        })
+        .collect()
+}
+
+/// The fuzzer's bytes, read front to back; past the end, zeroes
+struct Bytes<'a>(&'a [u8]);
+
+impl Bytes<'_> {
+    /// A [`Step`]: its index, forged account and mutations
+    fn step(&mut self) -> Step {
+        Step {
+            index: self.byte(),
+            forged: (self.byte(), self.byte()),
+            mutations: std::array::from_fn(|_| self.byte()),
+        }
+    }
+
+    fn byte(&mut self) -> u8 {
+        match self.0.split_first() {
+            Some((first, rest)) => {
+                self.0 = rest;
+                *first
+            }
+            None => 0,
+        }
+    }
+
+    /// An account type's serialized value, built by its `Arbitrary` impl
+    /// from the next bytes; `kind` counts from [`TYPED_STATE`], and past
+    /// the last type it is a length
+    fn state(&mut self, kind: u8) -> Vec<u8> {
+        fn value<'a, T: Arbitrary<'a> + BorshSerialize>(u: &mut Unstructured<'a>) -> Vec<u8> {
+            let value = T::arbitrary(u).expect("short input reads as zeroes");
+            to_vec(&value).unwrap()
+        }
+
+        let mut u = Unstructured::new(self.0);
+        let data = match kind {
+            0 => value::<StakingPool>(&mut u),
+            1 => value::<UserStake>(&mut u),
+            2 => value::<VaultConfig>(&mut u),
+            3 => [
+                VaultConfig::DISCRIMINATOR.to_vec(),
+                value::<VaultConfig>(&mut u),
+            ]
+            .concat(),
+            4 => value::<EscrowAccount>(&mut u),
+            5 => value::<UserProfile>(&mut u),
+            _ => return self.take(usize::from(TYPED_STATE + kind)),
+        };
+        self.0 = &self.0[self.0.len() - u.len()..];
+        data
+    }
+
+    /// The next `len` bytes, zero-padded if the input runs out first
+    fn take(&mut self, len: usize) -> Vec<u8> {
+        let (taken, rest) = self.0.split_at(len.min(self.0.len()));
+        self.0 = rest;
+        let mut bytes = taken.to_vec();
+        bytes.resize(len, 0);
+        bytes
+    }
+}
@@ -0,0 +1,197 @@
+//! Exploit hunting: attacks grown toward what the attacker is after, with
+//! no exploit written down and no crash to look for
+//!
+//! [`fuzz::exploit`] is an objective for a coverage-guided fuzzer rather
+//! than a property: it panics when an attack on the vulnerable build leaves
+//! the attacker with more lamports than it started with, or its key where
+//! a program account held someone else's, and the `exploits` fuzz target
+//! hands that to libFuzzer. [`hunt`] does the same in-tree, with the
+//! build's outcomes standing in for the coverage an instrumented build
+//! would report. It keeps a corpus of attacks, starting from the honest
+//! use's first instruction, mutates one at a time, and keeps the mutant if
+//! any of its steps went somewhere no attack before it had: a tag, a set
+//! of signers and an outcome not seen together yet. An attack that gets
+//! the attacker something is [`minimize`]d as a witness is, and what is
+//! left is the exploit, down to the amount it has to send.
+
+use std::collections::BTreeSet;
+use std::fmt;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::fuzz::{self, Exploit, Move, Objective, Step, MAX_ACCOUNTS, MAX_STEPS};
+use crate::witness::{describe, name};
+use crate::{property, Category, Version, REGISTRY};
+
+/// A minimized attack that gets the attacker something from an example's
+/// vulnerable build
+#[derive(Debug)]
+pub struct Hunt {
+    pub example: &'static str,
+    pub id: &'static str,
+    pub category: Category,
+    pub moves: Vec<Move>,
+    /// What `moves` get the attacker, and where
+    pub exploit: Exploit,
+    /// How many attacks the hunt tried, the first that worked included
+    pub attacks: usize,
+}
+
+/// Tries up to `attacks` attacks grown from `seed` against `example`'s
+/// vulnerable build, and minimizes the first that gets the attacker an
+/// [`Objective`]
+pub fn hunt(example: &str, seed: u64, attacks: usize) -> Option<Hunt> {
+    let vulnerability = REGISTRY
+        .find(example)
+        .unwrap_or_else(|| panic!("no example {example}"));
+    let mut rng = StdRng::seed_from_u64(seed);
+    let honest = vec![Move::default()];
+    let mut seen: BTreeSet<String> = fuzz::attack(example, Version::Vulnerable, &honest)
+        .outcomes
+        .into_iter()
+        .collect();
+    let mut corpus = vec![honest];
+
+    for tried in 1..=attacks {
+        let mut moves = corpus[rng.gen_range(0..corpus.len())].clone();
+        for _ in 0..rng.gen_range(1..=4) {
+            mutate(&mut rng, &mut moves);
+        }
+        let attack = fuzz::attack(example, Version::Vulnerable, &moves);
+        if attack.exploit.is_some() {
+            let moves = minimize(example, moves);
+            let exploit = fuzz::attack(example, Version::Vulnerable, &moves)
+                .exploit
+                .expect("minimizing keeps the exploit");
+            return Some(Hunt {
+                example: vulnerability.example(),
+                id: vulnerability.id(),
+                category: vulnerability.category(),
+                moves,
+                exploit,
+                attacks: tried,
+            });
+        }
+        let mut new = false;
+        for outcome in attack.outcomes {
+            new |= seen.insert(outcome);
+        }
+        if new {
+            corpus.push(moves);
+        }
+    }
+    None
+}
+
+/// One change to `moves`: a move added, dropped or retargeted, or one of
+/// its accounts, forgery, tag or amount redrawn
+fn mutate(rng: &mut StdRng, moves: &mut Vec<Move>) {
+    let at = rng.gen_range(0..moves.len());
+    match rng.gen_range(0..7) {
+        0 if moves.len() < MAX_STEPS => {
+            let step = Step {
+                index: rng.gen(),
+                ..Step::default()
+            };
+            let position = rng.gen_range(0..=moves.len());
+            moves.insert(
+                position,
+                Move {
+                    step,
+                    ..Move::default()
+                },
+            );
+        }
+        1 if moves.len() > 1 => {
+            moves.remove(at);
+        }
+        0..=2 => moves[at].step.index = rng.gen(),
+        // Most instructions name a few accounts, not all eight, so the
+        // first are redrawn most. Swapped twice as often as signed off or
+        // put back
+        3 => {
+            let named = rng.gen_range(1..=MAX_ACCOUNTS);
+            let account = rng.gen_range(0..named);
+            moves[at].step.mutations[account] = match rng.gen_range(0..4) {
+                0 => 0,
+                1 => 2,
+                _ => rng.gen::<u8>() & !3 | 1,
+            }
+        }
+        4 => moves[at].step.forged = (rng.gen(), rng.gen()),
+        // Tags are small, and most instructions keep theirs
+        5 => moves[at].tag = rng.gen_range(0..=8),
+        _ => moves[at].amount = rng.gen(),
+    }
+}
+
+/// `moves`, which exploit `example`'s vulnerable build, with every move
+/// and change they still exploit it without taken out
+pub fn minimize(example: &str, moves: Vec<Move>) -> Vec<Move> {
+    let exploits = |moves: &[Move]| match fuzz::attack(example, Version::Vulnerable, moves).exploit
+    {
+        Some(_) => Err(String::new()),
+        None => Ok(()),
+    };
+    let (mut moves, _) = property::shrink(moves, String::new(), exploits);
+
+    for index in 0..moves.len() {
+        for account in 0..MAX_ACCOUNTS {
+            if moves[index].step.mutations[account] == 0 {
+                continue;
+            }
+            let mut fewer = moves.clone();
+            fewer[index].step.mutations[account] = 0;
+            if exploits(&fewer).is_err() {
+                moves = fewer;
+            }
+        }
+        // The forged account, tag and amount, each only where it counts
+        let undo: [fn(&mut Move); 3] = [
+            |step| step.step.forged = (0, 0),
+            |step| step.tag = 0,
+            |step| step.amount = 0,
+        ];
+        for undo in undo {
+            let mut plainer = moves.clone();
+            undo(&mut plainer[index]);
+            if plainer != moves && exploits(&plainer).is_err() {
+                moves = plainer;
+            }
+        }
+    }
+    moves
+}
+
+impl fmt::Display for Hunt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let exploit = &self.exploit;
+        writeln!(
+            f,
+            "{} {} ({}), after {} attacks:",
+            self.id, self.example, self.category, self.attacks
+        )?;
+        for (index, instruction) in exploit.instructions.iter().enumerate() {
+            writeln!(
+                f,
+                "  step {index}: {}",
+                describe(instruction, &exploit.labels)
+            )?;
+        }
+        match exploit.objective {
+            Objective::Profit { lamports } => writeln!(
+                f,
+                "  after step {}, the attacker is {lamports} lamports up",
+                exploit.step
+            ),
+            Objective::Takeover { account, offset } => writeln!(
+                f,
+                "  after step {}, the attacker's key is at byte {offset} of {}, \
+                 where someone else's was",
+                exploit.step,
+                name(&account, &exploit.labels)
+            ),
+        }
+    }
+}
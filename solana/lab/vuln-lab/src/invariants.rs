@@ -0,0 +1,77 @@
+//! What must hold of an example's accounts after every operation, whatever
+//! the operation was
+//!
+//! An [`Invariant`] reads the accounts a program owns and says whether they
+//! still add up: a staking pool counts every stake, an escrow holds what it
+//! owes. Each example's exploit module implements its own, and [`of`]
+//! lists them. The harnesses check them after every operation they send:
+//! the flow fuzzer after each step, on both builds, and property tests
+//! from [`check`], so a violation comes back with the sequence that made
+//! it. An invariant holds of an honest use from its first transaction to
+//! its last, in both builds; the vulnerable build breaking one where the
+//! secure build does not is the bug, and the secure build breaking one is
+//! a finding.
+
+use std::fmt;
+
+use lab_svm::{Account, Svm};
+use solana_program::pubkey::Pubkey;
+
+use crate::exploits;
+
+/// Something that holds of a program's accounts between operations
+pub trait Invariant: Sync {
+    /// What holds, as a formula over the accounts' fields
+    fn name(&self) -> &'static str;
+    /// Whether it holds of the accounts `program_id` owns in `svm`, and if
+    /// not, what does not add up
+    fn check(&self, svm: &Svm, program_id: &Pubkey) -> Result<(), String>;
+}
+
+/// An invariant that did not hold
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Violation {
+    pub invariant: &'static str,
+    pub reason: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} does not hold: {}", self.invariant, self.reason)
+    }
+}
+
+/// `example`'s invariants; none for an example whose state has nothing to
+/// add up
+pub fn of(example: &str) -> &'static [&'static dyn Invariant] {
+    exploits::invariants(example)
+}
+
+/// The first of `example`'s invariants that does not hold of `svm`
+pub fn check(example: &str, svm: &Svm, program_id: &Pubkey) -> Result<(), Violation> {
+    for invariant in of(example) {
+        invariant
+            .check(svm, program_id)
+            .map_err(|reason| Violation {
+                invariant: invariant.name(),
+                reason,
+            })?;
+    }
+    Ok(())
+}
+
+/// The accounts `program_id` owns, by address
+pub fn owned<'a>(
+    svm: &'a Svm,
+    program_id: &'a Pubkey,
+) -> impl Iterator<Item = (&'a Pubkey, &'a Account)> + 'a {
+    svm.accounts()
+        .filter(move |(_, account)| account.owner == *program_id)
+}
+
+/// What `account` holds beyond the rent it needs
+pub fn spendable(svm: &Svm, account: &Account) -> u64 {
+    account
+        .lamports
+        .saturating_sub(svm.minimum_balance(account.data.len()))
+}
@@ -0,0 +1,71 @@
+//! Exploit suite and CLI for the vulnerable Solana examples
+//!
+//! Every example in `solana/` has a program crate under `programs/` that
+//! compiles the example file as-is, a `_secure` crate that compiles its
+//! fixed counterpart from `solana/secure/`, and a [`Vulnerability`] in
+//! [`exploits`] whose exploit must break the first and fail against the
+//! second. Each is registered by its `#[vulnerability]` attribute and
+//! [`REGISTRY`] collects them all. Exploits run on a [`Harness`]
+//! around a fresh [`Svm`] (the in-process runtime in `svm/`), which
+//! executes the programs natively under the account rules the on-chain
+//! runtime enforces, so no validator or SBF toolchain is needed:
+//!
+//! ```bash
+//! cd solana/lab
+//! cargo test
+//! cargo run -p vuln-lab -- verify missing_signer_check
+//! ```
+//!
+//! The same crates build to SBF and deploy with the `vuln-lab` binary's
+//! `build` and `deploy` subcommands, which drive `cargo build-sbf` and the
+//! `solana` CLI. Every exploit also plants and captures a flag held by the
+//! verifier program in `ctf/`, which [`ctf`] turns into CTF challenges.
+
+pub mod analysis;
+pub mod bench;
+pub mod collisions;
+pub mod config;
+pub mod corpus;
+pub mod ctf;
+pub mod curriculum;
+pub mod diff;
+pub mod exploits;
+pub mod failure;
+pub mod fixtures;
+pub mod funding;
+pub mod fuzz;
+pub mod hunt;
+pub mod invariants;
+pub mod mutants;
+pub mod programs;
+pub mod property;
+pub mod recording;
+pub mod report;
+pub mod serve;
+pub mod simulate;
+pub mod verifiable;
+pub mod walkthrough;
+pub mod witness;
+
+pub use exploits::REGISTRY;
+pub use lab_client::{decode, layout, rpc};
+pub use lab_registry::{
+    rejected_with, vulnerability, Category, Difficulty, ExploitOutcome, Field, FieldType, Harness,
+    Layout, Reference, Registry, Severity, VerifyFailure, Version, Vulnerability,
+};
+pub use lab_svm::{Keypair, Signer, Svm};
+
+/// Deploys programs and creates their accounts
+pub fn owner() -> Keypair {
+    fixtures::current().owner()
+}
+
+/// Stands in for honest users
+pub fn victim() -> Keypair {
+    fixtures::current().victim()
+}
+
+/// Runs the exploit
+pub fn attacker() -> Keypair {
+    fixtures::current().attacker()
+}
@@ -0,0 +1,1066 @@
+//! vuln-lab: build, deploy and exploit the vulnerable Solana examples
+//!
+//! ```bash
+//! vuln-lab list
+//! vuln-lab keys [--cluster CLUSTER]
+//! vuln-lab build [example] [--cluster CLUSTER] [--overflow-checks on|off]
+//! vuln-lab deploy [example] --cluster <svm|localnet|devnet|testnet|URL>
+//! vuln-lab verify-build [example] [--cluster CLUSTER] [--no-build]
+//! vuln-lab fund [--cluster CLUSTER] [--keypair FILE] [--sol N]
+//! vuln-lab reclaim [--cluster CLUSTER] [--keypair FILE]
+//! vuln-lab inspect <address> [--cluster CLUSTER]
+//! vuln-lab diff <address>... [--cluster CLUSTER]
+//! vuln-lab exploit <example> [--simulate [--cluster CLUSTER]] [--record FILE]
+//! vuln-lab replay <recording>
+//! vuln-lab verify [example]
+//! vuln-lab report [example] [--format json|sarif] [--output FILE]
+//! vuln-lab scan [example]
+//! vuln-lab witness [example] [--flows N] [--seed N]
+//! vuln-lab hunt [example] [--attacks N] [--seed N]
+//! vuln-lab corpus [example] [--out DIR]
+//! vuln-lab mutants [example]
+//! vuln-lab serve [--addr ADDR]
+//! vuln-lab path [done <example> | reset] [--user NAME] [--store FILE]
+//! vuln-lab ctf run [example]
+//! vuln-lab ctf setup [example] --out DIR
+//! vuln-lab ctf score --url URL --challenges DIR
+//! ```
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::net::{SocketAddr, TcpListener};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use anyhow::{bail, Context, Result as AnyResult};
+use clap::{Parser, Subcommand, ValueEnum};
+use lab_detector::Rule;
+use lab_svm::{Keypair, Signer, Svm, TransactionResult, LAMPORTS_PER_SOL};
+use solana_program::pubkey::Pubkey;
+use vuln_lab::analysis;
+use vuln_lab::config;
+use vuln_lab::corpus;
+use vuln_lab::ctf::{self, Challenge, Flag};
+use vuln_lab::curriculum::{curriculum, Progress, Status};
+use vuln_lab::decode;
+use vuln_lab::diff;
+use vuln_lab::failure;
+use vuln_lab::fixtures;
+use vuln_lab::funding::{self, Funding};
+use vuln_lab::hunt;
+use vuln_lab::layout;
+use vuln_lab::mutants::{self, Outcome};
+use vuln_lab::programs::{self, Cluster, OverflowChecks};
+use vuln_lab::recording::{self, Recorder, Recording};
+use vuln_lab::report::{self, ExploitReport};
+use vuln_lab::rpc::Rpc;
+use vuln_lab::serve;
+use vuln_lab::simulate::{self, Projection};
+use vuln_lab::verifiable;
+use vuln_lab::witness;
+use vuln_lab::{ExploitOutcome, Harness, Version, Vulnerability, REGISTRY};
+
+#[derive(Parser)]
+#[command(
+    name = "vuln-lab",
+    about = "Build, deploy and exploit the vulnerable Solana examples"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+    /// The workshop phrase every demo keypair and address derives from
+    /// (default: $VULN_LAB_SEED_PHRASE, else the lab's own)
+    #[arg(long, global = true)]
+    seed_phrase: Option<String>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// List the examples and what their exploits do
+    List,
+    /// Show the seed phrase and the wallets and accounts it derives, for
+    /// matching against the instructor's
+    Fixtures {
+        /// Only this example's accounts (default: all of them)
+        example: Option<String>,
+    },
+    /// Make a keypair for every program crate that has none on a cluster
+    /// and record their ids in Programs.toml
+    Keys {
+        #[arg(long, default_value = "devnet")]
+        cluster: Cluster,
+    },
+    /// Build program crates to SBF with cargo build-sbf
+    Build {
+        /// Only this example's crates (default: all of them)
+        example: Option<String>,
+        /// The cluster whose ids in Programs.toml the programs declare
+        #[arg(long, default_value = "localnet")]
+        cluster: Cluster,
+        /// on or off: build with overflow checks set, into
+        /// target/deploy/overflow-checks-<on|off>/
+        #[arg(long)]
+        overflow_checks: Option<OverflowChecks>,
+    },
+    /// Deploy built programs to their ids in Programs.toml, or register them
+    /// with the in-process runtime
+    Deploy {
+        /// Only this example's crates (default: all of them)
+        example: Option<String>,
+        /// svm, localnet, devnet, testnet or an RPC URL; never mainnet
+        #[arg(long, default_value = "localnet")]
+        cluster: Cluster,
+    },
+    /// Rebuild programs reproducibly and check their hashes against the
+    /// bytecode deployed at their ids
+    VerifyBuild {
+        /// Only this example's crates (default: all of them)
+        example: Option<String>,
+        #[arg(long, default_value = "localnet")]
+        cluster: Cluster,
+        /// Hash what is already in target/deploy instead of rebuilding
+        #[arg(long)]
+        no_build: bool,
+    },
+    /// Airdrop SOL to the demo wallets derived from a keypair, riding out
+    /// faucet rate limits
+    Fund {
+        #[arg(long, default_value = "devnet")]
+        cluster: Cluster,
+        /// Defaults to the Solana CLI's keypair
+        #[arg(long)]
+        keypair: Option<PathBuf>,
+        /// Balance to top each wallet up to
+        #[arg(long, default_value_t = 1.0)]
+        sol: f64,
+    },
+    /// Send what the demo wallets hold back to the keypair they derive from
+    Reclaim {
+        #[arg(long, default_value = "devnet")]
+        cluster: Cluster,
+        /// Defaults to the Solana CLI's keypair
+        #[arg(long)]
+        keypair: Option<PathBuf>,
+    },
+    /// Show an account on a cluster, decoded as the type its owning
+    /// example program stores
+    Inspect {
+        address: Pubkey,
+        #[arg(long, default_value = "localnet")]
+        cluster: Cluster,
+    },
+    /// Read accounts on a cluster, wait for Enter, read them again and
+    /// show what changed, field by field, e.g. while a PoC runs
+    Diff {
+        #[arg(required = true)]
+        addresses: Vec<Pubkey>,
+        #[arg(long, default_value = "localnet")]
+        cluster: Cluster,
+    },
+    /// Run an example's exploit against the vulnerable program and show
+    /// its transactions
+    Exploit {
+        example: String,
+        /// Simulate the attack instead of landing it, and show the balance
+        /// changes and logs it would produce
+        #[arg(long)]
+        simulate: bool,
+        /// Also simulate it here, e.g. on a validator started from this
+        /// example's `ctf setup`
+        #[arg(long, requires = "simulate")]
+        cluster: Option<Cluster>,
+        /// Write every transaction, with the accounts it ran against, here
+        /// for `replay`
+        #[arg(long, conflicts_with = "simulate")]
+        record: Option<PathBuf>,
+    },
+    /// Re-execute a recorded exploit on a fresh runtime and check that
+    /// every transaction ends as it did
+    Replay { recording: PathBuf },
+    /// Run an exploit against both versions and report whether the secure
+    /// one blocks it
+    Verify {
+        /// Only this example (default: all of them)
+        example: Option<String>,
+    },
+    /// Run an exploit against both versions and tell, from the logs, which
+    /// of the secure version's checks each attack transaction got past
+    Explain { example: String },
+    /// Run exploits against both versions and write the results for
+    /// graders and other tools
+    Report {
+        /// Only this example (default: all of them)
+        example: Option<String>,
+        #[arg(long, value_enum, default_value_t = Format::Json)]
+        format: Format,
+        /// Write here instead of stdout
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Run the static detector over both versions and compare what it
+    /// finds with each example's registered category
+    Scan {
+        /// Only this example (default: all of them)
+        example: Option<String>,
+    },
+    /// Search for flows that an example's vulnerable build takes and its
+    /// secure build does not, and print the shortest one found
+    Witness {
+        /// Only this example (default: all of them)
+        example: Option<String>,
+        /// How many random flows to try per example
+        #[arg(long, default_value_t = 3000)]
+        flows: usize,
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+    },
+    /// Grow attacks on an example's vulnerable build until one leaves the
+    /// attacker lamports up or holding someone else's authority, and print
+    /// it minimized
+    Hunt {
+        /// Only this example (default: all of them)
+        example: Option<String>,
+        /// How many attacks to try per example
+        #[arg(long, default_value_t = 6000)]
+        attacks: usize,
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+    },
+    /// Write seed corpora and dictionaries for the fuzz targets, built from
+    /// the instructions the examples' honest uses send
+    Corpus {
+        /// Only this example (default: all of them)
+        example: Option<String>,
+        /// The fuzz crate, under which corpus/ and dictionaries/ go
+        #[arg(long, default_value = "fuzz")]
+        out: PathBuf,
+    },
+    /// Take each check out of the secure versions in turn and run the
+    /// secure_checks tests against what is left; fails if any survives
+    Mutants {
+        /// Only this example (default: all of them)
+        example: Option<String>,
+    },
+    /// Serve the examples and their exploits over JSON-RPC, with progress
+    /// streamed as server-sent events, for a web front-end to drive
+    Serve {
+        #[arg(long, default_value = "127.0.0.1:8900")]
+        addr: SocketAddr,
+        /// Let pages from this origin, as http://localhost:3000, call in
+        #[arg(long)]
+        allow_origin: Option<String>,
+    },
+    /// Show the examples in the order to learn them, and how far a user
+    /// has got
+    Path {
+        #[command(subcommand)]
+        command: Option<PathCommand>,
+        /// Whose progress (default: $USER)
+        #[arg(long, global = true)]
+        user: Option<String>,
+        /// Where progress is kept (default: ~/.local/share/vuln-lab/progress.json)
+        #[arg(long, global = true)]
+        store: Option<PathBuf>,
+    },
+    /// Capture-the-flag challenges built from the examples
+    Ctf {
+        #[command(subcommand)]
+        command: CtfCommand,
+    },
+}
+
+#[derive(Subcommand)]
+enum CtfCommand {
+    /// Run the reference exploits in-process and score the flags they
+    /// capture
+    Run {
+        /// Only this example (default: all of them)
+        example: Option<String>,
+    },
+    /// Set challenges up to their flags and write them out as
+    /// solana-test-validator fixtures, one directory per example
+    Setup {
+        /// Only this example (default: all of them)
+        example: Option<String>,
+        #[arg(long, default_value = "ctf-challenges")]
+        out: PathBuf,
+    },
+    /// Read the flags back from a validator and rank their solvers
+    Score {
+        #[arg(long, default_value = "http://127.0.0.1:8899")]
+        url: String,
+        /// What `ctf setup` wrote
+        #[arg(long, default_value = "ctf-challenges")]
+        challenges: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+enum PathCommand {
+    /// Mark an example completed
+    Done { example: String },
+    /// Forget everything the user completed
+    Reset,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Json,
+    Sarif,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    if let Some(phrase) = &cli.seed_phrase {
+        fixtures::set_phrase(phrase);
+    }
+    match run(cli.command) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(err) => {
+            eprintln!("error: {err:#}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// `Ok(false)` when an exploit or a fix did not behave as registered
+fn run(command: Command) -> AnyResult<bool> {
+    match command {
+        Command::List => {
+            for vulnerability in REGISTRY.iter() {
+                println!(
+                    "{} {:<24} {:<8} {}",
+                    vulnerability.id(),
+                    vulnerability.example(),
+                    vulnerability.severity().to_string(),
+                    vulnerability.description()
+                );
+            }
+            Ok(true)
+        }
+        Command::Fixtures { example } => {
+            let fixtures = fixtures::current();
+            println!("seed phrase: {:?}", fixtures.phrase());
+            let wallets = ["owner", "victim", "attacker"];
+            for wallet in wallets {
+                println!("{wallet:<40} {}", fixtures.address(wallet));
+            }
+            for vulnerability in vulnerabilities(example.as_deref())? {
+                let (_, derived) = fixtures::recorded(|| {
+                    vulnerability.exploit(&mut Harness::new(Version::Vulnerable))
+                });
+                println!("\n{}", vulnerability.example());
+                for (label, address) in derived {
+                    if !wallets.contains(&label.as_str()) {
+                        println!("{label:<40} {address}");
+                    }
+                }
+            }
+            Ok(true)
+        }
+        Command::Keys { cluster } => {
+            let changed = config::sync(cluster.name())?;
+            for (krate, id) in &changed {
+                println!("{krate:<32} {id}");
+            }
+            if changed.is_empty() {
+                println!("Programs.toml is up to date for {}", cluster.name());
+            } else {
+                println!("rebuild with --cluster {} to declare them", cluster.name());
+            }
+            Ok(true)
+        }
+        Command::Build {
+            example,
+            cluster,
+            overflow_checks,
+        } => {
+            for krate in crates(example.as_deref())? {
+                let artifact = match overflow_checks {
+                    Some(checks) => programs::build_with(&krate, &cluster, checks)?,
+                    None => programs::build(&krate, &cluster)?,
+                };
+                println!("built {}", artifact.display());
+            }
+            Ok(true)
+        }
+        Command::Deploy { example, cluster } => {
+            let crates = crates(example.as_deref())?;
+            match cluster {
+                Cluster::Svm => {
+                    let mut svm = Svm::new();
+                    for krate in crates {
+                        let (id, entrypoint) = programs::id(&krate)
+                            .zip(programs::entrypoint(&krate))
+                            .with_context(|| format!("{krate} has no native entrypoint"))?;
+                        svm.add_program_with_id(id, entrypoint);
+                        println!("{krate:<32} {id}");
+                    }
+                }
+                Cluster::Rpc(_) => {
+                    for krate in crates {
+                        let id = programs::deploy(&krate, &cluster)?;
+                        println!("{krate:<32} {id}");
+                    }
+                }
+            }
+            Ok(true)
+        }
+        Command::VerifyBuild {
+            example,
+            cluster,
+            no_build,
+        } => {
+            let rpc = Rpc::new(cluster_url(cluster.clone())?);
+            let ids = config::ProgramIds::load()?;
+            if !no_build {
+                println!("building with {}", verifiable::toolchain()?);
+            }
+            let mut all_verified = true;
+            for krate in crates(example.as_deref())? {
+                let id = ids.get(cluster.name(), &krate).with_context(|| {
+                    format!("Programs.toml has no {} id for {krate}", cluster.name())
+                })?;
+                let artifact = if no_build {
+                    programs::artifact(&krate)
+                } else {
+                    programs::build(&krate, &cluster)?
+                };
+                let elf = fs::read(&artifact)
+                    .with_context(|| format!("reading {}", artifact.display()))?;
+                let verification = verifiable::verify(&rpc, id, &elf)?;
+                let verdict = match verification.deployed {
+                    _ if verification.verified() => "verified".to_string(),
+                    Some(deployed) => {
+                        all_verified = false;
+                        format!("MISMATCH: {id} runs {}", verifiable::hex(&deployed))
+                    }
+                    None => format!("not deployed at {id}"),
+                };
+                println!(
+                    "{krate:<32} {} {verdict}",
+                    verifiable::hex(&verification.local)
+                );
+            }
+            Ok(all_verified)
+        }
+        Command::Fund {
+            cluster,
+            keypair,
+            sol,
+        } => {
+            let rpc = Rpc::new(cluster_url(cluster)?);
+            let base = read_keypair(keypair)?;
+            let target = (sol * LAMPORTS_PER_SOL as f64) as u64;
+            let wallets = funding::demo_wallets(&base);
+            let targets: Vec<_> = wallets
+                .iter()
+                .map(|(_, wallet)| (wallet.pubkey(), target))
+                .collect();
+            Funding::default().fund(&rpc, &targets)?;
+            for (role, wallet) in &wallets {
+                println!(
+                    "{role:<8} {} {}",
+                    wallet.pubkey(),
+                    rpc.balance(&wallet.pubkey())?
+                );
+            }
+            Ok(true)
+        }
+        Command::Reclaim { cluster, keypair } => {
+            let rpc = Rpc::new(cluster_url(cluster)?);
+            let base = read_keypair(keypair)?;
+            let wallets = funding::demo_wallets(&base);
+            let wallets: Vec<&Keypair> = wallets.iter().map(|(_, wallet)| wallet).collect();
+            let reclaimed = funding::reclaim(&rpc, &wallets, &base)?;
+            println!("reclaimed {reclaimed} lamports to {}", base.pubkey());
+            Ok(true)
+        }
+        Command::Inspect { address, cluster } => {
+            let programs = config::ProgramIds::load()?.ids(cluster.name());
+            let programs: Vec<_> = programs
+                .iter()
+                .map(|(krate, id)| (krate.as_str(), *id))
+                .collect();
+            let rpc = Rpc::new(cluster_url(cluster)?);
+            let account = decode::fetch(&rpc, &address, &programs)?
+                .with_context(|| format!("no account at {address}"))?;
+            print!("{account}");
+            Ok(true)
+        }
+        Command::Diff { addresses, cluster } => {
+            let programs = config::ProgramIds::load()?.ids(cluster.name());
+            let url = cluster_url(cluster)?;
+            let rpc = Rpc::new(&url);
+            let before = diff::Snapshot::fetch(&rpc, &addresses)?;
+            println!(
+                "read {} accounts on {url}; press Enter once what you are watching for has landed",
+                addresses.len()
+            );
+            io::stdin().read_line(&mut String::new())?;
+            let after = diff::Snapshot::fetch(&rpc, &addresses)?;
+            let diffs = diff::between(&before, &after, |owner| {
+                programs
+                    .iter()
+                    .find(|(_, id)| id == owner)
+                    .map_or(&[][..], |(krate, _)| layout::layouts(krate))
+            });
+            if diffs.is_empty() {
+                println!("nothing changed");
+            }
+            print!("{}", diff::render(&diffs, io::stdout().is_terminal()));
+            Ok(true)
+        }
+        Command::Exploit {
+            example,
+            simulate: true,
+            cluster,
+            ..
+        } => {
+            let dry_run = simulate::dry_run(vulnerability(&example)?)?;
+            println!("setup:");
+            for (index, transaction) in dry_run.harness.history()[..dry_run.setup]
+                .iter()
+                .enumerate()
+            {
+                print_transaction(index, transaction);
+            }
+            println!("\nattack, simulated in-process:");
+            for (index, projection) in dry_run.projections.iter().enumerate() {
+                print_projection(dry_run.setup + index, projection);
+            }
+            if let Some(Cluster::Rpc(url)) = cluster {
+                let rpc = Rpc::new(&url);
+                println!("\nattack, simulated on {url}:");
+                for (index, transaction) in dry_run.attack.iter().enumerate() {
+                    let projection = simulate::on_cluster(&rpc, transaction)?;
+                    print_projection(dry_run.setup + index, &projection);
+                }
+            }
+            println!("\nnothing was landed");
+            Ok(true)
+        }
+        Command::Exploit {
+            example, record, ..
+        } => {
+            let vulnerability = vulnerability(&example)?;
+            let mut harness = Harness::new(Version::Vulnerable);
+            let recorder = record.as_ref().map(|_| Recorder::install(&mut harness));
+            let (harness, outcome, diffs) = diff::attack_on(harness, vulnerability);
+            for (index, transaction) in harness.history().iter().enumerate() {
+                print_transaction(index, transaction);
+            }
+            if !diffs.is_empty() {
+                println!("\nwhat the attack did to the accounts:");
+                print!("{}", diff::render(&diffs, io::stdout().is_terminal()));
+            }
+            if let (Some(recorder), Some(path)) = (recorder, record) {
+                recorder
+                    .finish(&harness, &example, Version::Vulnerable)?
+                    .save(&path)?;
+                println!("\nrecorded to {}", path.display());
+            }
+            match outcome {
+                ExploitOutcome::Succeeded => {
+                    println!("exploit succeeded: {}", vulnerability.description());
+                    Ok(true)
+                }
+                ExploitOutcome::Failed(err) => {
+                    println!("exploit failed: {}", failure::explain(&err));
+                    Ok(false)
+                }
+            }
+        }
+        Command::Replay { recording } => {
+            let recording = Recording::load(&recording)?;
+            let replay = recording::replay(&recording)?;
+            for (index, transaction) in replay.svm.history().iter().enumerate() {
+                print_transaction(index, transaction);
+            }
+            for mismatch in &replay.mismatches {
+                println!(
+                    "transaction {} ended differently: recorded {}, replayed {}",
+                    mismatch.index, mismatch.recorded, mismatch.replayed
+                );
+            }
+            let count = recording.transactions.len();
+            if replay.mismatches.is_empty() {
+                println!(
+                    "replayed {count} transactions of {}: all ended as recorded",
+                    recording.example
+                );
+            } else {
+                println!(
+                    "replayed {count} transactions of {}: {} ended differently",
+                    recording.example,
+                    replay.mismatches.len()
+                );
+            }
+            Ok(replay.mismatches.is_empty())
+        }
+        Command::Explain { example } => {
+            let analysis = analysis::analyze(vulnerability(&example)?)?;
+            print!("{}", analysis::terminal(&analysis));
+            Ok(true)
+        }
+        Command::Verify { example } => {
+            let mut all_passed = true;
+            for vulnerability in vulnerabilities(example.as_deref())? {
+                let example = vulnerability.example();
+                match vulnerability.verify() {
+                    Ok(()) => println!("{example:<24} ok: the secure version blocks the exploit"),
+                    Err(failure) => {
+                        all_passed = false;
+                        println!("{example:<24} FAILED: {failure}");
+                    }
+                }
+            }
+            Ok(all_passed)
+        }
+        Command::Report {
+            example,
+            format,
+            output,
+        } => {
+            let reports: Vec<ExploitReport> = vulnerabilities(example.as_deref())?
+                .into_iter()
+                .map(report::run)
+                .collect();
+            let rendered = match format {
+                Format::Json => report::to_json(&reports),
+                Format::Sarif => report::to_sarif(&reports),
+            };
+            match output {
+                Some(path) => fs::write(&path, rendered + "\n")
+                    .with_context(|| format!("writing {}", path.display()))?,
+                None => println!("{rendered}"),
+            }
+            Ok(reports.iter().all(|report| report.outcome.passed()))
+        }
+        Command::Scan { example } => {
+            let (mut covered, mut detected, mut secure_findings) = (0, 0, 0);
+            for vulnerability in vulnerabilities(example.as_deref())? {
+                let example = vulnerability.example();
+                let rule = Rule::for_category(vulnerability.category());
+                let findings = scan(example, Version::Vulnerable)?;
+                let verdict = match rule {
+                    Some(rule) if findings.iter().any(|finding| finding.rule == rule) => {
+                        covered += 1;
+                        detected += 1;
+                        "detected"
+                    }
+                    Some(_) => {
+                        covered += 1;
+                        "MISSED"
+                    }
+                    None => "not covered by the detector",
+                };
+                println!(
+                    "{} {example} ({}): {verdict}",
+                    vulnerability.id(),
+                    vulnerability.category()
+                );
+                for finding in &findings {
+                    println!("  solana/{example}.rs:{finding}");
+                }
+                let findings = scan(example, Version::Secure)?;
+                secure_findings += findings.len();
+                for finding in &findings {
+                    println!("  solana/secure/{example}.rs:{finding}");
+                }
+            }
+            println!(
+                "detected {detected} of the {covered} examples the detector covers; \
+                 {secure_findings} findings in secure versions"
+            );
+            Ok(true)
+        }
+        Command::Witness {
+            example,
+            flows,
+            seed,
+        } => {
+            // Off chain, msg! prints straight to stdout, and the search runs
+            // thousands of flows: search first, then print the witnesses
+            // together after the programs' chatter
+            let vulnerabilities = vulnerabilities(example.as_deref())?;
+            let witnesses: Vec<_> = vulnerabilities
+                .iter()
+                .map(|vulnerability| witness::search(vulnerability.example(), seed, flows))
+                .collect();
+            println!();
+            for (vulnerability, witness) in vulnerabilities.iter().zip(&witnesses) {
+                match witness {
+                    Some(witness) => print!("{witness}"),
+                    None => println!(
+                        "{} {} ({}): no witness in {flows} flows",
+                        vulnerability.id(),
+                        vulnerability.example(),
+                        vulnerability.category()
+                    ),
+                }
+            }
+            let found = witnesses.iter().flatten().count();
+            println!(
+                "witnesses for {found} of {} examples",
+                vulnerabilities.len()
+            );
+            Ok(true)
+        }
+        Command::Hunt {
+            example,
+            attacks,
+            seed,
+        } => {
+            // Hunted first and printed after, as witnesses are
+            let vulnerabilities = vulnerabilities(example.as_deref())?;
+            let hunts: Vec<_> = vulnerabilities
+                .iter()
+                .map(|vulnerability| hunt::hunt(vulnerability.example(), seed, attacks))
+                .collect();
+            println!();
+            for (vulnerability, hunt) in vulnerabilities.iter().zip(&hunts) {
+                match hunt {
+                    Some(hunt) => print!("{hunt}"),
+                    None => println!(
+                        "{} {} ({}): no exploit in {attacks} attacks",
+                        vulnerability.id(),
+                        vulnerability.example(),
+                        vulnerability.category()
+                    ),
+                }
+            }
+            let found = hunts.iter().flatten().count();
+            println!("exploits for {found} of {} examples", vulnerabilities.len());
+            Ok(true)
+        }
+        Command::Corpus { example, out } => {
+            // Gathered first, so the programs' msg! chatter comes before
+            // the summary
+            let vulnerabilities = vulnerabilities(example.as_deref())?;
+            let mut written = Vec::new();
+            for vulnerability in &vulnerabilities {
+                let example = vulnerability.example();
+                let counts = corpus::write(example, &out)
+                    .with_context(|| format!("writing {example}'s corpus"))?;
+                written.push((example, counts));
+            }
+            println!();
+            for (example, counts) in written {
+                println!(
+                    "{example}: {} seeds, {} flow seeds, {} dictionary entries",
+                    counts.seeds, counts.flows, counts.tokens
+                );
+            }
+            println!("wrote corpus/ and dictionaries/ under {}", out.display());
+            Ok(true)
+        }
+        Command::Mutants { example } => {
+            let vulnerabilities = vulnerabilities(example.as_deref())?;
+            println!("running the {} tests unmutated", mutants::TIER);
+            mutants::baseline()?;
+            let mut outcomes = Vec::new();
+            for vulnerability in &vulnerabilities {
+                for mutant in mutants::mutants(vulnerability.example())? {
+                    let outcome = mutants::test(&mutant)?;
+                    println!("{:<8} {mutant}", outcome.name());
+                    outcomes.push(outcome);
+                }
+            }
+            let count = |outcome| {
+                outcomes
+                    .iter()
+                    .filter(|&&candidate| candidate == outcome)
+                    .count()
+            };
+            let missed = count(Outcome::Missed);
+            println!(
+                "{} mutants: {} caught, {} unviable, {missed} missed",
+                outcomes.len(),
+                count(Outcome::Caught),
+                count(Outcome::Unviable)
+            );
+            Ok(missed == 0)
+        }
+        Command::Serve { addr, allow_origin } => {
+            let listener = TcpListener::bind(addr).with_context(|| format!("binding {addr}"))?;
+            let addr = listener.local_addr()?;
+            println!("serving JSON-RPC on http://{addr}, events on http://{addr}/events");
+            serve::run(listener, allow_origin.as_deref())?;
+            Ok(true)
+        }
+        Command::Path {
+            command,
+            user,
+            store,
+        } => {
+            let user = user
+                .or_else(|| std::env::var("USER").ok())
+                .unwrap_or_else(|| "default".into());
+            let store = store.unwrap_or_else(Progress::default_path);
+            path(command, &user, &store)
+        }
+        Command::Ctf { command } => ctf(command),
+    }
+}
+
+fn path(command: Option<PathCommand>, user: &str, store: &Path) -> AnyResult<bool> {
+    let mut progress = Progress::load(store)?;
+    match command {
+        Some(PathCommand::Done { example }) => {
+            let vulnerability = vulnerability(&example)?;
+            if let Status::Locked(waiting) = progress.status(user, vulnerability) {
+                println!("note: ahead of {}", waiting.join(", "));
+            }
+            if progress.complete(user, vulnerability.example()) {
+                progress.save(store)?;
+                println!("{user} completed {}", vulnerability.example());
+            } else {
+                println!("{user} had already completed {}", vulnerability.example());
+            }
+        }
+        Some(PathCommand::Reset) => {
+            progress.reset(user);
+            progress.save(store)?;
+            println!("reset {user}'s progress");
+        }
+        None => {
+            let path = curriculum(&REGISTRY)?;
+            let done = path
+                .iter()
+                .filter(|vulnerability| progress.completed(user, vulnerability.example()).is_some())
+                .count();
+            println!("learning path for {user}: {done} of {} done", path.len());
+            let mut next_shown = false;
+            for (step, vulnerability) in path.iter().enumerate() {
+                let (mark, status) = match progress.status(user, *vulnerability) {
+                    Status::Done(_) => ("x", String::new()),
+                    Status::Ready if !next_shown => {
+                        next_shown = true;
+                        (" ", "<- next".to_string())
+                    }
+                    Status::Ready => (" ", String::new()),
+                    Status::Locked(waiting) => (" ", format!("after {}", waiting.join(", "))),
+                };
+                let line = format!(
+                    "{:>2}. [{mark}] {} {:<24} {:<12} {status}",
+                    step + 1,
+                    vulnerability.id(),
+                    vulnerability.example(),
+                    vulnerability.difficulty().to_string(),
+                );
+                println!("{}", line.trim_end());
+            }
+        }
+    }
+    Ok(true)
+}
+
+fn ctf(command: CtfCommand) -> AnyResult<bool> {
+    match command {
+        CtfCommand::Run { example } => {
+            let mut solved = Vec::new();
+            let mut all_captured = true;
+            for vulnerability in vulnerabilities(example.as_deref())? {
+                let mut harness = Harness::new(Version::Vulnerable);
+                let outcome = vulnerability.exploit(&mut harness);
+                let points = ctf::points(vulnerability.severity());
+                let flags = ctf::flags(&harness);
+                let solvers: Vec<Pubkey> = flags
+                    .into_iter()
+                    .flat_map(|(_, flag)| flag.solvers)
+                    .collect();
+                match outcome {
+                    ExploitOutcome::Succeeded if !solvers.is_empty() => println!(
+                        "{} {:<24} {points:>3} captured by {}",
+                        vulnerability.id(),
+                        vulnerability.example(),
+                        join(&solvers)
+                    ),
+                    ExploitOutcome::Succeeded => {
+                        all_captured = false;
+                        println!(
+                            "{} {:<24} {points:>3} FAILED: no flag captured",
+                            vulnerability.id(),
+                            vulnerability.example()
+                        );
+                    }
+                    ExploitOutcome::Failed(err) => {
+                        all_captured = false;
+                        println!(
+                            "{} {:<24} {points:>3} FAILED: {}",
+                            vulnerability.id(),
+                            vulnerability.example(),
+                            failure::explain(&err)
+                        );
+                    }
+                }
+                solved.push((points, solvers));
+            }
+            print_leaderboard(ctf::leaderboard(
+                solved
+                    .iter()
+                    .map(|(points, solvers)| (*points, solvers.as_slice())),
+            ));
+            Ok(all_captured)
+        }
+        CtfCommand::Setup { example, out } => {
+            for vulnerability in vulnerabilities(example.as_deref())? {
+                let harness = ctf::setup(vulnerability)?;
+                let dir = out.join(vulnerability.example());
+                let challenge = ctf::export(vulnerability, &harness, &dir)?;
+                println!(
+                    "{} {:<24} {:>3} {}: {}",
+                    challenge.id,
+                    challenge.example,
+                    challenge.points,
+                    dir.display(),
+                    challenge.objective
+                );
+            }
+            Ok(true)
+        }
+        CtfCommand::Score { url, challenges } => {
+            let mut known = Vec::new();
+            for entry in fs::read_dir(&challenges)
+                .with_context(|| format!("reading {}", challenges.display()))?
+            {
+                let path = entry?.path().join("challenge.json");
+                if path.is_file() {
+                    let json = fs::read_to_string(&path)?;
+                    let challenge: Challenge = serde_json::from_str(&json)
+                        .with_context(|| format!("parsing {}", path.display()))?;
+                    known.push(challenge);
+                }
+            }
+            if known.is_empty() {
+                bail!(
+                    "no challenges in {}; run `vuln-lab ctf setup`",
+                    challenges.display()
+                );
+            }
+            known.sort_by(|a, b| a.id.cmp(&b.id));
+
+            let flags: HashMap<String, Flag> = Rpc::new(url)
+                .program_accounts(&ctf::VERIFIER)?
+                .into_iter()
+                .filter_map(|(address, data)| Some((address.to_string(), ctf::decode(&data)?)))
+                .collect();
+            let mut solved = Vec::new();
+            for challenge in &known {
+                let (status, solvers) = match flags.get(&challenge.flag) {
+                    None => ("flag not planted".to_string(), Vec::new()),
+                    Some(flag) if flag.solvers.is_empty() => ("unsolved".to_string(), Vec::new()),
+                    Some(flag) => (
+                        format!("captured by {}", join(&flag.solvers)),
+                        flag.solvers.clone(),
+                    ),
+                };
+                println!(
+                    "{} {:<24} {:>3} {status}",
+                    challenge.id, challenge.example, challenge.points
+                );
+                solved.push((challenge.points, solvers));
+            }
+            print_leaderboard(ctf::leaderboard(
+                solved
+                    .iter()
+                    .map(|(points, solvers)| (*points, solvers.as_slice())),
+            ));
+            Ok(true)
+        }
+    }
+}
+
+fn join(keys: &[Pubkey]) -> String {
+    keys.iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn print_leaderboard(leaderboard: Vec<(Pubkey, u64)>) {
+    if leaderboard.is_empty() {
+        println!("no flags captured");
+    }
+    for (rank, (solver, points)) in leaderboard.iter().enumerate() {
+        println!("{:>2}. {solver} {points}", rank + 1);
+    }
+}
+
+fn cluster_url(cluster: Cluster) -> AnyResult<String> {
+    match cluster {
+        Cluster::Svm => bail!("the in-process runtime has no RPC endpoint; pick a cluster"),
+        Cluster::Rpc(url) => Ok(url),
+    }
+}
+
+fn read_keypair(path: Option<PathBuf>) -> AnyResult<Keypair> {
+    funding::read_keypair(&path.unwrap_or_else(funding::default_keypair_path))
+}
+
+/// By example file name or id
+fn vulnerability(name: &str) -> AnyResult<&'static dyn Vulnerability> {
+    REGISTRY
+        .find(name)
+        .with_context(|| format!("no exploit for {name}; see `vuln-lab list`"))
+}
+
+fn scan(example: &str, version: Version) -> AnyResult<Vec<lab_detector::Finding>> {
+    lab_detector::scan_file(&programs::source(example, version))
+}
+
+/// One example, or all of them
+fn vulnerabilities(example: Option<&str>) -> AnyResult<Vec<&'static dyn Vulnerability>> {
+    Ok(match example {
+        Some(example) => vec![vulnerability(example)?],
+        None => REGISTRY.iter().collect(),
+    })
+}
+
+fn crates(example: Option<&str>) -> AnyResult<Vec<String>> {
+    let crates = match example {
+        Some(example) => programs::crates_for(example),
+        None => programs::crates(),
+    };
+    if crates.is_empty() {
+        bail!("no program crates for {}", example.unwrap_or("any example"));
+    }
+    Ok(crates)
+}
+
+fn print_projection(index: usize, projection: &Projection) {
+    let status = projection.err.as_deref().unwrap_or("ok");
+    match projection.compute_units {
+        Some(units) => println!("transaction {index} would be: {status}, {units} compute units"),
+        None => println!("transaction {index} would be: {status}"),
+    }
+    for change in &projection.changes {
+        println!(
+            "  {:<44} {:>14} -> {:>14} ({:+})",
+            change.address.to_string(),
+            change.before,
+            change.after,
+            change.delta()
+        );
+    }
+    for event in projection.events() {
+        println!("  {event:?}");
+    }
+    for line in &projection.logs {
+        println!("  {line}");
+    }
+}
+
+fn print_transaction(index: usize, transaction: &TransactionResult) {
+    let (meta, status) = match transaction {
+        Ok(meta) => (meta, "ok".to_string()),
+        Err(failed) => (failed.meta.as_ref(), failure::describe(&failed.err)),
+    };
+    println!("transaction {index} {}: {status}", meta.signature);
+    for line in &meta.logs {
+        println!("  {line}");
+    }
+}
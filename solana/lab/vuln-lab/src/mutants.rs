@@ -0,0 +1,251 @@
+//! Mutation testing for the secure handlers: each check taken out in turn,
+//! and the `secure_checks` tests run against what is left
+//!
+//! A secure example is only as good as the tests that would notice it
+//! stop being secure. cargo-mutants never sees the handlers, since a
+//! program crate compiles its example through an `include!` of a file its
+//! build script writes, so [`mutants`] makes the mutants the lab cares
+//! about itself, one for each check a secure source makes: a `?` on an
+//! `assert_*` helper or `find_and_validate`, an `if` that returns an
+//! error, and whatever a `// CHECK:` annotation is about. Each is the source
+//! with that code blanked out, line numbers kept.
+//!
+//! [`test()`] builds the secure crate from a mutant, through the build
+//! script's `LAB_SOURCE_<CRATE>`, and runs the [`TIER`] tests against it,
+//! in a target directory of their own under `target/mutants/`. As with
+//! cargo-mutants, a mutant those tests fail on is caught, one they pass
+//! is missed, and one that no longer compiles, because later code reads
+//! what the check bound, is unviable. A missed mutant is a check no test
+//! would miss.
+//!
+//! ```bash
+//! cargo run -p vuln-lab -- mutants reinitialization
+//! ```
+
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+use anyhow::{ensure, Context, Result as AnyResult};
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+use syn::{Expr, ItemFn, Stmt};
+
+use crate::programs;
+use crate::walkthrough::{annotations, Kind};
+use crate::Version;
+
+/// The test target every mutant is run against
+pub const TIER: &str = "secure_checks";
+
+/// A secure example with one check taken out
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mutant {
+    pub example: String,
+    /// 1-based first and last lines of the check
+    pub lines: (usize, usize),
+    /// The function it is in
+    pub function: Option<String>,
+    /// The first line of the check, trimmed
+    pub code: String,
+    /// What the `// CHECK:` annotation on it says, where it has one
+    pub fix: Option<String>,
+    /// The secure source without it
+    pub source: String,
+}
+
+impl Mutant {
+    /// The crate it replaces the source of
+    pub fn krate(&self) -> String {
+        format!("{}_secure", self.example)
+    }
+}
+
+impl fmt::Display for Mutant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "secure/{}.rs:{}: deleted `{}`",
+            self.example, self.lines.0, self.code
+        )?;
+        if let Some(function) = &self.function {
+            write!(f, " in {function}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Every check in `example`'s secure version, each taken out, in line
+/// order
+pub fn mutants(example: &str) -> AnyResult<Vec<Mutant>> {
+    let path = programs::source(example, Version::Secure);
+    let text = fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?;
+    let file = syn::parse_file(&text).with_context(|| format!("parsing {}", path.display()))?;
+    let mut checks = Checks::default();
+    checks.visit_file(&file);
+
+    let fixes: Vec<_> = annotations(&text)?
+        .into_iter()
+        .filter(|annotation| annotation.kind == Kind::Check)
+        .collect();
+    let mut spans = checks.spans;
+    spans.extend(fixes.iter().map(|fix| (fix.code, fix.function.clone())));
+    spans.sort();
+    spans.dedup_by_key(|(lines, _)| *lines);
+
+    let lines: Vec<&str> = text.lines().collect();
+    Ok(spans
+        .into_iter()
+        .map(|((start, end), function)| {
+            let source = lines
+                .iter()
+                .enumerate()
+                .map(|(index, line)| {
+                    if (start..=end).contains(&(index + 1)) {
+                        "\n".to_string()
+                    } else {
+                        format!("{line}\n")
+                    }
+                })
+                .collect();
+            Mutant {
+                example: example.to_string(),
+                lines: (start, end),
+                function,
+                code: lines[start - 1].trim().to_string(),
+                fix: fixes
+                    .iter()
+                    .find(|fix| fix.code == (start, end))
+                    .map(|fix| fix.text.clone()),
+                source,
+            }
+        })
+        .collect())
+}
+
+/// Statements that check something and otherwise do nothing, with the
+/// function each is in
+#[derive(Default)]
+struct Checks {
+    function: Option<String>,
+    spans: Vec<((usize, usize), Option<String>)>,
+}
+
+impl<'ast> Visit<'ast> for Checks {
+    fn visit_item_fn(&mut self, node: &'ast ItemFn) {
+        let outer = self.function.replace(node.sig.ident.to_string());
+        visit::visit_item_fn(self, node);
+        self.function = outer;
+    }
+
+    fn visit_stmt(&mut self, node: &'ast Stmt) {
+        if is_check(node) {
+            let span = node.span();
+            self.spans
+                .push(((span.start().line, span.end().line), self.function.clone()));
+        }
+        visit::visit_stmt(self, node);
+    }
+}
+
+/// `assert_*(..)?;`, `find_and_validate(..)?;` or an `if` with no `else`
+/// that ends in `return Err(..)`
+fn is_check(stmt: &Stmt) -> bool {
+    match stmt {
+        Stmt::Expr(Expr::Try(attempt), Some(_)) => {
+            let Expr::Call(call) = &*attempt.expr else {
+                return false;
+            };
+            let Expr::Path(function) = &*call.func else {
+                return false;
+            };
+            function.path.segments.last().is_some_and(|segment| {
+                let name = segment.ident.to_string();
+                name.starts_with("assert_") || name == "find_and_validate"
+            })
+        }
+        Stmt::Expr(Expr::If(guard), _) if guard.else_branch.is_none() => {
+            let Some(Stmt::Expr(Expr::Return(ret), _)) = guard.then_branch.stmts.last() else {
+                return false;
+            };
+            let Some(Expr::Call(call)) = ret.expr.as_deref() else {
+                return false;
+            };
+            matches!(&*call.func, Expr::Path(function) if function.path.is_ident("Err"))
+        }
+        _ => false,
+    }
+}
+
+/// What became of a mutant
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    /// The tests failed on it
+    Caught,
+    /// The tests passed on it: no test needs the check
+    Missed,
+    /// It did not compile
+    Unviable,
+}
+
+impl Outcome {
+    pub fn name(self) -> &'static str {
+        match self {
+            Outcome::Caught => "caught",
+            Outcome::Missed => "MISSED",
+            Outcome::Unviable => "unviable",
+        }
+    }
+}
+
+/// Runs the [`TIER`] tests against the secure versions as they are, which
+/// they must pass for any mutant's outcome to mean anything
+pub fn baseline() -> AnyResult<()> {
+    let output = cargo_test(None, false)?;
+    ensure!(
+        output.status.success(),
+        "the {TIER} tests fail against the secure versions as they are:\n{}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+    Ok(())
+}
+
+/// Builds `mutant` in place of its secure crate's source and runs the
+/// [`TIER`] tests against it
+pub fn test(mutant: &Mutant) -> AnyResult<Outcome> {
+    let path = dir().join(format!("{}-{}.rs", mutant.krate(), mutant.lines.0));
+    fs::create_dir_all(dir())?;
+    fs::write(&path, &mutant.source).with_context(|| format!("writing {}", path.display()))?;
+    let source = Some((mutant.krate(), path));
+    if !cargo_test(source.clone(), true)?.status.success() {
+        return Ok(Outcome::Unviable);
+    }
+    if cargo_test(source, false)?.status.success() {
+        Ok(Outcome::Missed)
+    } else {
+        Ok(Outcome::Caught)
+    }
+}
+
+/// Where the mutants and their builds go
+fn dir() -> PathBuf {
+    programs::lab_dir().join("target").join("mutants")
+}
+
+/// `cargo test` of the [`TIER`], with `source` in place of a crate's own,
+/// building only if `no_run`
+fn cargo_test(source: Option<(String, PathBuf)>, no_run: bool) -> AnyResult<Output> {
+    let mut command = Command::new("cargo");
+    command
+        .current_dir(programs::lab_dir())
+        .args(["test", "-p", "vuln-lab", "--test", TIER])
+        .env("CARGO_TARGET_DIR", dir().join("target"));
+    if no_run {
+        command.arg("--no-run");
+    }
+    if let Some((krate, path)) = source {
+        command.env(format!("LAB_SOURCE_{}", krate.to_uppercase()), path);
+    }
+    command.output().context("running cargo test")
+}
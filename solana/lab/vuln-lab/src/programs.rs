@@ -0,0 +1,398 @@
+//! The program crates under `programs/`, and building and deploying them
+//!
+//! Crates are named after the directory they live in: the example, its
+//! `_secure` counterpart and, where the exploit needs one, an `_attacker`
+//! program, or where overflow checks change what it does, a `_checked`
+//! twin built from the same source with them on; `ctf_verifier` is the
+//! CTF verifier from `ctf/`.
+//! `cargo build-sbf` writes each to `target/deploy/<crate>.so`, declaring
+//! the id [`config`] gives it for the cluster it is built for.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::str::FromStr;
+
+use anyhow::{bail, ensure, Context, Result as AnyResult};
+use solana_program::entrypoint::ProcessInstruction;
+use solana_program::pubkey::Pubkey;
+
+use crate::{config, Signer, Svm, Version};
+
+/// Every program crate's id, as it declares it, and native entrypoint,
+/// for the in-process runtime
+pub static PROGRAMS: &[(&str, Pubkey, ProcessInstruction)] = &[
+    (
+        "account_data_matching",
+        account_data_matching::ID,
+        account_data_matching::process_instruction,
+    ),
+    (
+        "account_data_matching_secure",
+        account_data_matching_secure::ID,
+        account_data_matching_secure::process_instruction,
+    ),
+    (
+        "account_versioning",
+        account_versioning::ID,
+        account_versioning::process_instruction,
+    ),
+    (
+        "account_versioning_secure",
+        account_versioning_secure::ID,
+        account_versioning_secure::process_instruction,
+    ),
+    (
+        "arbitrary_cpi",
+        arbitrary_cpi::ID,
+        arbitrary_cpi::process_instruction,
+    ),
+    (
+        "arbitrary_cpi_attacker",
+        arbitrary_cpi_attacker::ID,
+        arbitrary_cpi_attacker::process_instruction,
+    ),
+    (
+        "arbitrary_cpi_secure",
+        arbitrary_cpi_secure::ID,
+        arbitrary_cpi_secure::process_instruction,
+    ),
+    (
+        "arithmetic_errors",
+        arithmetic_errors::ID,
+        arithmetic_errors::process_instruction,
+    ),
+    (
+        "arithmetic_errors_checked",
+        arithmetic_errors_checked::ID,
+        arithmetic_errors_checked::process_instruction,
+    ),
+    (
+        "arithmetic_errors_secure",
+        arithmetic_errors_secure::ID,
+        arithmetic_errors_secure::process_instruction,
+    ),
+    (
+        "batch_payout",
+        batch_payout::ID,
+        batch_payout::process_instruction,
+    ),
+    (
+        "batch_payout_secure",
+        batch_payout_secure::ID,
+        batch_payout_secure::process_instruction,
+    ),
+    (
+        "ctf_verifier",
+        ctf_verifier::ID,
+        ctf_verifier::process_instruction,
+    ),
+    (
+        "missing_owner_check",
+        missing_owner_check::ID,
+        missing_owner_check::process_instruction,
+    ),
+    (
+        "missing_owner_check_secure",
+        missing_owner_check_secure::ID,
+        missing_owner_check_secure::process_instruction,
+    ),
+    (
+        "missing_signer_check",
+        missing_signer_check::ID,
+        missing_signer_check::process_instruction,
+    ),
+    (
+        "missing_signer_check_secure",
+        missing_signer_check_secure::ID,
+        missing_signer_check_secure::process_instruction,
+    ),
+    (
+        "pda_issues",
+        pda_issues::ID,
+        pda_issues::process_instruction,
+    ),
+    (
+        "pda_issues_secure",
+        pda_issues_secure::ID,
+        pda_issues_secure::process_instruction,
+    ),
+    (
+        "reinitialization",
+        reinitialization::ID,
+        reinitialization::process_instruction,
+    ),
+    (
+        "reinitialization_secure",
+        reinitialization_secure::ID,
+        reinitialization_secure::process_instruction,
+    ),
+    (
+        "rent_exemption",
+        rent_exemption::ID,
+        rent_exemption::process_instruction,
+    ),
+    (
+        "rent_exemption_secure",
+        rent_exemption_secure::ID,
+        rent_exemption_secure::process_instruction,
+    ),
+    (
+        "type_confusion",
+        type_confusion::ID,
+        type_confusion::process_instruction,
+    ),
+    (
+        "type_confusion_secure",
+        type_confusion_secure::ID,
+        type_confusion_secure::process_instruction,
+    ),
+];
+
+pub fn lab_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("..")
+}
+
+/// An example's source file, or its fixed counterpart under `secure/`
+pub fn source(example: &str, version: Version) -> PathBuf {
+    let solana = lab_dir().join("..");
+    match version {
+        Version::Vulnerable => solana.join(format!("{example}.rs")),
+        Version::Secure => solana.join("secure").join(format!("{example}.rs")),
+    }
+}
+
+pub fn deploy_dir() -> PathBuf {
+    lab_dir().join("target").join("deploy")
+}
+
+/// Every crate under `programs/`, sorted; the build script and root they
+/// share sit beside them
+pub fn crates() -> Vec<String> {
+    let mut crates: Vec<String> = fs::read_dir(lab_dir().join("programs"))
+        .map(|entries| {
+            entries
+                .filter_map(|entry| {
+                    let entry = entry.ok()?;
+                    entry.path().join("Cargo.toml").is_file().then_some(())?;
+                    entry.file_name().into_string().ok()
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    crates.sort();
+    crates
+}
+
+/// The crates one example builds from
+pub fn crates_for(example: &str) -> Vec<String> {
+    [
+        example.to_string(),
+        format!("{example}_checked"),
+        format!("{example}_secure"),
+        format!("{example}_attacker"),
+    ]
+    .into_iter()
+    .filter(|krate| crates().contains(krate))
+    .collect()
+}
+
+/// The id a crate declares, from `Programs.toml` for the cluster this
+/// binary was built for
+pub fn id(krate: &str) -> Option<Pubkey> {
+    PROGRAMS
+        .iter()
+        .find(|(name, _, _)| *name == krate)
+        .map(|(_, id, _)| *id)
+}
+
+pub fn entrypoint(krate: &str) -> Option<ProcessInstruction> {
+    PROGRAMS
+        .iter()
+        .find(|(name, _, _)| *name == krate)
+        .map(|(_, _, entrypoint)| *entrypoint)
+}
+
+/// The crate a program in the runtime was built from
+pub fn crate_name(svm: &Svm, program_id: &Pubkey) -> Option<&'static str> {
+    let entrypoint = svm.entrypoint(program_id)?;
+    PROGRAMS
+        .iter()
+        .find(|(_, _, candidate)| std::ptr::fn_addr_eq(*candidate, entrypoint))
+        .map(|(krate, _, _)| *krate)
+}
+
+pub fn artifact(krate: &str) -> PathBuf {
+    deploy_dir().join(format!("{krate}.so"))
+}
+
+/// Builds a crate to SBF with `cargo build-sbf`, declaring its id on
+/// `cluster`. The build is reproducible: same commit and toolchain, same
+/// bytes, wherever it is checked out (see [`verifiable`](crate::verifiable)).
+pub fn build(krate: &str, cluster: &Cluster) -> AnyResult<PathBuf> {
+    build_sbf(krate, cluster, &deploy_dir(), &[])?;
+    Ok(artifact(krate))
+}
+
+/// Builds a crate to SBF as [`build`] does, with overflow checks on or off,
+/// into `target/deploy/overflow-checks-<on|off>/` so that both builds sit
+/// side by side. `cargo build-sbf` leaves them off unless asked; a crate
+/// whose own profile sets them, as `arithmetic_errors_checked`'s does,
+/// keeps its setting
+pub fn build_with(krate: &str, cluster: &Cluster, checks: OverflowChecks) -> AnyResult<PathBuf> {
+    let out = checks.deploy_dir();
+    let config = format!(
+        "profile.release.overflow-checks={}",
+        checks == OverflowChecks::On
+    );
+    build_sbf(krate, cluster, &out, &["--config", &config])?;
+    Ok(out.join(format!("{krate}.so")))
+}
+
+fn build_sbf(krate: &str, cluster: &Cluster, out: &Path, cargo_args: &[&str]) -> AnyResult<()> {
+    let manifest = lab_dir().join("programs").join(krate).join("Cargo.toml");
+    ensure!(manifest.is_file(), "no program crate {krate}");
+    let cargo_home = std::env::var_os("CARGO_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| Path::new(&std::env::var_os("HOME").unwrap_or_default()).join(".cargo"));
+    // Source paths end up in panic messages; remapped, they are the same
+    // in every checkout
+    let rustflags = [
+        (lab_dir().join("..").canonicalize()?, "/solana"),
+        (cargo_home, "/cargo"),
+    ]
+    .iter()
+    .map(|(from, to)| format!("--remap-path-prefix={}={to}", from.display()))
+    .collect::<Vec<_>>()
+    .join(" ");
+    let status = Command::new("cargo")
+        .arg("build-sbf")
+        .arg("--manifest-path")
+        .arg(&manifest)
+        .arg("--sbf-out-dir")
+        .arg(out)
+        .args(["--", "--locked"])
+        .args(cargo_args)
+        .env("LAB_CLUSTER", cluster.name())
+        .env("RUSTFLAGS", rustflags)
+        .env("CARGO_INCREMENTAL", "0")
+        .status()
+        .context("running cargo build-sbf (is the Solana CLI installed?)")?;
+    ensure!(status.success(), "cargo build-sbf failed for {krate}");
+    Ok(())
+}
+
+/// Whether arithmetic that overflows panics, or wraps as it does on chain
+/// by default
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OverflowChecks {
+    On,
+    Off,
+}
+
+impl OverflowChecks {
+    pub fn name(self) -> &'static str {
+        match self {
+            OverflowChecks::On => "on",
+            OverflowChecks::Off => "off",
+        }
+    }
+
+    /// Where [`build_with`] puts crates built with these checks
+    pub fn deploy_dir(self) -> PathBuf {
+        deploy_dir().join(format!("overflow-checks-{}", self.name()))
+    }
+
+    /// The native crate built from `example`'s vulnerable source with these
+    /// checks, where the workspace builds it both ways: the example's own
+    /// crate without them, as on chain, and its `_checked` twin with them
+    pub fn native(self, example: &str) -> Option<String> {
+        let twin = format!("{example}_checked");
+        id(&twin)?;
+        Some(match self {
+            OverflowChecks::On => twin,
+            OverflowChecks::Off => example.to_string(),
+        })
+    }
+}
+
+impl FromStr for OverflowChecks {
+    type Err = anyhow::Error;
+
+    fn from_str(checks: &str) -> AnyResult<Self> {
+        match checks {
+            "on" => Ok(OverflowChecks::On),
+            "off" => Ok(OverflowChecks::Off),
+            other => bail!("unknown overflow checks {other}: expected on or off"),
+        }
+    }
+}
+
+/// Where `deploy` sends programs
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Cluster {
+    /// The in-process runtime; nothing leaves the machine
+    Svm,
+    /// A validator's JSON RPC endpoint
+    Rpc(String),
+}
+
+impl Cluster {
+    /// Whose ids in `Programs.toml` apply: the in-process runtime and any
+    /// URL that is not devnet's or testnet's count as localnet
+    pub fn name(&self) -> &'static str {
+        match self {
+            Cluster::Rpc(url) if url.contains("devnet") => "devnet",
+            Cluster::Rpc(url) if url.contains("testnet") => "testnet",
+            _ => "localnet",
+        }
+    }
+}
+
+impl FromStr for Cluster {
+    type Err = anyhow::Error;
+
+    fn from_str(cluster: &str) -> AnyResult<Self> {
+        if cluster.contains("mainnet") {
+            bail!("refusing to deploy vulnerable programs to mainnet");
+        }
+        Ok(match cluster {
+            "svm" => Cluster::Svm,
+            "localnet" => Cluster::Rpc("http://127.0.0.1:8899".into()),
+            "devnet" => Cluster::Rpc("https://api.devnet.solana.com".into()),
+            "testnet" => Cluster::Rpc("https://api.testnet.solana.com".into()),
+            url if url.starts_with("http://") || url.starts_with("https://") => {
+                Cluster::Rpc(url.into())
+            }
+            other => {
+                bail!("unknown cluster {other}: expected svm, localnet, devnet, testnet or a URL")
+            }
+        })
+    }
+}
+
+/// Deploys a built crate with `solana program deploy` to its id on
+/// `cluster`, paid for by the Solana CLI's configured keypair; returns the
+/// id
+pub fn deploy(krate: &str, cluster: &Cluster) -> AnyResult<Pubkey> {
+    let Cluster::Rpc(url) = cluster else {
+        bail!("the in-process runtime is deployed to with Svm::add_program_with_id");
+    };
+    let artifact = artifact(krate);
+    ensure!(
+        artifact.is_file(),
+        "{} not found; run `vuln-lab build` first",
+        artifact.display()
+    );
+    let ids = config::ProgramIds::load()?;
+    // Checked against Programs.toml, which the build declared
+    let keypair = config::program_keypair(&ids, cluster.name(), krate)?;
+    let status = Command::new("solana")
+        .args(["program", "deploy", "--url", url, "--program-id"])
+        .arg(config::keypair_path(cluster.name(), krate))
+        .arg(&artifact)
+        .status()
+        .context("running solana program deploy (is the Solana CLI installed?)")?;
+    ensure!(status.success(), "solana program deploy failed for {krate}");
+    Ok(keypair.pubkey())
+}
@@ -0,0 +1,100 @@
+//! Seeded property checks over sequences of operations, for the property
+//! tests under `tests/`
+//!
+//! [`check`] generates sequences of up to `max_ops` operations from a
+//! fixed seed, runs a property against each, and on the first failure
+//! shrinks the sequence, dropping one operation at a time for as long as
+//! the property still fails, so the [`Counterexample`] it returns is a
+//! sequence every one of whose operations matters. The same seed gives the
+//! same sequences on every run, so a failure in CI fails the same way
+//! locally. [`amount`] draws the amounts such sequences want: mostly the
+//! boundaries where arithmetic goes wrong, sometimes anything, and
+//! [`arbitrary()`] whole account states, such as a `lab_domain::StakingPool`.
+
+use std::fmt::{self, Debug, Display};
+
+use arbitrary::{Arbitrary, Unstructured};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// A sequence of operations the property failed on, shrunk
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Counterexample<Op> {
+    /// Which generated case failed, counting from zero
+    pub case: usize,
+    pub ops: Vec<Op>,
+    /// What the property said about `ops`
+    pub failure: String,
+}
+
+impl<Op: Debug> Display for Counterexample<Op> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "case {} failed: {}", self.case, self.failure)?;
+        for (index, op) in self.ops.iter().enumerate() {
+            writeln!(f, "  {index}: {op:?}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs `property` against `cases` sequences of one to `max_ops`
+/// operations, each drawn by `op` from an RNG seeded with `seed`; the
+/// first that fails, shrunk
+pub fn check<Op: Clone>(
+    seed: u64,
+    cases: usize,
+    max_ops: usize,
+    mut op: impl FnMut(&mut StdRng) -> Op,
+    property: impl Fn(&[Op]) -> Result<(), String>,
+) -> Result<(), Counterexample<Op>> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    for case in 0..cases {
+        let len = rng.gen_range(1..=max_ops);
+        let ops: Vec<Op> = (0..len).map(|_| op(&mut rng)).collect();
+        if let Err(failure) = property(&ops) {
+            let (ops, failure) = shrink(ops, failure, &property);
+            return Err(Counterexample { case, ops, failure });
+        }
+    }
+    Ok(())
+}
+
+/// `ops`, which `property` fails on, with every operation dropped that it
+/// still fails without
+pub fn shrink<Op: Clone>(
+    mut ops: Vec<Op>,
+    mut failure: String,
+    property: impl Fn(&[Op]) -> Result<(), String>,
+) -> (Vec<Op>, String) {
+    let mut index = 0;
+    while index < ops.len() {
+        let mut fewer = ops.clone();
+        fewer.remove(index);
+        match property(&fewer) {
+            Err(still) => (ops, failure) = (fewer, still),
+            Ok(()) => index += 1,
+        }
+    }
+    (ops, failure)
+}
+
+/// An amount to stake, transfer or withdraw: zero, one, a little, a
+/// little short of `u64::MAX` or all of it, half as often as anything
+pub fn amount(rng: &mut impl Rng) -> u64 {
+    match rng.gen_range(0..10) {
+        0 => 0,
+        1 => 1,
+        2 => rng.gen_range(2..=1_000),
+        3 => u64::MAX - rng.gen_range(0..=1_000),
+        4 => u64::MAX,
+        _ => rng.gen(),
+    }
+}
+
+/// A `T` built by its `Arbitrary` impl from random bytes, as a fuzz
+/// target would build one from its input
+pub fn arbitrary<T: for<'a> Arbitrary<'a>>(rng: &mut impl Rng) -> T {
+    let len = T::size_hint(0).1.unwrap_or(256);
+    let bytes: Vec<u8> = (0..len).map(|_| rng.gen()).collect();
+    T::arbitrary(&mut Unstructured::new(&bytes)).expect("enough bytes for any value")
+}
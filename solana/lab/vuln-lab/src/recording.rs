@@ -0,0 +1,317 @@
+//! Recording an exploit's transactions, and replaying them on a fresh
+//! runtime
+//!
+//! A [`Recorder`] installed on a runtime keeps every transaction sent to
+//! it, signed as it was sent, with the clock and every account it names as
+//! they stood just before it ran, and how it ended. [`replay`] rebuilds
+//! that on a fresh [`Svm`]: it deploys the recorded programs at their ids,
+//! puts each transaction's accounts back the way it found them and sends
+//! it again. The file needs neither the exploit's code nor its seed phrase,
+//! so a classroom run can be reproduced exactly later and elsewhere, and a
+//! transaction that ends differently there, because a program changed in
+//! between, is reported.
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use anyhow::{ensure, Context, Result as AnyResult};
+use base64::prelude::{Engine, BASE64_STANDARD};
+use lab_svm::{Account, TransactionResult, VersionedTransaction};
+use serde::{Deserialize, Serialize};
+use solana_program::clock::Clock;
+use solana_program::pubkey::Pubkey;
+
+use crate::{fixtures, programs, Svm, Version};
+
+/// A recorded run, as `vuln-lab exploit --record` writes it
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Recording {
+    pub example: String,
+    /// `vulnerable` or `secure`
+    pub version: String,
+    /// The phrase the run's keypairs derived from, for reference; replaying
+    /// does not need it
+    pub seed_phrase: String,
+    pub programs: Vec<Program>,
+    pub transactions: Vec<Step>,
+}
+
+/// A program the run had deployed
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Program {
+    #[serde(with = "address")]
+    pub id: Pubkey,
+    #[serde(rename = "crate")]
+    pub krate: String,
+}
+
+/// One transaction, and the state it ran against
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Step {
+    pub clock: Clock,
+    /// Every account the transaction names, in its order, then the
+    /// lookup tables it names them through
+    pub accounts: Vec<AccountState>,
+    /// The signed transaction, bincode in base64 as RPC nodes take it
+    pub transaction: String,
+    pub outcome: Outcome,
+}
+
+impl Step {
+    pub fn transaction(&self) -> AnyResult<VersionedTransaction> {
+        let bytes = BASE64_STANDARD.decode(&self.transaction)?;
+        Ok(bincode::deserialize(&bytes)?)
+    }
+}
+
+/// An account as a transaction found it; `None` if it did not exist
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountState {
+    #[serde(with = "address")]
+    pub address: Pubkey,
+    pub account: Option<StoredAccount>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoredAccount {
+    pub lamports: u64,
+    /// Base64
+    pub data: String,
+    #[serde(with = "address")]
+    pub owner: Pubkey,
+    pub executable: bool,
+    pub rent_epoch: u64,
+}
+
+impl From<&Account> for StoredAccount {
+    fn from(account: &Account) -> Self {
+        Self {
+            lamports: account.lamports,
+            data: BASE64_STANDARD.encode(&account.data),
+            owner: account.owner,
+            executable: account.executable,
+            rent_epoch: account.rent_epoch,
+        }
+    }
+}
+
+impl TryFrom<&StoredAccount> for Account {
+    type Error = base64::DecodeError;
+
+    fn try_from(stored: &StoredAccount) -> Result<Self, Self::Error> {
+        Ok(Account {
+            lamports: stored.lamports,
+            data: BASE64_STANDARD.decode(&stored.data)?,
+            owner: stored.owner,
+            executable: stored.executable,
+            rent_epoch: stored.rent_epoch,
+        })
+    }
+}
+
+/// How a transaction ended: its error, if any, and its logs
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Outcome {
+    pub error: Option<String>,
+    pub logs: Vec<String>,
+}
+
+impl From<&TransactionResult> for Outcome {
+    fn from(result: &TransactionResult) -> Self {
+        match result {
+            Ok(meta) => Self {
+                error: None,
+                logs: meta.logs.clone(),
+            },
+            Err(failed) => Self {
+                error: Some(failed.err.to_string()),
+                logs: failed.meta.logs.clone(),
+            },
+        }
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.error {
+            None => f.write_str("ok")?,
+            Some(error) => f.write_str(error)?,
+        }
+        write!(f, " ({} log lines)", self.logs.len())
+    }
+}
+
+/// Pubkeys in base58, as everywhere else they are shown
+mod address {
+    use std::str::FromStr;
+
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+    use solana_program::pubkey::Pubkey;
+
+    pub fn serialize<S: Serializer>(pubkey: &Pubkey, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(pubkey)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Pubkey, D::Error> {
+        let address = String::deserialize(deserializer)?;
+        Pubkey::from_str(&address).map_err(D::Error::custom)
+    }
+}
+
+impl Recording {
+    pub fn load(path: &Path) -> AnyResult<Self> {
+        let json =
+            fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        serde_json::from_str(&json).with_context(|| format!("parsing {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> AnyResult<()> {
+        let json = serde_json::to_string_pretty(self).expect("recordings serialize");
+        fs::write(path, json + "\n").with_context(|| format!("writing {}", path.display()))
+    }
+}
+
+/// The state each transaction ran against, kept by the runtime's inspector
+type Before = Arc<Mutex<Vec<(Clock, Vec<AccountState>)>>>;
+
+/// Records everything sent to a runtime from when it is installed
+pub struct Recorder {
+    before: Before,
+    /// How much history the runtime had when recording began
+    start: usize,
+}
+
+impl Recorder {
+    /// Replaces any inspector `svm` already had
+    pub fn install(svm: &mut Svm) -> Self {
+        let before = Before::default();
+        let states = Arc::clone(&before);
+        svm.inspect(move |svm, transaction| {
+            // A v0 transaction's lookups name accounts too; one whose
+            // lookups do not resolve fails before it touches any
+            let mut addresses: Vec<_> = match svm.account_metas(transaction) {
+                Ok(metas) => metas.into_iter().map(|meta| meta.pubkey).collect(),
+                Err(_) => transaction.message.static_account_keys().to_vec(),
+            };
+            let tables = transaction.message.address_table_lookups();
+            addresses.extend(
+                tables
+                    .into_iter()
+                    .flatten()
+                    .map(|lookup| lookup.account_key),
+            );
+            let accounts = addresses
+                .into_iter()
+                .map(|address| AccountState {
+                    address,
+                    account: svm.get_account(&address).map(StoredAccount::from),
+                })
+                .collect();
+            states.lock().unwrap().push((svm.clock().clone(), accounts));
+        });
+        Self {
+            before,
+            start: svm.history().len(),
+        }
+    }
+
+    /// What `svm` has been sent since [`install`](Recorder::install), as a
+    /// run of `version` of `example`
+    pub fn finish(self, svm: &Svm, example: &str, version: Version) -> AnyResult<Recording> {
+        let programs = svm
+            .program_ids()
+            .iter()
+            .map(|id| {
+                let krate = programs::crate_name(svm, id)
+                    .with_context(|| format!("no program crate for {id}"))?;
+                Ok(Program {
+                    id: *id,
+                    krate: krate.to_string(),
+                })
+            })
+            .collect::<AnyResult<_>>()?;
+        let before = std::mem::take(&mut *self.before.lock().unwrap());
+        let sent = &svm.transactions()[self.start..];
+        let results = &svm.history()[self.start..];
+        ensure!(
+            before.len() == sent.len(),
+            "recorded {} transactions of {}",
+            before.len(),
+            sent.len()
+        );
+        let transactions = before
+            .into_iter()
+            .zip(sent.iter().zip(results))
+            .map(|((clock, accounts), (transaction, result))| {
+                Ok(Step {
+                    clock,
+                    accounts,
+                    transaction: BASE64_STANDARD.encode(bincode::serialize(transaction)?),
+                    outcome: Outcome::from(result),
+                })
+            })
+            .collect::<AnyResult<_>>()?;
+        Ok(Recording {
+            example: example.to_string(),
+            version: match version {
+                Version::Vulnerable => "vulnerable",
+                Version::Secure => "secure",
+            }
+            .to_string(),
+            seed_phrase: fixtures::current().phrase().to_string(),
+            programs,
+            transactions,
+        })
+    }
+}
+
+/// A transaction that ended differently when replayed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mismatch {
+    pub index: usize,
+    pub recorded: Outcome,
+    pub replayed: Outcome,
+}
+
+/// A replayed run: the runtime it left behind, with every transaction in
+/// its history, and where it departed from the recording
+pub struct Replay {
+    pub svm: Svm,
+    pub mismatches: Vec<Mismatch>,
+}
+
+/// Re-executes `recording` on a fresh runtime
+pub fn replay(recording: &Recording) -> AnyResult<Replay> {
+    let mut svm = Svm::new();
+    for program in &recording.programs {
+        let entrypoint = programs::entrypoint(&program.krate)
+            .with_context(|| format!("no program crate {}", program.krate))?;
+        svm.add_program_with_id(program.id, entrypoint);
+    }
+    let mut mismatches = Vec::new();
+    for (index, step) in recording.transactions.iter().enumerate() {
+        let transaction = step
+            .transaction()
+            .with_context(|| format!("decoding transaction {index}"))?;
+        svm.set_clock(step.clock.clone());
+        for state in &step.accounts {
+            let account = match &state.account {
+                Some(stored) => Account::try_from(stored)
+                    .with_context(|| format!("decoding account {}", state.address))?,
+                None => Account::default(),
+            };
+            svm.set_account(state.address, account);
+        }
+        let replayed = Outcome::from(&svm.send_versioned_transaction(&transaction));
+        if replayed != step.outcome {
+            mismatches.push(Mismatch {
+                index,
+                recorded: step.outcome.clone(),
+                replayed,
+            });
+        }
+    }
+    Ok(Replay { svm, mismatches })
+}
@@ -0,0 +1,299 @@
+//! Machine-readable exploit results, as JSON or SARIF
+//!
+//! [`run`] executes an exploit the way [`Vulnerability::verify`] does, but
+//! keeps what each side did: the programs it deployed, every transaction it
+//! sent with its logs, and how the lamports of every account it touched
+//! moved.
+//! SARIF output places each confirmed exploit on the example's source file,
+//! and each bypassed fix on its `secure/` counterpart.
+
+use std::collections::HashMap;
+
+use anyhow::Result as AnyResult;
+use lab_svm::{Svm, TransactionResult};
+use serde::Serialize;
+use serde_json::{json, Value};
+use solana_program::pubkey::Pubkey;
+
+use crate::{
+    failure, rejected_with, Category, Harness, Reference, Severity, Version, Vulnerability,
+};
+
+/// One exploit, run against both versions of its example
+#[derive(Debug, Serialize)]
+pub struct ExploitReport {
+    pub id: &'static str,
+    pub example: &'static str,
+    pub category: Category,
+    pub severity: Severity,
+    pub description: &'static str,
+    pub references: &'static [Reference],
+    pub outcome: Outcome,
+    pub vulnerable: Run,
+    pub secure: Run,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    /// The exploit broke the vulnerable program and not the secure one
+    Blocked,
+    /// The exploit did not work against the vulnerable program
+    ExploitFailed,
+    /// The exploit still worked against the secure program
+    FixBypassed,
+    /// The exploit failed against the secure program, but not with the
+    /// rejection the example names
+    WrongRejection,
+}
+
+impl Outcome {
+    /// Whether the example behaves as registered
+    pub fn passed(self) -> bool {
+        self == Outcome::Blocked
+    }
+}
+
+/// One side of an exploit: the attack against one program
+#[derive(Clone, Debug, Serialize)]
+pub struct Run {
+    /// Source file of the program under attack, relative to the repository
+    pub source: String,
+    /// The program under attack, the first one the attack deploys
+    pub program_id: Option<String>,
+    /// Programs the attack deployed alongside it
+    pub other_programs: Vec<String>,
+    /// Whether the attack reached its goal
+    pub succeeded: bool,
+    /// Why it did not
+    pub error: Option<String>,
+    pub transactions: Vec<TransactionReport>,
+    pub balances: Vec<BalanceChange>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct TransactionReport {
+    pub signature: String,
+    /// `None` when the transaction succeeded
+    pub error: Option<String>,
+    pub logs: Vec<String>,
+}
+
+impl TransactionReport {
+    pub fn new(transaction: &TransactionResult) -> Self {
+        let (meta, error) = match transaction {
+            Ok(meta) => (meta, None),
+            Err(failed) => (failed.meta.as_ref(), Some(failure::describe(&failed.err))),
+        };
+        Self {
+            signature: meta.signature.to_string(),
+            error,
+            logs: meta.logs.clone(),
+        }
+    }
+}
+
+/// An account's lamports before the first transaction that touched it and
+/// after the last
+#[derive(Clone, Debug, Serialize)]
+pub struct BalanceChange {
+    pub account: String,
+    pub before: u64,
+    pub after: u64,
+}
+
+pub fn run(vulnerability: &dyn Vulnerability) -> ExploitReport {
+    let (vulnerable, _) = Run::new(vulnerability, Version::Vulnerable);
+    let (secure, rejected) = Run::new(vulnerability, Version::Secure);
+    let outcome = if !vulnerable.succeeded {
+        Outcome::ExploitFailed
+    } else if secure.succeeded {
+        Outcome::FixBypassed
+    } else if !rejected {
+        Outcome::WrongRejection
+    } else {
+        Outcome::Blocked
+    };
+    ExploitReport {
+        id: vulnerability.id(),
+        example: vulnerability.example(),
+        category: vulnerability.category(),
+        severity: vulnerability.severity(),
+        description: vulnerability.description(),
+        references: vulnerability.references(),
+        outcome,
+        vulnerable,
+        secure,
+    }
+}
+
+/// Source file of an example's program, relative to the repository
+pub fn source(example: &str, version: Version) -> String {
+    match version {
+        Version::Vulnerable => format!("solana/{example}.rs"),
+        Version::Secure => format!("solana/secure/{example}.rs"),
+    }
+}
+
+impl Run {
+    /// The attack on `version`, and whether it failed with the rejection
+    /// `vulnerability` names
+    fn new(vulnerability: &dyn Vulnerability, version: Version) -> (Self, bool) {
+        let source = source(vulnerability.example(), version);
+        let mut harness = Harness::new(version);
+        let result = vulnerability.exploit(&mut harness).into_result();
+        let rejected = matches!(&result, Err(err) if rejected_with(err, vulnerability.rejection()));
+        (Self::record(source, &harness, result), rejected)
+    }
+
+    /// What an attack did on `svm`, given how it ended
+    pub fn record(source: String, svm: &Svm, result: AnyResult<()>) -> Self {
+        let mut programs = svm.program_ids().iter().map(Pubkey::to_string);
+        let program_id = programs.next();
+
+        let mut transactions = Vec::new();
+        let mut balances: Vec<BalanceChange> = Vec::new();
+        let mut seen: HashMap<Pubkey, usize> = HashMap::new();
+        for transaction in svm.history() {
+            transactions.push(TransactionReport::new(transaction));
+            let meta = match transaction {
+                Ok(meta) => meta,
+                Err(failed) => failed.meta.as_ref(),
+            };
+            for ((key, pre), post) in meta
+                .account_keys
+                .iter()
+                .zip(&meta.pre_balances)
+                .zip(&meta.post_balances)
+            {
+                match seen.get(key) {
+                    Some(&index) => balances[index].after = *post,
+                    None => {
+                        seen.insert(*key, balances.len());
+                        balances.push(BalanceChange {
+                            account: key.to_string(),
+                            before: *pre,
+                            after: *post,
+                        });
+                    }
+                }
+            }
+        }
+
+        Self {
+            source,
+            program_id,
+            other_programs: programs.collect(),
+            succeeded: result.is_ok(),
+            error: result.err().as_ref().map(failure::explain),
+            transactions,
+            balances,
+        }
+    }
+}
+
+pub fn to_json(reports: &[ExploitReport]) -> String {
+    serde_json::to_string_pretty(reports).expect("reports serialize")
+}
+
+/// A SARIF 2.1.0 log with one rule per example
+pub fn to_sarif(reports: &[ExploitReport]) -> String {
+    let rules: Vec<Value> = reports
+        .iter()
+        .map(|report| {
+            let mut rule = json!({
+                "id": report.id,
+                "name": report.category,
+                "shortDescription": { "text": format!("{} ({})", report.category, report.example) },
+                "fullDescription": { "text": report.description },
+                "defaultConfiguration": { "level": "error" },
+                "properties": { "severity": report.severity },
+            });
+            if let Some(reference) = report.references.first() {
+                rule["helpUri"] = json!(reference.url);
+            }
+            rule
+        })
+        .collect();
+
+    let mut results = Vec::new();
+    for (index, report) in reports.iter().enumerate() {
+        let result = |kind: &str, level: &str, source: &str, text: String| {
+            json!({
+                "ruleId": report.id,
+                "ruleIndex": index,
+                "kind": kind,
+                "level": level,
+                "message": { "text": text },
+                "locations": [{
+                    "physicalLocation": { "artifactLocation": { "uri": source } }
+                }],
+                "properties": { "outcome": report.outcome },
+            })
+        };
+        let vulnerable = &report.vulnerable;
+        if vulnerable.succeeded {
+            results.push(result(
+                "fail",
+                "error",
+                &vulnerable.source,
+                format!("Exploitable: {}", report.description),
+            ));
+        } else {
+            results.push(result(
+                "fail",
+                "warning",
+                &vulnerable.source,
+                format!(
+                    "The exploit no longer works: {}",
+                    vulnerable.error.as_deref().unwrap_or("unknown error")
+                ),
+            ));
+        }
+        let secure = &report.secure;
+        if secure.succeeded {
+            results.push(result(
+                "fail",
+                "error",
+                &secure.source,
+                format!("The fix is bypassed: {}", report.description),
+            ));
+        } else if report.outcome == Outcome::WrongRejection {
+            results.push(result(
+                "fail",
+                "warning",
+                &secure.source,
+                format!(
+                    "The exploit fails, but not as the fix rejects it: {}",
+                    secure.error.as_deref().unwrap_or("unknown error")
+                ),
+            ));
+        } else {
+            results.push(result(
+                "pass",
+                "none",
+                &secure.source,
+                format!(
+                    "Blocks the exploit: {}",
+                    secure.error.as_deref().unwrap_or("unknown error")
+                ),
+            ));
+        }
+    }
+
+    let sarif = json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "vuln-lab",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                }
+            },
+            "results": results,
+        }],
+    });
+    serde_json::to_string_pretty(&sarif).expect("SARIF serializes")
+}
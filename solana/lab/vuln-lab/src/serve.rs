@@ -0,0 +1,491 @@
+//! `vuln-lab serve`: the harness behind a JSON-RPC service
+//!
+//! Lets a web front-end or classroom dashboard drive the lab over HTTP.
+//! JSON-RPC 2.0 requests, single or batched, are POSTed to `/`:
+//!
+//! - `lab_list`: every example, with its category, severity, difficulty
+//!   and references
+//! - `lab_exploit {example, version}`: queues the example's exploit on a
+//!   fresh harness, against the `"vulnerable"` build unless `version` is
+//!   `"secure"`, and returns `{run}`, the run's number
+//! - `lab_events {run}`: the run's events so far
+//!
+//! Notifications, requests without an `id`, are carried out but get no
+//! answer; a request or batch of nothing else gets an empty `204`. A body
+//! over [`MAX_BODY`] bytes is refused with `413`.
+//!
+//! Each run reports as it goes: `started`, then a `transaction` for every
+//! transaction the exploit sends, flag included, then `finished` with the
+//! whole run as `vuln-lab report` describes it, failed if the exploit
+//! panicked. `GET /events` streams them as server-sent events, replaying
+//! the history before following new runs; `GET /events?run=N` streams one
+//! run's and ends once it has finished, or at once with an `error` event if
+//! there is no such run or its events are no longer kept.
+//!
+//! [`WORKERS`] threads run the exploits, and at most [`QUEUED`] more wait
+//! for one; past that, `lab_exploit` fails until a run finishes. Only the
+//! last [`KEPT_RUNS`] runs' events are kept. A page served from another
+//! origin can call in only if [`run`] is given that origin.
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::io;
+use std::net::TcpListener as StdTcpListener;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender};
+use std::sync::{Arc, Mutex, Weak};
+use std::thread;
+
+use anyhow::anyhow;
+
+use bytes::Bytes;
+use http_body_util::combinators::BoxBody;
+use http_body_util::{BodyExt, Full, LengthLimitError, Limited, StreamBody};
+use hyper::body::{Frame, Incoming};
+use hyper::header::{self, HeaderValue};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use serde::Serialize;
+use serde_json::{json, Value};
+use tokio::net::TcpListener;
+use tokio::sync::{broadcast, mpsc};
+use tokio_stream::wrappers::ReceiverStream;
+
+use crate::report::{self, Run, TransactionReport};
+use crate::{Harness, Version, Vulnerability, REGISTRY};
+
+/// Something a run did, as sent to clients
+#[derive(Clone, Debug, Serialize)]
+struct Event {
+    run: u64,
+    #[serde(flatten)]
+    progress: Progress,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum Progress {
+    Started {
+        example: &'static str,
+        version: &'static str,
+    },
+    Transaction {
+        index: usize,
+        transaction: TransactionReport,
+    },
+    Finished {
+        result: Run,
+    },
+}
+
+/// Threads running exploits
+pub const WORKERS: usize = 4;
+/// Runs waiting for a worker, past which `lab_exploit` is refused
+pub const QUEUED: usize = 16;
+/// Runs whose events are kept, counting back from the latest; always more
+/// than can be queued or running, so no unfinished run loses its events
+pub const KEPT_RUNS: u64 = 64;
+/// Largest request body read, in bytes
+pub const MAX_BODY: usize = 64 * 1024;
+
+/// A run waiting for a worker
+struct Job {
+    run: u64,
+    vulnerability: &'static dyn Vulnerability,
+    version: Version,
+}
+
+/// The recent runs' events, the channel new ones go out on and the queue
+/// the workers take runs from
+struct Lab {
+    state: Mutex<State>,
+    live: broadcast::Sender<Event>,
+    jobs: SyncSender<Job>,
+}
+
+#[derive(Default)]
+struct State {
+    events: Vec<Event>,
+    runs: u64,
+}
+
+impl Lab {
+    /// The lab and its workers, which stop once it is dropped
+    fn new() -> Arc<Self> {
+        let (jobs, queue) = sync_channel(QUEUED);
+        let lab = Arc::new(Self {
+            state: Mutex::default(),
+            live: broadcast::channel(1024).0,
+            jobs,
+        });
+        let queue = Arc::new(Mutex::new(queue));
+        for _ in 0..WORKERS {
+            let lab = Arc::downgrade(&lab);
+            let queue = queue.clone();
+            thread::spawn(move || work(&lab, &queue));
+        }
+        lab
+    }
+
+    fn publish(&self, run: u64, progress: Progress) {
+        self.push(&mut self.state.lock().unwrap(), Event { run, progress });
+    }
+
+    /// Under the state's lock, so a subscriber sees each event exactly once
+    fn push(&self, state: &mut State, event: Event) {
+        let _ = self.live.send(event.clone());
+        state.events.push(event);
+    }
+
+    /// The events so far and a receiver for the rest
+    fn subscribe(&self) -> (Vec<Event>, broadcast::Receiver<Event>) {
+        let state = self.state.lock().unwrap();
+        (state.events.clone(), self.live.subscribe())
+    }
+
+    /// Queues `vulnerability`'s exploit for a worker; `None` if
+    /// [`QUEUED`] runs are already waiting
+    fn start(&self, vulnerability: &'static dyn Vulnerability, version: Version) -> Option<u64> {
+        // Under the lock, so a worker cannot publish before `started`
+        let mut state = self.state.lock().unwrap();
+        let run = state.runs;
+        let job = Job {
+            run,
+            vulnerability,
+            version,
+        };
+        if self.jobs.try_send(job).is_err() {
+            return None;
+        }
+        state.runs += 1;
+        state.events.retain(|event| event.run + KEPT_RUNS > run);
+        let progress = Progress::Started {
+            example: vulnerability.example(),
+            version: version_name(version),
+        };
+        self.push(&mut state, Event { run, progress });
+        Some(run)
+    }
+
+    /// Runs `job` and publishes what it does, ending with `finished` even
+    /// if the exploit panics
+    fn execute(self: &Arc<Self>, job: Job) {
+        let Job {
+            run,
+            vulnerability,
+            version,
+        } = job;
+        let mut harness = Harness::new(version);
+        let observer = self.clone();
+        let mut index = 0;
+        harness.observe(move |_, result| {
+            let transaction = TransactionReport::new(result);
+            observer.publish(run, Progress::Transaction { index, transaction });
+            index += 1;
+        });
+        let outcome = panic::catch_unwind(AssertUnwindSafe(|| {
+            vulnerability.exploit(&mut harness).into_result()
+        }))
+        .unwrap_or_else(|payload| {
+            let message = payload
+                .downcast_ref::<&str>()
+                .copied()
+                .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+                .unwrap_or("no message");
+            Err(anyhow!("the exploit panicked: {message}"))
+        });
+        let source = report::source(vulnerability.example(), version);
+        let result = Run::record(source, &harness, outcome);
+        self.publish(run, Progress::Finished { result });
+    }
+}
+
+/// A worker: takes runs off `queue` until the lab is gone
+fn work(lab: &Weak<Lab>, queue: &Mutex<Receiver<Job>>) {
+    loop {
+        let job = queue.lock().unwrap().recv();
+        match (job, lab.upgrade()) {
+            (Ok(job), Some(lab)) => lab.execute(job),
+            _ => return,
+        }
+    }
+}
+
+fn version_name(version: Version) -> &'static str {
+    match version {
+        Version::Vulnerable => "vulnerable",
+        Version::Secure => "secure",
+    }
+}
+
+/// Serves the lab on `listener` until accepting fails. A browser lets a
+/// page from `allow_origin`, as `http://localhost:3000`, read what the
+/// service answers; with none, only pages the service's own origin serves
+pub fn run(listener: StdTcpListener, allow_origin: Option<&str>) -> io::Result<()> {
+    let allow_origin = allow_origin
+        .map(HeaderValue::from_str)
+        .transpose()
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+    listener.set_nonblocking(true)?;
+    let runtime = tokio::runtime::Runtime::new()?;
+    runtime.block_on(async {
+        let listener = TcpListener::from_std(listener)?;
+        let lab = Lab::new();
+        loop {
+            let (stream, _) = listener.accept().await?;
+            let lab = lab.clone();
+            let allow_origin = allow_origin.clone();
+            tokio::spawn(async move {
+                let service =
+                    service_fn(move |request| handle(lab.clone(), allow_origin.clone(), request));
+                // A client going away mid-response is its own business
+                let _ = http1::Builder::new()
+                    .serve_connection(TokioIo::new(stream), service)
+                    .await;
+            });
+        }
+    })
+}
+
+type Body = BoxBody<Bytes, Infallible>;
+
+async fn handle(
+    lab: Arc<Lab>,
+    allow_origin: Option<HeaderValue>,
+    request: Request<Incoming>,
+) -> Result<Response<Body>, Infallible> {
+    let query = request.uri().query().unwrap_or_default().to_string();
+    let origin = request.headers().get(header::ORIGIN).cloned();
+    let mut response = match (request.method(), request.uri().path()) {
+        (&Method::POST, "/") => match Limited::new(request.into_body(), MAX_BODY).collect().await {
+            Ok(body) => rpc(&lab, &body.to_bytes()),
+            Err(err) if err.is::<LengthLimitError>() => plain(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                format!("requests are limited to {MAX_BODY} bytes"),
+            ),
+            Err(err) => plain(StatusCode::BAD_REQUEST, err.to_string()),
+        },
+        (&Method::GET, "/events") => match query_run(&query) {
+            Ok(run) => events(&lab, run),
+            Err(message) => plain(StatusCode::BAD_REQUEST, message),
+        },
+        (&Method::OPTIONS, _) => {
+            let mut response = plain(StatusCode::NO_CONTENT, String::new());
+            let headers = response.headers_mut();
+            headers.insert(
+                header::ACCESS_CONTROL_ALLOW_METHODS,
+                HeaderValue::from_static("GET, POST, OPTIONS"),
+            );
+            headers.insert(
+                header::ACCESS_CONTROL_ALLOW_HEADERS,
+                HeaderValue::from_static("content-type"),
+            );
+            response
+        }
+        _ => plain(
+            StatusCode::NOT_FOUND,
+            "POST JSON-RPC to / or GET /events".into(),
+        ),
+    };
+    if let Some(allow_origin) = allow_origin {
+        let headers = response.headers_mut();
+        headers.insert(header::VARY, HeaderValue::from_static("origin"));
+        if origin.as_ref() == Some(&allow_origin) {
+            headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, allow_origin);
+        }
+    }
+    Ok(response)
+}
+
+fn plain(status: StatusCode, text: String) -> Response<Body> {
+    let mut response = Response::new(Full::new(Bytes::from(text)).boxed());
+    *response.status_mut() = status;
+    response
+}
+
+/// `run=N` from the query string, if it has one
+fn query_run(query: &str) -> Result<Option<u64>, String> {
+    let parameters: HashMap<_, _> = query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .collect();
+    parameters
+        .get("run")
+        .map(|run| {
+            run.parse()
+                .map_err(|_| format!("run {run} is not a number"))
+        })
+        .transpose()
+}
+
+/// Streams events as server-sent events, only `run`'s if given
+fn events(lab: &Lab, run: Option<u64>) -> Response<Body> {
+    let (history, mut live) = lab.subscribe();
+    let (sender, receiver) = mpsc::channel::<Result<Frame<Bytes>, Infallible>>(64);
+    // Every kept run has its `started` event in the history
+    let unknown = run.filter(|run| !history.iter().any(|event| event.run == *run));
+    tokio::spawn(async move {
+        if let Some(run) = unknown {
+            let data = json!({ "run": run, "event": "error", "message": format!("no run {run}") });
+            let frame = format!("event: error\ndata: {data}\n\n");
+            let _ = sender.send(Ok(Frame::data(frame.into()))).await;
+            return;
+        }
+        // Whether the stream is over: the client left or the run finished
+        let send = |event: Event| {
+            let sender = sender.clone();
+            async move {
+                if run.is_some_and(|run| event.run != run) {
+                    return false;
+                }
+                let finished = matches!(event.progress, Progress::Finished { .. });
+                let frame = format!(
+                    "event: {}\ndata: {}\n\n",
+                    event_name(&event.progress),
+                    serde_json::to_string(&event).expect("events serialize")
+                );
+                sender.send(Ok(Frame::data(frame.into()))).await.is_err()
+                    || (finished && run.is_some())
+            }
+        };
+        for event in history {
+            if send(event).await {
+                return;
+            }
+        }
+        // A client that falls behind is cut off; reconnecting replays
+        while let Ok(event) = live.recv().await {
+            if send(event).await {
+                return;
+            }
+        }
+    });
+
+    let mut response = Response::new(BodyExt::boxed(StreamBody::new(ReceiverStream::new(
+        receiver,
+    ))));
+    let headers = response.headers_mut();
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("text/event-stream"),
+    );
+    headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+    response
+}
+
+fn event_name(progress: &Progress) -> &'static str {
+    match progress {
+        Progress::Started { .. } => "started",
+        Progress::Transaction { .. } => "transaction",
+        Progress::Finished { .. } => "finished",
+    }
+}
+
+/// Answers a JSON-RPC request or batch
+fn rpc(lab: &Lab, body: &[u8]) -> Response<Body> {
+    let answer = match serde_json::from_slice::<Value>(body) {
+        Err(err) => Some(error(Value::Null, -32700, format!("parse error: {err}"))),
+        Ok(Value::Array(batch)) if !batch.is_empty() => {
+            let answers: Vec<Value> = batch
+                .iter()
+                .filter_map(|request| call(lab, request))
+                .collect();
+            (!answers.is_empty()).then_some(Value::Array(answers))
+        }
+        Ok(request) => call(lab, &request),
+    };
+    let Some(answer) = answer else {
+        return plain(StatusCode::NO_CONTENT, String::new());
+    };
+    let mut response = Response::new(Full::new(Bytes::from(answer.to_string())).boxed());
+    response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/json"),
+    );
+    response
+}
+
+fn error(id: Value, code: i64, message: String) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+/// The answer to `request`; `None` if it is a notification
+fn call(lab: &Lab, request: &Value) -> Option<Value> {
+    let (Some("2.0"), Some(method)) = (
+        request.get("jsonrpc").and_then(Value::as_str),
+        request.get("method").and_then(Value::as_str),
+    ) else {
+        let id = request.get("id").cloned().unwrap_or(Value::Null);
+        return Some(error(id, -32600, "not a JSON-RPC 2.0 request".into()));
+    };
+    let params = request.get("params").cloned().unwrap_or(Value::Null);
+    let result = match method {
+        "lab_list" => Ok(list()),
+        "lab_exploit" => match exploit(lab, &params) {
+            Ok(Some(run)) => Ok(json!({ "run": run })),
+            Ok(None) => Err((
+                -32000,
+                format!("{QUEUED} runs are waiting already; try again later"),
+            )),
+            Err(message) => Err((-32602, message)),
+        },
+        "lab_events" => run_events(lab, &params).map_err(|message| (-32602, message)),
+        _ => Err((-32601, format!("no method {method}"))),
+    };
+    let id = request.get("id").cloned()?;
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err((code, message)) => error(id, code, message),
+    })
+}
+
+fn list() -> Value {
+    REGISTRY
+        .iter()
+        .map(|vulnerability| {
+            json!({
+                "id": vulnerability.id(),
+                "example": vulnerability.example(),
+                "category": vulnerability.category(),
+                "severity": vulnerability.severity(),
+                "difficulty": vulnerability.difficulty(),
+                "prerequisites": vulnerability.prerequisites(),
+                "description": vulnerability.description(),
+                "references": vulnerability.references(),
+            })
+        })
+        .collect()
+}
+
+/// The run `params` asks for, queued; `None` if the queue is full
+fn exploit(lab: &Lab, params: &Value) -> Result<Option<u64>, String> {
+    let Some(example) = params.get("example").and_then(Value::as_str) else {
+        return Err("expected {example, version}".into());
+    };
+    let vulnerability = REGISTRY
+        .find(example)
+        .ok_or_else(|| format!("no example {example}"))?;
+    let version = match params.get("version").and_then(Value::as_str) {
+        None | Some("vulnerable") => Version::Vulnerable,
+        Some("secure") => Version::Secure,
+        Some(version) => return Err(format!("version {version}: expected vulnerable or secure")),
+    };
+    Ok(lab.start(vulnerability, version))
+}
+
+fn run_events(lab: &Lab, params: &Value) -> Result<Value, String> {
+    let Some(run) = params.get("run").and_then(Value::as_u64) else {
+        return Err("expected {run}".into());
+    };
+    let (events, _) = lab.subscribe();
+    if !events.iter().any(|event| event.run == run) {
+        return Err(format!("no run {run}"));
+    }
+    let events: Vec<_> = events
+        .into_iter()
+        .filter(|event| event.run == run)
+        .collect();
+    Ok(serde_json::to_value(events).expect("events serialize"))
+}
@@ -0,0 +1,95 @@
+//! Dry runs: what an exploit's attack would do, without it landing
+//!
+//! [`dry_run`] runs an exploit on the in-process runtime as usual up to
+//! and including its flag, then switches the runtime to dry-run mode
+//! ([`Svm::set_dry_run`]), so the attack is simulated and recorded but
+//! changes nothing. The exploit then stops at its first check, finding
+//! nothing stolen. [`on_cluster`] simulates the same transaction with a
+//! cluster's `simulateTransaction`, which only makes sense against a
+//! validator holding the accounts it names: one started from this
+//! example's `ctf setup`, where the demo then leaves the flag for players.
+
+use std::cell::Cell;
+
+use anyhow::{bail, Result as AnyResult};
+use lab_svm::{Svm, TransactionResult, VersionedTransaction};
+
+use crate::rpc::Rpc;
+use crate::{Harness, Version, Vulnerability};
+
+thread_local! {
+    /// Set while [`dry_run`] runs an exploit
+    static DRY_RUN: Cell<bool> = const { Cell::new(false) };
+    /// How many transactions the exploit's setup sent, once it is done
+    static SETUP: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+/// Called by [`ctf::plant`](crate::ctf::plant): the setup is done and
+/// whatever the exploit sends next is its attack
+pub(crate) fn attack_begins(svm: &mut Svm) {
+    if DRY_RUN.get() {
+        SETUP.set(Some(svm.history().len()));
+        svm.set_dry_run(true);
+    }
+}
+
+pub use lab_client::simulate::{BalanceChange, Projection};
+
+/// What a transaction the in-process runtime simulated would have done
+fn in_process(result: &TransactionResult) -> Projection {
+    let (meta, err) = match result {
+        Ok(meta) => (meta, None),
+        Err(failed) => (failed.meta.as_ref(), Some(failed.err.to_string())),
+    };
+    Projection::new(
+        err,
+        meta.logs.clone(),
+        Some(meta.compute_units),
+        &meta.account_keys,
+        &meta.pre_balances,
+        &meta.post_balances,
+    )
+}
+
+/// An exploit run whose attack was simulated
+pub struct DryRun {
+    /// The runtime as the setup left it; the attack changed nothing
+    pub harness: Harness,
+    /// How many of the harness's transactions were the setup's
+    pub setup: usize,
+    /// The attack's transactions, as the exploit sent them
+    pub attack: Vec<VersionedTransaction>,
+    /// What each would have done, in-process
+    pub projections: Vec<Projection>,
+}
+
+/// Runs `vulnerability`'s exploit against the vulnerable build with its
+/// attack simulated
+pub fn dry_run(vulnerability: &dyn Vulnerability) -> AnyResult<DryRun> {
+    let mut harness = Harness::new(Version::Vulnerable);
+    DRY_RUN.set(true);
+    // Fails its own checks, with nothing stolen; the projections say how
+    // it would have gone
+    let _ = vulnerability.exploit(&mut harness);
+    DRY_RUN.set(false);
+    let Some(setup) = SETUP.take() else {
+        bail!(
+            "the {} exploit never plants a flag, so its attack cannot be told from its setup",
+            vulnerability.example()
+        );
+    };
+    harness.set_dry_run(false);
+
+    Ok(DryRun {
+        attack: harness.transactions()[setup..].to_vec(),
+        projections: harness.history()[setup..].iter().map(in_process).collect(),
+        setup,
+        harness,
+    })
+}
+
+/// Simulates `transaction` on the cluster behind `rpc`, as
+/// [`lab_client::simulate::simulate`] does
+pub fn on_cluster(rpc: &Rpc, transaction: &VersionedTransaction) -> AnyResult<Projection> {
+    lab_client::simulate::simulate(rpc, transaction)
+}
@@ -0,0 +1,116 @@
+//! Verifiable builds: is what a cluster runs what this source builds to?
+//!
+//! A program's on-chain bytecode can only be trusted as far as it can be
+//! rebuilt from source. [`programs::build`](crate::programs::build) builds reproducibly: with the
+//! locked dependencies, incremental compilation off and the machine's
+//! paths remapped out of the binary, two checkouts of the same commit on
+//! the same [`toolchain`] produce the same bytes. [`verify`] then reads a
+//! deployed program back from its cluster and compares hashes.
+//!
+//! Programs deployed with `solana program deploy` belong to the
+//! upgradeable loader: the program account only names a program data
+//! account, which holds a header and then the ELF, zero-padded to the size
+//! allocated for upgrades. The hash is SHA-256 over the ELF with trailing
+//! zeros trimmed, as `solana-verify get-executable-hash` and
+//! `get-program-hash` compute it, so either tool can check the result.
+
+use std::process::Command;
+
+use anyhow::{bail, ensure, Context, Result as AnyResult};
+use solana_program::hash::{hash, Hash};
+use solana_program::pubkey::Pubkey;
+
+use crate::rpc::Rpc;
+
+const BPF_LOADER_UPGRADEABLE: Pubkey =
+    solana_program::pubkey!("BPFLoaderUpgradeab1e11111111111111111111111");
+/// `UpgradeableLoaderState::Program`: a u32 tag, then the program data
+/// account's address
+const PROGRAM_TAG: u32 = 2;
+/// `UpgradeableLoaderState::ProgramData`: a u32 tag, the slot it was last
+/// deployed at and an optional upgrade authority, before the ELF
+const PROGRAM_DATA_TAG: u32 = 3;
+const PROGRAM_DATA_HEADER: usize = 4 + 8 + 1 + 32;
+
+/// SHA-256 of an executable, ignoring the zeros it is padded with
+pub fn executable_hash(elf: &[u8]) -> Hash {
+    let end = elf
+        .iter()
+        .rposition(|&byte| byte != 0)
+        .map_or(0, |last| last + 1);
+    hash(&elf[..end])
+}
+
+/// A hash as hex, the way `solana-verify` prints them
+pub fn hex(hash: &Hash) -> String {
+    hash.to_bytes()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// The version of the SBF toolchain builds are reproducible on
+pub fn toolchain() -> AnyResult<String> {
+    let output = Command::new("cargo")
+        .args(["build-sbf", "--version"])
+        .output()
+        .context("running cargo build-sbf (is the Solana CLI installed?)")?;
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// The executable a program id runs on the cluster behind `rpc`, with its
+/// padding; `None` if nothing is deployed there
+pub fn deployed(rpc: &Rpc, program_id: &Pubkey) -> AnyResult<Option<Vec<u8>>> {
+    let Some((owner, data)) = rpc.account(program_id)? else {
+        return Ok(None);
+    };
+    ensure!(
+        owner == BPF_LOADER_UPGRADEABLE,
+        "{program_id} is owned by {owner}, not the upgradeable loader"
+    );
+    let Some(program_data) = parse_program(&data) else {
+        bail!("{program_id} is not an upgradeable program account");
+    };
+    let Some((_, data)) = rpc.account(&program_data)? else {
+        bail!("{program_id}'s program data account {program_data} does not exist");
+    };
+    ensure!(
+        data.len() >= PROGRAM_DATA_HEADER && tag(&data) == Some(PROGRAM_DATA_TAG),
+        "{program_data} is not a program data account"
+    );
+    Ok(Some(data[PROGRAM_DATA_HEADER..].to_vec()))
+}
+
+fn tag(data: &[u8]) -> Option<u32> {
+    Some(u32::from_le_bytes(data.get(..4)?.try_into().ok()?))
+}
+
+/// The program data address an upgradeable program account names
+fn parse_program(data: &[u8]) -> Option<Pubkey> {
+    (tag(data)? == PROGRAM_TAG).then_some(())?;
+    Some(Pubkey::new_from_array(data.get(4..36)?.try_into().ok()?))
+}
+
+/// A local build against what is deployed at its id
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Verification {
+    pub program_id: Pubkey,
+    pub local: Hash,
+    /// `None` when nothing is deployed at the id
+    pub deployed: Option<Hash>,
+}
+
+impl Verification {
+    pub fn verified(&self) -> bool {
+        self.deployed == Some(self.local)
+    }
+}
+
+/// Compares the executable `elf` with what `program_id` runs
+pub fn verify(rpc: &Rpc, program_id: Pubkey, elf: &[u8]) -> AnyResult<Verification> {
+    Ok(Verification {
+        program_id,
+        local: executable_hash(elf),
+        deployed: deployed(rpc, &program_id)?.map(|elf| executable_hash(&elf)),
+    })
+}
@@ -0,0 +1,477 @@
+//! Walkthroughs generated from the examples' own annotations
+//!
+//! The example files mark each bug with a `// VULNERABILITY:` comment,
+//! numbered (`// VULNERABILITY 2:`) where an example has several, and
+//! their secure counterparts mark each check that fixes one with
+//! `// CHECK:`. Comment lines straight after an annotation continue it,
+//! and a `// CHECK:` in a vulnerable file, on a check it does make, is
+//! read but never paired with anything. syn keeps no comments,
+//! so [`annotations`] finds them in the text and uses syn's spans for the
+//! rest: the function each is in, and the code it is about, which is the
+//! statement, field or item that starts first after it or, for an
+//! annotation trailing a line of code, that line. [`walkthrough`] turns
+//! an example's bugs into steps in order, each with the fixes made in the
+//! same function of the secure version, and [`terminal`] and [`html`]
+//! render them with the exact lines they point at.
+
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result as AnyResult};
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+use syn::{Field, ImplItemFn, Item, ItemFn, Stmt};
+
+use crate::{programs, Category, Severity, Version, Vulnerability};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    Vulnerability,
+    Check,
+}
+
+impl Kind {
+    fn tag(self) -> &'static str {
+        match self {
+            Kind::Vulnerability => "VULNERABILITY",
+            Kind::Check => "CHECK",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Annotation {
+    pub kind: Kind,
+    /// The number after the tag, if it has one
+    pub number: Option<u32>,
+    /// The comment after the tag, continuation lines joined with spaces
+    pub text: String,
+    /// 1-based line of the tag
+    pub line: usize,
+    /// 1-based first and last lines of the code it is about
+    pub code: (usize, usize),
+    /// The function it is in
+    pub function: Option<String>,
+}
+
+/// Every `// VULNERABILITY:` and `// CHECK:` annotation in `source`, in line order
+pub fn annotations(source: &str) -> AnyResult<Vec<Annotation>> {
+    let file = syn::parse_file(source)?;
+    let mut spans = Spans::default();
+    spans.visit_file(&file);
+
+    let lines: Vec<&str> = source.lines().collect();
+    let mut annotations = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+        let Some((before, kind, number, text)) = parse_tag(line) else {
+            continue;
+        };
+        let tag_line = index + 1;
+        let trailing = !before.trim().is_empty();
+        let mut text = text.to_string();
+        let mut next = index + 1;
+        if !trailing {
+            while let Some(line) = lines.get(next) {
+                let Some(comment) = line.trim_start().strip_prefix("//") else {
+                    break;
+                };
+                if parse_tag(line).is_some() {
+                    break;
+                }
+                text.push(' ');
+                text.push_str(comment.trim());
+                next += 1;
+            }
+        }
+        let code = if trailing {
+            (tag_line, tag_line)
+        } else {
+            // The first line of code after the comment block
+            let start = (next..lines.len())
+                .find(|&i| !lines[i].trim().is_empty())
+                .map_or(tag_line, |i| i + 1);
+            spans.code_starting_at(start)
+        };
+        annotations.push(Annotation {
+            kind,
+            number,
+            text: text.trim().to_string(),
+            line: tag_line,
+            code,
+            function: spans.function_at(tag_line),
+        });
+    }
+    Ok(annotations)
+}
+
+/// The code before the comment, the tag, its number and the text after
+/// the colon
+fn parse_tag(line: &str) -> Option<(&str, Kind, Option<u32>, &str)> {
+    // The first `//` outside a string literal
+    let at = line.match_indices("//").map(|(at, _)| at).find(|&at| {
+        let before = line[..at].replace("\\\\", "").replace("\\\"", "");
+        before.matches('"').count().is_multiple_of(2)
+    })?;
+    let (before, comment) = (&line[..at], &line[at + 2..]);
+    let comment = comment.trim_start();
+    let (kind, rest) = [Kind::Vulnerability, Kind::Check]
+        .into_iter()
+        .find_map(|kind| Some((kind, comment.strip_prefix(kind.tag())?)))?;
+    let (number, text) = rest.split_once(':')?;
+    let number = match number.trim() {
+        "" => None,
+        number => Some(number.parse().ok()?),
+    };
+    Some((before, kind, number, text))
+}
+
+/// Line ranges of a file's functions and of the statements, fields and
+/// items an annotation can be about
+#[derive(Default)]
+struct Spans {
+    functions: Vec<(String, usize, usize)>,
+    nodes: Vec<(usize, usize)>,
+}
+
+impl Spans {
+    fn node(&mut self, node: &impl Spanned) {
+        let span = node.span();
+        self.nodes.push((span.start().line, span.end().line));
+    }
+
+    fn function(&mut self, name: String, node: &impl Spanned) {
+        let span = node.span();
+        self.functions
+            .push((name, span.start().line, span.end().line));
+    }
+
+    /// The outermost node starting on `line`, or just the line
+    fn code_starting_at(&self, line: usize) -> (usize, usize) {
+        self.nodes
+            .iter()
+            .filter(|(start, _)| *start == line)
+            .max_by_key(|(_, end)| *end)
+            .copied()
+            .unwrap_or((line, line))
+    }
+
+    /// The innermost function containing `line`
+    fn function_at(&self, line: usize) -> Option<String> {
+        self.functions
+            .iter()
+            .filter(|(_, start, end)| (*start..=*end).contains(&line))
+            .min_by_key(|(_, start, end)| end - start)
+            .map(|(name, _, _)| name.clone())
+    }
+}
+
+impl<'ast> Visit<'ast> for Spans {
+    fn visit_item_fn(&mut self, node: &'ast ItemFn) {
+        self.function(node.sig.ident.to_string(), node);
+        visit::visit_item_fn(self, node);
+    }
+
+    fn visit_impl_item_fn(&mut self, node: &'ast ImplItemFn) {
+        self.function(node.sig.ident.to_string(), node);
+        visit::visit_impl_item_fn(self, node);
+    }
+
+    fn visit_item(&mut self, node: &'ast Item) {
+        self.node(node);
+        visit::visit_item(self, node);
+    }
+
+    fn visit_stmt(&mut self, node: &'ast Stmt) {
+        self.node(node);
+        visit::visit_stmt(self, node);
+    }
+
+    fn visit_field(&mut self, node: &'ast Field) {
+        self.node(node);
+        visit::visit_field(self, node);
+    }
+}
+
+/// An annotated source file
+#[derive(Clone, Debug)]
+pub struct Source {
+    pub path: PathBuf,
+    pub lines: Vec<String>,
+    pub annotations: Vec<Annotation>,
+}
+
+impl Source {
+    pub fn load(path: &Path) -> AnyResult<Self> {
+        let text =
+            fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        Ok(Source {
+            path: path.to_path_buf(),
+            lines: text.lines().map(str::to_string).collect(),
+            annotations: annotations(&text).with_context(|| format!("in {}", path.display()))?,
+        })
+    }
+
+    /// The file's path from `solana/`
+    pub fn name(&self) -> String {
+        let solana = programs::lab_dir().join("..");
+        self.path
+            .strip_prefix(&solana)
+            .unwrap_or(&self.path)
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    /// The annotation's own lines through the last line of its code, each
+    /// with its 1-based number
+    pub fn excerpt(&self, annotation: &Annotation) -> Vec<(usize, &str)> {
+        let start = annotation.line.min(annotation.code.0);
+        (start..=annotation.code.1)
+            .filter_map(|line| Some((line, self.lines.get(line - 1)?.as_str())))
+            .collect()
+    }
+
+    fn of_kind(&self, kind: Kind) -> impl Iterator<Item = &Annotation> {
+        self.annotations
+            .iter()
+            .filter(move |annotation| annotation.kind == kind)
+    }
+}
+
+/// One bug, with the fixes the secure version makes in the same function
+#[derive(Clone, Debug)]
+pub struct Step {
+    pub vuln: Annotation,
+    pub fixes: Vec<Annotation>,
+}
+
+/// An example's walkthrough
+#[derive(Clone, Debug)]
+pub struct Walkthrough {
+    pub id: &'static str,
+    pub example: &'static str,
+    pub category: Category,
+    pub severity: Severity,
+    /// What the exploit does
+    pub description: &'static str,
+    pub vulnerable: Source,
+    pub secure: Source,
+    /// By annotation number, then line
+    pub steps: Vec<Step>,
+    /// Fixes in functions none of the steps are in
+    pub other_fixes: Vec<Annotation>,
+}
+
+pub fn walkthrough(vulnerability: &dyn Vulnerability) -> AnyResult<Walkthrough> {
+    let example = vulnerability.example();
+    let vulnerable = Source::load(&programs::source(example, Version::Vulnerable))?;
+    let secure = Source::load(&programs::source(example, Version::Secure))?;
+
+    let mut vulns: Vec<&Annotation> = vulnerable.of_kind(Kind::Vulnerability).collect();
+    vulns.sort_by_key(|vuln| (vuln.number.unwrap_or(u32::MAX), vuln.line));
+    let fixes: Vec<&Annotation> = secure.of_kind(Kind::Check).collect();
+    let mut steps: Vec<Step> = Vec::new();
+    for vuln in vulns {
+        // A function's fixes go with its first step
+        let taken = steps.iter().any(|step| step.vuln.function == vuln.function);
+        steps.push(Step {
+            vuln: vuln.clone(),
+            fixes: fixes
+                .iter()
+                .filter(|fix| !taken && counterparts(&vuln.function, &fix.function))
+                .map(|fix| (*fix).clone())
+                .collect(),
+        });
+    }
+    let other_fixes = fixes
+        .into_iter()
+        .filter(|fix| {
+            !steps
+                .iter()
+                .any(|step| counterparts(&step.vuln.function, &fix.function))
+        })
+        .cloned()
+        .collect();
+
+    Ok(Walkthrough {
+        id: vulnerability.id(),
+        example,
+        category: vulnerability.category(),
+        severity: vulnerability.severity(),
+        description: vulnerability.description(),
+        vulnerable,
+        secure,
+        steps,
+        other_fixes,
+    })
+}
+
+/// Whether a function of the vulnerable version and one of the secure
+/// version are the same handler: `stake` and `stake_secure`,
+/// `vulnerable_with_bump` and `secure_with_bump`, `withdraw_all` and
+/// `withdraw_secure`
+pub(crate) fn counterparts(vulnerable: &Option<String>, secure: &Option<String>) -> bool {
+    fn base(name: &str) -> &str {
+        let name = name.strip_prefix("vulnerable_").unwrap_or(name);
+        let name = name.strip_prefix("secure_").unwrap_or(name);
+        name.strip_suffix("_secure").unwrap_or(name)
+    }
+    let (Some(vulnerable), Some(secure)) = (vulnerable, secure) else {
+        return vulnerable == secure;
+    };
+    let (vulnerable, secure) = (base(vulnerable), base(secure));
+    vulnerable == secure
+        || vulnerable.starts_with(&format!("{secure}_"))
+        || secure.starts_with(&format!("{vulnerable}_"))
+}
+
+pub(crate) fn location(source: &Source, annotation: &Annotation) -> String {
+    match &annotation.function {
+        Some(function) => format!("{}:{} in {function}", source.name(), annotation.line),
+        None => format!("{}:{}", source.name(), annotation.line),
+    }
+}
+
+/// Plain text, with line numbers
+pub fn terminal(walkthrough: &Walkthrough) -> String {
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "{} {} ({}, {:?})\n{}\n",
+        walkthrough.id,
+        walkthrough.example,
+        walkthrough.severity,
+        walkthrough.category,
+        walkthrough.description
+    );
+    let excerpt = |out: &mut String, source: &Source, annotation: &Annotation| {
+        let _ = writeln!(out, "   {}", location(source, annotation));
+        let _ = writeln!(out, "   {}", annotation.text);
+        for (number, line) in source.excerpt(annotation) {
+            let _ = writeln!(out, "   {number:>5} | {line}");
+        }
+    };
+    for (index, step) in walkthrough.steps.iter().enumerate() {
+        let _ = writeln!(out, "{}. The bug", index + 1);
+        excerpt(&mut out, &walkthrough.vulnerable, &step.vuln);
+        for fix in &step.fixes {
+            let _ = writeln!(out, "   The fix");
+            excerpt(&mut out, &walkthrough.secure, fix);
+        }
+        out.push('\n');
+    }
+    for fix in &walkthrough.other_fixes {
+        let _ = writeln!(out, "Also fixed");
+        excerpt(&mut out, &walkthrough.secure, fix);
+        out.push('\n');
+    }
+    out
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// The anchor of a line of an example's vulnerable or secure source
+fn anchor(example: &str, kind: Kind, line: usize) -> String {
+    match kind {
+        Kind::Vulnerability => format!("{example}-L{line}"),
+        Kind::Check => format!("{example}-secure-L{line}"),
+    }
+}
+
+/// One self-contained page: every walkthrough's steps, linking to the
+/// lines they point at in full listings of both versions
+pub fn html(walkthroughs: &[Walkthrough]) -> String {
+    let mut out = String::from(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>Solana vulnerability walkthroughs</title>\n<style>\n\
+         body { font-family: sans-serif; max-width: 60rem; margin: auto; }\n\
+         pre { background: #f6f8fa; padding: .5rem; overflow-x: auto; }\n\
+         .line:target { outline: 2px solid #0969da; }\n\
+         .vuln { background: #ffebe9; }\n\
+         .fix { background: #dafbe1; }\n\
+         .number { color: #6e7781; user-select: none; }\n\
+         </style>\n</head>\n<body>\n<h1>Solana vulnerability walkthroughs</h1>\n<ul>\n",
+    );
+    for walkthrough in walkthroughs {
+        let _ = writeln!(
+            out,
+            "<li><a href=\"#{0}\">{1} {0}</a></li>",
+            walkthrough.example, walkthrough.id
+        );
+    }
+    out.push_str("</ul>\n");
+
+    for walkthrough in walkthroughs {
+        let example = walkthrough.example;
+        let _ = writeln!(
+            out,
+            "<section id=\"{example}\">\n<h2>{} {example}</h2>\n<p>{} &middot; {:?}</p>\n<p>{}</p>\n<ol>",
+            walkthrough.id,
+            walkthrough.severity,
+            walkthrough.category,
+            escape(walkthrough.description)
+        );
+        let link = |out: &mut String, source: &Source, annotation: &Annotation| {
+            let _ = write!(
+                out,
+                "<a href=\"#{}\">{}</a>: {}",
+                anchor(example, annotation.kind, annotation.line),
+                escape(&location(source, annotation)),
+                escape(&annotation.text)
+            );
+        };
+        for step in &walkthrough.steps {
+            out.push_str("<li>");
+            link(&mut out, &walkthrough.vulnerable, &step.vuln);
+            for fix in &step.fixes {
+                out.push_str("<br>Fixed by ");
+                link(&mut out, &walkthrough.secure, fix);
+            }
+            out.push_str("</li>\n");
+        }
+        out.push_str("</ol>\n");
+        for fix in &walkthrough.other_fixes {
+            out.push_str("<p>Also fixed: ");
+            link(&mut out, &walkthrough.secure, fix);
+            out.push_str("</p>\n");
+        }
+        listing(
+            &mut out,
+            example,
+            &walkthrough.vulnerable,
+            Kind::Vulnerability,
+        );
+        listing(&mut out, example, &walkthrough.secure, Kind::Check);
+        out.push_str("</section>\n");
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+/// A whole file, every line anchored and the annotated ones highlighted
+fn listing(out: &mut String, example: &str, source: &Source, kind: Kind) {
+    let _ = writeln!(out, "<h3>{}</h3>\n<pre><code>", escape(&source.name()));
+    for (index, line) in source.lines.iter().enumerate() {
+        let number = index + 1;
+        let highlighted = source.annotations.iter().any(|annotation| {
+            (annotation.line.min(annotation.code.0)..=annotation.code.1).contains(&number)
+        });
+        let class = match (highlighted, kind) {
+            (false, _) => "line",
+            (true, Kind::Vulnerability) => "line vuln",
+            (true, Kind::Check) => "line fix",
+        };
+        let _ = writeln!(
+            out,
+            "<span class=\"{class}\" id=\"{}\"><span class=\"number\">{number:>4}</span>  {}</span>",
+            anchor(example, kind, number),
+            escape(line)
+        );
+    }
+    out.push_str("</code></pre>\n");
+}
@@ -0,0 +1,167 @@
+//! Exploit witnesses: the shortest flows the differential fuzzer finds
+//! that an example takes and its fix does not
+//!
+//! [`search`] draws flows of [`Step`]s from a seeded RNG and replays each
+//! against both builds with [`fuzz::replay`] until the builds part. It
+//! then [`minimize`]s the flow: steps are dropped for as long as the
+//! builds still part, then every mutation left is undone in turn unless
+//! it is what makes them part. What remains is a [`Witness`]: the honest
+//! use's instructions, reordered and tampered with only as far as the bug
+//! needs, which is the example's vulnerability class, found rather than
+//! written down.
+
+use std::fmt;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use solana_program::instruction::Instruction;
+use solana_program::pubkey::Pubkey;
+
+use crate::fuzz::{self, Divergence, Step, MAX_ACCOUNTS, MAX_STEPS};
+use crate::{property, Category, REGISTRY};
+
+/// A minimized flow that parts an example's builds
+#[derive(Debug)]
+pub struct Witness {
+    pub example: &'static str,
+    pub id: &'static str,
+    pub category: Category,
+    pub steps: Vec<Step>,
+    /// Where and how the builds part over `steps`
+    pub divergence: Divergence,
+    /// How many flows the search tried, this one included
+    pub flows: usize,
+}
+
+/// Replays up to `flows` flows drawn from `seed` against `example`'s
+/// builds, and minimizes the first that parts them
+pub fn search(example: &str, seed: u64, flows: usize) -> Option<Witness> {
+    let vulnerability = REGISTRY
+        .find(example)
+        .unwrap_or_else(|| panic!("no example {example}"));
+    let mut rng = StdRng::seed_from_u64(seed);
+    for tried in 1..=flows {
+        let steps: Vec<Step> = (0..rng.gen_range(1..=MAX_STEPS))
+            .map(|_| step(&mut rng))
+            .collect();
+        if fuzz::replay(example, &steps).is_some() {
+            let steps = minimize(example, steps);
+            let divergence = fuzz::replay(example, &steps).expect("minimizing keeps the parting");
+            return Some(Witness {
+                example: vulnerability.example(),
+                id: vulnerability.id(),
+                category: vulnerability.category(),
+                steps,
+                divergence,
+                flows: tried,
+            });
+        }
+    }
+    None
+}
+
+/// A step that keeps most of its accounts as they are
+fn step(rng: &mut StdRng) -> Step {
+    Step {
+        index: rng.gen(),
+        forged: (rng.gen(), rng.gen()),
+        mutations: std::array::from_fn(|_| if rng.gen() { 0 } else { rng.gen() }),
+    }
+}
+
+/// `steps`, which part `example`'s builds, with every step and mutation
+/// they still part without taken out
+pub fn minimize(example: &str, steps: Vec<Step>) -> Vec<Step> {
+    let parts = |steps: &[Step]| match fuzz::replay(example, steps) {
+        Some(_) => Err(String::new()),
+        None => Ok(()),
+    };
+    let (mut steps, _) = property::shrink(steps, String::new(), parts);
+
+    for index in 0..steps.len() {
+        for account in 0..MAX_ACCOUNTS {
+            if steps[index].mutations[account] == 0 {
+                continue;
+            }
+            let mut fewer = steps.clone();
+            fewer[index].mutations[account] = 0;
+            if parts(&fewer).is_err() {
+                steps = fewer;
+            }
+        }
+        // The forged account only matters where a step names it
+        let mut plainer = steps.clone();
+        plainer[index].forged = (0, 0);
+        if parts(&plainer).is_err() {
+            steps = plainer;
+        }
+    }
+    steps
+}
+
+impl fmt::Display for Witness {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let divergence = &self.divergence;
+        writeln!(
+            f,
+            "{} {} ({}), after {} flows:",
+            self.id, self.example, self.category, self.flows
+        )?;
+        let instructions = divergence.earlier.iter().chain([&divergence.instruction]);
+        for (index, instruction) in instructions.enumerate() {
+            writeln!(f, "  step {index}: {}", self.instruction(instruction))?;
+        }
+        match &divergence.error {
+            Some(error) => writeln!(
+                f,
+                "  the vulnerable build takes step {}; the secure build refuses it: {error}",
+                divergence.step
+            )?,
+            None if divergence.lamports[0] != divergence.lamports[1] => writeln!(
+                f,
+                "  both builds take step {}, but move lamports {:?} and {:?}",
+                divergence.step, divergence.lamports[0], divergence.lamports[1]
+            )?,
+            None => writeln!(f, "  both builds take step {}", divergence.step)?,
+        }
+        match &divergence.violation {
+            Some(violation) => writeln!(f, "  and after it, in the vulnerable build, {violation}"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Witness {
+    fn instruction(&self, instruction: &Instruction) -> String {
+        describe(instruction, &self.divergence.labels)
+    }
+}
+
+/// `instruction`'s data and accounts, each by its label in `labels` where
+/// it has one
+pub(crate) fn describe(instruction: &Instruction, labels: &[(String, Pubkey)]) -> String {
+    let accounts: Vec<String> = instruction
+        .accounts
+        .iter()
+        .map(|meta| {
+            let mut account = name(&meta.pubkey, labels);
+            if meta.is_signer {
+                account.push_str(" (signer)");
+            }
+            account
+        })
+        .collect();
+    format!(
+        "data {:02x?}, accounts [{}]",
+        instruction.data,
+        accounts.join(", ")
+    )
+}
+
+/// `pubkey`'s label in `labels`, or the address itself
+pub(crate) fn name(pubkey: &Pubkey, labels: &[(String, Pubkey)]) -> String {
+    labels
+        .iter()
+        .find(|(_, key)| key == pubkey)
+        .map_or_else(|| pubkey.to_string(), |(label, _)| label.clone())
+}
@@ -0,0 +1,91 @@
+//! Transaction logs parse into invocations, and every attack is tied to
+//! the secure version's checks it got past
+
+use solana_program::pubkey::Pubkey;
+use vuln_lab::analysis::{self, Invocation, Outcome, Verdict};
+use vuln_lab::REGISTRY;
+
+#[test]
+fn logs_parse_into_nested_invocations() {
+    let (outer, inner) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let logs: Vec<String> = [
+        format!("Program {outer} invoke [1]"),
+        "Program log: withdrawing".to_string(),
+        format!("Program {inner} invoke [2]"),
+        format!("Program {inner} success"),
+        format!("Program {outer} consumed 1200 of 200000 compute units"),
+        format!("Program {outer} failed: missing required signature for instruction"),
+        "not a runtime line".to_string(),
+        format!("Program {outer} invoke [1]"),
+    ]
+    .into();
+    let leaf = |program_id, depth, outcome| Invocation {
+        program_id,
+        depth,
+        logs: Vec::new(),
+        events: Vec::new(),
+        outcome,
+        inner: Vec::new(),
+    };
+    assert_eq!(
+        analysis::parse(&logs),
+        [
+            Invocation {
+                logs: vec!["withdrawing".to_string()],
+                inner: vec![leaf(inner, 2, Outcome::Succeeded)],
+                ..leaf(
+                    outer,
+                    1,
+                    Outcome::Failed("missing required signature for instruction".to_string())
+                )
+            },
+            leaf(outer, 1, Outcome::Unfinished),
+        ]
+    );
+}
+
+#[test]
+fn every_attack_is_tied_to_a_missing_check() {
+    for vulnerability in REGISTRY.iter() {
+        let example = vulnerability.example();
+        let analysis = analysis::analyze(vulnerability).unwrap();
+        assert!(!analysis.steps.is_empty(), "{example}");
+        let blocked: Vec<_> = analysis
+            .steps
+            .iter()
+            .filter_map(|step| match &step.verdict {
+                Verdict::Blocked { checks, .. } => Some(checks),
+                _ => None,
+            })
+            .collect();
+        assert!(
+            blocked.iter().all(|checks| !checks.is_empty()),
+            "{example}: an error no check returns"
+        );
+        assert!(
+            !blocked.is_empty() || !analysis.unattributed.is_empty(),
+            "{example}"
+        );
+        assert!(!analysis::terminal(&analysis).is_empty(), "{example}");
+    }
+
+    let analysis = analysis::analyze(REGISTRY.find("missing_owner_check").unwrap()).unwrap();
+    let [step] = &analysis.steps[..] else {
+        panic!("{:?}", analysis.steps);
+    };
+    let Verdict::Blocked { error, checks } = &step.verdict else {
+        panic!("{:?}", step.verdict);
+    };
+    // ValidationError::WrongOwner, from load's owner check
+    assert_eq!(error, "custom program error: 0x3e9");
+    let [check] = &checks[..] else {
+        panic!("{checks:?}");
+    };
+    assert!(check.fix.text.contains("owned by this program"));
+    assert!(check
+        .vuln
+        .as_ref()
+        .unwrap()
+        .text
+        .contains("owned by this program"));
+}
@@ -0,0 +1,66 @@
+//! Arbitrary account states are well-formed values of their type, and the
+//! fuzz harness can put one in an account
+
+use borsh::{to_vec, BorshDeserialize};
+use lab_domain::{EscrowAccount, StakingPool, UserProfile, UserStake, VaultConfig};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use vuln_lab::property;
+use vuln_lab::{fuzz, Version};
+
+#[test]
+fn arbitrary_states_fill_their_layout_exactly() {
+    let mut rng = StdRng::seed_from_u64(1705);
+    for _ in 0..64 {
+        let pool: StakingPool = property::arbitrary(&mut rng);
+        let stake: UserStake = property::arbitrary(&mut rng);
+        let vault: VaultConfig = property::arbitrary(&mut rng);
+        let escrow: EscrowAccount = property::arbitrary(&mut rng);
+        let profile: UserProfile = property::arbitrary(&mut rng);
+
+        let data = to_vec(&pool).unwrap();
+        assert_eq!(data.len(), StakingPool::LEN);
+        assert_eq!(StakingPool::try_from_slice(&data).unwrap(), pool);
+        assert_eq!(to_vec(&stake).unwrap().len(), UserStake::LEN);
+        assert_eq!(to_vec(&vault).unwrap().len(), VaultConfig::LEN);
+        assert_eq!(to_vec(&escrow).unwrap().len(), EscrowAccount::LEN);
+        assert_eq!(to_vec(&profile).unwrap().len(), UserProfile::LEN);
+    }
+}
+
+/// A reward calculation over a pool and a stake account whose data is
+/// `pool` and `stake`, signed by a wallet that also stands in for the clock
+fn rewards(pool: &[u8], stake: &[u8]) -> Vec<u8> {
+    [
+        [4].as_slice(),
+        // wallet 0 and wallet 1, owned by the program
+        &[4, 16],
+        pool,
+        &[1 << 3 | 4, 16],
+        stake,
+        // wallet 2 signs, and wallet 3 is the clock
+        &[2 << 3 | 1, 16, 0],
+        &[3 << 3, 16, 0],
+        &[1],
+    ]
+    .concat()
+}
+
+#[test]
+fn a_typed_length_byte_gets_past_deserialization() {
+    // Raw bytes of the right length are as likely as any to be wrong;
+    // these are empty, and neither build gets as far as the arithmetic
+    let input = rewards(&[0], &[0]);
+    assert_eq!(
+        fuzz::execute("arithmetic_errors", Version::Vulnerable, &input),
+        None
+    );
+
+    // A StakingPool and a UserStake, all zeroes: the vulnerable build reads
+    // them and divides by total_staked, where the secure one refuses
+    let pool = [[fuzz::TYPED_STATE].as_slice(), &[0; StakingPool::LEN]].concat();
+    let stake = [[fuzz::TYPED_STATE + 1].as_slice(), &[0; UserStake::LEN]].concat();
+    let input = rewards(&pool, &stake);
+    let panic = fuzz::run("arithmetic_errors", &input).expect("the vulnerable build panics");
+    assert!(panic.contains("divide by zero"), "{panic}");
+}
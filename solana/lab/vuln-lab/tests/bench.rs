@@ -0,0 +1,25 @@
+//! Every example can be used honestly against both of its builds, so the
+//! bench has the same work to compare on each side
+
+use lab_svm::CountingAllocator;
+use vuln_lab::bench;
+use vuln_lab::REGISTRY;
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[test]
+fn every_example_is_used_honestly_by_both_builds() {
+    for vulnerability in REGISTRY.iter() {
+        let example = vulnerability.example();
+        let comparison = bench::compare(vulnerability, 1)
+            .unwrap_or_else(|err| panic!("{err:#}"))
+            .unwrap_or_else(|| panic!("{example} has no honest use"));
+        assert_eq!(
+            comparison.vulnerable.transactions, comparison.secure.transactions,
+            "{example}"
+        );
+        assert!(comparison.vulnerable.heap_bytes > 0, "{example}");
+        assert!(comparison.secure.heap_bytes > 0, "{example}");
+    }
+}
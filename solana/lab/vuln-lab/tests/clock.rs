@@ -0,0 +1,88 @@
+//! Time-dependent programs run against a MockClock off-chain, so a test can
+//! put them either side of a deadline
+
+use borsh::to_vec;
+use lab_domain::{EscrowAccount, StakingPool, UserProfile, UserStake};
+use lab_test_utils::{infos, Stored};
+use solana_common::clock::MockClock;
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+
+const RELEASE_TIME: i64 = 1_000;
+const ESCROWED: u64 = 100;
+
+/// Profile, escrow, beneficiary and signer for a release
+fn escrow(program_id: Pubkey) -> Vec<Stored> {
+    let signer = Stored::signer(Pubkey::new_unique());
+    let beneficiary = Stored::new(Pubkey::default(), Vec::new());
+    let escrow = Stored {
+        lamports: ESCROWED,
+        ..Stored::new(
+            program_id,
+            to_vec(&EscrowAccount::new(beneficiary.key, ESCROWED, RELEASE_TIME)).unwrap(),
+        )
+    };
+    let profile = Stored::new(
+        program_id,
+        to_vec(&UserProfile::new(signer.key, escrow.key, ESCROWED)).unwrap(),
+    );
+    vec![profile, escrow, beneficiary, signer]
+}
+
+#[test]
+fn escrow_releases_only_once_its_time_has_come() {
+    let program_id = Pubkey::new_unique();
+    let release = |now: i64| {
+        let mut accounts = escrow(program_id);
+        let result = account_data_matching_secure::process_instruction_with_clock(
+            &program_id,
+            &infos(&mut accounts),
+            &[],
+            &MockClock(now),
+        );
+        (result, accounts[2].lamports)
+    };
+
+    assert_eq!(
+        release(RELEASE_TIME - 1),
+        (Err(ProgramError::InvalidAccountData), 0)
+    );
+    assert_eq!(release(RELEASE_TIME), (Ok(()), ESCROWED));
+}
+
+#[test]
+fn a_claim_from_the_future_wraps_only_in_the_vulnerable_build() {
+    let program_id = Pubkey::new_unique();
+    let pool = to_vec(&StakingPool {
+        total_staked: 1_000,
+        reward_rate: 1,
+        last_update: 0,
+    })
+    .unwrap();
+    let stake = to_vec(&UserStake::new(1_000, RELEASE_TIME)).unwrap();
+    let accounts = || {
+        vec![
+            Stored::new(program_id, pool.clone()),
+            Stored::new(program_id, stake.clone()),
+            Stored::signer(Pubkey::new_unique()),
+            Stored::new(Pubkey::default(), Vec::new()),
+        ]
+    };
+    let before_claim = MockClock(RELEASE_TIME - 1);
+
+    // now - last_claim is -1, read as u64::MAX seconds of rewards
+    let vulnerable = arithmetic_errors::process_instruction_with_clock(
+        &program_id,
+        &infos(&mut accounts()),
+        &[1],
+        &before_claim,
+    );
+    assert_eq!(vulnerable, Ok(()));
+    let secure = arithmetic_errors_secure::process_instruction_with_clock(
+        &program_id,
+        &infos(&mut accounts()),
+        &[1],
+        &before_claim,
+    );
+    assert_eq!(secure, Err(ProgramError::InvalidAccountData));
+}
@@ -0,0 +1,59 @@
+//! No two kinds of PDA in this tree derive the same address, and a seed
+//! the caller sizes is found colliding with a longer literal prefix
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde_json::Value;
+use solana_program::pubkey::Pubkey;
+use vuln_lab::collisions::{self, Namespace, Seed};
+
+const SEED: u64 = 1712;
+const INPUTS: usize = 200;
+
+#[test]
+fn no_two_kinds_of_pda_in_this_tree_collide() {
+    assert_eq!(collisions::search(), []);
+
+    let mut rng = StdRng::seed_from_u64(SEED);
+    for _ in 0..INPUTS {
+        let input: Vec<u8> = (0..rng.gen_range(0..100)).map(|_| rng.gen()).collect();
+        if let Some(collision) = collisions::fuzz(&input) {
+            panic!("{collision}");
+        }
+    }
+}
+
+/// `user_data` then a key, beside `user`, up to 8 bytes the caller
+/// chooses, then a key: the caller's `_data` makes the two one address
+#[test]
+fn a_seed_the_caller_sizes_collides_with_a_longer_prefix() {
+    let namespaces = [
+        Namespace::new(
+            "seed_ambiguity",
+            "USER_DATA",
+            vec![Seed::Literal(b"user_data"), Seed::Key],
+        ),
+        Namespace::new(
+            "seed_ambiguity",
+            "USER_NOTE",
+            vec![Seed::Literal(b"user"), Seed::Bytes(8), Seed::Key],
+        ),
+    ];
+    let program_id = Pubkey::new_unique();
+    let collision = collisions::ambiguous(&namespaces[0], &namespaces[1], &program_id)
+        .expect("the layouts overlap");
+
+    let report: Value = serde_json::from_str(&collision.to_json()).unwrap();
+    assert_eq!(report["address"], collision.address.as_str());
+    let note = &report["derivations"][1];
+    assert_eq!(note["namespace"], "USER_NOTE");
+    assert_eq!(note["seeds"][1], "5f64617461", "{collision}");
+
+    // The same seeds, as fuzzer bytes, decode to the same collision
+    let key = [0; 32];
+    let input = [&[0, 1][..], &key, &[5], b"_data", &key].concat();
+    assert_eq!(
+        collisions::collide(&namespaces, &program_id, &input),
+        Some(collision)
+    );
+}
@@ -0,0 +1,50 @@
+//! The ids in `Programs.toml` are the ones the programs declare and the
+//! ones their committed localnet keypairs deploy to
+
+use vuln_lab::config::{self, ProgramIds};
+use vuln_lab::programs::{self, crates, Cluster};
+use vuln_lab::{ctf, Signer};
+
+#[test]
+fn every_crate_declares_its_localnet_id() {
+    let ids = ProgramIds::load().unwrap();
+    for krate in crates() {
+        let declared = programs::id(&krate).unwrap();
+        assert_eq!(ids.get("localnet", &krate), Some(declared), "{krate}");
+        let keypair = config::program_keypair(&ids, "localnet", &krate)
+            .unwrap_or_else(|err| panic!("{err:#}"));
+        assert_eq!(keypair.pubkey(), declared, "{krate}");
+    }
+    assert_eq!(ids.ids("localnet").len(), crates().len());
+    assert_eq!(Some(ctf::VERIFIER), ids.get("localnet", "ctf_verifier"));
+}
+
+#[test]
+fn ids_are_checked_when_parsed() {
+    let id = "6f7v39x5XGnq268fpGJyHibhkGWuj13QupBjN2hybD1m";
+    assert!(ProgramIds::parse(&format!("[devnet]\nexample = \"{id}\"\n")).is_ok());
+    assert!(ProgramIds::parse(&format!("[mainnet]\nexample = \"{id}\"\n")).is_err());
+    assert!(ProgramIds::parse("[devnet]\nexample = \"not an id\"\n").is_err());
+    assert!(ProgramIds::parse("devnet = 1\n").is_err());
+}
+
+#[test]
+fn set_adds_clusters_and_keeps_the_rest() {
+    let mut ids =
+        ProgramIds::parse("# ids\n[localnet]\na = \"11111111111111111111111111111111\"\n").unwrap();
+    let id = vuln_lab::owner().pubkey();
+    ids.set("devnet", "a", &id);
+    assert_eq!(ids.get("devnet", "a"), Some(id));
+    assert_eq!(ids.get("localnet", "a"), Some(Default::default()));
+    assert_eq!(ids.get("testnet", "a"), None);
+}
+
+#[test]
+fn clusters_map_to_their_ids() {
+    let name = |cluster: &str| cluster.parse::<Cluster>().unwrap().name();
+    assert_eq!(name("svm"), "localnet");
+    assert_eq!(name("localnet"), "localnet");
+    assert_eq!(name("devnet"), "devnet");
+    assert_eq!(name("https://api.testnet.solana.com"), "testnet");
+    assert_eq!(name("http://10.0.0.2:8899"), "localnet");
+}
@@ -0,0 +1,193 @@
+//! Staking and transfers conserve what they track under arithmetic_errors'
+//! secure handlers, whatever order they come in, and the vulnerable
+//! handlers stop conserving it within a few operations
+
+use borsh::{to_vec, BorshDeserialize};
+use lab_domain::{StakingPool, UserStake};
+use lab_instructions::arithmetic_errors::{calculate_rewards_ix, stake_ix, transfer_ix};
+use lab_svm::{Keypair, Signer, Svm, LAMPORTS_PER_SOL};
+use lab_test_utils::rent_exempt;
+use rand::rngs::StdRng;
+use rand::Rng;
+use solana_program::entrypoint::ProcessInstruction;
+use solana_program::pubkey::Pubkey;
+use vuln_lab::invariants;
+use vuln_lab::property::{self, Counterexample};
+
+const SEED: u64 = 1703;
+const CASES: usize = 256;
+const MAX_OPS: usize = 8;
+const USERS: usize = 3;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Op {
+    Stake {
+        user: usize,
+        amount: u64,
+    },
+    Rewards {
+        user: usize,
+    },
+    /// Between two of the tracked accounts, by index
+    Transfer {
+        from: usize,
+        to: usize,
+        amount: u64,
+    },
+}
+
+fn op(rng: &mut StdRng) -> Op {
+    let user = rng.gen_range(0..USERS);
+    match rng.gen_range(0..3) {
+        0 => Op::Stake {
+            user,
+            amount: property::amount(rng),
+        },
+        1 => Op::Rewards { user },
+        _ => Op::Transfer {
+            from: rng.gen_range(0..1 + USERS),
+            to: rng.gen_range(0..1 + USERS),
+            amount: property::amount(rng),
+        },
+    }
+}
+
+/// Runs `ops` against a fresh pool with a stake account per user, each
+/// signed by its user; refused operations are fine. After every one, the
+/// example's invariants must hold, the pool's total_staked must still be
+/// the sum of the stakes, and the pool's and stakes' lamports what they
+/// were
+fn conserves(program: ProcessInstruction, ops: &[Op]) -> Result<(), String> {
+    let mut svm = Svm::new();
+    let program_id = svm.add_program(program);
+    let users: Vec<Keypair> = (0..USERS)
+        .map(|index| Keypair::new_from_array([index as u8 + 1; 32]))
+        .collect();
+    let pool = StakingPool {
+        total_staked: 0,
+        reward_rate: 1,
+        last_update: 0,
+    };
+    // Each rent-exempt with a SOL to spare, owned by the program
+    let account = |svm: &mut Svm, data| {
+        rent_exempt(
+            svm,
+            Pubkey::new_unique(),
+            &program_id,
+            data,
+            LAMPORTS_PER_SOL,
+        )
+    };
+    let mut tracked = vec![account(&mut svm, to_vec(&pool).unwrap())];
+    for user in &users {
+        svm.airdrop(&user.pubkey(), LAMPORTS_PER_SOL);
+        let stake = to_vec(&UserStake::new(0, 0)).unwrap();
+        tracked.push(account(&mut svm, stake));
+    }
+    let lamports = |svm: &Svm| -> u128 {
+        tracked
+            .iter()
+            .map(|key| u128::from(svm.lamports(key)))
+            .sum()
+    };
+    let before = lamports(&svm);
+
+    for (index, op) in ops.iter().enumerate() {
+        let (instruction, user) = match *op {
+            Op::Stake { user, amount } => (
+                stake_ix(
+                    program_id,
+                    tracked[0],
+                    tracked[1 + user],
+                    users[user].pubkey(),
+                    amount,
+                ),
+                user,
+            ),
+            Op::Rewards { user } => (
+                calculate_rewards_ix(
+                    program_id,
+                    tracked[0],
+                    tracked[1 + user],
+                    users[user].pubkey(),
+                ),
+                user,
+            ),
+            Op::Transfer { from, to, amount } => (
+                transfer_ix(
+                    program_id,
+                    tracked[from],
+                    tracked[to],
+                    users[0].pubkey(),
+                    amount,
+                ),
+                0,
+            ),
+        };
+        let _ = svm.send(&[instruction], &[&users[user]]);
+
+        if let Err(violation) = invariants::check("arithmetic_errors", &svm, &program_id) {
+            return Err(format!("after operation {index}, {violation}"));
+        }
+        // The pool opened empty, so it may not count more than the stakes
+        // either
+        let data = |key: &Pubkey| svm.get_account(key).unwrap().data.clone();
+        let total_staked = StakingPool::try_from_slice(&data(&tracked[0]))
+            .unwrap()
+            .total_staked;
+        let stakes: u128 = tracked[1..]
+            .iter()
+            .map(|key| u128::from(UserStake::try_from_slice(&data(key)).unwrap().amount))
+            .sum();
+        if u128::from(total_staked) != stakes {
+            return Err(format!(
+                "after operation {index}, total_staked is {total_staked} but the stakes sum to \
+                 {stakes}"
+            ));
+        }
+        let after = lamports(&svm);
+        if after != before {
+            return Err(format!(
+                "after operation {index}, the tracked accounts hold {after} lamports, not {before}"
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn the_secure_handlers_conserve_stakes_and_lamports() {
+    let result = property::check(SEED, CASES, MAX_OPS, op, |ops| {
+        conserves(arithmetic_errors_secure::process_instruction, ops)
+    });
+    if let Err(counterexample) = result {
+        panic!("{counterexample}");
+    }
+}
+
+/// Shrunk, the violation is two users staking amounts that together pass
+/// u64::MAX: each stake holds its own amount, and total_staked wraps
+#[test]
+fn two_large_stakes_break_the_vulnerable_handlers() {
+    let counterexample: Counterexample<Op> = property::check(SEED, CASES, MAX_OPS, op, |ops| {
+        conserves(arithmetic_errors::process_instruction, ops)
+    })
+    .expect_err("the vulnerable handlers conserve everything");
+
+    let [Op::Stake {
+        user: first,
+        amount: a,
+    }, Op::Stake {
+        user: second,
+        amount: b,
+    }] = counterexample.ops[..]
+    else {
+        panic!("not two stakes:\n{counterexample}");
+    };
+    assert_ne!(first, second, "{counterexample}");
+    assert!(a.checked_add(b).is_none(), "{counterexample}");
+    assert!(
+        counterexample.failure.contains("total_staked"),
+        "{counterexample}"
+    );
+}
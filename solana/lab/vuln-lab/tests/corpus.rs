@@ -0,0 +1,40 @@
+//! The seed corpus is instructions the builds take, which random bytes
+//! almost never are, and none of it crashes a fuzz target
+
+use vuln_lab::{corpus, fuzz, Version, REGISTRY};
+
+/// Every example's vulnerable build takes at least one of its seeds, and
+/// no seed panics the secure build
+#[test]
+fn every_example_has_seeds_its_vulnerable_build_takes() {
+    for vulnerability in REGISTRY.iter() {
+        let example = vulnerability.example();
+        let seeds = corpus::seeds(example);
+        assert!(
+            seeds
+                .iter()
+                .any(|seed| fuzz::takes(example, Version::Vulnerable, seed)),
+            "{example}: none of {} seeds taken",
+            seeds.len()
+        );
+        for seed in &seeds {
+            fuzz::run(example, seed);
+        }
+        assert!(!corpus::dictionary(example).is_empty(), "{example}");
+    }
+}
+
+/// A flow seed replays some of the honest use without the builds parting,
+/// and the honest use in order is always among them
+#[test]
+fn flow_seeds_replay_the_honest_use() {
+    for (pick, vulnerability) in REGISTRY.iter().enumerate() {
+        let example = vulnerability.example();
+        let flows = corpus::flows(example);
+        assert!(!flows.is_empty(), "{example}");
+        for flow in &flows {
+            assert_eq!(usize::from(flow[0]), pick, "{example}");
+            assert!(fuzz::diverge(example, &flow[1..]).is_none(), "{example}");
+        }
+    }
+}
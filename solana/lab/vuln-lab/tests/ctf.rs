@@ -0,0 +1,44 @@
+//! Every exploit captures its example's flag, and only the vulnerable one
+
+use vuln_lab::{attacker, ctf, Harness, Signer, Version, REGISTRY};
+
+#[test]
+fn every_exploit_captures_its_flag() {
+    for vulnerability in REGISTRY.iter() {
+        let mut harness = Harness::new(Version::Vulnerable);
+        assert!(vulnerability.exploit(&mut harness).succeeded());
+        let flags = ctf::flags(&harness);
+        assert_eq!(flags.len(), 1, "{}", vulnerability.example());
+        assert_eq!(
+            flags[0].1.solvers,
+            [attacker().pubkey()],
+            "{}",
+            vulnerability.example()
+        );
+
+        let mut harness = Harness::new(Version::Secure);
+        assert!(!vulnerability.exploit(&mut harness).succeeded());
+        assert!(
+            ctf::flags(&harness)
+                .iter()
+                .all(|(_, flag)| flag.solvers.is_empty()),
+            "{} secure",
+            vulnerability.example()
+        );
+    }
+}
+
+#[test]
+fn setup_stops_at_the_flag() {
+    for vulnerability in REGISTRY.iter() {
+        let harness = ctf::setup(vulnerability).unwrap();
+        let flags = ctf::flags(&harness);
+        assert_eq!(flags.len(), 1, "{}", vulnerability.example());
+        assert!(flags[0].1.solvers.is_empty());
+        assert!(
+            harness.history().iter().all(Result::is_ok),
+            "{}",
+            vulnerability.example()
+        );
+    }
+}
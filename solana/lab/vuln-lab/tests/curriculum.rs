@@ -0,0 +1,61 @@
+//! The learning path puts every example after its prerequisites, and
+//! progress is kept per user
+
+use vuln_lab::curriculum::{curriculum, Progress, Status};
+use vuln_lab::REGISTRY;
+
+#[test]
+fn every_example_comes_after_its_prerequisites() {
+    let path = curriculum(&REGISTRY).unwrap();
+    assert_eq!(path.len(), REGISTRY.len());
+    let position = |example: &str| {
+        path.iter()
+            .position(|vulnerability| vulnerability.example() == example)
+            .unwrap_or_else(|| panic!("{example} is not on the path"))
+    };
+    for (step, vulnerability) in path.iter().enumerate() {
+        for prerequisite in vulnerability.prerequisites() {
+            assert!(
+                position(prerequisite) < step,
+                "{} comes before {prerequisite}",
+                vulnerability.example()
+            );
+        }
+    }
+    assert!(position("missing_signer_check") < position("missing_owner_check"));
+    assert!(position("missing_owner_check") < position("pda_issues"));
+    assert_eq!(path[0].example(), "missing_signer_check");
+}
+
+#[test]
+fn progress_is_kept_per_user_and_unlocks_the_next_examples() {
+    let owner_check = REGISTRY.find("missing_owner_check").unwrap();
+    let mut progress = Progress::default();
+    assert_eq!(
+        progress.status("alice", owner_check),
+        Status::Locked(vec!["missing_signer_check"])
+    );
+
+    assert!(progress.complete("alice", "missing_signer_check"));
+    assert!(!progress.complete("alice", "missing_signer_check"));
+    assert_eq!(progress.status("alice", owner_check), Status::Ready);
+    assert!(matches!(
+        progress.status("bob", owner_check),
+        Status::Locked(_)
+    ));
+
+    let store = std::env::temp_dir()
+        .join(format!("vuln-lab-progress-{}", std::process::id()))
+        .join("progress.json");
+    progress.save(&store).unwrap();
+    let mut loaded = Progress::load(&store).unwrap();
+    assert_eq!(loaded, progress);
+    assert!(matches!(
+        loaded.status("alice", REGISTRY.find("missing_signer_check").unwrap()),
+        Status::Done(_)
+    ));
+
+    loaded.reset("alice");
+    assert_eq!(loaded, Progress::default());
+    std::fs::remove_dir_all(store.parent().unwrap()).unwrap();
+}
@@ -0,0 +1,106 @@
+//! Account diffs decode each example's structs and show what its attack
+//! changed
+
+use vuln_lab::diff::{self, FieldChange, Value};
+use vuln_lab::{Field, FieldType, Layout, Signer, Version, REGISTRY};
+
+fn field<'a>(diffs: &'a [diff::AccountDiff], layout: &str, name: &str) -> &'a FieldChange {
+    diffs
+        .iter()
+        .filter(|diff| diff.layout == Some(layout))
+        .flat_map(|diff| &diff.fields)
+        .find(|field| field.name == name)
+        .unwrap_or_else(|| panic!("no change to {layout}.{name}"))
+}
+
+#[test]
+fn every_attack_shows_in_the_accounts() {
+    for vulnerability in REGISTRY.iter() {
+        let example = vulnerability.example();
+        let (_, outcome, diffs) = diff::attack(vulnerability, Version::Vulnerable);
+        assert!(outcome.succeeded(), "{example}");
+        assert!(!diffs.is_empty(), "{example}");
+        assert!(!diff::render(&diffs, false).is_empty(), "{example}");
+    }
+
+    let attack = |example| diff::attack(REGISTRY.find(example).unwrap(), Version::Vulnerable).2;
+    let diffs = attack("reinitialization");
+    let authority = field(&diffs, "VaultConfig", "authority");
+    assert_eq!(
+        authority.after,
+        Some(Value::Pubkey(vuln_lab::attacker().pubkey()))
+    );
+    assert!(diff::render(&diffs, false).contains("authority: owner → attacker"));
+
+    let diffs = attack("arithmetic_errors");
+    let total_staked = field(&diffs, "StakingPool", "total_staked");
+    assert_eq!(total_staked.note(), Some("wrapped past the maximum"));
+
+    let diffs = attack("type_confusion");
+    let account = diffs.iter().find(|diff| diff.layout.is_some()).unwrap();
+    assert_eq!(account.also_fits, ["AdminAccount"]);
+}
+
+#[test]
+fn data_decodes_only_when_it_fits_exactly() {
+    const LAYOUT: Layout = Layout {
+        name: "Vault",
+        fields: &[
+            Field {
+                name: "open",
+                ty: FieldType::Bool,
+            },
+            Field {
+                name: "balance",
+                ty: FieldType::U64,
+            },
+            Field {
+                name: "delta",
+                ty: FieldType::I16,
+            },
+            Field {
+                name: "tags",
+                ty: FieldType::Vec(&FieldType::U8),
+            },
+        ],
+    };
+    let mut data = vec![1];
+    data.extend(7u64.to_le_bytes());
+    data.extend((-2i16).to_le_bytes());
+    data.extend(2u32.to_le_bytes());
+    data.extend([4, 5]);
+    assert_eq!(
+        diff::decode(&LAYOUT, &data),
+        Some(vec![
+            ("open", Value::Bool(true)),
+            ("balance", Value::Unsigned(7, 64)),
+            ("delta", Value::Signed(-2)),
+            (
+                "tags",
+                Value::List(vec![Value::Unsigned(4, 8), Value::Unsigned(5, 8)])
+            ),
+        ])
+    );
+    assert_eq!(diff::decode(&LAYOUT, &data[..data.len() - 1]), None);
+    data.push(0);
+    assert_eq!(diff::decode(&LAYOUT, &data), None);
+    data[0] = 2;
+    data.pop();
+    assert_eq!(diff::decode(&LAYOUT, &data), None, "a bool is 0 or 1");
+
+    let change = |before, after| FieldChange {
+        name: "balance",
+        before: Some(Value::Unsigned(before, 64)),
+        after: Some(Value::Unsigned(after, 64)),
+    };
+    assert_eq!(
+        change(5, u128::from(u64::MAX) - 4).note(),
+        Some("wrapped below zero")
+    );
+    assert_eq!(
+        change(u128::from(u64::MAX), 3).note(),
+        Some("wrapped past the maximum")
+    );
+    assert_eq!(change(1000, 0).note(), Some("reset"));
+    assert_eq!(change(100, 150).note(), None);
+}
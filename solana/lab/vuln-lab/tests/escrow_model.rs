@@ -0,0 +1,204 @@
+//! account_data_matching's escrows against a reference model of their
+//! lifecycle: each set up for one user, holding lamports for them until
+//! its release time, then released once, by that user, to them. The secure
+//! build keeps to the model under any sequence of releases; the vulnerable
+//! build leaves it within a few
+
+use borsh::{to_vec, BorshDeserialize};
+use lab_domain::{EscrowAccount, UserProfile};
+use lab_instructions::account_data_matching::release_ix;
+use lab_svm::{Keypair, Signer, Svm, LAMPORTS_PER_SOL};
+use lab_test_utils::rent_exempt;
+use rand::rngs::StdRng;
+use rand::Rng;
+use solana_program::entrypoint::ProcessInstruction;
+use solana_program::pubkey::Pubkey;
+use vuln_lab::property::{self, Counterexample};
+
+const SEED: u64 = 1709;
+const CASES: usize = 256;
+const MAX_OPS: usize = 8;
+const USERS: usize = 2;
+/// How long after setup the escrows unlock
+const LOCKUP: i64 = 3_600;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Op {
+    /// A release signed by `signer`, of the escrow at `escrow` through the
+    /// profile at `profile`, to `beneficiary`'s wallet, all by user index
+    Release {
+        signer: usize,
+        profile: usize,
+        escrow: usize,
+        beneficiary: usize,
+    },
+    /// The clock passes the release time
+    Wait,
+}
+
+fn op(rng: &mut StdRng) -> Op {
+    if rng.gen_range(0..4) == 0 {
+        return Op::Wait;
+    }
+    // Mostly a user's own accounts, so that the model's releases happen
+    let signer = rng.gen_range(0..USERS);
+    let mut user = || match rng.gen_range(0..4) {
+        0 => rng.gen_range(0..USERS),
+        _ => signer,
+    };
+    Op::Release {
+        signer,
+        profile: user(),
+        escrow: user(),
+        beneficiary: user(),
+    }
+}
+
+/// What the escrows and wallets should hold
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Model {
+    unlocked: bool,
+    /// What each escrow still owes, as its data says
+    owed: [u64; USERS],
+    /// Each escrow's lamports, then each wallet's
+    lamports: [u64; 2 * USERS],
+}
+
+impl Model {
+    /// Applies `op` if the escrows' lifecycle allows it, and says whether
+    /// it did
+    fn apply(&mut self, op: &Op) -> bool {
+        match *op {
+            Op::Wait => {
+                self.unlocked = true;
+                true
+            }
+            Op::Release {
+                signer,
+                profile,
+                escrow,
+                beneficiary,
+            } => {
+                // Profile and escrow are the signer's own, and the escrow
+                // pays only its beneficiary, once it has unlocked
+                if !self.unlocked || profile != signer || escrow != signer || beneficiary != signer
+                {
+                    return false;
+                }
+                let amount = std::mem::take(&mut self.owed[escrow]);
+                self.lamports[escrow] -= amount;
+                self.lamports[USERS + beneficiary] += amount;
+                true
+            }
+        }
+    }
+}
+
+/// Runs `ops` against escrows set up for each user and the model side by
+/// side; after every one, the build must have taken it exactly when the
+/// model did, and hold what the model holds
+fn follows_the_model(program: ProcessInstruction, ops: &[Op]) -> Result<(), String> {
+    let mut svm = Svm::new();
+    let program_id = svm.add_program(program);
+    let payer = Keypair::new_from_array([0xfa; 32]);
+    svm.airdrop(&payer.pubkey(), LAMPORTS_PER_SOL);
+    let wallets: Vec<Keypair> = (0..USERS)
+        .map(|index| Keypair::new_from_array([index as u8 + 1; 32]))
+        .collect();
+    let release_time = svm.clock().unix_timestamp + LOCKUP;
+
+    let (mut profiles, mut escrows) = (Vec::new(), Vec::new());
+    for (index, wallet) in wallets.iter().enumerate() {
+        svm.airdrop(&wallet.pubkey(), LAMPORTS_PER_SOL);
+        let amount = (index as u64 + 1) * LAMPORTS_PER_SOL;
+        let data = to_vec(&EscrowAccount::new(wallet.pubkey(), amount, release_time)).unwrap();
+        let escrow = rent_exempt(&mut svm, Pubkey::new_unique(), &program_id, data, amount);
+        let data = to_vec(&UserProfile::new(wallet.pubkey(), escrow, amount)).unwrap();
+        let profile = rent_exempt(&mut svm, Pubkey::new_unique(), &program_id, data, 0);
+        profiles.push(profile);
+        escrows.push(escrow);
+    }
+    let mut tracked = escrows.clone();
+    tracked.extend(wallets.iter().map(Keypair::pubkey));
+    let held = |svm: &Svm| Model {
+        unlocked: svm.clock().unix_timestamp >= release_time,
+        owed: std::array::from_fn(|index| {
+            let data = &svm.get_account(&escrows[index]).unwrap().data;
+            EscrowAccount::try_from_slice(data).unwrap().amount
+        }),
+        lamports: std::array::from_fn(|index| svm.lamports(&tracked[index])),
+    };
+    let mut model = held(&svm);
+
+    for (index, op) in ops.iter().enumerate() {
+        let taken = match *op {
+            Op::Wait => {
+                let mut clock = svm.clock().clone();
+                clock.unix_timestamp = release_time;
+                svm.set_clock(clock);
+                true
+            }
+            Op::Release {
+                signer,
+                profile,
+                escrow,
+                beneficiary,
+            } => {
+                let instruction = release_ix(
+                    program_id,
+                    profiles[profile],
+                    escrows[escrow],
+                    wallets[beneficiary].pubkey(),
+                    wallets[signer].pubkey(),
+                );
+                svm.send(&[instruction], &[&payer, &wallets[signer]])
+                    .is_ok()
+            }
+        };
+        let expected = model.apply(op);
+        if taken != expected {
+            let verb = if taken { "took" } else { "refused" };
+            return Err(format!("operation {index}: the build {verb} it"));
+        }
+        let held = held(&svm);
+        if held != model {
+            return Err(format!(
+                "after operation {index}, the build holds {held:?}, not {model:?}"
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn the_secure_escrows_follow_the_model() {
+    let result = property::check(SEED, CASES, MAX_OPS, op, |ops| {
+        follows_the_model(account_data_matching_secure::process_instruction, ops)
+    });
+    if let Err(counterexample) = result {
+        panic!("{counterexample}");
+    }
+}
+
+/// Shrunk, the divergence is a user releasing their own escrow, once it
+/// has unlocked, to someone else's wallet: the beneficiary the escrow
+/// names is never checked
+#[test]
+fn a_redirected_release_leaves_the_model() {
+    let counterexample: Counterexample<Op> = property::check(SEED, CASES, MAX_OPS, op, |ops| {
+        follows_the_model(account_data_matching::process_instruction, ops)
+    })
+    .expect_err("the vulnerable escrows follow the model");
+
+    let [Op::Wait, Op::Release {
+        signer,
+        profile,
+        escrow,
+        beneficiary,
+    }] = counterexample.ops[..]
+    else {
+        panic!("not a wait and a release:\n{counterexample}");
+    };
+    assert_eq!((profile, escrow), (signer, signer), "{counterexample}");
+    assert_ne!(beneficiary, signer, "{counterexample}");
+}
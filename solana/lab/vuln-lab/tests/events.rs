@@ -0,0 +1,57 @@
+//! Attacks read back from the events the examples emit, rather than from
+//! their accounts' bytes
+
+use solana_common::events::{Event, WithdrawEvent};
+use vuln_lab::analysis::{self, Invocation};
+use vuln_lab::{attacker, Harness, Signer, Version, REGISTRY};
+
+/// Every event the example's exploit made `version` emit
+fn events(example: &str, version: Version) -> Vec<Event> {
+    fn collect(invocations: &[Invocation], events: &mut Vec<Event>) {
+        for invocation in invocations {
+            events.extend(invocation.events.iter().cloned());
+            collect(&invocation.inner, events);
+        }
+    }
+    let mut harness = Harness::new(version);
+    let _ = REGISTRY.find(example).unwrap().exploit(&mut harness);
+    let mut events = Vec::new();
+    for transaction in harness.history() {
+        let logs = match transaction {
+            Ok(meta) => &meta.logs,
+            Err(failed) => &failed.meta.logs,
+        };
+        collect(&analysis::parse(logs), &mut events);
+    }
+    events
+}
+
+#[test]
+fn a_second_initialize_hands_the_vault_over() {
+    let handed_to_attacker = |version| {
+        events("reinitialization", version)
+            .iter()
+            .any(|event| match event {
+                Event::AuthorityChanged(changed) => changed.authority == attacker().pubkey(),
+                _ => false,
+            })
+    };
+    assert!(handed_to_attacker(Version::Vulnerable));
+    assert!(!handed_to_attacker(Version::Secure));
+}
+
+#[test]
+fn drained_funds_are_withdrawn_to_the_attacker() {
+    for example in ["missing_signer_check", "missing_owner_check", "pda_issues"] {
+        let paid_attacker = |version| {
+            events(example, version).iter().any(|event| {
+                matches!(
+                    event,
+                    Event::Withdraw(WithdrawEvent { recipient, .. }) if *recipient == attacker().pubkey()
+                )
+            })
+        };
+        assert!(paid_attacker(Version::Vulnerable), "{example}");
+        assert!(!paid_attacker(Version::Secure), "{example}");
+    }
+}
@@ -0,0 +1,210 @@
+//! Every example ships a runnable exploit and a fix it cannot break
+
+use std::fs;
+use std::path::Path;
+
+use lab_registry::{Metadata, CATALOG};
+use vuln_lab::diff;
+use vuln_lab::programs::{crates, PROGRAMS};
+use vuln_lab::{rejected_with, ExploitOutcome, Harness, VerifyFailure, Version, REGISTRY};
+
+/// Verifies the pairing, showing what the failing run's attack did to the
+/// accounts if it breaks
+fn run(example: &str) {
+    let vulnerability = REGISTRY
+        .find(example)
+        .unwrap_or_else(|| panic!("no exploit registered for {example}"));
+    if let Err(failure) = vulnerability.verify() {
+        let version = match failure {
+            VerifyFailure::ExploitFailed(_) => Version::Vulnerable,
+            VerifyFailure::FixBypassed | VerifyFailure::WrongRejection { .. } => Version::Secure,
+        };
+        let (_, _, diffs) = diff::attack(vulnerability, version);
+        panic!(
+            "{example}: {failure}\nwhat the attack did to the accounts:\n{}",
+            diff::render(&diffs, false)
+        );
+    }
+}
+
+#[test]
+fn account_data_matching() {
+    run("account_data_matching");
+}
+
+#[test]
+fn account_versioning() {
+    run("account_versioning");
+}
+
+#[test]
+fn arbitrary_cpi() {
+    run("arbitrary_cpi");
+}
+
+#[test]
+fn arithmetic_errors() {
+    run("arithmetic_errors");
+}
+
+#[test]
+fn missing_owner_check() {
+    run("missing_owner_check");
+}
+
+#[test]
+fn missing_signer_check() {
+    run("missing_signer_check");
+}
+
+#[test]
+fn pda_issues() {
+    run("pda_issues");
+}
+
+#[test]
+fn reinitialization() {
+    run("reinitialization");
+}
+
+#[test]
+fn rent_exemption() {
+    run("rent_exemption");
+}
+
+#[test]
+fn type_confusion() {
+    run("type_confusion");
+}
+
+fn examples() -> Vec<String> {
+    stems(&Path::new(env!("CARGO_MANIFEST_DIR")).join("../.."))
+}
+
+/// `.rs` file names in `dir`, without the extension
+fn stems(dir: &Path) -> Vec<String> {
+    let mut examples: Vec<String> = fs::read_dir(dir)
+        .unwrap()
+        .filter_map(|entry| {
+            let path = entry.unwrap().path();
+            (path.extension()? == "rs").then(|| path.file_stem()?.to_str().map(String::from))?
+        })
+        .collect();
+    examples.sort();
+    examples
+}
+
+#[test]
+fn every_example_has_an_exploit() {
+    let mut registered: Vec<String> = REGISTRY
+        .iter()
+        .map(|vulnerability| vulnerability.example().to_string())
+        .collect();
+    registered.sort();
+
+    assert_eq!(examples(), registered);
+}
+
+#[test]
+fn ids_follow_the_readme_inventory() {
+    let readme =
+        fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join("../../README.md")).unwrap();
+    for vulnerability in REGISTRY.iter() {
+        let number: usize = vulnerability
+            .id()
+            .strip_prefix("SOL-")
+            .unwrap()
+            .parse()
+            .unwrap();
+        let heading = format!("### {number}. {}.rs", vulnerability.example());
+        assert!(
+            readme.contains(&heading),
+            "{} has no README section {heading:?}",
+            vulnerability.id()
+        );
+    }
+}
+
+#[test]
+fn ids_are_unique() {
+    let mut ids: Vec<&str> = REGISTRY
+        .iter()
+        .map(|vulnerability| vulnerability.id())
+        .collect();
+    ids.sort();
+    ids.dedup();
+    assert_eq!(ids.len(), REGISTRY.len(), "two exploits share an id");
+}
+
+#[test]
+fn the_catalog_agrees_with_the_registry() {
+    assert_eq!(CATALOG.len(), REGISTRY.len());
+    for (metadata, vulnerability) in CATALOG.iter().zip(REGISTRY.iter()) {
+        assert_eq!(
+            metadata,
+            &Metadata {
+                id: vulnerability.id(),
+                example: vulnerability.example(),
+                category: vulnerability.category(),
+                severity: vulnerability.severity(),
+                difficulty: vulnerability.difficulty(),
+                prerequisites: vulnerability.prerequisites(),
+                description: vulnerability.description(),
+                scenario: metadata.scenario,
+                references: vulnerability.references(),
+                accounts: vulnerability.accounts(Version::Vulnerable),
+                secure_accounts: vulnerability.accounts(Version::Secure),
+            }
+        );
+        assert!(!metadata.scenario.is_empty(), "{}", metadata.example);
+    }
+}
+
+#[test]
+fn every_vulnerability_has_a_reference() {
+    let missing: Vec<&str> = REGISTRY
+        .iter()
+        .filter(|vulnerability| vulnerability.references().is_empty())
+        .map(|vulnerability| vulnerability.id())
+        .collect();
+    assert!(missing.is_empty(), "no references for {missing:?}");
+}
+
+#[test]
+fn every_example_has_a_program_crate() {
+    let attackers = stems(&Path::new(env!("CARGO_MANIFEST_DIR")).join("../../attackers"));
+    let crates = crates();
+    let missing: Vec<String> = examples()
+        .into_iter()
+        .flat_map(|example| [format!("{example}_secure"), example])
+        .chain(
+            attackers
+                .iter()
+                .map(|example| format!("{example}_attacker")),
+        )
+        .filter(|krate| !crates.contains(krate))
+        .collect();
+    assert!(missing.is_empty(), "no programs/ crate for {missing:?}");
+}
+
+#[test]
+fn every_program_crate_has_a_native_entrypoint() {
+    let registered: Vec<String> = PROGRAMS
+        .iter()
+        .map(|(name, _, _)| name.to_string())
+        .collect();
+    assert_eq!(crates(), registered);
+}
+
+#[test]
+fn a_rejection_names_the_error_the_fix_returns() {
+    let vulnerability = REGISTRY.find("missing_owner_check").unwrap();
+    let ExploitOutcome::Failed(err) = vulnerability.exploit(&mut Harness::new(Version::Secure))
+    else {
+        panic!("the secure build stops the exploit");
+    };
+    assert_eq!(vulnerability.rejection(), "Validation(WrongOwner)");
+    assert!(rejected_with(&err, "Validation(WrongOwner)"));
+    assert!(rejected_with(&err, "custom program error: 0x3e9"));
+    assert!(!rejected_with(&err, "Validation(MissingSignature)"));
+}
@@ -0,0 +1,38 @@
+//! Failures from the shared checks are printed with what their code means
+
+use lab_svm::TransactionError;
+use solana_program::instruction::InstructionError;
+use vuln_lab::report;
+use vuln_lab::{failure, REGISTRY};
+
+#[test]
+fn shared_codes_are_spelled_out() {
+    let custom = |code| TransactionError::InstructionError(1, InstructionError::Custom(code));
+    assert_eq!(
+        failure::describe(&custom(2000)),
+        "Error processing Instruction 1: custom program error: 0x7d0 \
+         (arithmetic error 2000: arithmetic would overflow)"
+    );
+    // Not one of ours
+    assert_eq!(
+        failure::describe(&custom(7)),
+        "Error processing Instruction 1: custom program error: 0x7"
+    );
+    assert_eq!(
+        failure::describe(&TransactionError::AccountNotFound),
+        TransactionError::AccountNotFound.to_string()
+    );
+}
+
+#[test]
+fn a_blocked_exploit_says_which_check_stopped_it() {
+    let report = report::run(REGISTRY.find("missing_signer_check").unwrap());
+    let secure = &report.secure;
+    let decoded = "(validation error 1000: account did not sign)";
+    assert!(secure.error.as_ref().unwrap().ends_with(decoded));
+    assert!(secure.transactions[1]
+        .error
+        .as_ref()
+        .unwrap()
+        .ends_with(decoded));
+}
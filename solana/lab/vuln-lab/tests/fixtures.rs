@@ -0,0 +1,68 @@
+//! Demo keypairs derive from the seed phrase alone, so every run of every
+//! exploit uses the same addresses
+
+use vuln_lab::fixtures::{self, Fixtures, DEFAULT_PHRASE};
+use vuln_lab::{Harness, Signer, Version, REGISTRY};
+
+#[test]
+fn keypairs_follow_the_phrase_and_label() {
+    let workshop = Fixtures::new("room 101 workshop");
+    assert_eq!(workshop.phrase(), "room 101 workshop");
+    assert_eq!(
+        workshop.keypair("victim").to_bytes(),
+        Fixtures::new("  room 101\tworkshop ")
+            .keypair("victim")
+            .to_bytes(),
+        "whitespace is not part of the phrase"
+    );
+    assert_ne!(workshop.address("victim"), workshop.address("attacker"));
+    assert_ne!(
+        workshop.address("victim"),
+        Fixtures::default().address("victim")
+    );
+    assert_eq!(Fixtures::default().phrase(), DEFAULT_PHRASE);
+    assert_eq!(workshop.victim().pubkey(), workshop.address("victim"));
+
+    let a = ("a".to_string(), workshop.address("a"));
+    let b = ("b".to_string(), workshop.address("b"));
+    let ((), outer) = fixtures::recorded(|| {
+        workshop.keypair("a");
+        let ((), inner) = fixtures::recorded(|| {
+            workshop.keypair("b");
+        });
+        assert_eq!(inner, std::slice::from_ref(&b));
+        workshop.keypair("a");
+    });
+    assert_eq!(outer, [a, b]);
+}
+
+#[test]
+fn exploits_run_on_the_same_addresses_every_time() {
+    let wallets = fixtures::current();
+    assert_eq!(vuln_lab::attacker().pubkey(), wallets.address("attacker"));
+
+    for vulnerability in REGISTRY.iter() {
+        let example = vulnerability.example();
+        let run = || {
+            fixtures::recorded(|| {
+                let mut harness = Harness::new(Version::Vulnerable);
+                vulnerability.exploit(&mut harness);
+                harness
+            })
+        };
+        let (harness, first) = run();
+        let (_, second) = run();
+        assert_eq!(first, second, "{example}");
+
+        let program = format!("{example}/program");
+        let program_id = wallets.address(&program);
+        assert!(
+            first.iter().any(|(label, _)| *label == program),
+            "{example}"
+        );
+        assert!(harness.program_ids().contains(&program_id), "{example}");
+        for (label, address) in &first {
+            assert_eq!(wallets.address(label), *address, "{example}: {label}");
+        }
+    }
+}
@@ -0,0 +1,188 @@
+//! Funding rides out a faucet that refuses requests, against a stand-in
+//! RPC server; `devnet_round_trip` does it for real when run with
+//! `--ignored`
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use solana_program::pubkey::Pubkey;
+use vuln_lab::funding::{self, Backoff, Funding};
+use vuln_lab::rpc::Rpc;
+use vuln_lab::{Keypair, Signer};
+
+#[derive(Default)]
+struct Faucet {
+    balances: HashMap<String, u64>,
+    /// Airdrop requests to refuse before granting any
+    refusals: usize,
+    requests: Vec<u64>,
+}
+
+/// Serves getBalance, requestAirdrop and getSignatureStatuses from `faucet`
+fn serve(faucet: Arc<Mutex<Faucet>>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(&mut stream);
+            let mut length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                    length = value.trim().parse().unwrap();
+                }
+                if line == "\r\n" {
+                    break;
+                }
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            let request: Value = serde_json::from_slice(&body).unwrap();
+
+            let mut faucet = faucet.lock().unwrap();
+            let params = &request["params"];
+            let response = match request["method"].as_str().unwrap() {
+                "getBalance" => {
+                    let balance = faucet.balances.get(params[0].as_str().unwrap());
+                    json!({ "result": { "value": balance.copied().unwrap_or(0) } })
+                }
+                "requestAirdrop" if faucet.refusals > 0 => {
+                    faucet.refusals -= 1;
+                    json!({ "error": { "code": 429, "message": "rate limited" } })
+                }
+                "requestAirdrop" => {
+                    let lamports = params[1].as_u64().unwrap();
+                    faucet.requests.push(lamports);
+                    *faucet
+                        .balances
+                        .entry(params[0].as_str().unwrap().to_string())
+                        .or_default() += lamports;
+                    json!({ "result": Keypair::new().sign_message(b"airdrop").to_string() })
+                }
+                "getSignatureStatuses" => {
+                    let statuses: Vec<Value> = params[0]
+                        .as_array()
+                        .unwrap()
+                        .iter()
+                        .map(|_| json!({ "confirmationStatus": "confirmed", "err": null }))
+                        .collect();
+                    json!({ "result": { "value": statuses } })
+                }
+                method => panic!("unexpected {method}"),
+            };
+            let body = response.to_string();
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+                 Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+            .unwrap();
+        }
+    });
+    url
+}
+
+fn quick() -> Funding {
+    Funding {
+        max_airdrop: 1_000,
+        batch: 2,
+        backoff: Backoff {
+            initial: Duration::from_millis(1),
+            max: Duration::from_millis(4),
+            attempts: 4,
+        },
+        confirm_timeout: Duration::from_secs(1),
+    }
+}
+
+#[test]
+fn funding_retries_refused_airdrops_and_tops_up_to_target() {
+    let (topped_up, funded) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let faucet = Arc::new(Mutex::new(Faucet {
+        balances: HashMap::from([(topped_up.to_string(), 500), (funded.to_string(), 5_000)]),
+        refusals: 3,
+        ..Faucet::default()
+    }));
+    let rpc = Rpc::new(serve(faucet.clone()));
+
+    quick()
+        .fund(&rpc, &[(topped_up, 2_500), (funded, 1_000)])
+        .unwrap();
+
+    let faucet = faucet.lock().unwrap();
+    assert_eq!(faucet.balances[&topped_up.to_string()], 2_500);
+    assert_eq!(faucet.balances[&funded.to_string()], 5_000);
+    // 2_000 short, in requests of at most max_airdrop
+    assert_eq!(faucet.requests, [1_000, 1_000]);
+}
+
+#[test]
+fn funding_gives_up_after_its_retries() {
+    let faucet = Arc::new(Mutex::new(Faucet {
+        refusals: usize::MAX,
+        ..Faucet::default()
+    }));
+    let rpc = Rpc::new(serve(faucet));
+    let err = quick()
+        .fund(&rpc, &[(Pubkey::new_unique(), 1)])
+        .unwrap_err();
+    assert!(err.to_string().contains("rate limited"), "{err}");
+}
+
+#[test]
+fn backoff_doubles_up_to_its_cap() {
+    let backoff = Backoff {
+        initial: Duration::from_millis(100),
+        max: Duration::from_millis(350),
+        attempts: 5,
+    };
+    let delays: Vec<u128> = (0..4)
+        .map(|round| backoff.delay(round).as_millis())
+        .collect();
+    assert_eq!(delays, [100, 200, 350, 350]);
+}
+
+#[test]
+fn demo_wallets_are_stable_and_distinct() {
+    let base = Keypair::new_from_array([7; 32]);
+    let wallets = funding::demo_wallets(&base);
+    let again = funding::demo_wallets(&base);
+    for ((role, wallet), (_, same)) in wallets.iter().zip(&again) {
+        assert_eq!(wallet.pubkey(), same.pubkey(), "{role}");
+        assert_ne!(wallet.pubkey(), base.pubkey());
+    }
+    assert_ne!(wallets[0].1.pubkey(), wallets[1].1.pubkey());
+    let other = funding::demo_wallets(&Keypair::new_from_array([8; 32]));
+    assert_ne!(wallets[0].1.pubkey(), other[0].1.pubkey());
+}
+
+/// Needs the Solana CLI's keypair with a little devnet SOL for the fee
+#[test]
+#[ignore = "hits devnet"]
+fn devnet_round_trip() {
+    let rpc = Rpc::new("https://api.devnet.solana.com");
+    let base = funding::read_keypair(&funding::default_keypair_path()).unwrap();
+    let wallets = funding::demo_wallets(&base);
+    let targets: Vec<_> = wallets
+        .iter()
+        .map(|(_, wallet)| (wallet.pubkey(), 10_000_000))
+        .collect();
+    Funding::default().fund(&rpc, &targets).unwrap();
+    for (address, target) in &targets {
+        assert!(rpc.balance(address).unwrap() >= *target);
+    }
+
+    let wallets: Vec<&Keypair> = wallets.iter().map(|(_, wallet)| wallet).collect();
+    assert!(funding::reclaim(&rpc, &wallets, &base).unwrap() >= 30_000_000);
+    for (address, _) in &targets {
+        assert_eq!(rpc.balance(address).unwrap(), 0);
+    }
+}
@@ -0,0 +1,102 @@
+//! A vulnerable build's panic is expected and a secure build's is a
+//! finding, a flow the fix refuses and the example takes is the bug, and
+//! every example has a fuzz target that builds for libFuzzer and honggfuzz
+
+use std::fs;
+use std::path::Path;
+
+use lab_errors::StateError;
+use vuln_lab::{fuzz, Version, REGISTRY};
+
+#[test]
+fn only_secure_panics_are_findings() {
+    // No accounts and no instruction data: the vulnerable build reads a
+    // tag that is not there
+    let panic = fuzz::run("type_confusion", &[]).expect("the vulnerable build panics");
+    assert!(panic.contains("index out of bounds"), "{panic}");
+    assert_eq!(fuzz::execute("type_confusion", Version::Secure, &[]), None);
+
+    // A signed withdrawal of more lamports than the wallet holds, which
+    // the secure build once debited unchecked and overflowed
+    let input = [
+        [2].as_slice(),
+        // wallet 0, signer and writable, 1/16 SOL, no data
+        &[3, 1, 0],
+        // wallet 1, writable
+        &[1 << 3 | 2, 0, 0],
+        &u64::MAX.to_le_bytes(),
+    ]
+    .concat();
+    assert_eq!(
+        fuzz::execute("missing_signer_check", Version::Secure, &input),
+        None
+    );
+    fuzz::run("missing_signer_check", &input);
+}
+
+/// One step of a flow: the honest use's instruction `index`, with
+/// `mutations` applied to its account metas in order
+fn step(index: u8, mutations: &[u8]) -> Vec<u8> {
+    let mut bytes = vec![index, 0, 0];
+    bytes.extend(mutations);
+    bytes.resize(3 + fuzz::MAX_ACCOUNTS, 0);
+    bytes
+}
+
+#[test]
+fn honest_flows_agree_and_a_dropped_signature_does_not() {
+    assert!(fuzz::diverge("missing_signer_check", &[]).is_none());
+    assert!(fuzz::diverge("account_versioning", &[]).is_none());
+
+    // The withdrawal, with its depositor's signature left off
+    let input = [[0].as_slice(), &step(0, &[2])].concat();
+    let divergence = fuzz::diverge("missing_signer_check", &input).expect("the builds part");
+    assert_eq!(divergence.step, 0);
+    assert!(!divergence.instruction.accounts[0].is_signer);
+}
+
+#[test]
+fn a_second_migration_is_the_versioning_bug() {
+    // Migrate, then migrate again: only the secure build can tell the
+    // account is already v2, and refuses with WrongVersion
+    let input = [[1].as_slice(), &step(1, &[]), &step(1, &[])].concat();
+    let divergence = fuzz::diverge("account_versioning", &input).expect("the builds part");
+    assert_eq!(divergence.step, 1);
+    let error = divergence
+        .error
+        .as_deref()
+        .expect("the secure build refuses");
+    assert!(
+        error.contains(&format!("{:#x}", StateError::WrongVersion as u32)),
+        "{error}"
+    );
+}
+
+#[test]
+fn every_example_has_a_fuzz_target() {
+    let fuzz_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../fuzz");
+    let manifest = fs::read_to_string(fuzz_dir.join("Cargo.toml")).unwrap();
+    for vulnerability in REGISTRY.iter() {
+        let example = vulnerability.example();
+        let target = fuzz_dir.join("fuzz_targets").join(format!("{example}.rs"));
+        let source = fs::read_to_string(&target).unwrap_or_default();
+        assert!(
+            source.contains(&format!("\"{example}\"")),
+            "no fuzz target for {example}"
+        );
+        // Declared through the shared macro, not libfuzzer-sys, so it
+        // builds for either fuzzer
+        assert!(
+            source.contains("lab_fuzz::target!"),
+            "{example}'s fuzz target is libFuzzer-only"
+        );
+        assert!(
+            manifest.contains(&format!("name = \"{example}\"")),
+            "{example} is not a [[bin]] of fuzz/Cargo.toml"
+        );
+    }
+    assert!(manifest.contains("name = \"flows\""));
+    assert!(manifest.contains("name = \"exploits\""));
+    assert!(manifest.contains("name = \"pda_collisions\""));
+    assert!(manifest.contains("honggfuzz = [\"dep:honggfuzz\"]"));
+}
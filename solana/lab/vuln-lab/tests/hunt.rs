@@ -0,0 +1,67 @@
+//! The exploit hunt finds the reinitialization takeover and the amount
+//! that drains through the underflowing transfer, with neither written
+//! down anywhere it looks
+
+use vuln_lab::fixtures;
+use vuln_lab::fuzz::{self, Objective};
+use vuln_lab::{attacker, hunt, Signer, Svm, Version};
+
+const SEED: u64 = 1716;
+const ATTACKS: usize = 6000;
+
+/// An honest initialize, then the same initialize signed by the attacker,
+/// which the fix refuses
+#[test]
+fn a_second_initialize_takes_over_the_vault() {
+    let vault = fixtures::address("reinitialization/vault");
+    let hunt = hunt::hunt("reinitialization", SEED, ATTACKS).expect("an exploit");
+    let exploit = &hunt.exploit;
+    assert_eq!(
+        exploit.objective,
+        Objective::Takeover {
+            account: vault,
+            offset: 0
+        },
+        "{hunt}"
+    );
+    assert_eq!(exploit.instructions.len(), 2, "{hunt}");
+    for instruction in &exploit.instructions {
+        assert_eq!(instruction.data[0], 0, "{hunt}");
+        assert_eq!(instruction.accounts[0].pubkey, vault, "{hunt}");
+    }
+    let takeover = &exploit.instructions[1];
+    assert_eq!(takeover.accounts[1].pubkey, attacker().pubkey(), "{hunt}");
+    assert!(takeover.accounts[1].is_signer, "{hunt}");
+
+    let secure = fuzz::attack("reinitialization", Version::Secure, &hunt.moves);
+    assert!(secure.exploit.is_none(), "{:?}", secure.outcomes);
+}
+
+/// The honest stake turned into a transfer from the pool to the attacker,
+/// of exactly what the pool holds: a lamport more underflows it, and the
+/// runtime refuses the unbalanced result, while a lamport less leaves it
+/// short of rent
+#[test]
+fn the_transfer_drains_the_pool_to_the_lamport() {
+    let pool = fixtures::address("arithmetic_errors/pool");
+    let hunt = hunt::hunt("arithmetic_errors", SEED, ATTACKS).expect("an exploit");
+    let exploit = &hunt.exploit;
+    let [transfer] = exploit.instructions.as_slice() else {
+        panic!("{hunt}");
+    };
+    assert_eq!(transfer.data[0], 2, "{hunt}");
+    assert_eq!(transfer.accounts[0].pubkey, pool, "{hunt}");
+    // The attacker takes the lamports and signs as the user
+    for meta in &transfer.accounts[1..] {
+        assert_eq!(meta.pubkey, attacker().pubkey(), "{hunt}");
+    }
+
+    let amount = u64::from_le_bytes(transfer.data[1..9].try_into().unwrap());
+    // total_staked, reward_rate and last_update, and rent for no more
+    assert_eq!(amount, Svm::new().minimum_balance(24), "{hunt}");
+    assert_eq!(
+        exploit.objective,
+        Objective::Profit { lamports: amount },
+        "{hunt}"
+    );
+}
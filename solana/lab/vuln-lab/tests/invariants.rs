@@ -0,0 +1,93 @@
+//! Every honest use keeps its example's invariants in both builds, and the
+//! wrapping stake breaks arithmetic_errors' where the fix refuses it
+
+use std::sync::{Arc, Mutex};
+
+use borsh::to_vec;
+use lab_domain::{StakingPool, UserStake};
+use lab_instructions::arithmetic_errors::stake_ix;
+use lab_svm::{Keypair, Signer, Svm, LAMPORTS_PER_SOL};
+use lab_test_utils::rent_exempt;
+use solana_program::entrypoint::ProcessInstruction;
+use solana_program::pubkey::Pubkey;
+use vuln_lab::{fixtures, invariants, Harness, Version, REGISTRY};
+
+/// Checked before every transaction an honest use sends, and after the
+/// last
+#[test]
+fn every_honest_use_keeps_its_invariants() {
+    let mut checked = Vec::new();
+    for vulnerability in REGISTRY.iter() {
+        let example = vulnerability.example();
+        if invariants::of(example).is_empty() {
+            continue;
+        }
+        checked.push(example);
+        let program_id = fixtures::address(&format!("{example}/program"));
+        for version in [Version::Vulnerable, Version::Secure] {
+            let broken = Arc::new(Mutex::new(Vec::new()));
+            let mut harness = Harness::new(version);
+            let record = Arc::clone(&broken);
+            harness.inspect(move |svm, _| {
+                if let Err(violation) = invariants::check(example, svm, &program_id) {
+                    record.lock().unwrap().push(violation);
+                }
+            });
+            if let Some(Err(err)) = vulnerability.honest_use(&mut harness) {
+                panic!("honest use of {example} failed against the {version:?} build: {err:#}");
+            }
+            let last = invariants::check(example, &harness, &program_id);
+            let broken = broken.lock().unwrap();
+            assert!(broken.is_empty(), "{example} {version:?}: {broken:?}");
+            assert_eq!(last, Ok(()), "{example} {version:?}");
+        }
+    }
+    assert_eq!(
+        checked,
+        [
+            "account_data_matching",
+            "arithmetic_errors",
+            "reinitialization"
+        ]
+    );
+}
+
+/// The exploit's stake of 200 into a pool 100 short of u64::MAX
+#[test]
+fn the_wrapping_stake_breaks_the_vulnerable_pool() {
+    let stake = |program: ProcessInstruction| {
+        let mut svm = Svm::new();
+        let program_id = svm.add_program(program);
+        let pool = StakingPool {
+            total_staked: u64::MAX - 100,
+            reward_rate: 1,
+            last_update: 0,
+        };
+        let pool = to_vec(&pool).unwrap();
+        let pool = rent_exempt(&mut svm, Pubkey::new_unique(), &program_id, pool, 0);
+        let stake = to_vec(&UserStake::new(0, 0)).unwrap();
+        let stake = rent_exempt(&mut svm, Pubkey::new_unique(), &program_id, stake, 0);
+        let user = Keypair::new_from_array([1; 32]);
+        svm.airdrop(&user.pubkey(), LAMPORTS_PER_SOL);
+        invariants::check("arithmetic_errors", &svm, &program_id).unwrap();
+
+        let sent = svm.send(
+            &[stake_ix(program_id, pool, stake, user.pubkey(), 200)],
+            &[&user],
+        );
+        (
+            sent.is_ok(),
+            invariants::check("arithmetic_errors", &svm, &program_id),
+        )
+    };
+
+    let (taken, kept) = stake(arithmetic_errors::process_instruction);
+    assert!(taken);
+    let violation = kept.expect_err("the wrapped pool keeps its invariant");
+    assert_eq!(violation.invariant, "total_staked >= sum(user stakes)");
+    assert!(violation.reason.contains("is 99,"), "{violation}");
+
+    let (taken, kept) = stake(arithmetic_errors_secure::process_instruction);
+    assert!(!taken);
+    assert_eq!(kept, Ok(()));
+}
@@ -0,0 +1,59 @@
+//! The mutants `vuln-lab mutants` builds: each check in a secure version,
+//! and each `// CHECK:`, taken out on its own and nothing else with it
+
+use std::fs;
+
+use vuln_lab::mutants::mutants;
+use vuln_lab::programs;
+use vuln_lab::walkthrough::{annotations, Kind};
+use vuln_lab::{Version, REGISTRY};
+
+#[test]
+fn every_fix_is_taken_out_by_a_mutant_of_its_own() {
+    for vulnerability in REGISTRY.iter() {
+        let example = vulnerability.example();
+        let source = fs::read_to_string(programs::source(example, Version::Secure)).unwrap();
+        let mutants = mutants(example).unwrap();
+        for fix in annotations(&source).unwrap() {
+            if fix.kind != Kind::Check {
+                continue;
+            }
+            let mutant = mutants
+                .iter()
+                .find(|mutant| mutant.lines == fix.code)
+                .unwrap_or_else(|| panic!("no mutant for {example}'s fix at line {}", fix.line));
+            assert_eq!(mutant.fix.as_ref(), Some(&fix.text));
+        }
+
+        let lines: Vec<&str> = source.lines().collect();
+        for mutant in &mutants {
+            let mutated: Vec<&str> = mutant.source.lines().collect();
+            assert_eq!(mutated.len(), lines.len(), "{mutant}");
+            for (index, (line, mutated)) in lines.iter().zip(&mutated).enumerate() {
+                let removed = (mutant.lines.0..=mutant.lines.1).contains(&(index + 1));
+                assert_eq!(*mutated, if removed { "" } else { *line }, "{mutant}");
+            }
+        }
+    }
+}
+
+#[test]
+fn reinitializations_mutants_are_its_checks() {
+    let mutants: Vec<String> = mutants("reinitialization")
+        .unwrap()
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(
+        mutants,
+        [
+            "secure/reinitialization.rs:55: deleted `assert_owned_by(vault_account, program_id)?;` in initialize",
+            "secure/reinitialization.rs:57: deleted `assert_signer(authority_account)?;` in initialize",
+            "secure/reinitialization.rs:61: deleted `if vault_account.data.borrow().starts_with(&VaultConfig::DISCRIMINATOR) {` in initialize",
+            "secure/reinitialization.rs:93: deleted `assert_owned_by(vault_account, program_id)?;` in deposit",
+            "secure/reinitialization.rs:95: deleted `assert_signer(user_account)?;` in deposit",
+            "secure/reinitialization.rs:98: deleted `let mut vault_config = VaultConfig::try_deserialize_checked(&vault_account.data.borrow())` in deposit",
+            "secure/reinitialization.rs:109: deleted `if user_account.lamports() < amount {` in deposit",
+        ]
+    );
+}
@@ -0,0 +1,155 @@
+//! arithmetic_errors.rs's native builds both ways: with overflow checks
+//! off, as `cargo build-sbf` builds it, and on, as a debug build does. The
+//! same exploit that silently wraps a counter in the first panics in the
+//! second. Only the native builds are run here, not the SBF one
+
+use std::env;
+use std::process::Command;
+
+use borsh::{to_vec, BorshDeserialize};
+use lab_domain::{StakingPool, UserStake};
+use lab_instructions::arithmetic_errors::{calculate_rewards_ix, stake_ix};
+use lab_svm::{Keypair, Signer, Svm, TransactionResult, LAMPORTS_PER_SOL};
+use lab_test_utils::rent_exempt;
+use solana_program::instruction::Instruction;
+use solana_program::pubkey::Pubkey;
+use vuln_lab::programs::{self, OverflowChecks};
+
+const EXAMPLE: &str = "arithmetic_errors";
+const PANICKED: &str = "Program log: panicked: ";
+const REWARD: &str = "User reward calculated: ";
+
+/// A pool and one user's stake in it, on a runtime with arithmetic_errors'
+/// build for `checks`
+struct Staking {
+    svm: Svm,
+    program_id: Pubkey,
+    pool: Pubkey,
+    stake: Pubkey,
+    user: Keypair,
+}
+
+impl Staking {
+    fn new(checks: OverflowChecks, pool: StakingPool, stake: UserStake) -> Self {
+        let krate = checks.native(EXAMPLE).expect("built both ways");
+        let program_id = programs::id(&krate).unwrap();
+        let mut svm = Svm::new();
+        svm.add_program_with_id(program_id, programs::entrypoint(&krate).unwrap());
+        let [pool, stake] = [to_vec(&pool).unwrap(), to_vec(&stake).unwrap()]
+            .map(|data| rent_exempt(&mut svm, Pubkey::new_unique(), &program_id, data, 0));
+        let user = Keypair::new_from_array([1; 32]);
+        svm.airdrop(&user.pubkey(), LAMPORTS_PER_SOL);
+        Self {
+            svm,
+            program_id,
+            pool,
+            stake,
+            user,
+        }
+    }
+
+    fn send(&mut self, instruction: Instruction) -> TransactionResult {
+        self.svm.send(&[instruction], &[&self.user])
+    }
+
+    fn total_staked(&self) -> u64 {
+        let data = &self.svm.get_account(&self.pool).unwrap().data;
+        StakingPool::try_from_slice(data).unwrap().total_staked
+    }
+}
+
+/// What the program panicked with, if it did
+fn panic_message(result: &TransactionResult) -> Option<&str> {
+    let failed = result.as_ref().err()?;
+    failed
+        .meta
+        .logs
+        .iter()
+        .find_map(|line| line.strip_prefix(PANICKED))
+}
+
+/// The exploit the registry runs: 200 staked into a pool 100 short of
+/// `u64::MAX`
+#[test]
+fn a_native_stake_past_u64_max_wraps_unchecked_and_panics_checked() {
+    let pool = StakingPool {
+        total_staked: u64::MAX - 100,
+        reward_rate: 1,
+        last_update: 0,
+    };
+    for checks in [OverflowChecks::Off, OverflowChecks::On] {
+        let mut staking = Staking::new(checks, pool.clone(), UserStake::new(0, 0));
+        let instruction = stake_ix(
+            staking.program_id,
+            staking.pool,
+            staking.stake,
+            staking.user.pubkey(),
+            200,
+        );
+        let result = staking.send(instruction);
+        match checks {
+            OverflowChecks::Off => {
+                result.expect("the unchecked build takes the stake");
+                assert_eq!(staking.total_staked(), (u64::MAX - 100).wrapping_add(200));
+            }
+            OverflowChecks::On => {
+                assert_eq!(panic_message(&result), Some("attempt to add with overflow"));
+                assert_eq!(staking.total_staked(), u64::MAX - 100);
+            }
+        }
+    }
+}
+
+/// Calculates the reward for a stake in a pool whose reward rate is so
+/// high that a second of it overflows
+fn calculate_rewards(checks: OverflowChecks) -> TransactionResult {
+    let pool = StakingPool {
+        total_staked: 1,
+        reward_rate: u64::MAX,
+        last_update: 0,
+    };
+    let mut staking = Staking::new(checks, pool, UserStake::new(1, 0));
+    let instruction = calculate_rewards_ix(
+        staking.program_id,
+        staking.pool,
+        staking.stake,
+        staking.user.pubkey(),
+    );
+    staking.send(instruction)
+}
+
+/// The unchecked build logs whatever the product wrapped to as the
+/// reward; the checked build panics
+#[test]
+fn a_native_reward_past_u64_max_wraps_unchecked_and_panics_checked() {
+    let elapsed = Svm::new().clock().unix_timestamp as u64;
+    assert_eq!(logged_reward(), elapsed.wrapping_mul(u64::MAX));
+    assert_eq!(
+        panic_message(&calculate_rewards(OverflowChecks::On)),
+        Some("attempt to multiply with overflow")
+    );
+}
+
+/// Off chain, `msg!` prints to stdout, which the test harness keeps to
+/// itself; [`logged_reward`] runs this on its own to read the reward
+#[test]
+#[ignore = "run by a_native_reward_past_u64_max_wraps_unchecked_and_panics_checked"]
+fn unchecked_reward() {
+    calculate_rewards(OverflowChecks::Off).expect("the unchecked build calculates a reward");
+}
+
+/// The reward the unchecked build logs, from a run of [`unchecked_reward`]
+fn logged_reward() -> u64 {
+    let output = Command::new(env::current_exe().unwrap())
+        .args(["unchecked_reward", "--exact", "--ignored", "--nocapture"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{stdout}");
+    stdout
+        .lines()
+        .find_map(|line| line.strip_prefix(REWARD))
+        .expect("the reward is logged")
+        .parse()
+        .unwrap()
+}
@@ -0,0 +1,67 @@
+//! Recorded exploits replay on a fresh runtime exactly as they ran, and a
+//! replay against a changed program says where it departs
+
+use vuln_lab::recording::{self, Recorder, Recording};
+use vuln_lab::{diff, Harness, Version, REGISTRY};
+
+fn record(example: &str) -> (Harness, Recording) {
+    let vulnerability = REGISTRY.find(example).unwrap();
+    let mut harness = Harness::new(Version::Vulnerable);
+    let recorder = Recorder::install(&mut harness);
+    let (harness, outcome, _) = diff::attack_on(harness, vulnerability);
+    assert!(outcome.succeeded(), "{example}");
+    let recording = recorder
+        .finish(&harness, example, Version::Vulnerable)
+        .unwrap();
+    (harness, recording)
+}
+
+#[test]
+fn every_exploit_replays_as_recorded() {
+    let path = std::env::temp_dir().join(format!("vuln-lab-recording-{}.json", std::process::id()));
+    for vulnerability in REGISTRY.iter() {
+        let example = vulnerability.example();
+        let (harness, recording) = record(example);
+        assert_eq!(
+            recording.transactions.len(),
+            harness.history().len(),
+            "{example}"
+        );
+        recording.save(&path).unwrap();
+        let loaded = Recording::load(&path).unwrap();
+        assert_eq!(loaded, recording, "{example}");
+
+        let replay = recording::replay(&loaded).unwrap();
+        assert_eq!(replay.mismatches, [], "{example}");
+        for step in &recording.transactions {
+            for state in &step.accounts {
+                assert_eq!(
+                    replay.svm.get_account(&state.address),
+                    harness.get_account(&state.address),
+                    "{example}: {}",
+                    state.address
+                );
+            }
+        }
+    }
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn a_fixed_program_shows_where_the_replay_departs() {
+    let (_, mut recording) = record("missing_owner_check");
+    for program in &mut recording.programs {
+        if program.krate == "missing_owner_check" {
+            program.krate = "missing_owner_check_secure".to_string();
+        }
+    }
+    let replay = recording::replay(&recording).unwrap();
+    let [mismatch] = &replay.mismatches[..] else {
+        panic!("{:?}", replay.mismatches);
+    };
+    assert_eq!(mismatch.recorded.error, None);
+    assert_eq!(
+        mismatch.replayed.error.as_deref(),
+        Some("Error processing Instruction 0: custom program error: 0x3e9")
+    );
+}
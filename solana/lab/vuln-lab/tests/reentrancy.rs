@@ -0,0 +1,92 @@
+//! An attacker the relay hands a call to cannot come back in through it:
+//! the runtime refuses the callback, and the secure relay never makes the
+//! call. Only the relay's own recursion is left, which its lock stops
+
+use lab_instructions::arbitrary_cpi::invoke_ix;
+use lab_svm::{Keypair, Signer, Svm, TransactionError, TransactionResult, LAMPORTS_PER_SOL};
+use lab_test_utils::rent_exempt;
+use solana_program::account_info::AccountInfo;
+use solana_program::entrypoint::{ProcessInstruction, ProgramResult};
+use solana_program::instruction::{AccountMeta, Instruction, InstructionError};
+use solana_program::program::invoke;
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+use solana_system_interface::program as system_program;
+
+/// Invoked as the relay's target, calls back into the relay named by its
+/// data with the user's signature, asking it to relay to the system
+/// program
+fn call_back(_program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let relay_program = Pubkey::try_from(data).map_err(|_| ProgramError::InvalidInstructionData)?;
+    let [user, target_account] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    invoke(
+        &Instruction::new_with_bytes(
+            relay_program,
+            &[],
+            vec![
+                AccountMeta::new(*user.key, true),
+                AccountMeta::new(*target_account.key, false),
+            ],
+        ),
+        accounts,
+    )
+}
+
+/// The relay deployed as `program`, with its relay account, and a funded
+/// user; sends the call to `target_program`, passing the relay's id on
+fn relay_to(program: ProcessInstruction, target: ProcessInstruction) -> TransactionResult {
+    let mut svm = Svm::new();
+    let program_id = svm.add_program(program);
+    let target_program = svm.add_program(target);
+    let (relay, _bump) = arbitrary_cpi_secure::RELAY.find([], &program_id);
+    rent_exempt(&mut svm, relay, &program_id, vec![0], 0);
+    let user = Keypair::new();
+    svm.airdrop(&user.pubkey(), LAMPORTS_PER_SOL);
+
+    svm.send(
+        &[invoke_ix(
+            program_id,
+            user.pubkey(),
+            target_program,
+            system_program::ID,
+            relay,
+            program_id.to_bytes().to_vec(),
+        )],
+        &[&user],
+    )
+}
+
+fn instruction_error(result: TransactionResult) -> InstructionError {
+    match result.map_err(|failed| failed.err) {
+        Err(TransactionError::InstructionError(0, err)) => err,
+        other => panic!("expected an instruction error, got {other:?}"),
+    }
+}
+
+#[test]
+fn the_runtime_refuses_an_attacker_calling_back_into_the_vulnerable_relay() {
+    let result = relay_to(arbitrary_cpi::process_instruction, call_back);
+    assert_eq!(
+        instruction_error(result),
+        InstructionError::ReentrancyNotAllowed
+    );
+}
+
+#[test]
+fn the_secure_relay_never_hands_the_attacker_the_call() {
+    let result = relay_to(arbitrary_cpi_secure::process_instruction, call_back);
+    assert_eq!(
+        instruction_error(result),
+        InstructionError::IncorrectProgramId
+    );
+    let result = relay_to(
+        arbitrary_cpi_secure::process_instruction,
+        arbitrary_cpi_attacker::process_instruction,
+    );
+    assert_eq!(
+        instruction_error(result),
+        InstructionError::IncorrectProgramId
+    );
+}
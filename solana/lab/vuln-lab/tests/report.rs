@@ -0,0 +1,73 @@
+//! Reports carry what graders check: the outcome, the transactions and
+//! where the lamports went
+
+use serde_json::Value;
+use vuln_lab::report::{self, Outcome};
+use vuln_lab::REGISTRY;
+
+#[test]
+fn a_drained_deposit_shows_in_the_balances() {
+    let report = report::run(REGISTRY.find("missing_signer_check").unwrap());
+    assert_eq!(report.outcome, Outcome::Blocked);
+
+    let vulnerable = &report.vulnerable;
+    assert!(vulnerable.succeeded);
+    // Planting the flag, the attack, capturing the flag
+    assert_eq!(vulnerable.transactions.len(), 3);
+    assert!(vulnerable
+        .transactions
+        .iter()
+        .all(|transaction| transaction.error.is_none()));
+    let drained = vulnerable
+        .balances
+        .iter()
+        .find(|balance| balance.before > 0 && balance.after == 0)
+        .expect("the deposit account was emptied");
+    assert!(drained.before > 5_000_000_000);
+
+    let secure = &report.secure;
+    assert!(!secure.succeeded);
+    assert_eq!(secure.transactions.len(), 2);
+    assert!(secure.transactions[1].error.is_some());
+    assert!(!secure
+        .balances
+        .iter()
+        .any(|balance| balance.before > 0 && balance.after == 0));
+}
+
+#[test]
+fn json_lists_every_exploit() {
+    let reports: Vec<_> = REGISTRY.iter().map(report::run).collect();
+    let json: Value = serde_json::from_str(&report::to_json(&reports)).unwrap();
+    let entries = json.as_array().unwrap();
+    assert_eq!(entries.len(), REGISTRY.len());
+    for entry in entries {
+        assert_eq!(entry["outcome"], "blocked", "{}", entry["example"]);
+        assert!(entry["vulnerable"]["program_id"].is_string());
+    }
+}
+
+#[test]
+fn sarif_has_a_rule_and_a_finding_per_exploit() {
+    let reports: Vec<_> = REGISTRY.iter().map(report::run).collect();
+    let sarif: Value = serde_json::from_str(&report::to_sarif(&reports)).unwrap();
+    assert_eq!(sarif["version"], "2.1.0");
+    let run = &sarif["runs"][0];
+    assert_eq!(
+        run["tool"]["driver"]["rules"].as_array().unwrap().len(),
+        REGISTRY.len()
+    );
+
+    let results = run["results"].as_array().unwrap();
+    let findings: Vec<&str> = results
+        .iter()
+        .filter(|result| result["level"] == "error")
+        .map(|result| {
+            result["locations"][0]["physicalLocation"]["artifactLocation"]["uri"]
+                .as_str()
+                .unwrap()
+        })
+        .collect();
+    assert_eq!(findings.len(), REGISTRY.len());
+    assert!(findings.iter().all(|uri| !uri.contains("secure/")));
+}
@@ -0,0 +1,853 @@
+//! The tier `vuln-lab mutants` runs against each secure build with one of
+//! its checks taken out. Every case is an honest instruction with one
+//! thing wrong that only one check stops, and asserts the error that
+//! check fails with: with the check gone, the instruction either goes
+//! through or fails some other way, and the case fails. Each handler's
+//! honest instruction goes through too, so a check that stopped
+//! everything would not pass either
+
+use borsh::to_vec;
+use lab_domain::{EscrowAccount, StakingPool, UserProfile, UserStake, VaultConfig};
+use lab_errors::{AccessError, ArithmeticError, StateError, ValidationError};
+use lab_instructions::{
+    account_data_matching, account_versioning, arbitrary_cpi, arithmetic_errors, batch_payout,
+    missing_owner_check, missing_signer_check, pda_issues, reinitialization, rent_exemption,
+    type_confusion,
+};
+use lab_svm::{Keypair, Signer, Svm, TransactionError, TransactionResult, LAMPORTS_PER_SOL};
+use lab_test_utils::rent_exempt;
+use solana_common::access_control::{Member, Role};
+use solana_common::discriminator::AccountDiscriminator;
+use solana_common::pausable::{PauseState, WithdrawCap};
+use solana_common::token_utils::{TOKEN_ACCOUNT_LEN, TOKEN_PROGRAM_ID};
+use solana_program::account_info::AccountInfo;
+use solana_program::entrypoint::{ProcessInstruction, ProgramResult};
+use solana_program::instruction::{AccountMeta, Instruction, InstructionError};
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+use solana_system_interface::instruction as system_instruction;
+use solana_system_interface::program as system_program;
+
+/// A runtime with one program deployed, and a fee payer that is never
+/// one of its accounts
+struct Checks {
+    svm: Svm,
+    program_id: Pubkey,
+    payer: Keypair,
+}
+
+impl Checks {
+    fn new(program: ProcessInstruction) -> Self {
+        let mut svm = Svm::new();
+        let program_id = svm.add_program(program);
+        let payer = Keypair::new_from_array([0xfa; 32]);
+        svm.airdrop(&payer.pubkey(), LAMPORTS_PER_SOL);
+        Self {
+            svm,
+            program_id,
+            payer,
+        }
+    }
+
+    /// A funded wallet
+    fn wallet(&mut self, index: u8) -> Keypair {
+        let wallet = Keypair::new_from_array([index; 32]);
+        self.svm.airdrop(&wallet.pubkey(), LAMPORTS_PER_SOL);
+        wallet
+    }
+
+    /// A rent-exempt account at `key`, owned by `owner`, holding `data`
+    /// and `lamports` on top of the rent-exempt minimum
+    fn account_at(&mut self, key: Pubkey, owner: &Pubkey, data: Vec<u8>, lamports: u64) -> Pubkey {
+        rent_exempt(&mut self.svm, key, owner, data, lamports)
+    }
+
+    /// The same at a fresh address, owned by the program
+    fn account(&mut self, data: Vec<u8>, lamports: u64) -> Pubkey {
+        let program_id = self.program_id;
+        self.account_at(Pubkey::new_unique(), &program_id, data, lamports)
+    }
+
+    /// The same, owned by some other program
+    fn foreign(&mut self, data: Vec<u8>, lamports: u64) -> Pubkey {
+        self.account_at(Pubkey::new_unique(), &Pubkey::new_unique(), data, lamports)
+    }
+
+    fn send(&mut self, instruction: Instruction, signers: &[&Keypair]) -> TransactionResult {
+        let mut all = vec![&self.payer];
+        all.extend(signers);
+        self.svm.send(&[instruction], &all)
+    }
+}
+
+/// `instruction` with `key` no longer asked to sign
+fn unsigned(mut instruction: Instruction, key: &Pubkey) -> Instruction {
+    for meta in &mut instruction.accounts {
+        if meta.pubkey == *key {
+            meta.is_signer = false;
+        }
+    }
+    instruction
+}
+
+/// `instruction` with `key` passed read-only
+fn readonly(mut instruction: Instruction, key: &Pubkey) -> Instruction {
+    for meta in &mut instruction.accounts {
+        if meta.pubkey == *key {
+            meta.is_writable = false;
+        }
+    }
+    instruction
+}
+
+/// The program refused the transaction's only instruction with `error`
+#[track_caller]
+fn assert_refused(result: TransactionResult, error: impl Into<ProgramError>) {
+    let expected = InstructionError::from(u64::from(error.into()));
+    let failed = result.expect_err("the secure build took it");
+    assert_eq!(
+        failed.err,
+        TransactionError::InstructionError(0, expected),
+        "{:#?}",
+        failed.meta.logs
+    );
+}
+
+/// Data with the discriminator `T` leads with
+fn discriminated<T: AccountDiscriminator>(value: &T) -> Vec<u8> {
+    let mut data = Vec::new();
+    value.serialize_with_discriminator(&mut data).unwrap();
+    data
+}
+
+/// A program that takes any instruction and does nothing
+fn accept(_: &Pubkey, _: &[AccountInfo], _: &[u8]) -> ProgramResult {
+    Ok(())
+}
+
+/// As much of SPL Token as a transfer needs: it moves the amount between
+/// the two accounts' token balances
+fn token_transfer(_: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [source, destination, authority] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let amount = data
+        .get(1..9)
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(u64::from_le_bytes)
+        .ok_or(ProgramError::InvalidInstructionData)?;
+    for (account, change) in [(source, amount.wrapping_neg()), (destination, amount)] {
+        let mut data = account.try_borrow_mut_data()?;
+        let balance = u64::from_le_bytes(data[64..72].try_into().unwrap());
+        data[64..72].copy_from_slice(&balance.wrapping_add(change).to_le_bytes());
+    }
+    Ok(())
+}
+
+#[test]
+fn account_data_matching_releases() {
+    use account_data_matching::release_ix;
+
+    const AMOUNT: u64 = LAMPORTS_PER_SOL;
+    let mut checks = Checks::new(account_data_matching_secure::process_instruction);
+    let program_id = checks.program_id;
+    let owner = checks.wallet(1);
+    let other = checks.wallet(2);
+    let release_time = checks.svm.clock().unix_timestamp + 60;
+    let escrow = |beneficiary: &Keypair| {
+        to_vec(&EscrowAccount::new(
+            beneficiary.pubkey(),
+            AMOUNT,
+            release_time,
+        ))
+        .unwrap()
+    };
+    let profile = |escrow| to_vec(&UserProfile::new(owner.pubkey(), escrow, AMOUNT)).unwrap();
+    let (data, extra) = (escrow(&owner), escrow(&owner));
+    let owners_escrow = checks.account(data, AMOUNT);
+    let unnamed_escrow = checks.account(extra, AMOUNT);
+    let owners_profile = checks.account(profile(owners_escrow), 0);
+    let release = |profile, escrow, beneficiary: &Keypair, signer: &Keypair| {
+        release_ix(
+            program_id,
+            profile,
+            escrow,
+            beneficiary.pubkey(),
+            signer.pubkey(),
+        )
+    };
+
+    let honest = release(owners_profile, owners_escrow, &owner, &owner);
+    let result = checks.send(honest.clone(), &[&owner]);
+    assert_refused(result, ProgramError::InvalidAccountData);
+
+    let mut clock = checks.svm.clock().clone();
+    clock.unix_timestamp = release_time;
+    checks.svm.set_clock(clock);
+
+    let forged_profile = checks.foreign(profile(owners_escrow), 0);
+    let result = checks.send(
+        release(forged_profile, owners_escrow, &owner, &owner),
+        &[&owner],
+    );
+    assert_refused(result, ValidationError::WrongOwner);
+
+    let forged_escrow = checks.foreign(escrow(&owner), AMOUNT);
+    let naming_forged = checks.account(profile(forged_escrow), 0);
+    let result = checks.send(
+        release(naming_forged, forged_escrow, &owner, &owner),
+        &[&owner],
+    );
+    assert_refused(result, ValidationError::WrongOwner);
+
+    let result = checks.send(unsigned(honest.clone(), &owner.pubkey()), &[]);
+    assert_refused(result, ValidationError::MissingSignature);
+
+    for (escrow, beneficiary, signer) in [
+        (owners_escrow, &owner, &other),
+        (unnamed_escrow, &owner, &owner),
+        (owners_escrow, &other, &owner),
+    ] {
+        let result = checks.send(
+            release(owners_profile, escrow, beneficiary, signer),
+            &[signer],
+        );
+        assert_refused(result, ValidationError::KeyMismatch);
+    }
+
+    let before = checks.svm.lamports(&owner.pubkey());
+    checks.send(honest, &[&owner]).unwrap();
+    assert_eq!(checks.svm.lamports(&owner.pubkey()), before + AMOUNT);
+}
+
+#[test]
+fn account_data_matching_token_withdrawals() {
+    use account_data_matching_secure::{TokenVault, TOKEN_AUTHORITY};
+
+    let mut checks = Checks::new(account_data_matching_secure::secure_token_withdraw);
+    let program_id = checks.program_id;
+    checks
+        .svm
+        .add_program_with_id(TOKEN_PROGRAM_ID, token_transfer);
+    let authority = checks.wallet(1);
+    let other = checks.wallet(2);
+    let mut tokens = |amount: u64| {
+        let mut data = vec![0; TOKEN_ACCOUNT_LEN];
+        data[64..72].copy_from_slice(&amount.to_le_bytes());
+        checks.account_at(Pubkey::new_unique(), &TOKEN_PROGRAM_ID, data, 0)
+    };
+    let (token_account, other_tokens, destination) = (tokens(100), tokens(100), tokens(0));
+    let vault = TokenVault {
+        authority: authority.pubkey(),
+        token_account,
+    };
+    let vault = checks.account(to_vec(&vault).unwrap(), 0);
+    let (vault_signer, _) = TOKEN_AUTHORITY.find([], &program_id);
+    let withdraw = |token_account, authority: &Keypair| {
+        Instruction::new_with_bytes(
+            program_id,
+            &40u64.to_le_bytes(),
+            vec![
+                AccountMeta::new_readonly(vault, false),
+                AccountMeta::new(token_account, false),
+                AccountMeta::new_readonly(authority.pubkey(), true),
+                AccountMeta::new(destination, false),
+                AccountMeta::new_readonly(vault_signer, false),
+                AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            ],
+        )
+    };
+
+    let honest = withdraw(token_account, &authority);
+    let result = checks.send(unsigned(honest.clone(), &authority.pubkey()), &[]);
+    assert_refused(result, ValidationError::MissingSignature);
+    let result = checks.send(withdraw(token_account, &other), &[&other]);
+    assert_refused(result, ValidationError::KeyMismatch);
+    let result = checks.send(withdraw(other_tokens, &authority), &[&authority]);
+    assert_refused(result, ValidationError::KeyMismatch);
+
+    checks.send(honest, &[&authority]).unwrap();
+    let balance = &checks.svm.get_account(&destination).unwrap().data[64..72];
+    assert_eq!(balance, 40u64.to_le_bytes());
+}
+
+#[test]
+fn account_versioning_opens_withdrawals_and_migrations() {
+    use account_versioning::{migrate_ix, open_ix, withdraw_ix};
+    use account_versioning_secure::{UserAccountV1, UserAccountV2, VAULT};
+
+    const BALANCE: u64 = 100;
+    let mut checks = Checks::new(account_versioning_secure::process_instruction);
+    let program_id = checks.program_id;
+    let user = checks.wallet(1);
+    let other = checks.wallet(2);
+    let v1 = |owner: &Keypair| {
+        let account = UserAccountV1 {
+            owner: owner.pubkey(),
+            balance: BALANCE,
+        };
+        [vec![1], to_vec(&account).unwrap()].concat()
+    };
+    let v2 = |owner: &Keypair| {
+        let account = UserAccountV2 {
+            owner: owner.pubkey(),
+            delegate: Pubkey::default(),
+            balance: BALANCE,
+        };
+        [vec![2], to_vec(&account).unwrap()].concat()
+    };
+    let (vault, _) = VAULT.find([], &program_id);
+    checks.account_at(vault, &program_id, Vec::new(), LAMPORTS_PER_SOL);
+    let not_the_vault = checks.account(Vec::new(), LAMPORTS_PER_SOL);
+
+    let empty = checks.account(vec![0; UserAccountV2::LEN], 0);
+    let open = open_ix(program_id, user.pubkey(), empty, Pubkey::default());
+    let result = checks.send(unsigned(open.clone(), &user.pubkey()), &[]);
+    assert_refused(result, ValidationError::MissingSignature);
+    let forged = checks.foreign(vec![0; UserAccountV2::LEN], 0);
+    let result = checks.send(
+        open_ix(program_id, user.pubkey(), forged, Pubkey::default()),
+        &[&user],
+    );
+    assert_refused(result, ValidationError::WrongOwner);
+    let opened = checks.account(v2(&other), 0);
+    let result = checks.send(
+        open_ix(program_id, user.pubkey(), opened, Pubkey::default()),
+        &[&user],
+    );
+    assert_refused(result, ProgramError::AccountAlreadyInitialized);
+    checks.send(open, &[&user]).unwrap();
+
+    let (users_v1, users_v2) = (checks.account(v1(&user), 0), checks.account(v2(&user), 0));
+    let withdraw = |account, amount| withdraw_ix(program_id, user.pubkey(), account, vault, amount);
+    let result = checks.send(unsigned(withdraw(users_v2, 1), &user.pubkey()), &[]);
+    assert_refused(result, ValidationError::MissingSignature);
+    let forged = checks.foreign(v2(&user), 0);
+    let result = checks.send(withdraw(forged, 1), &[&user]);
+    assert_refused(result, ValidationError::WrongOwner);
+    let result = checks.send(
+        withdraw_ix(program_id, user.pubkey(), users_v2, not_the_vault, 1),
+        &[&user],
+    );
+    assert_refused(result, ValidationError::InvalidPda);
+    let result = checks.send(withdraw(users_v2, BALANCE + 1), &[&user]);
+    assert_refused(result, ProgramError::InsufficientFunds);
+    for account in [users_v1, users_v2] {
+        let result = checks.send(
+            withdraw_ix(program_id, other.pubkey(), account, vault, 1),
+            &[&other],
+        );
+        assert_refused(result, ValidationError::KeyMismatch);
+        checks.send(withdraw(account, BALANCE), &[&user]).unwrap();
+    }
+
+    let old = checks.account(v1(&user), 0);
+    let migrate = migrate_ix(program_id, user.pubkey(), old);
+    let result = checks.send(unsigned(migrate.clone(), &user.pubkey()), &[]);
+    assert_refused(result, ValidationError::MissingSignature);
+    let forged = checks.foreign(v1(&user), 0);
+    let result = checks.send(migrate_ix(program_id, user.pubkey(), forged), &[&user]);
+    assert_refused(result, ValidationError::WrongOwner);
+    let result = checks.send(migrate_ix(program_id, other.pubkey(), old), &[&other]);
+    assert_refused(result, ValidationError::KeyMismatch);
+    checks.send(migrate, &[&user]).unwrap();
+    assert_eq!(checks.svm.get_account(&old).unwrap().data, v2(&user));
+}
+
+#[test]
+fn arbitrary_cpi_invocations() {
+    use arbitrary_cpi::invoke_ix;
+
+    let mut checks = Checks::new(arbitrary_cpi_secure::process_instruction);
+    let program_id = checks.program_id;
+    let anything = checks.svm.add_program(accept);
+    let user = checks.wallet(1);
+    let recipient = checks.wallet(3).pubkey();
+    let transfer = system_instruction::transfer(&user.pubkey(), &recipient, 1_000).data;
+    let (relay, _bump) = arbitrary_cpi_secure::RELAY.find([], &program_id);
+    checks.account_at(relay, &program_id, vec![0], 0);
+    let other_relay = checks.account(vec![0], 0);
+    let invoke = |target_program, relay| {
+        invoke_ix(
+            program_id,
+            user.pubkey(),
+            target_program,
+            recipient,
+            relay,
+            transfer.clone(),
+        )
+    };
+
+    let honest = invoke(system_program::ID, relay);
+    let result = checks.send(unsigned(honest.clone(), &user.pubkey()), &[]);
+    assert_refused(result, ValidationError::MissingSignature);
+    let result = checks.send(invoke(anything, relay), &[&user]);
+    assert_refused(result, ProgramError::IncorrectProgramId);
+    let result = checks.send(invoke(system_program::ID, other_relay), &[&user]);
+    assert_refused(result, ValidationError::InvalidPda);
+    checks.account_at(relay, &program_id, vec![1], 0);
+    let result = checks.send(honest.clone(), &[&user]);
+    assert_refused(result, StateError::Locked);
+    checks.account_at(relay, &program_id, vec![0], 0);
+
+    checks.send(honest, &[&user]).unwrap();
+    assert_eq!(checks.svm.lamports(&recipient), LAMPORTS_PER_SOL + 1_000);
+    assert_eq!(checks.svm.get_account(&relay).unwrap().data, [0]);
+}
+
+#[test]
+fn arithmetic_errors_stakes_rewards_and_transfers() {
+    use arithmetic_errors::{calculate_rewards_ix, stake_ix, transfer_ix};
+
+    let mut checks = Checks::new(arithmetic_errors_secure::process_instruction);
+    let program_id = checks.program_id;
+    let user = checks.wallet(1);
+    let now = checks.svm.clock().unix_timestamp;
+    let mut pool = StakingPool::new(1, now);
+    pool.total_staked = 100;
+    let pool = checks.account(to_vec(&pool).unwrap(), 0);
+    let stake = checks.account(to_vec(&UserStake::new(100, now)).unwrap(), 0);
+    let full = StakingPool {
+        total_staked: u64::MAX - 100,
+        ..StakingPool::new(1, now)
+    };
+    let full = checks.account(to_vec(&full).unwrap(), 0);
+    let fresh = checks.account(to_vec(&UserStake::new(0, now)).unwrap(), 0);
+
+    let honest = stake_ix(program_id, pool, stake, user.pubkey(), 10);
+    let result = checks.send(unsigned(honest.clone(), &user.pubkey()), &[]);
+    assert_refused(result, ValidationError::MissingSignature);
+    let result = checks.send(
+        stake_ix(program_id, full, fresh, user.pubkey(), 200),
+        &[&user],
+    );
+    assert_refused(result, ArithmeticError::Overflow);
+    checks.send(honest, &[&user]).unwrap();
+
+    let honest = calculate_rewards_ix(program_id, pool, stake, user.pubkey());
+    let result = checks.send(unsigned(honest.clone(), &user.pubkey()), &[]);
+    assert_refused(result, ValidationError::MissingSignature);
+    checks.send(honest, &[&user]).unwrap();
+
+    let (from, to) = (
+        checks.account(Vec::new(), 1_000),
+        checks.account(Vec::new(), 0),
+    );
+    let honest = transfer_ix(program_id, from, to, user.pubkey(), 1_000);
+    let result = checks.send(unsigned(honest.clone(), &user.pubkey()), &[]);
+    assert_refused(result, ValidationError::MissingSignature);
+    let result = checks.send(
+        transfer_ix(program_id, from, from, user.pubkey(), 1_000),
+        &[&user],
+    );
+    assert_refused(result, ValidationError::DuplicateAccount);
+    checks.send(honest, &[&user]).unwrap();
+    assert_eq!(
+        checks.svm.lamports(&to),
+        checks.svm.minimum_balance(0) + 1_000
+    );
+}
+
+#[test]
+fn batch_payout_registrations_payouts_and_handovers() {
+    use batch_payout::{
+        accept_authority_ix, distribute_ix, initialize_ix, propose_authority_ix, register_ix,
+    };
+    use batch_payout_secure::Payroll;
+
+    let mut checks = Checks::new(batch_payout_secure::process_instruction);
+    let program_id = checks.program_id;
+    let authority = checks.wallet(1);
+    let successor = checks.wallet(2);
+    let other = checks.wallet(3);
+    let recipient = checks.wallet(4);
+    let payroll = checks.account(vec![0; 32 + 1 + 8 + 4 + 4], LAMPORTS_PER_SOL);
+    let initialize = initialize_ix(program_id, payroll, authority.pubkey(), 100);
+    checks.send(initialize, &[&authority]).unwrap();
+
+    let register = register_ix(program_id, payroll, recipient.pubkey(), authority.pubkey());
+    let result = checks.send(
+        unsigned(register.clone(), &authority.pubkey()),
+        &[&recipient],
+    );
+    assert_refused(result, ValidationError::MissingSignature);
+    let result = checks.send(
+        register_ix(program_id, payroll, recipient.pubkey(), other.pubkey()),
+        &[&recipient, &other],
+    );
+    assert_refused(result, ValidationError::KeyMismatch);
+    checks.send(register, &[&recipient, &authority]).unwrap();
+    let distribute = |authority: &Keypair| {
+        distribute_ix(
+            program_id,
+            payroll,
+            authority.pubkey(),
+            &[recipient.pubkey()],
+        )
+    };
+    let result = checks.send(distribute(&other), &[&other]);
+    assert_refused(result, ValidationError::KeyMismatch);
+
+    let accept = accept_authority_ix(program_id, payroll, successor.pubkey());
+    let result = checks.send(accept.clone(), &[&successor]);
+    assert_refused(result, AccessError::NoPendingAuthority);
+    let result = checks.send(
+        propose_authority_ix(program_id, payroll, other.pubkey(), other.pubkey()),
+        &[&other],
+    );
+    assert_refused(result, ValidationError::KeyMismatch);
+    let propose = propose_authority_ix(program_id, payroll, authority.pubkey(), successor.pubkey());
+    checks.send(propose, &[&authority]).unwrap();
+    // Proposed, the payroll is still the authority's
+    checks.send(distribute(&authority), &[&authority]).unwrap();
+    let result = checks.send(
+        accept_authority_ix(program_id, payroll, other.pubkey()),
+        &[&other],
+    );
+    assert_refused(result, AccessError::NotPendingAuthority);
+    let result = checks.send(unsigned(accept.clone(), &successor.pubkey()), &[]);
+    assert_refused(result, ValidationError::MissingSignature);
+    checks.send(accept, &[&successor]).unwrap();
+
+    let result = checks.send(distribute(&authority), &[&authority]);
+    assert_refused(result, ValidationError::KeyMismatch);
+    checks.send(distribute(&successor), &[&successor]).unwrap();
+    assert_eq!(
+        checks.svm.lamports(&recipient.pubkey()),
+        LAMPORTS_PER_SOL + 200
+    );
+    let data = &checks.svm.get_account(&payroll).unwrap().data;
+    let payroll = borsh::from_slice::<Payroll>(data).unwrap();
+    assert_eq!(payroll.authority.authority, successor.pubkey());
+    assert_eq!(payroll.authority.pending, None);
+}
+
+#[test]
+fn missing_owner_check_withdrawals() {
+    use missing_owner_check::withdraw_ix;
+    use missing_owner_check_secure::VaultData;
+
+    let mut checks = Checks::new(missing_owner_check_secure::process_instruction);
+    let program_id = checks.program_id;
+    let authority = checks.wallet(1);
+    let other = checks.wallet(2);
+    let recipient = checks.wallet(3).pubkey();
+    let vault = checks.account(Vec::new(), 1_000);
+    let other_vault = checks.account(Vec::new(), 1_000);
+    let mut vault_data = VaultData {
+        authority: Member::new(authority.pubkey(), Role::PAUSER),
+        vault,
+        pause: PauseState::new(authority.pubkey()),
+        cap: WithdrawCap::new(600, 60),
+    };
+    let pauser_data = checks.account(discriminated(&vault_data), 0);
+    vault_data.authority.roles = Role::TREASURER;
+    vault_data.pause.paused = true;
+    let paused_data = checks.account(discriminated(&vault_data), 0);
+    vault_data.pause.paused = false;
+    let vault_data = checks.account(discriminated(&vault_data), 0);
+    let withdraw = |vault, authority: &Keypair, amount| {
+        withdraw_ix(
+            program_id,
+            vault_data,
+            vault,
+            authority.pubkey(),
+            recipient,
+            amount,
+        )
+    };
+
+    let honest = withdraw(vault, &authority, 600);
+    let result = checks.send(unsigned(honest.clone(), &authority.pubkey()), &[]);
+    assert_refused(result, ValidationError::MissingSignature);
+    let result = checks.send(withdraw(other_vault, &authority, 1_000), &[&authority]);
+    assert_refused(result, ValidationError::KeyMismatch);
+    let result = checks.send(withdraw(vault, &other, 1_000), &[&other]);
+    assert_refused(result, ValidationError::KeyMismatch);
+    let result = checks.send(withdraw(vault, &authority, u64::MAX), &[&authority]);
+    assert_refused(result, ProgramError::InsufficientFunds);
+    let result = checks.send(withdraw(vault, &authority, 601), &[&authority]);
+    assert_refused(result, AccessError::WithdrawCapExceeded);
+    let paused = withdraw_ix(
+        program_id,
+        paused_data,
+        vault,
+        authority.pubkey(),
+        recipient,
+        600,
+    );
+    let result = checks.send(paused, &[&authority]);
+    assert_refused(result, AccessError::Paused);
+    let pauser = withdraw_ix(
+        program_id,
+        pauser_data,
+        vault,
+        authority.pubkey(),
+        recipient,
+        600,
+    );
+    let result = checks.send(pauser, &[&authority]);
+    assert_refused(result, AccessError::MissingRole);
+
+    checks.send(honest, &[&authority]).unwrap();
+    assert_eq!(checks.svm.lamports(&recipient), LAMPORTS_PER_SOL + 600);
+}
+
+#[test]
+fn missing_signer_check_withdrawals() {
+    use missing_signer_check::withdraw_ix;
+
+    let mut checks = Checks::new(missing_signer_check_secure::process_instruction);
+    let program_id = checks.program_id;
+    let user = Keypair::new_from_array([1; 32]);
+    checks.account_at(user.pubkey(), &program_id, Vec::new(), 1_000);
+    let destination = checks.wallet(3).pubkey();
+
+    let honest = withdraw_ix(program_id, user.pubkey(), destination, 1_000);
+    let result = checks.send(unsigned(honest.clone(), &user.pubkey()), &[]);
+    assert_refused(result, ValidationError::MissingSignature);
+    let result = checks.send(
+        withdraw_ix(program_id, user.pubkey(), destination, u64::MAX),
+        &[&user],
+    );
+    assert_refused(result, ProgramError::InsufficientFunds);
+
+    checks.send(honest, &[&user]).unwrap();
+    assert_eq!(checks.svm.lamports(&destination), LAMPORTS_PER_SOL + 1_000);
+}
+
+#[test]
+fn pda_issues_withdrawals() {
+    use pda_issues::withdraw_ix;
+    use pda_issues_secure::{UserData, USER_DATA};
+
+    const BALANCE: u64 = 1_000;
+    let mut checks = Checks::new(pda_issues_secure::process_instruction);
+    let program_id = checks.program_id;
+    let user = checks.wallet(1);
+    let other = checks.wallet(2);
+    let recipient = checks.wallet(3).pubkey();
+    let data = |owner: &Keypair| {
+        let data = UserData {
+            owner: owner.pubkey(),
+            balance: BALANCE,
+        };
+        to_vec(&data).unwrap()
+    };
+    let (users, _) = USER_DATA.find([&user.pubkey()], &program_id);
+    checks.account_at(users, &program_id, data(&user), BALANCE);
+    let (others, _) = USER_DATA.find([&other.pubkey()], &program_id);
+    checks.account_at(others, &program_id, data(&user), BALANCE);
+    let not_a_pda = checks.account(data(&user), BALANCE);
+    let withdraw = |signer: &Keypair, pda, amount| {
+        withdraw_ix(program_id, signer.pubkey(), pda, recipient, amount)
+    };
+
+    let honest = withdraw(&user, users, BALANCE);
+    let result = checks.send(unsigned(honest.clone(), &user.pubkey()), &[]);
+    assert_refused(result, ValidationError::MissingSignature);
+    let result = checks.send(withdraw(&user, not_a_pda, BALANCE), &[&user]);
+    assert_refused(result, ValidationError::InvalidPda);
+    let result = checks.send(withdraw(&other, others, BALANCE), &[&other]);
+    assert_refused(result, ValidationError::KeyMismatch);
+    let result = checks.send(withdraw(&user, users, BALANCE + 1), &[&user]);
+    assert_refused(result, ProgramError::InsufficientFunds);
+
+    let mut forged = Checks::new(pda_issues_secure::process_instruction);
+    let (users, _) = USER_DATA.find([&user.pubkey()], &forged.program_id);
+    forged.svm.airdrop(&user.pubkey(), LAMPORTS_PER_SOL);
+    forged.account_at(users, &Pubkey::new_unique(), data(&user), BALANCE);
+    let instruction = withdraw_ix(forged.program_id, user.pubkey(), users, recipient, BALANCE);
+    let result = forged.send(instruction, &[&user]);
+    assert_refused(result, ValidationError::WrongOwner);
+
+    checks.send(honest, &[&user]).unwrap();
+    assert_eq!(checks.svm.lamports(&recipient), LAMPORTS_PER_SOL + BALANCE);
+}
+
+#[test]
+fn pda_issues_vault_bumps() {
+    use pda_issues_secure::VAULT;
+
+    let mut checks = Checks::new(pda_issues_secure::secure_with_bump);
+    let program_id = checks.program_id;
+    let (canonical, bump) = VAULT.find([], &program_id);
+    let other_bump = (0..bump)
+        .rev()
+        .find_map(|bump| Pubkey::create_program_address(&[VAULT.prefix, &[bump]], &program_id).ok())
+        .expect("a bump below the canonical one");
+    let check = |vault| {
+        Instruction::new_with_bytes(
+            program_id,
+            &[],
+            vec![AccountMeta::new_readonly(vault, false)],
+        )
+    };
+
+    let result = checks.send(check(other_bump), &[]);
+    assert_refused(result, ValidationError::InvalidPda);
+    checks.send(check(canonical), &[]).unwrap();
+}
+
+#[test]
+fn reinitialization_initializes_and_deposits() {
+    use reinitialization::{deposit_ix, initialize_ix};
+
+    let mut checks = Checks::new(reinitialization_secure::process_instruction);
+    let program_id = checks.program_id;
+    let authority = checks.wallet(1);
+    let empty = checks.account(vec![0; VaultConfig::SPACE], 0);
+    let foreign = checks.foreign(vec![0; VaultConfig::SPACE], 0);
+    let initialized = discriminated(&VaultConfig::new(authority.pubkey(), 1));
+    let live = checks.account(initialized.clone(), 0);
+
+    let honest = initialize_ix(program_id, empty, authority.pubkey(), 1);
+    let result = checks.send(
+        initialize_ix(program_id, foreign, authority.pubkey(), 1),
+        &[&authority],
+    );
+    assert_refused(result, ValidationError::WrongOwner);
+    let result = checks.send(unsigned(honest.clone(), &authority.pubkey()), &[]);
+    assert_refused(result, ValidationError::MissingSignature);
+    let result = checks.send(
+        initialize_ix(program_id, live, authority.pubkey(), 1),
+        &[&authority],
+    );
+    assert_refused(result, ProgramError::AccountAlreadyInitialized);
+    checks.send(honest, &[&authority]).unwrap();
+
+    // Debited by the program, so it owns the depositor's account
+    let depositor = Keypair::new_from_array([2; 32]);
+    checks.account_at(depositor.pubkey(), &program_id, Vec::new(), 1_000);
+    let foreign = checks.foreign(initialized, 0);
+    let honest = deposit_ix(program_id, live, depositor.pubkey(), 1_000);
+    let result = checks.send(
+        deposit_ix(program_id, foreign, depositor.pubkey(), 1_000),
+        &[&depositor],
+    );
+    assert_refused(result, ValidationError::WrongOwner);
+    let result = checks.send(unsigned(honest.clone(), &depositor.pubkey()), &[]);
+    assert_refused(result, ValidationError::MissingSignature);
+    let result = checks.send(
+        deposit_ix(program_id, live, depositor.pubkey(), u64::MAX),
+        &[&depositor],
+    );
+    assert_refused(result, ProgramError::InsufficientFunds);
+    checks.send(honest, &[&depositor]).unwrap();
+    let vault = VaultConfig::try_deserialize_checked(&checks.svm.get_account(&live).unwrap().data);
+    assert_eq!(vault.unwrap().total_deposited, 1_000);
+}
+
+#[test]
+fn rent_exemption_initializes_and_withdrawals() {
+    use rent_exemption::{initialize_ix, withdraw_all_ix};
+    use rent_exemption_secure::UserData;
+
+    const LEN: usize = 32 + 8 + 32;
+    let mut checks = Checks::new(rent_exemption_secure::process_instruction);
+    let program_id = checks.program_id;
+    let user = checks.wallet(1);
+    let other = checks.wallet(2);
+    let recipient = checks.wallet(3).pubkey();
+
+    let record = checks.account(vec![0; LEN], 0);
+    let honest = initialize_ix(program_id, record, user.pubkey());
+    let foreign = checks.foreign(vec![0; LEN], 0);
+    let result = checks.send(initialize_ix(program_id, foreign, user.pubkey()), &[&user]);
+    assert_refused(result, ValidationError::WrongOwner);
+    let result = checks.send(readonly(honest.clone(), &record), &[&user]);
+    assert_refused(result, ValidationError::NotWritable);
+    let result = checks.send(unsigned(honest.clone(), &user.pubkey()), &[]);
+    assert_refused(result, ValidationError::MissingSignature);
+    let short = checks.account(vec![0; LEN], 0);
+    let mut account = checks.svm.get_account(&short).unwrap().clone();
+    account.lamports -= 1;
+    checks.svm.set_account(short, account);
+    let result = checks.send(initialize_ix(program_id, short, user.pubkey()), &[&user]);
+    assert_refused(result, ValidationError::NotRentExempt);
+    checks.send(honest, &[&user]).unwrap();
+
+    let data = UserData {
+        owner: user.pubkey(),
+        balance: 0,
+        metadata: [0; 32],
+    };
+    let data = to_vec(&data).unwrap();
+    let funded = checks.account(data.clone(), 1_000);
+    let bare = checks.account(data.clone(), 0);
+    let foreign = checks.foreign(data, 1_000);
+    let withdraw =
+        |record, signer: &Keypair| withdraw_all_ix(program_id, record, signer.pubkey(), recipient);
+    let honest = withdraw(funded, &user);
+    let result = checks.send(withdraw(foreign, &user), &[&user]);
+    assert_refused(result, ValidationError::WrongOwner);
+    let result = checks.send(readonly(honest.clone(), &funded), &[&user]);
+    assert_refused(result, ValidationError::NotWritable);
+    let result = checks.send(unsigned(honest.clone(), &user.pubkey()), &[]);
+    assert_refused(result, ValidationError::MissingSignature);
+    let result = checks.send(withdraw(funded, &other), &[&other]);
+    assert_refused(result, ValidationError::KeyMismatch);
+    let result = checks.send(withdraw(bare, &user), &[&user]);
+    assert_refused(result, ProgramError::InsufficientFunds);
+    checks.send(honest, &[&user]).unwrap();
+    assert_eq!(checks.svm.lamports(&recipient), LAMPORTS_PER_SOL + 1_000);
+}
+
+#[test]
+fn type_confusion_withdrawals_and_admin_actions() {
+    use type_confusion::{admin_action_ix, withdraw_user_ix};
+    use type_confusion_secure::{AdminAccountSecure, UserAccountSecure};
+
+    let mut checks = Checks::new(type_confusion_secure::process_instruction);
+    let program_id = checks.program_id;
+    let owner = checks.wallet(1);
+    let other = checks.wallet(2);
+    let user = UserAccountSecure {
+        owner: owner.pubkey(),
+        balance: 60,
+        rewards: 40,
+    };
+    let user = checks.account(discriminated(&user), 0);
+    let admin = |admin_level| AdminAccountSecure {
+        owner: owner.pubkey(),
+        balance: 0,
+        admin_level,
+    };
+    let (admin, junior) = (
+        checks.account(discriminated(&admin(5)), 0),
+        checks.account(discriminated(&admin(4)), 0),
+    );
+
+    let honest = withdraw_user_ix(program_id, user, owner.pubkey(), 100);
+    let result = checks.send(unsigned(honest.clone(), &owner.pubkey()), &[]);
+    assert_refused(result, ValidationError::MissingSignature);
+    let result = checks.send(
+        withdraw_user_ix(program_id, user, other.pubkey(), 100),
+        &[&other],
+    );
+    assert_refused(result, ValidationError::KeyMismatch);
+    let result = checks.send(
+        withdraw_user_ix(program_id, user, owner.pubkey(), 101),
+        &[&owner],
+    );
+    assert_refused(result, ProgramError::InsufficientFunds);
+    checks.send(honest, &[&owner]).unwrap();
+
+    let honest = admin_action_ix(program_id, admin, owner.pubkey());
+    let result = checks.send(unsigned(honest.clone(), &owner.pubkey()), &[]);
+    assert_refused(result, ValidationError::MissingSignature);
+    let result = checks.send(
+        admin_action_ix(program_id, admin, other.pubkey()),
+        &[&other],
+    );
+    assert_refused(result, ValidationError::KeyMismatch);
+    let result = checks.send(
+        admin_action_ix(program_id, junior, owner.pubkey()),
+        &[&owner],
+    );
+    assert_refused(result, ProgramError::InvalidAccountData);
+    checks.send(honest, &[&owner]).unwrap();
+}
@@ -0,0 +1,282 @@
+//! The JSON-RPC service lists the examples, runs their exploits and
+//! streams each run's progress as it goes
+
+use std::io::{BufRead, BufReader};
+use std::net::TcpListener;
+use std::sync::Mutex;
+use std::thread;
+
+use anyhow::{bail, Result as AnyResult};
+use serde_json::{json, Value};
+use vuln_lab::{serve, vulnerability, Harness, REGISTRY};
+
+/// Serves a fresh lab on a free port
+fn start() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    thread::spawn(move || serve::run(listener, None));
+    url
+}
+
+fn call(url: &str, method: &str, params: Value) -> Value {
+    ureq::post(url)
+        .send_json(json!({ "jsonrpc": "2.0", "id": 7, "method": method, "params": params }))
+        .unwrap()
+        .into_json()
+        .unwrap()
+}
+
+/// A run's server-sent events, up to and including `finished`
+fn stream(url: &str, run: u64) -> Vec<Value> {
+    let response = ureq::get(&format!("{url}/events?run={run}"))
+        .call()
+        .unwrap();
+    assert_eq!(response.content_type(), "text/event-stream");
+    let mut events = Vec::new();
+    let mut name = String::new();
+    for line in BufReader::new(response.into_reader()).lines() {
+        let line = line.unwrap();
+        if let Some(event) = line.strip_prefix("event: ") {
+            name = event.to_string();
+        } else if let Some(data) = line.strip_prefix("data: ") {
+            let event: Value = serde_json::from_str(data).unwrap();
+            assert_eq!(event["event"], name.as_str());
+            events.push(event);
+        }
+    }
+    events
+}
+
+#[test]
+fn an_exploit_run_streams_its_transactions_then_its_result() {
+    let url = start();
+
+    let list = call(&url, "lab_list", Value::Null);
+    assert_eq!(list["id"], 7);
+    let examples = list["result"].as_array().unwrap();
+    assert_eq!(examples.len(), REGISTRY.iter().count());
+    assert!(examples
+        .iter()
+        .any(|example| example["example"] == "missing_signer_check"));
+
+    let mut runs = Vec::new();
+    for version in ["vulnerable", "secure"] {
+        let started = call(
+            &url,
+            "lab_exploit",
+            json!({ "example": "missing_signer_check", "version": version }),
+        );
+        runs.push((version, started["result"]["run"].as_u64().unwrap()));
+    }
+    assert_eq!(runs[0].1 + 1, runs[1].1);
+
+    for (version, run) in runs {
+        let events = stream(&url, run);
+        assert!(events.iter().all(|event| event["run"] == run));
+        let (started, rest) = events.split_first().unwrap();
+        let (finished, transactions) = rest.split_last().unwrap();
+        assert_eq!(started["event"], "started");
+        assert_eq!(started["version"], version);
+        assert_eq!(finished["event"], "finished");
+
+        let result = &finished["result"];
+        assert_eq!(result["succeeded"], version == "vulnerable");
+        assert_eq!(
+            transactions.len(),
+            result["transactions"].as_array().unwrap().len()
+        );
+        for (index, event) in transactions.iter().enumerate() {
+            assert_eq!(event["index"], index);
+            assert_eq!(event["transaction"], result["transactions"][index]);
+        }
+
+        // Afterwards, the same events by polling
+        let polled = call(&url, "lab_events", json!({ "run": run }));
+        assert_eq!(polled["result"], Value::Array(events));
+    }
+}
+
+#[test]
+fn bad_requests_get_json_rpc_errors() {
+    let url = start();
+    let code = |response: Value| response["error"]["code"].as_i64().unwrap();
+
+    assert_eq!(code(call(&url, "lab_deploy", Value::Null)), -32601);
+    assert_eq!(
+        code(call(&url, "lab_exploit", json!({ "example": "nope" }))),
+        -32602
+    );
+    assert_eq!(
+        code(call(
+            &url,
+            "lab_exploit",
+            json!({ "example": "missing_signer_check", "version": "patched" })
+        )),
+        -32602
+    );
+    assert_eq!(code(call(&url, "lab_events", json!({ "run": 0 }))), -32602);
+
+    let batch: Value = ureq::post(&url)
+        .send_json(json!([
+            { "jsonrpc": "2.0", "id": 1, "method": "lab_list" },
+            { "id": 2, "method": "lab_list" },
+        ]))
+        .unwrap()
+        .into_json()
+        .unwrap();
+    assert!(batch[0]["result"].is_array());
+    assert_eq!(code(batch[1].clone()), -32600);
+
+    let response = ureq::post(&url).send_string("{").unwrap();
+    assert_eq!(code(response.into_json().unwrap()), -32700);
+}
+
+#[test]
+fn notifications_get_no_answer() {
+    let url = start();
+    let notification = json!({ "jsonrpc": "2.0", "method": "lab_list" });
+    let response = ureq::post(&url).send_json(&notification).unwrap();
+    assert_eq!(response.status(), 204);
+    let response = ureq::post(&url)
+        .send_json(json!([notification, { "jsonrpc": "2.0", "method": "lab_deploy" }]))
+        .unwrap();
+    assert_eq!(response.status(), 204);
+
+    let batch: Value = ureq::post(&url)
+        .send_json(json!([notification, { "jsonrpc": "2.0", "id": 1, "method": "lab_list" }]))
+        .unwrap()
+        .into_json()
+        .unwrap();
+    assert_eq!(batch.as_array().unwrap().len(), 1);
+    assert_eq!(batch[0]["id"], 1);
+}
+
+#[test]
+fn oversized_bodies_are_refused() {
+    let url = start();
+    let padding = "x".repeat(serve::MAX_BODY);
+    let response = ureq::post(&url).send_json(json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "lab_list",
+        "params": padding,
+    }));
+    let Err(ureq::Error::Status(413, _)) = response else {
+        panic!("expected a 413");
+    };
+}
+
+#[test]
+fn streaming_an_unknown_run_ends_with_an_error() {
+    let url = start();
+    let events = stream(&url, 3);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0]["event"], "error");
+    assert_eq!(events[0]["message"], "no run 3");
+}
+
+/// Held by a test while runs of `blocked` pile up
+static GATE: Mutex<()> = Mutex::new(());
+
+/// waits for the gate, then gives up
+#[vulnerability(
+    id = "SOL-T01",
+    example = "blocked",
+    category = "DenialOfService",
+    severity = "Low",
+    difficulty = "Beginner",
+    rejection = "released"
+)]
+fn blocked(_harness: &mut Harness) -> AnyResult<()> {
+    drop(GATE.lock());
+    bail!("released")
+}
+
+/// panics partway through
+#[vulnerability(
+    id = "SOL-T02",
+    example = "panicking",
+    category = "DenialOfService",
+    severity = "Low",
+    difficulty = "Beginner",
+    rejection = "out of cheese"
+)]
+fn panicking(_harness: &mut Harness) -> AnyResult<()> {
+    panic!("out of cheese")
+}
+
+fn exploit(url: &str, example: &str) -> Value {
+    call(url, "lab_exploit", json!({ "example": example }))
+}
+
+#[test]
+fn a_panicking_exploit_still_finishes() {
+    let url = start();
+    let run = exploit(&url, "panicking")["result"]["run"]
+        .as_u64()
+        .unwrap();
+
+    let events = stream(&url, run);
+    let finished = events.last().unwrap();
+    assert_eq!(finished["event"], "finished");
+    assert_eq!(finished["result"]["succeeded"], false);
+    let error = finished["result"]["error"].as_str().unwrap();
+    assert!(error.contains("panicked: out of cheese"), "{error}");
+}
+
+#[test]
+fn runs_past_the_queue_are_refused() {
+    let url = start();
+    let gate = GATE.lock().unwrap();
+    let mut runs = Vec::new();
+    let refused = loop {
+        let response = exploit(&url, "blocked");
+        match response["result"]["run"].as_u64() {
+            Some(run) => runs.push(run),
+            None => break response,
+        }
+        assert!(
+            runs.len() <= serve::WORKERS + serve::QUEUED,
+            "never refused"
+        );
+    };
+    assert_eq!(refused["error"]["code"], -32000);
+    // However many runs the workers had taken off the queue by then
+    assert!(runs.len() >= serve::QUEUED, "refused after {}", runs.len());
+
+    drop(gate);
+    for run in runs {
+        let finished = stream(&url, run).pop().unwrap();
+        assert_eq!(finished["result"]["error"], "released");
+    }
+}
+
+#[test]
+fn only_the_allowed_origin_may_read_answers() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    thread::spawn(move || serve::run(listener, Some("http://localhost:3000")));
+    let allowed = |origin: &str| {
+        let response = ureq::post(&url)
+            .set("origin", origin)
+            .send_json(json!({ "jsonrpc": "2.0", "id": 1, "method": "lab_list" }))
+            .unwrap();
+        response
+            .header("access-control-allow-origin")
+            .map(String::from)
+    };
+    assert_eq!(
+        allowed("http://localhost:3000").as_deref(),
+        Some("http://localhost:3000")
+    );
+    assert_eq!(allowed("https://evil.example"), None);
+
+    let url = start();
+    let response = ureq::get(&format!("{url}/nowhere"))
+        .set("origin", "http://localhost:3000")
+        .call();
+    let Err(ureq::Error::Status(404, response)) = response else {
+        panic!("expected a 404");
+    };
+    assert_eq!(response.header("access-control-allow-origin"), None);
+}
@@ -0,0 +1,115 @@
+//! Dry runs project each exploit's attack without it landing, in-process
+//! and, against a stand-in RPC server, on a cluster
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use serde_json::{json, Value};
+use vuln_lab::rpc::Rpc;
+use vuln_lab::{ctf, simulate, REGISTRY};
+
+#[test]
+fn every_attack_is_projected_and_nothing_lands() {
+    for vulnerability in REGISTRY.iter() {
+        let example = vulnerability.example();
+        let dry_run = simulate::dry_run(vulnerability).unwrap_or_else(|err| panic!("{err:#}"));
+        assert!(!dry_run.attack.is_empty(), "{example}");
+        assert_eq!(dry_run.attack.len(), dry_run.projections.len(), "{example}");
+        assert_eq!(
+            dry_run.harness.history().len(),
+            dry_run.setup + dry_run.attack.len(),
+            "{example}"
+        );
+        for projection in &dry_run.projections {
+            assert_eq!(projection.err, None, "{example}");
+            for change in &projection.changes {
+                assert_eq!(
+                    dry_run.harness.lamports(&change.address),
+                    change.before,
+                    "{example}"
+                );
+            }
+        }
+        let [(_, flag)] = <[_; 1]>::try_from(ctf::flags(&dry_run.harness)).unwrap();
+        assert!(flag.solvers.is_empty(), "{example}");
+        assert!(!dry_run.harness.is_dry_run(), "{example}");
+    }
+}
+
+/// Answers getBalance with `before` and simulateTransaction with `after`
+/// for every account, keeping the simulateTransaction requests
+fn serve(before: u64, after: u64, requests: Arc<Mutex<Vec<Value>>>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(&mut stream);
+            let mut length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                    length = value.trim().parse().unwrap();
+                }
+                if line == "\r\n" {
+                    break;
+                }
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            let request: Value = serde_json::from_slice(&body).unwrap();
+
+            let response = match request["method"].as_str().unwrap() {
+                "getBalance" => json!({ "result": { "value": before } }),
+                "simulateTransaction" => {
+                    let addresses = request["params"][1]["accounts"]["addresses"]
+                        .as_array()
+                        .unwrap()
+                        .len();
+                    requests.lock().unwrap().push(request);
+                    let accounts = vec![json!({ "lamports": after }); addresses];
+                    json!({ "result": { "value": {
+                        "err": null,
+                        "logs": ["Program log: simulated"],
+                        "accounts": accounts,
+                    } } })
+                }
+                method => panic!("unexpected {method}"),
+            };
+            let body = response.to_string();
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+                 Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+            .unwrap();
+        }
+    });
+    url
+}
+
+#[test]
+fn a_cluster_simulates_the_attack_unsigned_and_reports_balances() {
+    let dry_run = simulate::dry_run(REGISTRY.find("missing_signer_check").unwrap()).unwrap();
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let rpc = Rpc::new(serve(100, 250, requests.clone()));
+
+    let projection = simulate::on_cluster(&rpc, &dry_run.attack[0]).unwrap();
+    assert_eq!(projection.err, None);
+    assert_eq!(projection.logs, ["Program log: simulated"]);
+    let keys = &dry_run.attack[0].message.static_account_keys();
+    assert_eq!(projection.changes.len(), keys.len());
+    assert!(projection
+        .changes
+        .iter()
+        .all(|change| change.delta() == 150));
+
+    let requests = requests.lock().unwrap();
+    let config = &requests[0]["params"][1];
+    assert_eq!(config["sigVerify"], false);
+    assert_eq!(config["replaceRecentBlockhash"], true);
+}
@@ -0,0 +1,156 @@
+//! No sequence of transfers, self-transfers and duplicated accounts among
+//! them included, makes lamports under the secure transfer handlers, and
+//! the vulnerable ones try to within a few
+
+use lab_instructions::arithmetic_errors::transfer_ix;
+use lab_instructions::missing_signer_check::withdraw_ix;
+use lab_svm::{Keypair, Signer, Svm, TransactionError, LAMPORTS_PER_SOL};
+use lab_test_utils::rent_exempt;
+use rand::rngs::StdRng;
+use rand::Rng;
+use solana_program::entrypoint::ProcessInstruction;
+use solana_program::instruction::{Instruction, InstructionError};
+use solana_program::pubkey::Pubkey;
+use vuln_lab::property::{self, Counterexample};
+
+const SEED: u64 = 1704;
+const CASES: usize = 256;
+const MAX_OPS: usize = 8;
+/// Few enough that a transfer's accounts are often the same one
+const ACCOUNTS: usize = 3;
+
+/// A transfer between the accounts at two indices, authorized by the one
+/// at a third; any of them may be the same
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Transfer {
+    from: usize,
+    to: usize,
+    signer: usize,
+    amount: u64,
+}
+
+fn transfer(rng: &mut StdRng) -> Transfer {
+    let amount = match rng.gen_range(0..3) {
+        // What the accounts hold, give or take
+        0 => LAMPORTS_PER_SOL + rng.gen_range(0..=2 * LAMPORTS_PER_SOL),
+        _ => property::amount(rng),
+    };
+    Transfer {
+        from: rng.gen_range(0..ACCOUNTS),
+        to: rng.gen_range(0..ACCOUNTS),
+        signer: rng.gen_range(0..ACCOUNTS),
+        amount,
+    }
+}
+
+/// How a handler is asked for a transfer
+type Build = fn(Pubkey, &[Pubkey], &Transfer) -> (Instruction, usize);
+
+/// arithmetic_errors' transfer, signed by any user
+fn arithmetic(program_id: Pubkey, keys: &[Pubkey], op: &Transfer) -> (Instruction, usize) {
+    let instruction = transfer_ix(
+        program_id,
+        keys[op.from],
+        keys[op.to],
+        keys[op.signer],
+        op.amount,
+    );
+    (instruction, op.signer)
+}
+
+/// missing_signer_check's withdrawal, signed by the account it debits
+fn withdrawal(program_id: Pubkey, keys: &[Pubkey], op: &Transfer) -> (Instruction, usize) {
+    let instruction = withdraw_ix(program_id, keys[op.from], keys[op.to], op.amount);
+    (instruction, op.from)
+}
+
+/// Runs `ops` against accounts the program owns, each holding a little
+/// over one SOL. A refused transfer is fine, unless what refused it is the
+/// runtime's balance check: then the handler's own writes would have made
+/// or burned lamports, and only the runtime stopped them. Nor may the
+/// accounts ever hold more between them than they started with
+fn creates_nothing(
+    program: ProcessInstruction,
+    build: Build,
+    ops: &[Transfer],
+) -> Result<(), String> {
+    let mut svm = Svm::new();
+    let program_id = svm.add_program(program);
+    let payer = Keypair::new_from_array([0xfa; 32]);
+    svm.airdrop(&payer.pubkey(), LAMPORTS_PER_SOL);
+    let keypairs: Vec<Keypair> = (0..ACCOUNTS)
+        .map(|index| Keypair::new_from_array([index as u8 + 1; 32]))
+        .collect();
+    let keys: Vec<Pubkey> = keypairs.iter().map(Keypair::pubkey).collect();
+    for key in &keys {
+        rent_exempt(&mut svm, *key, &program_id, Vec::new(), LAMPORTS_PER_SOL);
+    }
+    let total = |svm: &Svm| -> u128 { keys.iter().map(|key| u128::from(svm.lamports(key))).sum() };
+    let before = total(&svm);
+
+    for (index, op) in ops.iter().enumerate() {
+        let (instruction, signer) = build(program_id, &keys, op);
+        let result = svm.send(&[instruction], &[&payer, &keypairs[signer]]);
+        if let Err(failed) = result {
+            if failed.err
+                == TransactionError::InstructionError(0, InstructionError::UnbalancedInstruction)
+            {
+                return Err(format!(
+                    "operation {index} changed the lamports it was given in total"
+                ));
+            }
+        }
+        let after = total(&svm);
+        if after > before {
+            return Err(format!(
+                "after operation {index}, {after} lamports, from {before}"
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn the_secure_transfers_make_no_lamports() {
+    let handlers: [(&str, ProcessInstruction, Build); 2] = [
+        (
+            "arithmetic_errors",
+            arithmetic_errors_secure::process_instruction,
+            arithmetic,
+        ),
+        (
+            "missing_signer_check",
+            missing_signer_check_secure::process_instruction,
+            withdrawal,
+        ),
+    ];
+    for (example, program, build) in handlers {
+        let result = property::check(SEED, CASES, MAX_OPS, transfer, |ops| {
+            creates_nothing(program, build, ops)
+        });
+        if let Err(counterexample) = result {
+            panic!("{example}: {counterexample}");
+        }
+    }
+}
+
+/// Shrunk, the violation is one transfer of more than its source holds:
+/// the unchecked subtraction wraps the source's lamports round to nearly
+/// u64::MAX
+#[test]
+fn an_overdrawn_transfer_is_the_underflow_bug() {
+    let counterexample: Counterexample<Transfer> =
+        property::check(SEED, CASES, MAX_OPS, transfer, |ops| {
+            creates_nothing(arithmetic_errors::process_instruction, arithmetic, ops)
+        })
+        .expect_err("the vulnerable transfer makes no lamports");
+
+    let [Transfer {
+        from, to, amount, ..
+    }] = counterexample.ops[..]
+    else {
+        panic!("not one transfer:\n{counterexample}");
+    };
+    assert_ne!(from, to, "{counterexample}");
+    assert!(amount > LAMPORTS_PER_SOL, "{counterexample}");
+}
@@ -0,0 +1,186 @@
+//! reinitialization's vault against a reference model of its lifecycle:
+//! initialized once, by a signer who becomes its authority, then deposited
+//! into. The secure build keeps to the model under any sequence of calls;
+//! the vulnerable build leaves it within a few
+
+use borsh::BorshDeserialize;
+use lab_domain::VaultConfig;
+use lab_instructions::reinitialization::{deposit_ix, initialize_ix};
+use lab_svm::{Keypair, Signer, Svm, LAMPORTS_PER_SOL};
+use lab_test_utils::rent_exempt;
+use rand::rngs::StdRng;
+use rand::Rng;
+use solana_common::discriminator::AccountDiscriminator;
+use solana_program::entrypoint::ProcessInstruction;
+use solana_program::pubkey::Pubkey;
+use vuln_lab::property::{self, Counterexample};
+
+const SEED: u64 = 1709;
+const CASES: usize = 256;
+const MAX_OPS: usize = 8;
+const USERS: usize = 2;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Op {
+    Initialize { authority: usize, fee: u8 },
+    Deposit { user: usize, amount: u64 },
+}
+
+fn op(rng: &mut StdRng) -> Op {
+    let user = rng.gen_range(0..USERS);
+    match rng.gen_range(0..2) {
+        0 => Op::Initialize {
+            authority: user,
+            fee: rng.gen(),
+        },
+        _ => Op::Deposit {
+            user,
+            amount: match rng.gen_range(0..3) {
+                // What a depositor holds, give or take
+                0 => rng.gen_range(0..=2 * LAMPORTS_PER_SOL),
+                _ => property::amount(rng),
+            },
+        },
+    }
+}
+
+/// What the vault and its depositors should hold
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Model {
+    /// None until the first initialize
+    vault: Option<VaultConfig>,
+    /// The vault's lamports, then each depositor's
+    lamports: [u64; 1 + USERS],
+}
+
+impl Model {
+    /// Applies `op` if the vault's lifecycle allows it, and says whether it
+    /// did. A depositor may be emptied, but not left short of rent
+    fn apply(&mut self, op: &Op, keys: &[Pubkey], minimum: u64) -> bool {
+        match (*op, &mut self.vault) {
+            (Op::Initialize { authority, fee }, vault @ None) => {
+                *vault = Some(VaultConfig::new(keys[authority], fee));
+                true
+            }
+            (Op::Initialize { .. }, Some(_)) | (Op::Deposit { .. }, None) => false,
+            (Op::Deposit { user, amount }, Some(vault)) => {
+                let Some(left) = self.lamports[1 + user].checked_sub(amount) else {
+                    return false;
+                };
+                let Some(total) = vault.total_deposited.checked_add(amount) else {
+                    return false;
+                };
+                if left != 0 && left < minimum {
+                    return false;
+                }
+                vault.total_deposited = total;
+                self.lamports[1 + user] = left;
+                self.lamports[0] += amount;
+                true
+            }
+        }
+    }
+}
+
+/// How a build stores a vault, or doesn't yet
+struct Build {
+    program: ProcessInstruction,
+    space: usize,
+    read: fn(&[u8]) -> Option<VaultConfig>,
+}
+
+const VULNERABLE: Build = Build {
+    program: reinitialization::process_instruction,
+    space: VaultConfig::LEN,
+    read: |data| {
+        let config = VaultConfig::try_from_slice(data).unwrap();
+        (data.iter().any(|byte| *byte != 0)).then_some(config)
+    },
+};
+
+const SECURE: Build = Build {
+    program: reinitialization_secure::process_instruction,
+    space: VaultConfig::SPACE,
+    read: |data| VaultConfig::try_deserialize_checked(data).ok(),
+};
+
+/// Runs `ops` against a fresh, zeroed vault and the model side by side;
+/// after every one, the build must have taken it exactly when the model
+/// did, and hold what the model holds
+fn follows_the_model(build: &Build, ops: &[Op]) -> Result<(), String> {
+    let mut svm = Svm::new();
+    let program_id = svm.add_program(build.program);
+    let users: Vec<Keypair> = (0..USERS)
+        .map(|index| Keypair::new_from_array([index as u8 + 1; 32]))
+        .collect();
+    let vault = Pubkey::new_unique();
+    let mut keys = vec![vault];
+    keys.extend(users.iter().map(Keypair::pubkey));
+    let minimum = svm.minimum_balance(0);
+    for (index, key) in keys.iter().enumerate() {
+        let data = vec![0; if index == 0 { build.space } else { 0 }];
+        rent_exempt(&mut svm, *key, &program_id, data, LAMPORTS_PER_SOL);
+    }
+    let mut model = Model {
+        vault: None,
+        lamports: std::array::from_fn(|index| svm.lamports(&keys[index])),
+    };
+
+    for (index, op) in ops.iter().enumerate() {
+        let (instruction, signer) = match *op {
+            Op::Initialize { authority, fee } => (
+                initialize_ix(program_id, vault, keys[1 + authority], fee),
+                authority,
+            ),
+            Op::Deposit { user, amount } => {
+                (deposit_ix(program_id, vault, keys[1 + user], amount), user)
+            }
+        };
+        let taken = svm.send(&[instruction], &[&users[signer]]).is_ok();
+        let expected = model.apply(op, &keys[1..], minimum);
+        if taken != expected {
+            let verb = if taken { "took" } else { "refused" };
+            return Err(format!("operation {index}: the build {verb} it"));
+        }
+        let held = Model {
+            vault: (build.read)(&svm.get_account(&vault).unwrap().data),
+            lamports: std::array::from_fn(|index| svm.lamports(&keys[index])),
+        };
+        if held != model {
+            return Err(format!(
+                "after operation {index}, the build holds {held:?}, not {model:?}"
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn the_secure_vault_follows_the_model() {
+    let result = property::check(SEED, CASES, MAX_OPS, op, |ops| {
+        follows_the_model(&SECURE, ops)
+    });
+    if let Err(counterexample) = result {
+        panic!("{counterexample}");
+    }
+}
+
+/// Shrunk, the divergence is a second initialize, which the model refuses
+/// and the vulnerable build takes, handing the vault to its signer
+#[test]
+fn a_second_initialize_leaves_the_model() {
+    let counterexample: Counterexample<Op> = property::check(SEED, CASES, MAX_OPS, op, |ops| {
+        follows_the_model(&VULNERABLE, ops)
+    })
+    .expect_err("the vulnerable vault follows the model");
+
+    let [Op::Initialize { .. }, Op::Initialize { .. }] = counterexample.ops[..] else {
+        panic!("not two initializes:\n{counterexample}");
+    };
+    assert!(
+        counterexample
+            .failure
+            .contains("operation 1: the build took it"),
+        "{counterexample}"
+    );
+}
@@ -0,0 +1,113 @@
+//! A build verifies against a program read back from a cluster only when
+//! their executables hash the same, padding aside
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::thread;
+
+use base64::prelude::{Engine, BASE64_STANDARD};
+use serde_json::{json, Value};
+use solana_program::pubkey::Pubkey;
+use vuln_lab::rpc::Rpc;
+use vuln_lab::verifiable::{executable_hash, hex, verify};
+
+const LOADER: &str = "BPFLoaderUpgradeab1e11111111111111111111111";
+
+/// Answers getAccountInfo from `accounts`: address to owner and data
+fn serve(accounts: HashMap<String, (String, Vec<u8>)>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(&mut stream);
+            let mut length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                    length = value.trim().parse().unwrap();
+                }
+                if line == "\r\n" {
+                    break;
+                }
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            let request: Value = serde_json::from_slice(&body).unwrap();
+            assert_eq!(request["method"], "getAccountInfo");
+
+            let address = request["params"][0].as_str().unwrap();
+            let value = match accounts.get(address) {
+                Some((owner, data)) => json!({
+                    "owner": owner,
+                    "data": [BASE64_STANDARD.encode(data), "base64"],
+                }),
+                None => Value::Null,
+            };
+            let body = json!({ "result": { "value": value } }).to_string();
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+                 Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+            .unwrap();
+        }
+    });
+    url
+}
+
+/// A program deployed as `solana program deploy` leaves it: the program
+/// account, then its program data account with the ELF padded for upgrades
+fn deployed(program_id: Pubkey, elf: &[u8]) -> HashMap<String, (String, Vec<u8>)> {
+    let program_data = Pubkey::new_unique();
+    let mut program = 2u32.to_le_bytes().to_vec();
+    program.extend_from_slice(program_data.as_ref());
+    let mut data = 3u32.to_le_bytes().to_vec();
+    data.extend_from_slice(&42u64.to_le_bytes());
+    data.push(1);
+    data.extend_from_slice(Pubkey::new_unique().as_ref());
+    data.extend_from_slice(elf);
+    data.resize(data.len() + 4096, 0);
+    HashMap::from([
+        (program_id.to_string(), (LOADER.to_string(), program)),
+        (program_data.to_string(), (LOADER.to_string(), data)),
+    ])
+}
+
+#[test]
+fn a_build_verifies_against_the_same_executable_deployed() {
+    let elf = b"\x7fELF a program, with a zero at the end\0".to_vec();
+    let program_id = Pubkey::new_unique();
+    let rpc = Rpc::new(serve(deployed(program_id, &elf)));
+
+    let verification = verify(&rpc, program_id, &elf).unwrap();
+    assert!(verification.verified());
+    assert_eq!(verification.local, executable_hash(&elf));
+    assert_eq!(hex(&verification.local).len(), 64);
+
+    let mut rebuilt = elf.clone();
+    rebuilt[1] ^= 1;
+    let verification = verify(&rpc, program_id, &rebuilt).unwrap();
+    assert!(!verification.verified());
+    assert_eq!(verification.deployed, Some(executable_hash(&elf)));
+}
+
+#[test]
+fn nothing_deployed_or_something_else_deployed_does_not_verify() {
+    let program_id = Pubkey::new_unique();
+    let mut accounts = deployed(Pubkey::new_unique(), b"\x7fELF");
+    let wallet = Pubkey::new_unique();
+    accounts.insert(
+        wallet.to_string(),
+        ("11111111111111111111111111111111".to_string(), Vec::new()),
+    );
+    let rpc = Rpc::new(serve(accounts));
+
+    let verification = verify(&rpc, program_id, b"\x7fELF").unwrap();
+    assert_eq!(verification.deployed, None);
+    assert!(!verification.verified());
+    assert!(verify(&rpc, wallet, b"\x7fELF").is_err());
+}
@@ -0,0 +1,74 @@
+//! Every example annotates its bugs and every fix its checks, and the
+//! annotations point at the code they are about
+
+use vuln_lab::walkthrough::{self, annotations, Kind};
+use vuln_lab::REGISTRY;
+
+#[test]
+fn every_example_walks_through_its_bugs_and_fixes() {
+    let mut walkthroughs = Vec::new();
+    for vulnerability in REGISTRY.iter() {
+        let example = vulnerability.example();
+        let walkthrough =
+            walkthrough::walkthrough(vulnerability).unwrap_or_else(|err| panic!("{err:#}"));
+        assert!(
+            !walkthrough.steps.is_empty(),
+            "{example} has no // VULNERABILITY:"
+        );
+        assert!(
+            walkthrough.steps.iter().any(|step| !step.fixes.is_empty()),
+            "no // CHECK: in {example}'s secure version goes with one of its bugs"
+        );
+        for step in &walkthrough.steps {
+            assert!(step.vuln.function.is_some(), "{example}:{}", step.vuln.line);
+            assert!(
+                !walkthrough.vulnerable.excerpt(&step.vuln).is_empty(),
+                "{example}:{}",
+                step.vuln.line
+            );
+        }
+        walkthroughs.push(walkthrough);
+    }
+
+    let html = walkthrough::html(&walkthroughs);
+    for walkthrough in &walkthroughs {
+        for step in &walkthrough.steps {
+            let anchor = format!("{}-L{}", walkthrough.example, step.vuln.line);
+            assert!(html.contains(&format!("href=\"#{anchor}\"")), "{anchor}");
+            assert!(html.contains(&format!("id=\"{anchor}\"")), "{anchor}");
+        }
+    }
+}
+
+#[test]
+fn annotations_span_their_code() {
+    let source = r#"
+fn withdraw(amount: u64, balance: &mut u64) {
+    // VULNERABILITY 2: No balance check
+    // so this underflows
+    *balance -= amount;
+
+    // CHECK: Compare first
+    if amount > *balance {
+        return;
+    }
+    let _ = "not // VULNERABILITY: an annotation";
+    let total = amount + 1; // VULNERABILITY 1: Unchecked
+}
+"#;
+    let found = annotations(source).unwrap();
+    assert_eq!(found.len(), 3);
+
+    assert_eq!(found[0].kind, Kind::Vulnerability);
+    assert_eq!(found[0].number, Some(2));
+    assert_eq!(found[0].text, "No balance check so this underflows");
+    assert_eq!((found[0].line, found[0].code), (3, (5, 5)));
+    assert_eq!(found[0].function.as_deref(), Some("withdraw"));
+
+    assert_eq!(found[1].kind, Kind::Check);
+    assert_eq!(found[1].code, (8, 10));
+
+    // Trailing: about its own line
+    assert_eq!(found[2].number, Some(1));
+    assert_eq!((found[2].line, found[2].code), (12, (12, 12)));
+}
@@ -0,0 +1,42 @@
+//! Witness search finds each bug the flow fuzzer can reach and minimizes it
+//! to the few instructions the bug needs
+
+use vuln_lab::fuzz::{self, Step};
+use vuln_lab::witness;
+
+const SEED: u64 = 1706;
+const FLOWS: usize = 3000;
+
+/// A signer-check bug needs one withdrawal whose depositor has not signed
+#[test]
+fn a_missing_signer_check_witness_is_one_unsigned_withdrawal() {
+    let witness = witness::search("missing_signer_check", SEED, FLOWS).expect("a witness");
+    assert_eq!(witness.steps.len(), 1, "{witness}");
+    assert_eq!(witness.divergence.step, 0, "{witness}");
+    let withdrawal = &witness.divergence.instruction;
+    assert!(
+        withdrawal.accounts.iter().all(|meta| !meta.is_signer),
+        "{witness}"
+    );
+    assert!(witness.divergence.error.is_some(), "{witness}");
+}
+
+/// Minimizing a flow padded with steps the bug does not need leaves only
+/// the two migrations, untouched
+#[test]
+fn minimizing_strips_a_versioning_flow_to_two_migrations() {
+    let migrate = Step {
+        index: 1,
+        ..Step::default()
+    };
+    let noise = Step {
+        index: 0,
+        forged: (3, 7),
+        mutations: [5; fuzz::MAX_ACCOUNTS],
+    };
+    let padded = vec![noise.clone(), migrate.clone(), noise, migrate.clone()];
+    assert!(fuzz::replay("account_versioning", &padded).is_some());
+
+    let steps = witness::minimize("account_versioning", padded);
+    assert_eq!(steps, [migrate.clone(), migrate]);
+}
@@ -5,9 +5,14 @@
  *
  * This program fails to verify that accounts are owned by the expected program,
  * allowing attackers to pass malicious account data.
+ *
+ * Funds sit in a program-owned vault; a separate VaultData account records
+ * who may withdraw them. The runtime stops a program from debiting or
+ * writing accounts it does not own, but nothing stops it from READING them.
  */
 
 use borsh::{BorshDeserialize, BorshSerialize};
+use solana_common::events::{emit, WithdrawEvent};
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
     entrypoint,
@@ -17,12 +22,14 @@ use solana_program::{
     pubkey::Pubkey,
 };
 
+/// Written once when the vault is created
 #[derive(BorshSerialize, BorshDeserialize, Debug)]
 pub struct VaultData {
     pub authority: Pubkey,
-    pub balance: u64,
+    pub vault: Pubkey,
 }
 
+#[cfg(not(feature = "no-entrypoint"))]
 entrypoint!(process_instruction);
 
 pub fn process_instruction(
@@ -33,17 +40,21 @@ pub fn process_instruction(
     msg!("Vulnerable: Missing Owner Check");
 
     let accounts_iter = &mut accounts.iter();
+    let vault_data_account = next_account_info(accounts_iter)?;
     let vault_account = next_account_info(accounts_iter)?;
     let authority_account = next_account_info(accounts_iter)?;
     let recipient_account = next_account_info(accounts_iter)?;
 
-    // VULNERABILITY: No check that vault_account is owned by this program
+    // VULNERABILITY: No check that vault_data_account is owned by this program
     // Attacker can pass an account they control with fake data
-    // Should have: if vault_account.owner != program_id { return Err(ProgramError::IncorrectProgramId); }
+    // Should have: if vault_data_account.owner != program_id { return Err(ProgramError::IncorrectProgramId); }
 
-    let mut vault_data = VaultData::try_from_slice(&vault_account.data.borrow())?;
+    let vault_data = VaultData::try_from_slice(&vault_data_account.data.borrow())?;
 
-    // VULNERABILITY: This check can be bypassed by providing fake account data
+    // VULNERABILITY: These checks can be bypassed by providing fake account data
+    if vault_data.vault != *vault_account.key {
+        return Err(ProgramError::InvalidAccountData);
+    }
     if vault_data.authority != *authority_account.key {
         return Err(ProgramError::InvalidAccountData);
     }
@@ -55,12 +66,14 @@ pub fn process_instruction(
 
     msg!("Withdrawing {} lamports", amount);
 
-    // Attacker's fake account will pass all checks
-    vault_data.balance -= amount;
-    vault_data.serialize(&mut &mut vault_account.data.borrow_mut()[..])?;
-
+    // The real vault pays out; the fake data only had to name it
     **vault_account.try_borrow_mut_lamports()? -= amount;
     **recipient_account.try_borrow_mut_lamports()? += amount;
+    emit(WithdrawEvent {
+        account: *vault_account.key,
+        recipient: *recipient_account.key,
+        amount,
+    });
 
     Ok(())
 }
@@ -74,12 +87,13 @@ pub fn process_instruction(
  *     instruction_data: &[u8],
  * ) -> ProgramResult {
  *     let accounts_iter = &mut accounts.iter();
+ *     let vault_data_account = next_account_info(accounts_iter)?;
  *     let vault_account = next_account_info(accounts_iter)?;
  *     let authority_account = next_account_info(accounts_iter)?;
  *     let recipient_account = next_account_info(accounts_iter)?;
  *
- *     // CHECK: Verify vault_account is owned by this program
- *     if vault_account.owner != program_id {
+ *     // CHECK: Verify vault_data_account is owned by this program
+ *     if vault_data_account.owner != program_id {
  *         return Err(ProgramError::IncorrectProgramId);
  *     }
  *
@@ -88,37 +102,45 @@ pub fn process_instruction(
  *         return Err(ProgramError::MissingRequiredSignature);
  *     }
  *
- *     let mut vault_data = VaultData::try_from_slice(&vault_account.data.borrow())?;
+ *     let vault_data = VaultData::try_from_slice(&vault_data_account.data.borrow())?;
  *
- *     // Now this check is meaningful
+ *     // Now these checks are meaningful
+ *     if vault_data.vault != *vault_account.key {
+ *         return Err(ProgramError::InvalidAccountData);
+ *     }
  *     if vault_data.authority != *authority_account.key {
  *         return Err(ProgramError::InvalidAccountData);
  *     }
  *
  *     let amount = u64::from_le_bytes(instruction_data[0..8].try_into().unwrap());
  *
- *     vault_data.balance -= amount;
- *     vault_data.serialize(&mut &mut vault_account.data.borrow_mut()[..])?;
- *
  *     **vault_account.try_borrow_mut_lamports()? -= amount;
  *     **recipient_account.try_borrow_mut_lamports()? += amount;
  *
  *     Ok(())
  * }
+ *
+ * // With solana-common, the owner check and the read are one line, and
+ * // VaultData carries a discriminator:
+ * // let vault_data = load::<VaultData>(vault_data_account, program_id)?;
+ *
+ * Compiled in secure/missing_owner_check.rs.
  */
 
 /*
  * EXPLOIT SCENARIO:
  *
- * 1. Attacker creates their own account with crafted data
- * 2. In the fake account data, attacker sets:
+ * 1. The program's vault holds users' SOL; its VaultData names the vault
+ *    and the authority allowed to withdraw
+ * 2. Attacker creates their own account (owned by a program they control)
+ *    with crafted data:
  *    - authority: ATTACKER's pubkey
- *    - balance: 1,000,000 (fake!)
- * 3. Attacker funds the fake account with actual SOL
- * 4. Attacker calls vulnerable program with:
- *    - vault_account: ATTACKER's fake account
- *    - authority_account: ATTACKER (they can sign)
+ *    - vault: the REAL vault's address
+ * 3. Attacker calls vulnerable program with:
+ *    - vault_data_account: ATTACKER's fake account
+ *    - vault_account: the real vault
+ *    - authority_account: ATTACKER
  *    - recipient_account: ATTACKER
- * 5. Program doesn't verify vault_account is owned by program
- * 6. Attacker withdraws real SOL from the fake account
+ * 4. Program doesn't verify vault_data_account is owned by program
+ * 5. Attacker withdraws the real vault's SOL
  */
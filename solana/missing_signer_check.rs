@@ -7,6 +7,7 @@
  * allowing unauthorized users to modify data or steal funds.
  */
 
+use solana_common::events::{emit, WithdrawEvent};
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
     entrypoint,
@@ -16,6 +17,7 @@ use solana_program::{
     pubkey::Pubkey,
 };
 
+#[cfg(not(feature = "no-entrypoint"))]
 entrypoint!(process_instruction);
 
 pub fn process_instruction(
@@ -40,6 +42,11 @@ pub fn process_instruction(
     // VULNERABILITY: This transfer will succeed even if user_account didn't sign
     **user_account.try_borrow_mut_lamports()? -= amount;
     **destination_account.try_borrow_mut_lamports()? += amount;
+    emit(WithdrawEvent {
+        account: *user_account.key,
+        recipient: *destination_account.key,
+        amount,
+    });
 
     Ok(())
 }
@@ -68,6 +75,8 @@ pub fn process_instruction(
  *
  *     Ok(())
  * }
+ *
+ * Compiled in secure/missing_signer_check.rs.
  */
 
 /*
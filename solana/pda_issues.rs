@@ -8,6 +8,7 @@
  */
 
 use borsh::{BorshDeserialize, BorshSerialize};
+use solana_common::events::{emit, WithdrawEvent};
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
     entrypoint,
@@ -23,6 +24,7 @@ pub struct UserData {
     pub balance: u64,
 }
 
+#[cfg(not(feature = "no-entrypoint"))]
 entrypoint!(process_instruction);
 
 pub fn process_instruction(
@@ -71,6 +73,11 @@ pub fn process_instruction(
 
     **pda_account.try_borrow_mut_lamports()? -= amount;
     **recipient_account.try_borrow_mut_lamports()? += amount;
+    emit(WithdrawEvent {
+        account: *pda_account.key,
+        recipient: *recipient_account.key,
+        amount,
+    });
 
     Ok(())
 }
@@ -130,6 +137,13 @@ pub fn process_instruction(
  *
  *     Ok(())
  * }
+ *
+ * // With solana-common, the derivation and the comparison are one line,
+ * // from a spec the tests derive the same address with:
+ * // pub const USER_DATA: PdaSpec<1> = PdaSpec::new(b"user_data", ["user"]);
+ * // find_and_validate(&USER_DATA.seeds([user_account.key]), program_id, pda_account.key)?;
+ *
+ * Compiled in secure/pda_issues.rs.
  */
 
 /*
@@ -169,7 +183,7 @@ pub fn vulnerable_with_bump(
 
     // VULNERABILITY: Accepts user-provided bump seed without validation
     // Should use find_program_address to get canonical bump
-    let seeds = &[b"vault", &[bump]];
+    let seeds: &[&[u8]] = &[b"vault", &[bump]];
 
     // Attacker can provide non-canonical bump and potentially bypass checks
     let pda = Pubkey::create_program_address(seeds, program_id)?;
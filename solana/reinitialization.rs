@@ -8,6 +8,7 @@
  */
 
 use borsh::{BorshDeserialize, BorshSerialize};
+use solana_common::events::{emit, AuthorityChanged, DepositEvent};
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
     entrypoint,
@@ -17,13 +18,11 @@ use solana_program::{
     pubkey::Pubkey,
 };
 
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
-pub struct VaultConfig {
-    pub authority: Pubkey,
-    pub total_deposited: u64,
-    pub fee_percentage: u8,
-}
+// { authority: Pubkey, total_deposited: u64, fee_percentage: u8 }, laid
+// out once for every example that keeps a vault
+pub use lab_domain::VaultConfig;
 
+#[cfg(not(feature = "no-entrypoint"))]
 entrypoint!(process_instruction);
 
 pub fn process_instruction(
@@ -75,6 +74,11 @@ pub fn initialize(
 
     msg!("Vault initialized with authority: {}", authority_account.key);
 
+    emit(AuthorityChanged {
+        account: *vault_account.key,
+        authority: *authority_account.key,
+    });
+
     Ok(())
 }
 
@@ -104,6 +108,11 @@ pub fn deposit(
 
     **user_account.try_borrow_mut_lamports()? -= amount;
     **vault_account.try_borrow_mut_lamports()? += amount;
+    emit(DepositEvent {
+        account: *vault_account.key,
+        depositor: *user_account.key,
+        amount,
+    });
 
     Ok(())
 }
@@ -111,13 +120,9 @@ pub fn deposit(
 /*
  * SECURE VERSION:
  *
- * #[derive(BorshSerialize, BorshDeserialize, Debug)]
- * pub struct VaultConfig {
- *     pub is_initialized: bool,  // ADD THIS FIELD
- *     pub authority: Pubkey,
- *     pub total_deposited: u64,
- *     pub fee_percentage: u8,
- * }
+ * // VaultConfig derives AccountDiscriminator: USE ITS DISCRIMINATOR,
+ * // written ahead of the fields
+ * use solana_common::discriminator::AccountDiscriminator;
  *
  * pub fn initialize_secure(
  *     program_id: &Pubkey,
@@ -136,31 +141,31 @@ pub fn deposit(
  *         return Err(ProgramError::MissingRequiredSignature);
  *     }
  *
- *     // CHECK: Try to deserialize existing data
- *     if let Ok(existing_config) = VaultConfig::try_from_slice(&vault_account.data.borrow()) {
- *         // CHECK: If already initialized, reject
- *         if existing_config.is_initialized {
- *             msg!("Vault already initialized");
- *             return Err(ProgramError::AccountAlreadyInitialized);
- *         }
+ *     // CHECK: An account that already starts with the discriminator
+ *     // has been initialized
+ *     if vault_account.data.borrow().starts_with(&VaultConfig::DISCRIMINATOR) {
+ *         msg!("Vault already initialized");
+ *         return Err(ProgramError::AccountAlreadyInitialized);
  *     }
  *
  *     let fee_percentage = instruction_data[0];
  *
  *     let vault_config = VaultConfig {
- *         is_initialized: true,  // SET FLAG
  *         authority: *authority_account.key,
  *         total_deposited: 0,
  *         fee_percentage,
  *     };
  *
- *     vault_config.serialize(&mut &mut vault_account.data.borrow_mut()[..])?;
+ *     // WRITE THE DISCRIMINATOR
+ *     vault_config.serialize_with_discriminator(&mut &mut vault_account.data.borrow_mut()[..])?;
  *
  *     Ok(())
  * }
  *
  * // Alternative: Use Anchor framework which handles this automatically
  * // with #[account(init)] macro
+ *
+ * Compiled in secure/reinitialization.rs.
  */
 
 /*
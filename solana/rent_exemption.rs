@@ -8,6 +8,7 @@
  */
 
 use borsh::{BorshDeserialize, BorshSerialize};
+use solana_common::events::{emit, WithdrawEvent};
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
     entrypoint,
@@ -26,6 +27,7 @@ pub struct UserData {
     pub metadata: [u8; 32],
 }
 
+#[cfg(not(feature = "no-entrypoint"))]
 entrypoint!(process_instruction);
 
 pub fn process_instruction(
@@ -120,6 +122,11 @@ pub fn withdraw_all(
 
     **user_data_account.try_borrow_mut_lamports()? = 0;
     **recipient_account.try_borrow_mut_lamports()? += balance;
+    emit(WithdrawEvent {
+        account: *user_data_account.key,
+        recipient: *recipient_account.key,
+        amount: balance,
+    });
 
     Ok(())
 }
@@ -211,6 +218,8 @@ pub fn withdraw_all(
  *
  *     Ok(())
  * }
+ *
+ * Compiled in secure/rent_exemption.rs.
  */
 
 /*
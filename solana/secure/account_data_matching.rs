@@ -0,0 +1,149 @@
+/*
+ * SECURE SOLANA PROGRAM
+ *
+ * Fixed counterpart of ../account_data_matching.rs
+ *
+ * Accounts are checked against the addresses stored in each other: the
+ * escrow must be the one the signer's profile names, and the funds go
+ * only to the beneficiary the escrow names.
+ */
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_common::clock::{ClockProvider, SysvarClock};
+use solana_common::deserialize::strict_deserialize;
+use solana_common::events::{emit, WithdrawEvent};
+use solana_common::pda::{find_and_validate, PdaSpec};
+use solana_common::token_utils::{received, transfer, Authority};
+use solana_common::validation::{assert_key_eq, assert_owned_by, assert_signer};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+// UserProfile { owner: Pubkey, escrow_account: Pubkey, total_deposits: u64 }
+// and EscrowAccount { beneficiary: Pubkey, amount: u64, release_time: i64 },
+// laid out once for every example that keeps an escrow
+pub use lab_domain::{EscrowAccount, UserProfile};
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct TokenVault {
+    pub authority: Pubkey,
+    pub token_account: Pubkey,
+}
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    process_instruction_with_clock(program_id, accounts, instruction_data, &SysvarClock)
+}
+
+/// process_instruction, with the time read from `clock`
+pub fn process_instruction_with_clock(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    _instruction_data: &[u8],
+    clock: &impl ClockProvider,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let user_profile_account = next_account_info(accounts_iter)?;
+    let escrow_account = next_account_info(accounts_iter)?;
+    let beneficiary_account = next_account_info(accounts_iter)?;
+    let signer_account = next_account_info(accounts_iter)?;
+
+    assert_owned_by(user_profile_account, program_id)?;
+
+    assert_owned_by(escrow_account, program_id)?;
+
+    assert_signer(signer_account)?;
+
+    let user_profile = strict_deserialize::<UserProfile>(&user_profile_account.data.borrow())?;
+    let mut escrow_data = strict_deserialize::<EscrowAccount>(&escrow_account.data.borrow())?;
+
+    // CHECK: Verify signer owns the profile
+    assert_key_eq(signer_account, &user_profile.owner)?;
+
+    // CHECK: Verify escrow account matches profile's escrow
+    assert_key_eq(escrow_account, &user_profile.escrow_account)?;
+
+    // CHECK: Verify release time
+    let current_time = clock.unix_timestamp()?;
+    if current_time < escrow_data.release_time {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    // CHECK: Verify beneficiary matches
+    assert_key_eq(beneficiary_account, &escrow_data.beneficiary)?;
+
+    let amount = escrow_data.amount;
+    escrow_data.amount = 0;
+    escrow_data.serialize(&mut &mut escrow_account.data.borrow_mut()[..])?;
+
+    msg!("Releasing {} lamports", amount);
+
+    **escrow_account.try_borrow_mut_lamports()? -= amount;
+    **beneficiary_account.try_borrow_mut_lamports()? += amount;
+    emit(WithdrawEvent {
+        account: *escrow_account.key,
+        recipient: *beneficiary_account.key,
+        amount,
+    });
+
+    Ok(())
+}
+
+/// The PDA that owns every vault's token account, and signs their
+/// transfers; only the vault's own token_account check keeps one vault's
+/// authority out of the others
+pub const TOKEN_AUTHORITY: PdaSpec<0> = PdaSpec::new(b"token_authority", []);
+
+pub fn secure_token_withdraw(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let vault_account = next_account_info(accounts_iter)?;
+    let token_account = next_account_info(accounts_iter)?;
+    let authority = next_account_info(accounts_iter)?;
+    let destination = next_account_info(accounts_iter)?;
+    let vault_signer = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+
+    assert_signer(authority)?;
+
+    let vault_data = strict_deserialize::<TokenVault>(&vault_account.data.borrow())?;
+
+    assert_key_eq(authority, &vault_data.authority)?;
+
+    // CHECK: The token account is the one the vault was created with
+    assert_key_eq(token_account, &vault_data.token_account)?;
+
+    let amount = instruction_data
+        .get(..8)
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(u64::from_le_bytes)
+        .ok_or(ProgramError::InvalidInstructionData)?;
+
+    let bump = find_and_validate(&TOKEN_AUTHORITY.seeds([]), program_id, vault_signer.key)?;
+    let seeds: &[&[u8]] = &[TOKEN_AUTHORITY.prefix, &[bump]];
+    let signer = Authority::Pda {
+        account: vault_signer,
+        seeds,
+    };
+    // transfer checks token_program is SPL Token before invoking it
+    let received = received(destination, || {
+        transfer(token_program, token_account, destination, signer, amount)
+    })?;
+
+    msg!("Transferred {} tokens to {}", received, destination.key);
+
+    Ok(())
+}
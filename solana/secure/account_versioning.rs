@@ -0,0 +1,200 @@
+/*
+ * SECURE SOLANA PROGRAM
+ *
+ * Fixed counterpart of ../account_versioning.rs
+ *
+ * Every user account starts with a byte naming its layout. Withdrawal
+ * reads whichever layout that is, and only that one, so a v2 account's
+ * delegate is never a balance. Migration reads a v1 account, strictly,
+ * and rewrites it in place as v2 behind the new byte, growing it at the
+ * user's expense.
+ */
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use lab_errors::StateError;
+use solana_common::events::{emit, WithdrawEvent};
+use solana_common::pda::{find_and_validate, PdaSpec};
+use solana_common::validation::{assert_key_eq, assert_owned_by, assert_signer};
+use solana_common::versioning::{
+    migrate_in_place, read_versioned, version, write_versioned, Versioned,
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+
+/// The program's one vault, which holds every user's lamports
+pub const VAULT: PdaSpec<0> = PdaSpec::new(b"vault", []);
+
+/// A user account as the first release wrote it
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct UserAccountV1 {
+    pub owner: Pubkey,
+    pub balance: u64,
+}
+
+impl UserAccountV1 {
+    /// With its version byte
+    pub const LEN: usize = 1 + 32 + 8;
+}
+
+// CHECK: Each layout has its own version byte, written ahead of its fields
+impl Versioned for UserAccountV1 {
+    const VERSION: u8 = 1;
+}
+
+/// A user account as the second release writes it
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct UserAccountV2 {
+    pub owner: Pubkey,
+    pub delegate: Pubkey,
+    pub balance: u64,
+}
+
+impl UserAccountV2 {
+    /// With its version byte
+    pub const LEN: usize = 1 + 32 + 32 + 8;
+}
+
+impl Versioned for UserAccountV2 {
+    const VERSION: u8 = 2;
+}
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let (instruction, rest) = instruction_data
+        .split_first()
+        .ok_or(ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        0 => open(program_id, accounts, rest),
+        1 => withdraw(program_id, accounts, rest),
+        2 => migrate(program_id, accounts),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+/// Writes a new user account, in the second release's layout
+pub fn open(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let user = next_account_info(accounts_iter)?;
+    let user_account = next_account_info(accounts_iter)?;
+
+    assert_signer(user)?;
+    assert_owned_by(user_account, program_id)?;
+    if user_account.data.borrow().iter().any(|byte| *byte != 0) {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    let delegate = Pubkey::try_from(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    // CHECK: The version byte goes first
+    let account = UserAccountV2 {
+        owner: *user.key,
+        delegate,
+        balance: 0,
+    };
+    write_versioned(&account, &mut user_account.data.borrow_mut())
+}
+
+/// Pays `amount` of the user's balance out of the vault
+pub fn withdraw(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let user = next_account_info(accounts_iter)?;
+    let user_account = next_account_info(accounts_iter)?;
+    let vault = next_account_info(accounts_iter)?;
+
+    assert_signer(user)?;
+    assert_owned_by(user_account, program_id)?;
+    find_and_validate(&VAULT.seeds([]), program_id, vault.key)?;
+
+    let amount = instruction_data
+        .get(..8)
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(u64::from_le_bytes)
+        .ok_or(ProgramError::InvalidInstructionData)?;
+
+    let mut data = user_account.data.borrow_mut();
+    // CHECK: Read the layout the account says it holds, and write it back
+    // in that layout
+    match version(&data)? {
+        UserAccountV1::VERSION => {
+            let mut account = read_versioned::<UserAccountV1>(&data)?;
+            assert_key_eq(user, &account.owner)?;
+            account.balance = account
+                .balance
+                .checked_sub(amount)
+                .ok_or(ProgramError::InsufficientFunds)?;
+            write_versioned(&account, &mut data)?;
+        }
+        UserAccountV2::VERSION => {
+            let mut account = read_versioned::<UserAccountV2>(&data)?;
+            assert_key_eq(user, &account.owner)?;
+            account.balance = account
+                .balance
+                .checked_sub(amount)
+                .ok_or(ProgramError::InsufficientFunds)?;
+            write_versioned(&account, &mut data)?;
+        }
+        _ => return Err(StateError::WrongVersion.into()),
+    }
+
+    msg!("Withdrawing {}", amount);
+
+    **vault.try_borrow_mut_lamports()? -= amount;
+    **user.try_borrow_mut_lamports()? += amount;
+    emit(WithdrawEvent {
+        account: *user_account.key,
+        recipient: *user.key,
+        amount,
+    });
+
+    Ok(())
+}
+
+/// Moves a first-release account to the second release's layout
+pub fn migrate(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let user = next_account_info(accounts_iter)?;
+    let user_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    assert_signer(user)?;
+    assert_owned_by(user_account, program_id)?;
+
+    // CHECK: Only a v1 account migrates, and it is read strictly first
+    migrate_in_place::<UserAccountV1, UserAccountV2>(
+        user_account,
+        user,
+        system_program,
+        &Rent::get()?,
+        |old| {
+            assert_key_eq(user, &old.owner)?;
+            Ok(UserAccountV2 {
+                owner: old.owner,
+                delegate: Pubkey::default(),
+                balance: old.balance,
+            })
+        },
+    )
+}
@@ -0,0 +1,79 @@
+/*
+ * SECURE SOLANA PROGRAM
+ *
+ * Fixed counterpart of ../arbitrary_cpi.rs
+ *
+ * The user's signature is only forwarded to programs on an allowlist, so
+ * a caller-chosen program can never act with it.
+ *
+ * The relay account's lock is held while the call is out. The runtime
+ * already refuses a call back in from any other program, and the
+ * allowlist keeps this one from relaying to itself, so the lock is the
+ * line behind them: should the allowlist ever take this program, a call
+ * that comes back in through it finds the lock held.
+ */
+
+use solana_common::pda::{find_and_validate, PdaSpec};
+use solana_common::reentrancy::{acquire, release};
+use solana_common::validation::assert_signer;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    msg,
+    program::invoke,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use solana_system_interface::program as system_program;
+
+/// Programs this one may invoke on a user's behalf
+pub const ALLOWED_PROGRAMS: &[Pubkey] = &[system_program::ID];
+
+/// The program's one relay account, which holds the lock
+pub const RELAY: PdaSpec<0> = PdaSpec::new(b"relay", []);
+/// Where the relay account's lock byte is
+pub const RELAY_LOCK: usize = 0;
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let user_account = next_account_info(accounts_iter)?;
+    let target_program = next_account_info(accounts_iter)?;
+    let target_account = next_account_info(accounts_iter)?;
+    let relay = next_account_info(accounts_iter)?;
+
+    assert_signer(user_account)?;
+
+    // CHECK: Whitelist allowed programs
+    if !ALLOWED_PROGRAMS.contains(target_program.key) {
+        msg!("Program not in whitelist");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let instruction = Instruction {
+        program_id: *target_program.key,
+        accounts: vec![
+            AccountMeta::new(*user_account.key, true),
+            AccountMeta::new(*target_account.key, false),
+        ],
+        data: instruction_data.to_vec(),
+    };
+
+    msg!("Invoking program: {}", target_program.key);
+
+    // CHECK: Hold the relay's lock until the call returns
+    find_and_validate(&RELAY.seeds([]), program_id, relay.key)?;
+    acquire(relay, RELAY_LOCK)?;
+    invoke(
+        &instruction,
+        &[user_account.clone(), target_account.clone()],
+    )?;
+    release(relay, RELAY_LOCK)
+}
@@ -0,0 +1,171 @@
+/*
+ * SECURE SOLANA PROGRAM
+ *
+ * Fixed counterpart of ../arithmetic_errors.rs
+ *
+ * Every operation on an amount goes through CheckedU64 and fails the
+ * instruction instead of wrapping; a staker's share of the pool is a
+ * U64F64 fraction rather than an integer division that rounds it to 0,
+ * and a transfer is refused when the source cannot cover it or is also
+ * the destination.
+ */
+
+use borsh::BorshSerialize;
+use solana_common::clock::{ClockProvider, SysvarClock};
+use solana_common::deserialize::strict_deserialize;
+use solana_common::events::{emit, DepositEvent};
+use solana_common::fixed_point::{Rounding, U64F64};
+use solana_common::safe_math::CheckedU64;
+use solana_common::validation::{assert_distinct, assert_signer};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+// StakingPool { total_staked: u64, reward_rate: u64, last_update: i64 } and
+// UserStake { amount: u64, last_claim: i64 }, laid out once for every
+// example that stakes
+pub use lab_domain::{StakingPool, UserStake};
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    process_instruction_with_clock(program_id, accounts, instruction_data, &SysvarClock)
+}
+
+/// process_instruction, with the time read from `clock`
+pub fn process_instruction_with_clock(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+    clock: &impl ClockProvider,
+) -> ProgramResult {
+    let (instruction, rest) = instruction_data
+        .split_first()
+        .ok_or(ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        0 => stake_secure(program_id, accounts, rest),
+        1 => calculate_rewards_secure(program_id, accounts, rest, clock),
+        2 => transfer_secure(program_id, accounts, rest),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+fn read_amount(instruction_data: &[u8]) -> Result<u64, ProgramError> {
+    instruction_data
+        .get(..8)
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(u64::from_le_bytes)
+        .ok_or(ProgramError::InvalidInstructionData)
+}
+
+pub fn stake_secure(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let pool_account = next_account_info(accounts_iter)?;
+    let user_stake_account = next_account_info(accounts_iter)?;
+    let user_account = next_account_info(accounts_iter)?;
+
+    assert_signer(user_account)?;
+
+    let mut pool_data = strict_deserialize::<StakingPool>(&pool_account.data.borrow())?;
+    let mut user_data = strict_deserialize::<UserStake>(&user_stake_account.data.borrow())?;
+
+    let amount = read_amount(instruction_data)?;
+
+    // CHECK: Use checked arithmetic
+    pool_data.total_staked = (CheckedU64(pool_data.total_staked) + amount)?.get();
+
+    user_data.amount = (CheckedU64(user_data.amount) + amount)?.get();
+
+    pool_data.serialize(&mut &mut pool_account.data.borrow_mut()[..])?;
+    user_data.serialize(&mut &mut user_stake_account.data.borrow_mut()[..])?;
+
+    msg!("Staked {} tokens", amount);
+
+    emit(DepositEvent {
+        account: *pool_account.key,
+        depositor: *user_account.key,
+        amount,
+    });
+
+    Ok(())
+}
+
+pub fn calculate_rewards_secure(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    _instruction_data: &[u8],
+    clock: &impl ClockProvider,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let pool_account = next_account_info(accounts_iter)?;
+    let user_stake_account = next_account_info(accounts_iter)?;
+    let user_account = next_account_info(accounts_iter)?;
+
+    assert_signer(user_account)?;
+
+    let pool_data = strict_deserialize::<StakingPool>(&pool_account.data.borrow())?;
+    let user_data = strict_deserialize::<UserStake>(&user_stake_account.data.borrow())?;
+
+    let current_time = clock.unix_timestamp()?;
+
+    // CHECK: Checked subtraction, and a negative interval is an error
+    let time_elapsed = current_time
+        .checked_sub(user_data.last_claim)
+        .and_then(|elapsed| u64::try_from(elapsed).ok())
+        .ok_or(ProgramError::InvalidAccountData)?;
+
+    // CHECK: Checked multiplication
+    let base_reward = (CheckedU64(time_elapsed) * pool_data.reward_rate)?;
+
+    // CHECK: The share keeps its fraction, an empty pool is an error, and
+    // the reward rounds down, in the pool's favour
+    let share = U64F64::from_ratio(user_data.amount, pool_data.total_staked, Rounding::Down)?;
+    let user_reward = share.mul_int(base_reward.get(), Rounding::Down)?;
+
+    msg!("User reward calculated: {}", user_reward);
+
+    Ok(())
+}
+
+pub fn transfer_secure(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let from_account = next_account_info(accounts_iter)?;
+    let to_account = next_account_info(accounts_iter)?;
+    let user_account = next_account_info(accounts_iter)?;
+
+    assert_signer(user_account)?;
+
+    let amount = read_amount(instruction_data)?;
+
+    // CHECK: Both balances are read before either is written, so a transfer
+    // to itself would credit the account as if it had not been debited
+    assert_distinct(from_account, to_account)?;
+
+    // CHECK: Check balance first
+    let from_balance = (CheckedU64(from_account.lamports()) - amount)
+        .map_err(|_| ProgramError::InsufficientFunds)?;
+    let to_balance = (CheckedU64(to_account.lamports()) + amount)?;
+
+    **from_account.try_borrow_mut_lamports()? = from_balance.get();
+    **to_account.try_borrow_mut_lamports()? = to_balance.get();
+
+    Ok(())
+}
@@ -0,0 +1,249 @@
+/*
+ * SECURE SOLANA PROGRAM
+ *
+ * Fixed counterpart of ../batch_payout.rs
+ *
+ * The authority approves every recipient, and distribute pays a bounded
+ * page of them at a time, remembering where it stopped, so however long
+ * the list grows, each page fits in a transaction.
+ *
+ * The authority hands the payroll over in two steps: it proposes a new
+ * authority, which takes over only when it signs an accept itself, so a
+ * mistyped key leaves the payroll where it was.
+ */
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_common::access_control::TwoStepAuthority;
+use solana_common::deserialize::strict_deserialize;
+use solana_common::events::{emit, AuthorityChanged};
+use solana_common::validation::{assert_owned_by, assert_signer, assert_writable};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+
+/// Recipients one distribute pays: with the payroll, the authority and
+/// the program, well inside a transaction's 64 account locks
+pub const MAX_PAGE: usize = 48;
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct Payroll {
+    /// Approves recipients and pays them; the key it is being handed to,
+    /// if any, is `authority.pending`
+    pub authority: TwoStepAuthority,
+    /// Lamports each recipient is paid per payout
+    pub amount: u64,
+    /// In the order they registered; the account grows with the list
+    pub recipients: Vec<Pubkey>,
+    /// The first recipient the next page pays
+    pub cursor: u32,
+}
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let (instruction, rest) = instruction_data
+        .split_first()
+        .ok_or(ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        0 => initialize_secure(program_id, accounts, rest),
+        1 => register_secure(program_id, accounts, rest),
+        2 => distribute_secure(program_id, accounts, rest),
+        3 => propose_authority_secure(program_id, accounts, rest),
+        4 => accept_authority_secure(program_id, accounts, rest),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+pub fn initialize_secure(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let payroll_account = next_account_info(accounts_iter)?;
+    let authority = next_account_info(accounts_iter)?;
+
+    assert_owned_by(payroll_account, program_id)?;
+    assert_writable(payroll_account)?;
+
+    assert_signer(authority)?;
+
+    let existing = strict_deserialize::<Payroll>(&payroll_account.data.borrow())?;
+    if existing.authority.authority != Pubkey::default() {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    let amount = instruction_data
+        .try_into()
+        .map(u64::from_le_bytes)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    let payroll = Payroll {
+        authority: TwoStepAuthority::new(*authority.key),
+        amount,
+        recipients: Vec::new(),
+        cursor: 0,
+    };
+
+    payroll.serialize(&mut &mut payroll_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+pub fn register_secure(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    _instruction_data: &[u8],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let payroll_account = next_account_info(accounts_iter)?;
+    let recipient = next_account_info(accounts_iter)?;
+    let approver = next_account_info(accounts_iter)?;
+
+    assert_owned_by(payroll_account, program_id)?;
+    assert_writable(payroll_account)?;
+
+    assert_signer(recipient)?;
+
+    let mut payroll = strict_deserialize::<Payroll>(&payroll_account.data.borrow())?;
+
+    // CHECK: The authority approves every recipient
+    payroll.authority.only_authority(approver)?;
+
+    payroll.recipients.push(*recipient.key);
+
+    payroll_account.resize(borsh::object_length(&payroll)?)?;
+    payroll.serialize(&mut &mut payroll_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+pub fn distribute_secure(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    _instruction_data: &[u8],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let payroll_account = next_account_info(accounts_iter)?;
+    let authority = next_account_info(accounts_iter)?;
+    let recipient_accounts = accounts_iter.as_slice();
+
+    assert_owned_by(payroll_account, program_id)?;
+    assert_writable(payroll_account)?;
+
+    let mut payroll = strict_deserialize::<Payroll>(&payroll_account.data.borrow())?;
+
+    payroll.authority.only_authority(authority)?;
+
+    // CHECK: A page of at most MAX_PAGE recipients, starting where the last
+    // page stopped; the round ends when the cursor wraps
+    if recipient_accounts.is_empty() || recipient_accounts.len() > MAX_PAGE {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let start = payroll.cursor as usize;
+    let end = start + recipient_accounts.len();
+    let page = payroll
+        .recipients
+        .get(start..end)
+        .ok_or(ProgramError::InvalidArgument)?;
+    if recipient_accounts
+        .iter()
+        .zip(page)
+        .any(|(account, key)| account.key != key)
+    {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let total = payroll
+        .amount
+        .checked_mul(recipient_accounts.len() as u64)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    let min_balance = Rent::get()?.minimum_balance(payroll_account.data_len());
+    if payroll_account.lamports().saturating_sub(min_balance) < total {
+        return Err(ProgramError::InsufficientFunds);
+    }
+
+    **payroll_account.try_borrow_mut_lamports()? -= total;
+    for recipient in recipient_accounts {
+        assert_writable(recipient)?;
+        **recipient.try_borrow_mut_lamports()? += payroll.amount;
+    }
+
+    msg!(
+        "Paid recipients {} to {} of {}",
+        start,
+        end,
+        payroll.recipients.len()
+    );
+
+    payroll.cursor = (end % payroll.recipients.len()) as u32;
+    payroll.serialize(&mut &mut payroll_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+pub fn propose_authority_secure(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let payroll_account = next_account_info(accounts_iter)?;
+    let authority = next_account_info(accounts_iter)?;
+
+    assert_owned_by(payroll_account, program_id)?;
+    assert_writable(payroll_account)?;
+
+    let new_authority =
+        Pubkey::try_from(instruction_data).map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    let mut payroll = strict_deserialize::<Payroll>(&payroll_account.data.borrow())?;
+
+    // CHECK: Only the authority offers the payroll, and the offer moves
+    // nothing until it is accepted
+    payroll.authority.propose(authority, new_authority)?;
+
+    payroll_account.resize(borsh::object_length(&payroll)?)?;
+    payroll.serialize(&mut &mut payroll_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+pub fn accept_authority_secure(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    _instruction_data: &[u8],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let payroll_account = next_account_info(accounts_iter)?;
+    let new_authority = next_account_info(accounts_iter)?;
+
+    assert_owned_by(payroll_account, program_id)?;
+    assert_writable(payroll_account)?;
+
+    let mut payroll = strict_deserialize::<Payroll>(&payroll_account.data.borrow())?;
+
+    // CHECK: The payroll goes only to the key it was offered to, signing
+    payroll.authority.accept(new_authority)?;
+
+    payroll_account.resize(borsh::object_length(&payroll)?)?;
+    payroll.serialize(&mut &mut payroll_account.data.borrow_mut()[..])?;
+    emit(AuthorityChanged {
+        account: *payroll_account.key,
+        authority: *new_authority.key,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,105 @@
+/*
+ * SECURE SOLANA PROGRAM
+ *
+ * Fixed counterpart of ../missing_owner_check.rs
+ *
+ * VaultData is only trusted when this program owns the account it was read
+ * from - only this program could have written it - and the authority it
+ * names must have signed, and hold the treasurer role. load checks the
+ * owner, and VaultData's discriminator, as it reads the account.
+ *
+ * VaultData also carries the vault's incident-response controls: while
+ * it is paused nothing leaves the vault, and at most its cap leaves in
+ * any window, so a stolen authority key drains one window's worth
+ * rather than the vault.
+ */
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_common::access_control::{only_role, Member, Role};
+use solana_common::account::load;
+use solana_common::clock::{ClockProvider, SysvarClock};
+use solana_common::discriminator::AccountDiscriminator;
+use solana_common::events::{emit, WithdrawEvent};
+use solana_common::pausable::{PauseState, WithdrawCap};
+use solana_common::validation::assert_key_eq;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+/// Written when the vault is created; each withdrawal counts against
+/// `cap`
+#[derive(BorshSerialize, BorshDeserialize, AccountDiscriminator, Debug)]
+pub struct VaultData {
+    /// Withdraws while it holds [`Role::TREASURER`]
+    pub authority: Member,
+    pub vault: Pubkey,
+    pub pause: PauseState,
+    pub cap: WithdrawCap,
+}
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    process_instruction_with_clock(program_id, accounts, instruction_data, &SysvarClock)
+}
+
+/// process_instruction, with the time read from `clock`
+pub fn process_instruction_with_clock(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+    clock: &impl ClockProvider,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let vault_data_account = next_account_info(accounts_iter)?;
+    let vault_account = next_account_info(accounts_iter)?;
+    let authority_account = next_account_info(accounts_iter)?;
+    let recipient_account = next_account_info(accounts_iter)?;
+
+    // CHECK: Verify vault_data_account is owned by this program as it is read
+    let mut vault_data = load::<VaultData>(vault_data_account, program_id)?;
+
+    // Now these checks are meaningful
+    assert_key_eq(vault_account, &vault_data.vault)?;
+
+    // CHECK: The authority signed, and may move the vault's funds
+    only_role(authority_account, &vault_data.authority, Role::TREASURER)?;
+
+    // CHECK: Nothing leaves a paused vault
+    vault_data.pause.when_not_paused()?;
+
+    let amount = instruction_data
+        .get(..8)
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(u64::from_le_bytes)
+        .ok_or(ProgramError::InvalidInstructionData)?;
+
+    if vault_account.lamports() < amount {
+        return Err(ProgramError::InsufficientFunds);
+    }
+
+    // CHECK: No more than the cap leaves in one window
+    vault_data.cap.record(amount, clock.unix_timestamp()?)?;
+    vault_data.serialize_with_discriminator(&mut &mut vault_data_account.data.borrow_mut()[..])?;
+
+    msg!("Withdrawing {} lamports", amount);
+
+    **vault_account.try_borrow_mut_lamports()? -= amount;
+    **recipient_account.try_borrow_mut_lamports()? += amount;
+    emit(WithdrawEvent {
+        account: *vault_account.key,
+        recipient: *recipient_account.key,
+        amount,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,57 @@
+/*
+ * SECURE SOLANA PROGRAM
+ *
+ * Fixed counterpart of ../missing_signer_check.rs
+ *
+ * The account being debited must have signed the transaction; passing
+ * someone else's account without their signature fails before any
+ * lamports move.
+ */
+
+use solana_common::events::{emit, WithdrawEvent};
+use solana_common::validation::assert_signer;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let user_account = next_account_info(accounts_iter)?;
+    let destination_account = next_account_info(accounts_iter)?;
+
+    // CHECK: Verify user_account is a signer
+    assert_signer(user_account)?;
+
+    let amount = instruction_data
+        .get(..8)
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(u64::from_le_bytes)
+        .ok_or(ProgramError::InvalidInstructionData)?;
+
+    if user_account.lamports() < amount {
+        return Err(ProgramError::InsufficientFunds);
+    }
+
+    msg!("Transferring {} lamports", amount);
+
+    **user_account.try_borrow_mut_lamports()? -= amount;
+    **destination_account.try_borrow_mut_lamports()? += amount;
+    emit(WithdrawEvent {
+        account: *user_account.key,
+        recipient: *destination_account.key,
+        amount,
+    });
+
+    Ok(())
+}
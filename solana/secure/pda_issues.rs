@@ -0,0 +1,101 @@
+/*
+ * SECURE SOLANA PROGRAM
+ *
+ * Fixed counterpart of ../pda_issues.rs
+ *
+ * The user's data account must be the PDA derived from
+ * [b"user_data", user], so no other program-owned account that happens
+ * to name the user - an escrow, another record - can be withdrawn from in
+ * its place. Bumps are always the canonical one from find_program_address.
+ */
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_common::deserialize::strict_deserialize;
+use solana_common::events::{emit, WithdrawEvent};
+use solana_common::pda::{find_and_validate, PdaSpec};
+use solana_common::validation::{assert_key_eq, assert_owned_by, assert_signer};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+/// Each user's data account
+pub const USER_DATA: PdaSpec<1> = PdaSpec::new(b"user_data", ["user"]);
+/// The program's one vault
+pub const VAULT: PdaSpec<0> = PdaSpec::new(b"vault", []);
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct UserData {
+    pub owner: Pubkey,
+    pub balance: u64,
+}
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let user_account = next_account_info(accounts_iter)?;
+    let pda_account = next_account_info(accounts_iter)?;
+    let recipient_account = next_account_info(accounts_iter)?;
+
+    assert_signer(user_account)?;
+
+    // CHECK: Derive the expected PDA and verify pda_account is at it
+    find_and_validate(&USER_DATA.seeds([user_account.key]), program_id, pda_account.key)?;
+
+    // CHECK: Verify PDA is owned by this program
+    assert_owned_by(pda_account, program_id)?;
+
+    let mut user_data = strict_deserialize::<UserData>(&pda_account.data.borrow())?;
+
+    // Now this check is meaningful since PDA was validated
+    assert_key_eq(user_account, &user_data.owner)?;
+
+    let amount = instruction_data
+        .get(..8)
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(u64::from_le_bytes)
+        .ok_or(ProgramError::InvalidInstructionData)?;
+
+    if user_data.balance < amount {
+        return Err(ProgramError::InsufficientFunds);
+    }
+
+    msg!("Withdrawing {} from PDA", amount);
+
+    user_data.balance -= amount;
+    user_data.serialize(&mut &mut pda_account.data.borrow_mut()[..])?;
+
+    **pda_account.try_borrow_mut_lamports()? -= amount;
+    **recipient_account.try_borrow_mut_lamports()? += amount;
+    emit(WithdrawEvent {
+        account: *pda_account.key,
+        recipient: *recipient_account.key,
+        amount,
+    });
+
+    Ok(())
+}
+
+/// Accepts only the vault PDA at its canonical bump
+pub fn secure_with_bump(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    _instruction_data: &[u8],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let pda_account = next_account_info(accounts_iter)?;
+
+    // CHECK: The bump comes from find_program_address, never the caller
+    find_and_validate(&VAULT.seeds([]), program_id, pda_account.key)?;
+
+    Ok(())
+}
@@ -0,0 +1,128 @@
+/*
+ * SECURE SOLANA PROGRAM
+ *
+ * Fixed counterpart of ../reinitialization.rs
+ *
+ * VaultConfig derives AccountDiscriminator, so initialize writes an 8-byte
+ * discriminator ahead of it and refuses any account that already starts
+ * with one, and the authority and the deposit total can only ever be
+ * written once. deposit reads only an account that starts with it.
+ */
+
+use solana_common::discriminator::AccountDiscriminator;
+use solana_common::events::{emit, AuthorityChanged, DepositEvent};
+use solana_common::validation::{assert_owned_by, assert_signer};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+// { authority: Pubkey, total_deposited: u64, fee_percentage: u8 }, laid
+// out once for every example that keeps a vault
+pub use lab_domain::VaultConfig;
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let (instruction, rest) = instruction_data
+        .split_first()
+        .ok_or(ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        0 => initialize(program_id, accounts, rest),
+        1 => deposit(program_id, accounts, rest),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+pub fn initialize(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let vault_account = next_account_info(accounts_iter)?;
+    let authority_account = next_account_info(accounts_iter)?;
+
+    assert_owned_by(vault_account, program_id)?;
+
+    assert_signer(authority_account)?;
+
+    // CHECK: An account that starts with VaultConfig's discriminator has
+    // already been initialized
+    if vault_account.data.borrow().starts_with(&VaultConfig::DISCRIMINATOR) {
+        msg!("Vault already initialized");
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    let fee_percentage = *instruction_data
+        .first()
+        .ok_or(ProgramError::InvalidInstructionData)?;
+
+    let vault_config = VaultConfig::new(*authority_account.key, fee_percentage);
+
+    vault_config.serialize_with_discriminator(&mut &mut vault_account.data.borrow_mut()[..])?;
+
+    msg!("Vault initialized with authority: {}", authority_account.key);
+
+    emit(AuthorityChanged {
+        account: *vault_account.key,
+        authority: *authority_account.key,
+    });
+
+    Ok(())
+}
+
+pub fn deposit(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let vault_account = next_account_info(accounts_iter)?;
+    let user_account = next_account_info(accounts_iter)?;
+
+    assert_owned_by(vault_account, program_id)?;
+
+    assert_signer(user_account)?;
+
+    // CHECK: Deposits only into a vault that initialize has written
+    let mut vault_config = VaultConfig::try_deserialize_checked(&vault_account.data.borrow())
+        .map_err(|_| ProgramError::UninitializedAccount)?;
+
+    let amount = instruction_data
+        .get(..8)
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(u64::from_le_bytes)
+        .ok_or(ProgramError::InvalidInstructionData)?;
+
+    // CHECK: A deposit larger than the depositor's balance fails rather than
+    // underflowing it
+    if user_account.lamports() < amount {
+        return Err(ProgramError::InsufficientFunds);
+    }
+
+    vault_config.total_deposited = vault_config
+        .total_deposited
+        .checked_add(amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    vault_config.serialize_with_discriminator(&mut &mut vault_account.data.borrow_mut()[..])?;
+
+    **user_account.try_borrow_mut_lamports()? -= amount;
+    **vault_account.try_borrow_mut_lamports()? += amount;
+    emit(DepositEvent {
+        account: *vault_account.key,
+        depositor: *user_account.key,
+        amount,
+    });
+
+    Ok(())
+}
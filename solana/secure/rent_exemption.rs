@@ -0,0 +1,131 @@
+/*
+ * SECURE SOLANA PROGRAM
+ *
+ * Fixed counterpart of ../rent_exemption.rs
+ *
+ * Data accounts must be rent-exempt to be initialized, and withdrawals
+ * leave the rent-exempt minimum behind, so a user's record is never
+ * emptied and purged along with its lamports.
+ */
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_common::deserialize::strict_deserialize;
+use solana_common::events::{emit, WithdrawEvent};
+use solana_common::validation::{
+    assert_key_eq, assert_owned_by, assert_rent_exempt, assert_signer, assert_writable,
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct UserData {
+    pub owner: Pubkey,
+    pub balance: u64,
+    pub metadata: [u8; 32],
+}
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let (instruction, rest) = instruction_data
+        .split_first()
+        .ok_or(ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        0 => initialize_secure(program_id, accounts, rest),
+        1 => withdraw_secure(program_id, accounts, rest),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+pub fn initialize_secure(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    _instruction_data: &[u8],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let user_data_account = next_account_info(accounts_iter)?;
+    let user_account = next_account_info(accounts_iter)?;
+
+    assert_owned_by(user_data_account, program_id)?;
+    assert_writable(user_data_account)?;
+
+    assert_signer(user_account)?;
+
+    // CHECK: Verify account is rent-exempt
+    assert_rent_exempt(user_data_account, &Rent::get()?)?;
+
+    let user_data = UserData {
+        owner: *user_account.key,
+        balance: 0,
+        metadata: [0u8; 32],
+    };
+
+    user_data.serialize(&mut &mut user_data_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+pub fn withdraw_secure(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    _instruction_data: &[u8],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let user_data_account = next_account_info(accounts_iter)?;
+    let user_account = next_account_info(accounts_iter)?;
+    let recipient_account = next_account_info(accounts_iter)?;
+
+    assert_owned_by(user_data_account, program_id)?;
+    assert_writable(user_data_account)?;
+
+    assert_signer(user_account)?;
+
+    let user_data = strict_deserialize::<UserData>(&user_data_account.data.borrow())?;
+
+    assert_key_eq(user_account, &user_data.owner)?;
+
+    let balance = user_data_account.lamports();
+
+    // CHECK: Calculate minimum balance for rent exemption
+    let rent = Rent::get()?;
+    let min_balance = rent.minimum_balance(user_data_account.data_len());
+
+    // CHECK: Only withdraw excess above rent exemption
+    let withdrawable = balance
+        .checked_sub(min_balance)
+        .ok_or(ProgramError::InsufficientFunds)?;
+
+    if withdrawable == 0 {
+        msg!("No funds available for withdrawal");
+        return Err(ProgramError::InsufficientFunds);
+    }
+
+    msg!(
+        "Withdrawing {} lamports (keeping {} for rent)",
+        withdrawable,
+        min_balance
+    );
+
+    **user_data_account.try_borrow_mut_lamports()? -= withdrawable;
+    **recipient_account.try_borrow_mut_lamports()? += withdrawable;
+    emit(WithdrawEvent {
+        account: *user_data_account.key,
+        recipient: *recipient_account.key,
+        amount: withdrawable,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,132 @@
+/*
+ * SECURE SOLANA PROGRAM
+ *
+ * Fixed counterpart of ../type_confusion.rs
+ *
+ * Every account type derives AccountDiscriminator, so its data starts with
+ * an 8-byte discriminator hashed from its name, and is read with load,
+ * which checks it before any other field is trusted: an AdminAccount
+ * passed where a UserAccount is expected (or the reverse) is rejected even
+ * though the rest of the layout matches.
+ */
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_common::account::load;
+use solana_common::discriminator::AccountDiscriminator;
+use solana_common::events::{emit, WithdrawEvent};
+use solana_common::validation::{assert_key_eq, assert_signer};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+#[derive(BorshSerialize, BorshDeserialize, AccountDiscriminator, Debug)]
+pub struct UserAccountSecure {
+    pub owner: Pubkey,
+    pub balance: u64,
+    pub rewards: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, AccountDiscriminator, Debug)]
+pub struct AdminAccountSecure {
+    pub owner: Pubkey,
+    pub balance: u64,
+    pub admin_level: u64,
+}
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let (instruction, rest) = instruction_data
+        .split_first()
+        .ok_or(ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        0 => withdraw_user_secure(program_id, accounts, rest),
+        1 => admin_action_secure(program_id, accounts, rest),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+pub fn withdraw_user_secure(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let user_account_info = next_account_info(accounts_iter)?;
+    let owner_account = next_account_info(accounts_iter)?;
+
+    assert_signer(owner_account)?;
+
+    // CHECK: Read it only if this program owns it and its discriminator says
+    // it is a UserAccount
+    let mut user_data = load::<UserAccountSecure>(user_account_info, program_id)?;
+
+    assert_key_eq(owner_account, &user_data.owner)?;
+
+    let amount = instruction_data
+        .get(..8)
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(u64::from_le_bytes)
+        .ok_or(ProgramError::InvalidInstructionData)?;
+
+    let available = user_data
+        .balance
+        .checked_add(user_data.rewards)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    if available < amount {
+        return Err(ProgramError::InsufficientFunds);
+    }
+
+    msg!(
+        "Withdrawing {} (balance: {}, rewards: {})",
+        amount,
+        user_data.balance,
+        user_data.rewards
+    );
+
+    user_data.balance = user_data.balance.saturating_sub(amount);
+    user_data.serialize_with_discriminator(&mut &mut user_account_info.data.borrow_mut()[..])?;
+    emit(WithdrawEvent {
+        account: *user_account_info.key,
+        recipient: *owner_account.key,
+        amount,
+    });
+
+    Ok(())
+}
+
+pub fn admin_action_secure(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    _instruction_data: &[u8],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let admin_account_info = next_account_info(accounts_iter)?;
+    let admin_signer = next_account_info(accounts_iter)?;
+
+    assert_signer(admin_signer)?;
+
+    // CHECK: Read it only if this program owns it and its discriminator says
+    // it is an AdminAccount
+    let admin_data = load::<AdminAccountSecure>(admin_account_info, program_id)?;
+
+    assert_key_eq(admin_signer, &admin_data.owner)?;
+
+    if admin_data.admin_level < 5 {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    msg!("Admin action executed");
+
+    Ok(())
+}
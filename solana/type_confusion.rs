@@ -8,6 +8,7 @@
  */
 
 use borsh::{BorshDeserialize, BorshSerialize};
+use solana_common::events::{emit, WithdrawEvent};
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
     entrypoint,
@@ -32,6 +33,7 @@ pub struct AdminAccount {
     pub admin_level: u64,  // But means something different!
 }
 
+#[cfg(not(feature = "no-entrypoint"))]
 entrypoint!(process_instruction);
 
 pub fn process_instruction(
@@ -87,6 +89,11 @@ pub fn withdraw_user(
 
     user_data.balance = user_data.balance.saturating_sub(amount);
     user_data.serialize(&mut &mut user_account_info.data.borrow_mut()[..])?;
+    emit(WithdrawEvent {
+        account: *user_account_info.key,
+        recipient: *owner_account.key,
+        amount,
+    });
 
     Ok(())
 }
@@ -128,22 +135,20 @@ pub fn admin_action(
 /*
  * SECURE VERSION WITH DISCRIMINATOR:
  *
- * use std::mem::size_of;
+ * use solana_common::account::load;
+ * use solana_common::discriminator::AccountDiscriminator;
  *
- * const USER_ACCOUNT_DISCRIMINATOR: u64 = 0x1111111111111111;
- * const ADMIN_ACCOUNT_DISCRIMINATOR: u64 = 0x2222222222222222;
- *
- * #[derive(BorshSerialize, BorshDeserialize, Debug)]
+ * // ADD DISCRIMINATOR: the first 8 bytes of sha256("account:<TypeName>"),
+ * // written ahead of the fields
+ * #[derive(BorshSerialize, BorshDeserialize, AccountDiscriminator, Debug)]
  * pub struct UserAccountSecure {
- *     pub discriminator: u64,  // ADD DISCRIMINATOR
  *     pub owner: Pubkey,
  *     pub balance: u64,
  *     pub rewards: u64,
  * }
  *
- * #[derive(BorshSerialize, BorshDeserialize, Debug)]
+ * #[derive(BorshSerialize, BorshDeserialize, AccountDiscriminator, Debug)]
  * pub struct AdminAccountSecure {
- *     pub discriminator: u64,  // ADD DISCRIMINATOR
  *     pub owner: Pubkey,
  *     pub balance: u64,
  *     pub admin_level: u64,
@@ -158,21 +163,13 @@ pub fn admin_action(
  *     let user_account_info = next_account_info(accounts_iter)?;
  *     let owner_account = next_account_info(accounts_iter)?;
  *
- *     if user_account_info.owner != program_id {
- *         return Err(ProgramError::IncorrectProgramId);
- *     }
- *
  *     if !owner_account.is_signer {
  *         return Err(ProgramError::MissingRequiredSignature);
  *     }
  *
- *     let mut user_data = UserAccountSecure::try_from_slice(&user_account_info.data.borrow())?;
- *
- *     // CHECK: Verify discriminator matches UserAccount type
- *     if user_data.discriminator != USER_ACCOUNT_DISCRIMINATOR {
- *         msg!("Invalid account type - expected UserAccount");
- *         return Err(ProgramError::InvalidAccountData);
- *     }
+ *     // CHECK: Read it only if this program owns it and its discriminator
+ *     // says it is a UserAccount
+ *     let mut user_data = load::<UserAccountSecure>(user_account_info, program_id)?;
  *
  *     if user_data.owner != *owner_account.key {
  *         return Err(ProgramError::InvalidAccountData);
@@ -185,12 +182,14 @@ pub fn admin_action(
  *     }
  *
  *     user_data.balance = user_data.balance.saturating_sub(amount);
- *     user_data.serialize(&mut &mut user_account_info.data.borrow_mut()[..])?;
+ *     user_data.serialize_with_discriminator(&mut &mut user_account_info.data.borrow_mut()[..])?;
  *
  *     Ok(())
  * }
  *
  * // Anchor framework provides automatic discriminators with #[account] macro
+ *
+ * Compiled in secure/type_confusion.rs.
  */
 
 /*
@@ -0,0 +1,219 @@
+/*
+ * SECURE SUBSTRATE PALLET
+ *
+ * Fixed counterpart of ../unsigned_extrinsic.rs
+ *
+ * Prices arrive as a payload signed by an authority's oracle key.
+ * validate_unsigned verifies the signature, the signer and the block the
+ * payload is bound to before the transaction reaches the pool, and each
+ * authority can report at most once per UnsignedInterval blocks.
+ */
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use pallet::*;
+
+use sp_runtime::KeyTypeId;
+
+/// Keystore type of the oracle keys offchain workers sign prices with
+pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"orcl");
+
+/// sr25519 oracle keys, usable as `Config::AuthorityId` by runtimes whose
+/// `SigningTypes` are `MultiSigner` / `MultiSignature` or plain sr25519
+pub mod crypto {
+    use super::KEY_TYPE;
+    use sp_core::sr25519::Signature as Sr25519Signature;
+    use sp_runtime::app_crypto::{app_crypto, sr25519};
+    use sp_runtime::traits::Verify;
+    use sp_runtime::{MultiSignature, MultiSigner};
+
+    app_crypto!(sr25519, KEY_TYPE);
+
+    pub struct OracleAuthId;
+
+    impl frame_system::offchain::AppCrypto<MultiSigner, MultiSignature> for OracleAuthId {
+        type RuntimeAppPublic = Public;
+        type GenericSignature = sp_core::sr25519::Signature;
+        type GenericPublic = sp_core::sr25519::Public;
+    }
+
+    impl frame_system::offchain::AppCrypto<<Sr25519Signature as Verify>::Signer, Sr25519Signature>
+        for OracleAuthId
+    {
+        type RuntimeAppPublic = Public;
+        type GenericSignature = sp_core::sr25519::Signature;
+        type GenericPublic = sp_core::sr25519::Public;
+    }
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+    use frame_support::pallet_prelude::*;
+    use frame_system::offchain::{AppCrypto, SignedPayload, SigningTypes};
+    use frame_system::pallet_prelude::*;
+    use sp_runtime::traits::{IdentifyAccount, Saturating, UniqueSaturatedInto};
+
+    pub const MAX_PRICES: u32 = 64;
+    pub const MAX_AUTHORITIES: u32 = 16;
+
+    /// What an oracle signs: the price, its key, and the block it was
+    /// read at
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+    pub struct PricePayload<Public, BlockNumber> {
+        pub block_number: BlockNumber,
+        pub price: u32,
+        pub public: Public,
+    }
+
+    impl<T: SigningTypes> SignedPayload<T> for PricePayload<T::Public, BlockNumberFor<T>> {
+        fn public(&self) -> T::Public {
+            self.public.clone()
+        }
+    }
+
+    #[pallet::pallet]
+    pub struct Pallet<T>(_);
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config + SigningTypes {
+        type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+        /// The key type price payloads must be signed with
+        type AuthorityId: AppCrypto<Self::Public, Self::Signature>;
+
+        /// Blocks an authority waits between accepted prices; also how
+        /// long a submission stays in the pool
+        #[pallet::constant]
+        type UnsignedInterval: Get<BlockNumberFor<Self>>;
+
+        #[pallet::constant]
+        type UnsignedPriority: Get<TransactionPriority>;
+    }
+
+    #[pallet::storage]
+    pub type Authorities<T: Config> =
+        StorageValue<_, BoundedVec<T::AccountId, ConstU32<MAX_AUTHORITIES>>, ValueQuery>;
+
+    #[pallet::storage]
+    pub type Prices<T> = StorageValue<_, BoundedVec<u32, ConstU32<MAX_PRICES>>, ValueQuery>;
+
+    /// First block each authority may sign its next price at
+    #[pallet::storage]
+    pub type NextUnsignedAt<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, BlockNumberFor<T>, ValueQuery>;
+
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        NewPrice { price: u32, reporter: T::AccountId },
+    }
+
+    #[pallet::error]
+    pub enum Error<T> {
+        NotAuthority,
+        TooManyAuthorities,
+        StalePayload,
+    }
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        #[pallet::call_index(0)]
+        #[pallet::weight(Weight::from_parts(10_000, 0).saturating_add(T::DbWeight::get().reads_writes(1, 1)))]
+        pub fn add_authority(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+            ensure_root(origin)?;
+            Authorities::<T>::try_append(who).map_err(|_| Error::<T>::TooManyAuthorities)?;
+            Ok(())
+        }
+
+        /// Records the price in a payload validate_unsigned has verified
+        #[pallet::call_index(1)]
+        #[pallet::weight(Weight::from_parts(10_000, 0).saturating_add(T::DbWeight::get().reads_writes(3, 2)))]
+        pub fn submit_price_unsigned_with_signed_payload(
+            origin: OriginFor<T>,
+            price_payload: PricePayload<T::Public, BlockNumberFor<T>>,
+            _signature: T::Signature,
+        ) -> DispatchResult {
+            ensure_none(origin)?;
+            // SAFE: The signature was checked in validate_unsigned, which
+            // runs again as pre_dispatch before inclusion; the reporter is
+            // the key that signed, not an argument
+            let reporter = price_payload.public.into_account();
+            ensure!(
+                Authorities::<T>::get().contains(&reporter),
+                Error::<T>::NotAuthority
+            );
+            ensure!(
+                price_payload.block_number >= NextUnsignedAt::<T>::get(&reporter),
+                Error::<T>::StalePayload
+            );
+
+            // SAFE: This payload, and any older one, is now stale
+            let now = frame_system::Pallet::<T>::block_number();
+            NextUnsignedAt::<T>::insert(&reporter, now.saturating_add(T::UnsignedInterval::get()));
+            Prices::<T>::mutate(|prices| {
+                if prices.is_full() {
+                    prices.remove(0);
+                }
+                let _ = prices.try_push(price_payload.price);
+            });
+            Self::deposit_event(Event::NewPrice {
+                price: price_payload.price,
+                reporter,
+            });
+            Ok(())
+        }
+    }
+
+    #[pallet::validate_unsigned]
+    impl<T: Config> ValidateUnsigned for Pallet<T> {
+        type Call = Call<T>;
+
+        fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+            let Call::submit_price_unsigned_with_signed_payload {
+                price_payload: payload,
+                signature,
+            } = call
+            else {
+                return InvalidTransaction::Call.into();
+            };
+
+            // CHECK: Signed by the key the payload names
+            if !SignedPayload::<T>::verify::<T::AuthorityId>(payload, signature.clone()) {
+                return InvalidTransaction::BadProof.into();
+            }
+            // CHECK: That key belongs to an authority
+            let reporter = payload.public.clone().into_account();
+            if !Authorities::<T>::get().contains(&reporter) {
+                return InvalidTransaction::BadSigner.into();
+            }
+            // CHECK: Bound to a block - not from the future, and not older
+            // than the authority's next allowed submission (no replays)
+            if payload.block_number > frame_system::Pallet::<T>::block_number() {
+                return InvalidTransaction::Future.into();
+            }
+            if payload.block_number < NextUnsignedAt::<T>::get(&reporter) {
+                return InvalidTransaction::Stale.into();
+            }
+
+            // SAFE: One pending submission per authority, expiring with
+            // the interval
+            ValidTransaction::with_tag_prefix("PriceOracle")
+                .priority(T::UnsignedPriority::get())
+                .and_provides(reporter)
+                .longevity(T::UnsignedInterval::get().unique_saturated_into())
+                .propagate(true)
+                .build()
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        pub fn average_price() -> Option<u32> {
+            let prices = Prices::<T>::get();
+            let count = u64::try_from(prices.len())
+                .ok()
+                .filter(|count| *count > 0)?;
+            let sum: u64 = prices.iter().map(|price| u64::from(*price)).sum();
+            u32::try_from(sum / count).ok()
+        }
+    }
+}
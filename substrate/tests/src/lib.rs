@@ -0,0 +1,14 @@
+//! Mock runtimes for the vulnerable Substrate pallets
+//!
+//! Every example in `substrate/` has a pallet crate under `pallets/` that
+//! compiles the example file as-is and a `_secure` crate that compiles its
+//! fixed counterpart from `substrate/secure/`. Each module here builds a
+//! minimal runtime around both, and `tests/` runs the same attack against
+//! each: it must work on the first and be rejected by the second.
+//!
+//! ```bash
+//! cd substrate/tests
+//! cargo test
+//! ```
+
+pub mod unsigned_extrinsic;
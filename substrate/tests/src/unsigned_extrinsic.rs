@@ -0,0 +1,139 @@
+//! Runtimes with the price oracle pallet, and the keys that report to it
+
+use sp_core::sr25519;
+use sp_core::Pair;
+
+/// Offchain worker key of the one authority in both runtimes
+pub fn oracle() -> sr25519::Pair {
+    sr25519::Pair::from_string("//Alice", None).expect("static seed")
+}
+
+pub fn attacker() -> sr25519::Pair {
+    sr25519::Pair::from_string("//Mallory", None).expect("static seed")
+}
+
+pub mod vulnerable {
+    use frame_support::{construct_runtime, derive_impl, parameter_types};
+    use sp_core::{sr25519, Pair};
+    use sp_runtime::traits::IdentityLookup;
+    use sp_runtime::transaction_validity::TransactionPriority;
+    use sp_runtime::BuildStorage;
+
+    type Block = frame_system::mocking::MockBlock<Test>;
+
+    construct_runtime!(
+        pub enum Test {
+            System: frame_system,
+            Oracle: unsigned_extrinsic,
+        }
+    );
+
+    #[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+    impl frame_system::Config for Test {
+        type Block = Block;
+        type AccountId = sr25519::Public;
+        type Lookup = IdentityLookup<Self::AccountId>;
+    }
+
+    parameter_types! {
+        pub const UnsignedPriority: TransactionPriority = 1 << 20;
+    }
+
+    impl unsigned_extrinsic::Config for Test {
+        type RuntimeEvent = RuntimeEvent;
+        type UnsignedPriority = UnsignedPriority;
+    }
+
+    /// Block 1, with [`oracle`](super::oracle) as the only authority
+    pub fn new_test_ext() -> sp_io::TestExternalities {
+        let storage = frame_system::GenesisConfig::<Test>::default()
+            .build_storage()
+            .expect("genesis builds");
+        let mut ext = sp_io::TestExternalities::new(storage);
+        ext.execute_with(|| {
+            System::set_block_number(1);
+            unsigned_extrinsic::Authorities::<Test>::put(frame_support::BoundedVec::truncate_from(
+                vec![super::oracle().public()],
+            ));
+        });
+        ext
+    }
+}
+
+pub mod secure {
+    use codec::Encode;
+    use frame_support::{construct_runtime, derive_impl, parameter_types};
+    use sp_core::{sr25519, Pair};
+    use sp_runtime::traits::IdentityLookup;
+    use sp_runtime::transaction_validity::TransactionPriority;
+    use sp_runtime::BuildStorage;
+    use unsigned_extrinsic_secure::PricePayload;
+
+    type Block = frame_system::mocking::MockBlock<Test>;
+
+    construct_runtime!(
+        pub enum Test {
+            System: frame_system,
+            Oracle: unsigned_extrinsic_secure,
+        }
+    );
+
+    #[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+    impl frame_system::Config for Test {
+        type Block = Block;
+        type AccountId = sr25519::Public;
+        type Lookup = IdentityLookup<Self::AccountId>;
+    }
+
+    impl frame_system::offchain::SigningTypes for Test {
+        type Public = sr25519::Public;
+        type Signature = sr25519::Signature;
+    }
+
+    parameter_types! {
+        pub const UnsignedInterval: u64 = 5;
+        pub const UnsignedPriority: TransactionPriority = 1 << 20;
+    }
+
+    impl unsigned_extrinsic_secure::Config for Test {
+        type RuntimeEvent = RuntimeEvent;
+        type AuthorityId = unsigned_extrinsic_secure::crypto::OracleAuthId;
+        type UnsignedInterval = UnsignedInterval;
+        type UnsignedPriority = UnsignedPriority;
+    }
+
+    /// Block 1, with [`oracle`](super::oracle) as the only authority
+    pub fn new_test_ext() -> sp_io::TestExternalities {
+        let storage = frame_system::GenesisConfig::<Test>::default()
+            .build_storage()
+            .expect("genesis builds");
+        let mut ext = sp_io::TestExternalities::new(storage);
+        ext.execute_with(|| {
+            System::set_block_number(1);
+            unsigned_extrinsic_secure::Authorities::<Test>::put(
+                frame_support::BoundedVec::truncate_from(vec![super::oracle().public()]),
+            );
+        });
+        ext
+    }
+
+    /// The price-report call, with the payload naming `public` and signed
+    /// by `signer`
+    pub fn report(
+        signer: &sr25519::Pair,
+        public: sr25519::Public,
+        price: u32,
+        block_number: u64,
+    ) -> unsigned_extrinsic_secure::Call<Test> {
+        let price_payload = PricePayload {
+            block_number,
+            price,
+            public,
+        };
+        let signature = signer.sign(&price_payload.encode());
+        unsigned_extrinsic_secure::Call::submit_price_unsigned_with_signed_payload {
+            price_payload,
+            signature,
+        }
+    }
+}
@@ -0,0 +1,191 @@
+//! Spoofed, replayed and spammed price reports: valid under the vulnerable
+//! pallet's validate_unsigned, rejected by the secure one's
+
+use frame_support::assert_ok;
+use sp_core::Pair;
+use sp_runtime::traits::ValidateUnsigned;
+use sp_runtime::transaction_validity::{
+    InvalidTransaction, TransactionLongevity, TransactionSource, TransactionValidityError,
+};
+use substrate_pallet_tests::unsigned_extrinsic::{attacker, oracle};
+
+mod vulnerable {
+    use super::*;
+    use substrate_pallet_tests::unsigned_extrinsic::vulnerable::{
+        new_test_ext, Oracle, RuntimeOrigin,
+    };
+    use unsigned_extrinsic::Call;
+
+    #[test]
+    fn anyone_reports_a_price_in_the_oracles_name() {
+        new_test_ext().execute_with(|| {
+            // Nothing in the call came from the oracle's key
+            let call = Call::submit_price_unsigned {
+                reporter: oracle().public(),
+                price: 1,
+            };
+            assert_ok!(Oracle::validate_unsigned(
+                TransactionSource::External,
+                &call
+            ));
+            for _ in 0..10 {
+                assert_ok!(Oracle::submit_price_unsigned(
+                    RuntimeOrigin::none(),
+                    oracle().public(),
+                    1
+                ));
+            }
+            assert_eq!(Oracle::average_price(), Some(1));
+        });
+    }
+
+    #[test]
+    fn a_dispatched_report_is_valid_again() {
+        new_test_ext().execute_with(|| {
+            let call = Call::submit_price_unsigned {
+                reporter: oracle().public(),
+                price: 100,
+            };
+            assert_ok!(Oracle::validate_unsigned(
+                TransactionSource::External,
+                &call
+            ));
+            assert_ok!(Oracle::submit_price_unsigned(
+                RuntimeOrigin::none(),
+                oracle().public(),
+                100
+            ));
+            assert_ok!(Oracle::validate_unsigned(
+                TransactionSource::External,
+                &call
+            ));
+        });
+    }
+
+    #[test]
+    fn every_distinct_payload_is_a_new_transaction_that_never_expires() {
+        new_test_ext().execute_with(|| {
+            let mut tags = Vec::new();
+            for price in 0..100 {
+                let call = Call::submit_price_unsigned {
+                    reporter: attacker().public(),
+                    price,
+                };
+                let validity = Oracle::validate_unsigned(TransactionSource::External, &call)
+                    .expect("spam is valid");
+                assert_eq!(validity.longevity, TransactionLongevity::MAX);
+                tags.extend(validity.provides);
+            }
+            tags.sort();
+            tags.dedup();
+            assert_eq!(tags.len(), 100);
+        });
+    }
+}
+
+mod secure {
+    use super::*;
+    use substrate_pallet_tests::unsigned_extrinsic::secure::{
+        new_test_ext, report, Oracle, RuntimeOrigin, System, Test, UnsignedInterval,
+    };
+    use unsigned_extrinsic_secure::{Call, NextUnsignedAt};
+
+    fn submit(call: Call<Test>) {
+        let Call::submit_price_unsigned_with_signed_payload {
+            price_payload,
+            signature,
+        } = call
+        else {
+            unreachable!("report builds this call")
+        };
+        assert_ok!(Oracle::submit_price_unsigned_with_signed_payload(
+            RuntimeOrigin::none(),
+            price_payload,
+            signature
+        ));
+    }
+
+    fn rejected(call: &Call<Test>) -> InvalidTransaction {
+        match Oracle::validate_unsigned(TransactionSource::External, call) {
+            Err(TransactionValidityError::Invalid(reason)) => reason,
+            other => panic!("expected an invalid transaction, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn the_oracle_reports_a_price() {
+        new_test_ext().execute_with(|| {
+            let call = report(&oracle(), oracle().public(), 100, 1);
+            let validity = Oracle::validate_unsigned(TransactionSource::External, &call)
+                .expect("a signed report is valid");
+            assert_eq!(validity.longevity, UnsignedInterval::get());
+            submit(call);
+            assert_eq!(Oracle::average_price(), Some(100));
+            assert_eq!(
+                NextUnsignedAt::<Test>::get(oracle().public()),
+                1 + UnsignedInterval::get()
+            );
+        });
+    }
+
+    #[test]
+    fn a_report_signed_by_another_key_is_rejected() {
+        new_test_ext().execute_with(|| {
+            let call = report(&attacker(), oracle().public(), 1, 1);
+            assert_eq!(rejected(&call), InvalidTransaction::BadProof);
+        });
+    }
+
+    #[test]
+    fn a_non_authority_is_rejected() {
+        new_test_ext().execute_with(|| {
+            let call = report(&attacker(), attacker().public(), 1, 1);
+            assert_eq!(rejected(&call), InvalidTransaction::BadSigner);
+        });
+    }
+
+    #[test]
+    fn a_dispatched_report_is_stale_for_good() {
+        new_test_ext().execute_with(|| {
+            let call = report(&oracle(), oracle().public(), 100, 1);
+            submit(call.clone());
+            assert_eq!(rejected(&call), InvalidTransaction::Stale);
+
+            // Still stale once the oracle may report again
+            System::set_block_number(1 + UnsignedInterval::get());
+            assert_eq!(rejected(&call), InvalidTransaction::Stale);
+            let fresh = report(
+                &oracle(),
+                oracle().public(),
+                101,
+                1 + UnsignedInterval::get(),
+            );
+            assert_ok!(Oracle::validate_unsigned(
+                TransactionSource::External,
+                &fresh
+            ));
+        });
+    }
+
+    #[test]
+    fn a_report_from_a_future_block_is_rejected() {
+        new_test_ext().execute_with(|| {
+            let call = report(&oracle(), oracle().public(), 100, 2);
+            assert_eq!(rejected(&call), InvalidTransaction::Future);
+        });
+    }
+
+    #[test]
+    fn one_pending_report_per_authority() {
+        new_test_ext().execute_with(|| {
+            let first = report(&oracle(), oracle().public(), 100, 1);
+            let second = report(&oracle(), oracle().public(), 101, 1);
+            let first =
+                Oracle::validate_unsigned(TransactionSource::External, &first).expect("valid");
+            let second =
+                Oracle::validate_unsigned(TransactionSource::External, &second).expect("valid");
+            // Same tag: the pool keeps one and drops the other
+            assert_eq!(first.provides, second.provides);
+        });
+    }
+}
@@ -0,0 +1,174 @@
+/*
+ * VULNERABLE SUBSTRATE PALLET - DO NOT USE IN PRODUCTION
+ *
+ * Unsigned Extrinsic Accepted Without Proof
+ *
+ * Unsigned extrinsics have no sender and pay no fees; the only thing
+ * standing between them and the block is the pallet's validate_unsigned.
+ * This price oracle lets its offchain workers report prices unsigned, but
+ * validate_unsigned accepts any payload: anyone can submit prices in an
+ * oracle's name, and flood the pool with them for free.
+ */
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use pallet::*;
+
+#[frame_support::pallet]
+pub mod pallet {
+    use frame_support::pallet_prelude::*;
+    use frame_system::pallet_prelude::*;
+
+    /// Prices kept for the average; the oldest is dropped first
+    pub const MAX_PRICES: u32 = 64;
+    pub const MAX_AUTHORITIES: u32 = 16;
+
+    #[pallet::pallet]
+    pub struct Pallet<T>(_);
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config {
+        type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+        /// Pool priority of price submissions
+        #[pallet::constant]
+        type UnsignedPriority: Get<TransactionPriority>;
+    }
+
+    /// Accounts whose offchain workers may report prices
+    #[pallet::storage]
+    pub type Authorities<T: Config> =
+        StorageValue<_, BoundedVec<T::AccountId, ConstU32<MAX_AUTHORITIES>>, ValueQuery>;
+
+    #[pallet::storage]
+    pub type Prices<T> = StorageValue<_, BoundedVec<u32, ConstU32<MAX_PRICES>>, ValueQuery>;
+
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        NewPrice { price: u32, reporter: T::AccountId },
+    }
+
+    #[pallet::error]
+    pub enum Error<T> {
+        NotAuthority,
+        TooManyAuthorities,
+    }
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// Root-only: lets `who` report prices
+        #[pallet::call_index(0)]
+        #[pallet::weight(Weight::from_parts(10_000, 0).saturating_add(T::DbWeight::get().reads_writes(1, 1)))]
+        pub fn add_authority(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+            ensure_root(origin)?;
+            Authorities::<T>::try_append(who).map_err(|_| Error::<T>::TooManyAuthorities)?;
+            Ok(())
+        }
+
+        /// Records `price` as reported by `reporter`, from an offchain
+        /// worker's unsigned transaction
+        #[pallet::call_index(1)]
+        #[pallet::weight(Weight::from_parts(10_000, 0).saturating_add(T::DbWeight::get().reads_writes(2, 1)))]
+        pub fn submit_price_unsigned(
+            origin: OriginFor<T>,
+            reporter: T::AccountId,
+            price: u32,
+        ) -> DispatchResult {
+            ensure_none(origin)?;
+            // VULNERABILITY 1: The reporter is whoever the payload says
+            // This looks like an authorization check, but reporter is an
+            // argument anyone can fill in with an oracle's account
+            ensure!(
+                Authorities::<T>::get().contains(&reporter),
+                Error::<T>::NotAuthority
+            );
+            Prices::<T>::mutate(|prices| {
+                if prices.is_full() {
+                    prices.remove(0);
+                }
+                let _ = prices.try_push(price);
+            });
+            Self::deposit_event(Event::NewPrice { price, reporter });
+            Ok(())
+        }
+    }
+
+    #[pallet::validate_unsigned]
+    impl<T: Config> ValidateUnsigned for Pallet<T> {
+        type Call = Call<T>;
+
+        /// Run by the pool on every unsigned transaction it receives, and
+        /// again (as pre_dispatch) before one is included in a block
+        fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+            let Call::submit_price_unsigned { reporter, price } = call else {
+                return InvalidTransaction::Call.into();
+            };
+
+            // VULNERABILITY 2: No signature
+            // Nothing proves the reporter's key was involved; the call
+            // carries no signature to check
+
+            // VULNERABILITY 3: No nonce or block binding
+            // Each new (reporter, price) pair is a new valid transaction,
+            // and a dispatched one is valid again later - so submissions
+            // can be replayed and the pool filled at no cost
+
+            // VULNERABILITY 4: Top priority, forever
+            // Spam outranks fee-paying transactions and never expires
+            ValidTransaction::with_tag_prefix("PriceOracle")
+                .priority(T::UnsignedPriority::get())
+                .and_provides((reporter, price))
+                .longevity(TransactionLongevity::MAX)
+                .propagate(true)
+                .build()
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Mean of the stored prices
+        pub fn average_price() -> Option<u32> {
+            let prices = Prices::<T>::get();
+            let count = u64::try_from(prices.len())
+                .ok()
+                .filter(|count| *count > 0)?;
+            let sum: u64 = prices.iter().map(|price| u64::from(*price)).sum();
+            u32::try_from(sum / count).ok()
+        }
+    }
+}
+
+/*
+ * SECURE VERSION (SIGNED PAYLOAD):
+ *
+ * The call carries a PricePayload { block_number, price, public } and the
+ * signature of the oracle key named in it. validate_unsigned checks the
+ * signature with SignedPayload::verify, checks the key belongs to an
+ * authority, and binds the payload to a block: it may not be from the
+ * future, nor older than that authority's next allowed submission, which
+ * each accepted price moves UnsignedInterval blocks ahead. The pool tag is
+ * the authority, so each can have one submission pending, and it expires
+ * after UnsignedInterval blocks.
+ *
+ * Where the submitter can pay, a signed transaction with a SignedExtension
+ * that waives the fee for authorities is the simpler alternative.
+ *
+ * Compiled in secure/unsigned_extrinsic.rs.
+ */
+
+/*
+ * EXPLOIT SCENARIOS:
+ *
+ * Spoofed Oracle Price:
+ * 1. Alice's offchain worker is the oracle; the price is around 100
+ * 2. Attacker submits submit_price_unsigned { reporter: Alice, price: 1 }
+ *    many times over
+ * 3. Every one is valid: the average collapses, and anything priced from
+ *    it (loans, liquidations) follows
+ *
+ * Free Pool Spam:
+ * 1. Attacker submits thousands of distinct (reporter, price) payloads
+ * 2. Each is a valid unsigned transaction at UnsignedPriority that never
+ *    expires, paying no fee
+ * 3. The pool fills and fee-paying transactions are delayed or dropped
+ */